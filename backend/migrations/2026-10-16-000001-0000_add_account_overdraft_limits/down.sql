@@ -0,0 +1,9 @@
+-- Revert: Remove overdraft and credit limit settings from accounts
+ALTER TABLE accounts
+DROP CONSTRAINT IF EXISTS chk_accounts_credit_limit_non_negative,
+DROP CONSTRAINT IF EXISTS chk_accounts_overdraft_limit_non_negative;
+
+ALTER TABLE accounts
+DROP COLUMN IF EXISTS credit_limit,
+DROP COLUMN IF EXISTS overdraft_limit,
+DROP COLUMN IF EXISTS allow_overdraft;
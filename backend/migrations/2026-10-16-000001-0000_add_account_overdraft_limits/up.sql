@@ -0,0 +1,9 @@
+-- Overdraft and credit limit settings for accounts
+ALTER TABLE accounts
+ADD COLUMN allow_overdraft BOOLEAN NOT NULL DEFAULT TRUE,
+ADD COLUMN overdraft_limit DECIMAL(19, 2),
+ADD COLUMN credit_limit DECIMAL(19, 2);
+
+ALTER TABLE accounts
+ADD CONSTRAINT chk_accounts_overdraft_limit_non_negative CHECK (overdraft_limit IS NULL OR overdraft_limit >= 0),
+ADD CONSTRAINT chk_accounts_credit_limit_non_negative CHECK (credit_limit IS NULL OR credit_limit >= 0);
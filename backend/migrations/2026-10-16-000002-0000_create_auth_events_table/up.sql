@@ -0,0 +1,28 @@
+-- Create auth_events table for auditing the authentication lifecycle
+CREATE TABLE auth_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- NULL when the attempt could not be tied to an account (e.g. unknown email)
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(30) NOT NULL,
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Indexes for per-user history and retention purges
+CREATE INDEX idx_auth_events_user_id_created_at ON auth_events(user_id, created_at DESC);
+CREATE INDEX idx_auth_events_created_at ON auth_events(created_at);
+
+-- Add check constraint for event_type
+ALTER TABLE auth_events
+    ADD CONSTRAINT check_auth_event_type
+    CHECK (event_type IN (
+        'register',
+        'login',
+        'login_failed',
+        'logout',
+        'token_refresh',
+        'password_changed',
+        'two_factor_enabled',
+        'two_factor_disabled'
+    ));
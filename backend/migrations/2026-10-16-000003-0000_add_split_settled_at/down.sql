@@ -0,0 +1,3 @@
+-- Remove local split settlement
+DROP INDEX IF EXISTS idx_transaction_splits_unsettled;
+ALTER TABLE transaction_splits DROP COLUMN IF EXISTS settled_at;
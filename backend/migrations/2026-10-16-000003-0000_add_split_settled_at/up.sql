@@ -0,0 +1,7 @@
+-- Allow splits to be settled locally, without an external split provider
+ALTER TABLE transaction_splits ADD COLUMN settled_at TIMESTAMPTZ;
+
+-- Debt calculations only look at unsettled splits
+CREATE INDEX idx_transaction_splits_unsettled
+    ON transaction_splits(person_id, created_at)
+    WHERE settled_at IS NULL;
@@ -0,0 +1,3 @@
+-- Remove external transaction IDs
+DROP INDEX IF EXISTS idx_transactions_user_external_id;
+ALTER TABLE transactions DROP COLUMN IF EXISTS external_id;
@@ -0,0 +1,7 @@
+-- Identify transactions imported from an external aggregator
+ALTER TABLE transactions ADD COLUMN external_id VARCHAR(255);
+
+-- Re-importing the same external transaction must not create a duplicate
+CREATE UNIQUE INDEX idx_transactions_user_external_id
+    ON transactions(user_id, external_id)
+    WHERE external_id IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Remove transaction status
+DROP INDEX IF EXISTS idx_transactions_account_status;
+ALTER TABLE transactions DROP COLUMN IF EXISTS status;
+DROP TYPE IF EXISTS transaction_status;
@@ -0,0 +1,9 @@
+-- Distinguish pending bank feed transactions from posted and voided ones
+CREATE TYPE transaction_status AS ENUM ('pending', 'posted', 'void');
+
+-- Existing transactions are all cleared
+ALTER TABLE transactions
+    ADD COLUMN status transaction_status NOT NULL DEFAULT 'posted';
+
+-- Balance and budget calculations filter by status per account
+CREATE INDEX idx_transactions_account_status ON transactions(account_id, status);
@@ -0,0 +1,4 @@
+-- Restore the single-column person index
+CREATE INDEX idx_splits_person_id ON transaction_splits(person_id);
+
+DROP INDEX IF EXISTS idx_splits_person_settled;
@@ -0,0 +1,7 @@
+-- Grouped debt totals only read the person, settled status and amount of each split;
+-- covering them lets the sum be answered from the index alone
+CREATE INDEX idx_splits_person_settled
+    ON transaction_splits(person_id, settled_at) INCLUDE (amount);
+
+-- Superseded by the composite index above
+DROP INDEX IF EXISTS idx_splits_person_id;
@@ -0,0 +1,6 @@
+-- Remove transaction-level original currency
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS chk_transactions_original_currency;
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS exchange_rate,
+    DROP COLUMN IF EXISTS original_amount,
+    DROP COLUMN IF EXISTS original_currency;
@@ -0,0 +1,13 @@
+-- Foreign-currency transactions keep the charged amount in the account currency
+-- alongside the original amount and the rate it was converted at
+ALTER TABLE transactions
+    ADD COLUMN original_currency currency_code,
+    ADD COLUMN original_amount DECIMAL(19, 2),
+    ADD COLUMN exchange_rate DECIMAL(19, 8);
+
+-- The original values are recorded together or not at all
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_original_currency CHECK (
+        (original_currency IS NULL AND original_amount IS NULL AND exchange_rate IS NULL)
+        OR (original_currency IS NOT NULL AND original_amount IS NOT NULL AND exchange_rate > 0)
+    );
@@ -0,0 +1,6 @@
+-- Restore the original sync statuses
+UPDATE split_sync_records SET sync_status = 'synced' WHERE sync_status = 'drifted';
+ALTER TABLE split_sync_records DROP CONSTRAINT check_sync_status;
+ALTER TABLE split_sync_records
+    ADD CONSTRAINT check_sync_status
+    CHECK (sync_status IN ('pending', 'synced', 'failed', 'deleted'));
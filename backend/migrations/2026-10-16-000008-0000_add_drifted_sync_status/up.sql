@@ -0,0 +1,5 @@
+-- Background reconciliation marks expenses changed on the provider as drifted
+ALTER TABLE split_sync_records DROP CONSTRAINT check_sync_status;
+ALTER TABLE split_sync_records
+    ADD CONSTRAINT check_sync_status
+    CHECK (sync_status IN ('pending', 'synced', 'drifted', 'failed', 'deleted'));
@@ -0,0 +1,13 @@
+-- Create orphaned_external_expenses table for external expenses left behind when a
+-- synced transaction is force-deleted locally
+CREATE TABLE orphaned_external_expenses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    split_provider_id UUID NOT NULL REFERENCES split_providers(id) ON DELETE CASCADE,
+    -- The expense ID on the external platform
+    external_expense_id VARCHAR(255) NOT NULL,
+    -- The deleted local transaction (no foreign key, the row no longer exists)
+    transaction_id UUID NOT NULL,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(split_provider_id, external_expense_id)
+);
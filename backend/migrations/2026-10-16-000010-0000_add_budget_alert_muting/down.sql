@@ -0,0 +1,3 @@
+ALTER TABLE budgets
+    DROP COLUMN IF EXISTS alerts_snoozed_until,
+    DROP COLUMN IF EXISTS alerts_muted;
@@ -0,0 +1,4 @@
+-- Let users mute a budget's alerts entirely or snooze them until a point in time
+ALTER TABLE budgets
+    ADD COLUMN alerts_muted BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN alerts_snoozed_until TIMESTAMPTZ;
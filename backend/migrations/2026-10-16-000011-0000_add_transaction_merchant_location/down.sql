@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_transactions_user_merchant;
+
+ALTER TABLE transactions
+    DROP CONSTRAINT IF EXISTS chk_transactions_location,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude,
+    DROP COLUMN IF EXISTS merchant;
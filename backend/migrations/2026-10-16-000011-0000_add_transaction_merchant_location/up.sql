@@ -0,0 +1,16 @@
+-- Merchant name and purchase location, captured by mobile clients
+ALTER TABLE transactions
+    ADD COLUMN merchant VARCHAR(255),
+    ADD COLUMN latitude DOUBLE PRECISION,
+    ADD COLUMN longitude DOUBLE PRECISION;
+
+-- Coordinates are recorded together and within range
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_location CHECK (
+        (latitude IS NULL AND longitude IS NULL)
+        OR (latitude BETWEEN -90 AND 90 AND longitude BETWEEN -180 AND 180)
+    );
+
+-- Merchant filter and merchant spending report
+CREATE INDEX idx_transactions_user_merchant ON transactions(user_id, lower(merchant))
+    WHERE merchant IS NOT NULL;
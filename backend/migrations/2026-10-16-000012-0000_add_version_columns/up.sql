@@ -0,0 +1,4 @@
+-- Row versions for optimistic concurrency control; incremented on every update
+ALTER TABLE transactions ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE accounts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE budgets ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
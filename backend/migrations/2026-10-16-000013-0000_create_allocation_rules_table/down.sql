@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS allocation_rule_destinations;
+DROP TRIGGER IF EXISTS update_allocation_rules_updated_at ON allocation_rules;
+DROP TABLE IF EXISTS allocation_rules;
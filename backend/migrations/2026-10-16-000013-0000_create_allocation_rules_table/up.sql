@@ -0,0 +1,32 @@
+-- Allocation rules split income landing in a source account across other accounts
+CREATE TABLE allocation_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    source_account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    -- Only income in this category is allocated; NULL matches all income
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_allocation_rules_user_id ON allocation_rules(user_id);
+CREATE INDEX idx_allocation_rules_source_account_id ON allocation_rules(source_account_id);
+
+CREATE TRIGGER update_allocation_rules_updated_at
+    BEFORE UPDATE ON allocation_rules
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Share of the income transferred to each destination account; a rule's
+-- percentages add up to 100
+CREATE TABLE allocation_rule_destinations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rule_id UUID NOT NULL REFERENCES allocation_rules(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    percentage NUMERIC(5, 2) NOT NULL,
+    CONSTRAINT chk_allocation_percentage CHECK (percentage > 0 AND percentage <= 100),
+    UNIQUE(rule_id, account_id)
+);
+
+CREATE INDEX idx_allocation_rule_destinations_account_id ON allocation_rule_destinations(account_id);
@@ -0,0 +1,6 @@
+-- Both legs of a transfer between accounts share a transfer_id; transfers are
+-- movements of money, not income or expenses
+ALTER TABLE transactions ADD COLUMN transfer_id UUID;
+
+CREATE INDEX idx_transactions_transfer_id ON transactions(transfer_id)
+    WHERE transfer_id IS NOT NULL;
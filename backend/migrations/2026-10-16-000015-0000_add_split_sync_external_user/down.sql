@@ -0,0 +1 @@
+ALTER TABLE split_sync_records DROP COLUMN external_user_id;
@@ -0,0 +1,3 @@
+-- Provider user each split was synced as, so reconciliation can compare every
+-- split with its own share of the external expense
+ALTER TABLE split_sync_records ADD COLUMN external_user_id VARCHAR(255);
@@ -0,0 +1,11 @@
+-- Keys sent with create requests, so a retried request returns the resource
+-- created by the first attempt instead of creating it again
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Kind of resource the key was used to create, e.g. 'account'
+    scope VARCHAR(50) NOT NULL,
+    key VARCHAR(255) NOT NULL,
+    resource_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, scope, key)
+);
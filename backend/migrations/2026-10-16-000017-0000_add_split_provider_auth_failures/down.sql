@@ -0,0 +1,3 @@
+ALTER TABLE split_providers
+    DROP COLUMN IF EXISTS disabled_by_user,
+    DROP COLUMN IF EXISTS consecutive_auth_failures;
@@ -0,0 +1,5 @@
+-- Track rejected credentials so a provider is disabled after repeated auth failures,
+-- and tell providers the user disabled apart from ones that need reconnecting
+ALTER TABLE split_providers
+    ADD COLUMN consecutive_auth_failures INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN disabled_by_user BOOLEAN NOT NULL DEFAULT FALSE;
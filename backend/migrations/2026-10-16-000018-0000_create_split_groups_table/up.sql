@@ -0,0 +1,28 @@
+-- Split groups are named sets of people a transaction can be split with in one step
+CREATE TABLE split_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_split_groups_user_id ON split_groups(user_id);
+
+CREATE TRIGGER update_split_groups_updated_at
+    BEFORE UPDATE ON split_groups
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Share of a transaction each member owes by default; a group's percentages add
+-- up to at most 100, and the rest is the user's own share
+CREATE TABLE split_group_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id UUID NOT NULL REFERENCES split_groups(id) ON DELETE CASCADE,
+    person_id UUID NOT NULL REFERENCES people(id) ON DELETE CASCADE,
+    percentage NUMERIC(5, 2) NOT NULL,
+    CONSTRAINT chk_split_group_percentage CHECK (percentage > 0 AND percentage <= 100),
+    UNIQUE(group_id, person_id)
+);
+
+CREATE INDEX idx_split_group_members_person_id ON split_group_members(person_id);
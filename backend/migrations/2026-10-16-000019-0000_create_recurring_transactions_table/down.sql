@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS update_recurring_transactions_updated_at ON recurring_transactions;
+DROP TABLE IF EXISTS recurring_transactions;
+DROP TYPE IF EXISTS recurrence_frequency;
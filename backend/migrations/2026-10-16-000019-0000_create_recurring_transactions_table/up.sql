@@ -0,0 +1,31 @@
+-- Recurring transaction rules create a transaction from their template on a schedule
+CREATE TYPE recurrence_frequency AS ENUM ('DAILY', 'WEEKLY', 'MONTHLY', 'YEARLY');
+
+CREATE TABLE recurring_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE SET NULL,
+    title VARCHAR(255) NOT NULL,
+    amount DECIMAL(19, 2) NOT NULL,
+    notes TEXT,
+    frequency recurrence_frequency NOT NULL,
+    "interval" INTEGER NOT NULL,
+    -- Occurrences are counted from the start date, so month-end dates are
+    -- clamped per month (Jan 31, Feb 28, Mar 31) instead of drifting
+    start_date DATE NOT NULL,
+    run_count INTEGER NOT NULL DEFAULT 0,
+    next_run_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_recurring_interval CHECK ("interval" > 0),
+    CONSTRAINT chk_recurring_amount CHECK (amount <> 0)
+);
+
+CREATE INDEX idx_recurring_transactions_user_id ON recurring_transactions(user_id);
+CREATE INDEX idx_recurring_transactions_next_run_date ON recurring_transactions(next_run_date);
+
+CREATE TRIGGER update_recurring_transactions_updated_at
+    BEFORE UPDATE ON recurring_transactions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,6 @@
+-- Deleted transactions are kept with a deletion time so they can be restored;
+-- every query leaves them out unless asked for them
+ALTER TABLE transactions ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_transactions_deleted_at ON transactions(user_id, deleted_at)
+    WHERE deleted_at IS NOT NULL;
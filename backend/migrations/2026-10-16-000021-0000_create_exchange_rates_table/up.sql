@@ -0,0 +1,11 @@
+-- Exchange rates fetched from the provider, one per currency pair and day, so
+-- amounts can be converted at the rate of their date
+CREATE TABLE exchange_rates (
+    base_currency currency_code NOT NULL,
+    quote_currency currency_code NOT NULL,
+    rate_date DATE NOT NULL,
+    rate DECIMAL(19, 8) NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (base_currency, quote_currency, rate_date),
+    CONSTRAINT chk_exchange_rate_positive CHECK (rate > 0)
+);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS update_category_rules_updated_at ON category_rules;
+DROP TABLE IF EXISTS category_rules;
+DROP TYPE IF EXISTS category_rule_match_type;
@@ -0,0 +1,22 @@
+-- Rules that categorize transactions by their title, tried in priority order
+CREATE TYPE category_rule_match_type AS ENUM ('CONTAINS', 'STARTS_WITH', 'REGEX');
+
+CREATE TABLE category_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    match_type category_rule_match_type NOT NULL,
+    pattern VARCHAR(255) NOT NULL,
+    -- Lower priorities are tried first
+    priority INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_category_rule_pattern CHECK (pattern <> '')
+);
+
+CREATE INDEX idx_category_rules_user_priority ON category_rules(user_id, priority);
+
+CREATE TRIGGER update_category_rules_updated_at
+    BEFORE UPDATE ON category_rules
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
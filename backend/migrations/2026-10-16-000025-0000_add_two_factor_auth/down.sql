@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS two_factor_recovery_codes;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS two_factor_enabled,
+    DROP COLUMN IF EXISTS totp_secret_encrypted;
@@ -0,0 +1,17 @@
+-- TOTP two-factor authentication. The secret is stored encrypted and only
+-- takes effect at login once a code from it has been verified.
+ALTER TABLE users
+    ADD COLUMN totp_secret_encrypted TEXT,
+    ADD COLUMN two_factor_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Single-use codes for logging in without the authenticator app
+CREATE TABLE two_factor_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 of the normalized code, hex encoded
+    code_hash VARCHAR(255) NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_two_factor_recovery_codes_user_id ON two_factor_recovery_codes(user_id);
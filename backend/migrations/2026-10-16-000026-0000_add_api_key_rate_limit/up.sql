@@ -0,0 +1,3 @@
+-- Per-key request limit; NULL means the key is not rate limited
+ALTER TABLE api_keys
+    ADD COLUMN requests_per_minute INTEGER CHECK (requests_per_minute > 0);
@@ -0,0 +1,8 @@
+DROP INDEX idx_idempotency_keys_created_at;
+
+ALTER TABLE idempotency_keys DROP COLUMN response_body;
+ALTER TABLE idempotency_keys DROP COLUMN response_status;
+ALTER TABLE idempotency_keys DROP COLUMN request_hash;
+
+DELETE FROM idempotency_keys WHERE resource_id IS NULL;
+ALTER TABLE idempotency_keys ALTER COLUMN resource_id SET NOT NULL;
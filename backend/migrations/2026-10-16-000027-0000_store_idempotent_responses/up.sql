@@ -0,0 +1,12 @@
+-- Transaction create requests are replayed from their stored response rather
+-- than from a single created resource, which a transfer or bulk request lacks
+ALTER TABLE idempotency_keys ALTER COLUMN resource_id DROP NOT NULL;
+
+-- SHA-256 of the request, so reusing a key for a different request is refused
+ALTER TABLE idempotency_keys ADD COLUMN request_hash VARCHAR(64);
+-- Response of the first request; NULL while it is still in progress
+ALTER TABLE idempotency_keys ADD COLUMN response_status SMALLINT;
+ALTER TABLE idempotency_keys ADD COLUMN response_body TEXT;
+
+-- Keys expire after a day
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_import_profiles_updated_at ON import_profiles;
+DROP TABLE IF EXISTS import_profiles;
@@ -0,0 +1,23 @@
+-- Named CSV column mappings, reused when importing statements from the same bank
+CREATE TABLE import_profiles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- CSV header of the column each transaction field is read from
+    mapping JSONB NOT NULL,
+    -- strftime format of the date column; NULL means %Y-%m-%d
+    date_format VARCHAR(50),
+    amount_sign VARCHAR(30) NOT NULL DEFAULT 'negative_debits',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_import_profile_name CHECK (name <> ''),
+    CONSTRAINT chk_import_profile_amount_sign
+        CHECK (amount_sign IN ('negative_debits', 'debit_credit_columns'))
+);
+
+CREATE UNIQUE INDEX idx_import_profiles_user_name ON import_profiles(user_id, LOWER(name));
+
+CREATE TRIGGER update_import_profiles_updated_at
+    BEFORE UPDATE ON import_profiles
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,21 @@
+-- Fractions of the limit (e.g. 0.8 for 80%) at which a budget raises an alert
+ALTER TABLE budgets
+    ADD COLUMN alert_thresholds DOUBLE PRECISION[] NOT NULL DEFAULT '{}',
+    ADD CONSTRAINT chk_budget_alert_thresholds
+        CHECK (array_position(alert_thresholds, NULL) IS NULL);
+
+-- Thresholds a budget's spending has crossed, recorded once per range
+CREATE TABLE budget_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    budget_range_id UUID NOT NULL REFERENCES budget_ranges(id) ON DELETE CASCADE,
+    threshold DOUBLE PRECISION NOT NULL,
+    -- Share of the limit spent when the crossing was detected, in percent
+    percentage_used DOUBLE PRECISION NOT NULL,
+    triggered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ,
+    CONSTRAINT uq_budget_alert_crossing UNIQUE (budget_range_id, threshold)
+);
+
+CREATE INDEX idx_budget_alerts_user_triggered ON budget_alerts(user_id, triggered_at DESC);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_transactions_reimbursable_outstanding;
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_reimbursed_check;
+ALTER TABLE transactions DROP COLUMN IF EXISTS reimbursed_at;
+ALTER TABLE transactions DROP COLUMN IF EXISTS reimbursable;
@@ -0,0 +1,10 @@
+-- Expenses paid on someone else's behalf, such as work expenses, are marked
+-- reimbursable; reimbursed_at records when the money came back
+ALTER TABLE transactions ADD COLUMN reimbursable BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE transactions ADD COLUMN reimbursed_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_reimbursed_check
+    CHECK (reimbursable OR reimbursed_at IS NULL);
+
+CREATE INDEX idx_transactions_reimbursable_outstanding ON transactions(user_id, date)
+    WHERE reimbursable AND reimbursed_at IS NULL;
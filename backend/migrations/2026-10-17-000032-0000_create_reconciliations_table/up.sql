@@ -0,0 +1,17 @@
+-- Checkpoints of an account's balance confirmed against a bank statement
+CREATE TABLE reconciliations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    -- Last day covered by the statement, in UTC
+    statement_date DATE NOT NULL,
+    statement_balance DECIMAL(19, 2) NOT NULL,
+    -- Posted balance at the end of statement_date, before any adjustment
+    balance DECIMAL(19, 2) NOT NULL,
+    -- Transaction booking the difference, when one was created
+    adjustment_transaction_id UUID REFERENCES transactions(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_reconciliations_account_date
+    ON reconciliations(account_id, statement_date DESC, created_at DESC);
@@ -0,0 +1,40 @@
+-- Append-only record of changes made to a user's data
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Orders each user's entries; every entry's previous_hash is the hash of the one before
+    sequence BIGINT GENERATED ALWAYS AS IDENTITY UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entity_type VARCHAR(30) NOT NULL,
+    -- Not a foreign key: entries outlive the entities they describe
+    entity_id UUID NOT NULL,
+    action VARCHAR(10) NOT NULL,
+    -- Changed fields, each as {"old": ..., "new": ...}
+    changes JSONB NOT NULL,
+    previous_hash VARCHAR(64),
+    hash VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    CONSTRAINT check_audit_log_action CHECK (action IN ('CREATE', 'UPDATE', 'DELETE')),
+    CONSTRAINT check_audit_log_entity_type CHECK (entity_type IN (
+        'account',
+        'transaction',
+        'budget',
+        'category',
+        'person'
+    ))
+);
+
+CREATE INDEX idx_audit_log_user_sequence ON audit_log(user_id, sequence DESC);
+CREATE INDEX idx_audit_log_entity ON audit_log(entity_id, sequence DESC);
+
+-- Entries are never changed; deleting a user still removes theirs
+CREATE FUNCTION reject_audit_log_update()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log entries cannot be modified';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE ON audit_log
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_audit_log_update();
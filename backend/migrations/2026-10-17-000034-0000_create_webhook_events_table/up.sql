@@ -0,0 +1,14 @@
+-- Webhook deliveries that could not be applied, kept for later inspection
+CREATE TABLE webhook_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    provider_type VARCHAR(50) NOT NULL,
+    event_type VARCHAR(100),
+    external_id VARCHAR(255),
+    -- 'unrecognized': not an event we handle; 'unmatched': no local data it applies to
+    status VARCHAR(20) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_webhook_events_status CHECK (status IN ('unrecognized', 'unmatched'))
+);
+
+CREATE INDEX idx_webhook_events_created_at ON webhook_events(created_at DESC);
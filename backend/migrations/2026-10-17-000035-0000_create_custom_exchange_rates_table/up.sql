@@ -0,0 +1,14 @@
+-- Exchange rates set by hand for pairs the providers lack or get wrong. They
+-- belong to the user who set them and only apply to that user's conversions,
+-- taking precedence over fetched rates.
+CREATE TABLE custom_exchange_rates (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    base_currency currency_code NOT NULL,
+    quote_currency currency_code NOT NULL,
+    rate_date DATE NOT NULL,
+    rate DECIMAL(19, 8) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, base_currency, quote_currency, rate_date),
+    CONSTRAINT chk_custom_exchange_rate_positive CHECK (rate > 0)
+);
@@ -0,0 +1,23 @@
+ALTER TABLE accounts
+DROP CONSTRAINT IF EXISTS chk_accounts_original_principal_non_negative,
+DROP CONSTRAINT IF EXISTS chk_accounts_interest_rate_range;
+
+ALTER TABLE accounts
+DROP COLUMN IF EXISTS original_principal,
+DROP COLUMN IF EXISTS interest_rate;
+
+-- Postgres cannot drop enum values, so the type is recreated without them.
+-- This fails while loan or mortgage accounts exist.
+ALTER TYPE account_type RENAME TO account_type_old;
+
+CREATE TYPE account_type AS ENUM (
+    'CHECKING',
+    'SAVINGS',
+    'CREDIT_CARD',
+    'INVESTMENT',
+    'CASH'
+);
+
+ALTER TABLE accounts ALTER COLUMN type TYPE account_type USING type::text::account_type;
+
+DROP TYPE account_type_old;
@@ -0,0 +1,13 @@
+-- Loans and mortgages carry the principal still owed as a negative balance,
+-- like credit cards, so they reduce net worth
+ALTER TYPE account_type ADD VALUE IF NOT EXISTS 'LOAN';
+ALTER TYPE account_type ADD VALUE IF NOT EXISTS 'MORTGAGE';
+
+-- Annual interest rate in percent, and the principal the loan started at
+ALTER TABLE accounts
+ADD COLUMN interest_rate DECIMAL(7, 4),
+ADD COLUMN original_principal DECIMAL(19, 2);
+
+ALTER TABLE accounts
+ADD CONSTRAINT chk_accounts_interest_rate_range CHECK (interest_rate IS NULL OR (interest_rate >= 0 AND interest_rate <= 100)),
+ADD CONSTRAINT chk_accounts_original_principal_non_negative CHECK (original_principal IS NULL OR original_principal >= 0);
@@ -0,0 +1,3 @@
+-- Time step of the last TOTP code accepted for each user, so a code can't be
+-- used again within the window it stays valid for
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT;
@@ -0,0 +1,4 @@
+-- A budget filtering on a category that is deleted without a replacement is
+-- deactivated instead of widening to all spending; it has no status until its
+-- filters are updated
+ALTER TABLE budgets ADD COLUMN deactivated_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Account create requests are replayed from their stored response like
+-- transaction requests, so no key names a created resource any more. Keys
+-- recorded without a request hash can't be matched to a retry.
+DELETE FROM idempotency_keys WHERE request_hash IS NULL;
+ALTER TABLE idempotency_keys DROP COLUMN resource_id;
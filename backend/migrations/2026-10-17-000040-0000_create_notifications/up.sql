@@ -0,0 +1,42 @@
+-- Channels each user receives each kind of notification through; event types
+-- and channels without a row use their defaults (in-app on, others off)
+CREATE TABLE notification_preferences (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(30) NOT NULL,
+    channel VARCHAR(20) NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    -- Where webhook notifications are posted; only used by the webhook channel
+    webhook_url TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_notification_preference UNIQUE (user_id, event_type, channel),
+    CONSTRAINT chk_notification_preference_event_type CHECK (event_type IN ('budget_alert')),
+    CONSTRAINT chk_notification_preference_channel
+        CHECK (channel IN ('in_app', 'email', 'webhook')),
+    CONSTRAINT chk_notification_preference_webhook_url CHECK (
+        (channel = 'webhook' OR webhook_url IS NULL)
+        AND (channel <> 'webhook' OR NOT enabled OR webhook_url IS NOT NULL)
+    )
+);
+
+CREATE TRIGGER update_notification_preferences_updated_at
+    BEFORE UPDATE ON notification_preferences
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Notifications delivered through the in-app channel
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(30) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- Details of the event, e.g. the budget and alert of a budget alert
+    data JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ,
+    CONSTRAINT chk_notification_event_type CHECK (event_type IN ('budget_alert'))
+);
+
+CREATE INDEX idx_notifications_user_created ON notifications(user_id, created_at DESC);
@@ -0,0 +1,11 @@
+-- Secret webhook deliveries are signed with (HMAC-SHA256 of the body), so
+-- receivers can tell them from forged requests; set with webhook_url
+ALTER TABLE notification_preferences ADD COLUMN webhook_secret VARCHAR(64);
+
+UPDATE notification_preferences
+SET webhook_secret = replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', '')
+WHERE webhook_url IS NOT NULL;
+
+ALTER TABLE notification_preferences
+    ADD CONSTRAINT chk_notification_preference_webhook_secret
+        CHECK ((webhook_url IS NULL) = (webhook_secret IS NULL));
@@ -0,0 +1,339 @@
+//! OpenAPI documentation for the REST API.
+//!
+//! The specification is generated at compile time from the `#[utoipa::path]`
+//! annotations on the handlers and the `ToSchema` derives on the request and
+//! response DTOs. It is served as JSON at `/api/docs/openapi.json` and rendered
+//! with Swagger UI at `/api/docs`.
+use crate::{
+    errors::{ErrorResponse, VersionConflictResponse},
+    handlers,
+    models::{
+        AccountMatch, AccountResponse, AccountSummaryResponse, AggregatorAccount,
+        AggregatorAccountResult, AggregatorImportData, AggregatorImportError,
+        AggregatorImportRequest, AggregatorImportResponse, AggregatorTransaction,
+        AllocationDestinationInput, AllocationDestinationResponse, AllocationRuleResponse,
+        AmortizationPayment, AmortizationResponse, AmountSign, ApplyCategoryRulesResponse,
+        AuditAction, AuditEntityType, AuditLogResponse, AuthEventResponse, AuthEventType,
+        AuthResponse, BalancePoint, BudgetAlertResponse, BudgetRangeResponse, BudgetResponse,
+        BudgetStatus, BulkCreateData, BulkCreateError, BulkCreateRequest, BulkCreateResponse,
+        BulkItemResult, BulkItemStatus, BulkOperation, BulkTransactionRequest,
+        BulkTransactionResponse, BulkUpdateItem, CategoryReimbursementTotal, CategoryRuleResponse,
+        CategorySuggestionResponse, ChangePasswordRequest, CreateAccountRequest,
+        CreateAllocationRuleRequest, CreateBudgetRangeRequest, CreateBudgetRequest,
+        CreateCategoryRuleRequest, CreateImportProfileRequest, CreatePersonRequest,
+        CreateRecurringTransactionRequest, CreateSplitGroupRequest, CreateTransactionRequest,
+        CreateTransferRequest, CreateUserRequest, CsvMapping, CurrencyResponse,
+        ForgotPasswordRequest, ImportProfileResponse, LoginRequest, LoginResponse,
+        MuteBudgetRequest, NotificationChannelKind, NotificationEventType,
+        NotificationPreferenceInput, NotificationPreferenceResponse, NotificationResponse,
+        OutstandingReimbursementsResponse, PersonResponse, PersonTransactionResponse,
+        ReconcileAccountRequest, ReconciliationResponse, RecurringTransactionResponse,
+        RefreshTokenRequest, RegistrationPreferences, ReimbursementTotal, ResetPasswordRequest,
+        SetupTwoFactorRequest, SnoozeBudgetRequest, SplitGroupMemberInput,
+        SplitGroupMemberResponse, SplitGroupResponse, SplitSyncState, SyncStatus, TagMode,
+        TagResponse, TransactionResponse, TransactionSplitResponse, TransferResponse,
+        TwoFactorChallenge, TwoFactorSetupResponse, UpdateAccountRequest, UpdateBudgetRequest,
+        UpdateCategoryRuleRequest, UpdateImportProfileRequest,
+        UpdateNotificationPreferencesRequest, UpdatePersonRequest,
+        UpdateRecurringTransactionRequest, UpdateSplitGroupRequest, UpdateTransactionRequest,
+        UserResponse, VerifyTwoFactorRequest,
+    },
+    services::{
+        analytics_service::{
+            AccountBalance, Cashflow, CashflowMonth, CategoryBreakdown, DashboardCategories,
+            DashboardSummary, MerchantSpending, NetWorth, NetWorthPoint, NetWorthTrend,
+        },
+        debt_service::{PersonDebt, PersonLedgerEntry},
+        event_service::ChangeEvent,
+    },
+    types::{
+        AccountType, BudgetPeriod, CategoryRuleMatchType, CurrencyCode, Granularity, Locale, Money,
+        RecurrenceFrequency, TransactionStatus,
+    },
+};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+
+/// OpenAPI document describing the `/api/v1` endpoints.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Master of Coin API",
+        description = "Personal finance management API"
+    ),
+    paths(
+        handlers::auth::register,
+        handlers::auth::login,
+        handlers::auth::refresh,
+        handlers::auth::forgot_password,
+        handlers::auth::reset_password,
+        handlers::auth::get_current_user,
+        handlers::auth::logout,
+        handlers::auth::change_password,
+        handlers::auth::setup_two_factor,
+        handlers::auth::verify_two_factor,
+        handlers::auth::list_events,
+        handlers::dashboard::get_summary,
+        handlers::dashboard::net_worth,
+        handlers::dashboard::recent_transactions,
+        handlers::dashboard::budget_statuses,
+        handlers::dashboard::categories,
+        handlers::dashboard::merchant_spending,
+        handlers::dashboard::net_worth_trend,
+        handlers::dashboard::cashflow,
+        handlers::events::stream,
+        handlers::audit::list,
+        handlers::transactions::list,
+        handlers::transactions::export_csv,
+        handlers::transactions::list_by_account,
+        handlers::transactions::list_by_category,
+        handlers::transactions::suggest_category,
+        handlers::transactions::outstanding_reimbursements,
+        handlers::transactions::create,
+        handlers::transactions::create_transfer,
+        handlers::transactions::get,
+        handlers::transactions::update,
+        handlers::transactions::delete,
+        handlers::transactions::restore,
+        handlers::transactions::bulk_create,
+        handlers::transactions::bulk,
+        handlers::transactions::settle_split,
+        handlers::transactions::post,
+        handlers::transactions::void,
+        handlers::transactions::unvoid,
+        handlers::transactions::mark_reimbursed,
+        handlers::import::import_aggregator,
+        handlers::accounts::list,
+        handlers::accounts::create,
+        handlers::accounts::get,
+        handlers::accounts::summary,
+        handlers::accounts::balance_history,
+        handlers::accounts::amortization,
+        handlers::accounts::reconcile,
+        handlers::accounts::update,
+        handlers::accounts::delete,
+        handlers::allocation_rules::list,
+        handlers::allocation_rules::create,
+        handlers::allocation_rules::get,
+        handlers::allocation_rules::delete,
+        handlers::recurring::list,
+        handlers::recurring::create,
+        handlers::recurring::get,
+        handlers::recurring::update,
+        handlers::recurring::delete,
+        handlers::category_rules::list,
+        handlers::category_rules::create,
+        handlers::category_rules::get,
+        handlers::category_rules::update,
+        handlers::category_rules::delete,
+        handlers::category_rules::apply,
+        handlers::import_profiles::list,
+        handlers::import_profiles::create,
+        handlers::import_profiles::get,
+        handlers::import_profiles::update,
+        handlers::import_profiles::delete,
+        handlers::budgets::list,
+        handlers::budgets::create,
+        handlers::budgets::get,
+        handlers::budgets::status,
+        handlers::budgets::update,
+        handlers::budgets::delete,
+        handlers::budgets::add_range,
+        handlers::budgets::mute,
+        handlers::budgets::snooze,
+        handlers::budgets::list_alerts,
+        handlers::budgets::ack_alert,
+        handlers::notifications::list,
+        handlers::notifications::mark_read,
+        handlers::notifications::get_preferences,
+        handlers::notifications::update_preferences,
+        handlers::people::list,
+        handlers::people::create,
+        handlers::people::get,
+        handlers::people::update,
+        handlers::people::delete,
+        handlers::people::list_debts,
+        handlers::people::get_debts,
+        handlers::people::get_ledger,
+        handlers::people::list_transactions,
+        handlers::people::settle_debt,
+        handlers::split_groups::list,
+        handlers::split_groups::create,
+        handlers::split_groups::get,
+        handlers::split_groups::update,
+        handlers::split_groups::delete,
+        handlers::tags::list,
+        handlers::meta::list_currencies,
+        handlers::meta::list_account_types,
+    ),
+    components(schemas(
+        ErrorResponse,
+        VersionConflictResponse,
+        AccountType,
+        BudgetPeriod,
+        CurrencyCode,
+        Locale,
+        Money,
+        TransactionStatus,
+        CreateUserRequest,
+        RegistrationPreferences,
+        LoginRequest,
+        RefreshTokenRequest,
+        ForgotPasswordRequest,
+        ResetPasswordRequest,
+        ChangePasswordRequest,
+        SetupTwoFactorRequest,
+        VerifyTwoFactorRequest,
+        UserResponse,
+        AuthResponse,
+        TwoFactorChallenge,
+        LoginResponse,
+        TwoFactorSetupResponse,
+        AuthEventType,
+        AuthEventResponse,
+        DashboardSummary,
+        DashboardCategories,
+        NetWorth,
+        AccountBalance,
+        MerchantSpending,
+        NetWorthTrend,
+        NetWorthPoint,
+        Cashflow,
+        CashflowMonth,
+        CategoryBreakdown,
+        ChangeEvent,
+        AuditLogResponse,
+        AuditEntityType,
+        AuditAction,
+        CreateTransactionRequest,
+        UpdateTransactionRequest,
+        TransactionResponse,
+        TransactionSplitResponse,
+        SplitSyncState,
+        SyncStatus,
+        CategorySuggestionResponse,
+        OutstandingReimbursementsResponse,
+        ReimbursementTotal,
+        CategoryReimbursementTotal,
+        CreateTransferRequest,
+        TransferResponse,
+        BulkCreateRequest,
+        BulkCreateResponse,
+        BulkCreateData,
+        BulkCreateError,
+        BulkTransactionRequest,
+        BulkUpdateItem,
+        BulkTransactionResponse,
+        BulkItemResult,
+        BulkOperation,
+        BulkItemStatus,
+        AggregatorImportRequest,
+        AggregatorAccount,
+        AggregatorTransaction,
+        AggregatorImportResponse,
+        AggregatorImportData,
+        AggregatorAccountResult,
+        AggregatorImportError,
+        AccountMatch,
+        CreateAccountRequest,
+        UpdateAccountRequest,
+        AccountResponse,
+        AccountSummaryResponse,
+        BalancePoint,
+        AmortizationResponse,
+        AmortizationPayment,
+        Granularity,
+        ReconcileAccountRequest,
+        ReconciliationResponse,
+        CreateAllocationRuleRequest,
+        AllocationDestinationInput,
+        AllocationRuleResponse,
+        AllocationDestinationResponse,
+        CreateBudgetRequest,
+        UpdateBudgetRequest,
+        MuteBudgetRequest,
+        SnoozeBudgetRequest,
+        BudgetResponse,
+        BudgetStatus,
+        BudgetAlertResponse,
+        NotificationEventType,
+        NotificationChannelKind,
+        NotificationResponse,
+        NotificationPreferenceResponse,
+        NotificationPreferenceInput,
+        UpdateNotificationPreferencesRequest,
+        CreateBudgetRangeRequest,
+        BudgetRangeResponse,
+        CreatePersonRequest,
+        UpdatePersonRequest,
+        PersonResponse,
+        PersonTransactionResponse,
+        PersonDebt,
+        PersonLedgerEntry,
+        handlers::people::SettleDebtRequest,
+        CreateSplitGroupRequest,
+        UpdateSplitGroupRequest,
+        SplitGroupMemberInput,
+        SplitGroupResponse,
+        SplitGroupMemberResponse,
+        CreateRecurringTransactionRequest,
+        UpdateRecurringTransactionRequest,
+        RecurringTransactionResponse,
+        RecurrenceFrequency,
+        CreateCategoryRuleRequest,
+        UpdateCategoryRuleRequest,
+        CategoryRuleResponse,
+        ApplyCategoryRulesResponse,
+        CategoryRuleMatchType,
+        CreateImportProfileRequest,
+        UpdateImportProfileRequest,
+        ImportProfileResponse,
+        CsvMapping,
+        AmountSign,
+        TagMode,
+        TagResponse,
+        CurrencyResponse,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "auth", description = "Registration, login, current user and sign-in activity"),
+        (name = "dashboard", description = "Dashboard summary and spending reports"),
+        (name = "events", description = "Server-sent notifications of data changes"),
+        (name = "audit", description = "Audit log of changes to the user's data"),
+        (name = "transactions", description = "Transaction management"),
+        (name = "accounts", description = "Account management"),
+        (name = "allocation-rules", description = "Automatic allocation of income across accounts"),
+        (name = "recurring", description = "Transactions created on a schedule"),
+        (name = "category-rules", description = "Automatic categorization of transactions by title"),
+        (name = "import-profiles", description = "Saved CSV column mappings for statement imports"),
+        (name = "budgets", description = "Budget management"),
+        (name = "notifications", description = "In-app notifications and the channels notifications are sent through"),
+        (name = "people", description = "People and debt management"),
+        (name = "split-groups", description = "Groups of people to split transactions with"),
+        (name = "tags", description = "Free-form transaction labels"),
+        (name = "meta", description = "Values the API supports, such as currencies"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer_auth` scheme referenced by protected endpoints.
+///
+/// Both JWT tokens and API keys are sent as `Authorization: Bearer <token>`.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
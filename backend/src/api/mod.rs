@@ -1,2 +1,3 @@
 // API routing module
+pub mod docs;
 pub mod routes;
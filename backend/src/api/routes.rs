@@ -10,17 +10,73 @@
 //! ### Public Routes (No Authentication)
 //! - `POST /api/v1/auth/register` - User registration
 //! - `POST /api/v1/auth/login` - User login
+//! - `POST /api/v1/auth/refresh` - Exchange a refresh token for new tokens
+//! - `POST /api/v1/auth/forgot-password` - Email a password reset token
+//! - `POST /api/v1/auth/reset-password` - Set a new password with a reset token
 //! - `GET /api/v1/integrations/splitwise/callback` - Handle Splitwise OAuth callback (user identified via encrypted state)
+//! - `POST /api/v1/integrations/splitwise/webhook` - Apply expense changes pushed by Splitwise (verified by signature)
+//! - `POST /api/v1/admin/purge` - Permanently remove transactions deleted beyond the retention period (requires `X-Admin-Key`)
+//!
+//! ### API Documentation (No Authentication)
+//! - `GET /api/docs` - Swagger UI
+//! - `GET /api/docs/openapi.json` - OpenAPI specification
 //!
 //! ### Protected Routes (Authentication Required)
 //! - `GET /api/v1/auth/me` - Get current user
-//! - `GET /api/v1/dashboard` - Dashboard summary
+//! - `POST /api/v1/auth/logout` - Record logout
+//! - `POST /api/v1/auth/change-password` - Change password, logging out other sessions
+//! - `POST /api/v1/auth/2fa/setup` - Start two-factor setup (TOTP secret and recovery codes)
+//! - `POST /api/v1/auth/2fa/verify` - Confirm a code and enable two-factor authentication
+//! - `GET /api/v1/auth/events` - Recent authentication events for the current user
+//! - `GET /api/v1/dashboard` - Dashboard summary (`?base_currency=` or `?group_by_currency=true`)
+//! - `GET /api/v1/dashboard/net-worth` - Net worth and account balances only
+//! - `GET /api/v1/dashboard/recent` - Recent transactions only
+//! - `GET /api/v1/dashboard/budgets` - Budget statuses only
+//! - `GET /api/v1/dashboard/categories` - Category breakdown only (same parameters as the summary)
+//! - `GET /api/v1/dashboard/merchants` - Spending grouped by merchant
+//! - `GET /api/v1/dashboard/net-worth-trend` - Net worth at the end of each recent month (`?months=`, `?currency=`)
+//! - `GET /api/v1/dashboard/cashflow` - Income, expense and net per recent month (`?months=`, `?currency=`)
+//! - `GET /api/v1/events` - Server-sent notifications of changes to the user's data
+//! - `GET /api/v1/audit` - Audit log of changes to the user's data (`?entity_type=&entity_id=`)
+//! - `GET /api/v1/exchange-rates` - Current exchange rates (`?base=`), or one rate on a day (`?quote=&date=`)
+//! - `GET /api/v1/exchange-rates/convert` - Preview a currency conversion
+//! - `POST /api/v1/exchange-rates/custom` - Set a manual exchange rate
+//! - `DELETE /api/v1/exchange-rates/custom` - Remove a manual exchange rate
+//! - `GET /api/v1/meta/currencies` - Supported currencies, with their decimal places
+//! - `GET /api/v1/meta/account-types` - Supported account types
 //! - `/api/v1/transactions/*` - Transaction management
+//! - `GET /api/v1/transactions/export.csv` - Download the transactions matching the list filters as CSV
+//! - `POST /api/v1/transactions/transfer` - Transfer between accounts as a linked transaction pair
+//! - `POST /api/v1/transactions/bulk` - Create, update and delete transactions in one DB transaction
+//! - `GET /api/v1/transactions/suggest-category?title=` - Suggest a category from title history
+//! - `GET /api/v1/transactions/reimbursable/outstanding` - Reimbursable transactions not paid back yet, with totals
+//! - `POST /api/v1/transactions/import` - Import a CSV file in batches while it is uploaded (`?dry_run=true` previews it)
+//! - `POST /api/v1/transactions/import/ofx` - Import an OFX/QFX statement, skipping transactions imported before
+//! - `POST /api/v1/transactions/:id/restore` - Restore a soft-deleted transaction
+//! - `POST /api/v1/transactions/:id/post` - Post a pending transaction
+//! - `POST /api/v1/transactions/:id/void` - Void a transaction
+//! - `POST /api/v1/transactions/:id/unvoid` - Restore a void transaction as posted
+//! - `POST /api/v1/import/aggregator` - Import a Plaid-style export, deduplicated by external ID
+//! - `/api/v1/import/profiles/*` - Saved CSV column mappings for imports
 //! - `/api/v1/accounts/*` - Account management
+//! - `GET /api/v1/accounts/:id/summary` - Summarize an account's activity over a period
+//! - `GET /api/v1/accounts/:id/balance-history` - An account's balance at the end of each day, week or month
+//! - `GET /api/v1/accounts/:id/amortization?payment=` - Monthly payments paying off a loan or mortgage
+//! - `POST /api/v1/accounts/:id/reconcile` - Check an account's balance against a bank statement
+//! - `/api/v1/allocation-rules/*` - Income allocation rules
+//! - `/api/v1/recurring/*` - Recurring transactions created on a schedule
 //! - `/api/v1/budgets/*` - Budget management
 //! - `/api/v1/people/*` - People and debt management
+//! - `/api/v1/split-groups/*` - Named groups of people to split transactions with
 //! - `/api/v1/categories/*` - Category management
-//! - `/api/v1/api-keys/*` - API key management
+//! - `GET /api/v1/tags` - List tags with the number of transactions using each
+//! - `/api/v1/category-rules/*` - Rules categorizing transactions by title
+//! - `POST /api/v1/category-rules/apply` - Categorize existing uncategorized transactions by rule
+//! - `GET /api/v1/notifications` - In-app notifications, newest first (`?unread=true`)
+//! - `POST /api/v1/notifications/:id/read` - Mark an in-app notification read
+//! - `GET /api/v1/notifications/preferences` - Channels each kind of notification is sent through
+//! - `PUT /api/v1/notifications/preferences` - Turn in-app, email and webhook channels on or off per event type
+//! - `/api/v1/api-keys/*` - API key management (not available to API keys)
 //! - `/api/v1/integrations/*` - Split provider integrations
 //!
 //! ### Integration Routes (Authentication Required)
@@ -29,13 +85,29 @@
 //! - `GET /api/v1/integrations/splitwise/friends` - List Splitwise friends
 //! - `GET /api/v1/integrations/providers` - List configured providers
 //! - `DELETE /api/v1/integrations/providers/:id` - Disconnect a provider
+//! - `POST /api/v1/integrations/providers/:id/disable` - Stop syncing with a provider
+//! - `POST /api/v1/integrations/providers/:id/enable` - Resume syncing with a provider
 //! - `GET /api/v1/integrations/providers/:id/friends` - Get provider friends
+//! - `GET /api/v1/integrations/sync/status` - Summarize sync status of split expenses
+//! - `GET /api/v1/integrations/sync-report` - Compare splits in a date range with each person's provider
 //!
 //! ### Person Split Config Routes (Authentication Required)
 //! - `PUT /api/v1/people/:id/split-config` - Set split provider config for person
 //! - `GET /api/v1/people/:id/split-config` - Get split provider config for person
 //! - `DELETE /api/v1/people/:id/split-config` - Delete split provider config for person
 //!
+//! ### Split Settlement Routes (Authentication Required)
+//! - `POST /api/v1/transactions/:id/splits/:split_id/settle` - Mark a split as settled locally
+//! - `POST /api/v1/transactions/:id/mark-reimbursed` - Record that a reimbursable transaction was paid back
+//!
+//! ### Nested Transaction Routes (Authentication Required)
+//! - `GET /api/v1/accounts/:id/transactions` - List an account's transactions
+//! - `GET /api/v1/categories/:id/transactions` - List a category's transactions
+//! - `DELETE /api/v1/categories/:id?reassign_to=` - Delete a category, moving or uncategorizing its transactions
+//! - `POST /api/v1/categories/:id/merge` - Move everything in a category to another one and delete it
+//! - `GET /api/v1/people/:id/transactions` - List the transactions a person has a split in
+//! - `GET /api/v1/people/:id/ledger` - List the splits and settlements behind a person's debt
+//!
 //! ### Split Sync Routes (Authentication Required)
 //! - `GET /api/v1/splits/:id/sync-status` - Get sync status for a split
 //! - `POST /api/v1/splits/:id/retry-sync` - Retry a failed sync
@@ -48,17 +120,58 @@
 //! API keys are subject to scope-based authorization. Each route checks if the
 //! API key has the required permission (read or write) for the resource type.
 //! JWT tokens have full access to all resources.
+//!
+//! ## Rate Limiting
+//!
+//! Requests are limited with in-memory token buckets, set in
+//! [`RateLimitConfig`](crate::config::RateLimitConfig):
+//!
+//! - Public routes share a bucket per client IP (`RATE_LIMIT_IP_*`)
+//! - `POST /auth/login` also has a stricter bucket per client IP, to slow
+//!   credential stuffing (`RATE_LIMIT_LOGIN_*`)
+//! - Protected routes have a bucket per client IP checked before
+//!   authentication, so requests with invalid tokens or API keys are counted
+//!   too (`RATE_LIMIT_PROTECTED_IP_*`)
+//! - After authentication, each user has a bucket shared by their JWT and API
+//!   key requests (`RATE_LIMIT_USER_*`)
+//! - API keys created with `requests_per_minute` have a bucket of their own;
+//!   their responses carry the requests left in `X-RateLimit-Remaining`
+//!
+//! Each bucket is sized by `*_CAPACITY` and refilled at `*_REFILL_PER_MINUTE`;
+//! a capacity of 0 disables it. Requests over any limit fail with 429 Too Many
+//! Requests and a `Retry-After` header giving the seconds to wait.
+//!
+//! ## Idempotency
+//!
+//! `POST /accounts`, `/transactions`, `/transactions/transfer`,
+//! `/transactions/bulk` and `/transactions/bulk-create` accept an
+//! `Idempotency-Key` header. A retry with
+//! the same key replays the stored response, while reusing a key for a different
+//! request fails with 409. Keys expire after 24 hours.
 use crate::{
-    AppState, handlers,
-    middleware::{auth::require_auth, scope::require_scope},
-    models::{OperationType, ResourceType},
+    AppState,
+    api::docs::ApiDoc,
+    handlers,
+    middleware::{
+        auth::require_auth,
+        client_ip, idempotency, rate_limit,
+        scope::{require_jwt, require_scope},
+    },
+    models::{OperationType, ResourceType, idempotency_key::IdempotencyScope},
 };
 use axum::{
-    Router, middleware,
+    Router,
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{delete, get, post, put},
 };
 use std::path::PathBuf;
 use tower_http::services::{ServeDir, ServeFile};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Room left in the streamed import's body limit for the form fields around the file
+const MULTIPART_FIELDS_ALLOWANCE: usize = 64 * 1024;
 
 /// Creates the main application router with all API routes.
 ///
@@ -74,27 +187,112 @@ use tower_http::services::{ServeDir, ServeFile};
 /// A configured [`Router`] ready to be served by Axum
 pub fn create_router(state: AppState) -> Router {
     // Public routes (no authentication required)
+    let limits = &state.config.rate_limit;
     let auth_routes = Router::new()
         .route("/auth/register", post(handlers::auth::register))
-        .route("/auth/login", post(handlers::auth::login))
+        // Login attempts get a stricter limit of their own, to slow credential stuffing
+        .route(
+            "/auth/login",
+            post(handlers::auth::login).layer(middleware::from_fn_with_state(
+                (state.rate_limiter.clone(), limits.login),
+                rate_limit::limit_logins,
+            )),
+        )
+        .route("/auth/refresh", post(handlers::auth::refresh))
+        .route(
+            "/auth/forgot-password",
+            post(handlers::auth::forgot_password),
+        )
+        .route("/auth/reset-password", post(handlers::auth::reset_password))
         // Splitwise OAuth callback - must be public since it's a browser redirect from Splitwise
         // User identity is verified via encrypted state parameter
         .route(
             "/integrations/splitwise/callback",
             get(handlers::splitwise_integration::oauth_callback),
-        );
+        )
+        // Splitwise webhook - public, deliveries are authenticated by their signature
+        .route(
+            "/integrations/splitwise/webhook",
+            post(handlers::splitwise_integration::webhook),
+        )
+        // Admin maintenance - authenticated by the admin key rather than a user
+        .route("/admin/purge", post(handlers::admin::purge))
+        .layer(middleware::from_fn_with_state(
+            (state.rate_limiter.clone(), limits.ip),
+            rate_limit::limit_by_ip,
+        ));
 
     // Protected routes (authentication required)
     let protected_routes = Router::new()
         // Auth routes (no scope check needed - always accessible)
         .route("/auth/me", get(handlers::auth::get_current_user))
+        .route("/auth/logout", post(handlers::auth::logout))
+        .route(
+            "/auth/change-password",
+            post(handlers::auth::change_password),
+        )
+        .route("/auth/2fa/setup", post(handlers::auth::setup_two_factor))
+        .route("/auth/2fa/verify", post(handlers::auth::verify_two_factor))
+        .route("/auth/events", get(handlers::auth::list_events))
         // Dashboard (no scope check - read-only summary)
         .route("/dashboard", get(handlers::dashboard::get_summary))
-        // Exchange rates (no scope check - read-only utility)
+        .route("/dashboard/net-worth", get(handlers::dashboard::net_worth))
+        .route(
+            "/dashboard/recent",
+            get(handlers::dashboard::recent_transactions),
+        )
+        .route(
+            "/dashboard/budgets",
+            get(handlers::dashboard::budget_statuses),
+        )
+        .route(
+            "/dashboard/categories",
+            get(handlers::dashboard::categories),
+        )
+        .route(
+            "/dashboard/merchants",
+            get(handlers::dashboard::merchant_spending),
+        )
+        .route(
+            "/dashboard/net-worth-trend",
+            get(handlers::dashboard::net_worth_trend),
+        )
+        .route("/dashboard/cashflow", get(handlers::dashboard::cashflow))
+        // Change notifications (no scope check - events carry only IDs)
+        .route("/events", get(handlers::events::stream))
+        // Audit log (scopes checked per entity type by the handler)
+        .route("/audit", get(handlers::audit::list))
+        // Supported values (no scope check - reference data, not user data)
+        .route("/meta/currencies", get(handlers::meta::list_currencies))
+        .route(
+            "/meta/account-types",
+            get(handlers::meta::list_account_types),
+        )
+        // Exchange rates (no scope check - shared rates; a user's own manual rates
+        // replace them for their pairs, but reveal nothing about their accounts)
         .route(
             "/exchange-rates",
             get(handlers::exchange_rates::get_exchange_rates),
         )
+        .route(
+            "/exchange-rates/convert",
+            get(handlers::exchange_rates::convert),
+        )
+        // Manual exchange rates convert the user's account balances
+        .route(
+            "/exchange-rates/custom",
+            post(handlers::exchange_rates::create_custom_rate)
+                .delete(handlers::exchange_rates::delete_custom_rate)
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Accounts,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
         // Transactions - with scope enforcement
         .route(
             "/transactions",
@@ -110,7 +308,99 @@ pub fn create_router(state: AppState) -> Router {
         )
         .route(
             "/transactions",
-            post(handlers::transactions::create).layer(middleware::from_fn(|auth, req, next| {
+            post(handlers::transactions::create)
+                .layer(middleware::from_fn_with_state(
+                    (state.db.clone(), IdempotencyScope::Transaction),
+                    idempotency::idempotent,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        // Export as CSV (static path, matched before `:id`)
+        .route(
+            "/transactions/export.csv",
+            get(handlers::transactions::export_csv).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Transfer between accounts (static path, matched before `:id`)
+        .route(
+            "/transactions/transfer",
+            post(handlers::transactions::create_transfer)
+                .layer(middleware::from_fn_with_state(
+                    (state.db.clone(), IdempotencyScope::Transfer),
+                    idempotency::idempotent,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        // Suggest a category from title history (static path, matched before `:id`)
+        .route(
+            "/transactions/suggest-category",
+            get(handlers::transactions::suggest_category).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Outstanding reimbursements (static path, matched before `:id`)
+        .route(
+            "/transactions/reimbursable/outstanding",
+            get(handlers::transactions::outstanding_reimbursements).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        .route(
+            "/transactions/:id",
+            get(handlers::transactions::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/transactions/:id",
+            put(handlers::transactions::update).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
                     ResourceType::Transactions,
                     OperationType::Write,
@@ -122,7 +412,193 @@ pub fn create_router(state: AppState) -> Router {
         )
         .route(
             "/transactions/:id",
-            get(handlers::transactions::get).layer(middleware::from_fn(|auth, req, next| {
+            delete(handlers::transactions::delete).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Restore a soft-deleted transaction
+        .route(
+            "/transactions/:id/restore",
+            post(handlers::transactions::restore).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Settle a single split locally (without a split provider)
+        .route(
+            "/transactions/:id/splits/:split_id/settle",
+            post(handlers::transactions::settle_split).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Post a pending transaction
+        .route(
+            "/transactions/:id/post",
+            post(handlers::transactions::post).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Void a transaction, or restore a void one
+        .route(
+            "/transactions/:id/void",
+            post(handlers::transactions::void).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/transactions/:id/unvoid",
+            post(handlers::transactions::unvoid).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/transactions/:id/mark-reimbursed",
+            post(handlers::transactions::mark_reimbursed).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        .route(
+            "/transactions/bulk",
+            post(handlers::transactions::bulk)
+                .layer(middleware::from_fn_with_state(
+                    (state.db.clone(), IdempotencyScope::BulkTransactions),
+                    idempotency::idempotent,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        // Bulk create transactions (general purpose)
+        .route(
+            "/transactions/bulk-create",
+            post(handlers::transactions::bulk_create)
+                .layer(middleware::from_fn_with_state(
+                    (state.db.clone(), IdempotencyScope::BulkCreateTransactions),
+                    idempotency::idempotent,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        // Import routes - CSV parsing
+        .route(
+            "/transactions/import/parse",
+            post(handlers::import::parse_csv).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Import routes - OFX/QFX statements
+        .route(
+            "/transactions/import/ofx",
+            post(handlers::import::import_ofx).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Import routes - streamed CSV import; the file may exceed the default body limit
+        .route(
+            "/transactions/import",
+            post(handlers::import::import_csv)
+                .layer(DefaultBodyLimit::max(
+                    state.config.import.max_stream_file_size + MULTIPART_FIELDS_ALLOWANCE,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        // Import routes - aggregator exports
+        .route(
+            "/import/aggregator",
+            post(handlers::import::import_aggregator).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Import profiles - column mappings used by imports, so they share the transactions scope
+        .route(
+            "/import/profiles",
+            get(handlers::import_profiles::list).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
                     ResourceType::Transactions,
                     OperationType::Read,
@@ -133,8 +609,34 @@ pub fn create_router(state: AppState) -> Router {
             })),
         )
         .route(
-            "/transactions/:id",
-            put(handlers::transactions::update).layer(middleware::from_fn(|auth, req, next| {
+            "/import/profiles",
+            post(handlers::import_profiles::create).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        .route(
+            "/import/profiles/:id",
+            get(handlers::import_profiles::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/import/profiles/:id",
+            put(handlers::import_profiles::update).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
                     ResourceType::Transactions,
                     OperationType::Write,
@@ -145,10 +647,98 @@ pub fn create_router(state: AppState) -> Router {
             })),
         )
         .route(
-            "/transactions/:id",
-            delete(handlers::transactions::delete).layer(middleware::from_fn(|auth, req, next| {
+            "/import/profiles/:id",
+            delete(handlers::import_profiles::delete).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Accounts - with scope enforcement
+        .route(
+            "/accounts",
+            get(handlers::accounts::list).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/accounts",
+            post(handlers::accounts::create)
+                .layer(middleware::from_fn_with_state(
+                    (state.db.clone(), IdempotencyScope::Account),
+                    idempotency::idempotent,
+                ))
+                .layer(middleware::from_fn(|auth, req, next| {
+                    require_scope(
+                        ResourceType::Accounts,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                })),
+        )
+        .route(
+            "/accounts/:id",
+            get(handlers::accounts::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/accounts/:id",
+            put(handlers::accounts::update).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Accounts,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/accounts/:id",
+            delete(handlers::accounts::delete).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Accounts,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/accounts/:id/summary",
+            get(handlers::accounts::summary).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/accounts/:id/balance-history",
+            get(handlers::accounts::balance_history).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+                },
+            )),
+        )
+        .route(
+            "/accounts/:id/amortization",
+            get(handlers::accounts::amortization).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/accounts/:id/reconcile",
+            post(handlers::accounts::reconcile).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
-                    ResourceType::Transactions,
+                    ResourceType::Accounts,
                     OperationType::Write,
                     auth,
                     req,
@@ -156,13 +746,33 @@ pub fn create_router(state: AppState) -> Router {
                 )
             })),
         )
-        // Bulk create transactions (general purpose)
         .route(
-            "/transactions/bulk-create",
-            post(handlers::transactions::bulk_create).layer(middleware::from_fn(
+            "/accounts/:id/transactions",
+            get(handlers::transactions::list_by_account).layer(middleware::from_fn(
                 |auth, req, next| {
                     require_scope(
                         ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Allocation rules - configure account flows, so they share the accounts scope
+        .route(
+            "/allocation-rules",
+            get(handlers::allocation_rules::list).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/allocation-rules",
+            post(handlers::allocation_rules::create).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Accounts,
                         OperationType::Write,
                         auth,
                         req,
@@ -171,31 +781,44 @@ pub fn create_router(state: AppState) -> Router {
                 },
             )),
         )
-        // Import routes - CSV parsing
         .route(
-            "/transactions/import/parse",
-            post(handlers::import::parse_csv).layer(middleware::from_fn(|auth, req, next| {
+            "/allocation-rules/:id",
+            get(handlers::allocation_rules::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/allocation-rules/:id",
+            delete(handlers::allocation_rules::delete).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Accounts,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Recurring transactions - create transactions, so they share the transactions scope
+        .route(
+            "/recurring",
+            get(handlers::recurring::list).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
                     ResourceType::Transactions,
-                    OperationType::Write,
+                    OperationType::Read,
                     auth,
                     req,
                     next,
                 )
             })),
         )
-        // Accounts - with scope enforcement
-        .route(
-            "/accounts",
-            get(handlers::accounts::list).layer(middleware::from_fn(|auth, req, next| {
-                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
-            })),
-        )
         .route(
-            "/accounts",
-            post(handlers::accounts::create).layer(middleware::from_fn(|auth, req, next| {
+            "/recurring",
+            post(handlers::recurring::create).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
-                    ResourceType::Accounts,
+                    ResourceType::Transactions,
                     OperationType::Write,
                     auth,
                     req,
@@ -204,16 +827,22 @@ pub fn create_router(state: AppState) -> Router {
             })),
         )
         .route(
-            "/accounts/:id",
-            get(handlers::accounts::get).layer(middleware::from_fn(|auth, req, next| {
-                require_scope(ResourceType::Accounts, OperationType::Read, auth, req, next)
+            "/recurring/:id",
+            get(handlers::recurring::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
             })),
         )
         .route(
-            "/accounts/:id",
-            put(handlers::accounts::update).layer(middleware::from_fn(|auth, req, next| {
+            "/recurring/:id",
+            put(handlers::recurring::update).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
-                    ResourceType::Accounts,
+                    ResourceType::Transactions,
                     OperationType::Write,
                     auth,
                     req,
@@ -222,10 +851,10 @@ pub fn create_router(state: AppState) -> Router {
             })),
         )
         .route(
-            "/accounts/:id",
-            delete(handlers::accounts::delete).layer(middleware::from_fn(|auth, req, next| {
+            "/recurring/:id",
+            delete(handlers::recurring::delete).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(
-                    ResourceType::Accounts,
+                    ResourceType::Transactions,
                     OperationType::Write,
                     auth,
                     req,
@@ -246,6 +875,18 @@ pub fn create_router(state: AppState) -> Router {
                 require_scope(ResourceType::Budgets, OperationType::Write, auth, req, next)
             })),
         )
+        .route(
+            "/budgets/alerts",
+            get(handlers::budgets::list_alerts).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Budgets, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/budgets/alerts/:id/ack",
+            post(handlers::budgets::ack_alert).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Budgets, OperationType::Write, auth, req, next)
+            })),
+        )
         .route(
             "/budgets/:id",
             get(handlers::budgets::get).layer(middleware::from_fn(|auth, req, next| {
@@ -264,6 +905,24 @@ pub fn create_router(state: AppState) -> Router {
                 require_scope(ResourceType::Budgets, OperationType::Write, auth, req, next)
             })),
         )
+        .route(
+            "/budgets/:id/status",
+            get(handlers::budgets::status).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Budgets, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/budgets/:id/mute",
+            post(handlers::budgets::mute).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Budgets, OperationType::Write, auth, req, next)
+            })),
+        )
+        .route(
+            "/budgets/:id/snooze",
+            post(handlers::budgets::snooze).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::Budgets, OperationType::Write, auth, req, next)
+            })),
+        )
         .route(
             "/budgets/:id/ranges",
             post(handlers::budgets::add_range).layer(middleware::from_fn(|auth, req, next| {
@@ -283,6 +942,12 @@ pub fn create_router(state: AppState) -> Router {
                 require_scope(ResourceType::People, OperationType::Write, auth, req, next)
             })),
         )
+        .route(
+            "/people/debts",
+            get(handlers::people::list_debts).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Read, auth, req, next)
+            })),
+        )
         .route(
             "/people/:id",
             get(handlers::people::get).layer(middleware::from_fn(|auth, req, next| {
@@ -307,12 +972,63 @@ pub fn create_router(state: AppState) -> Router {
                 require_scope(ResourceType::People, OperationType::Read, auth, req, next)
             })),
         )
+        .route(
+            "/people/:id/ledger",
+            get(handlers::people::get_ledger).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/people/:id/transactions",
+            get(handlers::people::list_transactions).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
         .route(
             "/people/:id/settle",
             post(handlers::people::settle_debt).layer(middleware::from_fn(|auth, req, next| {
                 require_scope(ResourceType::People, OperationType::Write, auth, req, next)
             })),
         )
+        // Split groups - sets of people, so they share the people scope
+        .route(
+            "/split-groups",
+            get(handlers::split_groups::list).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/split-groups",
+            post(handlers::split_groups::create).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Write, auth, req, next)
+            })),
+        )
+        .route(
+            "/split-groups/:id",
+            get(handlers::split_groups::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Read, auth, req, next)
+            })),
+        )
+        .route(
+            "/split-groups/:id",
+            put(handlers::split_groups::update).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Write, auth, req, next)
+            })),
+        )
+        .route(
+            "/split-groups/:id",
+            delete(handlers::split_groups::delete).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(ResourceType::People, OperationType::Write, auth, req, next)
+            })),
+        )
         // Person split config routes - with scope enforcement (uses People scope)
         .route(
             "/people/:id/split-config",
@@ -363,6 +1079,18 @@ pub fn create_router(state: AppState) -> Router {
                 )
             })),
         )
+        .route(
+            "/categories/:id",
+            get(handlers::categories::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
         .route(
             "/categories/:id",
             put(handlers::categories::update).layer(middleware::from_fn(|auth, req, next| {
@@ -387,6 +1115,120 @@ pub fn create_router(state: AppState) -> Router {
                 )
             })),
         )
+        .route(
+            "/categories/:id/merge",
+            post(handlers::categories::merge).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/categories/:id/transactions",
+            get(handlers::transactions::list_by_category).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Transactions,
+                        OperationType::Read,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
+        // Tags label transactions, so they share the transactions scope
+        .route(
+            "/tags",
+            get(handlers::tags::list).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        // Category rules - categorize transactions, so applying them needs the transactions scope
+        .route(
+            "/category-rules",
+            get(handlers::category_rules::list).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/category-rules",
+            post(handlers::category_rules::create).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/category-rules/apply",
+            post(handlers::category_rules::apply).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Transactions,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/category-rules/:id",
+            get(handlers::category_rules::get).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Read,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/category-rules/:id",
+            put(handlers::category_rules::update).layer(middleware::from_fn(|auth, req, next| {
+                require_scope(
+                    ResourceType::Categories,
+                    OperationType::Write,
+                    auth,
+                    req,
+                    next,
+                )
+            })),
+        )
+        .route(
+            "/category-rules/:id",
+            delete(handlers::category_rules::delete).layer(middleware::from_fn(
+                |auth, req, next| {
+                    require_scope(
+                        ResourceType::Categories,
+                        OperationType::Write,
+                        auth,
+                        req,
+                        next,
+                    )
+                },
+            )),
+        )
         // Split sync status - with scope enforcement (uses Transactions scope)
         .route(
             "/splits/:id/sync-status",
@@ -433,33 +1275,91 @@ pub fn create_router(state: AppState) -> Router {
             "/integrations/providers/:id",
             delete(handlers::split_providers::disconnect_provider),
         )
+        .route(
+            "/integrations/providers/:id/disable",
+            post(handlers::split_providers::disable_provider),
+        )
+        .route(
+            "/integrations/providers/:id/enable",
+            post(handlers::split_providers::enable_provider),
+        )
         .route(
             "/integrations/providers/:id/friends",
             get(handlers::split_providers::get_provider_friends),
         )
-        // API Keys - no scope enforcement (always accessible to authenticated users)
-        // API keys cannot manage other API keys via API key authentication
+        .route(
+            "/integrations/sync/status",
+            get(handlers::split_sync::get_sync_summary),
+        )
+        .route(
+            "/integrations/sync-report",
+            get(handlers::split_sync::get_sync_report),
+        )
+        // Notifications - no scope enforcement, but only with JWT authentication:
+        // API keys could otherwise send the user's notifications to their own webhook
+        .route(
+            "/notifications",
+            get(handlers::notifications::list).layer(middleware::from_fn(require_jwt)),
+        )
+        .route(
+            "/notifications/preferences",
+            get(handlers::notifications::get_preferences)
+                .put(handlers::notifications::update_preferences)
+                .layer(middleware::from_fn(require_jwt)),
+        )
+        .route(
+            "/notifications/:id/read",
+            post(handlers::notifications::mark_read).layer(middleware::from_fn(require_jwt)),
+        )
+        // API Keys - no scope enforcement, but only with JWT authentication:
+        // API keys cannot manage API keys, including themselves
         .route(
             "/api-keys",
-            get(handlers::api_keys::list).post(handlers::api_keys::create),
+            get(handlers::api_keys::list)
+                .post(handlers::api_keys::create)
+                .layer(middleware::from_fn(require_jwt)),
         )
         .route(
             "/api-keys/:id",
             get(handlers::api_keys::get)
                 .patch(handlers::api_keys::update)
-                .delete(handlers::api_keys::revoke),
+                .delete(handlers::api_keys::revoke)
+                .layer(middleware::from_fn(require_jwt)),
         )
+        // Rate limit API keys; runs after authentication, which is the outer layer
+        .layer(middleware::from_fn_with_state(
+            state.api_key_rate_limiter.clone(),
+            rate_limit::limit_api_keys,
+        ))
+        // Rate limit users across all their requests, also after authentication
+        .layer(middleware::from_fn_with_state(
+            (state.rate_limiter.clone(), limits.user),
+            rate_limit::limit_by_user,
+        ))
         // Apply authentication middleware to all protected routes
         .layer(middleware::from_fn_with_state(
             state.db.clone(),
             require_auth,
+        ))
+        // Rate limit by IP before authentication, so failed attempts count too
+        .layer(middleware::from_fn_with_state(
+            (state.rate_limiter.clone(), limits.protected_ip),
+            rate_limit::limit_protected_by_ip,
         ));
 
     // API routes under /api/v1 prefix
     let api_routes = Router::new()
         .nest("/api/v1", auth_routes.merge(protected_routes))
+        // Outermost, so rate limits and handlers all see the resolved client IP
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            client_ip::resolve_client_ip,
+        ))
         .with_state(state.clone());
 
+    // OpenAPI spec and Swagger UI (public)
+    let docs_routes = SwaggerUi::new("/api/docs").url("/api/docs/openapi.json", ApiDoc::openapi());
+
     // Static file serving for frontend with SPA fallback
     // ServeDir will serve files if they exist, otherwise fall back to index.html for SPA routing
     let static_dir = PathBuf::from("/app/static");
@@ -471,5 +1371,8 @@ pub fn create_router(state: AppState) -> Router {
 
     // Combine API routes with static file serving
     // API routes take precedence, then ServeDir handles everything else (including SPA fallback)
-    Router::new().merge(api_routes).fallback_service(serve_dir)
+    Router::new()
+        .merge(api_routes)
+        .merge(docs_routes)
+        .fallback_service(serve_dir)
 }
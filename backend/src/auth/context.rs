@@ -10,12 +10,19 @@ use crate::models::{ApiKeyScopes, OperationType, ResourceType, User};
 #[derive(Debug, Clone)]
 pub enum AuthContext {
     /// JWT token authentication (full access)
-    Jwt { user: User },
+    Jwt {
+        user: User,
+        /// Refresh token family the token was issued with, for tokens from a
+        /// login or refresh
+        session_id: Option<Uuid>,
+    },
     /// API key authentication (scoped access)
     ApiKey {
         user: User,
         api_key_id: Uuid,
         scopes: ApiKeyScopes,
+        /// Requests allowed per minute, or `None` for no limit
+        requests_per_minute: Option<i32>,
     },
 }
 
@@ -26,7 +33,7 @@ impl AuthContext {
     /// * `&User` - Reference to the authenticated user
     pub fn user(&self) -> &User {
         match self {
-            AuthContext::Jwt { user } => user,
+            AuthContext::Jwt { user, .. } => user,
             AuthContext::ApiKey { user, .. } => user,
         }
     }
@@ -85,6 +92,32 @@ impl AuthContext {
         }
     }
 
+    /// Get the session ID if authenticated via a session JWT
+    ///
+    /// # Returns
+    /// * `Option<Uuid>` - The refresh token family the JWT was issued with, None otherwise
+    pub fn session_id(&self) -> Option<Uuid> {
+        match self {
+            AuthContext::Jwt { session_id, .. } => *session_id,
+            _ => None,
+        }
+    }
+
+    /// Get the per-minute request limit if authenticated via a rate limited API key
+    ///
+    /// # Returns
+    /// * `Option<(Uuid, u32)>` - The API key ID and its limit, None for JWTs and unlimited keys
+    pub fn rate_limit(&self) -> Option<(Uuid, u32)> {
+        match self {
+            AuthContext::ApiKey {
+                api_key_id,
+                requests_per_minute: Some(limit),
+                ..
+            } => Some((*api_key_id, (*limit).max(1) as u32)),
+            _ => None,
+        }
+    }
+
     /// Get the scopes if authenticated via API key
     ///
     /// # Returns
@@ -16,6 +16,9 @@ pub struct Claims {
     pub exp: i64,
     /// Issued at timestamp (Unix timestamp)
     pub iat: i64,
+    /// Refresh token family of the session the token was issued to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
 }
 
 /// Generate a JWT token for a user
@@ -32,6 +35,26 @@ pub struct Claims {
 /// - Token expiration is configurable via JwtConfig
 /// - Never logs the secret or token
 pub fn generate_token(user: &User, config: &JwtConfig) -> Result<String, ApiError> {
+    encode_token(user, config, None)
+}
+
+/// Generate a JWT token for a user's session
+///
+/// Like [`generate_token`], but the token names the refresh token family
+/// `session_id`, so that logging out with it can end the session.
+pub fn generate_session_token(
+    user: &User,
+    config: &JwtConfig,
+    session_id: Uuid,
+) -> Result<String, ApiError> {
+    encode_token(user, config, Some(session_id))
+}
+
+fn encode_token(
+    user: &User,
+    config: &JwtConfig,
+    session_id: Option<Uuid>,
+) -> Result<String, ApiError> {
     let now = Utc::now().timestamp();
     let exp = now + (config.expiration_hours * 3600);
 
@@ -40,6 +63,7 @@ pub fn generate_token(user: &User, config: &JwtConfig) -> Result<String, ApiErro
         username: user.username.clone(),
         exp,
         iat: now,
+        sid: session_id,
     };
 
     encode(
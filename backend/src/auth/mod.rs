@@ -3,3 +3,6 @@ pub mod api_key;
 pub mod context;
 pub mod jwt;
 pub mod password;
+pub mod refresh_token;
+pub mod reset_token;
+pub mod totp;
@@ -0,0 +1,52 @@
+use rand::{Rng, distributions::Alphanumeric};
+use sha2::{Digest, Sha256};
+
+/// Length of a refresh token
+const REFRESH_TOKEN_LENGTH: usize = 64;
+
+/// Generate a new refresh token
+///
+/// # Returns
+/// * `String` - 64 random alphanumeric characters
+pub fn generate_refresh_token() -> String {
+    generate_token(REFRESH_TOKEN_LENGTH)
+}
+
+/// Hash a refresh token for storage and lookup
+///
+/// # Returns
+/// * `String` - The SHA-256 of the token, hex encoded
+pub fn hash_refresh_token(token: &str) -> String {
+    hash_token(token)
+}
+
+/// Generate a random token to be stored hashed with [`hash_token`]
+///
+/// # Returns
+/// * `String` - `length` random alphanumeric characters
+///
+/// # Security
+/// - Uses a cryptographically secure random number generator
+pub fn generate_token(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// Hash a token from [`generate_token`] for storage and lookup
+///
+/// # Returns
+/// * `String` - The SHA-256 of the token, hex encoded
+///
+/// # Security
+/// - Unlike passwords and API keys, these tokens are long and random, so an
+///   unsalted fast hash is enough and lets tokens be looked up by hash
+/// - Never logs the token or hash
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,23 @@
+use super::refresh_token;
+
+/// Length of a password reset token
+const RESET_TOKEN_LENGTH: usize = 48;
+
+/// Generate a new password reset token
+///
+/// # Returns
+/// * `String` - 48 random alphanumeric characters
+pub fn generate_reset_token() -> String {
+    refresh_token::generate_token(RESET_TOKEN_LENGTH)
+}
+
+/// Hash a password reset token for storage and lookup
+///
+/// Reset tokens are long, random and short-lived, so they are hashed and
+/// looked up like refresh tokens.
+///
+/// # Returns
+/// * `String` - The SHA-256 of the token, hex encoded
+pub fn hash_reset_token(token: &str) -> String {
+    refresh_token::hash_token(token)
+}
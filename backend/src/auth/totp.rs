@@ -0,0 +1,138 @@
+use rand::{Rng, RngCore, distributions::Alphanumeric};
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use super::refresh_token;
+use crate::errors::ApiError;
+
+/// Issuer shown by authenticator apps
+const ISSUER: &str = "Master of Coin";
+
+/// Length of a TOTP secret in bytes (160 bits, as RFC 4226 recommends)
+const SECRET_LENGTH: usize = 20;
+
+/// Number of recovery codes issued at setup
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Random characters in each half of a recovery code
+const RECOVERY_CODE_HALF_LENGTH: usize = 5;
+
+/// Generate a new TOTP secret
+///
+/// # Returns
+/// * `String` - The secret, base32 encoded without padding
+///
+/// # Security
+/// - Uses a cryptographically secure random number generator
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill_bytes(&mut secret);
+    Secret::Raw(secret.to_vec()).to_encoded().to_string()
+}
+
+/// Build the `otpauth://` URI authenticator apps import a secret from
+///
+/// # Arguments
+/// * `secret` - Base32 encoded secret
+/// * `account_name` - Account shown next to the issuer in the app
+pub fn otpauth_uri(secret: &str, account_name: &str) -> Result<String, ApiError> {
+    Ok(totp(secret, account_name)?.get_url())
+}
+
+/// Length of a TOTP time step in seconds
+const STEP_SECONDS: u64 = 30;
+
+/// Check a 6-digit code against a secret
+///
+/// Codes from the previous and next 30-second steps are accepted too, to allow
+/// for clock drift between the server and the user's device.
+///
+/// # Returns
+/// * `Option<i64>` - The time step the code belongs to, which callers store so
+///   the code can't be used again, or `None` if it doesn't match
+///
+/// # Security
+/// - Compares codes in constant time
+/// - Never logs the secret or code
+pub fn verify_code(secret: &str, code: &str) -> Result<Option<i64>, ApiError> {
+    let totp = totp(secret, "")?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| {
+            tracing::error!("System clock error while checking TOTP code: {}", e);
+            ApiError::Internal
+        })?
+        .as_secs();
+    let current_step = now / STEP_SECONDS;
+    let code = code.trim();
+    Ok((current_step.saturating_sub(1)..=current_step + 1)
+        .find(|step| totp.check(code, step * STEP_SECONDS))
+        .map(|step| step as i64))
+}
+
+/// Generate the recovery codes issued at setup
+///
+/// # Returns
+/// * `Vec<String>` - Codes formatted as `xxxxx-xxxxx`, lowercase alphanumeric
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let mut half = || -> String {
+        (&mut rng)
+            .sample_iter(&Alphanumeric)
+            .take(RECOVERY_CODE_HALF_LENGTH)
+            .map(|byte| char::from(byte).to_ascii_lowercase())
+            .collect()
+    };
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| format!("{}-{}", half(), half()))
+        .collect()
+}
+
+/// Normalize a recovery code as typed by a user for comparison
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_ascii_lowercase()
+}
+
+/// Hash a recovery code for storage and lookup, after normalizing it
+///
+/// Recovery codes are random with about 51 bits of entropy and only accepted
+/// after the password, so they are hashed and looked up like refresh tokens
+/// rather than with a slow password hash.
+///
+/// # Returns
+/// * `String` - The SHA-256 of the normalized code, hex encoded
+pub fn hash_recovery_code(code: &str) -> String {
+    refresh_token::hash_token(&normalize_recovery_code(code))
+}
+
+/// Check whether a code is shaped like a code from an authenticator app
+///
+/// Such codes are six digits, which a recovery code never is.
+pub fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == 6 && code.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn totp(secret: &str, account_name: &str) -> Result<TOTP, ApiError> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| {
+            tracing::error!("Stored TOTP secret is not valid base32: {:?}", e);
+            ApiError::Internal
+        })?;
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        // Steps either side are checked by `verify_code`, which needs to know
+        // which one matched
+        0,
+        STEP_SECONDS,
+        bytes,
+        Some(ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|e| {
+        tracing::error!("Failed to build TOTP: {}", e);
+        ApiError::Internal
+    })
+}
@@ -10,6 +10,8 @@
 //! - [`ApiError::Unauthorized`]: Authentication/authorization errors (401)
 //! - [`ApiError::Validation`]: Input validation errors (400)
 //! - [`ApiError::Conflict`]: Resource conflict errors (409)
+//! - [`ApiError::VersionConflict`]: Updates based on an outdated version (409, with the current state)
+//! - [`ApiError::TooManyRequests`]: Rate limit exceeded (429)
 //! - [`ApiError::Internal`]: Internal server errors (500)
 //!
 //! All errors are automatically logged with appropriate severity levels and
@@ -45,6 +47,14 @@ pub enum ApiError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// The resource changed since the version the client sent; carries the current
+    /// state of the resource once it has been looked up
+    #[error("Version conflict")]
+    VersionConflict(Option<serde_json::Value>),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Configuration error: {0}")]
     Configuration(String),
 
@@ -59,14 +69,30 @@ pub enum ApiError {
 }
 
 /// Error response structure for JSON responses
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+/// Response to an update based on an outdated version
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct VersionConflictResponse {
+    pub error: String,
+    /// Current state of the resource, including its current `version`
+    #[schema(value_type = Object)]
+    pub current: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    /// Version conflict carrying the current state of the resource
+    pub fn version_conflict<T: Serialize>(current: &T) -> Self {
+        ApiError::VersionConflict(serde_json::to_value(current).ok())
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
+        let (status, error_message) = match self {
             ApiError::Database(e) => {
                 error!("Database error: {:?}", e);
                 match e {
@@ -81,27 +107,40 @@ impl IntoResponse for ApiError {
             }
             ApiError::NotFound(msg) => {
                 tracing::warn!("Not found: {}", msg);
-                (StatusCode::NOT_FOUND, msg.clone())
+                (StatusCode::NOT_FOUND, msg)
             }
             ApiError::Unauthorized(msg) => {
                 tracing::warn!("Unauthorized: {}", msg);
-                (StatusCode::UNAUTHORIZED, msg.clone())
+                (StatusCode::UNAUTHORIZED, msg)
             }
             ApiError::Forbidden(msg) => {
                 tracing::warn!("Forbidden: {}", msg);
-                (StatusCode::FORBIDDEN, msg.clone())
+                (StatusCode::FORBIDDEN, msg)
             }
             ApiError::Validation(msg) => {
                 tracing::warn!("Validation error: {}", msg);
-                (StatusCode::UNPROCESSABLE_ENTITY, msg.clone())
+                (StatusCode::UNPROCESSABLE_ENTITY, msg)
             }
             ApiError::BadRequest(msg) => {
                 tracing::warn!("Bad request: {}", msg);
-                (StatusCode::BAD_REQUEST, msg.clone())
+                (StatusCode::BAD_REQUEST, msg)
             }
             ApiError::Conflict(msg) => {
                 tracing::warn!("Conflict: {}", msg);
-                (StatusCode::CONFLICT, msg.clone())
+                (StatusCode::CONFLICT, msg)
+            }
+            ApiError::VersionConflict(current) => {
+                tracing::warn!("Version conflict");
+                // Carries the current state, so it has a body of its own
+                let body = Json(VersionConflictResponse {
+                    error: "Resource was modified since the given version".to_string(),
+                    current,
+                });
+                return (StatusCode::CONFLICT, body).into_response();
+            }
+            ApiError::TooManyRequests(msg) => {
+                tracing::warn!("Too many requests: {}", msg);
+                (StatusCode::TOO_MANY_REQUESTS, msg)
             }
             ApiError::Configuration(msg) => {
                 error!("Configuration error: {}", msg);
@@ -112,7 +151,7 @@ impl IntoResponse for ApiError {
             }
             ApiError::External(msg) => {
                 error!("External service error: {}", msg);
-                (StatusCode::BAD_GATEWAY, msg.clone())
+                (StatusCode::BAD_GATEWAY, msg)
             }
             ApiError::Internal => {
                 error!("Internal server error");
@@ -123,7 +162,7 @@ impl IntoResponse for ApiError {
             }
             ApiError::InternalWithMessage(msg) => {
                 error!("Internal server error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, msg.clone())
+                (StatusCode::INTERNAL_SERVER_ERROR, msg)
             }
         };
 
@@ -1,33 +1,86 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
-    models::{AccountResponse, CreateAccountRequest, UpdateAccountRequest},
-    services::account_service,
+    errors::{ApiError, ErrorResponse, VersionConflictResponse},
+    handlers::{etag, version},
+    models::{
+        AccountResponse, AccountSummaryQuery, AccountSummaryResponse, AmortizationQuery,
+        AmortizationResponse, BalanceHistoryQuery, BalancePoint, CreateAccountRequest, Paginated,
+        Pagination, PaginationQuery, ReconcileAccountRequest, ReconciliationResponse, SyncQuery,
+        UpdateAccountRequest,
+    },
+    services::{account_service, event_service::ChangeEvent, reconciliation_service},
 };
 use axum::{
-    Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
 
 /// List all accounts for the authenticated user
 /// GET /accounts
+///
+/// Every account is returned unless `?paginated=true` asks for a page in a
+/// `Paginated` envelope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts",
+    tag = "accounts",
+    params(SyncQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "Accounts with balances",
+            content(
+                (Vec<AccountResponse> = "application/json"),
+                (Paginated<AccountResponse> = "application/vnd.master-of-coin.paginated+json"),
+            )
+        ),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-) -> Result<Json<Vec<AccountResponse>>, ApiError> {
+    Query(query): Query<SyncQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Listing accounts for user {}", user_id);
 
-    let accounts = account_service::list_accounts(&state.db, user_id).await?;
+    if pagination.paginated {
+        let since = query.updated_since;
+        let page = account_service::list_accounts(&state.read_db, user_id, query, Some(pagination))
+            .await?;
+        let total = account_service::count_accounts(&state.read_db, user_id, since).await?;
+        return Ok(Json(Paginated::new(page, total, pagination)).into_response());
+    }
 
-    Ok(Json(accounts))
+    let accounts = account_service::list_accounts(&state.read_db, user_id, query, None).await?;
+    Ok(Json(accounts).into_response())
 }
 
 /// Create a new account
 /// POST /accounts
+#[utoipa::path(
+    post,
+    path = "/api/v1/accounts",
+    tag = "accounts",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; retrying with it replays the stored response"),
+    ),
+    request_body = CreateAccountRequest,
+    responses(
+        (status = 201, description = "Account created", body = AccountResponse),
+        (status = 400, description = "Invalid Idempotency-Key", body = ErrorResponse),
+        (status = 409, description = "Idempotency-Key still in progress or used for a different request", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -43,29 +96,222 @@ pub async fn create(
 
 /// Get a single account by ID
 /// GET /accounts/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{id}",
+    tag = "accounts",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "Account with balance", body = AccountResponse),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<AccountResponse>, ApiError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::debug!("Fetching account {} for user {}", id, user_id);
 
-    let account = account_service::get_account(&state.db, id, user_id).await?;
+    let account = account_service::get_account(&state.read_db, id, user_id).await?;
 
-    Ok(Json(account))
+    etag::json_with_etag(&headers, &account)
+}
+
+/// Summarize an account's activity over a period
+/// GET /accounts/:id/summary
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{id}/summary",
+    tag = "accounts",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        AccountSummaryQuery,
+    ),
+    responses(
+        (status = 200, description = "Opening and closing balance, income, expense and net change for the period", body = AccountSummaryResponse),
+        (status = 400, description = "Start is after end", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn summary(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AccountSummaryQuery>,
+) -> Result<Json<AccountSummaryResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Summarizing account {} for user {}", id, user_id);
+
+    let summary = account_service::get_account_summary(&state.read_db, id, user_id, query).await?;
+
+    Ok(Json(summary))
+}
+
+/// Get an account's balance over time
+/// GET /accounts/:id/balance-history
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{id}/balance-history",
+    tag = "accounts",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        BalanceHistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Posted balance at the end of each interval, oldest first", body = Vec<BalancePoint>),
+        (status = 400, description = "Start is after end, or the period has too many intervals", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn balance_history(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BalanceHistoryQuery>,
+) -> Result<Json<Vec<BalancePoint>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!(
+        "Fetching balance history of account {} for user {}",
+        id,
+        user_id
+    );
+
+    let history = account_service::get_balance_history(&state.read_db, id, user_id, query).await?;
+
+    Ok(Json(history))
+}
+
+/// Project the payments paying off a loan or mortgage
+/// GET /accounts/:id/amortization
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{id}/amortization",
+    tag = "accounts",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        AmortizationQuery,
+    ),
+    responses(
+        (status = 200, description = "Monthly payments until the principal owed is paid off", body = AmortizationResponse),
+        (status = 400, description = "Payment is not positive, or too small to ever pay the loan off", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 422, description = "Account is not a loan or mortgage, or has no interest rate", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn amortization(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AmortizationQuery>,
+) -> Result<Json<AmortizationResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!(
+        "Projecting amortization of account {} for user {}",
+        id,
+        user_id
+    );
+
+    let schedule = account_service::get_amortization(&state.read_db, id, user_id, query).await?;
+
+    Ok(Json(schedule))
+}
+
+/// Reconcile an account with a bank statement
+/// POST /accounts/:id/reconcile
+///
+/// Compares the statement balance with the posted balance at the end of the
+/// statement date, returns the transactions since the previous reconciliation
+/// and records this one as the next checkpoint.
+#[utoipa::path(
+    post,
+    path = "/api/v1/accounts/{id}/reconcile",
+    tag = "accounts",
+    params(("id" = Uuid, Path, description = "Account ID")),
+    request_body = ReconcileAccountRequest,
+    responses(
+        (status = 201, description = "Reconciliation recorded, with the difference to the statement", body = ReconciliationResponse),
+        (status = 422, description = "Statement date in the future or before the previous reconciliation", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reconcile(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ReconcileAccountRequest>,
+) -> Result<(StatusCode, Json<ReconciliationResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Reconciling account {} for user {}", id, user_id);
+
+    let reconciliation =
+        reconciliation_service::reconcile_account(&state.db, id, user_id, request).await?;
+
+    if let Some(adjustment) = &reconciliation.adjustment {
+        state.events.publish(
+            user_id,
+            ChangeEvent::TransactionCreated {
+                transaction_id: adjustment.id,
+            },
+        );
+    }
+
+    Ok((StatusCode::CREATED, Json(reconciliation)))
 }
 
 /// Update an account
 /// PUT /accounts/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/accounts/{id}",
+    tag = "accounts",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        ("If-Match" = Option<String>, Header, description = "Version the client last saw, e.g. \"3\" (alternative to `version` in the body)"),
+    ),
+    request_body = UpdateAccountRequest,
+    responses(
+        (status = 200, description = "Account updated", body = AccountResponse),
+        (status = 400, description = "Invalid If-Match header", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Account was modified since the given version", body = VersionConflictResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdateAccountRequest>,
+    headers: HeaderMap,
+    Json(mut request): Json<UpdateAccountRequest>,
 ) -> Result<Json<AccountResponse>, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Updating account {} for user {}", id, user_id);
+    request.version = version::expected_version(&headers, request.version)?;
 
     let account = account_service::update_account(&state.db, id, user_id, request).await?;
 
@@ -74,6 +320,20 @@ pub async fn update(
 
 /// Delete an account
 /// DELETE /accounts/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/accounts/{id}",
+    tag = "accounts",
+    params(("id" = Uuid, Path, description = "Account ID")),
+    responses(
+        (status = 204, description = "Account deleted"),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 422, description = "Account has transactions", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -0,0 +1,54 @@
+use crate::handlers::json::Json;
+use crate::{AppState, errors::ApiError, models::PurgeResponse, services::purge_service};
+use axum::{extract::State, http::HeaderMap};
+use sha2::{Digest, Sha256};
+
+/// Header admin endpoints take the `ADMIN_API_KEY` in
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Permanently remove transactions deleted longer ago than the retention
+/// period (ADMIN endpoint - requires `X-Admin-Key`)
+/// POST /api/v1/admin/purge
+///
+/// The same purge runs daily in the background. Returns the cutoff and the
+/// rows removed per table.
+pub async fn purge(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeResponse>, ApiError> {
+    require_admin_key(&state, &headers)?;
+
+    tracing::info!("Purging deleted transactions on admin request");
+    let response = purge_service::purge_deleted(&state.db, &state.config.purge).await?;
+
+    Ok(Json(response))
+}
+
+/// Check the request carries the configured admin key
+///
+/// A missing or wrong key, or no configured key, is rejected with 401. The
+/// keys' digests are compared so the time taken reveals nothing about the key.
+fn require_admin_key(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(expected) = state.config.admin_api_key.as_deref() else {
+        return Err(ApiError::Unauthorized(
+            "Admin endpoints are not configured".to_string(),
+        ));
+    };
+    let given = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let expected = Sha256::digest(expected.as_bytes());
+    let given = Sha256::digest(given.as_bytes());
+    let difference = expected
+        .iter()
+        .zip(given.iter())
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b));
+    if difference != 0 {
+        tracing::warn!("Admin request with a missing or wrong admin key");
+        return Err(ApiError::Unauthorized("Invalid admin key".to_string()));
+    }
+
+    Ok(())
+}
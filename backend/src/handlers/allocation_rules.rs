@@ -0,0 +1,122 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{AllocationRuleResponse, CreateAllocationRuleRequest},
+    services::allocation_rule_service,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+/// List the authenticated user's allocation rules
+/// GET /allocation-rules
+#[utoipa::path(
+    get,
+    path = "/api/v1/allocation-rules",
+    tag = "allocation-rules",
+    responses(
+        (status = 200, description = "Allocation rules", body = Vec<AllocationRuleResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<AllocationRuleResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing allocation rules for user {}", user_id);
+
+    let rules = allocation_rule_service::list_rules(&state.read_db, user_id).await?;
+
+    Ok(Json(rules))
+}
+
+/// Create an allocation rule
+/// POST /allocation-rules
+#[utoipa::path(
+    post,
+    path = "/api/v1/allocation-rules",
+    tag = "allocation-rules",
+    request_body = CreateAllocationRuleRequest,
+    responses(
+        (status = 201, description = "Allocation rule created", body = AllocationRuleResponse),
+        (status = 403, description = "Account or category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account or category not found", body = ErrorResponse),
+        (status = 422, description = "Validation error, e.g. percentages not adding up to 100", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateAllocationRuleRequest>,
+) -> Result<(StatusCode, Json<AllocationRuleResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating allocation rule for user {}", user_id);
+
+    let rule = allocation_rule_service::create_rule(&state.db, user_id, request).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Get an allocation rule
+/// GET /allocation-rules/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/allocation-rules/{id}",
+    tag = "allocation-rules",
+    params(("id" = Uuid, Path, description = "Allocation rule ID")),
+    responses(
+        (status = 200, description = "Allocation rule", body = AllocationRuleResponse),
+        (status = 403, description = "Allocation rule belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Allocation rule not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AllocationRuleResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching allocation rule {} for user {}", id, user_id);
+
+    let rule = allocation_rule_service::get_rule(&state.read_db, id, user_id).await?;
+
+    Ok(Json(rule))
+}
+
+/// Delete an allocation rule
+/// DELETE /allocation-rules/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/allocation-rules/{id}",
+    tag = "allocation-rules",
+    params(("id" = Uuid, Path, description = "Allocation rule ID")),
+    responses(
+        (status = 204, description = "Allocation rule deleted"),
+        (status = 403, description = "Allocation rule belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Allocation rule not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Deleting allocation rule {} for user {}", id, user_id);
+
+    allocation_rule_service::delete_rule(&state.db, id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
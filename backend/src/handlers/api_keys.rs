@@ -1,3 +1,4 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
@@ -9,7 +10,6 @@ use crate::{
     services::api_key_service,
 };
 use axum::{
-    Json,
     extract::{Extension, Path, State},
     http::StatusCode,
 };
@@ -0,0 +1,85 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{
+        AuditEntityType, AuditLogQuery, AuditLogResponse, OperationType, Paginated, Pagination,
+        PaginationQuery,
+    },
+    repositories,
+};
+use axum::{
+    extract::{Extension, Query, State},
+    response::{IntoResponse, Response},
+};
+
+/// List the authenticated user's audit log, newest first
+/// GET /audit
+///
+/// API keys only see entries about entity types they have read access to;
+/// asking for another entity type is forbidden.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "audit",
+    params(AuditLogQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "Audit log entries, newest first",
+            content(
+                (Vec<AuditLogResponse> = "application/json"),
+                (Paginated<AuditLogResponse> = "application/vnd.master-of-coin.paginated+json"),
+            )
+        ),
+        (status = 400, description = "Invalid filter or pagination", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "No read access to the entity type", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<AuditLogQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing audit log for user {}", user_id);
+
+    let entity_types: Vec<AuditEntityType> = match query.entity_type {
+        Some(entity_type) => {
+            let resource = entity_type.resource_type();
+            if !auth_context.has_permission(resource, OperationType::Read) {
+                return Err(ApiError::Forbidden(format!(
+                    "Insufficient permissions: {:?} access to {:?} required",
+                    OperationType::Read,
+                    resource
+                )));
+            }
+            vec![entity_type]
+        }
+        None => AuditEntityType::ALL
+            .into_iter()
+            .filter(|t| auth_context.has_permission(t.resource_type(), OperationType::Read))
+            .collect(),
+    };
+
+    let entries = repositories::audit_log::list(
+        &state.read_db,
+        user_id,
+        entity_types.clone(),
+        query.entity_id,
+        pagination,
+    )
+    .await?;
+    let responses: Vec<AuditLogResponse> = entries.into_iter().map(|e| e.into()).collect();
+
+    if pagination.paginated {
+        let total =
+            repositories::audit_log::count(&state.read_db, user_id, entity_types, query.entity_id)
+                .await?;
+        return Ok(Json(Paginated::new(responses, total, pagination)).into_response());
+    }
+
+    Ok(Json(responses).into_response())
+}
@@ -1,44 +1,402 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
-    models::{AuthResponse, CreateUserRequest, LoginRequest, UserResponse},
-    services::auth_service,
+    errors::{ApiError, ErrorResponse},
+    models::{
+        AuthEventQuery, AuthEventResponse, AuthEventType, AuthResponse, ChangePasswordRequest,
+        CreateUserRequest, ForgotPasswordRequest, LoginRequest, LoginResponse, RefreshTokenRequest,
+        RegisterQuery, RegistrationPreferences, ResetPasswordRequest, SetupTwoFactorRequest,
+        TwoFactorSetupResponse, UserResponse, VerifyTwoFactorRequest,
+    },
+    services::{
+        auth_event_service::{self, ClientInfo},
+        auth_service, two_factor_service,
+    },
 };
 use axum::{
-    Json,
-    extract::{Extension, State},
+    extract::{Extension, Query, State},
     http::StatusCode,
 };
 
 /// Register a new user
 /// POST /auth/register
+///
+/// With `?skip_defaults=true` the user starts with a clean slate: no cash
+/// account and no default categories.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    params(RegisterQuery),
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "User registered", body = AuthResponse),
+        (status = 409, description = "Username or email already exists", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+    ),
+)]
 pub async fn register(
     State(state): State<AppState>,
-    Json(request): Json<CreateUserRequest>,
+    client: ClientInfo,
+    Query(query): Query<RegisterQuery>,
+    Json(mut request): Json<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
     tracing::info!("Registering new user: {}", request.username);
 
-    let response = auth_service::register(&state.db, &state.config.jwt, request).await?;
+    if query.skip_defaults {
+        request.preferences = Some(RegistrationPreferences {
+            create_cash_account: Some(false),
+            create_default_categories: Some(false),
+            ..request.preferences.unwrap_or_default()
+        });
+    }
+
+    let response = auth_service::register(
+        &state.db,
+        &state.config.jwt,
+        &state.config.onboarding,
+        request,
+    )
+    .await?;
+
+    auth_event_service::record(
+        &state.db,
+        Some(response.user.id),
+        AuthEventType::Register,
+        &client,
+    )
+    .await;
 
     Ok((StatusCode::CREATED, Json(response)))
 }
 
 /// Login with username/email and password
 /// POST /auth/login
+///
+/// For users with two-factor authentication enabled, a login without
+/// `two_factor_code` returns a `requires_2fa` challenge instead of tokens.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Login successful, or a two-factor challenge", body = LoginResponse),
+        (status = 401, description = "Invalid credentials or two-factor code", body = ErrorResponse),
+    ),
+)]
 pub async fn login(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(request): Json<LoginRequest>,
-) -> Result<Json<AuthResponse>, ApiError> {
+) -> Result<Json<LoginResponse>, ApiError> {
     tracing::info!("Login attempt for: {}", request.email);
 
-    let response = auth_service::login(&state.db, &state.config.jwt, request).await?;
+    let email = request.email.clone();
+
+    match auth_service::login(&state.db, &state.config.jwt, request).await {
+        Ok(response) => {
+            if let LoginResponse::Authenticated(auth) = &response {
+                auth_event_service::record(
+                    &state.db,
+                    Some(auth.user.id),
+                    AuthEventType::Login,
+                    &client,
+                )
+                .await;
+            }
+            Ok(Json(response))
+        }
+        Err(ApiError::Unauthorized(msg)) => {
+            auth_event_service::record_login_failure(&state.db, &email, &client).await;
+            Err(ApiError::Unauthorized(msg))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Exchange a refresh token for a new access token
+/// POST /auth/refresh
+///
+/// The refresh token is rotated: the response carries a new one and the old one
+/// can no longer be used. Using it again revokes every token rotated from the
+/// same login.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New access and refresh tokens", body = AuthResponse),
+        (status = 401, description = "Unknown, expired or revoked refresh token", body = ErrorResponse),
+    ),
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(request): Json<RefreshTokenRequest>,
+) -> Result<Json<AuthResponse>, ApiError> {
+    tracing::info!("Refreshing access token");
+
+    let response = auth_service::refresh(&state.db, &state.config.jwt, request).await?;
+
+    auth_event_service::record(
+        &state.db,
+        Some(response.user.id),
+        AuthEventType::TokenRefresh,
+        &client,
+    )
+    .await;
+
+    Ok(Json(response))
+}
+
+/// Request a password reset email
+/// POST /auth/forgot-password
+///
+/// Always succeeds for a well-formed email, whether or not an account uses it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "auth",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "Reset email sent if the account exists"),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+    ),
+)]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(request): Json<ForgotPasswordRequest>,
+) -> Result<StatusCode, ApiError> {
+    tracing::info!("Password reset requested");
+
+    auth_service::forgot_password(&state.db, state.email.as_ref(), request).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Set a new password with a password reset token
+/// POST /auth/reset-password
+///
+/// Logs out every session of the user: their refresh tokens are revoked.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 204, description = "Password reset"),
+        (status = 400, description = "Invalid, expired or used reset token", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+    ),
+)]
+pub async fn reset_password(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(request): Json<ResetPasswordRequest>,
+) -> Result<StatusCode, ApiError> {
+    tracing::info!("Resetting password");
+
+    let user_id = auth_service::reset_password(&state.db, request).await?;
+
+    auth_event_service::record(
+        &state.db,
+        Some(user_id),
+        AuthEventType::PasswordChanged,
+        &client,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Change the current user's password
+/// POST /auth/change-password
+///
+/// Logs out the user's other sessions: their refresh tokens are revoked,
+/// except for the session of the `refresh_token` in the request.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/change-password",
+    tag = "auth",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 401, description = "Missing authentication or wrong current password", body = ErrorResponse),
+        (status = 422, description = "Weak or unchanged new password", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    client: ClientInfo,
+    Json(request): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Changing password for user {}", user_id);
+
+    auth_service::change_password(&state.db, user_id, request).await?;
+
+    auth_event_service::record(
+        &state.db,
+        Some(user_id),
+        AuthEventType::PasswordChanged,
+        &client,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Start setting up two-factor authentication
+/// POST /auth/2fa/setup
+///
+/// Returns a new TOTP secret and recovery codes. Login doesn't ask for a code
+/// until one from the secret is confirmed at `POST /auth/2fa/verify`; calling
+/// this again before then replaces the secret and codes.
+///
+/// Requires the current password, and isn't available to API keys.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/setup",
+    tag = "auth",
+    request_body = SetupTwoFactorRequest,
+    responses(
+        (status = 200, description = "TOTP secret, otpauth URI and recovery codes", body = TwoFactorSetupResponse),
+        (status = 401, description = "Missing authentication or wrong current password", body = ErrorResponse),
+        (status = 403, description = "Authenticated with an API key", body = ErrorResponse),
+        (status = 409, description = "Two-factor authentication already enabled", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn setup_two_factor(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<SetupTwoFactorRequest>,
+) -> Result<Json<TwoFactorSetupResponse>, ApiError> {
+    require_session(&auth_context)?;
+    let user_id = auth_context.user_id();
+    tracing::info!("Starting 2FA setup for user {}", user_id);
+
+    let response = two_factor_service::setup(&state.db, user_id, request).await?;
 
     Ok(Json(response))
 }
 
+/// Confirm a code from the setup secret and enable two-factor authentication
+/// POST /auth/2fa/verify
+///
+/// Requires the current password, and isn't available to API keys.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/verify",
+    tag = "auth",
+    request_body = VerifyTwoFactorRequest,
+    responses(
+        (status = 204, description = "Two-factor authentication enabled"),
+        (status = 400, description = "Setup not started", body = ErrorResponse),
+        (status = 401, description = "Missing authentication, wrong current password or invalid code", body = ErrorResponse),
+        (status = 403, description = "Authenticated with an API key", body = ErrorResponse),
+        (status = 409, description = "Two-factor authentication already enabled", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn verify_two_factor(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    client: ClientInfo,
+    Json(request): Json<VerifyTwoFactorRequest>,
+) -> Result<StatusCode, ApiError> {
+    require_session(&auth_context)?;
+    let user_id = auth_context.user_id();
+    tracing::info!("Verifying 2FA setup for user {}", user_id);
+
+    two_factor_service::verify(&state.db, user_id, request).await?;
+
+    auth_event_service::record(
+        &state.db,
+        Some(user_id),
+        AuthEventType::TwoFactorEnabled,
+        &client,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Log out the current session
+/// POST /auth/logout
+///
+/// Revokes the refresh token of the session the access token was issued to,
+/// so it can't be exchanged again. The access token itself is stateless and
+/// the client is responsible for discarding it. The logout is recorded in the
+/// user's auth event history.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 204, description = "Session ended"),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    client: ClientInfo,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    auth_service::logout(&state.db, user_id, auth_context.session_id()).await?;
+    tracing::info!("User logged out: {}", user_id);
+
+    auth_event_service::record(&state.db, Some(user_id), AuthEventType::Logout, &client).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List recent authentication events for the current user
+/// GET /auth/events
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/events",
+    tag = "auth",
+    params(AuthEventQuery),
+    responses(
+        (status = 200, description = "Recent authentication events, newest first", body = Vec<AuthEventResponse>),
+        (status = 422, description = "Invalid query parameters", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_events(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<AuthEventQuery>,
+) -> Result<Json<Vec<AuthEventResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Listing auth events for user {}", user_id);
+
+    let events = auth_event_service::list_recent(&state.db, user_id, query).await?;
+
+    Ok(Json(events))
+}
+
 /// Get current authenticated user
 /// GET /auth/me
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Current user", body = UserResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_current_user(
     Extension(auth_context): Extension<AuthContext>,
 ) -> Result<Json<UserResponse>, ApiError> {
@@ -53,3 +411,14 @@ pub async fn get_current_user(
         created_at: user.created_at,
     }))
 }
+
+/// Refuse account security changes to API keys, which only a logged in user
+/// may make
+fn require_session(auth_context: &AuthContext) -> Result<(), ApiError> {
+    if auth_context.is_api_key() {
+        return Err(ApiError::Forbidden(
+            "Two-factor settings can't be changed with an API key".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -1,33 +1,97 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
-    models::{BudgetResponse, CreateBudgetRangeRequest, CreateBudgetRequest, UpdateBudgetRequest},
-    services::budget_service,
+    errors::{ApiError, ErrorResponse, VersionConflictResponse},
+    handlers::{etag, version},
+    models::{
+        BudgetAlertQuery, BudgetAlertResponse, BudgetListQuery, BudgetResponse, BudgetStatus,
+        BudgetStatusQuery, CreateBudgetRangeRequest, CreateBudgetRequest, MuteBudgetRequest,
+        Paginated, Pagination, PaginationQuery, SnoozeBudgetRequest, SyncQuery,
+        UpdateBudgetRequest,
+    },
+    services::{budget_alert_service, budget_service, event_service::ChangeEvent},
 };
 use axum::{
-    Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use chrono::Utc;
 use uuid::Uuid;
 
 /// List all budgets for the authenticated user
 /// GET /budgets
+///
+/// Every budget is returned unless `?paginated=true` asks for a page in a
+/// `Paginated` envelope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    params(SyncQuery, BudgetListQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "Budgets",
+            content(
+                (Vec<BudgetResponse> = "application/json"),
+                (Paginated<BudgetResponse> = "application/vnd.master-of-coin.paginated+json"),
+            )
+        ),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-) -> Result<Json<Vec<BudgetResponse>>, ApiError> {
+    Query(query): Query<SyncQuery>,
+    Query(list_query): Query<BudgetListQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Listing budgets for user {}", user_id);
 
-    let budgets = budget_service::list_budgets(&state.db, user_id).await?;
+    let since = query.updated_since;
+    let page = pagination.paginated.then_some(pagination);
+    let budgets =
+        budget_service::list_budgets(&state.read_db, user_id, query, list_query, page).await?;
 
-    Ok(Json(budgets))
+    let statuses: Vec<BudgetStatus> = budgets
+        .iter()
+        .filter_map(|budget| budget.status.clone())
+        .collect();
+    budget_alert_service::record_crossings(
+        &state.db,
+        &state.events,
+        &state.notifier,
+        user_id,
+        &statuses,
+    )
+    .await?;
+
+    if pagination.paginated {
+        let total = budget_service::count_budgets(&state.read_db, user_id, since).await?;
+        return Ok(Json(Paginated::new(budgets, total, pagination)).into_response());
+    }
+
+    Ok(Json(budgets).into_response())
 }
 
 /// Create a new budget
 /// POST /budgets
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    request_body = CreateBudgetRequest,
+    responses(
+        (status = 201, description = "Budget created", body = BudgetResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -38,42 +102,147 @@ pub async fn create(
 
     let budget = budget_service::create_budget(&state.db, user_id, request).await?;
 
+    state.events.publish(
+        user_id,
+        ChangeEvent::BudgetChanged {
+            budget_id: budget.id,
+        },
+    );
+
     Ok((StatusCode::CREATED, Json(budget)))
 }
 
 /// Get a single budget by ID
 /// GET /budgets/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(
+        ("id" = Uuid, Path, description = "Budget ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "Budget", body = BudgetResponse),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<BudgetResponse>, ApiError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::debug!("Fetching budget {} for user {}", id, user_id);
 
-    let budget = budget_service::get_budget(&state.db, id, user_id).await?;
+    let budget = budget_service::get_budget(&state.read_db, id, user_id).await?;
 
-    Ok(Json(budget))
+    etag::json_with_etag(&headers, &budget)
+}
+
+/// Get a budget's spending against its limit
+/// GET /budgets/:id/status
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/{id}/status",
+    tag = "budgets",
+    params(
+        ("id" = Uuid, Path, description = "Budget ID"),
+        BudgetStatusQuery,
+    ),
+    responses(
+        (status = 200, description = "Status for the range containing `as_of`", body = BudgetStatus),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found, or no range covers `as_of`", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn status(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BudgetStatusQuery>,
+) -> Result<Json<BudgetStatus>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching status of budget {} for user {}", id, user_id);
+
+    let as_of = query.as_of.unwrap_or_else(|| Utc::now().date_naive());
+    let status = budget_service::compute_status(&state.read_db, id, user_id, as_of).await?;
+    budget_alert_service::record_crossings(
+        &state.db,
+        &state.events,
+        &state.notifier,
+        user_id,
+        std::slice::from_ref(&status),
+    )
+    .await?;
+
+    Ok(Json(status))
 }
 
 /// Update a budget
 /// PUT /budgets/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(
+        ("id" = Uuid, Path, description = "Budget ID"),
+        ("If-Match" = Option<String>, Header, description = "Version the client last saw, e.g. \"3\" (alternative to `version` in the body)"),
+    ),
+    request_body = UpdateBudgetRequest,
+    responses(
+        (status = 200, description = "Budget updated", body = BudgetResponse),
+        (status = 400, description = "Invalid If-Match header", body = ErrorResponse),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 409, description = "Budget was modified since the given version", body = VersionConflictResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdateBudgetRequest>,
+    headers: HeaderMap,
+    Json(mut request): Json<UpdateBudgetRequest>,
 ) -> Result<Json<BudgetResponse>, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Updating budget {} for user {}", id, user_id);
+    request.version = version::expected_version(&headers, request.version)?;
 
     let budget = budget_service::update_budget(&state.db, id, user_id, request).await?;
 
+    state
+        .events
+        .publish(user_id, ChangeEvent::BudgetChanged { budget_id: id });
+
     Ok(Json(budget))
 }
 
 /// Delete a budget
 /// DELETE /budgets/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path, description = "Budget ID")),
+    responses(
+        (status = 204, description = "Budget deleted"),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -84,11 +253,99 @@ pub async fn delete(
 
     budget_service::delete_budget(&state.db, id, user_id).await?;
 
+    state
+        .events
+        .publish(user_id, ChangeEvent::BudgetDeleted { budget_id: id });
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Mute or unmute a budget's alerts
+/// POST /budgets/:id/mute
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/{id}/mute",
+    tag = "budgets",
+    params(("id" = Uuid, Path, description = "Budget ID")),
+    request_body = MuteBudgetRequest,
+    responses(
+        (status = 200, description = "Budget with updated alert muting", body = BudgetResponse),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn mute(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<MuteBudgetRequest>,
+) -> Result<Json<BudgetResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Muting budget {} for user {}", id, user_id);
+
+    let budget = budget_service::mute_budget(&state.db, id, user_id, request).await?;
+
+    state
+        .events
+        .publish(user_id, ChangeEvent::BudgetChanged { budget_id: id });
+
+    Ok(Json(budget))
+}
+
+/// Snooze a budget's alerts until a given time
+/// POST /budgets/:id/snooze
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/{id}/snooze",
+    tag = "budgets",
+    params(("id" = Uuid, Path, description = "Budget ID")),
+    request_body = SnoozeBudgetRequest,
+    responses(
+        (status = 200, description = "Budget with updated snooze", body = BudgetResponse),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 422, description = "Snooze end is not in the future", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn snooze(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<SnoozeBudgetRequest>,
+) -> Result<Json<BudgetResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Snoozing budget {} for user {}", id, user_id);
+
+    let budget = budget_service::snooze_budget(&state.db, id, user_id, request).await?;
+
+    state
+        .events
+        .publish(user_id, ChangeEvent::BudgetChanged { budget_id: id });
+
+    Ok(Json(budget))
+}
+
 /// Add a budget range to a budget
 /// POST /budgets/:id/ranges
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/{id}/ranges",
+    tag = "budgets",
+    params(("id" = Uuid, Path, description = "Budget ID")),
+    request_body = CreateBudgetRangeRequest,
+    responses(
+        (status = 201, description = "Budget range added", body = crate::models::BudgetRangeResponse),
+        (status = 403, description = "Budget belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn add_range(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -100,5 +357,63 @@ pub async fn add_range(
 
     let range = budget_service::add_range(&state.db, budget_id, user_id, request).await?;
 
+    state
+        .events
+        .publish(user_id, ChangeEvent::BudgetChanged { budget_id });
+
     Ok((StatusCode::CREATED, Json(range)))
 }
+
+/// List budget alerts for the authenticated user
+/// GET /budgets/alerts
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/alerts",
+    tag = "budgets",
+    params(BudgetAlertQuery),
+    responses(
+        (status = 200, description = "Budget alerts, newest first", body = Vec<BudgetAlertResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<BudgetAlertQuery>,
+) -> Result<Json<Vec<BudgetAlertResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing budget alerts for user {}", user_id);
+
+    let alerts = budget_alert_service::list_alerts(&state.read_db, user_id, query).await?;
+
+    Ok(Json(alerts))
+}
+
+/// Mark a budget alert read
+/// POST /budgets/alerts/:id/ack
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/alerts/{id}/ack",
+    tag = "budgets",
+    params(("id" = Uuid, Path, description = "Budget alert ID")),
+    responses(
+        (status = 200, description = "Acknowledged alert", body = BudgetAlertResponse),
+        (status = 403, description = "Alert belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Alert not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn ack_alert(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BudgetAlertResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Acknowledging budget alert {} for user {}", id, user_id);
+
+    let alert = budget_alert_service::acknowledge_alert(&state.db, id, user_id).await?;
+
+    Ok(Json(alert))
+}
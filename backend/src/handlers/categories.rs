@@ -1,33 +1,61 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
     errors::ApiError,
-    models::{CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest},
+    handlers::etag,
+    models::{
+        CategoryReassignment, CategoryResponse, CreateCategoryRequest, DeleteCategoryQuery,
+        DeleteCategoryResponse, MergeCategoryRequest, MergeCategoryResponse, OperationType,
+        Paginated, Pagination, ResourceType, SyncQuery, UpdateCategoryRequest,
+    },
     repositories,
+    services::event_service::ChangeEvent,
 };
 use axum::{
-    Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
 use validator::Validate;
 
 /// List all categories for the authenticated user
 /// GET /categories
+///
+/// Every category is returned unless `?paginated=true` asks for a page in a
+/// `Paginated` envelope.
 pub async fn list(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-) -> Result<Json<Vec<CategoryResponse>>, ApiError> {
+    Query(query): Query<SyncQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Listing categories for user {}", user_id);
 
-    let categories = repositories::category::list_by_user(&state.db, user_id).await?;
+    let categories = match (pagination.paginated, query.updated_since) {
+        (true, since) => {
+            repositories::category::list_page_by_user(&state.read_db, user_id, since, pagination)
+                .await?
+        }
+        (false, Some(since)) => {
+            repositories::category::list_updated_since(&state.read_db, user_id, since).await?
+        }
+        (false, None) => repositories::category::list_by_user(&state.read_db, user_id).await?,
+    };
 
     let responses: Vec<CategoryResponse> =
         categories.into_iter().map(CategoryResponse::from).collect();
 
-    Ok(Json(responses))
+    if pagination.paginated {
+        let total =
+            repositories::category::count_by_user(&state.read_db, user_id, query.updated_since)
+                .await?;
+        return Ok(Json(Paginated::new(responses, total, pagination)).into_response());
+    }
+
+    Ok(Json(responses).into_response())
 }
 
 /// Create a new category
@@ -59,6 +87,31 @@ pub async fn create(
     Ok((StatusCode::CREATED, Json(category.into())))
 }
 
+/// Get a single category by ID
+/// GET /categories/:id
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching category {} for user {}", id, user_id);
+
+    let category = repositories::category::find_by_id(&state.read_db, id).await?;
+
+    // Verify ownership
+    if category.user_id != user_id {
+        return Err(ApiError::Forbidden(
+            "Category does not belong to user".to_string(),
+        ));
+    }
+
+    let response: CategoryResponse = category.into();
+
+    etag::json_with_etag(&headers, &response)
+}
+
 /// Update a category
 /// PUT /categories/:id
 pub async fn update(
@@ -97,23 +150,134 @@ pub async fn update(
 
 /// Delete a category
 /// DELETE /categories/:id
+///
+/// The category's transactions move to `?reassign_to=` if given, or are left
+/// uncategorized otherwise. Budgets filtering on the category follow them:
+/// their filter moves to `reassign_to`, or is cleared. Everything happens in
+/// one DB transaction, and the number of transactions and budgets changed is
+/// returned. Reassigning needs write access to transactions and budgets on top
+/// of the route's category scope.
 pub async fn delete(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<StatusCode, ApiError> {
+    Query(query): Query<DeleteCategoryQuery>,
+) -> Result<Json<DeleteCategoryResponse>, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Deleting category {} for user {}", id, user_id);
 
-    // Verify ownership
-    let category = repositories::category::find_by_id(&state.db, id).await?;
-    if category.user_id != user_id {
-        return Err(ApiError::Forbidden(
-            "Category does not belong to user".to_string(),
+    let mut category_ids = vec![id];
+    if let Some(target_id) = query.reassign_to {
+        require_reassign_permissions(&auth_context)?;
+        if target_id == id {
+            return Err(ApiError::Validation(
+                "Cannot reassign a category's transactions to itself".to_string(),
+            ));
+        }
+        category_ids.push(target_id);
+    }
+    verify_ownership(&state, user_id, &category_ids).await?;
+
+    let reassignment =
+        repositories::category::delete_category(&state.db, id, query.reassign_to).await?;
+    publish_reassignment(&state, user_id, &reassignment);
+
+    Ok(Json(DeleteCategoryResponse {
+        reassigned_to: query.reassign_to,
+        affected_transactions: reassignment.transaction_ids.len(),
+        affected_budgets: reassignment.budget_ids.len(),
+        deactivated_budgets: reassignment.deactivated_budget_ids.len(),
+    }))
+}
+
+/// Merge a category into another one
+/// POST /categories/:id/merge
+///
+/// The category's transactions, the budgets filtering on it, its recurring
+/// transactions, rules and subcategories move to `target_category_id`, then the
+/// category is deleted, all in one DB transaction. Moving transactions and
+/// budgets needs write access to them on top of the route's category scope.
+pub async fn merge(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<MergeCategoryRequest>,
+) -> Result<Json<MergeCategoryResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    let target_id = request.target_category_id;
+    tracing::info!(
+        "Merging category {} into {} for user {}",
+        id,
+        target_id,
+        user_id
+    );
+
+    require_reassign_permissions(&auth_context)?;
+
+    if id == target_id {
+        return Err(ApiError::Validation(
+            "Cannot merge a category into itself".to_string(),
         ));
     }
 
-    repositories::category::delete_category(&state.db, id).await?;
+    verify_ownership(&state, user_id, &[id, target_id]).await?;
 
-    Ok(StatusCode::NO_CONTENT)
+    let reassignment =
+        repositories::category::delete_category(&state.db, id, Some(target_id)).await?;
+    publish_reassignment(&state, user_id, &reassignment);
+
+    Ok(Json(MergeCategoryResponse {
+        target_category_id: target_id,
+        reassigned_transactions: reassignment.transaction_ids.len(),
+        reassigned_budgets: reassignment.budget_ids.len(),
+    }))
+}
+
+/// Moving a category's transactions and budgets writes to both
+fn require_reassign_permissions(auth_context: &AuthContext) -> Result<(), ApiError> {
+    for resource in [ResourceType::Transactions, ResourceType::Budgets] {
+        if !auth_context.has_permission(resource, OperationType::Write) {
+            return Err(ApiError::Forbidden(format!(
+                "Insufficient permissions: {:?} access to {:?} required",
+                OperationType::Write,
+                resource
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn verify_ownership(
+    state: &AppState,
+    user_id: Uuid,
+    category_ids: &[Uuid],
+) -> Result<(), ApiError> {
+    for category_id in category_ids {
+        let category = repositories::category::find_by_id(&state.db, *category_id).await?;
+        if category.user_id != user_id {
+            return Err(ApiError::Forbidden(
+                "Category does not belong to user".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn publish_reassignment(state: &AppState, user_id: Uuid, reassignment: &CategoryReassignment) {
+    for transaction_id in &reassignment.transaction_ids {
+        state.events.publish(
+            user_id,
+            ChangeEvent::TransactionUpdated {
+                transaction_id: *transaction_id,
+            },
+        );
+    }
+    for budget_id in &reassignment.budget_ids {
+        state.events.publish(
+            user_id,
+            ChangeEvent::BudgetChanged {
+                budget_id: *budget_id,
+            },
+        );
+    }
 }
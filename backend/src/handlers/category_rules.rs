@@ -0,0 +1,191 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{
+        ApplyCategoryRulesResponse, CategoryRuleResponse, CreateCategoryRuleRequest,
+        UpdateCategoryRuleRequest,
+    },
+    services::{category_rule_service, event_service::ChangeEvent},
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+/// List the authenticated user's category rules
+/// GET /category-rules
+#[utoipa::path(
+    get,
+    path = "/api/v1/category-rules",
+    tag = "category-rules",
+    responses(
+        (status = 200, description = "Category rules in the order they are tried", body = Vec<CategoryRuleResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<CategoryRuleResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing category rules for user {}", user_id);
+
+    let rules = category_rule_service::list_rules(&state.read_db, user_id).await?;
+
+    Ok(Json(rules))
+}
+
+/// Create a category rule
+/// POST /category-rules
+#[utoipa::path(
+    post,
+    path = "/api/v1/category-rules",
+    tag = "category-rules",
+    request_body = CreateCategoryRuleRequest,
+    responses(
+        (status = 201, description = "Category rule created", body = CategoryRuleResponse),
+        (status = 403, description = "Category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 422, description = "Validation error or invalid regex", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateCategoryRuleRequest>,
+) -> Result<(StatusCode, Json<CategoryRuleResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating category rule for user {}", user_id);
+
+    let rule = category_rule_service::create_rule(&state.db, user_id, request).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Get a category rule
+/// GET /category-rules/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/category-rules/{id}",
+    tag = "category-rules",
+    params(("id" = Uuid, Path, description = "Category rule ID")),
+    responses(
+        (status = 200, description = "Category rule", body = CategoryRuleResponse),
+        (status = 403, description = "Category rule belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Category rule not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CategoryRuleResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching category rule {} for user {}", id, user_id);
+
+    let rule = category_rule_service::get_rule(&state.read_db, id, user_id).await?;
+
+    Ok(Json(rule))
+}
+
+/// Update a category rule
+/// PUT /category-rules/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/category-rules/{id}",
+    tag = "category-rules",
+    params(("id" = Uuid, Path, description = "Category rule ID")),
+    request_body = UpdateCategoryRuleRequest,
+    responses(
+        (status = 200, description = "Category rule updated", body = CategoryRuleResponse),
+        (status = 403, description = "Category rule or category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Category rule or category not found", body = ErrorResponse),
+        (status = 422, description = "Validation error or invalid regex", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateCategoryRuleRequest>,
+) -> Result<Json<CategoryRuleResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Updating category rule {} for user {}", id, user_id);
+
+    let rule = category_rule_service::update_rule(&state.db, id, user_id, request).await?;
+
+    Ok(Json(rule))
+}
+
+/// Delete a category rule
+/// DELETE /category-rules/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/category-rules/{id}",
+    tag = "category-rules",
+    params(("id" = Uuid, Path, description = "Category rule ID")),
+    responses(
+        (status = 204, description = "Category rule deleted"),
+        (status = 403, description = "Category rule belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Category rule not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Deleting category rule {} for user {}", id, user_id);
+
+    category_rule_service::delete_rule(&state.db, id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Categorize existing uncategorized transactions with the category rules
+/// POST /category-rules/apply
+#[utoipa::path(
+    post,
+    path = "/api/v1/category-rules/apply",
+    tag = "category-rules",
+    responses(
+        (status = 200, description = "Number of transactions given a category", body = ApplyCategoryRulesResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn apply(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<ApplyCategoryRulesResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Applying category rules for user {}", user_id);
+
+    let updated = category_rule_service::apply_to_uncategorized(&state.db, user_id).await?;
+
+    for transaction_id in &updated {
+        state.events.publish(
+            user_id,
+            ChangeEvent::TransactionUpdated {
+                transaction_id: *transaction_id,
+            },
+        );
+    }
+
+    Ok(Json(ApplyCategoryRulesResponse {
+        updated: updated.len(),
+    }))
+}
@@ -1,24 +1,241 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
-    services::analytics_service::{self, DashboardSummary},
-};
-use axum::{
-    Json,
-    extract::{Extension, State},
+    errors::{ApiError, ErrorResponse},
+    models::{BudgetStatus, TransactionResponse},
+    services::{
+        analytics_service::{
+            self, Cashflow, CashflowQuery, DashboardCategories, DashboardQuery, DashboardSummary,
+            MerchantSpending, MerchantSpendingQuery, NetWorth, NetWorthTrend, NetWorthTrendQuery,
+        },
+        budget_alert_service,
+    },
 };
+use axum::extract::{Extension, Query, State};
 
 /// Get dashboard summary for the authenticated user
 /// GET /dashboard
+///
+/// The category breakdown is converted into `base_currency` (default: the primary
+/// currency), or reported per currency with `group_by_currency=true`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard",
+    tag = "dashboard",
+    params(DashboardQuery),
+    responses(
+        (status = 200, description = "Dashboard summary", body = DashboardSummary),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_summary(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<DashboardQuery>,
 ) -> Result<Json<DashboardSummary>, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Fetching dashboard summary for user {}", user_id);
 
-    let summary = analytics_service::get_dashboard_summary(&state.db, user_id).await?;
+    let summary = analytics_service::get_dashboard_summary(&state.read_db, user_id, query).await?;
+    budget_alert_service::record_crossings(
+        &state.db,
+        &state.events,
+        &state.notifier,
+        user_id,
+        &summary.budget_statuses,
+    )
+    .await?;
 
     Ok(Json(summary))
 }
+
+/// Get current net worth for the authenticated user
+/// GET /dashboard/net-worth
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/net-worth",
+    tag = "dashboard",
+    responses(
+        (status = 200, description = "Net worth and each account's balance, in the primary currency", body = NetWorth),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn net_worth(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<NetWorth>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching net worth for user {}", user_id);
+
+    let net_worth = analytics_service::calculate_net_worth(&state.read_db, user_id).await?;
+
+    Ok(Json(net_worth))
+}
+
+/// Get the most recent transactions for the authenticated user
+/// GET /dashboard/recent
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/recent",
+    tag = "dashboard",
+    responses(
+        (status = 200, description = "Last 10 transactions, newest first", body = Vec<TransactionResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn recent_transactions(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<TransactionResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching recent transactions for user {}", user_id);
+
+    let transactions = analytics_service::get_recent_transactions(&state.read_db, user_id).await?;
+
+    Ok(Json(transactions))
+}
+
+/// Get the status of every active budget for the authenticated user
+/// GET /dashboard/budgets
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/budgets",
+    tag = "dashboard",
+    responses(
+        (status = 200, description = "Current-period status of each budget with an active range", body = Vec<BudgetStatus>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn budget_statuses(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<BudgetStatus>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching budget statuses for user {}", user_id);
+
+    let statuses = analytics_service::get_all_budget_statuses(&state.read_db, user_id).await?;
+    budget_alert_service::record_crossings(
+        &state.db,
+        &state.events,
+        &state.notifier,
+        user_id,
+        &statuses,
+    )
+    .await?;
+
+    Ok(Json(statuses))
+}
+
+/// Get the category breakdown of the last 30 days for the authenticated user
+/// GET /dashboard/categories
+///
+/// Takes the same currency parameters as the dashboard summary.
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/categories",
+    tag = "dashboard",
+    params(DashboardQuery),
+    responses(
+        (status = 200, description = "Category breakdown and uncategorized count", body = DashboardCategories),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn categories(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<DashboardQuery>,
+) -> Result<Json<DashboardCategories>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching category breakdown for user {}", user_id);
+
+    let categories =
+        analytics_service::get_dashboard_categories(&state.read_db, user_id, query).await?;
+
+    Ok(Json(categories))
+}
+
+/// Get spending grouped by merchant for the authenticated user
+/// GET /dashboard/merchants
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/merchants",
+    tag = "dashboard",
+    params(MerchantSpendingQuery),
+    responses(
+        (status = 200, description = "Spending per merchant and currency, largest first", body = Vec<MerchantSpending>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn merchant_spending(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<MerchantSpendingQuery>,
+) -> Result<Json<Vec<MerchantSpending>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching merchant spending for user {}", user_id);
+
+    let report = analytics_service::get_merchant_spending(&state.read_db, user_id, query).await?;
+
+    Ok(Json(report))
+}
+
+/// Get net worth at the end of each recent month for the authenticated user
+/// GET /dashboard/net-worth-trend
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/net-worth-trend",
+    tag = "dashboard",
+    params(NetWorthTrendQuery),
+    responses(
+        (status = 200, description = "Net worth at the end of each month, oldest first", body = NetWorthTrend),
+        (status = 400, description = "Month count out of range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn net_worth_trend(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<NetWorthTrendQuery>,
+) -> Result<Json<NetWorthTrend>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching net worth trend for user {}", user_id);
+
+    let trend = analytics_service::get_net_worth_trend(&state.read_db, user_id, query).await?;
+
+    Ok(Json(trend))
+}
+
+/// Get income and expense per recent month for the authenticated user
+/// GET /dashboard/cashflow
+#[utoipa::path(
+    get,
+    path = "/api/v1/dashboard/cashflow",
+    tag = "dashboard",
+    params(CashflowQuery),
+    responses(
+        (status = 200, description = "Income, expense and net per month, oldest first", body = Cashflow),
+        (status = 400, description = "Month count out of range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cashflow(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<CashflowQuery>,
+) -> Result<Json<Cashflow>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Fetching cashflow for user {}", user_id);
+
+    let cashflow = analytics_service::get_cashflow(&state.read_db, user_id, query).await?;
+
+    Ok(Json(cashflow))
+}
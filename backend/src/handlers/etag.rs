@@ -0,0 +1,69 @@
+//! Conditional GET support
+//!
+//! Single-resource GET handlers tag their JSON body with a weak ETag and answer
+//! `If-None-Match` requests for an unchanged resource with `304 Not Modified`.
+//!
+//! The ETag is derived from the serialized response rather than from
+//! `updated_at`, because some responses (e.g. account balances or transaction
+//! splits) change without the underlying row being touched. Every response
+//! serializes its `updated_at`, so the ETag also changes with each update.
+
+use axum::{
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::errors::ApiError;
+
+/// Respond with `body` as JSON tagged with its ETag, or with 304 if the client's copy is current
+pub fn json_with_etag<T: Serialize>(headers: &HeaderMap, body: &T) -> Result<Response, ApiError> {
+    let bytes = serde_json::to_vec(body).map_err(|e| {
+        tracing::error!("Failed to serialize response for ETag: {}", e);
+        ApiError::Internal
+    })?;
+    let etag = weak_etag(&bytes);
+    let etag_header = HeaderValue::from_str(&etag).map_err(|_| ApiError::Internal)?;
+
+    if if_none_match(headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag_header)]).into_response());
+    }
+
+    Ok((
+        [
+            (ETAG, etag_header),
+            (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Weak ETag of a serialized response body
+///
+/// SHA-256 keeps the tag stable across builds and between instances of a mixed
+/// deploy, which the standard library's hasher does not promise.
+fn weak_etag(bytes: &[u8]) -> String {
+    let digest: String = Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("W/\"{}\"", digest)
+}
+
+/// Whether any entity tag in `If-None-Match` matches `etag` (weak comparison)
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let current = opaque(etag);
+
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == current)
+}
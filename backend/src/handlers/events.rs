@@ -0,0 +1,54 @@
+use std::convert::Infallible;
+
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::ErrorResponse,
+    services::event_service::{ChangeEvent, StreamItem},
+};
+use axum::{
+    extract::{Extension, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{Stream, StreamExt};
+
+/// Stream change notifications for the authenticated user
+/// GET /events
+///
+/// Server-sent events named after the change (e.g. `transaction_created`) carry
+/// the [`ChangeEvent`] as JSON data. A `lagged` event, whose data is the number
+/// of missed events, means notifications were dropped and everything should be
+/// refetched.
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "events",
+    responses(
+        (status = 200, description = "Stream of server-sent change events", content_type = "text/event-stream", body = ChangeEvent),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn stream(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Opening event stream for user {}", user_id);
+
+    let events = state.events.subscribe(user_id).map(|item| {
+        let event = match item {
+            StreamItem::Event(change) => Event::default()
+                .event(change.name())
+                .json_data(&change)
+                .unwrap_or_else(|e| {
+                    tracing::error!("Failed to serialize change event: {}", e);
+                    Event::default().event(change.name())
+                }),
+            StreamItem::Lagged(missed) => Event::default().event("lagged").data(missed.to_string()),
+        };
+        Ok(event)
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
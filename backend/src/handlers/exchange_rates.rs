@@ -1,41 +1,91 @@
+use crate::handlers::json::Json;
 use crate::{
+    AppState,
     auth::context::AuthContext,
     errors::ApiError,
-    models::{ExchangeRateQuery, ExchangeRateResponse},
+    models::{
+        ConversionResponse, ConvertQuery, CustomRateQuery, CustomRateRequest, CustomRateResponse,
+        ExchangeRateQuery, ExchangeRateResponse, HistoricalRateResponse, NewCustomExchangeRate,
+    },
+    repositories,
     services::exchange_rate_service::{ExchangeRateService, PRIMARY_CURRENCY},
     types::CurrencyCode,
 };
 use axum::{
-    Json,
-    extract::{Extension, Query},
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, RoundingMode, Signed};
+use chrono::Utc;
 use std::collections::HashMap;
 
 /// Get exchange rates with configurable base currency
 /// GET /exchange-rates?base=EUR
+/// GET /exchange-rates?base=USD&quote=EUR&date=2023-06-01
 ///
 /// Returns current exchange rates for all supported currencies.
-/// Rates are cached for 24 hours to minimize API calls.
+/// Rates are cached for 24 hours to minimize API calls, and concurrent
+/// requests for the same base currency share a single upstream fetch.
+///
+/// With `quote`, returns only the rate from `base` to `quote` on `date`
+/// (today by default). Rates are stored per day; a day without a stored rate
+/// is fetched from the provider, falling back to the nearest earlier stored rate.
 ///
 /// # Query Parameters
 ///
 /// * `base` - Optional base currency code (defaults to EUR)
+/// * `quote` - Optional currency code to get the single rate to
+/// * `date` - Optional day of the rate (`YYYY-MM-DD`); requires `quote`
 ///
 /// # Returns
 ///
 /// * `ExchangeRateResponse` - Exchange rates for all supported currencies
+/// * `HistoricalRateResponse` - The rate to `quote`, when given
 ///
 /// # Errors
 ///
+/// * `ApiError::BadRequest` - If `date` is given without `quote`
 /// * `ApiError::Internal` - If exchange rate service fails
 pub async fn get_exchange_rates(
+    State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Query(query): Query<ExchangeRateQuery>,
-) -> Result<Json<ExchangeRateResponse>, ApiError> {
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     let base_currency = query.base.unwrap_or(PRIMARY_CURRENCY);
 
+    if let Some(quote_currency) = query.quote {
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        tracing::info!(
+            "Fetching exchange rate from {} to {} on {} for user {}",
+            base_currency.as_str(),
+            quote_currency.as_str(),
+            date,
+            user_id
+        );
+
+        let rate = exchange_rate_service(&state)?
+            .with_manual_rates(state.db.clone(), user_id)
+            .get_rate_on(&state.db, base_currency, quote_currency, date)
+            .await?;
+
+        let response = HistoricalRateResponse {
+            base: base_currency,
+            quote: quote_currency,
+            date,
+            rate_date: rate.date,
+            rate: rate.rate.to_string(),
+            fetched_at: rate.fetched_at,
+        };
+        return Ok(Json(response).into_response());
+    }
+    if query.date.is_some() {
+        return Err(ApiError::BadRequest(
+            "quote is required with date".to_string(),
+        ));
+    }
+
     tracing::info!(
         "Fetching exchange rates for user {} with base currency {}",
         user_id,
@@ -43,10 +93,8 @@ pub async fn get_exchange_rates(
     );
 
     // Get exchange rate service
-    let exchange_rate_service = ExchangeRateService::new()?;
-
     // Fetch rates from service (uses cache if available)
-    let rates: HashMap<CurrencyCode, BigDecimal> = exchange_rate_service
+    let rates: HashMap<CurrencyCode, BigDecimal> = exchange_rate_service(&state)?
         .get_exchange_rates(base_currency)
         .await?;
 
@@ -62,5 +110,167 @@ pub async fn get_exchange_rates(
         conversion_rates,
     };
 
-    Ok(Json(response))
+    Ok(Json(response).into_response())
+}
+
+/// Preview a currency conversion
+/// GET /exchange-rates/convert?from=EUR&to=USD&amount=20
+///
+/// Converts an amount with the same cached rates as `GET /exchange-rates`, or
+/// the current user's manual rate for the pair, without recording anything.
+///
+/// # Query Parameters
+///
+/// * `from` - Currency code the amount is given in
+/// * `to` - Currency code to convert to
+/// * `amount` - Positive amount to convert
+///
+/// # Returns
+///
+/// * `ConversionResponse` - Converted amount, the rate used and when it was fetched
+///
+/// # Errors
+///
+/// * `ApiError::BadRequest` - If the amount is not positive
+/// * `ApiError::Internal` - If exchange rate service fails
+pub async fn convert(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<ConvertQuery>,
+) -> Result<Json<ConversionResponse>, ApiError> {
+    if !query.amount.is_positive() {
+        return Err(ApiError::BadRequest(
+            "amount must be greater than 0".to_string(),
+        ));
+    }
+
+    tracing::info!(
+        "Previewing conversion from {} to {} for user {}",
+        query.from.as_str(),
+        query.to.as_str(),
+        auth_context.user_id()
+    );
+
+    let rate = exchange_rate_service(&state)?
+        .with_manual_rates(state.db.clone(), auth_context.user_id())
+        .get_rate(query.from, query.to)
+        .await?;
+
+    let converted_amount = (&query.amount * &rate.rate)
+        .with_scale_round(query.to.minor_units(), RoundingMode::HalfEven);
+
+    Ok(Json(ConversionResponse {
+        from: query.from,
+        to: query.to,
+        amount: query.amount.to_string(),
+        converted_amount: converted_amount.to_string(),
+        rate: rate.rate.to_string(),
+        fetched_at: rate.fetched_at,
+    }))
+}
+
+/// Set a manual exchange rate
+/// POST /exchange-rates/custom
+///
+/// Stores the rate from `base` to `quote` on `date` (today by default) as a
+/// manual rate of the current user. Manual rates are used instead of the
+/// fetched rate for their pair and day in that user's conversions, and a pair
+/// no provider has falls back to their latest manual rate. Other users'
+/// conversions are not affected.
+///
+/// # Errors
+///
+/// * `ApiError::Validation` - If a currency is unknown, both currencies are
+///   the same, or the rate is not positive
+pub async fn create_custom_rate(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CustomRateRequest>,
+) -> Result<(StatusCode, Json<CustomRateResponse>), ApiError> {
+    if request.base == request.quote {
+        return Err(ApiError::Validation(
+            "base and quote must be different currencies".to_string(),
+        ));
+    }
+    if !request.rate.is_positive() {
+        return Err(ApiError::Validation(
+            "rate must be greater than 0".to_string(),
+        ));
+    }
+
+    let user_id = auth_context.user_id();
+    let date = request.date.unwrap_or_else(|| Utc::now().date_naive());
+    tracing::info!(
+        "Setting manual exchange rate from {} to {} on {} for user {}",
+        request.base.as_str(),
+        request.quote.as_str(),
+        date,
+        user_id
+    );
+
+    let record = repositories::exchange_rate::upsert_manual_rate(
+        &state.db,
+        NewCustomExchangeRate {
+            user_id,
+            base_currency: request.base,
+            quote_currency: request.quote,
+            rate_date: date,
+            rate: request.rate,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(record.into())))
+}
+
+/// Remove a manual exchange rate
+/// DELETE /exchange-rates/custom?base=USD&quote=EUR&date=2023-06-01
+///
+/// Removes the current user's manual rate from `base` to `quote` on `date`
+/// (today by default).
+///
+/// # Errors
+///
+/// * `ApiError::NotFound` - If no manual rate is set for the pair and day
+pub async fn delete_custom_rate(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<CustomRateQuery>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    tracing::info!(
+        "Removing manual exchange rate from {} to {} on {} for user {}",
+        query.base.as_str(),
+        query.quote.as_str(),
+        date,
+        user_id
+    );
+
+    let deleted = repositories::exchange_rate::delete_manual_rate(
+        &state.db,
+        user_id,
+        query.base,
+        query.quote,
+        date,
+    )
+    .await?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!(
+            "No manual exchange rate from {} to {} on {}",
+            query.base.as_str(),
+            query.quote.as_str(),
+            date
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The application's exchange rate service, failing when no rate provider is available
+fn exchange_rate_service(state: &AppState) -> Result<ExchangeRateService, ApiError> {
+    state.exchange_rates.clone().ok_or_else(|| {
+        tracing::error!("No exchange rate provider is available");
+        ApiError::Internal
+    })
 }
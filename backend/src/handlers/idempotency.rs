@@ -0,0 +1,37 @@
+//! Idempotent create requests
+//!
+//! A create request may carry an `Idempotency-Key` header, a client-chosen
+//! string (e.g. a UUID) identifying the request. Retrying with the same key, for
+//! instance after a timeout, returns the resource created by the first attempt
+//! instead of creating a duplicate. Keys are scoped to the user and the kind of
+//! resource.
+
+use axum::http::HeaderMap;
+
+use crate::errors::ApiError;
+
+/// Name of the header carrying the idempotency key
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Longest accepted idempotency key
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Idempotency key of a request, if it sent one
+pub fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, ApiError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY) else {
+        return Ok(None);
+    };
+
+    let key = value
+        .to_str()
+        .map_err(|_| ApiError::BadRequest("Invalid Idempotency-Key header".to_string()))?
+        .trim();
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(ApiError::BadRequest(format!(
+            "Idempotency-Key must be between 1 and {} characters",
+            MAX_KEY_LENGTH
+        )));
+    }
+
+    Ok(Some(key.to_string()))
+}
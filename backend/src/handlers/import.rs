@@ -1,12 +1,16 @@
 //! Import API handlers
 //!
-//! This module provides HTTP endpoints for CSV statement import functionality:
+//! This module provides HTTP endpoints for statement import functionality:
 //! - Parse CSV files and return transactions for preview
+//! - Import large CSV files in batches while they are uploaded
+//! - Import OFX/QFX statements, skipping transactions imported before
 //! - Bulk create transactions from parsed data
+//! - Import aggregator (Plaid-style) exports idempotently
 
+use crate::handlers::json::Json;
 use axum::{
-    Extension, Json,
-    extract::{Multipart, State},
+    Extension,
+    extract::{Multipart, Query, State},
 };
 use std::path::Path;
 use uuid::Uuid;
@@ -14,12 +18,22 @@ use uuid::Uuid;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
+    config::MAX_DUPLICATE_WINDOW_DAYS,
+    errors::{ApiError, ErrorResponse},
     models::{
-        BulkCreateData, BulkCreateError, BulkCreateRequest, BulkCreateResponse, ParseData,
-        ParseResponse,
+        AggregatorImportRequest, AggregatorImportResponse, AmountSign, BulkCreateData,
+        BulkCreateError, BulkCreateRequest, BulkCreateResponse, CsvImportQuery, CsvImportResponse,
+        CsvMapping, ImportErrorMode, OfxImportResponse, OperationType, ParseData, ParseResponse,
+        ResourceType,
+    },
+    services::{
+        account_service,
+        csv_parser_service::*,
+        import_profile_service,
+        import_service::{self, CsvImport, CsvImportOptions},
+        ofx_parser_service::OfxStatementParser,
+        transaction_service,
     },
-    services::{account_service, csv_parser_service::*, import_service, transaction_service},
 };
 
 /// Parse CSV file and return transactions for preview
@@ -31,10 +45,14 @@ use crate::{
 /// Multipart form data with:
 /// - `file`: CSV file
 /// - `account_id`: UUID of target account
+/// - `auto_create_categories`: `true` to create categories named in the file
+///   that do not exist yet (optional, default `false`)
 ///
 /// # Response
 ///
-/// Returns parsed transactions with duplicate detection and validation
+/// Returns parsed transactions with duplicate detection and validation, and
+/// the category and account names in the file resolved to IDs. An account name
+/// that matches none of the user's accounts fails the whole import.
 pub async fn parse_csv(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -45,6 +63,7 @@ pub async fn parse_csv(
     let mut file_data: Option<Vec<u8>> = None;
     let mut account_id: Option<Uuid> = None;
     let mut filename: Option<String> = None;
+    let mut auto_create_categories = false;
 
     // Extract multipart fields
     while let Some(field) = multipart
@@ -82,6 +101,14 @@ pub async fn parse_csv(
                         ApiError::Validation("Invalid account_id format".to_string())
                     })?);
             }
+            "auto_create_categories" => {
+                let text = field.text().await.map_err(|_| {
+                    ApiError::Validation("Invalid auto_create_categories".to_string())
+                })?;
+                auto_create_categories = text.trim().parse().map_err(|_| {
+                    ApiError::Validation("auto_create_categories must be true or false".to_string())
+                })?;
+            }
             _ => {}
         }
     }
@@ -119,6 +146,15 @@ pub async fn parse_csv(
         }
     }
 
+    // Resolve category and account names to the user's categories and accounts
+    import_service::resolve_names(
+        &state.db,
+        user_id,
+        &mut transactions,
+        auto_create_categories,
+    )
+    .await?;
+
     // Check for duplicates against database
     import_service::check_duplicates(&state.db, user_id, account_id, &mut transactions).await?;
 
@@ -136,6 +172,319 @@ pub async fn parse_csv(
     }))
 }
 
+/// Import a CSV file, saving its transactions in batches as it is uploaded
+///
+/// POST /api/v1/transactions/import
+///
+/// # Request
+///
+/// Multipart form data with, in this order:
+/// - `account_id`: UUID of the account of rows that do not name one
+/// - `auto_create_categories`: `true` to create categories named in the file
+///   that do not exist yet (optional, default `false`)
+/// - `on_error`: `abort` to stop at the first failed batch or `continue` to
+///   skip failed batches (optional, default `abort`)
+/// - `profile_id`: import profile giving the file's columns (optional)
+/// - `mapping`: JSON object naming the header of the column each field is read
+///   from, e.g. `{"date": "Posted Date", "amount": "Amount", "description": "Details"}`
+///   (optional, replaces the profile's)
+/// - `date_format`: strftime format of the date column (optional, default
+///   `%Y-%m-%d`)
+/// - `amount_sign`: `negative_debits` for one signed amount column or
+///   `debit_credit_columns` for separate debit and credit columns (optional,
+///   default `negative_debits`)
+/// - `force`: `true` to save rows that duplicate existing transactions
+///   (optional, default `false`)
+/// - `duplicate_window_days`: days either side of a row's date searched for a
+///   transaction it duplicates (optional, default `IMPORT_DUPLICATE_WINDOW_DAYS`)
+/// - `file`: CSV file in the format accepted by the parse endpoint, or with the
+///   columns of the mapping, which must come last since it is imported while
+///   it is read
+///
+/// With `?dry_run=true` the file is checked the same way but nothing is saved.
+///
+/// # Response
+///
+/// Returns how many rows were read, saved and skipped as duplicates, the
+/// transactions the skipped rows duplicate, and why each failed batch was not
+/// saved. Batches saved before a failure are kept. A dry run returns the
+/// counts and errors the import would return, with a `preview` of the file's
+/// column mapping and the transactions that would be created.
+pub async fn import_csv(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<CsvImportQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<CsvImportResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+
+    let mut account_id: Option<Uuid> = None;
+    let mut auto_create_categories = false;
+    let mut on_error = ImportErrorMode::default();
+    let mut profile_id: Option<Uuid> = None;
+    let mut mapping: Option<CsvMapping> = None;
+    let mut date_format: Option<String> = None;
+    let mut amount_sign: Option<AmountSign> = None;
+    let mut force = false;
+    let mut duplicate_window_days = state.config.import.duplicate_window_days;
+    let mut data = None;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::Validation("Invalid multipart data".to_string()))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+
+        match name.as_str() {
+            "file" => {
+                let account_id = account_id.ok_or_else(|| {
+                    ApiError::Validation("account_id must be sent before the file".to_string())
+                })?;
+
+                let filename = field
+                    .file_name()
+                    .ok_or_else(|| ApiError::Validation("Missing filename".to_string()))?;
+                let is_csv = Path::new(filename)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+                if !is_csv {
+                    return Err(ApiError::Validation(
+                        "Only .csv files can be imported".to_string(),
+                    ));
+                }
+
+                // Verify account belongs to user
+                account_service::get_account(&state.db, account_id, user_id).await?;
+
+                let format = import_profile_service::resolve_format(
+                    &state.db,
+                    user_id,
+                    profile_id,
+                    mapping.take(),
+                    date_format.take(),
+                    amount_sign.take(),
+                )
+                .await?;
+
+                let options = CsvImportOptions {
+                    account_id,
+                    auto_create_categories,
+                    on_error,
+                    batch_size: state.config.import.batch_size,
+                    dry_run: query.dry_run,
+                    format,
+                    duplicate_window_days,
+                    force,
+                };
+                let mut import = CsvImport::start(&state.db, user_id, options).await?;
+
+                // The body limit of the route caps the file size
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|_| ApiError::Validation("Failed to read file data".to_string()))?
+                {
+                    import.push(&chunk).await?;
+                    if import.is_aborted() {
+                        break;
+                    }
+                }
+
+                data = Some(import.finish().await?);
+                // Fields after the file are ignored, and an aborted file is not read further
+                break;
+            }
+            "account_id" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid account_id".to_string()))?;
+                account_id =
+                    Some(Uuid::parse_str(&text).map_err(|_| {
+                        ApiError::Validation("Invalid account_id format".to_string())
+                    })?);
+            }
+            "auto_create_categories" => {
+                let text = field.text().await.map_err(|_| {
+                    ApiError::Validation("Invalid auto_create_categories".to_string())
+                })?;
+                auto_create_categories = text.trim().parse().map_err(|_| {
+                    ApiError::Validation("auto_create_categories must be true or false".to_string())
+                })?;
+            }
+            "force" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid force".to_string()))?;
+                force = text
+                    .trim()
+                    .parse()
+                    .map_err(|_| ApiError::Validation("force must be true or false".to_string()))?;
+            }
+            "duplicate_window_days" => {
+                let text = field.text().await.map_err(|_| {
+                    ApiError::Validation("Invalid duplicate_window_days".to_string())
+                })?;
+                duplicate_window_days = text
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|days| (0..=MAX_DUPLICATE_WINDOW_DAYS).contains(days))
+                    .ok_or_else(|| {
+                        ApiError::Validation(format!(
+                            "duplicate_window_days must be between 0 and {}",
+                            MAX_DUPLICATE_WINDOW_DAYS
+                        ))
+                    })?;
+            }
+            "on_error" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid on_error".to_string()))?;
+                on_error = text.parse().map_err(ApiError::Validation)?;
+            }
+            "profile_id" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid profile_id".to_string()))?;
+                profile_id =
+                    Some(Uuid::parse_str(text.trim()).map_err(|_| {
+                        ApiError::Validation("Invalid profile_id format".to_string())
+                    })?);
+            }
+            "mapping" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid mapping".to_string()))?;
+                mapping = Some(
+                    serde_json::from_str(&text)
+                        .map_err(|e| ApiError::Validation(format!("Invalid mapping: {}", e)))?,
+                );
+            }
+            "date_format" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid date_format".to_string()))?;
+                date_format = Some(text.trim().to_string()).filter(|format| !format.is_empty());
+            }
+            "amount_sign" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid amount_sign".to_string()))?;
+                amount_sign = Some(text.parse().map_err(ApiError::Validation)?);
+            }
+            _ => {}
+        }
+    }
+
+    let data = data.ok_or_else(|| ApiError::Validation("Missing file".to_string()))?;
+
+    Ok(Json(CsvImportResponse {
+        success: data.failed == 0,
+        data,
+    }))
+}
+
+/// Import an OFX or QFX statement into an account
+///
+/// POST /api/v1/transactions/import/ofx
+///
+/// # Request
+///
+/// Multipart form data with:
+/// - `file`: `.ofx` or `.qfx` file
+/// - `account_id`: UUID of target account
+///
+/// # Response
+///
+/// Returns how many of the statement's transactions were created, skipped as
+/// already imported, or failed validation. A malformed statement fails the
+/// whole import with a description of the problem.
+pub async fn import_ofx(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    mut multipart: Multipart,
+) -> Result<Json<OfxImportResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+
+    let mut file_data: Option<Vec<u8>> = None;
+    let mut account_id: Option<Uuid> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::Validation("Invalid multipart data".to_string()))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+
+        match name.as_str() {
+            "file" => {
+                let is_ofx = field
+                    .file_name()
+                    .and_then(|filename| Path::new(filename).extension())
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| {
+                        e.eq_ignore_ascii_case("ofx") || e.eq_ignore_ascii_case("qfx")
+                    });
+                if !is_ofx {
+                    return Err(ApiError::Validation(
+                        "Only .ofx and .qfx files can be imported".to_string(),
+                    ));
+                }
+
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|_| ApiError::Validation("Failed to read file data".to_string()))?;
+
+                // Validate file size
+                if data.len() > state.config.import.max_file_size {
+                    return Err(ApiError::Validation(format!(
+                        "File size exceeds maximum of {} bytes",
+                        state.config.import.max_file_size
+                    )));
+                }
+
+                file_data = Some(data.to_vec());
+            }
+            "account_id" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::Validation("Invalid account_id".to_string()))?;
+                account_id =
+                    Some(Uuid::parse_str(&text).map_err(|_| {
+                        ApiError::Validation("Invalid account_id format".to_string())
+                    })?);
+            }
+            _ => {}
+        }
+    }
+
+    let file_data = file_data.ok_or_else(|| ApiError::Validation("Missing file".to_string()))?;
+    let account_id =
+        account_id.ok_or_else(|| ApiError::Validation("Missing account_id".to_string()))?;
+
+    let statement = OfxStatementParser
+        .parse_statement(&file_data, &state.config.import)
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+
+    let data = import_service::import_ofx(&state.db, user_id, account_id, statement).await?;
+
+    Ok(Json(OfxImportResponse {
+        success: data.failed == 0,
+        data,
+    }))
+}
+
 /// Bulk create transactions
 ///
 /// POST /api/v1/transactions/bulk-create
@@ -168,6 +517,7 @@ pub async fn bulk_create_transactions(
             &state.db,
             user_id,
             (*transaction_request).clone(),
+            false,
         )
         .await
         {
@@ -195,3 +545,60 @@ pub async fn bulk_create_transactions(
         },
     }))
 }
+
+/// Import accounts and transactions from an aggregator export
+///
+/// POST /api/v1/import/aggregator
+///
+/// # Request
+///
+/// Plaid-shaped JSON with `accounts` and `transactions`, plus an optional
+/// `account_mapping` and `create_missing_accounts` flag
+///
+/// # Response
+///
+/// Returns counts of created/updated/skipped/failed transactions and how each
+/// external account was matched
+#[utoipa::path(
+    post,
+    path = "/api/v1/import/aggregator",
+    tag = "transactions",
+    request_body = AggregatorImportRequest,
+    responses(
+        (status = 200, description = "Per-account and per-transaction import results", body = AggregatorImportResponse),
+        (status = 422, description = "Invalid request or account mapping", body = ErrorResponse),
+        (status = 403, description = "API key may not create accounts", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_aggregator(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<AggregatorImportRequest>,
+) -> Result<Json<AggregatorImportResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!(
+        "Importing {} aggregator transactions for user {}",
+        request.transactions.len(),
+        user_id
+    );
+
+    // Creating accounts needs account write access on top of the route's transaction scope
+    if request.create_missing_accounts
+        && !auth_context.has_permission(ResourceType::Accounts, OperationType::Write)
+    {
+        return Err(ApiError::Forbidden(format!(
+            "Insufficient permissions: {:?} access to {:?} required",
+            OperationType::Write,
+            ResourceType::Accounts
+        )));
+    }
+
+    let data = import_service::import_aggregator(&state.db, user_id, request).await?;
+
+    Ok(Json(AggregatorImportResponse {
+        success: data.failed == 0,
+        data,
+    }))
+}
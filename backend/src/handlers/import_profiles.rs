@@ -0,0 +1,153 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{CreateImportProfileRequest, ImportProfileResponse, UpdateImportProfileRequest},
+    services::import_profile_service,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+/// List the authenticated user's import profiles
+/// GET /import/profiles
+#[utoipa::path(
+    get,
+    path = "/api/v1/import/profiles",
+    tag = "import-profiles",
+    responses(
+        (status = 200, description = "Import profiles by name", body = Vec<ImportProfileResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<ImportProfileResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing import profiles for user {}", user_id);
+
+    let profiles = import_profile_service::list_profiles(&state.read_db, user_id).await?;
+
+    Ok(Json(profiles))
+}
+
+/// Create an import profile
+/// POST /import/profiles
+#[utoipa::path(
+    post,
+    path = "/api/v1/import/profiles",
+    tag = "import-profiles",
+    request_body = CreateImportProfileRequest,
+    responses(
+        (status = 201, description = "Import profile created", body = ImportProfileResponse),
+        (status = 409, description = "Another profile has the same name", body = ErrorResponse),
+        (status = 422, description = "Validation error or incomplete mapping", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateImportProfileRequest>,
+) -> Result<(StatusCode, Json<ImportProfileResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating import profile for user {}", user_id);
+
+    let profile = import_profile_service::create_profile(&state.db, user_id, request).await?;
+
+    Ok((StatusCode::CREATED, Json(profile)))
+}
+
+/// Get an import profile
+/// GET /import/profiles/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "import-profiles",
+    params(("id" = Uuid, Path, description = "Import profile ID")),
+    responses(
+        (status = 200, description = "Import profile", body = ImportProfileResponse),
+        (status = 403, description = "Import profile belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ImportProfileResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching import profile {} for user {}", id, user_id);
+
+    let profile = import_profile_service::get_profile(&state.read_db, id, user_id).await?;
+
+    Ok(Json(profile))
+}
+
+/// Update an import profile
+/// PUT /import/profiles/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "import-profiles",
+    params(("id" = Uuid, Path, description = "Import profile ID")),
+    request_body = UpdateImportProfileRequest,
+    responses(
+        (status = 200, description = "Import profile updated", body = ImportProfileResponse),
+        (status = 403, description = "Import profile belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 409, description = "Another profile has the same name", body = ErrorResponse),
+        (status = 422, description = "Validation error or incomplete mapping", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateImportProfileRequest>,
+) -> Result<Json<ImportProfileResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Updating import profile {} for user {}", id, user_id);
+
+    let profile = import_profile_service::update_profile(&state.db, id, user_id, request).await?;
+
+    Ok(Json(profile))
+}
+
+/// Delete an import profile
+/// DELETE /import/profiles/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "import-profiles",
+    params(("id" = Uuid, Path, description = "Import profile ID")),
+    responses(
+        (status = 204, description = "Import profile deleted"),
+        (status = 403, description = "Import profile belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Deleting import profile {} for user {}", id, user_id);
+
+    import_profile_service::delete_profile(&state.db, id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,50 @@
+//! JSON request bodies with API error responses
+//!
+//! Drop-in replacement for [`axum::Json`]. A body that is valid JSON but does not
+//! match the request type, e.g. an unknown `account_type`, is rejected as an
+//! [`ApiError::Validation`] naming the offending field, so clients get the usual
+//! `{"error": ...}` response. Other rejections (malformed JSON, a missing
+//! `Content-Type`) keep axum's responses.
+
+use std::error::Error;
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::errors::ApiError;
+
+/// JSON request body extractor and response
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Response> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            // The source names the field path and the deserialization error
+            Err(JsonRejection::JsonDataError(e)) => Err(ApiError::Validation(
+                e.source()
+                    .map_or_else(|| e.body_text(), |source| source.to_string()),
+            )
+            .into_response()),
+            Err(rejection) => Err(rejection.into_response()),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
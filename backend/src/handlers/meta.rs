@@ -0,0 +1,43 @@
+use crate::handlers::json::Json;
+use crate::{
+    errors::ErrorResponse,
+    models::CurrencyResponse,
+    types::{AccountType, CurrencyCode},
+};
+
+/// List the currencies accounts and transactions may use
+/// GET /meta/currencies
+#[utoipa::path(
+    get,
+    path = "/api/v1/meta/currencies",
+    tag = "meta",
+    responses(
+        (status = 200, description = "Supported currencies", body = Vec<CurrencyResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_currencies() -> Json<Vec<CurrencyResponse>> {
+    Json(
+        CurrencyCode::ALL
+            .into_iter()
+            .map(CurrencyResponse::from)
+            .collect(),
+    )
+}
+
+/// List the types an account may have
+/// GET /meta/account-types
+#[utoipa::path(
+    get,
+    path = "/api/v1/meta/account-types",
+    tag = "meta",
+    responses(
+        (status = 200, description = "Supported account types", body = Vec<AccountType>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_account_types() -> Json<Vec<AccountType>> {
+    Json(AccountType::ALL.to_vec())
+}
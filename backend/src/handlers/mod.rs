@@ -1,14 +1,30 @@
 // HTTP request handlers
 pub mod accounts;
+pub mod admin;
+pub mod allocation_rules;
 pub mod api_keys;
+pub mod audit;
 pub mod auth;
 pub mod budgets;
 pub mod categories;
+pub mod category_rules;
 pub mod dashboard;
+pub mod etag;
+pub mod events;
 pub mod exchange_rates;
+pub mod idempotency;
 pub mod import;
+pub mod import_profiles;
+pub mod json;
+pub mod meta;
+pub mod negotiate;
+pub mod notifications;
 pub mod people;
+pub mod recurring;
+pub mod split_groups;
 pub mod split_providers;
 pub mod split_sync;
 pub mod splitwise_integration;
+pub mod tags;
 pub mod transactions;
+pub mod version;
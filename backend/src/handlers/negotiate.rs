@@ -0,0 +1,43 @@
+//! Content negotiation for list endpoints
+//!
+//! Lists are returned as JSON unless the client's `Accept` header prefers
+//! `text/csv`, e.g. `curl -H 'Accept: text/csv'` for spreadsheets.
+
+use axum::http::{HeaderMap, header::ACCEPT};
+
+/// Content type of CSV responses
+pub const TEXT_CSV: &str = "text/csv; charset=utf-8";
+
+/// Whether the client prefers CSV over JSON
+///
+/// Media ranges are ranked by their quality (`q`), ties going to the first
+/// listed; ranges other than `text/csv`, JSON and wildcards are ignored. Without
+/// an `Accept` header the response is JSON.
+pub fn prefers_csv(headers: &HeaderMap) -> bool {
+    let mut preferred: Option<(f32, bool)> = None;
+
+    for value in headers.get_all(ACCEPT) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for range in value.split(',') {
+            let mut parts = range.split(';');
+            let media_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let csv = match media_type.as_str() {
+                "text/csv" => true,
+                "application/json" | "application/*" | "*/*" => false,
+                _ => continue,
+            };
+            if quality > 0.0 && preferred.is_none_or(|(best, _)| quality > best) {
+                preferred = Some((quality, csv));
+            }
+        }
+    }
+
+    preferred.is_some_and(|(_, csv)| csv)
+}
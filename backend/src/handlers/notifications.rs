@@ -0,0 +1,146 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{
+        NotificationPreferenceResponse, NotificationQuery, NotificationResponse, Paginated,
+        Pagination, PaginationQuery, UpdateNotificationPreferencesRequest,
+    },
+    services::notification_service,
+};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+/// List in-app notifications for the authenticated user
+/// GET /notifications
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications",
+    tag = "notifications",
+    params(NotificationQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "In-app notifications, newest first",
+            content(
+                (Vec<NotificationResponse> = "application/json"),
+                (Paginated<NotificationResponse> = "application/vnd.master-of-coin.paginated+json"),
+            )
+        ),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "Not available to API keys", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<NotificationQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing notifications for user {}", user_id);
+
+    let notifications =
+        notification_service::list_notifications(&state.read_db, user_id, &query, pagination)
+            .await?;
+
+    if pagination.paginated {
+        let total =
+            notification_service::count_notifications(&state.read_db, user_id, &query).await?;
+        return Ok(Json(Paginated::new(notifications, total, pagination)).into_response());
+    }
+
+    Ok(Json(notifications).into_response())
+}
+
+/// Mark an in-app notification read
+/// POST /notifications/:id/read
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/{id}/read",
+    tag = "notifications",
+    params(("id" = Uuid, Path, description = "Notification ID")),
+    responses(
+        (status = 200, description = "Read notification", body = NotificationResponse),
+        (status = 403, description = "Notification belongs to another user, or requested with an API key", body = ErrorResponse),
+        (status = 404, description = "Notification not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn mark_read(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<NotificationResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Marking notification {} read for user {}", id, user_id);
+
+    let notification = notification_service::mark_read(&state.db, id, user_id).await?;
+
+    Ok(Json(notification))
+}
+
+/// Get the channels each kind of notification is sent through
+/// GET /notifications/preferences
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/preferences",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Setting of every event type and channel", body = Vec<NotificationPreferenceResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "Not available to API keys", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<NotificationPreferenceResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching notification preferences for user {}", user_id);
+
+    let preferences = notification_service::get_preferences(&state.read_db, user_id).await?;
+
+    Ok(Json(preferences))
+}
+
+/// Turn notification channels on or off per event type
+/// PUT /notifications/preferences
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/preferences",
+    tag = "notifications",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses(
+        (status = 200, description = "Setting of every event type and channel after the update", body = Vec<NotificationPreferenceResponse>),
+        (status = 400, description = "Invalid preferences", body = ErrorResponse),
+        (status = 422, description = "Invalid webhook URL, or one resolving to a non-public address", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "Not available to API keys", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<Vec<NotificationPreferenceResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Updating notification preferences for user {}", user_id);
+
+    let preferences = notification_service::update_preferences(
+        &state.db,
+        &state.config.notifications,
+        user_id,
+        request,
+    )
+    .await?;
+
+    Ok(Json(preferences))
+}
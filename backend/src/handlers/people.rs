@@ -1,47 +1,99 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
+    errors::{ApiError, ErrorResponse},
+    handlers::etag,
     models::{
-        CreatePersonRequest, NewPerson, NewPersonSplitConfig, PersonResponse,
-        PersonSplitConfigResponse, SetPersonSplitConfigRequest, UpdatePerson, UpdatePersonRequest,
+        CreatePersonRequest, NewPerson, NewPersonSplitConfig, Paginated, Pagination,
+        PaginationQuery, PersonResponse, PersonSplitConfigResponse, PersonTransactionResponse,
+        SetPersonSplitConfigRequest, SyncQuery, UpdatePerson, UpdatePersonRequest,
     },
     repositories, services,
 };
 use axum::{
-    Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use serde::Deserialize;
 use uuid::Uuid;
 use validator::Validate;
 
 /// Request DTO for settling debt
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct SettleDebtRequest {
     pub amount: f64,
     pub account_id: Uuid,
+    /// Also mark the oldest unsettled splits covered by the payment as settled
+    #[serde(default)]
+    pub settle_splits: bool,
 }
 
 /// List all people for the authenticated user
 /// GET /people
+///
+/// With `?paginated=true` the page is wrapped in a `Paginated` envelope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/people",
+    tag = "people",
+    params(SyncQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "People",
+            content(
+                (Vec<PersonResponse> = "application/json"),
+                (Paginated<PersonResponse> = "application/vnd.master-of-coin.paginated+json"),
+            )
+        ),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-) -> Result<Json<Vec<PersonResponse>>, ApiError> {
+    Query(query): Query<SyncQuery>,
+    pagination: Pagination,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Listing people for user {}", user_id);
 
-    let people = repositories::person::list_by_user(&state.db, user_id).await?;
+    let people = match query.updated_since {
+        Some(since) => {
+            repositories::person::list_updated_since(&state.read_db, user_id, since, pagination)
+                .await?
+        }
+        None => repositories::person::list_by_user(&state.read_db, user_id, pagination).await?,
+    };
 
     let responses: Vec<PersonResponse> = people.into_iter().map(|p| p.into()).collect();
 
-    Ok(Json(responses))
+    if pagination.paginated {
+        let total =
+            repositories::person::count_by_user(&state.read_db, user_id, query.updated_since)
+                .await?;
+        return Ok(Json(Paginated::new(responses, total, pagination)).into_response());
+    }
+
+    Ok(Json(responses).into_response())
 }
 
 /// Create a new person
 /// POST /people
+#[utoipa::path(
+    post,
+    path = "/api/v1/people",
+    tag = "people",
+    request_body = CreatePersonRequest,
+    responses(
+        (status = 201, description = "Person created", body = PersonResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -72,15 +124,33 @@ pub async fn create(
 
 /// Get a single person by ID
 /// GET /people/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/people/{id}",
+    tag = "people",
+    params(
+        ("id" = Uuid, Path, description = "Person ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "Person", body = PersonResponse),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<PersonResponse>, ApiError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::debug!("Fetching person {} for user {}", id, user_id);
 
-    let person = repositories::person::find_by_id(&state.db, id).await?;
+    let person = repositories::person::find_by_id(&state.read_db, id).await?;
 
     // Verify ownership
     if person.user_id != user_id {
@@ -89,13 +159,28 @@ pub async fn get(
         ));
     }
 
-    let response = person.into();
+    let response: PersonResponse = person.into();
 
-    Ok(Json(response))
+    etag::json_with_etag(&headers, &response)
 }
 
 /// Update a person
 /// PUT /people/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/people/{id}",
+    tag = "people",
+    params(("id" = Uuid, Path, description = "Person ID")),
+    request_body = UpdatePersonRequest,
+    responses(
+        (status = 200, description = "Person updated", body = PersonResponse),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -134,6 +219,19 @@ pub async fn update(
 
 /// Delete a person
 /// DELETE /people/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/people/{id}",
+    tag = "people",
+    params(("id" = Uuid, Path, description = "Person ID")),
+    responses(
+        (status = 204, description = "Person deleted"),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -155,8 +253,44 @@ pub async fn delete(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// List outstanding debts with all people
+/// GET /people/debts
+#[utoipa::path(
+    get,
+    path = "/api/v1/people/debts",
+    tag = "people",
+    responses(
+        (status = 200, description = "Outstanding debts, one entry per person with a non-zero balance", body = Vec<services::debt_service::PersonDebt>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_debts(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<services::debt_service::PersonDebt>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Listing debts for user {}", user_id);
+
+    let debts = services::debt_service::get_all_debts_for_user(&state.read_db, user_id).await?;
+    Ok(Json(debts))
+}
+
 /// Get debts for a specific person
 /// GET /people/:id/debts
+#[utoipa::path(
+    get,
+    path = "/api/v1/people/{id}/debts",
+    tag = "people",
+    params(("id" = Uuid, Path, description = "Person ID")),
+    responses(
+        (status = 200, description = "Outstanding debt with the person", body = services::debt_service::PersonDebt),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_debts(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -166,7 +300,7 @@ pub async fn get_debts(
     tracing::debug!("Fetching debts for person {} and user {}", id, user_id);
 
     // Verify ownership
-    let person = repositories::person::find_by_id(&state.db, id).await?;
+    let person = repositories::person::find_by_id(&state.read_db, id).await?;
     if person.user_id != user_id {
         return Err(ApiError::Forbidden(
             "Person does not belong to user".to_string(),
@@ -174,7 +308,7 @@ pub async fn get_debts(
     }
 
     let debt_amount =
-        services::debt_service::calculate_debt_for_person(&state.db, id, user_id).await?;
+        services::debt_service::calculate_debt_for_person(&state.read_db, id, user_id).await?;
 
     let debt = services::debt_service::PersonDebt {
         person_id: id,
@@ -185,8 +319,88 @@ pub async fn get_debts(
     Ok(Json(debt))
 }
 
+/// Get the ledger of changes to the debt with a person
+/// GET /people/:id/ledger
+#[utoipa::path(
+    get,
+    path = "/api/v1/people/{id}/ledger",
+    tag = "people",
+    params(("id" = Uuid, Path, description = "Person ID")),
+    responses(
+        (status = 200, description = "Splits and settlements affecting the debt, oldest first, with the running balance", body = Vec<services::debt_service::PersonLedgerEntry>),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_ledger(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<services::debt_service::PersonLedgerEntry>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching ledger for person {} and user {}", id, user_id);
+
+    let ledger = services::debt_service::get_ledger(&state.read_db, id, user_id).await?;
+    Ok(Json(ledger))
+}
+
+/// List the transactions a person has a split in
+/// GET /people/:id/transactions
+#[utoipa::path(
+    get,
+    path = "/api/v1/people/{id}/transactions",
+    tag = "people",
+    params(
+        ("id" = Uuid, Path, description = "Person ID"),
+        PaginationQuery,
+    ),
+    responses(
+        (status = 200, description = "Transactions with the person's split of each, newest first", body = Vec<PersonTransactionResponse>),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_transactions(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    pagination: Pagination,
+) -> Result<Json<Vec<PersonTransactionResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Listing transactions of person {} for user {}", id, user_id);
+
+    let transactions = services::transaction_service::list_person_transactions(
+        &state.read_db,
+        id,
+        user_id,
+        pagination,
+    )
+    .await?;
+
+    Ok(Json(transactions))
+}
+
 /// Settle debt with a person
 /// POST /people/:id/settle
+#[utoipa::path(
+    post,
+    path = "/api/v1/people/{id}/settle",
+    tag = "people",
+    params(("id" = Uuid, Path, description = "Person ID")),
+    request_body = SettleDebtRequest,
+    responses(
+        (status = 204, description = "Debt settled"),
+        (status = 403, description = "Person belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn settle_debt(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -201,8 +415,15 @@ pub async fn settle_debt(
         user_id
     );
 
-    services::debt_service::settle_debt(&state.db, id, user_id, request.amount, request.account_id)
-        .await?;
+    services::debt_service::settle_debt(
+        &state.db,
+        id,
+        user_id,
+        request.amount,
+        request.account_id,
+        request.settle_splits,
+    )
+    .await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -273,7 +494,7 @@ pub async fn get_split_config(
     tracing::debug!("Fetching split config for person {}", person_id);
 
     // Verify person ownership
-    let person = repositories::person::find_by_id(&state.db, person_id).await?;
+    let person = repositories::person::find_by_id(&state.read_db, person_id).await?;
     if person.user_id != user_id {
         return Err(ApiError::Forbidden(
             "Person does not belong to user".to_string(),
@@ -281,14 +502,15 @@ pub async fn get_split_config(
     }
 
     // Get config
-    let config = repositories::person_split_config::find_by_person_id(&state.db, person_id)
+    let config = repositories::person_split_config::find_by_person_id(&state.read_db, person_id)
         .await?
         .ok_or_else(|| ApiError::NotFound("Split config not found".to_string()))?;
 
     // Get provider to include type in response
-    let provider = repositories::split_provider::find_by_id(&state.db, config.split_provider_id)
-        .await?
-        .ok_or_else(|| ApiError::NotFound("Split provider not found".to_string()))?;
+    let provider =
+        repositories::split_provider::find_by_id(&state.read_db, config.split_provider_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Split provider not found".to_string()))?;
 
     let mut response = PersonSplitConfigResponse::from(config);
     response.provider_type = provider.provider_type;
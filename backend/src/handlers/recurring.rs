@@ -0,0 +1,156 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{
+        CreateRecurringTransactionRequest, RecurringTransactionResponse,
+        UpdateRecurringTransactionRequest,
+    },
+    services::recurring_service,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+/// List the authenticated user's recurring transactions
+/// GET /recurring
+#[utoipa::path(
+    get,
+    path = "/api/v1/recurring",
+    tag = "recurring",
+    responses(
+        (status = 200, description = "Recurring transactions, soonest next run first", body = Vec<RecurringTransactionResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<RecurringTransactionResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing recurring transactions for user {}", user_id);
+
+    let rules = recurring_service::list_rules(&state.read_db, user_id).await?;
+
+    Ok(Json(rules))
+}
+
+/// Create a recurring transaction
+/// POST /recurring
+#[utoipa::path(
+    post,
+    path = "/api/v1/recurring",
+    tag = "recurring",
+    request_body = CreateRecurringTransactionRequest,
+    responses(
+        (status = 201, description = "Recurring transaction created", body = RecurringTransactionResponse),
+        (status = 403, description = "Account or category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account or category not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateRecurringTransactionRequest>,
+) -> Result<(StatusCode, Json<RecurringTransactionResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating recurring transaction for user {}", user_id);
+
+    let rule = recurring_service::create_rule(&state.db, user_id, request).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Get a recurring transaction
+/// GET /recurring/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/recurring/{id}",
+    tag = "recurring",
+    params(("id" = Uuid, Path, description = "Recurring transaction ID")),
+    responses(
+        (status = 200, description = "Recurring transaction", body = RecurringTransactionResponse),
+        (status = 403, description = "Recurring transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecurringTransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching recurring transaction {} for user {}", id, user_id);
+
+    let rule = recurring_service::get_rule(&state.read_db, id, user_id).await?;
+
+    Ok(Json(rule))
+}
+
+/// Update a recurring transaction
+/// PUT /recurring/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/recurring/{id}",
+    tag = "recurring",
+    params(("id" = Uuid, Path, description = "Recurring transaction ID")),
+    request_body = UpdateRecurringTransactionRequest,
+    responses(
+        (status = 200, description = "Recurring transaction updated", body = RecurringTransactionResponse),
+        (status = 403, description = "Recurring transaction, account or category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction, account or category not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateRecurringTransactionRequest>,
+) -> Result<Json<RecurringTransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Updating recurring transaction {} for user {}", id, user_id);
+
+    let rule = recurring_service::update_rule(&state.db, id, user_id, request).await?;
+
+    Ok(Json(rule))
+}
+
+/// Delete a recurring transaction
+/// DELETE /recurring/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/recurring/{id}",
+    tag = "recurring",
+    params(("id" = Uuid, Path, description = "Recurring transaction ID")),
+    responses(
+        (status = 204, description = "Recurring transaction deleted"),
+        (status = 403, description = "Recurring transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Deleting recurring transaction {} for user {}", id, user_id);
+
+    recurring_service::delete_rule(&state.db, id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,161 @@
+use crate::handlers::json::Json;
+use crate::handlers::transactions::check_split_count;
+use crate::{
+    AppState,
+    auth::context::AuthContext,
+    errors::{ApiError, ErrorResponse},
+    models::{CreateSplitGroupRequest, SplitGroupResponse, UpdateSplitGroupRequest},
+    services::split_group_service,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+/// List the authenticated user's split groups
+/// GET /split-groups
+#[utoipa::path(
+    get,
+    path = "/api/v1/split-groups",
+    tag = "split-groups",
+    responses(
+        (status = 200, description = "Split groups", body = Vec<SplitGroupResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<SplitGroupResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing split groups for user {}", user_id);
+
+    let groups = split_group_service::list_groups(&state.read_db, user_id).await?;
+
+    Ok(Json(groups))
+}
+
+/// Create a split group
+/// POST /split-groups
+#[utoipa::path(
+    post,
+    path = "/api/v1/split-groups",
+    tag = "split-groups",
+    request_body = CreateSplitGroupRequest,
+    responses(
+        (status = 201, description = "Split group created", body = SplitGroupResponse),
+        (status = 403, description = "A member belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Person not found", body = ErrorResponse),
+        (status = 422, description = "Validation error, e.g. percentages adding up to more than 100", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateSplitGroupRequest>,
+) -> Result<(StatusCode, Json<SplitGroupResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating split group for user {}", user_id);
+
+    // Each member becomes a split of the transactions the group is used for
+    check_split_count(&state, request.members.len())?;
+
+    let group = split_group_service::create_group(&state.db, user_id, request).await?;
+
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+/// Get a split group
+/// GET /split-groups/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/split-groups/{id}",
+    tag = "split-groups",
+    params(("id" = Uuid, Path, description = "Split group ID")),
+    responses(
+        (status = 200, description = "Split group", body = SplitGroupResponse),
+        (status = 403, description = "Split group belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Split group not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SplitGroupResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Fetching split group {} for user {}", id, user_id);
+
+    let group = split_group_service::get_group(&state.read_db, id, user_id).await?;
+
+    Ok(Json(group))
+}
+
+/// Update a split group
+/// PUT /split-groups/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/split-groups/{id}",
+    tag = "split-groups",
+    params(("id" = Uuid, Path, description = "Split group ID")),
+    request_body = UpdateSplitGroupRequest,
+    responses(
+        (status = 200, description = "Split group updated", body = SplitGroupResponse),
+        (status = 403, description = "Split group or a member belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Split group or person not found", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateSplitGroupRequest>,
+) -> Result<Json<SplitGroupResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Updating split group {} for user {}", id, user_id);
+
+    if let Some(ref members) = request.members {
+        check_split_count(&state, members.len())?;
+    }
+
+    let group = split_group_service::update_group(&state.db, id, user_id, request).await?;
+
+    Ok(Json(group))
+}
+
+/// Delete a split group
+/// DELETE /split-groups/:id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/split-groups/{id}",
+    tag = "split-groups",
+    params(("id" = Uuid, Path, description = "Split group ID")),
+    responses(
+        (status = 204, description = "Split group deleted"),
+        (status = 403, description = "Split group belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Split group not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Deleting split group {} for user {}", id, user_id);
+
+    split_group_service::delete_group(&state.db, id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,9 +1,9 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState, auth::context::AuthContext, errors::ApiError, models::SplitProviderResponse,
     repositories, utils,
 };
 use axum::{
-    Json,
     extract::{Extension, Path, State},
     http::StatusCode,
 };
@@ -51,6 +51,42 @@ pub async fn disconnect_provider(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Disable a split provider
+/// POST /api/integrations/providers/:id/disable
+///
+/// Syncing with a disabled provider is refused without contacting it. The
+/// provider's configuration and sync records are kept.
+pub async fn disable_provider(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SplitProviderResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Disabling provider {} for user {}", id, user_id);
+
+    let provider = repositories::split_provider::set_enabled(&state.db, id, user_id, false).await?;
+
+    Ok(Json(provider.into()))
+}
+
+/// Enable a split provider
+/// POST /api/integrations/providers/:id/enable
+///
+/// Also clears the provider's auth failures. A provider whose credentials were
+/// revoked keeps failing until it is reconnected.
+pub async fn enable_provider(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SplitProviderResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Enabling provider {} for user {}", id, user_id);
+
+    let provider = repositories::split_provider::set_enabled(&state.db, id, user_id, true).await?;
+
+    Ok(Json(provider.into()))
+}
+
 /// Splitwise friend response
 #[derive(Debug, Serialize)]
 pub struct SplitwiseFriendResponse {
@@ -101,8 +137,9 @@ pub async fn get_provider_friends(
         .and_then(|v| v.as_str())
         .ok_or_else(|| ApiError::InternalWithMessage("Invalid credentials format".to_string()))?;
 
-    let credentials = utils::decrypt_credentials(encrypted)
-        .map_err(|e| ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e)))?;
+    let credentials = utils::decrypt_credentials(encrypted).map_err(|e| {
+        ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e))
+    })?;
 
     // Get access token
     let access_token = credentials
@@ -1,12 +1,15 @@
+use crate::handlers::json::Json;
 use crate::{
-    AppState, auth::context::AuthContext, errors::ApiError,
-    models::split_sync_record::SplitSyncStatusResponse,
-    repositories::split_sync_record::SplitSyncRecordRepository,
-};
-use axum::{
-    Json,
-    extract::{Extension, Path, State},
+    AppState,
+    auth::context::AuthContext,
+    errors::ApiError,
+    models::split_sync_record::{
+        SplitSyncStatusResponse, SyncReportQuery, SyncReportResponse, SyncStatus,
+        SyncStatusSummaryResponse,
+    },
+    repositories::{self, split_sync_record::SplitSyncRecordRepository},
 };
+use axum::extract::{Extension, Path, Query, State};
 use uuid::Uuid;
 
 /// Get sync status for a transaction split
@@ -35,6 +38,7 @@ pub async fn get_sync_status(
                 transaction_split_id: record.transaction_split_id,
                 split_provider_id: record.split_provider_id,
                 provider_type: String::new(), // TODO: join with split_providers table
+                external_user_id: record.external_user_id,
                 external_expense_id: record.external_expense_id,
                 sync_status: status,
                 last_sync_at: record.last_sync_at,
@@ -75,6 +79,7 @@ pub async fn retry_sync(
         transaction_split_id: record.transaction_split_id,
         split_provider_id: record.split_provider_id,
         provider_type: String::new(), // TODO: join with split_providers table
+        external_user_id: record.external_user_id,
         external_expense_id: record.external_expense_id,
         sync_status: status,
         last_sync_at: record.last_sync_at,
@@ -85,3 +90,73 @@ pub async fn retry_sync(
 
     Ok(Json(response))
 }
+
+/// Summarize the sync status of the user's split expenses
+/// GET /integrations/sync/status
+///
+/// Counts are kept current by the background reconciliation, which marks
+/// expenses changed on the provider as drifted.
+pub async fn get_sync_summary(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<SyncStatusSummaryResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Summarizing sync status for user {}", user_id);
+
+    let mut summary = SyncStatusSummaryResponse::default();
+    for (status, count) in SplitSyncRecordRepository::count_by_status_for_user(&state.db, user_id)?
+    {
+        match SyncStatus::from_str(&status) {
+            Some(SyncStatus::Pending) => summary.pending = count,
+            Some(SyncStatus::Synced) => summary.synced = count,
+            Some(SyncStatus::Drifted) => summary.drifted = count,
+            Some(SyncStatus::Failed) => summary.failed = count,
+            Some(SyncStatus::Deleted) => summary.deleted = count,
+            None => tracing::warn!("Unknown sync status '{}'", status),
+        }
+    }
+
+    summary.orphaned =
+        SplitSyncRecordRepository::list_orphaned_for_user(&state.db, user_id)?.len() as i64;
+
+    summary.providers_needing_reconnect =
+        repositories::split_provider::list_by_user(&state.db, user_id)
+            .await?
+            .into_iter()
+            .filter(|provider| provider.needs_reconnect())
+            .map(|provider| provider.id)
+            .collect();
+
+    Ok(Json(summary))
+}
+
+/// Compare the user's splits within a date range with each person's provider
+/// GET /integrations/sync-report
+///
+/// Read-only: a provider that cannot be read is reported in `errors` instead of
+/// failing the request.
+pub async fn get_sync_report(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<SyncReportQuery>,
+) -> Result<Json<SyncReportResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Building sync report for user {}", user_id);
+
+    if query.start_date > query.end_date {
+        return Err(ApiError::BadRequest(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+
+    let sync_service = state
+        .split_sync
+        .as_ref()
+        .ok_or_else(|| ApiError::Configuration("Split sync service not configured".to_string()))?;
+
+    let report = sync_service
+        .sync_report(user_id, query.start_date, query.end_date)
+        .await?;
+
+    Ok(Json(report))
+}
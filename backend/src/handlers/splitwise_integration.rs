@@ -1,15 +1,21 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
     errors::ApiError,
-    models::NewSplitProvider,
+    models::{NewSplitProvider, WebhookResponse},
     repositories,
-    services::splitwise_oauth::{SplitwiseOAuth, SplitwiseOAuthError},
+    services::{
+        event_service::ChangeEvent,
+        splitwise_oauth::{SplitwiseOAuth, SplitwiseOAuthError},
+        splitwise_webhook_service,
+    },
     utils,
 };
 use axum::{
-    Json,
+    body::Bytes,
     extract::{Extension, Query, State},
+    http::HeaderMap,
     response::{IntoResponse, Redirect, Response},
 };
 use serde::{Deserialize, Serialize};
@@ -232,6 +238,45 @@ pub async fn list_splitwise_friends(
     Ok(Json(friends))
 }
 
+/// Receive an expense change from Splitwise (PUBLIC endpoint - no auth required)
+/// POST /api/v1/integrations/splitwise/webhook
+///
+/// Deliveries are authenticated by the `X-Splitwise-Signature` header, the
+/// hex-encoded HMAC-SHA256 of the body under `SPLITWISE_WEBHOOK_SECRET`. A
+/// missing or wrong signature, or no configured secret, is rejected with 401
+/// before anything is read or written.
+pub async fn webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<WebhookResponse>, ApiError> {
+    let Some(secret) = state.config.splitwise_webhook_secret.as_deref() else {
+        return Err(ApiError::Unauthorized(
+            "Splitwise webhooks are not configured".to_string(),
+        ));
+    };
+    let signature = headers
+        .get(splitwise_webhook_service::SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !splitwise_webhook_service::verify_signature(secret, &body, signature) {
+        return Err(ApiError::Unauthorized(
+            "Invalid webhook signature".to_string(),
+        ));
+    }
+
+    let result = splitwise_webhook_service::handle_delivery(&state.db, &body).await?;
+    for (user_id, transaction_id) in result.changed_transactions {
+        state
+            .events
+            .publish(user_id, ChangeEvent::TransactionUpdated { transaction_id });
+    }
+
+    Ok(Json(WebhookResponse {
+        outcome: result.outcome,
+    }))
+}
+
 /// Map SplitwiseOAuthError to ApiError
 fn map_oauth_error(error: SplitwiseOAuthError) -> ApiError {
     match error {
@@ -0,0 +1,35 @@
+use crate::handlers::json::Json;
+use crate::{
+    AppState, auth::context::AuthContext, errors::ApiError, errors::ErrorResponse,
+    models::TagResponse, repositories,
+};
+use axum::extract::{Extension, State};
+
+/// List the authenticated user's tags with how many transactions use each
+/// GET /tags
+#[utoipa::path(
+    get,
+    path = "/api/v1/tags",
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tags by name, with usage counts", body = Vec<TagResponse>),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+) -> Result<Json<Vec<TagResponse>>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing tags for user {}", user_id);
+
+    let tags = repositories::tag::list_with_usage(&state.read_db, user_id).await?;
+
+    let responses: Vec<TagResponse> = tags
+        .into_iter()
+        .map(|(tag, usage_count)| TagResponse::new(tag, usage_count))
+        .collect();
+
+    Ok(Json(responses))
+}
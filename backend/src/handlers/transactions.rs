@@ -1,45 +1,377 @@
+use crate::handlers::json::Json;
 use crate::{
     AppState,
     auth::context::AuthContext,
-    errors::ApiError,
+    errors::{ApiError, ErrorResponse, VersionConflictResponse},
+    handlers::{etag, negotiate, version},
     models::{
-        CreateTransactionRequest, TransactionFilter, TransactionResponse, UpdateTransactionRequest,
+        BulkItemStatus, BulkTransactionRequest, BulkTransactionResponse, CategorySuggestionQuery,
+        CategorySuggestionResponse, CreateTransactionRequest, CreateTransferRequest,
+        DeleteTransactionQuery, DisplayQuery, OutstandingReimbursementsResponse, Paginated,
+        Pagination, PaginationQuery, ReimbursementQuery, TransactionFilter, TransactionResponse,
+        TransactionSplitResponse, TransactionTotals, TransactionWriteQuery, TransferResponse,
+        UpdateTransactionRequest,
     },
-    services::{split_sync_service::SplitSyncService, transaction_service},
+    services::{
+        bulk_transaction_service, category_rule_service, event_service::ChangeEvent,
+        split_sync_service::SplitSyncService, transaction_service, transfer_service,
+    },
+    types::{Locale, Money},
 };
 use axum::{
-    Json,
+    body::Body,
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{
+        HeaderMap, HeaderName, HeaderValue, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, VARY},
+    },
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
+use validator::Validate;
+
+/// Number of transactions matching a list request's filters, across all pages
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+/// Sum of the matching positive amounts, per currency (e.g. `EUR 150.00, USD 20.00`)
+pub const TOTAL_INCOME_HEADER: HeaderName = HeaderName::from_static("x-total-income");
+/// Sum of the matching negative amounts as positive numbers, per currency
+pub const TOTAL_EXPENSES_HEADER: HeaderName = HeaderName::from_static("x-total-expenses");
+/// Income minus expenses, per currency
+pub const TOTAL_NET_HEADER: HeaderName = HeaderName::from_static("x-total-net");
+
+/// Summary headers of transaction lists, exposed to browsers by the CORS layer
+pub const SUMMARY_HEADERS: [HeaderName; 4] = [
+    TOTAL_COUNT_HEADER,
+    TOTAL_INCOME_HEADER,
+    TOTAL_EXPENSES_HEADER,
+    TOTAL_NET_HEADER,
+];
+
+/// Build the summary headers from the totals of a list request's filters
+fn summary_headers(totals: &[TransactionTotals]) -> Result<HeaderMap, ApiError> {
+    let per_currency = |amount: fn(&TransactionTotals) -> bigdecimal::BigDecimal| {
+        let value = totals
+            .iter()
+            .map(|t| {
+                format!(
+                    "{} {}",
+                    t.currency.as_str(),
+                    Money::new(amount(t), t.currency)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        HeaderValue::from_str(&value).map_err(|_| ApiError::Internal)
+    };
+
+    let count: i64 = totals.iter().map(|t| t.count).sum();
+    let mut headers = HeaderMap::new();
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(count));
+    headers.insert(TOTAL_INCOME_HEADER, per_currency(|t| t.income.clone())?);
+    headers.insert(TOTAL_EXPENSES_HEADER, per_currency(|t| t.expenses.clone())?);
+    headers.insert(TOTAL_NET_HEADER, per_currency(TransactionTotals::net)?);
+    Ok(headers)
+}
+
+/// Locale to format amounts in: the request's, else the configured default
+fn display_locale(state: &AppState, query: &DisplayQuery) -> Option<Locale> {
+    query.locale.or(state.config.display.default_locale)
+}
+
+/// Reject splitting a transaction with more people than the configured maximum
+pub(crate) fn check_split_count(state: &AppState, count: usize) -> Result<(), ApiError> {
+    let max_splits = state.config.transactions.max_splits;
+    if count > max_splits {
+        return Err(ApiError::Validation(format!(
+            "A transaction can be split with at most {} people",
+            max_splits
+        )));
+    }
+    Ok(())
+}
+
+/// Number of people a new transaction is split with directly in the request
+fn requested_split_count(request: &CreateTransactionRequest) -> usize {
+    match (
+        &request.splits,
+        &request.split_equally_with,
+        &request.participants,
+    ) {
+        (Some(splits), _, _) => splits.len(),
+        (None, Some(person_ids), _) => person_ids.len(),
+        (None, None, Some(participants)) => participants.len(),
+        (None, None, None) => 0,
+    }
+}
 
 /// List transactions with optional filters
 /// GET /transactions
+///
+/// The `X-Total-*` headers summarize every transaction matching the filters,
+/// not just the returned page. With `Accept: text/csv` the page is streamed as CSV,
+/// in the columns of the export, and with `?paginated=true` it is wrapped in a
+/// `Paginated` envelope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions",
+    tag = "transactions",
+    params(
+        TransactionFilter,
+        PaginationQuery,
+        DisplayQuery,
+        ("Accept" = Option<String>, Header, description = "`text/csv` returns the transactions as CSV, in the columns of `/transactions/export.csv`, instead of JSON"),
+    ),
+    responses(
+        (status = 200, description = "Transactions matching the filters",
+            content(
+                (Vec<TransactionResponse> = "application/json"),
+                (Paginated<TransactionResponse> = "application/vnd.master-of-coin.paginated+json"),
+                (String = "text/csv"),
+            ),
+            headers(
+                ("X-Total-Count" = i64, description = "Number of transactions matching the filters"),
+                ("X-Total-Income" = String, description = "Sum of positive amounts per currency, e.g. `EUR 150.00, USD 20.00`"),
+                ("X-Total-Expenses" = String, description = "Sum of negative amounts as positive numbers, per currency"),
+                ("X-Total-Net" = String, description = "Income minus expenses, per currency"),
+            )
+        ),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 422, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-    Query(filters): Query<TransactionFilter>,
-) -> Result<Json<Vec<TransactionResponse>>, ApiError> {
+    Query(mut filters): Query<TransactionFilter>,
+    pagination: Pagination,
+    Query(display): Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Listing transactions for user {}", user_id);
 
-    let transactions = transaction_service::list_transactions(&state.db, user_id, filters).await?;
+    filters.limit = Some(pagination.limit);
+    filters.offset = Some(pagination.offset);
+
+    let totals =
+        transaction_service::summarize_transactions(&state.read_db, user_id, filters.clone())
+            .await?;
+
+    let mut response_headers = summary_headers(&totals)?;
+    response_headers.insert(VARY, HeaderValue::from_static("accept"));
+
+    if negotiate::prefers_csv(&headers) {
+        let csv = transaction_service::transactions_csv(&state.read_db, user_id, filters).await?;
+        response_headers.insert(CONTENT_TYPE, HeaderValue::from_static(negotiate::TEXT_CSV));
+        return Ok((response_headers, Body::from_stream(csv)).into_response());
+    }
+
+    let mut transactions =
+        transaction_service::list_transactions(&state.read_db, user_id, filters.clone()).await?;
+
+    if let Some(locale) = display_locale(&state, &display) {
+        for transaction in &mut transactions {
+            transaction.set_display_locale(locale);
+        }
+    }
+
+    if pagination.paginated {
+        let total =
+            transaction_service::count_transactions(&state.read_db, user_id, filters).await?;
+        return Ok((
+            response_headers,
+            Json(Paginated::new(transactions, total, pagination)),
+        )
+            .into_response());
+    }
 
-    Ok(Json(transactions))
+    Ok((response_headers, Json(transactions)).into_response())
+}
+
+/// Export every transaction matching the filters as a CSV file
+/// GET /transactions/export.csv
+///
+/// Rows are streamed as they are read, so large exports are not held in memory.
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/export.csv",
+    tag = "transactions",
+    params(TransactionFilter),
+    responses(
+        (status = 200, description = "CSV with a header row and the columns date, account, category, title, amount, currency and notes",
+            content_type = "text/csv",
+            body = String,
+            headers(
+                ("Content-Disposition" = String, description = "`attachment; filename=\"transactions.csv\"`"),
+            )
+        ),
+        (status = 403, description = "The account or category filtered on belongs to another user", body = ErrorResponse),
+        (status = 422, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_csv(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(filters): Query<TransactionFilter>,
+) -> Result<Response, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Exporting transactions for user {}", user_id);
+
+    let csv =
+        transaction_service::export_transactions_csv(&state.read_db, user_id, filters).await?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, negotiate::TEXT_CSV),
+            (
+                CONTENT_DISPOSITION,
+                "attachment; filename=\"transactions.csv\"",
+            ),
+        ],
+        Body::from_stream(csv),
+    )
+        .into_response())
+}
+
+/// List an account's transactions with optional filters
+/// GET /accounts/:id/transactions
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{id}/transactions",
+    tag = "transactions",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        TransactionFilter,
+        PaginationQuery,
+    ),
+    responses(
+        (status = 200, description = "Transactions of the account", body = Vec<TransactionResponse>),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 422, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_by_account(
+    state: State<AppState>,
+    auth_context: Extension<AuthContext>,
+    Path(account_id): Path<Uuid>,
+    Query(mut filters): Query<TransactionFilter>,
+    pagination: Pagination,
+    display: Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    filters.account_id = Some(account_id);
+    list(
+        state,
+        auth_context,
+        Query(filters),
+        pagination,
+        display,
+        headers,
+    )
+    .await
+}
+
+/// List a category's transactions with optional filters
+/// GET /categories/:id/transactions
+#[utoipa::path(
+    get,
+    path = "/api/v1/categories/{id}/transactions",
+    tag = "transactions",
+    params(
+        ("id" = Uuid, Path, description = "Category ID"),
+        TransactionFilter,
+        PaginationQuery,
+    ),
+    responses(
+        (status = 200, description = "Transactions in the category", body = Vec<TransactionResponse>),
+        (status = 400, description = "Invalid pagination", body = ErrorResponse),
+        (status = 422, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 403, description = "Category belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_by_category(
+    state: State<AppState>,
+    auth_context: Extension<AuthContext>,
+    Path(category_id): Path<Uuid>,
+    Query(mut filters): Query<TransactionFilter>,
+    pagination: Pagination,
+    display: Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    filters.category_id = Some(category_id);
+    list(
+        state,
+        auth_context,
+        Query(filters),
+        pagination,
+        display,
+        headers,
+    )
+    .await
 }
 
 /// Create a new transaction
 /// POST /transactions
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions",
+    tag = "transactions",
+    params(
+        TransactionWriteQuery,
+        DisplayQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; retrying with it replays the stored response"),
+    ),
+    request_body = CreateTransactionRequest,
+    responses(
+        (status = 201, description = "Transaction created (with any `warnings`)", body = TransactionResponse),
+        (status = 409, description = "Idempotency-Key still in progress or used for a different request", body = ErrorResponse),
+        (status = 422, description = "Validation error, overdraft limit exceeded, or warnings raised with `strict=true`", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
-    Json(request): Json<CreateTransactionRequest>,
+    Query(query): Query<TransactionWriteQuery>,
+    Query(display): Query<DisplayQuery>,
+    Json(mut request): Json<CreateTransactionRequest>,
 ) -> Result<(StatusCode, Json<TransactionResponse>), ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Creating transaction for user {}", user_id);
+    check_split_count(&state, requested_split_count(&request))?;
+
+    // Categorize by the user's category rules, then from title history when
+    // enabled, if no category was given
+    if request.category_id.is_none() {
+        request.category_id =
+            category_rule_service::apply_category_rules(&state.db, user_id, &request.title).await?;
+    }
+    let suggestion_config = &state.config.category_suggestion;
+    if suggestion_config.auto_apply && request.category_id.is_none() {
+        let suggestion =
+            transaction_service::suggest_category(&state.db, user_id, &request.title).await?;
+        if suggestion.confidence >= suggestion_config.min_confidence {
+            request.category_id = suggestion.category_id;
+        }
+    }
 
-    let transaction = transaction_service::create_transaction(&state.db, user_id, request).await?;
+    let mut transaction =
+        transaction_service::create_transaction(&state.db, user_id, request, query.strict).await?;
+    if let Some(locale) = display_locale(&state, &display) {
+        transaction.set_display_locale(locale);
+    }
 
     // Trigger split sync if splits were created (fire-and-forget, don't block response)
     if let Some(ref splits) = transaction.splits {
@@ -50,78 +382,519 @@ pub async fn create(
         }
     }
 
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionCreated {
+            transaction_id: transaction.id,
+        },
+    );
+
     Ok((StatusCode::CREATED, Json(transaction)))
 }
 
+/// Transfer money between two of the user's accounts
+/// POST /transactions/transfer
+///
+/// Creates a linked pair of transactions: a negative one on the source account
+/// and a positive one on the destination account.
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/transfer",
+    tag = "transactions",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; retrying with it replays the stored response"),
+    ),
+    request_body = CreateTransferRequest,
+    responses(
+        (status = 201, description = "Transfer created", body = TransferResponse),
+        (status = 409, description = "Idempotency-Key still in progress or used for a different request", body = ErrorResponse),
+        (status = 403, description = "An account belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 422, description = "Validation error or overdraft limit exceeded", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 502, description = "Exchange rate between the account currencies unavailable", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_transfer(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<CreateTransferRequest>,
+) -> Result<(StatusCode, Json<TransferResponse>), ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Creating transfer for user {}", user_id);
+
+    let transfer = transfer_service::create_transfer(&state.db, user_id, request).await?;
+
+    for transaction in [&transfer.from_transaction, &transfer.to_transaction] {
+        state.events.publish(
+            user_id,
+            ChangeEvent::TransactionCreated {
+                transaction_id: transaction.id,
+            },
+        );
+    }
+
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+/// Suggest a category for a new transaction from past transactions with the same title
+/// GET /transactions/suggest-category
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/suggest-category",
+    tag = "transactions",
+    params(CategorySuggestionQuery),
+    responses(
+        (status = 200, description = "Suggested category (none if no past transaction matched)", body = CategorySuggestionResponse),
+        (status = 422, description = "Missing or invalid title", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn suggest_category(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<CategorySuggestionQuery>,
+) -> Result<Json<CategorySuggestionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::debug!("Suggesting category for user {}", user_id);
+
+    query.validate().map_err(|e| {
+        tracing::warn!("Category suggestion validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let suggestion =
+        transaction_service::suggest_category(&state.read_db, user_id, &query.title).await?;
+    Ok(Json(suggestion))
+}
+
+/// List reimbursable transactions that were not paid back yet, with the amounts owed
+/// GET /transactions/reimbursable/outstanding
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/reimbursable/outstanding",
+    tag = "transactions",
+    params(ReimbursementQuery),
+    responses(
+        (status = 200, description = "Outstanding reimbursements with totals per currency and category", body = OutstandingReimbursementsResponse),
+        (status = 403, description = "Category belongs to another user", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn outstanding_reimbursements(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Query(query): Query<ReimbursementQuery>,
+) -> Result<Json<OutstandingReimbursementsResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Listing outstanding reimbursements for user {}", user_id);
+
+    let outstanding =
+        transaction_service::outstanding_reimbursements(&state.read_db, user_id, query).await?;
+    Ok(Json(outstanding))
+}
+
 /// Get a single transaction by ID
 /// GET /transactions/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/{id}",
+    tag = "transactions",
+    params(
+        ("id" = Uuid, Path, description = "Transaction ID"),
+        DisplayQuery,
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "Transaction with splits", body = TransactionResponse),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<TransactionResponse>, ApiError> {
+    Query(display): Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = auth_context.user_id();
     tracing::debug!("Fetching transaction {} for user {}", id, user_id);
 
-    let transaction = transaction_service::get_transaction(&state.db, id, user_id).await?;
+    let mut transaction = transaction_service::get_transaction(&state.read_db, id, user_id).await?;
+    if let Some(locale) = display_locale(&state, &display) {
+        transaction.set_display_locale(locale);
+    }
 
-    Ok(Json(transaction))
+    etag::json_with_etag(&headers, &transaction)
 }
 
 /// Update a transaction
 /// PUT /transactions/:id
+#[utoipa::path(
+    put,
+    path = "/api/v1/transactions/{id}",
+    tag = "transactions",
+    params(
+        ("id" = Uuid, Path, description = "Transaction ID"),
+        ("If-Match" = Option<String>, Header, description = "Version the client last saw, e.g. \"3\" (alternative to `version` in the body)"),
+        TransactionWriteQuery,
+        DisplayQuery,
+    ),
+    request_body = UpdateTransactionRequest,
+    responses(
+        (status = 200, description = "Transaction updated (with any `warnings`)", body = TransactionResponse),
+        (status = 400, description = "Invalid If-Match header", body = ErrorResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Settled splits cannot be changed or removed, a transfer leg's amount or account cannot be changed, or the transaction was modified since the given version (`current` holds its current state)", body = VersionConflictResponse),
+        (status = 422, description = "Validation error, overdraft limit exceeded, or warnings raised with `strict=true`", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdateTransactionRequest>,
+    Query(query): Query<TransactionWriteQuery>,
+    Query(display): Query<DisplayQuery>,
+    headers: HeaderMap,
+    Json(mut request): Json<UpdateTransactionRequest>,
 ) -> Result<Json<TransactionResponse>, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Updating transaction {} for user {}", id, user_id);
+    request.version = version::expected_version(&headers, request.version)?;
+    if let Some(ref splits) = request.splits {
+        check_split_count(&state, splits.len())?;
+    }
 
-    let transaction =
-        transaction_service::update_transaction(&state.db, id, user_id, request).await?;
+    let mut transaction =
+        transaction_service::update_transaction(&state.db, id, user_id, request, query.strict)
+            .await?;
+    if let Some(locale) = display_locale(&state, &display) {
+        transaction.set_display_locale(locale);
+    }
 
-    // Trigger split sync update for all splits (fire-and-forget)
-    if let Some(ref splits) = transaction.splits {
-        for split in splits {
-            trigger_split_sync_updated(state.split_sync.clone(), split.id).await;
-        }
+    // Re-sync the linked external expense after splits were edited (fire-and-forget).
+    // Syncing any remaining split updates the whole expense.
+    if transaction.needs_resync
+        && let Some(split) = transaction.splits.as_ref().and_then(|s| s.first())
+    {
+        trigger_split_sync_updated(state.split_sync.clone(), split.id).await;
     }
 
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
     Ok(Json(transaction))
 }
 
 /// Delete a transaction
 /// DELETE /transactions/:id
+///
+/// The transaction is kept until restored. Deleting either leg of a transfer
+/// deletes both. External expenses synced from
+/// the transaction's splits are deleted from their provider first. If that fails
+/// the transaction is kept, unless `force=true`.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/transactions/{id}",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID"), DeleteTransactionQuery),
+    responses(
+        (status = 204, description = "Transaction deleted"),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+        (status = 502, description = "A linked external expense could not be deleted", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
     Path(id): Path<Uuid>,
+    Query(query): Query<DeleteTransactionQuery>,
 ) -> Result<StatusCode, ApiError> {
     let user_id = auth_context.user_id();
     tracing::info!("Deleting transaction {} for user {}", id, user_id);
 
-    // Get splits before deletion so we can notify sync service
-    let existing = transaction_service::get_transaction(&state.db, id, user_id).await?;
-    let split_ids: Vec<Uuid> = existing
-        .splits
-        .as_ref()
-        .map(|s| s.iter().map(|split| split.id).collect())
-        .unwrap_or_default();
+    // Verify ownership before touching any external expense
+    let transaction = transaction_service::get_transaction(&state.db, id, user_id).await?;
+
+    // Delete linked external expenses first; the sync records go with the splits
+    if let Some(service) = &state.split_sync {
+        for transaction_id in
+            transaction_service::linked_transaction_ids(&state.db, &transaction).await?
+        {
+            service
+                .delete_transaction_expenses(transaction_id, query.force)
+                .await?;
+        }
+    }
 
-    transaction_service::delete_transaction(&state.db, id, user_id).await?;
+    let deleted = transaction_service::delete_transaction(&state.db, id, user_id).await?;
 
-    // Trigger split sync deletion for each split (fire-and-forget)
-    for split_id in split_ids {
-        trigger_split_sync_deleted(state.split_sync.clone(), id, split_id).await;
+    for transaction_id in deleted {
+        state
+            .events
+            .publish(user_id, ChangeEvent::TransactionDeleted { transaction_id });
     }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Restore a deleted transaction
+/// POST /transactions/:id/restore
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/restore",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID")),
+    responses(
+        (status = 200, description = "Transaction restored, with both legs of a transfer", body = TransactionResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction is not deleted", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restore(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Restoring transaction {} for user {}", id, user_id);
+
+    let restored = transaction_service::restore_transaction(&state.db, id, user_id).await?;
+
+    // Their external expenses were deleted with them, so sync the splits again
+    for transaction_id in restored {
+        let transaction =
+            transaction_service::get_transaction(&state.db, transaction_id, user_id).await?;
+        if let Some(splits) = transaction.splits.filter(|splits| !splits.is_empty()) {
+            let split_ids = splits.iter().map(|s| s.id).collect();
+            trigger_split_sync_created(state.split_sync.clone(), transaction_id, split_ids).await;
+        }
+
+        state
+            .events
+            .publish(user_id, ChangeEvent::TransactionCreated { transaction_id });
+    }
+
+    let transaction = transaction_service::get_transaction(&state.db, id, user_id).await?;
+
+    Ok(Json(transaction))
+}
+
+/// Mark a transaction split as settled locally
+/// POST /transactions/:id/splits/:split_id/settle
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/splits/{split_id}/settle",
+    tag = "transactions",
+    params(
+        ("id" = Uuid, Path, description = "Transaction ID"),
+        ("split_id" = Uuid, Path, description = "Split ID"),
+    ),
+    responses(
+        (status = 200, description = "Split settled", body = TransactionSplitResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction or split not found", body = ErrorResponse),
+        (status = 409, description = "Split is already settled", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn settle_split(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path((id, split_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<TransactionSplitResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!(
+        "Settling split {} of transaction {} for user {}",
+        split_id,
+        id,
+        user_id
+    );
+
+    let split = transaction_service::settle_split(&state.db, id, split_id, user_id).await?;
+
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
+    Ok(Json(split))
+}
+
+/// Post a pending transaction
+/// POST /transactions/:id/post
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/post",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID")),
+    responses(
+        (status = 200, description = "Transaction posted", body = TransactionResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction is not pending", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn post(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Posting transaction {} for user {}", id, user_id);
+
+    let transaction = transaction_service::post_transaction(&state.db, id, user_id).await?;
+
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
+    Ok(Json(transaction))
+}
+
+/// Void a transaction
+/// POST /transactions/:id/void
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/void",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID")),
+    responses(
+        (status = 200, description = "Transaction voided", body = TransactionResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction is already void, or is a transfer leg", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn void(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Voiding transaction {} for user {}", id, user_id);
+
+    let transaction = transaction_service::void_transaction(&state.db, id, user_id).await?;
+
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
+    Ok(Json(transaction))
+}
+
+/// Restore a void transaction as posted
+/// POST /transactions/:id/unvoid
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/unvoid",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID")),
+    responses(
+        (status = 200, description = "Transaction restored as posted", body = TransactionResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction is not void", body = ErrorResponse),
+        (status = 422, description = "Restoring the amount would exceed the account's limit", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn unvoid(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Unvoiding transaction {} for user {}", id, user_id);
+
+    let transaction = transaction_service::unvoid_transaction(&state.db, id, user_id).await?;
+
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
+    Ok(Json(transaction))
+}
+
+/// Record that a reimbursable transaction was paid back
+/// POST /transactions/:id/mark-reimbursed
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/{id}/mark-reimbursed",
+    tag = "transactions",
+    params(("id" = Uuid, Path, description = "Transaction ID")),
+    responses(
+        (status = 200, description = "Transaction with `reimbursed_at` set", body = TransactionResponse),
+        (status = 403, description = "Transaction belongs to another user", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Transaction is not reimbursable", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn mark_reimbursed(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!("Marking transaction {} reimbursed for user {}", id, user_id);
+
+    let transaction = transaction_service::mark_reimbursed(&state.db, id, user_id).await?;
+
+    state.events.publish(
+        user_id,
+        ChangeEvent::TransactionUpdated { transaction_id: id },
+    );
+
+    Ok(Json(transaction))
+}
+
 /// Bulk create transactions
 /// POST /transactions/bulk-create
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/bulk-create",
+    tag = "transactions",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; retrying with it replays the stored response"),
+    ),
+    request_body = crate::models::BulkCreateRequest,
+    responses(
+        (status = 200, description = "Per-transaction results of the bulk create", body = crate::models::BulkCreateResponse),
+        (status = 409, description = "Idempotency-Key still in progress or used for a different request", body = ErrorResponse),
+        (status = 403, description = "Account belongs to another user", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn bulk_create(
     State(state): State<AppState>,
     Extension(auth_context): Extension<AuthContext>,
@@ -142,14 +915,28 @@ pub async fn bulk_create(
 
     // Create transactions one by one
     for (index, transaction_request) in request.transactions.iter().enumerate() {
-        match transaction_service::create_transaction(
-            &state.db,
-            user_id,
-            (*transaction_request).clone(),
-        )
-        .await
-        {
-            Ok(transaction) => created_transactions.push(transaction),
+        let result = match check_split_count(&state, requested_split_count(transaction_request)) {
+            Ok(()) => {
+                transaction_service::create_transaction(
+                    &state.db,
+                    user_id,
+                    (*transaction_request).clone(),
+                    false,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(transaction) => {
+                state.events.publish(
+                    user_id,
+                    ChangeEvent::TransactionCreated {
+                        transaction_id: transaction.id,
+                    },
+                );
+                created_transactions.push(transaction);
+            }
             Err(e) => {
                 errors.push(crate::models::BulkCreateError {
                     index,
@@ -174,6 +961,81 @@ pub async fn bulk_create(
     }))
 }
 
+/// Create, update and delete transactions in one go, all or none
+/// POST /transactions/bulk
+///
+/// If any item is invalid nothing is applied and the response lists which items
+/// failed, with `success: false`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/bulk",
+    tag = "transactions",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; retrying with it replays the stored response"),
+    ),
+    request_body = BulkTransactionRequest,
+    responses(
+        (status = 200, description = "Per-item results; nothing is applied unless `success` is true", body = BulkTransactionResponse),
+        (status = 404, description = "A transaction was deleted during the request", body = ErrorResponse),
+        (status = 409, description = "A transaction was modified during the request, or the Idempotency-Key is in progress or used for a different request", body = ErrorResponse),
+        (status = 422, description = "The changes would exceed an account's limit", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid authentication", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn bulk(
+    State(state): State<AppState>,
+    Extension(auth_context): Extension<AuthContext>,
+    Json(request): Json<BulkTransactionRequest>,
+) -> Result<Json<BulkTransactionResponse>, ApiError> {
+    let user_id = auth_context.user_id();
+    tracing::info!(
+        "Bulk creating {}, updating {} and deleting {} transactions for user {}",
+        request.create.len(),
+        request.update.len(),
+        request.delete.len(),
+        user_id
+    );
+
+    let (response, deleted) =
+        bulk_transaction_service::apply_bulk(&state.db, user_id, request).await?;
+
+    // Already deleted locally, so expenses that cannot be deleted are recorded as orphaned
+    if let Some(service) = &state.split_sync {
+        for transaction_id in &deleted {
+            if let Err(e) = service
+                .delete_transaction_expenses(*transaction_id, true)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to delete external expenses of transaction {}: {}",
+                    transaction_id,
+                    e
+                );
+            }
+        }
+    }
+
+    for result in &response.results {
+        let Some(transaction_id) = result.transaction_id else {
+            continue;
+        };
+        let event = match result.status {
+            BulkItemStatus::Created => ChangeEvent::TransactionCreated { transaction_id },
+            BulkItemStatus::Updated => ChangeEvent::TransactionUpdated { transaction_id },
+            _ => continue,
+        };
+        state.events.publish(user_id, event);
+    }
+    for transaction_id in deleted {
+        state
+            .events
+            .publish(user_id, ChangeEvent::TransactionDeleted { transaction_id });
+    }
+
+    Ok(Json(response))
+}
+
 // --- Split Sync Helper Functions ---
 // These are fire-and-forget: sync failures never block transaction operations.
 
@@ -205,21 +1067,3 @@ async fn trigger_split_sync_updated(sync_service: Option<SplitSyncService>, spli
         }
     }
 }
-
-/// Trigger sync after a split is deleted
-async fn trigger_split_sync_deleted(
-    sync_service: Option<SplitSyncService>,
-    transaction_id: Uuid,
-    split_id: Uuid,
-) {
-    if let Some(service) = sync_service {
-        if let Err(e) = service.on_split_deleted(transaction_id, split_id).await {
-            tracing::warn!(
-                "Split sync failed after deleting split {} from transaction {}: {}",
-                split_id,
-                transaction_id,
-                e
-            );
-        }
-    }
-}
@@ -0,0 +1,53 @@
+//! Optimistic concurrency for updates
+//!
+//! Transactions, accounts and budgets carry a `version` that is incremented on
+//! every update. A PUT may name the version the client last saw, either in the
+//! body's `version` field or in an `If-Match` header (`If-Match: "3"`); the update
+//! is then applied only if the resource is still at that version, and otherwise
+//! fails with `409 Conflict` and the resource's current state.
+//!
+//! Updates that name no version are applied unconditionally.
+
+use axum::http::{HeaderMap, header::IF_MATCH};
+
+use crate::errors::ApiError;
+
+/// Expected version of an update, from `If-Match` and/or the request body
+///
+/// `If-Match: *` matches any version. When both the header and the body name a
+/// version they must agree.
+pub fn expected_version(
+    headers: &HeaderMap,
+    body_version: Option<i32>,
+) -> Result<Option<i32>, ApiError> {
+    let header_version = match headers.get(IF_MATCH) {
+        None => None,
+        Some(value) => {
+            let value = value
+                .to_str()
+                .map_err(|_| ApiError::BadRequest("Invalid If-Match header".to_string()))?
+                .trim();
+            if value == "*" {
+                None
+            } else {
+                let version = value
+                    .trim_start_matches("W/")
+                    .trim_matches('"')
+                    .parse::<i32>()
+                    .map_err(|_| {
+                        ApiError::BadRequest(
+                            "If-Match must contain a resource version, e.g. \"3\"".to_string(),
+                        )
+                    })?;
+                Some(version)
+            }
+        }
+    };
+
+    match (header_version, body_version) {
+        (Some(header), Some(body)) if header != body => Err(ApiError::BadRequest(
+            "If-Match and version in the body disagree".to_string(),
+        )),
+        (header, body) => Ok(header.or(body)),
+    }
+}
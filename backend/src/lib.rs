@@ -66,12 +66,29 @@ pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
-    /// Database connection pool
+    /// Database connection pool (the primary, used for all writes)
     pub db: DbPool,
+    /// Pool for read-only requests: a read replica if configured, otherwise the primary
+    ///
+    /// Replicas may lag behind the primary, so anything read as part of a write
+    /// (ownership checks, the response of a mutation) must use `db` instead.
+    pub read_db: DbPool,
     /// Application configuration
     pub config: Config,
     /// Split sync service for syncing transaction splits to external providers
     pub split_sync: Option<services::split_sync_service::SplitSyncService>,
+    /// Change notifications streamed to clients at `/api/v1/events`
+    pub events: services::event_service::EventBus,
+    /// Exchange rate service for conversions; `None` when no rate provider is available
+    pub exchange_rates: Option<services::exchange_rate_service::ExchangeRateService>,
+    /// Transport for outgoing emails such as password resets
+    pub email: std::sync::Arc<dyn services::email_service::EmailSender>,
+    /// Delivers notifications such as budget alerts through users' channels
+    pub notifier: services::notification_service::Notifier,
+    /// Token buckets of rate limited API keys
+    pub api_key_rate_limiter: middleware::rate_limit::RateLimiter<uuid::Uuid>,
+    /// Token buckets of client IPs, users and login attempts
+    pub rate_limiter: middleware::rate_limit::RateLimiter<middleware::rate_limit::ClientKey>,
 }
 
 impl AppState {
@@ -82,10 +99,54 @@ impl AppState {
             db.clone(),
         ));
 
+        let email: std::sync::Arc<dyn services::email_service::EmailSender> =
+            std::sync::Arc::new(services::email_service::LogEmailSender);
+        let notifier = services::notification_service::Notifier::new(
+            db.clone(),
+            email.clone(),
+            &config.notifications,
+        );
+
         Self {
+            read_db: db.clone(),
             db,
             config,
             split_sync,
+            events: services::event_service::EventBus::default(),
+            exchange_rates: services::exchange_rate_service::ExchangeRateService::new().ok(),
+            email,
+            notifier,
+            api_key_rate_limiter: middleware::rate_limit::RateLimiter::default(),
+            rate_limiter: middleware::rate_limit::RateLimiter::default(),
         }
     }
+
+    /// Serve read-only requests from a read replica instead of the primary
+    pub fn with_read_replica(mut self, read_db: DbPool) -> Self {
+        self.read_db = read_db;
+        self
+    }
+
+    /// Convert currencies with `exchange_rates` instead of the configured providers
+    pub fn with_exchange_rate_service(
+        mut self,
+        exchange_rates: services::exchange_rate_service::ExchangeRateService,
+    ) -> Self {
+        self.exchange_rates = Some(exchange_rates);
+        self
+    }
+
+    /// Send emails, including notification emails, through `email` instead of logging them
+    pub fn with_email_sender(
+        mut self,
+        email: std::sync::Arc<dyn services::email_service::EmailSender>,
+    ) -> Self {
+        self.notifier = services::notification_service::Notifier::new(
+            self.db.clone(),
+            email.clone(),
+            &self.config.notifications,
+        );
+        self.email = email;
+        self
+    }
 }
@@ -2,6 +2,7 @@ use axum::middleware;
 use diesel::PgConnection;
 use diesel::r2d2::{self, ConnectionManager};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use std::path::PathBuf;
 use tracing_subscriber::EnvFilter;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -20,8 +21,10 @@ async fn main() {
 
     tracing::info!("🚀 Master of Coin Backend starting...");
 
-    // 2. Load configuration from environment
-    let config = master_of_coin_backend::Config::from_env().expect("Failed to load configuration");
+    // 2. Load configuration from the config file (if any) and environment
+    let config_file = config_file_arg(std::env::args().skip(1));
+    let config = master_of_coin_backend::Config::load(config_file.as_deref())
+        .expect("Failed to load configuration");
 
     tracing::info!(
         "Configuration loaded - Server: {}:{}",
@@ -42,6 +45,16 @@ async fn main() {
         config.database.max_connections
     );
 
+    // Read-only endpoints use the replica when one is configured
+    let replica_pool = config.database.replica_url.as_ref().map(|replica_url| {
+        let pool = r2d2::Pool::builder()
+            .max_size(config.database.max_connections)
+            .build(ConnectionManager::<PgConnection>::new(replica_url))
+            .expect("Failed to create read replica pool");
+        tracing::info!("✅ Read replica pool created for read-only endpoints");
+        pool
+    });
+
     // 4. Run pending migrations
     {
         let mut conn = pool.get().expect("Failed to get database connection");
@@ -74,13 +87,63 @@ async fn main() {
         );
     }
 
-    // 6. Build application state
-    let state = master_of_coin_backend::AppState::new(pool, config.clone());
+    // 6. Start background maintenance tasks
+    master_of_coin_backend::services::auth_event_service::spawn_retention_purge(
+        pool.clone(),
+        config.auth_events.clone(),
+    );
+    master_of_coin_backend::services::idempotency_service::spawn_expiry_purge(pool.clone());
+    master_of_coin_backend::services::purge_service::spawn_retention_purge(
+        pool.clone(),
+        config.purge.clone(),
+    );
+    if config.encryption_key_configured {
+        master_of_coin_backend::services::split_sync_service::spawn_reconciliation(
+            master_of_coin_backend::services::split_sync_service::SplitSyncService::new(
+                pool.clone(),
+            ),
+            config.split_reconciliation.clone(),
+        );
+    }
+
+    if let Err(e) = master_of_coin_backend::services::exchange_rate_service::configure_providers(
+        &config.exchange_rates.providers,
+    ) {
+        tracing::warn!("⚠️  Exchange rates are unavailable: {}", e);
+    } else {
+        tracing::info!(
+            "✅ Exchange rate providers: {}",
+            config
+                .exchange_rates
+                .providers
+                .iter()
+                .map(|kind| kind.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    master_of_coin_backend::services::exchange_rate_service::spawn_refresh(
+        pool.clone(),
+        config.exchange_rate_refresh.clone(),
+    );
+
+    // 7. Build application state
+    let mut state = master_of_coin_backend::AppState::new(pool, config.clone());
+    if let Some(replica_pool) = replica_pool {
+        state = state.with_read_replica(replica_pool);
+    }
+
+    // Recurring transactions notify clients through the state's event bus
+    master_of_coin_backend::services::recurring_service::spawn_materialization(
+        state.db.clone(),
+        state.events.clone(),
+        config.recurring.clone(),
+    );
 
-    // 7. Create router with middleware layers
+    // 8. Create router with middleware layers
     // Middleware is applied in reverse order (bottom to top):
     // - Routes with auth middleware (innermost, applied in routes.rs)
-    // - Request logging middleware
+    // - Request logging middleware (request IDs and the request's tracing span)
     // - CORS middleware (outermost)
     let app = master_of_coin_backend::api::routes::create_router(state)
         .layer(middleware::from_fn(
@@ -88,7 +151,7 @@ async fn main() {
         ))
         .layer(master_of_coin_backend::middleware::cors::create_cors_layer());
 
-    // 8. Bind to configured address and start server
+    // 9. Bind to configured address and start server
     let addr = format!("{}:{}", config.server.host, config.server.port);
     let listener = tokio::net::TcpListener::bind(&addr)
         .await
@@ -98,15 +161,31 @@ async fn main() {
         });
 
     tracing::info!("🚀 Server listening on {}", addr);
-    tracing::info!(
-        "📝 API documentation will be available at http://{}/api/docs",
-        addr
-    );
+    tracing::info!("📝 API documentation available at http://{}/api/docs", addr);
     tracing::info!("✨ Ready to accept requests!");
 
     // Start server with graceful shutdown capability
-    axum::serve(listener, app).await.unwrap_or_else(|e| {
+    // Connection info gives rate limiting the client IP
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap_or_else(|e| {
         tracing::error!("Server error: {}", e);
         std::process::exit(1);
     });
 }
+
+/// Extract the config file path from `--config <path>` or `--config=<path>`
+fn config_file_arg(mut args: impl Iterator<Item = String>) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
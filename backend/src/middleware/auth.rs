@@ -77,6 +77,9 @@ pub async fn require_auth(
         authenticate_with_jwt(&pool, token).await?
     };
 
+    // Tag the request's logging span with the authenticated user
+    tracing::Span::current().record("user_id", tracing::field::display(auth_context.user_id()));
+
     // Add AuthContext to request extensions
     req.extensions_mut().insert(auth_context);
 
@@ -110,7 +113,10 @@ async fn authenticate_with_jwt(pool: &DbPool, token: &str) -> Result<AuthContext
         }
     };
 
-    Ok(AuthContext::Jwt { user })
+    Ok(AuthContext::Jwt {
+        user,
+        session_id: claims.sid,
+    })
 }
 
 /// Authenticate with API key
@@ -140,5 +146,6 @@ async fn authenticate_with_api_key(
         user,
         api_key_id: api_key_record.id,
         scopes,
+        requests_per_minute: api_key_record.requests_per_minute,
     })
 }
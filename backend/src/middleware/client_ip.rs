@@ -0,0 +1,81 @@
+//! Client IP resolution.
+//!
+//! The client IP is the address of the peer the connection came from. Only
+//! when that peer is one of the configured trusted proxies are the
+//! `X-Forwarded-For` and `X-Real-IP` headers believed, so clients can't forge
+//! the IP that rate limits count against and the security log records.
+//!
+//! [`resolve_client_ip`] runs before every other middleware and stores the
+//! result as a [`ClientIp`] request extension, so all of them see the same IP.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::AppState;
+
+/// IP address of the client a request came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Middleware storing the request's [`ClientIp`]
+///
+/// Nothing is stored when the server was not started with connection info, as
+/// in tests.
+pub async fn resolve_client_ip(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(peer) = peer {
+        let ip = client_ip(
+            peer,
+            request.headers(),
+            &state.config.server.trusted_proxies,
+        );
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    next.run(request).await
+}
+
+/// IP address of the client behind the connection from `peer`
+///
+/// When `peer` is a trusted proxy, `X-Forwarded-For` is read from the right,
+/// skipping trusted proxies, and the first other address is the client: entries
+/// to its left were sent by the client and can't be believed. `X-Real-IP` is
+/// used when all entries are trusted proxies. Without a believable header the
+/// peer itself is the client.
+pub fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+    for entry in forwarded.into_iter().rev() {
+        match entry.trim().parse::<IpAddr>() {
+            Ok(ip) if trusted_proxies.contains(&ip) => continue,
+            Ok(ip) => return ip,
+            // A trusted proxy would not have added it, so the client did
+            Err(_) => return peer,
+        }
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(peer)
+}
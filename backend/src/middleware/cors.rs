@@ -1,12 +1,20 @@
-use axum::http::Method;
+use axum::http::{HeaderName, Method};
 use tower_http::cors::CorsLayer;
 
+use crate::{
+    handlers::{idempotency::IDEMPOTENCY_KEY, transactions::SUMMARY_HEADERS},
+    middleware::logging::REQUEST_ID_HEADER,
+};
+
 /// Creates a CORS layer for the application
 ///
 /// This configuration:
 /// - Allows all origins (should be restricted in production)
 /// - Allows common HTTP methods (GET, POST, PUT, DELETE, OPTIONS)
-/// - Allows all headers
+/// - Allows the headers used by the API, including `If-None-Match` for conditional GETs and
+///   `If-Match` for versioned updates, `Idempotency-Key` for retried creates and `X-Request-Id`
+/// - Exposes the `ETag` and `X-Request-Id` response headers and the `X-Total-*` summary headers
+///   of transaction lists
 /// - Allows credentials (cookies, authorization headers)
 ///
 /// # Production Considerations
@@ -16,7 +24,7 @@ use tower_http::cors::CorsLayer;
 /// - Use environment variables to configure allowed origins
 /// - Consider using `allow_origin()` with specific origins instead of `Any`
 pub fn create_cors_layer() -> CorsLayer {
-    use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+    use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH};
 
     // For development, allow localhost origins
     // In production, this should be configured via environment variables
@@ -34,6 +42,20 @@ pub fn create_cors_layer() -> CorsLayer {
             Method::DELETE,
             Method::OPTIONS,
         ])
-        .allow_headers([AUTHORIZATION, CONTENT_TYPE, ACCEPT])
+        .allow_headers([
+            AUTHORIZATION,
+            CONTENT_TYPE,
+            ACCEPT,
+            IF_MATCH,
+            IF_NONE_MATCH,
+            HeaderName::from_static(IDEMPOTENCY_KEY),
+            REQUEST_ID_HEADER,
+        ])
+        .expose_headers(
+            [ETAG, REQUEST_ID_HEADER]
+                .into_iter()
+                .chain(SUMMARY_HEADERS)
+                .collect::<Vec<_>>(),
+        )
         .allow_credentials(true)
 }
@@ -0,0 +1,127 @@
+//! Idempotency middleware for account and transaction create requests.
+//!
+//! Requests carrying an `Idempotency-Key` header claim the key before the
+//! handler runs, and their successful response is stored with it. A retry with
+//! the same key and request gets the stored response without running the
+//! handler again. Failed requests release the key so they can be retried.
+//!
+//! The handler runs in its own task, so a client that disconnects while waiting
+//! does not leave the key claimed without a response.
+
+use axum::{
+    Extension,
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    DbPool,
+    auth::context::AuthContext,
+    errors::ApiError,
+    handlers::idempotency::idempotency_key,
+    models::idempotency_key::IdempotencyScope,
+    services::idempotency_service::{self, Claim},
+};
+
+/// Largest request body read to hash it, axum's default body limit
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// Make a create route idempotent for requests sent with an `Idempotency-Key`
+///
+/// Keys are scoped to the user and to `scope`, which names the route.
+pub async fn idempotent(
+    State((pool, scope)): State<(DbPool, IdempotencyScope)>,
+    Extension(auth_context): Extension<AuthContext>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = idempotency_key(request.headers())? else {
+        return Ok(next.run(request).await);
+    };
+    let user_id = auth_context.user_id();
+
+    // The query string is part of the request, e.g. `strict=true`
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_REQUEST_BYTES)
+        .await
+        .map_err(|_| ApiError::BadRequest("Request body is too large".to_string()))?;
+    let mut hasher = Sha256::new();
+    hasher.update(parts.uri.query().unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(&body);
+    let request_hash: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    match idempotency_service::claim(&pool, user_id, scope, key.clone(), request_hash).await? {
+        Claim::Completed { status, body } => {
+            tracing::debug!(
+                "Replaying {} response for Idempotency-Key of user {}",
+                scope.as_str(),
+                user_id
+            );
+            let status = StatusCode::from_u16(status).map_err(|_| ApiError::Internal)?;
+            return Ok((status, [(header::CONTENT_TYPE, "application/json")], body).into_response());
+        }
+        Claim::Claimed => {}
+    }
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let task = tokio::spawn(async move {
+        let response = next.run(request).await;
+
+        if !response.status().is_success() {
+            release(&pool, user_id, scope, key).await;
+            return response;
+        }
+
+        let (parts, body) = response.into_parts();
+        let body = match to_bytes(body, usize::MAX).await {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to read response to store: {}", e);
+                release(&pool, user_id, scope, key).await;
+                return ApiError::Internal.into_response();
+            }
+        };
+
+        let stored = idempotency_service::complete(
+            &pool,
+            user_id,
+            scope,
+            key.clone(),
+            parts.status.as_u16(),
+            String::from_utf8_lossy(&body).into_owned(),
+        )
+        .await;
+        if let Err(e) = stored {
+            // Without a stored response the key would stay in progress until it expires
+            tracing::error!("Failed to store response for Idempotency-Key: {}", e);
+            release(&pool, user_id, scope, key).await;
+        }
+
+        Response::from_parts(parts, Body::from(body))
+    });
+
+    task.await.map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })
+}
+
+/// Release a claimed key, logging a failure as the response is sent regardless
+async fn release(pool: &DbPool, user_id: uuid::Uuid, scope: IdempotencyScope, key: String) {
+    if let Err(e) = idempotency_service::release(pool, user_id, scope, key).await {
+        tracing::error!(
+            "Failed to release Idempotency-Key of user {}: {}",
+            user_id,
+            e
+        );
+    }
+}
@@ -1,34 +1,75 @@
-use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
 use tracing::Instrument;
 use uuid::Uuid;
 
+/// Header carrying the ID of a request, read from clients and set on every response
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request ID that is kept; longer ones are replaced
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// ID of the current request, available to handlers as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Use the client's `X-Request-Id` if it is usable, or generate a UUID
+    ///
+    /// Client IDs must be 1 to 128 visible ASCII characters, so they can't
+    /// break up log lines.
+    fn from_header(value: Option<&HeaderValue>) -> Self {
+        let client_id = value.and_then(|value| value.to_str().ok()).filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LENGTH
+                && id.bytes().all(|b| b.is_ascii_graphic())
+        });
+
+        match client_id {
+            Some(id) => Self(id.to_string()),
+            None => Self(Uuid::new_v4().to_string()),
+        }
+    }
+}
+
 /// Middleware to log incoming requests and their responses
 ///
 /// This middleware:
-/// - Generates a unique request ID for each request
-/// - Creates a tracing span that propagates the request_id to all logs within the request
+/// - Takes the request ID from the `X-Request-Id` header, or generates one
+/// - Stores it as a [`RequestId`] request extension and echoes it in the response
+/// - Creates a tracing span that propagates the request_id to all logs within the request;
+///   the authentication middleware adds the `user_id` to it
 /// - Logs request details (method, URI, request_id)
 /// - Measures request duration
 /// - Logs response status and duration
-pub async fn log_request(req: Request<Body>, next: Next) -> Response {
-    let request_id = Uuid::new_v4();
+pub async fn log_request(mut req: Request<Body>, next: Next) -> Response {
+    let request_id = RequestId::from_header(req.headers().get(&REQUEST_ID_HEADER));
     let method = req.method().clone();
     let uri = req.uri().clone();
 
     // Create a span that will propagate request_id to all logs within this request
     let span = tracing::info_span!(
         "request",
-        request_id = %request_id,
+        request_id = %request_id.0,
         method = %method,
         uri = %uri,
+        user_id = tracing::field::Empty,
     );
 
+    let header_value = HeaderValue::from_str(&request_id.0).ok();
+    req.extensions_mut().insert(request_id);
+
     // Enter the span for the entire request lifecycle
     async move {
         tracing::info!("Incoming request");
 
         let start = std::time::Instant::now();
-        let response = next.run(req).await;
+        let mut response = next.run(req).await;
         let duration = start.elapsed();
 
         tracing::info!(
@@ -37,6 +78,12 @@ pub async fn log_request(req: Request<Body>, next: Next) -> Response {
             "Request completed"
         );
 
+        if let Some(header_value) = header_value {
+            response
+                .headers_mut()
+                .insert(REQUEST_ID_HEADER, header_value);
+        }
+
         response
     }
     .instrument(span)
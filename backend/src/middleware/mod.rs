@@ -1,5 +1,8 @@
 // HTTP middleware
 pub mod auth;
+pub mod client_ip;
 pub mod cors;
+pub mod idempotency;
 pub mod logging;
+pub mod rate_limit;
 pub mod scope;
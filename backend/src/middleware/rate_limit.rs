@@ -0,0 +1,269 @@
+//! Rate limiting middleware.
+//!
+//! Requests are limited with token buckets: each client has a bucket holding
+//! up to its per-minute limit of tokens, refilled continuously at that rate,
+//! and every request takes one token. This allows short bursts up to the limit
+//! while holding the sustained rate to it.
+//!
+//! Buckets live in memory, so limits apply per server process.
+//!
+//! Besides the per-key limits of API keys, every request is limited by client
+//! IP before authentication, and requests to protected routes by user after
+//! it too. Login attempts get a stricter bucket of their own. Limits are set in [`RateLimitConfig`].
+//!
+//! [`RateLimitConfig`]: crate::config::RateLimitConfig
+
+use axum::{
+    Extension,
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use super::client_ip::ClientIp;
+use crate::{auth::context::AuthContext, config::BucketLimit, errors::ApiError};
+
+/// Header carrying the number of requests left before the limit is reached
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+/// Number of buckets above which full buckets are dropped
+///
+/// A full bucket behaves like a missing one, so dropping them only bounds the
+/// memory used by clients that stopped sending requests.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Tokens left for one client
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+    /// When the bucket will be full again if no requests are made
+    full_at: Instant,
+}
+
+/// Client a request is counted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    /// Unauthenticated requests from an IP
+    Ip(IpAddr),
+    /// Authenticated requests of a user
+    User(Uuid),
+    /// Login attempts from an IP
+    Login(IpAddr),
+    /// Requests to protected routes from an IP, before authentication
+    ProtectedIp(IpAddr),
+}
+
+/// Token buckets keyed by client
+#[derive(Debug, Clone)]
+pub struct RateLimiter<K> {
+    buckets: Arc<Mutex<HashMap<K, TokenBucket>>>,
+}
+
+impl<K> Default for RateLimiter<K> {
+    fn default() -> Self {
+        Self {
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    /// Take a token from the bucket of `key`, allowing `per_minute` requests a minute
+    ///
+    /// # Returns
+    /// * `Ok(u32)` - The whole tokens left after this request
+    /// * `Err(u64)` - The bucket is empty; seconds until the next token
+    pub fn acquire(&self, key: K, per_minute: u32) -> Result<u32, u64> {
+        self.take(
+            key,
+            BucketLimit {
+                capacity: per_minute,
+                refill_per_minute: per_minute,
+            },
+        )
+    }
+
+    /// Take a token from the bucket of `key`, sized and refilled as `limit` says
+    ///
+    /// # Returns
+    /// * `Ok(u32)` - The whole tokens left after this request
+    /// * `Err(u64)` - The bucket is empty; seconds until the next token
+    pub fn take(&self, key: K, limit: BucketLimit) -> Result<u32, u64> {
+        let capacity = f64::from(limit.capacity);
+        let per_second = f64::from(limit.refill_per_minute) / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+        let bucket = buckets.entry(key).or_insert(TokenBucket {
+            tokens: capacity,
+            refilled_at: now,
+            full_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        let result = if bucket.tokens < 1.0 {
+            Err(((1.0 - bucket.tokens) / per_second).ceil() as u64)
+        } else {
+            bucket.tokens -= 1.0;
+            Ok(bucket.tokens.floor() as u32)
+        };
+        bucket.full_at = now + Duration::from_secs_f64((capacity - bucket.tokens) / per_second);
+        result
+    }
+}
+
+/// Response for a request over its limit, retryable after `retry_after` seconds
+fn too_many_requests(message: String, retry_after: u64) -> Response {
+    let mut response = ApiError::TooManyRequests(message).into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// IP address the request came from, as resolved by [`resolve_client_ip`]
+///
+/// Unspecified when the server was not started with connection info, as in
+/// tests, so all such requests share one bucket.
+///
+/// [`resolve_client_ip`]: super::client_ip::resolve_client_ip
+fn client_ip(request: &Request) -> IpAddr {
+    request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| *ip)
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
+
+/// Run the request if `key` has a token left in its bucket, otherwise fail with 429
+async fn limit(
+    limiter: &RateLimiter<ClientKey>,
+    key: ClientKey,
+    limit: BucketLimit,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !limit.is_enabled() {
+        return next.run(request).await;
+    }
+
+    match limiter.take(key, limit) {
+        Ok(_) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::warn!("{:?} exceeded its rate limit", key);
+            too_many_requests(
+                "Too many requests, try again later".to_string(),
+                retry_after,
+            )
+        }
+    }
+}
+
+/// Middleware limiting unauthenticated requests by client IP
+///
+/// Once the IP's bucket is empty, requests fail with 429 Too Many Requests
+/// and a `Retry-After` header.
+pub async fn limit_by_ip(
+    State((limiter, bucket)): State<(RateLimiter<ClientKey>, BucketLimit)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = ClientKey::Ip(client_ip(&request));
+    limit(&limiter, key, bucket, request, next).await
+}
+
+/// Middleware limiting requests to protected routes by client IP
+///
+/// Must run before authentication, so requests failing it are counted too and
+/// tokens or API keys can't be guessed at an unlimited rate. Uses a bucket
+/// separate from the one of unauthenticated requests.
+pub async fn limit_protected_by_ip(
+    State((limiter, bucket)): State<(RateLimiter<ClientKey>, BucketLimit)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = ClientKey::ProtectedIp(client_ip(&request));
+    limit(&limiter, key, bucket, request, next).await
+}
+
+/// Middleware limiting login attempts by client IP, to slow credential stuffing
+///
+/// Counts every attempt, successful or not, in a bucket separate from the
+/// IP's other requests.
+pub async fn limit_logins(
+    State((limiter, bucket)): State<(RateLimiter<ClientKey>, BucketLimit)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = ClientKey::Login(client_ip(&request));
+    limit(&limiter, key, bucket, request, next).await
+}
+
+/// Middleware limiting authenticated requests by user
+///
+/// Must run after authentication. Requests made with a user's API keys share
+/// the user's bucket, on top of any limit of the key itself.
+pub async fn limit_by_user(
+    State((limiter, bucket)): State<(RateLimiter<ClientKey>, BucketLimit)>,
+    Extension(auth_context): Extension<AuthContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = ClientKey::User(auth_context.user_id());
+    limit(&limiter, key, bucket, request, next).await
+}
+
+/// Middleware limiting requests made with rate limited API keys
+///
+/// Must run after authentication. Requests authenticated with a JWT, or with
+/// an API key without a limit, pass through untouched. Otherwise the response
+/// carries the requests left in `X-RateLimit-Remaining`, and once the key's
+/// limit is used up requests fail with 429 Too Many Requests and a
+/// `Retry-After` header.
+pub async fn limit_api_keys(
+    State(limiter): State<RateLimiter<Uuid>>,
+    Extension(auth_context): Extension<AuthContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some((api_key_id, per_minute)) = auth_context.rate_limit() else {
+        return next.run(request).await;
+    };
+
+    match limiter.acquire(api_key_id, per_minute) {
+        Ok(remaining) => {
+            let mut response = next.run(request).await;
+            response
+                .headers_mut()
+                .insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(remaining));
+            response
+        }
+        Err(retry_after) => {
+            tracing::warn!("API key {} exceeded its rate limit", api_key_id);
+            let mut response = too_many_requests(
+                format!("Rate limit of {} requests per minute exceeded", per_minute),
+                retry_after,
+            );
+            response
+                .headers_mut()
+                .insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(0));
+            response
+        }
+    }
+}
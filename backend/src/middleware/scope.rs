@@ -6,6 +6,9 @@
 //! - JWT tokens: Always granted full access (return true from has_permission)
 //! - API keys: Must have the specific scope for the resource and operation
 //!
+//! Routes only a logged in user may use are guarded by [`require_jwt`]
+//! instead, which refuses API keys altogether.
+//!
 //! ## Usage
 //!
 //! Apply to routes that need scope checking. See the route configuration
@@ -69,3 +72,35 @@ pub async fn require_scope(
     // Permission granted, proceed to the handler
     Ok(next.run(request).await)
 }
+
+/// Middleware refusing API key authentication
+///
+/// Guards the API key management routes, where a key could otherwise raise its
+/// own rate limit or create a key with more scopes than it has, and the
+/// notification routes, where it could redirect the user's notifications to a
+/// webhook of its choice.
+///
+/// # Errors
+///
+/// Returns [`ApiError::Forbidden`] if the user is authenticated via API key
+pub async fn require_jwt(
+    Extension(auth_context): Extension<AuthContext>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(api_key_id) = auth_context.api_key_id() {
+        tracing::warn!(
+            "Access denied: API key {} of user {} attempted {} {}",
+            api_key_id,
+            auth_context.user_id(),
+            request.method(),
+            request.uri().path()
+        );
+
+        return Err(ApiError::Forbidden(
+            "This endpoint is not available to API keys".to_string(),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
@@ -1,10 +1,12 @@
-use chrono::{DateTime, Utc};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::schema::accounts;
-use crate::types::{AccountType, CurrencyCode};
+use crate::types::{AccountType, CurrencyCode, Granularity, Money, nullable};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = accounts)]
@@ -19,6 +21,18 @@ pub struct Account {
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Whether transactions may push the balance past its limit (warn instead of reject)
+    pub allow_overdraft: bool,
+    /// How far below zero the balance may go (non-credit accounts)
+    pub overdraft_limit: Option<BigDecimal>,
+    /// Maximum outstanding balance for credit card accounts
+    pub credit_limit: Option<BigDecimal>,
+    /// Incremented on every update, for optimistic concurrency control
+    pub version: i32,
+    /// Annual interest rate in percent, for loans and mortgages
+    pub interest_rate: Option<BigDecimal>,
+    /// Principal the loan started at, as a positive amount
+    pub original_principal: Option<BigDecimal>,
 }
 
 #[derive(Debug, Insertable)]
@@ -30,6 +44,11 @@ pub struct NewAccount {
     pub account_type: AccountType,
     pub currency: CurrencyCode,
     pub notes: Option<String>,
+    pub allow_overdraft: bool,
+    pub overdraft_limit: Option<BigDecimal>,
+    pub credit_limit: Option<BigDecimal>,
+    pub interest_rate: Option<BigDecimal>,
+    pub original_principal: Option<BigDecimal>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,41 +64,203 @@ pub struct UpdateAccount {
     pub name: Option<String>,
     pub account_type: Option<AccountType>,
     pub currency: Option<CurrencyCode>,
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
+    pub allow_overdraft: Option<bool>,
+    pub overdraft_limit: Option<BigDecimal>,
+    pub credit_limit: Option<BigDecimal>,
+    pub interest_rate: Option<BigDecimal>,
+    pub original_principal: Option<BigDecimal>,
+    /// Apply the update only if the account is still at this version
+    pub expected_version: Option<i32>,
 }
 
 // Request DTOs
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct CreateAccountRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub account_type: AccountType,
     pub currency: Option<CurrencyCode>,
+    /// Opening balance; for loans and mortgages, the principal still owed
     pub initial_balance: Option<f64>,
     #[validate(length(max = 500))]
     pub notes: Option<String>,
+    /// Allow transactions that breach the overdraft/credit limit (default: true)
+    pub allow_overdraft: Option<bool>,
+    /// How far below zero the balance may go before warning/rejecting
+    #[validate(range(min = 0.0, message = "Overdraft limit must be non-negative"))]
+    pub overdraft_limit: Option<f64>,
+    /// Credit limit for credit card accounts
+    #[validate(range(min = 0.0, message = "Credit limit must be non-negative"))]
+    pub credit_limit: Option<f64>,
+    /// Annual interest rate in percent, for loans and mortgages
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "Interest rate must be between 0 and 100"
+    ))]
+    pub interest_rate: Option<f64>,
+    /// Principal the loan started at
+    #[validate(range(min = 0.0, message = "Original principal must be non-negative"))]
+    pub original_principal: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct UpdateAccountRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
     pub account_type: Option<AccountType>,
     pub currency: Option<CurrencyCode>,
     pub is_active: Option<bool>,
+    /// Omit to keep the notes, `null` to clear them
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
     #[validate(length(max = 500))]
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
+    pub allow_overdraft: Option<bool>,
+    #[validate(range(min = 0.0, message = "Overdraft limit must be non-negative"))]
+    pub overdraft_limit: Option<f64>,
+    #[validate(range(min = 0.0, message = "Credit limit must be non-negative"))]
+    pub credit_limit: Option<f64>,
+    #[validate(range(
+        min = 0.0,
+        max = 100.0,
+        message = "Interest rate must be between 0 and 100"
+    ))]
+    pub interest_rate: Option<f64>,
+    #[validate(range(min = 0.0, message = "Original principal must be non-negative"))]
+    pub original_principal: Option<f64>,
+    /// Version the client last saw; when given (here or as `If-Match`), the update
+    /// fails with 409 if the account has changed since
+    pub version: Option<i32>,
+}
+
+/// Query parameters for an account's period summary
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountSummaryQuery {
+    /// Start of the period, inclusive (RFC 3339); omitted means since the first transaction
+    pub start: Option<DateTime<Utc>>,
+    /// End of the period, inclusive (RFC 3339); omitted means up to the latest transaction
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for an account's balance history
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BalanceHistoryQuery {
+    /// First day of the history (YYYY-MM-DD); omitted means the day of the first posted transaction
+    pub start: Option<NaiveDate>,
+    /// Last day of the history (YYYY-MM-DD); omitted means today
+    pub end: Option<NaiveDate>,
+    /// Length of the intervals between points (default: DAY)
+    #[serde(default)]
+    #[param(value_type = Option<Granularity>)]
+    pub granularity: Granularity,
+}
+
+/// Query parameters for a loan's amortization schedule
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AmortizationQuery {
+    /// Amount paid each month; must be more than the first month's interest
+    #[param(value_type = String)]
+    pub payment: BigDecimal,
 }
 
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AccountResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub name: String,
     pub account_type: AccountType,
     pub currency: CurrencyCode,
+    /// Cleared balance (posted transactions only)
     pub balance: f64,
+    /// Cleared balance plus pending transactions
+    pub available_balance: f64,
     pub is_active: bool,
     pub notes: Option<String>,
+    pub allow_overdraft: bool,
+    pub overdraft_limit: Option<f64>,
+    pub credit_limit: Option<f64>,
+    /// Remaining credit (credit_limit + available_balance) for credit card accounts with a limit
+    pub available_credit: Option<f64>,
+    /// Annual interest rate in percent
+    pub interest_rate: Option<f64>,
+    /// Principal the loan started at
+    pub original_principal: Option<f64>,
+    /// Principal still owed (the negated balance) for loan and mortgage accounts
+    pub outstanding_principal: Option<f64>,
+    /// Current version, to send back with updates
+    pub version: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Posted activity of an account over a period
+///
+/// Transfers between accounts are not counted as income or expense, but are
+/// included in the net change, so `closing_balance = opening_balance + net_change`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AccountSummaryResponse {
+    pub account_id: Uuid,
+    pub currency: CurrencyCode,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// Balance before the start of the period
+    pub opening_balance: Money,
+    /// Sum of incoming transactions, excluding transfers
+    pub total_income: Money,
+    /// Sum of outgoing transactions as a positive amount, excluding transfers
+    pub total_expense: Money,
+    /// Change in balance over the period, including transfers
+    pub net_change: Money,
+    /// Balance at the end of the period
+    pub closing_balance: Money,
+}
+
+/// Posted balance of an account at the end of an interval
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BalancePoint {
+    /// First day of the interval, in UTC
+    pub date: NaiveDate,
+    /// Balance after the interval's last transaction, or after the history's
+    /// `end` for the last interval
+    pub balance: Money,
+}
+
+/// Projected repayment of a loan or mortgage at a fixed monthly payment
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AmortizationResponse {
+    pub account_id: Uuid,
+    pub currency: CurrencyCode,
+    /// Principal owed now, from the posted balance
+    pub principal: Money,
+    /// Annual interest rate in percent
+    pub interest_rate: String,
+    /// Amount paid each month; the last payment may be smaller
+    pub payment: Money,
+    /// Interest paid over the whole schedule
+    pub total_interest: Money,
+    /// Date of the last payment; `null` when nothing is owed
+    pub payoff_date: Option<NaiveDate>,
+    /// Monthly payments until the loan is paid off
+    pub schedule: Vec<AmortizationPayment>,
+}
+
+/// One monthly payment of an amortization schedule
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AmortizationPayment {
+    /// Number of the payment, from 1
+    pub number: u32,
+    pub date: NaiveDate,
+    pub payment: Money,
+    /// Part of the payment that is interest
+    pub interest: Money,
+    /// Part of the payment that repays principal
+    pub principal: Money,
+    /// Principal still owed after the payment
+    pub remaining: Money,
 }
@@ -0,0 +1,134 @@
+//! Aggregator import models
+//!
+//! Request and response types for importing accounts and transactions exported
+//! from a Plaid-style aggregator.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Request for importing an aggregator export
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AggregatorImportRequest {
+    /// Accounts referenced by the transactions
+    #[validate(nested)]
+    pub accounts: Vec<AggregatorAccount>,
+    #[validate(nested)]
+    pub transactions: Vec<AggregatorTransaction>,
+    /// Explicit mapping from external account ID to local account ID
+    ///
+    /// External accounts without a mapping are matched to a local account by name.
+    #[serde(default)]
+    pub account_mapping: HashMap<String, Uuid>,
+    /// Create a local account for external accounts that cannot be matched
+    #[serde(default)]
+    pub create_missing_accounts: bool,
+}
+
+/// Account as exported by the aggregator
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AggregatorAccount {
+    #[validate(length(min = 1, max = 255))]
+    pub account_id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub official_name: Option<String>,
+    /// Aggregator account type, e.g. `depository` or `credit`
+    #[serde(rename = "type")]
+    pub account_type: Option<String>,
+    /// Aggregator account subtype, e.g. `checking` or `savings`
+    pub subtype: Option<String>,
+    #[serde(default)]
+    pub balances: AggregatorBalances,
+}
+
+/// Balance details of an aggregator account (only the currency is used)
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct AggregatorBalances {
+    pub iso_currency_code: Option<String>,
+}
+
+/// Transaction as exported by the aggregator
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AggregatorTransaction {
+    /// Aggregator transaction ID, stored as the external ID for deduplication
+    #[validate(length(min = 1, max = 255))]
+    pub transaction_id: String,
+    /// External ID of the account the transaction belongs to
+    pub account_id: String,
+    /// Amount as reported by the aggregator: positive values are money leaving the account
+    pub amount: f64,
+    /// Currency of the amount; defaults to the external account's currency
+    pub iso_currency_code: Option<String>,
+    pub date: NaiveDate,
+    /// Exact time of the transaction, when known
+    pub datetime: Option<DateTime<Utc>>,
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub merchant_name: Option<String>,
+    /// Pending transactions are skipped until they post
+    #[serde(default)]
+    pub pending: bool,
+}
+
+/// How an external account was matched to a local account
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountMatch {
+    /// Matched through `account_mapping`
+    Mapping,
+    /// Matched by account name
+    Name,
+    /// Created because `create_missing_accounts` was set
+    Created,
+}
+
+/// Response from the aggregator import endpoint
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AggregatorImportResponse {
+    pub success: bool,
+    pub data: AggregatorImportData,
+}
+
+/// Data payload for aggregator import response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AggregatorImportData {
+    /// Number of newly imported transactions
+    pub created: usize,
+    /// Number of previously imported transactions that changed at the aggregator
+    pub updated: usize,
+    /// Number of transactions already imported unchanged, pending or repeated in the request
+    pub skipped: usize,
+    /// Number of transactions that could not be imported
+    pub failed: usize,
+    /// Outcome of matching each external account
+    pub accounts: Vec<AggregatorAccountResult>,
+    /// Errors for failed transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<AggregatorImportError>>,
+}
+
+/// Outcome of matching an external account to a local account
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AggregatorAccountResult {
+    pub external_account_id: String,
+    /// Local account the external account maps to
+    pub account_id: Option<Uuid>,
+    pub matched_by: Option<AccountMatch>,
+    /// Why the account's transactions cannot be imported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Error information for a transaction that failed to import
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AggregatorImportError {
+    /// Aggregator transaction ID
+    pub transaction_id: String,
+    /// Error message
+    pub error: String,
+}
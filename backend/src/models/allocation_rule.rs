@@ -0,0 +1,222 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, RoundingMode, Signed};
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::{allocation_rule_destinations, allocation_rules};
+
+/// Rule that splits income landing in a source account across other accounts
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = allocation_rules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AllocationRule {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub source_account_id: Uuid,
+    /// Only income in this category is allocated; `None` matches all income
+    pub category_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = allocation_rules)]
+pub struct NewAllocationRule {
+    pub user_id: Uuid,
+    pub name: String,
+    pub source_account_id: Uuid,
+    pub category_id: Option<Uuid>,
+}
+
+/// Share of an allocation rule's income transferred to one account
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = allocation_rule_destinations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AllocationRuleDestination {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub account_id: Uuid,
+    /// Percentage of the income, with at most two decimal places
+    pub percentage: BigDecimal,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = allocation_rule_destinations)]
+pub struct NewAllocationRuleDestination {
+    pub rule_id: Uuid,
+    pub account_id: Uuid,
+    pub percentage: BigDecimal,
+}
+
+/// Divide `total` across destination accounts by percentage
+///
+/// `destinations` pairs each account with its percentage; the percentages add up
+/// to 100. Shares are rounded towards zero to `minor_units` decimal places, and
+/// the minor units left over go one at a time to destinations in ascending
+/// account ID order, so the shares add up exactly to the rounded total and do
+/// not depend on the order the destinations are given in.
+///
+/// Shares are returned in ascending account ID order.
+pub fn allocate(
+    total: &BigDecimal,
+    destinations: &[(Uuid, BigDecimal)],
+    minor_units: i64,
+) -> Vec<(Uuid, BigDecimal)> {
+    let mut ordered = destinations.to_vec();
+    ordered.sort_by_key(|(account_id, _)| *account_id);
+
+    let (total_units, _) = total
+        .with_scale_round(minor_units, RoundingMode::HalfEven)
+        .into_bigint_and_exponent();
+    // Percentages in hundredths of a percent, so the arithmetic stays in integers
+    let basis_points = |percentage: &BigDecimal| {
+        percentage
+            .with_scale_round(2, RoundingMode::HalfEven)
+            .into_bigint_and_exponent()
+            .0
+    };
+
+    // Integer division truncates towards zero, so the remainder has the sign of the total
+    let shares: Vec<(Uuid, BigInt)> = ordered
+        .iter()
+        .map(|(account_id, percentage)| {
+            (
+                *account_id,
+                &total_units * basis_points(percentage) / BigInt::from(10_000),
+            )
+        })
+        .collect();
+    let allocated: BigInt = shares.iter().map(|(_, units)| units).sum();
+    let remainder = &total_units - allocated;
+    let leftover = remainder.abs();
+    let step = remainder.signum();
+
+    shares
+        .into_iter()
+        .enumerate()
+        .map(|(index, (account_id, units))| {
+            let units = if BigInt::from(index) < leftover {
+                units + &step
+            } else {
+                units
+            };
+            (account_id, BigDecimal::new(units, minor_units))
+        })
+        .collect()
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_create_allocation_rule_request"))]
+pub struct CreateAllocationRuleRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Account whose incoming transactions are allocated
+    pub source_account_id: Uuid,
+
+    /// Only allocate income in this category (all income when omitted)
+    pub category_id: Option<Uuid>,
+
+    /// Accounts receiving a share of the income; percentages must add up to 100
+    #[validate(nested)]
+    pub destinations: Vec<AllocationDestinationInput>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AllocationDestinationInput {
+    pub account_id: Uuid,
+
+    /// Percentage of the income, e.g. `25` or `33.33`
+    #[validate(range(
+        exclusive_min = 0.0,
+        max = 100.0,
+        message = "Percentage must be greater than 0 and at most 100"
+    ))]
+    pub percentage: f64,
+}
+
+/// Convert a requested percentage to a decimal with at most two decimal places
+pub fn percentage_to_decimal(percentage: f64) -> Option<BigDecimal> {
+    let decimal = BigDecimal::from_str(&percentage.to_string()).ok()?;
+    (decimal.with_scale(2) == decimal).then_some(decimal)
+}
+
+fn validate_create_allocation_rule_request(
+    req: &CreateAllocationRuleRequest,
+) -> Result<(), validator::ValidationError> {
+    let mut accounts = HashSet::new();
+    let mut total = BigDecimal::from(0);
+    for destination in &req.destinations {
+        if destination.account_id == req.source_account_id {
+            let mut error = validator::ValidationError::new("destination_is_source");
+            error.message = Some("The source account cannot be a destination".into());
+            return Err(error);
+        }
+        if !accounts.insert(destination.account_id) {
+            let mut error = validator::ValidationError::new("duplicate_destination");
+            error.message = Some("Each destination account may only be listed once".into());
+            return Err(error);
+        }
+        let Some(percentage) = percentage_to_decimal(destination.percentage) else {
+            let mut error = validator::ValidationError::new("percentage_precision");
+            error.message = Some("Percentages may have at most two decimal places".into());
+            return Err(error);
+        };
+        total += percentage;
+    }
+
+    if total != 100 {
+        let mut error = validator::ValidationError::new("percentages_sum");
+        error.message = Some("Destination percentages must add up to 100".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AllocationRuleResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub source_account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub destinations: Vec<AllocationDestinationResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AllocationDestinationResponse {
+    pub account_id: Uuid,
+    /// Percentage of the income as a string, e.g. `"33.33"`
+    pub percentage: String,
+}
+
+impl AllocationRuleResponse {
+    pub fn new(rule: AllocationRule, destinations: Vec<AllocationRuleDestination>) -> Self {
+        Self {
+            id: rule.id,
+            name: rule.name,
+            source_account_id: rule.source_account_id,
+            category_id: rule.category_id,
+            destinations: destinations
+                .into_iter()
+                .map(|destination| AllocationDestinationResponse {
+                    account_id: destination.account_id,
+                    percentage: destination.percentage.with_scale(2).to_string(),
+                })
+                .collect(),
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
@@ -24,6 +24,8 @@ pub struct ApiKey {
     pub last_used_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Requests allowed per minute, or `None` for no limit
+    pub requests_per_minute: Option<i32>,
 }
 
 /// Model for inserting new API keys
@@ -37,6 +39,7 @@ pub struct NewApiKey {
     pub scopes: JsonValue,
     pub status: ApiKeyStatus,
     pub expires_at: Option<DateTime<Utc>>,
+    pub requests_per_minute: Option<i32>,
 }
 
 /// Scope permissions for API keys
@@ -116,6 +119,9 @@ pub struct CreateApiKeyRequest {
     pub name: String,
     pub scopes: ApiKeyScopes,
     pub expires_in_days: Option<i64>, // null means never expires
+    #[validate(range(min = 1, max = 10000))]
+    #[serde(default)]
+    pub requests_per_minute: Option<i32>, // null means no rate limit
 }
 
 /// Request to update an existing API key
@@ -125,6 +131,8 @@ pub struct UpdateApiKeyRequest {
     pub name: Option<String>,
     pub expires_in_days: Option<i64>,
     pub scopes: Option<ApiKeyScopes>,
+    #[validate(range(min = 1, max = 10000))]
+    pub requests_per_minute: Option<i32>,
 }
 
 // Response DTOs
@@ -139,6 +147,7 @@ pub struct CreateApiKeyResponse {
     pub scopes: ApiKeyScopes,
     pub status: ApiKeyStatus,
     pub expires_at: Option<DateTime<Utc>>,
+    pub requests_per_minute: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -151,6 +160,7 @@ pub struct ApiKeyResponse {
     pub scopes: ApiKeyScopes,
     pub status: ApiKeyStatus,
     pub expires_at: Option<DateTime<Utc>>,
+    pub requests_per_minute: Option<i32>,
     pub last_used_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -168,6 +178,7 @@ impl ApiKeyResponse {
             scopes,
             status: api_key.status,
             expires_at: api_key.expires_at,
+            requests_per_minute: api_key.requests_per_minute,
             last_used_at: api_key.last_used_at,
             created_at: api_key.created_at,
             updated_at: api_key.updated_at,
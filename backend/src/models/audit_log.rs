@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::ResourceType;
+use crate::schema::audit_log;
+
+/// Kind of entity an audit entry describes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityType {
+    Account,
+    Transaction,
+    Budget,
+    Category,
+    Person,
+}
+
+impl AuditEntityType {
+    pub const ALL: [AuditEntityType; 5] = [
+        AuditEntityType::Account,
+        AuditEntityType::Transaction,
+        AuditEntityType::Budget,
+        AuditEntityType::Category,
+        AuditEntityType::Person,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntityType::Account => "account",
+            AuditEntityType::Transaction => "transaction",
+            AuditEntityType::Budget => "budget",
+            AuditEntityType::Category => "category",
+            AuditEntityType::Person => "person",
+        }
+    }
+
+    /// API key scope needed to read entries about this kind of entity
+    pub fn resource_type(&self) -> ResourceType {
+        match self {
+            AuditEntityType::Account => ResourceType::Accounts,
+            AuditEntityType::Transaction => ResourceType::Transactions,
+            AuditEntityType::Budget => ResourceType::Budgets,
+            AuditEntityType::Category => ResourceType::Categories,
+            AuditEntityType::Person => ResourceType::People,
+        }
+    }
+}
+
+impl std::str::FromStr for AuditEntityType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|entity_type| entity_type.as_str() == s)
+            .ok_or_else(|| format!("Unknown audit entity type: {}", s))
+    }
+}
+
+/// Kind of change an audit entry records
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "CREATE",
+            AuditAction::Update => "UPDATE",
+            AuditAction::Delete => "DELETE",
+        }
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CREATE" => Ok(AuditAction::Create),
+            "UPDATE" => Ok(AuditAction::Update),
+            "DELETE" => Ok(AuditAction::Delete),
+            _ => Err(format!("Unknown audit action: {}", s)),
+        }
+    }
+}
+
+/// An entry of a user's audit log
+///
+/// Each user's entries form a hash chain: `hash` is the SHA-256 of the entry's
+/// fields and the `previous_hash` of the entry before it (see
+/// [`AuditLogEntry::compute_hash`]), so changing or removing an entry breaks
+/// every later hash.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = audit_log)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub sequence: i64,
+    pub user_id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub action: String,
+    pub changes: serde_json::Value,
+    pub previous_hash: Option<String>,
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewAuditLogEntry {
+    pub user_id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub action: String,
+    pub changes: serde_json::Value,
+    pub previous_hash: Option<String>,
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditLogEntry {
+    /// Hex SHA-256 chaining an entry to the one before it
+    ///
+    /// Hashes `previous_hash` (empty for a user's first entry), user ID,
+    /// entity type, entity ID, action, the compact JSON of `changes` (keys
+    /// sorted) and `created_at` in RFC 3339 with microseconds, joined by `|`.
+    pub fn compute_hash(
+        previous_hash: Option<&str>,
+        user_id: Uuid,
+        entity_type: &str,
+        entity_id: Uuid,
+        action: &str,
+        changes: &serde_json::Value,
+        created_at: DateTime<Utc>,
+    ) -> String {
+        use sha2::{Digest, Sha256};
+
+        let content = format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            previous_hash.unwrap_or_default(),
+            user_id,
+            entity_type,
+            entity_id,
+            action,
+            changes,
+            created_at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+        );
+        Sha256::digest(content.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Whether `hash` matches the entry's fields and `previous_hash`
+    pub fn hash_is_valid(&self) -> bool {
+        self.hash
+            == Self::compute_hash(
+                self.previous_hash.as_deref(),
+                self.user_id,
+                &self.entity_type,
+                self.entity_id,
+                &self.action,
+                &self.changes,
+                self.created_at,
+            )
+    }
+}
+
+// Query DTOs
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogQuery {
+    /// Only entries about this kind of entity
+    pub entity_type: Option<AuditEntityType>,
+    /// Only entries about this entity
+    pub entity_id: Option<Uuid>,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub sequence: i64,
+    pub entity_type: AuditEntityType,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    /// Changed fields, each as `{"old": ..., "new": ...}`; creations have only
+    /// new values and deletions only old ones
+    #[schema(value_type = Object)]
+    pub changes: serde_json::Value,
+    pub previous_hash: Option<String>,
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLogEntry> for AuditLogResponse {
+    fn from(entry: AuditLogEntry) -> Self {
+        Self {
+            id: entry.id,
+            sequence: entry.sequence,
+            // The check constraints guarantee known values; fall back defensively
+            entity_type: entry
+                .entity_type
+                .parse()
+                .unwrap_or(AuditEntityType::Transaction),
+            entity_id: entry.entity_id,
+            action: entry.action.parse().unwrap_or(AuditAction::Update),
+            changes: entry.changes,
+            previous_hash: entry.previous_hash,
+            hash: entry.hash,
+            created_at: entry.created_at,
+        }
+    }
+}
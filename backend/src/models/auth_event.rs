@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::auth_events;
+
+/// Authentication lifecycle event type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEventType {
+    Register,
+    Login,
+    LoginFailed,
+    Logout,
+    TokenRefresh,
+    PasswordChanged,
+    TwoFactorEnabled,
+}
+
+impl AuthEventType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AuthEventType::Register => "register",
+            AuthEventType::Login => "login",
+            AuthEventType::LoginFailed => "login_failed",
+            AuthEventType::Logout => "logout",
+            AuthEventType::TokenRefresh => "token_refresh",
+            AuthEventType::PasswordChanged => "password_changed",
+            AuthEventType::TwoFactorEnabled => "two_factor_enabled",
+        }
+    }
+}
+
+impl std::str::FromStr for AuthEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "register" => Ok(AuthEventType::Register),
+            "login" => Ok(AuthEventType::Login),
+            "login_failed" => Ok(AuthEventType::LoginFailed),
+            "logout" => Ok(AuthEventType::Logout),
+            "token_refresh" => Ok(AuthEventType::TokenRefresh),
+            "password_changed" => Ok(AuthEventType::PasswordChanged),
+            "two_factor_enabled" => Ok(AuthEventType::TwoFactorEnabled),
+            _ => Err(format!("Unknown auth event type: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = auth_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuthEvent {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub event_type: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = auth_events)]
+pub struct NewAuthEvent {
+    pub user_id: Option<Uuid>,
+    pub event_type: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// Query DTOs
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuthEventQuery {
+    /// Maximum number of events to return, newest first (default: 50)
+    #[validate(range(min = 1, max = 200))]
+    pub limit: Option<i64>,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthEventResponse {
+    pub id: Uuid,
+    pub event_type: AuthEventType,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuthEvent> for AuthEventResponse {
+    fn from(event: AuthEvent) -> Self {
+        Self {
+            id: event.id,
+            event_type: event.event_type(),
+            ip_address: event.ip_address,
+            user_agent: event.user_agent,
+            created_at: event.created_at,
+        }
+    }
+}
+
+impl AuthEvent {
+    pub fn event_type(&self) -> AuthEventType {
+        // The check constraint guarantees a known value; fall back defensively
+        self.event_type.parse().unwrap_or(AuthEventType::Login)
+    }
+}
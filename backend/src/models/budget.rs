@@ -1,7 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::schema::budgets;
@@ -16,6 +17,22 @@ pub struct Budget {
     pub filters: JsonValue,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub alerts_muted: bool,
+    pub alerts_snoozed_until: Option<DateTime<Utc>>,
+    /// Incremented on every update, for optimistic concurrency control
+    pub version: i32,
+    /// Fractions of the limit at which an alert is recorded, ascending
+    pub alert_thresholds: Vec<f64>,
+    /// When the category the budget filtered on was deleted; the budget has no
+    /// status until its filters are updated
+    pub deactivated_at: Option<DateTime<Utc>>,
+}
+
+impl Budget {
+    /// Whether the budget's alerts are muted, or snoozed past `now`
+    pub fn alerts_suppressed(&self, now: DateTime<Utc>) -> bool {
+        self.alerts_muted || self.alerts_snoozed_until.is_some_and(|until| until > now)
+    }
 }
 
 #[derive(Debug, Insertable)]
@@ -24,6 +41,7 @@ pub struct NewBudget {
     pub user_id: Uuid,
     pub name: String,
     pub filters: JsonValue,
+    pub alert_thresholds: Vec<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,30 +54,128 @@ pub struct CreateBudget {
 pub struct UpdateBudget {
     pub name: Option<String>,
     pub filters: Option<JsonValue>,
+    pub alert_thresholds: Option<Vec<f64>>,
+    /// Apply the update only if the budget is still at this version
+    pub expected_version: Option<i32>,
 }
 
 // Request DTOs
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct CreateBudgetRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub filters: JsonValue,
+    /// Fractions of the limit to be alerted at, e.g. `[0.8, 1.0]` (default: none)
+    #[serde(default)]
+    #[validate(custom(function = "validate_alert_thresholds"))]
+    pub alert_thresholds: Vec<f64>,
 }
 
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct UpdateBudgetRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
     pub filters: Option<JsonValue>,
+    /// Replaces the alert thresholds; `[]` turns alerts off
+    #[validate(custom(function = "validate_alert_thresholds"))]
+    pub alert_thresholds: Option<Vec<f64>>,
+    /// Version the client last saw; when given (here or as `If-Match`), the update
+    /// fails with 409 if the budget has changed since
+    pub version: Option<i32>,
+}
+
+/// Most alert thresholds a budget may have
+pub const MAX_ALERT_THRESHOLDS: usize = 10;
+
+fn validate_alert_thresholds(thresholds: &[f64]) -> Result<(), validator::ValidationError> {
+    if thresholds.len() > MAX_ALERT_THRESHOLDS {
+        let mut error = validator::ValidationError::new("too_many_thresholds");
+        error.message = Some(format!("At most {} alert thresholds", MAX_ALERT_THRESHOLDS).into());
+        return Err(error);
+    }
+    if thresholds
+        .iter()
+        .any(|threshold| !(*threshold > 0.0 && *threshold <= 10.0))
+    {
+        let mut error = validator::ValidationError::new("threshold_range");
+        error.message = Some("Alert thresholds must be above 0 and at most 10".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MuteBudgetRequest {
+    /// Mute (`true`) or unmute (`false`) the budget's alerts
+    pub muted: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SnoozeBudgetRequest {
+    /// Suppress the budget's alerts until this time; `null` ends the snooze
+    pub until: Option<DateTime<Utc>>,
 }
 
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BudgetResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub name: String,
     pub filters: JsonValue,
+    pub alerts_muted: bool,
+    /// Fractions of the limit at which an alert is recorded, ascending
+    pub alert_thresholds: Vec<f64>,
+    /// End of the current snooze; omitted once it has passed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts_snoozed_until: Option<DateTime<Utc>>,
+    /// Current version, to send back with updates
+    pub version: i32,
+    /// When the category the budget filtered on was deleted, leaving it without
+    /// a status until its `filters` are updated; omitted for active budgets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Spending in the current period; only included when requested with `?include_status=true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<BudgetStatus>,
+}
+
+/// Budget status information
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetStatus {
+    pub budget_id: Uuid,
+    /// The range the status is for
+    pub range_id: Uuid,
+    pub current_spending: String,
+    pub limit_amount: String,
+    pub percentage_used: f64,
+    pub is_over_budget: bool,
+    /// The budget's alerts are muted or snoozed, so over-budget warnings should not be shown
+    pub alerts_suppressed: bool,
+    /// First day of the range the status is for
+    pub range_start: NaiveDate,
+    /// Last day of the range the status is for, `None` if open-ended
+    pub range_end: Option<NaiveDate>,
+}
+
+/// Query parameters for a budget's status
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BudgetStatusQuery {
+    /// Day whose range to report on, as `YYYY-MM-DD` (default: today)
+    pub as_of: Option<NaiveDate>,
+}
+
+/// Query parameters for listing budgets
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BudgetListQuery {
+    /// Include each budget's status for the current period (deactivated budgets
+    /// and budgets without an active range get none)
+    #[serde(default)]
+    pub include_status: bool,
 }
 
 impl From<Budget> for BudgetResponse {
@@ -69,6 +185,17 @@ impl From<Budget> for BudgetResponse {
             user_id: budget.user_id,
             name: budget.name,
             filters: budget.filters,
+            alerts_muted: budget.alerts_muted,
+            alert_thresholds: budget.alert_thresholds,
+            // Snoozes expire on their own
+            alerts_snoozed_until: budget
+                .alerts_snoozed_until
+                .filter(|until| *until > Utc::now()),
+            version: budget.version,
+            deactivated_at: budget.deactivated_at,
+            created_at: budget.created_at,
+            updated_at: budget.updated_at,
+            status: None,
         }
     }
 }
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::schema::budget_alerts;
+
+/// A budget threshold crossed within one of the budget's ranges
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = budget_alerts)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BudgetAlert {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub budget_id: Uuid,
+    pub budget_range_id: Uuid,
+    pub threshold: f64,
+    pub percentage_used: f64,
+    pub triggered_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = budget_alerts)]
+pub struct NewBudgetAlert {
+    pub user_id: Uuid,
+    pub budget_id: Uuid,
+    pub budget_range_id: Uuid,
+    pub threshold: f64,
+    pub percentage_used: f64,
+}
+
+/// Query parameters for listing budget alerts
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BudgetAlertQuery {
+    /// Only list alerts that have not been acknowledged
+    #[serde(default)]
+    pub unread: bool,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BudgetAlertResponse {
+    pub id: Uuid,
+    pub budget_id: Uuid,
+    pub budget_name: String,
+    /// The range whose spending crossed the threshold
+    pub budget_range_id: Uuid,
+    /// Fraction of the limit that was crossed, e.g. `0.8`
+    pub threshold: f64,
+    /// Share of the limit spent when the crossing was detected, in percent
+    pub percentage_used: f64,
+    pub triggered_at: DateTime<Utc>,
+    /// When the alert was acknowledged; `None` while unread
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl BudgetAlertResponse {
+    pub fn new(alert: BudgetAlert, budget_name: String) -> Self {
+        Self {
+            id: alert.id,
+            budget_id: alert.budget_id,
+            budget_name,
+            budget_range_id: alert.budget_range_id,
+            threshold: alert.threshold,
+            percentage_used: alert.percentage_used,
+            triggered_at: alert.triggered_at,
+            read_at: alert.read_at,
+        }
+    }
+}
@@ -2,6 +2,7 @@ use bigdecimal::BigDecimal;
 use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::schema::budget_ranges;
@@ -48,7 +49,7 @@ pub struct UpdateBudgetRange {
 }
 
 // Request DTOs
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct CreateBudgetRangeRequest {
     #[validate(range(min = 0.01))]
     pub limit_amount: f64,
@@ -67,7 +68,7 @@ pub struct UpdateBudgetRangeRequest {
 }
 
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BudgetRangeResponse {
     pub id: Uuid,
     pub budget_id: Uuid,
@@ -1,26 +1,27 @@
 //! Bulk transaction operations models
 
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::{CreateTransactionRequest, TransactionResponse};
+use super::{CreateTransactionRequest, TransactionResponse, UpdateTransactionRequest};
 
 /// Request for bulk create transactions
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BulkCreateRequest {
     pub account_id: Uuid,
     pub transactions: Vec<CreateTransactionRequest>,
 }
 
 /// Response from bulk create endpoint
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BulkCreateResponse {
     pub success: bool,
     pub data: BulkCreateData,
 }
 
 /// Data payload for bulk create response
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BulkCreateData {
     /// Number of successfully created transactions
     pub created: usize,
@@ -34,10 +35,84 @@ pub struct BulkCreateData {
 }
 
 /// Error information for a failed transaction in bulk create
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BulkCreateError {
     /// Index of the transaction in the request array
     pub index: usize,
     /// Error message
     pub error: String,
 }
+
+/// Request for creating, updating and deleting transactions in one go
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkTransactionRequest {
+    /// Transactions to create; splits are not supported
+    #[serde(default)]
+    pub create: Vec<CreateTransactionRequest>,
+    /// Transactions to update; splits are not supported
+    #[serde(default)]
+    pub update: Vec<BulkUpdateItem>,
+    /// IDs of transactions to delete; deleting either leg of a transfer deletes both
+    #[serde(default)]
+    pub delete: Vec<Uuid>,
+}
+
+/// Update of one transaction in a bulk request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkUpdateItem {
+    /// Transaction to update
+    pub id: Uuid,
+    /// Fields to update, as for a single update
+    #[serde(flatten)]
+    pub changes: UpdateTransactionRequest,
+}
+
+/// Operation of an item in a bulk request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Outcome of an item in a bulk request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Created,
+    Updated,
+    Deleted,
+    /// The item is invalid; nothing in the request was applied
+    Failed,
+    /// The item is valid but was not applied because another item failed
+    Skipped,
+}
+
+/// Response from the bulk transaction endpoint
+///
+/// Either every item was applied (`success` is true) or none was.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkTransactionResponse {
+    pub success: bool,
+    /// One result per item, creates first, then updates, then deletes
+    pub results: Vec<BulkItemResult>,
+}
+
+/// Result of an item in a bulk request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkItemResult {
+    pub operation: BulkOperation,
+    /// Index of the item in its operation's array
+    pub index: usize,
+    pub status: BulkItemStatus,
+    /// Created, updated or deleted transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<Uuid>,
+    /// Created or updated transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionResponse>,
+    /// Why the item failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
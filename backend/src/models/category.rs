@@ -29,6 +29,34 @@ pub struct NewCategory {
     pub parent_id: Option<Uuid>,
 }
 
+/// Categories seeded for new users: name, icon and color
+const DEFAULT_CATEGORIES: [(&str, &str, &str); 10] = [
+    ("Groceries", "🛒", "#4CAF50"),
+    ("Dining", "🍽️", "#FF9800"),
+    ("Transport", "🚌", "#2196F3"),
+    ("Rent", "🏠", "#795548"),
+    ("Utilities", "💡", "#FFC107"),
+    ("Health", "🩺", "#E91E63"),
+    ("Entertainment", "🎬", "#9C27B0"),
+    ("Shopping", "🛍️", "#3F51B5"),
+    ("Salary", "💰", "#009688"),
+    ("Other Income", "💵", "#8BC34A"),
+];
+
+/// The standard set of categories new users start with, owned by `user_id`
+pub fn default_set(user_id: Uuid) -> Vec<NewCategory> {
+    DEFAULT_CATEGORIES
+        .iter()
+        .map(|(name, icon, color)| NewCategory {
+            user_id,
+            name: name.to_string(),
+            icon: Some(icon.to_string()),
+            color: Some(color.to_string()),
+            parent_id: None,
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateCategory {
     pub name: String,
@@ -45,6 +73,27 @@ pub struct UpdateCategory {
     pub parent_id: Option<Uuid>,
 }
 
+/// What referred to a deleted category and was moved to another one or
+/// uncategorized
+#[derive(Debug, Default)]
+pub struct CategoryReassignment {
+    /// Transactions changed, deleted ones aside
+    pub transaction_ids: Vec<Uuid>,
+    /// Budgets whose category filter was changed or cleared
+    pub budget_ids: Vec<Uuid>,
+    /// Budgets deactivated because their category was deleted without a
+    /// replacement, also listed in `budget_ids`
+    pub deactivated_budget_ids: Vec<Uuid>,
+}
+
+// Query DTOs
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// Category to move the deleted category's transactions to, instead of
+    /// leaving them uncategorized
+    pub reassign_to: Option<Uuid>,
+}
+
 // Request DTOs
 #[derive(Debug, Deserialize, validator::Validate)]
 pub struct CreateCategoryRequest {
@@ -67,7 +116,34 @@ pub struct UpdateCategoryRequest {
     pub color: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeCategoryRequest {
+    /// Category that takes over everything of the merged one
+    pub target_category_id: Uuid,
+}
+
 // Response DTOs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteCategoryResponse {
+    /// Category the transactions were moved to, `None` if they were uncategorized
+    pub reassigned_to: Option<Uuid>,
+    /// Number of transactions moved or uncategorized
+    pub affected_transactions: usize,
+    /// Number of budgets whose category filter was moved or cleared
+    pub affected_budgets: usize,
+    /// Number of those budgets deactivated because no `reassign_to` was given
+    pub deactivated_budgets: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeCategoryResponse {
+    pub target_category_id: Uuid,
+    /// Number of transactions moved to the target category
+    pub reassigned_transactions: usize,
+    /// Number of budgets now filtering on the target category
+    pub reassigned_budgets: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CategoryResponse {
     pub id: Uuid,
@@ -75,6 +151,8 @@ pub struct CategoryResponse {
     pub parent_id: Option<Uuid>,
     pub icon: Option<String>,
     pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl From<Category> for CategoryResponse {
@@ -85,6 +163,8 @@ impl From<Category> for CategoryResponse {
             parent_id: category.parent_id,
             icon: category.icon,
             color: category.color,
+            created_at: category.created_at,
+            updated_at: category.updated_at,
         }
     }
 }
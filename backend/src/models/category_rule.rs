@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::category_rules;
+use crate::types::CategoryRuleMatchType;
+
+/// Rule assigning a category to transactions whose title matches a pattern
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = category_rules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CategoryRule {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub category_id: Uuid,
+    pub match_type: CategoryRuleMatchType,
+    pub pattern: String,
+    /// Rules are tried from the lowest priority up
+    pub priority: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = category_rules)]
+pub struct NewCategoryRule {
+    pub user_id: Uuid,
+    pub category_id: Uuid,
+    pub match_type: CategoryRuleMatchType,
+    pub pattern: String,
+    pub priority: i32,
+}
+
+/// Values of a category rule after an update
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = category_rules)]
+pub struct UpdateCategoryRule {
+    pub category_id: Uuid,
+    pub match_type: CategoryRuleMatchType,
+    pub pattern: String,
+    pub priority: i32,
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCategoryRuleRequest {
+    /// Category assigned to matching transactions
+    pub category_id: Uuid,
+
+    pub match_type: CategoryRuleMatchType,
+
+    /// Text or regular expression matched against titles, ignoring case
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Pattern must be between 1 and 255 characters"
+    ))]
+    pub pattern: String,
+
+    /// Rules are tried from the lowest priority up (default: 0); equal
+    /// priorities are tried oldest first
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Changes to a category rule; omitted fields are kept
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateCategoryRuleRequest {
+    pub category_id: Option<Uuid>,
+
+    pub match_type: Option<CategoryRuleMatchType>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Pattern must be between 1 and 255 characters"
+    ))]
+    pub pattern: Option<String>,
+
+    pub priority: Option<i32>,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryRuleResponse {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub match_type: CategoryRuleMatchType,
+    pub pattern: String,
+    pub priority: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CategoryRule> for CategoryRuleResponse {
+    fn from(rule: CategoryRule) -> Self {
+        Self {
+            id: rule.id,
+            category_id: rule.category_id,
+            match_type: rule.match_type,
+            pattern: rule.pattern,
+            priority: rule.priority,
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
+
+/// Result of running the category rules over uncategorized transactions
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApplyCategoryRulesResponse {
+    /// Number of transactions that were given a category
+    pub updated: usize,
+}
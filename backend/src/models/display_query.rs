@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::types::Locale;
+
+/// Query parameters for formatting amounts for display
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DisplayQuery {
+    /// Also return amounts formatted for this locale: `en-US`, `en-GB`, `de-DE`,
+    /// `fr-FR`, `it-IT` or `ja-JP` (default: the server's `DISPLAY_LOCALE`, if set)
+    pub locale: Option<Locale>,
+}
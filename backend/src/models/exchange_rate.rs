@@ -1,11 +1,70 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::schema::{custom_exchange_rates, exchange_rates};
+use crate::types::CurrencyCode;
+
+/// Rate from one currency to another on a day, fetched from a provider
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = exchange_rates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ExchangeRateRecord {
+    pub base_currency: CurrencyCode,
+    pub quote_currency: CurrencyCode,
+    pub rate_date: NaiveDate,
+    pub rate: BigDecimal,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = exchange_rates)]
+pub struct NewExchangeRateRecord {
+    pub base_currency: CurrencyCode,
+    pub quote_currency: CurrencyCode,
+    pub rate_date: NaiveDate,
+    pub rate: BigDecimal,
+}
+
+/// Rate from one currency to another on a day, set by hand by a user
+///
+/// Only used for that user's conversions, where it takes precedence over the
+/// fetched rate for its pair and day.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = custom_exchange_rates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CustomExchangeRate {
+    pub user_id: Uuid,
+    pub base_currency: CurrencyCode,
+    pub quote_currency: CurrencyCode,
+    pub rate_date: NaiveDate,
+    pub rate: BigDecimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = custom_exchange_rates)]
+pub struct NewCustomExchangeRate {
+    pub user_id: Uuid,
+    pub base_currency: CurrencyCode,
+    pub quote_currency: CurrencyCode,
+    pub rate_date: NaiveDate,
+    pub rate: BigDecimal,
+}
 
 /// Query parameters for exchange rates endpoint
 #[derive(Debug, Deserialize)]
 pub struct ExchangeRateQuery {
     /// Base currency code (defaults to user's primary currency or EUR)
-    pub base: Option<crate::types::CurrencyCode>,
+    pub base: Option<CurrencyCode>,
+    /// Quote currency code; when given, only the rate from `base` to it is returned
+    pub quote: Option<CurrencyCode>,
+    /// Day to get the rate of (defaults to today); requires `quote`
+    pub date: Option<NaiveDate>,
 }
 
 /// Response structure for exchange rates API
@@ -18,3 +77,88 @@ pub struct ExchangeRateResponse {
     /// Map of currency codes to their exchange rates
     pub conversion_rates: HashMap<String, String>,
 }
+
+/// Query parameters for the currency conversion preview endpoint
+#[derive(Debug, Deserialize)]
+pub struct ConvertQuery {
+    /// Currency the amount is given in
+    pub from: CurrencyCode,
+    /// Currency to convert to
+    pub to: CurrencyCode,
+    /// Amount to convert; must be positive
+    pub amount: BigDecimal,
+}
+
+/// Response structure for the currency conversion preview endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConversionResponse {
+    pub from: CurrencyCode,
+    pub to: CurrencyCode,
+    /// Amount as requested
+    pub amount: String,
+    /// Converted amount, rounded to the target currency's decimal places
+    pub converted_amount: String,
+    /// Rate from `from` to `to`
+    pub rate: String,
+    /// When the rate was fetched; `null` when both currencies are the same
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+/// Response structure for a single rate on a day
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoricalRateResponse {
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    /// Day the rate was requested for
+    pub date: NaiveDate,
+    /// Day the rate is for; earlier than `date` when no rate was found for that day
+    pub rate_date: NaiveDate,
+    /// Rate from `base` to `quote`
+    pub rate: String,
+    /// When the rate was fetched; `null` when both currencies are the same
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+/// Request body for setting a manual rate
+#[derive(Debug, Deserialize)]
+pub struct CustomRateRequest {
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    /// Rate from `base` to `quote`; must be positive
+    pub rate: BigDecimal,
+    /// Day the rate is for (defaults to today)
+    pub date: Option<NaiveDate>,
+}
+
+/// Query parameters for removing a manual rate
+#[derive(Debug, Deserialize)]
+pub struct CustomRateQuery {
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    /// Day of the rate to remove (defaults to today)
+    pub date: Option<NaiveDate>,
+}
+
+/// Response structure for a manual rate
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomRateResponse {
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    pub date: NaiveDate,
+    /// Rate from `base` to `quote`
+    pub rate: String,
+    /// When the rate was set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CustomExchangeRate> for CustomRateResponse {
+    fn from(record: CustomExchangeRate) -> Self {
+        Self {
+            base: record.base_currency,
+            quote: record.quote_currency,
+            date: record.rate_date,
+            rate: record.rate.to_string(),
+            updated_at: record.updated_at,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::schema::idempotency_keys;
+
+/// Kind of resource an idempotency key creates
+///
+/// Keys are scoped per user and kind, so the same key may be used for an
+/// account and a transaction without clashing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdempotencyScope {
+    Account,
+    Transaction,
+    Transfer,
+    BulkTransactions,
+    BulkCreateTransactions,
+}
+
+impl IdempotencyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdempotencyScope::Account => "account",
+            IdempotencyScope::Transaction => "transaction",
+            IdempotencyScope::Transfer => "transfer",
+            IdempotencyScope::BulkTransactions => "bulk_transactions",
+            IdempotencyScope::BulkCreateTransactions => "bulk_create_transactions",
+        }
+    }
+}
+
+/// Outcome of a request sent with an `Idempotency-Key`
+///
+/// Records a hash of the request and, once it completes, its response.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IdempotencyKey {
+    pub user_id: Uuid,
+    pub scope: String,
+    pub key: String,
+    pub created_at: DateTime<Utc>,
+    pub request_hash: Option<String>,
+    /// `None` while the first request is still in progress
+    pub response_status: Option<i16>,
+    pub response_body: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = idempotency_keys)]
+pub struct NewIdempotencyKey {
+    pub user_id: Uuid,
+    pub scope: String,
+    pub key: String,
+    pub request_hash: Option<String>,
+}
@@ -1,8 +1,9 @@
-//! Import-related data models for CSV parsing
+//! Import-related data models for statement parsing and imports
 
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::types::ConfidenceLevel;
@@ -24,6 +25,14 @@ pub struct ParsedTransaction {
     pub original_currency: Option<String>,
     /// Original amount before currency conversion
     pub original_amount: Option<BigDecimal>,
+    /// Category name given in the statement
+    pub category_name: Option<String>,
+    /// Account name given in the statement
+    pub account_name: Option<String>,
+    /// Category resolved from `category_name`
+    pub category_id: Option<Uuid>,
+    /// Account resolved from `account_name` (`None` means the import's account)
+    pub account_id: Option<Uuid>,
     /// Whether the transaction passed validation
     pub is_valid: bool,
     /// Validation error messages if any
@@ -78,3 +87,166 @@ pub struct ParseData {
     pub transactions: Vec<ParsedTransaction>,
     pub summary: ImportSummary,
 }
+
+/// What a streamed CSV import does when a batch fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportErrorMode {
+    /// Stop at the first failed batch, keeping the batches saved before it
+    #[default]
+    Abort,
+    /// Skip failed batches and import the rest of the file
+    Continue,
+}
+
+impl FromStr for ImportErrorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "abort" => Ok(Self::Abort),
+            "continue" => Ok(Self::Continue),
+            other => Err(format!(
+                "invalid on_error '{}'; expected one of abort, continue",
+                other
+            )),
+        }
+    }
+}
+
+/// Response from the streamed CSV import endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CsvImportResponse {
+    pub success: bool,
+    pub data: CsvImportData,
+}
+
+/// Data payload for the streamed CSV import response
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CsvImportData {
+    /// Number of rows read from the file
+    pub total: usize,
+    /// Number of transactions saved
+    pub created: usize,
+    /// Number of rows in failed batches, none of which were saved
+    pub failed: usize,
+    /// Number of batches saved
+    pub batches: usize,
+    /// Number of rows not saved because they duplicate an existing transaction
+    pub skipped_duplicates: usize,
+    /// Whether the import stopped at a failed batch, leaving the rest of the file unread
+    pub aborted: bool,
+    /// Rows skipped as duplicates, and the transactions they duplicate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<Vec<CsvImportDuplicate>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<CsvImportBatchError>>,
+    /// What the import would save, for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<CsvImportPreview>,
+}
+
+/// A row of a streamed CSV import skipped as a duplicate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportDuplicate {
+    /// Line of the file the row was read from
+    pub line: usize,
+    pub title: String,
+    /// The existing transaction the row duplicates
+    pub transaction_id: Uuid,
+}
+
+/// Result of a dry-run CSV import, which saves nothing
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CsvImportPreview {
+    /// How the file's columns were mapped to transaction fields
+    pub columns: Vec<CsvColumnMapping>,
+    /// Transactions the import would create, in file order
+    pub transactions: Vec<ImportPreviewTransaction>,
+}
+
+/// A column of a CSV file and the transaction field read from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    /// Position of the column, starting at 0
+    pub index: usize,
+    /// Name of the column in the file's header
+    pub header: String,
+    /// Field read from the column, or `None` if the column is ignored
+    pub field: Option<String>,
+}
+
+/// A transaction a dry-run CSV import would create
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreviewTransaction {
+    /// Line of the file the transaction was read from
+    pub line: usize,
+    /// The transaction, with its account and category resolved
+    #[serde(flatten)]
+    pub transaction: ParsedTransaction,
+    /// How the transaction's category was chosen, if it has one
+    pub category_source: Option<ImportCategorySource>,
+}
+
+/// How an imported transaction's category was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportCategorySource {
+    /// Named in the file, matching an existing category
+    File,
+    /// Named in the file, and created by the import
+    Created,
+    /// Matched one of the user's category rules
+    Rule,
+}
+
+/// Query parameters of the streamed CSV import endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct CsvImportQuery {
+    /// Parse and check the file without saving anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Why a batch of a streamed CSV import was not saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportBatchError {
+    /// Position of the batch in the file, starting at 1
+    pub batch: usize,
+    /// Line number of the batch's first row
+    pub first_line: usize,
+    /// Line number of the batch's last row
+    pub last_line: usize,
+    /// Problems found in the batch, with the lines they occurred on
+    pub errors: Vec<String>,
+}
+
+/// Response from the OFX/QFX import endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OfxImportResponse {
+    pub success: bool,
+    pub data: OfxImportData,
+}
+
+/// Data payload for the OFX/QFX import response
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OfxImportData {
+    /// Number of transactions in the statement
+    pub total: usize,
+    /// Number of transactions saved
+    pub created: usize,
+    /// Number of transactions already imported, by their `FITID`
+    pub skipped: usize,
+    /// Number of transactions that failed validation and were not saved
+    pub failed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<OfxImportError>>,
+}
+
+/// Why a transaction of an OFX/QFX statement was not saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfxImportError {
+    /// The bank's ID of the transaction
+    pub fit_id: String,
+    pub error: String,
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::import_profiles;
+
+/// Date format of mapped CSV files that do not give one
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Named CSV column mapping, reused for statements from the same bank
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = import_profiles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ImportProfile {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// A serialized [`CsvMapping`]
+    pub mapping: JsonValue,
+    pub date_format: Option<String>,
+    /// An [`AmountSign`] as text
+    pub amount_sign: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = import_profiles)]
+pub struct NewImportProfile {
+    pub user_id: Uuid,
+    pub name: String,
+    pub mapping: JsonValue,
+    pub date_format: Option<String>,
+    pub amount_sign: String,
+}
+
+/// Values of an import profile after an update
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = import_profiles)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateImportProfile {
+    pub name: String,
+    pub mapping: JsonValue,
+    pub date_format: Option<String>,
+    pub amount_sign: String,
+}
+
+/// CSV header of the column each transaction field is read from
+///
+/// Headers are matched ignoring case and surrounding whitespace. Columns that
+/// are not mapped are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CsvMapping {
+    /// Date of the transaction
+    pub date: String,
+    /// Title of the transaction
+    pub description: String,
+    /// Signed amount, required with `negative_debits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    /// Money out, required with `debit_credit_columns`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debit: Option<String>,
+    /// Money in, required with `debit_credit_columns`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Category name, resolved like the `category` column of the default format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Account name, resolved like the `account` column of the default format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl CsvMapping {
+    /// Mapped fields and their headers, in field order
+    pub fn fields(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("date", Some(&self.date)),
+            ("description", Some(&self.description)),
+            ("amount", self.amount.as_ref()),
+            ("debit", self.debit.as_ref()),
+            ("credit", self.credit.as_ref()),
+            ("notes", self.notes.as_ref()),
+            ("category", self.category.as_ref()),
+            ("account", self.account.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, header)| header.map(|header| (field, header.as_str())))
+        .collect()
+    }
+}
+
+/// How a mapped CSV file tells money in from money out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountSign {
+    /// One amount column, negative for money out
+    #[default]
+    NegativeDebits,
+    /// Money out and money in are in separate columns, both positive
+    DebitCreditColumns,
+}
+
+impl AmountSign {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NegativeDebits => "negative_debits",
+            Self::DebitCreditColumns => "debit_credit_columns",
+        }
+    }
+}
+
+impl FromStr for AmountSign {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "negative_debits" => Ok(Self::NegativeDebits),
+            "debit_credit_columns" => Ok(Self::DebitCreditColumns),
+            other => Err(format!(
+                "invalid amount_sign '{}'; expected one of negative_debits, debit_credit_columns",
+                other
+            )),
+        }
+    }
+}
+
+/// How to read a CSV file whose columns differ from the default format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvFormat {
+    pub mapping: CsvMapping,
+    /// strftime format of the date column, [`DEFAULT_DATE_FORMAT`] if `None`
+    pub date_format: Option<String>,
+    pub amount_sign: AmountSign,
+}
+
+impl CsvFormat {
+    /// Format of the date column
+    pub fn date_format(&self) -> &str {
+        self.date_format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT)
+    }
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateImportProfileRequest {
+    /// Unique name of the profile, ignoring case
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+
+    pub mapping: CsvMapping,
+
+    /// strftime format of the date column (default: `%Y-%m-%d`); dates
+    /// without a time are read as midnight UTC
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Date format must be between 1 and 50 characters"
+    ))]
+    pub date_format: Option<String>,
+
+    #[serde(default)]
+    pub amount_sign: AmountSign,
+}
+
+/// Changes to an import profile; omitted fields are kept
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateImportProfileRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: Option<String>,
+
+    pub mapping: Option<CsvMapping>,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Date format must be between 1 and 50 characters"
+    ))]
+    pub date_format: Option<String>,
+
+    pub amount_sign: Option<AmountSign>,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportProfileResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub mapping: CsvMapping,
+    pub date_format: Option<String>,
+    pub amount_sign: AmountSign,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ImportProfileResponse {
+    /// The format saved in the profile
+    pub fn format(&self) -> CsvFormat {
+        CsvFormat {
+            mapping: self.mapping.clone(),
+            date_format: self.date_format.clone(),
+            amount_sign: self.amount_sign,
+        }
+    }
+}
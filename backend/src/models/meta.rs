@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::types::CurrencyCode;
+
+// Response DTOs
+/// A supported currency, for clients to offer in currency pickers
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyResponse {
+    pub code: CurrencyCode,
+    /// Number of decimal places amounts in the currency are shown with
+    pub minor_units: i64,
+}
+
+impl From<CurrencyCode> for CurrencyResponse {
+    fn from(code: CurrencyCode) -> Self {
+        Self {
+            code,
+            minor_units: code.minor_units(),
+        }
+    }
+}
@@ -1,86 +1,221 @@
 pub mod account;
+pub mod aggregator_import;
+pub mod allocation_rule;
 pub mod api_key;
+pub mod audit_log;
+pub mod auth_event;
 pub mod budget;
+pub mod budget_alert;
 pub mod budget_range;
 pub mod bulk_transaction;
 pub mod category;
+pub mod category_rule;
+pub mod display_query;
 pub mod exchange_rate;
+pub mod idempotency_key;
 pub mod import;
+pub mod import_profile;
+pub mod meta;
+pub mod notification;
+pub mod pagination;
 pub mod parser_error;
+pub mod password_reset_token;
 pub mod person;
 pub mod person_split_config;
+pub mod purge;
+pub mod reconciliation;
+pub mod recurring_transaction;
+pub mod refresh_token;
+pub mod reimbursement;
+pub mod split_group;
 pub mod split_provider;
 pub mod split_sync_record;
+pub mod sync_query;
+pub mod tag;
 pub mod transaction;
 pub mod transaction_split;
+pub mod transfer;
+pub mod two_factor;
 pub mod user;
+pub mod webhook_event;
 
 // Re-export base models
 pub use account::{Account, CreateAccount, UpdateAccount};
+pub use allocation_rule::{AllocationRule, AllocationRuleDestination};
 pub use api_key::ApiKey;
+pub use audit_log::{AuditAction, AuditEntityType, AuditLogEntry};
+pub use auth_event::{AuthEvent, AuthEventType};
 pub use budget::{Budget, CreateBudget, UpdateBudget};
+pub use budget_alert::BudgetAlert;
 pub use budget_range::{BudgetRange, CreateBudgetRange, UpdateBudgetRange};
-pub use category::{Category, CreateCategory, UpdateCategory};
+pub use category::{Category, CategoryReassignment, CreateCategory, UpdateCategory};
+pub use category_rule::{CategoryRule, UpdateCategoryRule};
+pub use exchange_rate::{CustomExchangeRate, ExchangeRateRecord};
+pub use idempotency_key::{IdempotencyKey, IdempotencyScope};
+pub use import_profile::{AmountSign, CsvFormat, CsvMapping, ImportProfile, UpdateImportProfile};
+pub use notification::{
+    Notification, NotificationChannelKind, NotificationEventType, NotificationPreference,
+};
+pub use password_reset_token::PasswordResetToken;
 pub use person::{CreatePerson, Person, UpdatePerson};
 pub use person_split_config::{PersonSplitConfig, UpdatePersonSplitConfig};
+pub use purge::PurgeCounts;
+pub use reconciliation::Reconciliation;
+pub use recurring_transaction::{
+    RecurrenceSchedule, RecurringTransaction, UpdateRecurringTransaction,
+};
+pub use refresh_token::RefreshToken;
+pub use split_group::{SplitGroup, SplitGroupMember};
 pub use split_provider::{SplitProvider, UpdateSplitProvider};
 pub use split_sync_record::{SplitSyncRecord, SyncStatus, UpdateSplitSyncRecord};
+pub use tag::{Tag, TagMode};
 pub use transaction::{CreateTransaction, Transaction, UpdateTransaction};
 pub use transaction_split::{CreateTransactionSplit, TransactionSplit, UpdateTransactionSplit};
+pub use two_factor::RecoveryCode;
 pub use user::{CreateUser, UpdateUser, User};
+pub use webhook_event::{WebhookEvent, WebhookOutcome};
 
 // Re-export New* structs for insertions
 pub use account::NewAccount;
+pub use allocation_rule::{NewAllocationRule, NewAllocationRuleDestination};
 pub use api_key::NewApiKey;
+pub use audit_log::NewAuditLogEntry;
+pub use auth_event::NewAuthEvent;
 pub use budget::NewBudget;
+pub use budget_alert::NewBudgetAlert;
 pub use budget_range::NewBudgetRange;
 pub use category::NewCategory;
+pub use category_rule::NewCategoryRule;
+pub use exchange_rate::{NewCustomExchangeRate, NewExchangeRateRecord};
+pub use idempotency_key::NewIdempotencyKey;
+pub use import_profile::NewImportProfile;
+pub use notification::{NewNotification, NewNotificationPreference};
+pub use password_reset_token::NewPasswordResetToken;
 pub use person::NewPerson;
 pub use person_split_config::NewPersonSplitConfig;
+pub use reconciliation::NewReconciliation;
+pub use recurring_transaction::NewRecurringTransaction;
+pub use refresh_token::NewRefreshToken;
+pub use split_group::{NewSplitGroup, NewSplitGroupMember};
 pub use split_provider::NewSplitProvider;
 pub use split_sync_record::NewSplitSyncRecord;
+pub use tag::{NewTag, NewTransactionTag};
 pub use transaction::NewTransaction;
 pub use transaction_split::NewTransactionSplit;
+pub use two_factor::NewRecoveryCode;
 pub use user::NewUser;
+pub use webhook_event::NewWebhookEvent;
 
 // Re-export Request DTOs
-pub use account::{CreateAccountRequest, UpdateAccountRequest};
+pub use account::{
+    AccountSummaryQuery, AmortizationQuery, BalanceHistoryQuery, CreateAccountRequest,
+    UpdateAccountRequest,
+};
+pub use allocation_rule::{AllocationDestinationInput, CreateAllocationRuleRequest};
 pub use api_key::{CreateApiKeyRequest, UpdateApiKeyRequest};
-pub use budget::{CreateBudgetRequest, UpdateBudgetRequest};
+pub use audit_log::AuditLogQuery;
+pub use auth_event::AuthEventQuery;
+pub use budget::{
+    BudgetListQuery, BudgetStatusQuery, CreateBudgetRequest, MuteBudgetRequest,
+    SnoozeBudgetRequest, UpdateBudgetRequest,
+};
+pub use budget_alert::BudgetAlertQuery;
 pub use budget_range::{CreateBudgetRangeRequest, UpdateBudgetRangeRequest};
-pub use category::{CreateCategoryRequest, UpdateCategoryRequest};
-pub use exchange_rate::ExchangeRateQuery;
+pub use category::{
+    CreateCategoryRequest, DeleteCategoryQuery, MergeCategoryRequest, UpdateCategoryRequest,
+};
+pub use category_rule::{CreateCategoryRuleRequest, UpdateCategoryRuleRequest};
+pub use display_query::DisplayQuery;
+pub use exchange_rate::{ConvertQuery, CustomRateQuery, CustomRateRequest, ExchangeRateQuery};
+pub use import_profile::{CreateImportProfileRequest, UpdateImportProfileRequest};
+pub use notification::{
+    NotificationPreferenceInput, NotificationQuery, UpdateNotificationPreferencesRequest,
+};
+pub use pagination::{PAGINATED_JSON, Paginated, Pagination, PaginationQuery};
+pub use password_reset_token::{ForgotPasswordRequest, ResetPasswordRequest};
 pub use person::{CreatePersonRequest, UpdatePersonRequest};
 pub use person_split_config::SetPersonSplitConfigRequest;
+pub use reconciliation::ReconcileAccountRequest;
+pub use recurring_transaction::{
+    CreateRecurringTransactionRequest, UpdateRecurringTransactionRequest,
+};
+pub use refresh_token::RefreshTokenRequest;
+pub use reimbursement::ReimbursementQuery;
+pub use split_group::{CreateSplitGroupRequest, SplitGroupMemberInput, UpdateSplitGroupRequest};
 pub use split_provider::CreateSplitProviderRequest;
+pub use sync_query::SyncQuery;
 pub use transaction::{
-    CreateTransactionRequest, TransactionFilter, TransactionType, UpdateTransactionRequest,
+    CategorySuggestionQuery, CreateTransactionRequest, DeleteTransactionQuery, TransactionFilter,
+    TransactionTotals, TransactionType, TransactionWriteQuery, UpdateTransactionRequest,
+};
+pub use transaction_split::{SplitParticipant, SplitStrategy};
+pub use transfer::CreateTransferRequest;
+pub use two_factor::{SetupTwoFactorRequest, VerifyTwoFactorRequest};
+pub use user::{
+    AuthResponse, ChangePasswordRequest, CreateUserRequest, LoginRequest, RegisterQuery,
+    RegistrationPreferences,
 };
-pub use user::{AuthResponse, CreateUserRequest, LoginRequest};
 
 // Re-export Response DTOs
-pub use account::AccountResponse;
+pub use account::{
+    AccountResponse, AccountSummaryResponse, AmortizationPayment, AmortizationResponse,
+    BalancePoint,
+};
+pub use allocation_rule::{AllocationDestinationResponse, AllocationRuleResponse};
 pub use api_key::{ApiKeyResponse, CreateApiKeyResponse, ListApiKeysResponse};
-pub use budget::BudgetResponse;
+pub use audit_log::AuditLogResponse;
+pub use auth_event::AuthEventResponse;
+pub use budget::{BudgetResponse, BudgetStatus};
+pub use budget_alert::BudgetAlertResponse;
 pub use budget_range::BudgetRangeResponse;
-pub use category::CategoryResponse;
-pub use exchange_rate::ExchangeRateResponse;
-pub use person::PersonResponse;
+pub use category::{CategoryResponse, DeleteCategoryResponse, MergeCategoryResponse};
+pub use category_rule::{ApplyCategoryRulesResponse, CategoryRuleResponse};
+pub use exchange_rate::{
+    ConversionResponse, CustomRateResponse, ExchangeRateResponse, HistoricalRateResponse,
+};
+pub use import_profile::ImportProfileResponse;
+pub use meta::CurrencyResponse;
+pub use notification::{NotificationPreferenceResponse, NotificationResponse};
+pub use person::{PersonResponse, PersonTransactionResponse};
 pub use person_split_config::PersonSplitConfigResponse;
+pub use purge::PurgeResponse;
+pub use reconciliation::ReconciliationResponse;
+pub use recurring_transaction::RecurringTransactionResponse;
+pub use reimbursement::{
+    CategoryReimbursementTotal, OutstandingReimbursementsResponse, ReimbursementTotal,
+};
+pub use split_group::{SplitGroupMemberResponse, SplitGroupResponse};
 pub use split_provider::{SplitProviderResponse, SplitwiseCredentials};
-pub use split_sync_record::SplitSyncStatusResponse;
-pub use transaction::TransactionResponse;
+pub use split_sync_record::{SplitSyncState, SplitSyncStatusResponse};
+pub use tag::TagResponse;
+pub use transaction::{CategorySuggestionResponse, TransactionExportRow, TransactionResponse};
 pub use transaction_split::TransactionSplitResponse;
-pub use user::UserResponse;
+pub use transfer::TransferResponse;
+pub use two_factor::TwoFactorSetupResponse;
+pub use user::{LoginResponse, TwoFactorChallenge, UserResponse};
+pub use webhook_event::WebhookResponse;
 
 // Re-export API key specific types
 pub use api_key::{ApiKeyScopes, OperationType, ResourceType, ScopePermission};
 
 // Re-export import models
+pub use aggregator_import::{
+    AccountMatch, AggregatorAccount, AggregatorAccountResult, AggregatorImportData,
+    AggregatorImportError, AggregatorImportRequest, AggregatorImportResponse,
+    AggregatorTransaction,
+};
 pub use bulk_transaction::{
-    BulkCreateData, BulkCreateError, BulkCreateRequest, BulkCreateResponse,
+    BulkCreateData, BulkCreateError, BulkCreateRequest, BulkCreateResponse, BulkItemResult,
+    BulkItemStatus, BulkOperation, BulkTransactionRequest, BulkTransactionResponse, BulkUpdateItem,
+};
+pub use import::{
+    CsvColumnMapping, CsvImportBatchError, CsvImportData, CsvImportDuplicate, CsvImportPreview,
+    CsvImportQuery, CsvImportResponse, DuplicateMatch, ImportCategorySource, ImportErrorMode,
+    ImportPreviewTransaction, ImportSummary, OfxImportData, OfxImportError, OfxImportResponse,
+    ParseData, ParseResponse, ParsedTransaction,
 };
-pub use import::{DuplicateMatch, ImportSummary, ParseData, ParseResponse, ParsedTransaction};
 
 // Re-export types from types module for convenience
-pub use crate::types::{AccountType, ApiKeyStatus, BudgetPeriod, ConfidenceLevel, CurrencyCode};
+pub use crate::types::{
+    AccountType, ApiKeyStatus, BudgetPeriod, ConfidenceLevel, CurrencyCode, TransactionStatus,
+};
@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::{notification_preferences, notifications};
+
+/// Kind of event users are notified of
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEventType {
+    /// A budget's spending crossed one of its alert thresholds
+    BudgetAlert,
+}
+
+impl NotificationEventType {
+    pub const ALL: [NotificationEventType; 1] = [NotificationEventType::BudgetAlert];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEventType::BudgetAlert => "budget_alert",
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == s)
+            .ok_or_else(|| format!("Unknown notification event type: {}", s))
+    }
+}
+
+/// Way a notification reaches its user
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannelKind {
+    /// Listed at `/api/v1/notifications`
+    InApp,
+    /// Emailed to the user's address
+    Email,
+    /// POSTed as JSON to a URL of the user's choice
+    Webhook,
+}
+
+impl NotificationChannelKind {
+    pub const ALL: [NotificationChannelKind; 3] = [
+        NotificationChannelKind::InApp,
+        NotificationChannelKind::Email,
+        NotificationChannelKind::Webhook,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannelKind::InApp => "in_app",
+            NotificationChannelKind::Email => "email",
+            NotificationChannelKind::Webhook => "webhook",
+        }
+    }
+
+    /// Whether the channel is used for event types the user has not configured it for
+    pub fn enabled_by_default(&self) -> bool {
+        matches!(self, NotificationChannelKind::InApp)
+    }
+}
+
+impl std::str::FromStr for NotificationChannelKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.as_str() == s)
+            .ok_or_else(|| format!("Unknown notification channel: {}", s))
+    }
+}
+
+/// Whether a user receives one kind of notification through one channel
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = notification_preferences)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NotificationPreference {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// A [`NotificationEventType`] as text
+    pub event_type: String,
+    /// A [`NotificationChannelKind`] as text
+    pub channel: String,
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Key webhook deliveries are signed with; set together with `webhook_url`
+    pub webhook_secret: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = notification_preferences)]
+pub struct NewNotificationPreference {
+    pub user_id: Uuid,
+    pub event_type: String,
+    pub channel: String,
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+}
+
+/// A notification delivered through the in-app channel
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = notifications)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// A [`NotificationEventType`] as text
+    pub event_type: String,
+    pub title: String,
+    pub body: String,
+    pub data: JsonValue,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub event_type: String,
+    pub title: String,
+    pub body: String,
+    pub data: JsonValue,
+}
+
+// Request DTOs
+
+/// Turn one channel on or off for one kind of notification
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct NotificationPreferenceInput {
+    pub event_type: NotificationEventType,
+    pub channel: NotificationChannelKind,
+    pub enabled: bool,
+    /// URL notifications are POSTed to; required to enable the webhook
+    /// channel and not accepted for other channels
+    #[validate(length(max = 2048))]
+    pub webhook_url: Option<String>,
+}
+
+/// Preferences to change; channels and event types not listed keep theirs
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[validate(length(min = 1), nested)]
+    pub preferences: Vec<NotificationPreferenceInput>,
+}
+
+/// Query parameters for listing in-app notifications
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NotificationQuery {
+    /// Only list notifications that have not been read
+    #[serde(default)]
+    pub unread: bool,
+}
+
+// Response DTOs
+
+/// Whether one kind of notification is sent through one channel
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferenceResponse {
+    pub event_type: NotificationEventType,
+    pub channel: NotificationChannelKind,
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    /// Key the `X-Signature-256` header of webhook deliveries is computed
+    /// with; a new one is generated whenever `webhook_url` changes
+    pub webhook_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationResponse {
+    pub id: Uuid,
+    pub event_type: NotificationEventType,
+    pub title: String,
+    pub body: String,
+    /// Details of the event, e.g. `budget_id` and `alert_id` of a budget alert
+    #[schema(value_type = Object)]
+    pub data: JsonValue,
+    pub created_at: DateTime<Utc>,
+    /// When the notification was read; `None` while unread
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            // The check constraint guarantees known values; fall back defensively
+            event_type: notification
+                .event_type
+                .parse()
+                .unwrap_or(NotificationEventType::BudgetAlert),
+            title: notification.title,
+            body: notification.body,
+            data: notification.data,
+            created_at: notification.created_at,
+            read_at: notification.read_at,
+        }
+    }
+}
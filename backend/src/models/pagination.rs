@@ -0,0 +1,183 @@
+//! Pagination for list endpoints
+//!
+//! [`Pagination`] is an extractor shared by paginated list handlers. It reads the
+//! `limit` and `offset` query parameters, or `page` and `per_page`, applies the
+//! configured default page size and clamps the page size to the configured maximum.
+//!
+//! List endpoints return a bare array unless the client asks for the
+//! [`Paginated`] envelope with `?paginated=true` or
+//! `Accept: application/vnd.master-of-coin.paginated+json`.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{HeaderMap, header::ACCEPT, request::Parts},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{AppState, errors::ApiError};
+
+/// Query parameters for paginated list endpoints
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationQuery {
+    /// Maximum number of items to return (default and maximum are configured on the server)
+    pub limit: Option<i64>,
+    /// Number of items to skip (default: 0)
+    pub offset: Option<i64>,
+    /// 1-based page number, instead of `offset`
+    pub page: Option<i64>,
+    /// Items per page, instead of `limit`
+    pub per_page: Option<i64>,
+    /// Wrap the items in a `Paginated` envelope with the total count
+    #[serde(default)]
+    pub paginated: bool,
+}
+
+/// Media type requesting the [`Paginated`] envelope, as an alternative to `?paginated=true`
+pub const PAGINATED_JSON: &str = "application/vnd.master-of-coin.paginated+json";
+
+/// Validated page of a list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+    /// Whether the client asked for the [`Paginated`] envelope
+    pub paginated: bool,
+}
+
+impl Pagination {
+    /// Resolve query parameters against the default and maximum page size
+    ///
+    /// Limits above `max_page_size` are clamped; a limit below 1 or a negative
+    /// offset is rejected. `page` and `per_page` are turned into the matching
+    /// offset and limit, and can't be mixed with them.
+    pub fn from_query(
+        query: PaginationQuery,
+        default_page_size: i64,
+        max_page_size: i64,
+    ) -> Result<Self, ApiError> {
+        if query.page.is_some() || query.per_page.is_some() {
+            if query.limit.is_some() || query.offset.is_some() {
+                return Err(ApiError::BadRequest(
+                    "page and per_page can't be combined with limit and offset".to_string(),
+                ));
+            }
+            return Self::from_page(query, default_page_size, max_page_size);
+        }
+
+        let limit = query.limit.unwrap_or(default_page_size);
+        if limit < 1 {
+            return Err(ApiError::BadRequest("limit must be at least 1".to_string()));
+        }
+
+        let offset = query.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(ApiError::BadRequest(
+                "offset must not be negative".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            limit: limit.min(max_page_size),
+            offset,
+            paginated: query.paginated,
+        })
+    }
+
+    fn from_page(
+        query: PaginationQuery,
+        default_page_size: i64,
+        max_page_size: i64,
+    ) -> Result<Self, ApiError> {
+        let per_page = query.per_page.unwrap_or(default_page_size);
+        if per_page < 1 {
+            return Err(ApiError::BadRequest(
+                "per_page must be at least 1".to_string(),
+            ));
+        }
+
+        let page = query.page.unwrap_or(1);
+        if page < 1 {
+            return Err(ApiError::BadRequest("page must be at least 1".to_string()));
+        }
+
+        let limit = per_page.min(max_page_size);
+        let offset = (page - 1)
+            .checked_mul(limit)
+            .ok_or_else(|| ApiError::BadRequest("page is too large".to_string()))?;
+
+        Ok(Self {
+            limit,
+            offset,
+            paginated: query.paginated,
+        })
+    }
+}
+
+/// Whether the `Accept` header asks for the [`Paginated`] envelope
+fn accepts_paginated(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            range
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .eq_ignore_ascii_case(PAGINATED_JSON)
+        })
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Pagination {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        // Non-numeric or out-of-range values fail to parse
+        let Query(query) = Query::<PaginationQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid pagination: {}", e.body_text())))?;
+
+        let mut pagination = Self::from_query(
+            query,
+            state.config.pagination.default_page_size,
+            state.config.pagination.max_page_size,
+        )?;
+        pagination.paginated |= accepts_paginated(&parts.headers);
+        Ok(pagination)
+    }
+}
+
+/// Page of a list with the total number of items, returned when the client
+/// asks for it
+///
+/// `total` is counted with the list's filters but without pagination, by a
+/// separate count query run alongside the paged one.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Paginated<T> {
+    pub data: Vec<T>,
+    /// Number of items matching the filters, over all pages
+    pub total: i64,
+    /// 1-based page number; with `offset`, the page the first item falls on
+    pub page: i64,
+    pub per_page: i64,
+    /// Whether items follow this page
+    pub has_more: bool,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(data: Vec<T>, total: i64, pagination: Pagination) -> Self {
+        let has_more = pagination.offset + (data.len() as i64) < total;
+        Self {
+            data,
+            total,
+            page: pagination.offset / pagination.limit + 1,
+            per_page: pagination.limit,
+            has_more,
+        }
+    }
+}
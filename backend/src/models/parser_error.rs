@@ -14,19 +14,32 @@ pub enum ParserError {
     #[error("Invalid timestamp '{value}' at line {line}. Expected format: YYYY-MM-DD HH:MM:SS")]
     InvalidTimestamp { value: String, line: usize },
 
+    #[error("Invalid date '{value}' at line {line}. Expected format: {format}")]
+    InvalidDate {
+        value: String,
+        format: String,
+        line: usize,
+    },
+
+    #[error("Mapped columns not found in the CSV header: {}", .columns.join(", "))]
+    MissingColumns { columns: Vec<String> },
+
     #[error("Invalid amount '{value}' at line {line}")]
     InvalidAmount { value: String, line: usize },
 
     #[error("Invalid or unsupported currency '{value}' at line {line}. Supported: €, £, $")]
     InvalidCurrency { value: String, line: usize },
 
-    #[error("Unsupported file type: {extension}. Supported types: .csv")]
+    #[error("Invalid OFX file: {error}")]
+    InvalidOfx { error: String },
+
+    #[error("Unsupported file type: {extension}. Supported types: .csv, .ofx, .qfx")]
     UnsupportedFileType { extension: String },
 
     #[error("Too many transactions: found {found}, maximum allowed is {max}")]
     TooManyTransactions { found: usize, max: usize },
 
-    #[error("Empty CSV file or no valid transactions found")]
+    #[error("Empty file or no valid transactions found")]
     EmptyFile,
 }
 
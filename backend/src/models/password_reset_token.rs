@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::password_reset_tokens;
+
+/// Single-use token for resetting a forgotten password
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = password_reset_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    /// Set once the token has been used
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct NewPasswordResetToken {
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Request DTOs
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+pub struct ForgotPasswordRequest {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+pub struct ResetPasswordRequest {
+    /// Token from the password reset email
+    #[validate(length(min = 1))]
+    pub token: String,
+    /// Subject to the same rules as the password chosen at registration
+    #[validate(length(min = 8))]
+    pub new_password: String,
+}
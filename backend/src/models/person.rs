@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::{TransactionResponse, TransactionSplitResponse};
 use crate::schema::people;
+use crate::types::nullable;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = people)]
@@ -40,13 +43,13 @@ pub struct CreatePerson {
 #[derive(Debug, Deserialize)]
 pub struct UpdatePerson {
     pub name: Option<String>,
-    pub email: Option<String>,
-    pub phone: Option<String>,
-    pub notes: Option<String>,
+    pub email: Option<Option<String>>,
+    pub phone: Option<Option<String>>,
+    pub notes: Option<Option<String>>,
 }
 
 // Request DTOs
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct CreatePersonRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
@@ -58,29 +61,36 @@ pub struct CreatePersonRequest {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize, validator::Validate)]
+#[derive(Debug, Deserialize, validator::Validate, ToSchema)]
 pub struct UpdatePersonRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
+    /// Omit to keep the email, `null` to clear it (likewise `phone` and `notes`)
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
     #[validate(email)]
-    pub email: Option<String>,
+    pub email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
     #[validate(length(max = 20))]
-    pub phone: Option<String>,
+    pub phone: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
     #[validate(length(max = 500))]
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
 }
 
 // Response DTOs
 
 /// Split config info included in PersonResponse
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PersonSplitConfigInfo {
     pub split_provider_id: Uuid,
     pub provider_type: String,
     pub external_user_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PersonResponse {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -91,6 +101,8 @@ pub struct PersonResponse {
     /// Optional split provider configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub split_config: Option<PersonSplitConfigInfo>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl From<Person> for PersonResponse {
@@ -103,6 +115,16 @@ impl From<Person> for PersonResponse {
             phone: person.phone,
             notes: person.notes,
             split_config: None, // Populated separately when needed
+            created_at: person.created_at,
+            updated_at: person.updated_at,
         }
     }
 }
+
+/// A transaction a person has a split in, with their split of it
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PersonTransactionResponse {
+    pub transaction: TransactionResponse,
+    /// The person's share of the transaction; `settled_at` is set once it is settled
+    pub split: TransactionSplitResponse,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Rows permanently removed by a purge, per table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeCounts {
+    pub transactions: usize,
+    pub transaction_splits: usize,
+    pub split_sync_records: usize,
+    pub transaction_tags: usize,
+}
+
+impl PurgeCounts {
+    /// Whether nothing was purged
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Add the rows purged by another batch
+    pub fn add(&mut self, other: PurgeCounts) {
+        self.transactions += other.transactions;
+        self.transaction_splits += other.transaction_splits;
+        self.split_sync_records += other.split_sync_records;
+        self.transaction_tags += other.transaction_tags;
+    }
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeResponse {
+    /// Rows deleted before this time were purged; `None` when retention is
+    /// disabled and nothing is purged
+    pub cutoff: Option<DateTime<Utc>>,
+    pub purged: PurgeCounts,
+}
@@ -0,0 +1,77 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::TransactionResponse;
+use crate::schema::reconciliations;
+use crate::types::Money;
+
+/// An account's balance checked against a bank statement
+///
+/// The latest reconciliation of an account is the checkpoint the next one is
+/// compared against.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = reconciliations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Reconciliation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    /// Last day covered by the statement, in UTC
+    pub statement_date: NaiveDate,
+    pub statement_balance: BigDecimal,
+    /// Posted balance at the end of `statement_date`, before any adjustment
+    pub balance: BigDecimal,
+    pub adjustment_transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = reconciliations)]
+pub struct NewReconciliation {
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub statement_date: NaiveDate,
+    pub statement_balance: BigDecimal,
+    pub balance: BigDecimal,
+    pub adjustment_transaction_id: Option<Uuid>,
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReconcileAccountRequest {
+    /// Balance on the bank statement at the end of `statement_date`
+    pub statement_balance: Money,
+    /// Last day covered by the statement (YYYY-MM-DD, UTC)
+    pub statement_date: NaiveDate,
+    /// Book the difference as a posted transaction, so the balance matches the
+    /// statement (default: false)
+    #[serde(default)]
+    pub create_adjustment: bool,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReconciliationResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub statement_date: NaiveDate,
+    pub statement_balance: Money,
+    /// Posted balance at the end of `statement_date`, before any adjustment
+    pub balance: Money,
+    /// `statement_balance` minus `balance`; zero when the account is reconciled
+    pub difference: Money,
+    /// Statement date of the previous reconciliation, if any
+    pub previous_statement_date: Option<NaiveDate>,
+    /// Statement balance of the previous reconciliation, if any
+    pub previous_statement_balance: Option<Money>,
+    /// Posted transactions after the previous reconciliation up to the end of
+    /// `statement_date`, oldest first
+    pub transactions: Vec<TransactionResponse>,
+    /// Transaction booking the difference, with `create_adjustment=true`
+    pub adjustment: Option<TransactionResponse>,
+    pub created_at: DateTime<Utc>,
+}
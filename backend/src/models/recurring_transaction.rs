@@ -0,0 +1,233 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::NewTransaction;
+use crate::schema::recurring_transactions;
+use crate::types::{Money, RecurrenceFrequency, TransactionStatus, nullable};
+
+/// Rule creating a transaction from its template on a fixed schedule
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = recurring_transactions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RecurringTransaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    pub amount: BigDecimal,
+    pub notes: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    /// Number of `frequency` periods between occurrences
+    pub interval: i32,
+    /// Date of the first occurrence of the current schedule
+    pub start_date: NaiveDate,
+    /// Occurrences of the current schedule already created as transactions
+    pub run_count: i32,
+    /// Date of the next occurrence, always occurrence number `run_count`
+    pub next_run_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = recurring_transactions)]
+pub struct NewRecurringTransaction {
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    pub amount: BigDecimal,
+    pub notes: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    pub interval: i32,
+    pub start_date: NaiveDate,
+    pub next_run_date: NaiveDate,
+}
+
+/// Values of a recurring transaction after an update
+#[derive(Debug)]
+pub struct UpdateRecurringTransaction {
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    pub amount: BigDecimal,
+    pub notes: Option<String>,
+    /// New schedule, restarting from its first occurrence; `None` keeps the
+    /// current schedule and progress
+    pub schedule: Option<RecurrenceSchedule>,
+}
+
+/// When a recurring transaction runs
+#[derive(Debug, Clone, Copy)]
+pub struct RecurrenceSchedule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: i32,
+    pub start_date: NaiveDate,
+}
+
+impl RecurringTransaction {
+    /// Date of occurrence number `index` of the current schedule
+    pub fn occurrence(&self, index: i32) -> Option<NaiveDate> {
+        self.frequency.occurrence(
+            self.start_date,
+            u32::try_from(self.interval).ok()?,
+            u32::try_from(index).ok()?,
+        )
+    }
+
+    /// Dates of the occurrences due by `today` that have not been created yet,
+    /// oldest first and at most `limit` of them
+    pub fn due_dates(&self, today: NaiveDate, limit: usize) -> Vec<NaiveDate> {
+        (self.run_count..)
+            .map_while(|index| self.occurrence(index))
+            .take_while(|date| *date <= today)
+            .take(limit)
+            .collect()
+    }
+
+    /// Transaction for the occurrence on `date`, dated midnight UTC
+    pub fn to_transaction(&self, date: NaiveDate) -> NewTransaction {
+        NewTransaction {
+            user_id: self.user_id,
+            account_id: self.account_id,
+            category_id: self.category_id,
+            title: self.title.clone(),
+            amount: self.amount.clone(),
+            date: date.and_time(chrono::NaiveTime::MIN).and_utc(),
+            notes: self.notes.clone(),
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
+        }
+    }
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateRecurringTransactionRequest {
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Title must be between 1 and 255 characters"
+    ))]
+    pub title: String,
+
+    /// Amount of each transaction; must be non-zero (negative for expenses)
+    #[validate(custom(function = "validate_amount_not_zero"))]
+    pub amount: Money,
+
+    #[validate(length(max = 1000, message = "Notes must not exceed 1000 characters"))]
+    pub notes: Option<String>,
+
+    pub frequency: RecurrenceFrequency,
+
+    /// Number of `frequency` periods between transactions (default: 1)
+    #[serde(default = "default_interval")]
+    #[validate(range(min = 1, max = 1000, message = "Interval must be between 1 and 1000"))]
+    pub interval: i32,
+
+    /// Date of the first transaction; a past date creates the missed ones too
+    pub next_run_date: NaiveDate,
+}
+
+/// Changes to a recurring transaction; omitted fields are kept
+///
+/// Changing `frequency`, `interval` or `next_run_date` restarts the schedule
+/// from the (new) next run date.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateRecurringTransactionRequest {
+    pub account_id: Option<Uuid>,
+
+    /// `null` removes the category
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<Uuid>)]
+    pub category_id: Option<Option<Uuid>>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Title must be between 1 and 255 characters"
+    ))]
+    pub title: Option<String>,
+
+    #[validate(custom(function = "validate_amount_not_zero"))]
+    pub amount: Option<Money>,
+
+    /// `null` removes the notes
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
+    #[validate(length(max = 1000, message = "Notes must not exceed 1000 characters"))]
+    pub notes: Option<Option<String>>,
+
+    pub frequency: Option<RecurrenceFrequency>,
+
+    #[validate(range(min = 1, max = 1000, message = "Interval must be between 1 and 1000"))]
+    pub interval: Option<i32>,
+
+    pub next_run_date: Option<NaiveDate>,
+}
+
+fn default_interval() -> i32 {
+    1
+}
+
+fn validate_amount_not_zero(amount: &Money) -> Result<(), validator::ValidationError> {
+    if amount.is_zero() {
+        let mut error = validator::ValidationError::new("amount_zero");
+        error.message = Some("Amount cannot be zero".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecurringTransactionResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    pub amount: Money,
+    pub notes: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    pub interval: i32,
+    /// Date the next transaction will be created on
+    pub next_run_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<RecurringTransaction> for RecurringTransactionResponse {
+    fn from(rule: RecurringTransaction) -> Self {
+        Self {
+            id: rule.id,
+            account_id: rule.account_id,
+            category_id: rule.category_id,
+            title: rule.title,
+            amount: Money::from(rule.amount),
+            notes: rule.notes,
+            frequency: rule.frequency,
+            interval: rule.interval,
+            next_run_date: rule.next_run_date,
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::TransactionResponse;
+use crate::types::{CurrencyCode, Money};
+
+/// Query parameters for outstanding reimbursements
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReimbursementQuery {
+    /// Only count transactions in this category
+    pub category_id: Option<Uuid>,
+    /// Only count transactions on or after this time
+    pub start_date: Option<DateTime<Utc>>,
+    /// Only count transactions on or before this time
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+/// Amount still to be paid back in one account currency
+///
+/// Reimbursable expenses are negative, so `amount` is the negated sum of the
+/// transactions' amounts: what is owed back.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReimbursementTotal {
+    pub currency: CurrencyCode,
+    pub count: i64,
+    pub amount: Money,
+}
+
+/// Amount still to be paid back for one category, in one account currency
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryReimbursementTotal {
+    /// `None` for uncategorized transactions
+    pub category_id: Option<Uuid>,
+    pub currency: CurrencyCode,
+    pub count: i64,
+    pub amount: Money,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OutstandingReimbursementsResponse {
+    /// Owed amounts per currency, by currency code
+    pub totals: Vec<ReimbursementTotal>,
+    /// Owed amounts per category and currency
+    pub by_category: Vec<CategoryReimbursementTotal>,
+    /// The transactions not reimbursed yet, newest first
+    pub transactions: Vec<TransactionResponse>,
+}
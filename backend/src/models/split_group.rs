@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::allocation_rule::{allocate, percentage_to_decimal};
+use crate::schema::{split_group_members, split_groups};
+
+/// Named set of people a transaction can be split with in one step
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = split_groups)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SplitGroup {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = split_groups)]
+pub struct NewSplitGroup {
+    pub user_id: Uuid,
+    pub name: String,
+}
+
+/// Person in a split group with the share of a transaction they owe by default
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = split_group_members)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SplitGroupMember {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    pub person_id: Uuid,
+    /// Percentage of the transaction amount, with at most two decimal places
+    pub percentage: BigDecimal,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = split_group_members)]
+pub struct NewSplitGroupMember {
+    pub group_id: Uuid,
+    pub person_id: Uuid,
+    pub percentage: BigDecimal,
+}
+
+/// Divide `total` between the members of a split group and the user
+///
+/// `members` pairs each person with their percentage; whatever the percentages
+/// leave of 100 is the user's own share. Shares are rounded like allocation
+/// rules (see [`allocate`]), with the user taking part in the rounding under
+/// `user_id`, so the same group and amount always give the same splits.
+///
+/// Only the members' shares are returned, in ascending person ID order, and
+/// zero shares are left out.
+pub fn group_shares(
+    total: &BigDecimal,
+    user_id: Uuid,
+    members: &[(Uuid, BigDecimal)],
+    minor_units: i64,
+) -> Vec<(Uuid, BigDecimal)> {
+    let mut participants = members.to_vec();
+    let members_total: BigDecimal = members.iter().map(|(_, percentage)| percentage).sum();
+    let user_percentage = BigDecimal::from(100) - members_total;
+    if user_percentage.is_positive() {
+        participants.push((user_id, user_percentage));
+    }
+
+    allocate(total, &participants, minor_units)
+        .into_iter()
+        .filter(|(person_id, share)| *person_id != user_id && !share.is_zero())
+        .collect()
+}
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_create_split_group_request"))]
+pub struct CreateSplitGroupRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// People in the group; percentages may add up to at most 100, the rest being your share
+    #[validate(nested)]
+    pub members: Vec<SplitGroupMemberInput>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_update_split_group_request"))]
+pub struct UpdateSplitGroupRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+
+    /// Replaces all members of the group when given
+    #[validate(nested)]
+    pub members: Option<Vec<SplitGroupMemberInput>>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SplitGroupMemberInput {
+    pub person_id: Uuid,
+
+    /// Percentage of the transaction amount the person owes, e.g. `25` or `33.33`
+    #[validate(range(
+        exclusive_min = 0.0,
+        max = 100.0,
+        message = "Percentage must be greater than 0 and at most 100"
+    ))]
+    pub percentage: f64,
+}
+
+fn validate_create_split_group_request(
+    req: &CreateSplitGroupRequest,
+) -> Result<(), validator::ValidationError> {
+    validate_members(&req.members)
+}
+
+fn validate_update_split_group_request(
+    req: &UpdateSplitGroupRequest,
+) -> Result<(), validator::ValidationError> {
+    match &req.members {
+        Some(members) => validate_members(members),
+        None => Ok(()),
+    }
+}
+
+fn validate_members(members: &[SplitGroupMemberInput]) -> Result<(), validator::ValidationError> {
+    if members.is_empty() {
+        let mut error = validator::ValidationError::new("no_members");
+        error.message = Some("A split group needs at least one member".into());
+        return Err(error);
+    }
+
+    let mut people = HashSet::new();
+    let mut total = BigDecimal::from(0);
+    for member in members {
+        if !people.insert(member.person_id) {
+            let mut error = validator::ValidationError::new("duplicate_member");
+            error.message = Some("Each person may only be listed once".into());
+            return Err(error);
+        }
+        let Some(percentage) = percentage_to_decimal(member.percentage) else {
+            let mut error = validator::ValidationError::new("percentage_precision");
+            error.message = Some("Percentages may have at most two decimal places".into());
+            return Err(error);
+        };
+        total += percentage;
+    }
+
+    if total > 100 {
+        let mut error = validator::ValidationError::new("percentages_sum");
+        error.message = Some("Member percentages must add up to at most 100".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SplitGroupResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub members: Vec<SplitGroupMemberResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SplitGroupMemberResponse {
+    pub person_id: Uuid,
+    /// Percentage of the transaction amount as a string, e.g. `"33.33"`
+    pub percentage: String,
+}
+
+impl SplitGroupResponse {
+    pub fn new(group: SplitGroup, members: Vec<SplitGroupMember>) -> Self {
+        Self {
+            id: group.id,
+            name: group.name,
+            members: members
+                .into_iter()
+                .map(|member| SplitGroupMemberResponse {
+                    person_id: member.person_id,
+                    percentage: member.percentage.with_scale(2).to_string(),
+                })
+                .collect(),
+            created_at: group.created_at,
+            updated_at: group.updated_at,
+        }
+    }
+}
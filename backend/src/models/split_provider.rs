@@ -17,6 +17,17 @@ pub struct SplitProvider {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Sync requests rejected for bad credentials since the last successful one
+    pub consecutive_auth_failures: i32,
+    /// The user disabled the provider, as opposed to it being disabled for bad credentials
+    pub disabled_by_user: bool,
+}
+
+impl SplitProvider {
+    /// Whether the provider was disabled because its credentials stopped working
+    pub fn needs_reconnect(&self) -> bool {
+        !self.is_active && !self.disabled_by_user
+    }
 }
 
 #[derive(Debug, Insertable)]
@@ -49,6 +60,8 @@ pub struct SplitProviderResponse {
     pub user_id: Uuid,
     pub provider_type: String,
     pub is_active: bool,
+    /// The provider was disabled because its credentials stopped working
+    pub needs_reconnect: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     // Note: credentials are never exposed in responses for security
@@ -56,11 +69,13 @@ pub struct SplitProviderResponse {
 
 impl From<SplitProvider> for SplitProviderResponse {
     fn from(provider: SplitProvider) -> Self {
+        let needs_reconnect = provider.needs_reconnect();
         Self {
             id: provider.id,
             user_id: provider.user_id,
             provider_type: provider.provider_type,
             is_active: provider.is_active,
+            needs_reconnect,
             created_at: provider.created_at,
             updated_at: provider.updated_at,
         }
@@ -1,16 +1,19 @@
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::schema::split_sync_records;
+use crate::schema::{orphaned_external_expenses, split_sync_records};
 
 /// Sync status enum
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SyncStatus {
     Pending,
     Synced,
+    /// The external expense no longer matches the local transaction
+    Drifted,
     Failed,
     Deleted,
 }
@@ -20,6 +23,7 @@ impl SyncStatus {
         match self {
             SyncStatus::Pending => "pending",
             SyncStatus::Synced => "synced",
+            SyncStatus::Drifted => "drifted",
             SyncStatus::Failed => "failed",
             SyncStatus::Deleted => "deleted",
         }
@@ -29,6 +33,7 @@ impl SyncStatus {
         match s {
             "pending" => Some(SyncStatus::Pending),
             "synced" => Some(SyncStatus::Synced),
+            "drifted" => Some(SyncStatus::Drifted),
             "failed" => Some(SyncStatus::Failed),
             "deleted" => Some(SyncStatus::Deleted),
             _ => None,
@@ -50,6 +55,8 @@ pub struct SplitSyncRecord {
     pub retry_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Provider user the split's person was synced as
+    pub external_user_id: Option<String>,
 }
 
 #[derive(Debug, Insertable)]
@@ -62,6 +69,7 @@ pub struct NewSplitSyncRecord {
     pub last_sync_at: Option<DateTime<Utc>>,
     pub last_error: Option<String>,
     pub retry_count: i32,
+    pub external_user_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -71,6 +79,29 @@ pub struct UpdateSplitSyncRecord {
     pub last_sync_at: Option<DateTime<Utc>>,
     pub last_error: Option<String>,
     pub retry_count: Option<i32>,
+    pub external_user_id: Option<String>,
+}
+
+/// External expense left behind when a synced transaction was force-deleted
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = orphaned_external_expenses)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OrphanedExternalExpense {
+    pub id: Uuid,
+    pub split_provider_id: Uuid,
+    pub external_expense_id: String,
+    pub transaction_id: Uuid,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = orphaned_external_expenses)]
+pub struct NewOrphanedExternalExpense {
+    pub split_provider_id: Uuid,
+    pub external_expense_id: String,
+    pub transaction_id: Uuid,
+    pub last_error: Option<String>,
 }
 
 // Response DTOs
@@ -80,6 +111,7 @@ pub struct SplitSyncStatusResponse {
     pub transaction_split_id: Uuid,
     pub split_provider_id: Uuid,
     pub provider_type: String, // Included for convenience
+    pub external_user_id: Option<String>,
     pub external_expense_id: Option<String>,
     pub sync_status: SyncStatus,
     pub last_sync_at: Option<DateTime<Utc>>,
@@ -88,6 +120,106 @@ pub struct SplitSyncStatusResponse {
     pub external_url: Option<String>, // Constructed based on provider
 }
 
+/// Sync state of a transaction split on one provider, as listed in its transaction
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SplitSyncState {
+    pub split_provider_id: Uuid,
+    /// Provider type, e.g. `splitwise`
+    pub provider_type: String,
+    /// Provider user the split's person was synced as
+    pub external_user_id: Option<String>,
+    pub external_expense_id: Option<String>,
+    pub sync_status: SyncStatus,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl SplitSyncState {
+    pub fn new(record: SplitSyncRecord, provider_type: String) -> Self {
+        Self {
+            split_provider_id: record.split_provider_id,
+            provider_type,
+            sync_status: record.status(),
+            external_user_id: record.external_user_id,
+            external_expense_id: record.external_expense_id,
+            last_sync_at: record.last_sync_at,
+            last_error: record.last_error,
+        }
+    }
+}
+
+/// Counts of a user's sync records by status
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncStatusSummaryResponse {
+    pub pending: i64,
+    pub synced: i64,
+    pub drifted: i64,
+    pub failed: i64,
+    pub deleted: i64,
+    /// External expenses of force-deleted transactions awaiting cleanup
+    pub orphaned: i64,
+    /// Providers whose credentials were rejected and that must be reconnected
+    pub providers_needing_reconnect: Vec<Uuid>,
+}
+
+/// Query parameters for the sync report
+#[derive(Debug, Deserialize)]
+pub struct SyncReportQuery {
+    /// Start of the range of transaction dates, inclusive (RFC 3339)
+    pub start_date: DateTime<Utc>,
+    /// End of the range of transaction dates, inclusive (RFC 3339)
+    pub end_date: DateTime<Utc>,
+}
+
+/// Comparison of local splits with the expenses on each person's provider
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncReportResponse {
+    pub in_sync: i64,
+    pub drifted: i64,
+    pub missing_on_external: i64,
+    pub missing_on_local: i64,
+    /// Splits whose external expense no longer matches them
+    pub drifted_splits: Vec<SyncReportSplit>,
+    /// Splits without an expense on their person's provider
+    pub missing_on_external_splits: Vec<SyncReportSplit>,
+    /// Provider expenses shared with a person that no local split was synced to
+    pub missing_on_local_expenses: Vec<SyncReportExpense>,
+    /// People left out of the report because their provider could not be read
+    pub errors: Vec<SyncReportError>,
+}
+
+/// A local split listed in the sync report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncReportSplit {
+    pub person_id: Uuid,
+    pub split_provider_id: Uuid,
+    pub transaction_id: Uuid,
+    pub transaction_split_id: Uuid,
+    pub external_expense_id: Option<String>,
+    /// Why the split is not in sync
+    pub reason: String,
+}
+
+/// A provider expense listed in the sync report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncReportExpense {
+    pub person_id: Uuid,
+    pub split_provider_id: Uuid,
+    pub external_expense_id: String,
+    /// Total cost of the expense
+    pub cost: String,
+    /// The person's share of the expense
+    pub owed_share: String,
+}
+
+/// A provider that could not be compared for a person
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncReportError {
+    pub person_id: Uuid,
+    pub split_provider_id: Uuid,
+    pub error: String,
+}
+
 impl SplitSyncRecord {
     pub fn status(&self) -> SyncStatus {
         SyncStatus::from_str(&self.sync_status).unwrap_or(SyncStatus::Pending)
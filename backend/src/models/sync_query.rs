@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// Query parameters for incremental sync of list endpoints
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncQuery {
+    /// Only return items created or modified at or after this time (RFC 3339)
+    pub updated_since: Option<DateTime<Utc>>,
+}
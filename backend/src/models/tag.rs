@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::{tags, transaction_tags};
+
+/// Longest tag name, in characters
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Most tags a transaction may have
+pub const MAX_TAGS_PER_TRANSACTION: usize = 20;
+
+/// Free-form label of a user's transactions, such as `vacation-2024`
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = tags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Tag {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Always lowercase
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = tags)]
+pub struct NewTag {
+    pub user_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = transaction_tags)]
+pub struct NewTransactionTag {
+    pub transaction_id: Uuid,
+    pub tag_id: Uuid,
+}
+
+/// How transactions are matched against several tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMode {
+    /// Transactions with at least one of the tags
+    #[default]
+    Any,
+    /// Transactions with every one of the tags
+    All,
+}
+
+/// Normalize tag names as they are stored: trimmed, lowercase and without duplicates
+///
+/// Fails with a message naming the first invalid tag.
+pub fn normalize_tags<S: AsRef<str>>(names: &[S]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.as_ref().trim().to_lowercase();
+        if name.is_empty() {
+            return Err("Tags must not be empty".to_string());
+        }
+        if name.chars().count() > MAX_TAG_LENGTH {
+            return Err(format!(
+                "Tag '{}' is longer than {} characters",
+                name, MAX_TAG_LENGTH
+            ));
+        }
+        if name.contains(',') {
+            return Err(format!("Tag '{}' must not contain a comma", name));
+        }
+        if !normalized.contains(&name) {
+            normalized.push(name);
+        }
+    }
+    if normalized.len() > MAX_TAGS_PER_TRANSACTION {
+        return Err(format!(
+            "A transaction may have at most {} tags",
+            MAX_TAGS_PER_TRANSACTION
+        ));
+    }
+    normalized.sort();
+    Ok(normalized)
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TagResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// Number of transactions with the tag, not counting deleted ones
+    pub usage_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TagResponse {
+    pub fn new(tag: Tag, usage_count: i64) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            usage_count,
+            created_at: tag.created_at,
+        }
+    }
+}
@@ -1,12 +1,19 @@
+use std::str::FromStr;
+
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
-use super::transaction_split::{self, TransactionSplitResponse};
+use super::tag::{self, TagMode};
+use super::transaction_split::{
+    self, NewTransactionSplit, SplitParticipant, SplitStrategy, TransactionSplitResponse,
+};
 use crate::schema::transactions;
+use crate::types::{CurrencyCode, Locale, Money, TransactionStatus, nullable};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = transactions)]
@@ -22,6 +29,24 @@ pub struct Transaction {
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub external_id: Option<String>,
+    pub status: TransactionStatus,
+    pub original_currency: Option<CurrencyCode>,
+    pub original_amount: Option<BigDecimal>,
+    pub exchange_rate: Option<BigDecimal>,
+    pub merchant: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Incremented on every update, for optimistic concurrency control
+    pub version: i32,
+    /// Shared by both legs of a transfer between accounts
+    pub transfer_id: Option<Uuid>,
+    /// When the transaction was deleted; deleted transactions can be restored
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Paid on someone else's behalf and expected to be paid back
+    pub reimbursable: bool,
+    /// When a reimbursable transaction was paid back
+    pub reimbursed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Insertable)]
@@ -34,6 +59,27 @@ pub struct NewTransaction {
     pub amount: BigDecimal,
     pub date: DateTime<Utc>,
     pub notes: Option<String>,
+    /// ID of the transaction at an external aggregator, used to deduplicate imports
+    pub external_id: Option<String>,
+    pub status: TransactionStatus,
+    /// Currency the transaction was made in, when it differs from the account currency
+    pub original_currency: Option<CurrencyCode>,
+    /// Amount in `original_currency`; `amount` stays in the account currency
+    pub original_amount: Option<BigDecimal>,
+    /// Rate from `original_currency` to the account currency
+    pub exchange_rate: Option<BigDecimal>,
+    /// Name of the merchant the purchase was made at
+    pub merchant: Option<String>,
+    /// Where the purchase was made; set together with `longitude`
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Links both legs of a transfer between accounts; transfers are neither
+    /// income nor expenses
+    pub transfer_id: Option<Uuid>,
+    pub reimbursable: bool,
+    /// Normalized tag names, saved in the same DB transaction as the transaction
+    #[diesel(skip_insertion)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,30 +95,55 @@ pub struct CreateTransaction {
 #[derive(Debug, Deserialize)]
 pub struct UpdateTransaction {
     pub account_id: Option<Uuid>,
-    pub category_id: Option<Uuid>,
+    /// `Some(None)` clears the category
+    pub category_id: Option<Option<Uuid>>,
     pub title: Option<String>,
     pub amount: Option<BigDecimal>,
     pub date: Option<DateTime<Utc>>,
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
+    pub original_currency: Option<CurrencyCode>,
+    pub original_amount: Option<BigDecimal>,
+    pub exchange_rate: Option<BigDecimal>,
+    pub merchant: Option<Option<String>>,
+    /// Set together with `longitude`
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Clearing the flag also clears `reimbursed_at`
+    pub reimbursable: Option<bool>,
+    /// Apply the update only if the transaction is still at this version
+    pub expected_version: Option<i32>,
+    /// Replaces the splits in the same DB transaction as the other fields,
+    /// matching existing splits by person
+    #[serde(skip)]
+    pub splits: Option<Vec<NewTransactionSplit>>,
+    /// Normalized tag names replacing the tags in the same DB transaction as
+    /// the other fields
+    #[serde(skip)]
+    pub tags: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Kind of transaction, as filtered on by the `type` query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionType {
+    /// Positive amount
     Income,
+    /// Negative amount
     Expense,
+    /// Either leg of a transfer between accounts
     Transfer,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct TransactionSplitInput {
     pub person_id: Uuid,
     /// Amount must be positive and non-zero
-    #[validate(range(min = 0.01, message = "Split amount must be greater than 0"))]
-    pub amount: f64,
+    #[validate(custom(function = "transaction_split::validate_positive_amount"))]
+    pub amount: Money,
 }
 
 // Request DTOs
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 #[validate(schema(function = "validate_transaction_request"))]
 pub struct CreateTransactionRequest {
     pub account_id: Uuid,
@@ -87,22 +158,103 @@ pub struct CreateTransactionRequest {
 
     /// Amount must be non-zero (can be negative for expenses)
     #[validate(custom(function = "validate_amount_not_zero"))]
-    pub amount: f64,
+    pub amount: Money,
 
     pub date: DateTime<Utc>,
 
     #[validate(length(max = 1000, message = "Notes must not exceed 1000 characters"))]
     pub notes: Option<String>,
 
+    /// Clearing state (default: posted); pending transactions can be posted later
+    pub status: Option<TransactionStatus>,
+
+    /// Currency the transaction was made in, for foreign-currency spending
+    ///
+    /// `original_currency`, `original_amount` and `exchange_rate` are given together;
+    /// `amount` stays in the account currency and must equal
+    /// `original_amount * exchange_rate` within rounding.
+    pub original_currency: Option<CurrencyCode>,
+    /// Amount in `original_currency`
+    pub original_amount: Option<Money>,
+    /// Rate from `original_currency` to the account currency
+    pub exchange_rate: Option<f64>,
+
+    /// Name of the merchant the purchase was made at
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Merchant must be between 1 and 255 characters"
+    ))]
+    pub merchant: Option<String>,
+
+    /// Latitude where the purchase was made, given together with `longitude`
+    #[validate(range(
+        min = -90.0,
+        max = 90.0,
+        message = "Latitude must be between -90 and 90"
+    ))]
+    pub latitude: Option<f64>,
+
+    /// Longitude where the purchase was made, given together with `latitude`
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: Option<f64>,
+
     /// Optional splits for shared transactions
     /// Each split must have a positive amount, and total splits must not exceed transaction amount
     #[validate(nested)]
     pub splits: Option<Vec<TransactionSplitInput>>,
+
+    /// Split the amount equally between you and these people, instead of passing `splits`
+    ///
+    /// Leftover minor units are distributed deterministically (see
+    /// [`transaction_split::split_equally`]); only the people's shares are recorded as splits.
+    pub split_equally_with: Option<Vec<Uuid>>,
+
+    /// Split the amount with the members of a split group by their percentages, instead of
+    /// passing `splits`
+    ///
+    /// Shares are rounded deterministically (see
+    /// [`group_shares`](super::split_group::group_shares)); only the members' shares are
+    /// recorded as splits.
+    pub split_group_id: Option<Uuid>,
+
+    /// Have the amount divided between `participants` by this strategy, instead of
+    /// passing `splits`
+    ///
+    /// The splits add up exactly to the amount; leftover minor units are handed out
+    /// one at a time by person ID (see [`transaction_split::split_by_strategy`]).
+    pub split_strategy: Option<SplitStrategy>,
+
+    /// People to split with by `split_strategy`, each with a `percentage` for
+    /// `PERCENTAGE` or `shares` for `SHARES`
+    #[validate(nested)]
+    pub participants: Option<Vec<SplitParticipant>>,
+
+    /// Paid on someone else's behalf, such as a work expense, and expected to be
+    /// paid back (default: false)
+    #[serde(default)]
+    pub reimbursable: bool,
+
+    /// Free-form tags, such as `vacation-2024`; tags the user does not have yet are created
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), validator::ValidationError> {
+    tag::normalize_tags(tags).map(|_| ()).map_err(|message| {
+        let mut error = validator::ValidationError::new("invalid_tags");
+        error.message = Some(message.into());
+        error
+    })
 }
 
 // Custom validator for amount not being zero
-fn validate_amount_not_zero(amount: f64) -> Result<(), validator::ValidationError> {
-    if amount == 0.0 {
+fn validate_amount_not_zero(amount: &Money) -> Result<(), validator::ValidationError> {
+    if amount.is_zero() {
         let mut error = validator::ValidationError::new("amount_zero");
         error.message = Some("Transaction amount cannot be zero".into());
         return Err(error);
@@ -115,6 +267,16 @@ fn validate_transaction_request(
     req: &CreateTransactionRequest,
 ) -> Result<(), validator::ValidationError> {
     if let Some(ref splits) = req.splits {
+        let mut person_ids = std::collections::HashSet::new();
+        if !splits
+            .iter()
+            .all(|split| person_ids.insert(split.person_id))
+        {
+            let mut error = validator::ValidationError::new("duplicate_split_person");
+            error.message = Some("Each person can only appear once in splits".into());
+            return Err(error);
+        }
+
         // Validate each split
         for split in splits {
             split.validate().map_err(|_| {
@@ -125,16 +287,86 @@ fn validate_transaction_request(
         }
 
         // Validate splits sum using the function from transaction_split module
-        let split_amounts: Vec<f64> = splits.iter().map(|s| s.amount).collect();
-        transaction_split::validate_splits_sum(&split_amounts, req.amount)?;
+        let split_amounts: Vec<BigDecimal> = splits
+            .iter()
+            .map(|s| s.amount.as_decimal().clone())
+            .collect();
+        transaction_split::validate_splits_sum(&split_amounts, req.amount.as_decimal())?;
+    }
+
+    validate_original_currency(
+        req.original_currency,
+        req.original_amount.as_ref(),
+        req.exchange_rate,
+    )?;
+    if let (Some(original_amount), Some(exchange_rate)) = (
+        &req.original_amount,
+        req.exchange_rate.and_then(rate_to_decimal),
+    ) {
+        validate_conversion(
+            req.amount.as_decimal(),
+            original_amount.as_decimal(),
+            &exchange_rate,
+        )?;
+    }
+    validate_location(req.latitude, req.longitude)?;
+
+    if req.split_group_id.is_some() && (req.splits.is_some() || req.split_equally_with.is_some()) {
+        let mut error = validator::ValidationError::new("conflicting_splits");
+        error.message =
+            Some("Provide only one of splits, split_equally_with or split_group_id".into());
+        return Err(error);
+    }
+
+    match (req.split_strategy, &req.participants) {
+        (Some(strategy), Some(participants)) => {
+            if req.splits.is_some()
+                || req.split_equally_with.is_some()
+                || req.split_group_id.is_some()
+            {
+                let mut error = validator::ValidationError::new("conflicting_splits");
+                error.message = Some(
+                    "Provide only one of splits, split_equally_with, split_group_id or \
+                     split_strategy"
+                        .into(),
+                );
+                return Err(error);
+            }
+            transaction_split::validate_split_participants(strategy, participants)?;
+        }
+        (None, None) => {}
+        _ => {
+            let mut error = validator::ValidationError::new("incomplete_split_strategy");
+            error.message = Some("split_strategy and participants must be given together".into());
+            return Err(error);
+        }
+    }
+
+    if let Some(ref person_ids) = req.split_equally_with {
+        if req.splits.is_some() {
+            let mut error = validator::ValidationError::new("conflicting_splits");
+            error.message = Some("Provide either splits or split_equally_with, not both".into());
+            return Err(error);
+        }
+
+        let unique: std::collections::HashSet<&Uuid> = person_ids.iter().collect();
+        if person_ids.is_empty() || unique.len() != person_ids.len() {
+            let mut error = validator::ValidationError::new("invalid_split_participants");
+            error.message = Some("split_equally_with must list distinct people".into());
+            return Err(error);
+        }
     }
     Ok(())
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_update_transaction_request"))]
 pub struct UpdateTransactionRequest {
     pub account_id: Option<Uuid>,
-    pub category_id: Option<Uuid>,
+    /// Omit to keep the category, `null` to clear it
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<Uuid>)]
+    pub category_id: Option<Option<Uuid>>,
 
     #[validate(length(
         min = 1,
@@ -144,30 +376,284 @@ pub struct UpdateTransactionRequest {
     pub title: Option<String>,
 
     /// Amount must be non-zero if provided
-    #[validate(custom(function = "validate_optional_amount_not_zero"))]
-    pub amount: Option<f64>,
+    #[validate(custom(function = "validate_amount_not_zero"))]
+    pub amount: Option<Money>,
 
     pub date: Option<DateTime<Utc>>,
 
+    /// Omit to keep the notes, `null` to clear them
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
     #[validate(length(max = 1000, message = "Notes must not exceed 1000 characters"))]
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
+
+    /// Replaces the original currency details (all three are given together)
+    pub original_currency: Option<CurrencyCode>,
+    pub original_amount: Option<Money>,
+    pub exchange_rate: Option<f64>,
+
+    /// Omit to keep the merchant, `null` to clear it
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Merchant must be between 1 and 255 characters"
+    ))]
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>)]
+    pub merchant: Option<Option<String>>,
+
+    /// Replaces the location (`latitude` and `longitude` are given together)
+    #[validate(range(
+        min = -90.0,
+        max = 90.0,
+        message = "Latitude must be between -90 and 90"
+    ))]
+    pub latitude: Option<f64>,
+
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: Option<f64>,
+
+    /// Replaces the transaction's splits when provided
+    /// Existing splits are matched by person and updated, new people are added and
+    /// people no longer listed are removed. An empty array removes all splits.
+    #[validate(nested)]
+    pub splits: Option<Vec<TransactionSplitInput>>,
+
+    /// Marks the transaction reimbursable; `false` also forgets when it was reimbursed
+    pub reimbursable: Option<bool>,
+
+    /// Replaces the transaction's tags when provided; an empty array removes all tags
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+
+    /// Version the client last saw; when given (here or as `If-Match`), the update
+    /// fails with 409 if the transaction has changed since
+    pub version: Option<i32>,
 }
 
-// Custom validator for optional amount not being zero
-fn validate_optional_amount_not_zero(amount: f64) -> Result<(), validator::ValidationError> {
-    if amount == 0.0 {
-        let mut error = validator::ValidationError::new("amount_zero");
-        error.message = Some("Transaction amount cannot be zero".into());
+/// Maximum difference in cents tolerated between `amount` and
+/// `original_amount * exchange_rate`, covering the rounding of the account amount
+const CONVERSION_TOLERANCE_CENTS: i64 = 1;
+
+/// Convert an exchange rate from a request to a decimal
+pub fn rate_to_decimal(rate: f64) -> Option<BigDecimal> {
+    BigDecimal::from_str(&rate.to_string()).ok()
+}
+
+/// Validate that the original currency details are given together and the rate is positive
+fn validate_original_currency(
+    original_currency: Option<CurrencyCode>,
+    original_amount: Option<&Money>,
+    exchange_rate: Option<f64>,
+) -> Result<(), validator::ValidationError> {
+    match (original_currency, original_amount, exchange_rate) {
+        (None, None, None) => Ok(()),
+        (Some(_), Some(_), Some(rate)) if rate > 0.0 => Ok(()),
+        (Some(_), Some(_), Some(_)) => {
+            let mut error = validator::ValidationError::new("exchange_rate_not_positive");
+            error.message = Some("Exchange rate must be greater than 0".into());
+            Err(error)
+        }
+        _ => {
+            let mut error = validator::ValidationError::new("incomplete_original_currency");
+            error.message = Some(
+                "original_currency, original_amount and exchange_rate must be given together"
+                    .into(),
+            );
+            Err(error)
+        }
+    }
+}
+
+/// Validate that latitude and longitude are given together
+///
+/// Their ranges are checked by the field validators.
+fn validate_location(
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<(), validator::ValidationError> {
+    if latitude.is_some() != longitude.is_some() {
+        let mut error = validator::ValidationError::new("incomplete_location");
+        error.message = Some("latitude and longitude must be given together".into());
         return Err(error);
     }
     Ok(())
 }
 
+/// Validate that `amount` is `original_amount` converted at `exchange_rate`, within rounding
+pub fn validate_conversion(
+    amount: &BigDecimal,
+    original_amount: &BigDecimal,
+    exchange_rate: &BigDecimal,
+) -> Result<(), validator::ValidationError> {
+    let converted = original_amount * exchange_rate;
+    if (amount - &converted).abs() > BigDecimal::new(CONVERSION_TOLERANCE_CENTS.into(), 2) {
+        let mut error = validator::ValidationError::new("conversion_mismatch");
+        error.message = Some(
+            format!(
+                "Amount ({}) does not match original amount ({}) at exchange rate ({}): expected {}",
+                Money::from(amount.clone()),
+                original_amount.normalized(),
+                exchange_rate.normalized(),
+                Money::from(converted)
+            )
+            .into(),
+        );
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Schema-level validation for UpdateTransactionRequest
+// The splits sum and conversion are checked against the stored values by the service when
+// only part of them is given
+fn validate_update_transaction_request(
+    req: &UpdateTransactionRequest,
+) -> Result<(), validator::ValidationError> {
+    validate_original_currency(
+        req.original_currency,
+        req.original_amount.as_ref(),
+        req.exchange_rate,
+    )?;
+    validate_location(req.latitude, req.longitude)?;
+
+    if let Some(ref splits) = req.splits {
+        let mut person_ids = std::collections::HashSet::new();
+        if !splits
+            .iter()
+            .all(|split| person_ids.insert(split.person_id))
+        {
+            let mut error = validator::ValidationError::new("duplicate_split_person");
+            error.message = Some("Each person can only appear once in splits".into());
+            return Err(error);
+        }
+
+        if let Some(ref amount) = req.amount {
+            let split_amounts: Vec<BigDecimal> = splits
+                .iter()
+                .map(|s| s.amount.as_decimal().clone())
+                .collect();
+            transaction_split::validate_splits_sum(&split_amounts, amount.as_decimal())?;
+        }
+    }
+    Ok(())
+}
+
+/// Query parameters for suggesting a category for a new transaction
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CategorySuggestionQuery {
+    /// Title of the new transaction
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: String,
+}
+
+/// Category suggested from past transactions with the same title
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategorySuggestionResponse {
+    /// Category used most often for the title, if any past transaction matched
+    pub category_id: Option<Uuid>,
+    /// Share of the matching transactions in the suggested category, from 0 to 1
+    pub confidence: f64,
+    /// Number of past categorized transactions whose title matched
+    pub matching_transactions: i64,
+}
+
+/// Query parameters for deleting a transaction
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteTransactionQuery {
+    /// Delete locally even if a linked external expense cannot be deleted; the
+    /// external expense is recorded as orphaned (default: false)
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Query parameters for creating or updating a transaction
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TransactionWriteQuery {
+    /// Reject the transaction with `422` instead of saving it when it raises
+    /// any `warnings` (default: false)
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Field transaction lists can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionSortKey {
+    Date,
+    Amount,
+    Title,
+}
+
+/// Order of a transaction list, parsed from the `sort` query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionSort {
+    pub key: TransactionSortKey,
+    pub descending: bool,
+}
+
+impl TransactionSort {
+    /// Values accepted by `sort`; a leading `-` sorts in descending order
+    pub const ALLOWED_KEYS: [&'static str; 3] = ["date", "amount", "title"];
+}
+
+impl Default for TransactionSort {
+    /// Newest first
+    fn default() -> Self {
+        Self {
+            key: TransactionSortKey::Date,
+            descending: true,
+        }
+    }
+}
+
+impl FromStr for TransactionSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (descending, name) = match value.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, value),
+        };
+        let key = match name {
+            "date" => TransactionSortKey::Date,
+            "amount" => TransactionSortKey::Amount,
+            "title" => TransactionSortKey::Title,
+            _ => {
+                return Err(format!(
+                    "Invalid sort '{}'; allowed keys are {}, prefixed with '-' for descending order",
+                    value,
+                    Self::ALLOWED_KEYS.join(", ")
+                ));
+            }
+        };
+        Ok(Self { key, descending })
+    }
+}
+
 // Filter for querying transactions (renamed from TransactionFilters to match mod.rs export)
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "validate_transaction_filter"))]
 pub struct TransactionFilter {
     pub account_id: Option<Uuid>,
     pub category_id: Option<Uuid>,
+
+    /// Only return transactions without a category, leaving out transfers, which
+    /// need none (default: false)
+    #[serde(default)]
+    pub uncategorized: bool,
+
+    /// Also return deleted transactions, which have `deleted_at` set (default: false)
+    #[serde(default)]
+    pub include_deleted: bool,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
 
@@ -177,33 +663,208 @@ pub struct TransactionFilter {
     /// Maximum amount filter (can be negative)
     pub max_amount: Option<f64>,
 
+    /// Only return income (positive amounts), expenses (negative amounts) or
+    /// transfer legs
+    #[serde(rename = "type")]
+    pub transaction_type: Option<TransactionType>,
+
+    /// Only return transactions created or modified at or after this time
+    pub updated_since: Option<DateTime<Utc>>,
+
+    /// Only return transactions with this status
+    pub status: Option<TransactionStatus>,
+
     /// Search term for title or notes
     #[validate(length(max = 100, message = "Search term must not exceed 100 characters"))]
     pub search: Option<String>,
 
-    /// Pagination: limit (1-1000)
-    #[validate(range(min = 1, max = 1000, message = "Limit must be between 1 and 1000"))]
+    /// Only return transactions at this merchant (case-insensitive)
+    #[validate(length(max = 255, message = "Merchant must not exceed 255 characters"))]
+    pub merchant: Option<String>,
+
+    /// Only return transactions with these tags, comma-separated (case-insensitive)
+    #[validate(length(max = 1000, message = "Tags must not exceed 1000 characters"))]
+    pub tags: Option<String>,
+
+    /// Whether transactions need `any` (default) or `all` of `tags`
+    #[serde(default)]
+    pub tag_mode: TagMode,
+
+    /// Only return reimbursable (`true`) or other (`false`) transactions
+    pub reimbursable: Option<bool>,
+
+    /// Only return transactions that were (`true`) or were not (`false`) reimbursed
+    pub reimbursed: Option<bool>,
+
+    /// Sort by `date`, `amount` or `title`, with a leading `-` for descending
+    /// order (default: `-date`)
+    pub sort: Option<String>,
+
+    /// Pagination: limit, set from the [`Pagination`](super::Pagination) extractor for
+    /// API requests; `None` returns all matching transactions
+    #[serde(skip)]
+    #[param(ignore)]
     pub limit: Option<i64>,
 
-    /// Pagination: offset
-    #[validate(range(min = 0, message = "Offset must be non-negative"))]
+    /// Pagination: offset, set from the [`Pagination`](super::Pagination) extractor
+    #[serde(skip)]
+    #[param(ignore)]
     pub offset: Option<i64>,
 }
 
+fn validate_transaction_filter(
+    filter: &TransactionFilter,
+) -> Result<(), validator::ValidationError> {
+    if filter.uncategorized && filter.category_id.is_some() {
+        let mut error = validator::ValidationError::new("conflicting_category_filters");
+        error.message = Some("Provide either category_id or uncategorized, not both".into());
+        return Err(error);
+    }
+    if let (Some(min_amount), Some(max_amount)) = (filter.min_amount, filter.max_amount)
+        && min_amount > max_amount
+    {
+        let mut error = validator::ValidationError::new("invalid_amount_range");
+        error.message = Some(
+            format!(
+                "min_amount ({}) must not be greater than max_amount ({})",
+                min_amount, max_amount
+            )
+            .into(),
+        );
+        return Err(error);
+    }
+    if let Err(message) = filter.tag_names() {
+        let mut error = validator::ValidationError::new("invalid_tags");
+        error.message = Some(message.into());
+        return Err(error);
+    }
+    if let Err(message) = filter.sort_order() {
+        let mut error = validator::ValidationError::new("invalid_sort");
+        error.message = Some(message.into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+impl TransactionFilter {
+    /// Normalized names of the `tags` filter, empty when not filtering by tag
+    pub fn tag_names(&self) -> Result<Vec<String>, String> {
+        match &self.tags {
+            Some(tags) => {
+                let names: Vec<&str> = tags
+                    .split(',')
+                    .filter(|name| !name.trim().is_empty())
+                    .collect();
+                tag::normalize_tags(&names)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Order of the list, newest first when `sort` is not given
+    pub fn sort_order(&self) -> Result<TransactionSort, String> {
+        self.sort
+            .as_deref()
+            .map_or(Ok(TransactionSort::default()), str::parse)
+    }
+}
+
+/// Totals of the transactions matching a filter in one account currency
+///
+/// Covers every matching transaction, not just the returned page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionTotals {
+    pub currency: CurrencyCode,
+    pub count: i64,
+    /// Sum of positive amounts
+    pub income: BigDecimal,
+    /// Sum of negative amounts, as a positive number
+    pub expenses: BigDecimal,
+}
+
+impl TransactionTotals {
+    /// Income minus expenses
+    pub fn net(&self) -> BigDecimal {
+        &self.income - &self.expenses
+    }
+}
+
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TransactionResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub account_id: Uuid,
     pub category_id: Option<Uuid>,
     pub title: String,
-    /// BigDecimal as string for JSON serialization
-    pub amount: String,
+    /// Amount in the account currency
+    pub amount: Money,
+    /// `amount` formatted for the requested locale, e.g. `"-1.234,56"` for `de-DE`
+    /// (display only; `amount` stays authoritative)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_display: Option<String>,
     pub date: DateTime<Utc>,
     pub notes: Option<String>,
+    pub status: TransactionStatus,
+    /// Currency the transaction was made in, when it differs from the account currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_currency: Option<CurrencyCode>,
+    /// Amount in `original_currency`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_amount: Option<Money>,
+    /// Rate `amount` was converted at from `original_amount`, as a string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange_rate: Option<String>,
     /// Splits associated with this transaction
     pub splits: Option<Vec<TransactionSplitResponse>>,
+    /// Tags of the transaction, sorted by name
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Account balance after this transaction (only set on create/update)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projected_balance: Option<Money>,
+    /// Warning when the transaction breaches the account's overdraft or credit limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_warning: Option<String>,
+    /// Inputs that are suspicious but valid, such as a date in the future
+    /// (only set on create/update)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Set when edited splits belong to an expense synced to an external split provider
+    /// and the expense must be re-synced
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_resync: bool,
+    /// ID of the transaction at the aggregator it was imported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Name of the merchant the purchase was made at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant: Option<String>,
+    /// Where the purchase was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    /// Shared by both legs of a transfer between accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Uuid>,
+    /// IDs of the transfer transactions an allocation rule created for this income
+    /// (only set on create/post)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allocations: Vec<Uuid>,
+    /// When the transaction was deleted (only listed with `include_deleted=true`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Paid on someone else's behalf and expected to be paid back
+    #[serde(default)]
+    pub reimbursable: bool,
+    /// When the transaction was paid back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reimbursed_at: Option<DateTime<Utc>>,
+    /// Current version, to send back with updates
+    pub version: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl From<Transaction> for TransactionResponse {
@@ -214,10 +875,68 @@ impl From<Transaction> for TransactionResponse {
             account_id: transaction.account_id,
             category_id: transaction.category_id,
             title: transaction.title,
-            amount: format!("{:.2}", transaction.amount),
+            amount: Money::from(transaction.amount),
+            amount_display: None,
             date: transaction.date,
             notes: transaction.notes,
+            status: transaction.status,
+            original_currency: transaction.original_currency,
+            original_amount: transaction
+                .original_amount
+                .zip(transaction.original_currency)
+                .map(|(amount, currency)| Money::new(amount, currency)),
+            exchange_rate: transaction
+                .exchange_rate
+                .map(|rate| rate.normalized().to_string()),
             splits: None, // Populated separately when needed
+            tags: Vec::new(),
+            projected_balance: None,
+            balance_warning: None,
+            warnings: Vec::new(),
+            needs_resync: false,
+            external_id: transaction.external_id,
+            merchant: transaction.merchant,
+            latitude: transaction.latitude,
+            longitude: transaction.longitude,
+            transfer_id: transaction.transfer_id,
+            allocations: Vec::new(),
+            deleted_at: transaction.deleted_at,
+            reimbursable: transaction.reimbursable,
+            reimbursed_at: transaction.reimbursed_at,
+            version: transaction.version,
+            created_at: transaction.created_at,
+            updated_at: transaction.updated_at,
         }
     }
 }
+
+/// Row of the transaction CSV export, also used for CSV transaction lists
+///
+/// The amount is the stored decimal as is, in the account's currency.
+#[derive(Debug, Serialize)]
+pub struct TransactionExportRow {
+    pub date: DateTime<Utc>,
+    pub account: String,
+    pub category: Option<String>,
+    pub title: String,
+    pub amount: String,
+    pub currency: CurrencyCode,
+    pub notes: Option<String>,
+}
+
+impl TransactionResponse {
+    /// Format the amount and split amounts in the account's currency
+    pub fn set_currency(&mut self, currency: CurrencyCode) {
+        self.amount.set_currency(currency);
+        for split in self.splits.iter_mut().flatten() {
+            split.amount.set_currency(currency);
+        }
+    }
+
+    /// Add the amount formatted for display in `locale`
+    ///
+    /// Call after [`Self::set_currency`], so the display uses the currency's decimal places.
+    pub fn set_display_locale(&mut self, locale: Locale) {
+        self.amount_display = Some(locale.format(&self.amount));
+    }
+}
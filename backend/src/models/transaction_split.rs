@@ -1,11 +1,16 @@
-use bigdecimal::BigDecimal;
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, RoundingMode, Signed, Zero};
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::split_sync_record::SplitSyncState;
 use crate::schema::transaction_splits;
+use crate::types::Money;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = transaction_splits)]
@@ -17,6 +22,8 @@ pub struct TransactionSplit {
     pub amount: BigDecimal,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Set when the split was settled locally rather than through a provider
+    pub settled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Insertable)]
@@ -47,8 +54,8 @@ pub struct CreateTransactionSplitRequest {
     pub person_id: Uuid,
 
     /// Amount must be positive and non-zero
-    #[validate(range(min = 0.01, message = "Split amount must be greater than 0"))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub amount: Money,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -56,13 +63,13 @@ pub struct UpdateTransactionSplitRequest {
     pub person_id: Option<Uuid>,
 
     /// Amount must be positive and non-zero if provided
-    #[validate(custom(function = "validate_optional_positive_amount"))]
-    pub amount: Option<f64>,
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub amount: Option<Money>,
 }
 
-// Custom validator for optional positive amount
-fn validate_optional_positive_amount(amount: f64) -> Result<(), validator::ValidationError> {
-    if amount <= 0.0 {
+// Custom validator for positive split amounts
+pub(crate) fn validate_positive_amount(amount: &Money) -> Result<(), validator::ValidationError> {
+    if !amount.is_positive() {
         let mut error = validator::ValidationError::new("amount_not_positive");
         error.message = Some("Split amount must be greater than 0".into());
         return Err(error);
@@ -71,23 +78,23 @@ fn validate_optional_positive_amount(amount: f64) -> Result<(), validator::Valid
 }
 
 pub fn validate_splits_sum(
-    splits: &[f64],
-    transaction_amount: f64,
+    splits: &[BigDecimal],
+    transaction_amount: &BigDecimal,
 ) -> Result<(), validator::ValidationError> {
     if splits.is_empty() {
         return Ok(());
     }
 
-    let total: f64 = splits.iter().sum();
+    let total: BigDecimal = splits.iter().sum();
 
     // Splits sum must not exceed transaction amount
     if total > transaction_amount.abs() {
         let mut error = validator::ValidationError::new("splits_exceed_amount");
         error.message = Some(
             format!(
-                "Sum of splits ({:.2}) cannot exceed transaction amount ({:.2})",
-                total,
-                transaction_amount.abs()
+                "Sum of splits ({}) cannot exceed transaction amount ({})",
+                Money::from(total),
+                Money::from(transaction_amount.abs())
             )
             .into(),
         );
@@ -97,13 +104,239 @@ pub fn validate_splits_sum(
     Ok(())
 }
 
+/// Split `total` equally between `participants`, in minor units
+///
+/// Rounding policy: `total` is first rounded to `minor_units` decimal places
+/// (half-even), every participant receives the total divided by the participant
+/// count rounded towards zero, and the leftover minor units are handed out one
+/// at a time to participants in ascending `person_id` order (see
+/// [`distribute`]). The shares therefore always sum exactly to the rounded
+/// total, differ by at most one minor unit, and do not depend on the order
+/// `participants` are given in.
+///
+/// Shares are returned in ascending `person_id` order and carry the sign of
+/// `total`.
+pub fn split_equally(
+    total: &BigDecimal,
+    participants: &[Uuid],
+    minor_units: i64,
+) -> Vec<(Uuid, BigDecimal)> {
+    let weights: Vec<(Uuid, BigInt)> = participants
+        .iter()
+        .map(|person_id| (*person_id, BigInt::from(1)))
+        .collect();
+    distribute(total, &weights, minor_units)
+}
+
+/// Divide `total` between people in proportion to integer weights, in minor units
+///
+/// `total` is rounded to `minor_units` decimal places (half-even) and every
+/// share is rounded towards zero. The minor units left over go one at a time to
+/// people with a non-zero weight in ascending `person_id` order, so the shares
+/// add up exactly to the rounded total and do not depend on the order `weights`
+/// are given in.
+///
+/// Shares are returned in ascending `person_id` order and carry the sign of
+/// `total`.
+fn distribute(
+    total: &BigDecimal,
+    weights: &[(Uuid, BigInt)],
+    minor_units: i64,
+) -> Vec<(Uuid, BigDecimal)> {
+    let mut ordered = weights.to_vec();
+    ordered.sort_by_key(|(person_id, _)| *person_id);
+
+    let total_weight: BigInt = ordered.iter().map(|(_, weight)| weight).sum();
+    if total_weight.is_zero() {
+        return Vec::new();
+    }
+
+    let (total_units, _) = total
+        .with_scale_round(minor_units, RoundingMode::HalfEven)
+        .into_bigint_and_exponent();
+    // Integer division truncates towards zero, so the remainder has the sign of the total
+    let shares: Vec<(Uuid, BigInt, bool)> = ordered
+        .iter()
+        .map(|(person_id, weight)| {
+            (
+                *person_id,
+                &total_units * weight / &total_weight,
+                !weight.is_zero(),
+            )
+        })
+        .collect();
+    let allocated: BigInt = shares.iter().map(|(_, units, _)| units).sum();
+    let remainder = &total_units - allocated;
+    // Each truncated share loses less than one unit, so there are fewer leftover
+    // units than people with a weight
+    let mut leftover = remainder.abs();
+    let step = remainder.signum();
+
+    shares
+        .into_iter()
+        .map(|(person_id, units, weighted)| {
+            let units = if weighted && leftover.is_positive() {
+                leftover -= 1;
+                units + &step
+            } else {
+                units
+            };
+            (person_id, BigDecimal::new(units, minor_units))
+        })
+        .collect()
+}
+
+/// How a transaction's amount is divided between split participants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SplitStrategy {
+    /// Everyone pays the same amount
+    Equal,
+    /// Everyone pays their `percentage`; the percentages add up to 100
+    Percentage,
+    /// Everyone pays in proportion to their number of `shares`
+    Shares,
+}
+
+/// Person taking part in a split computed by a [`SplitStrategy`]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SplitParticipant {
+    pub person_id: Uuid,
+
+    /// Percentage of the amount the person pays, for `PERCENTAGE`
+    #[validate(range(
+        exclusive_min = 0.0,
+        max = 100.0,
+        message = "Percentage must be greater than 0 and at most 100"
+    ))]
+    pub percentage: Option<f64>,
+
+    /// Number of shares the person pays, for `SHARES`
+    #[validate(range(min = 1, message = "Shares must be at least 1"))]
+    pub shares: Option<u32>,
+}
+
+/// Check that participants fit a split strategy
+///
+/// Participants must be distinct and give exactly the weight their strategy
+/// uses; percentages must add up to 100.
+pub fn validate_split_participants(
+    strategy: SplitStrategy,
+    participants: &[SplitParticipant],
+) -> Result<(), validator::ValidationError> {
+    let invalid = |code: &'static str, message: &'static str| {
+        let mut error = validator::ValidationError::new(code);
+        error.message = Some(message.into());
+        error
+    };
+
+    let mut person_ids = std::collections::HashSet::new();
+    if participants.is_empty() || !participants.iter().all(|p| person_ids.insert(p.person_id)) {
+        return Err(invalid(
+            "invalid_split_participants",
+            "participants must list distinct people",
+        ));
+    }
+
+    let (percentages, shares) = match strategy {
+        SplitStrategy::Equal => (false, false),
+        SplitStrategy::Percentage => (true, false),
+        SplitStrategy::Shares => (false, true),
+    };
+    if participants
+        .iter()
+        .any(|p| p.percentage.is_some() != percentages || p.shares.is_some() != shares)
+    {
+        return Err(invalid(
+            "invalid_split_weights",
+            "Give a percentage for every participant with PERCENTAGE, shares with SHARES, \
+             and neither with EQUAL",
+        ));
+    }
+
+    if strategy == SplitStrategy::Percentage {
+        let total: BigDecimal = participants
+            .iter()
+            .filter_map(|p| p.percentage.and_then(percentage_to_decimal))
+            .sum();
+        if total != 100 {
+            return Err(invalid(
+                "percentages_not_100",
+                "Participant percentages must add up to 100",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Percentage as a decimal with two places, e.g. `33.33`
+fn percentage_to_decimal(percentage: f64) -> Option<BigDecimal> {
+    BigDecimal::try_from(percentage)
+        .ok()
+        .map(|p| p.with_scale_round(2, RoundingMode::HalfEven))
+}
+
+/// Divide `total` between participants by a split strategy, in minor units
+///
+/// Rounding follows [`split_equally`]: `total` is rounded to `minor_units`
+/// decimal places (half-even), every participant's share is rounded towards
+/// zero, and the leftover minor units are handed out one at a time in ascending
+/// `person_id` order. An `EQUAL` split therefore matches [`split_equally`]
+/// between the same people.
+///
+/// Shares are returned in the order participants are given in, carry the sign
+/// of `total`, and zero shares are left out. Participants are expected to have
+/// passed [`validate_split_participants`].
+pub fn split_by_strategy(
+    total: &BigDecimal,
+    strategy: SplitStrategy,
+    participants: &[SplitParticipant],
+    minor_units: i64,
+) -> Vec<(Uuid, BigDecimal)> {
+    // Integer weights, so the arithmetic stays in integers
+    let weights: Vec<(Uuid, BigInt)> = participants
+        .iter()
+        .map(|participant| {
+            let weight = match strategy {
+                SplitStrategy::Equal => BigInt::from(1),
+                // Hundredths of a percent
+                SplitStrategy::Percentage => participant
+                    .percentage
+                    .and_then(percentage_to_decimal)
+                    .map(|p| p.into_bigint_and_exponent().0)
+                    .unwrap_or_default(),
+                SplitStrategy::Shares => BigInt::from(participant.shares.unwrap_or_default()),
+            };
+            (participant.person_id, weight)
+        })
+        .collect();
+    let mut shares: HashMap<Uuid, BigDecimal> = distribute(total, &weights, minor_units)
+        .into_iter()
+        .collect();
+
+    participants
+        .iter()
+        .filter_map(|participant| {
+            shares
+                .remove(&participant.person_id)
+                .map(|share| (participant.person_id, share))
+        })
+        .filter(|(_, share)| !share.is_zero())
+        .collect()
+}
+
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TransactionSplitResponse {
     pub id: Uuid,
     pub person_id: Uuid,
-    /// BigDecimal as string for JSON serialization
-    pub amount: String,
+    pub amount: Money,
+    /// When the split was settled locally; settled splits no longer count towards debt
+    pub settled_at: Option<DateTime<Utc>>,
+    /// Sync state on each split provider the split was synced to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sync: Vec<SplitSyncState>,
 }
 
 impl From<TransactionSplit> for TransactionSplitResponse {
@@ -111,7 +344,9 @@ impl From<TransactionSplit> for TransactionSplitResponse {
         TransactionSplitResponse {
             id: split.id,
             person_id: split.person_id,
-            amount: format!("{:.2}", split.amount),
+            amount: Money::from(split.amount),
+            settled_at: split.settled_at,
+            sync: Vec::new(),
         }
     }
 }
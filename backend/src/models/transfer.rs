@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::transaction::TransactionResponse;
+use crate::types::Money;
+
+// Request DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_transfer_request"))]
+pub struct CreateTransferRequest {
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+
+    /// Amount leaving the source account, in its currency; must be positive
+    #[validate(custom(function = "validate_amount_positive"))]
+    pub amount: Money,
+
+    pub date: DateTime<Utc>,
+
+    #[validate(length(max = 1000, message = "Notes must not exceed 1000 characters"))]
+    pub notes: Option<String>,
+
+    /// Rate from the source to the destination account currency, for transfers
+    /// between currencies (default: the exchange rate on `date`)
+    #[validate(range(exclusive_min = 0.0, message = "Exchange rate must be greater than 0"))]
+    pub exchange_rate: Option<f64>,
+}
+
+fn validate_amount_positive(amount: &Money) -> Result<(), validator::ValidationError> {
+    if !amount.is_positive() {
+        let mut error = validator::ValidationError::new("amount_not_positive");
+        error.message = Some("Transfer amount must be greater than 0".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_transfer_request(
+    req: &CreateTransferRequest,
+) -> Result<(), validator::ValidationError> {
+    if req.from_account_id == req.to_account_id {
+        let mut error = validator::ValidationError::new("same_account");
+        error.message = Some("Cannot transfer to the same account".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Response DTOs
+/// Both legs of a transfer between accounts
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransferResponse {
+    /// Shared by both legs
+    pub transfer_id: Uuid,
+    /// Outgoing leg on the source account, with a negative amount
+    pub from_transaction: TransactionResponse,
+    /// Incoming leg on the destination account, with a positive amount; between
+    /// currencies it records the original amount and the exchange rate used
+    pub to_transaction: TransactionResponse,
+}
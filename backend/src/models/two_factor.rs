@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::two_factor_recovery_codes;
+
+/// Single-use code for logging in without the authenticator app
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = two_factor_recovery_codes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    /// Set once the code has been used to log in
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = two_factor_recovery_codes)]
+pub struct NewRecoveryCode {
+    pub user_id: Uuid,
+    pub code_hash: String,
+}
+
+// Request DTOs
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+pub struct SetupTwoFactorRequest {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+pub struct VerifyTwoFactorRequest {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+    /// Current code from the authenticator app
+    #[validate(length(min = 1))]
+    pub code: String,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 TOTP secret, for entering into the authenticator app by hand
+    pub secret: String,
+    /// `otpauth://` URI for the authenticator app, usually shown as a QR code
+    pub otpauth_uri: String,
+    /// Single-use codes for logging in without the app; they are only shown once
+    pub recovery_codes: Vec<String>,
+}
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::schema::users;
+use crate::types::CurrencyCode;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
 #[diesel(table_name = users)]
@@ -17,6 +19,14 @@ pub struct User {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// TOTP secret from the last 2FA setup, encrypted with `encrypt_credentials`
+    #[serde(skip_serializing, default)]
+    pub totp_secret_encrypted: Option<String>,
+    /// Whether login requires a code, set once a code from the secret is verified
+    pub two_factor_enabled: bool,
+    /// Time step of the last TOTP code accepted, which can't be used again
+    #[serde(skip_serializing, default)]
+    pub totp_last_step: Option<i64>,
 }
 
 #[derive(Debug, Insertable)]
@@ -44,7 +54,7 @@ pub struct UpdateUser {
 }
 
 // Request DTOs
-#[derive(Debug, Serialize, Deserialize, validator::Validate)]
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
 pub struct CreateUserRequest {
     #[validate(length(min = 3, max = 50))]
     pub username: String,
@@ -54,18 +64,57 @@ pub struct CreateUserRequest {
     pub password: String,
     #[validate(length(min = 1, max = 100))]
     pub name: String,
+    /// Starter data to create with the user; omitted settings use the server defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<RegistrationPreferences>,
 }
 
-#[derive(Debug, Serialize, Deserialize, validator::Validate)]
+// Query DTOs
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RegisterQuery {
+    /// Start with no starter data, whatever the preferences and server defaults
+    #[serde(default)]
+    pub skip_defaults: bool,
+}
+
+/// Starter preferences chosen at registration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RegistrationPreferences {
+    /// Currency of the seeded cash account
+    pub default_currency: Option<CurrencyCode>,
+    /// Create a cash account
+    pub create_cash_account: Option<bool>,
+    /// Create a default set of categories
+    pub create_default_categories: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
 pub struct LoginRequest {
     #[validate(email)]
     pub email: String,
     #[validate(length(min = 1))]
     pub password: String,
+    /// Code from the authenticator app, or a recovery code, for users with 2FA enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two_factor_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+    /// Subject to the same rules as the password chosen at registration
+    #[validate(length(min = 8))]
+    pub new_password: String,
+    /// Refresh token of the current session, which stays valid; every other
+    /// refresh token of the user is revoked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 // Response DTOs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
@@ -86,8 +135,26 @@ impl From<User> for UserResponse {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AuthResponse {
+    /// Access token sent as `Authorization: Bearer <token>`
     pub token: String,
+    /// Token for `POST /auth/refresh` to get a new access token; it can be used once
+    pub refresh_token: String,
     pub user: UserResponse,
 }
+
+/// Response to a login without a code from a user with 2FA enabled
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorChallenge {
+    /// Always true: repeat the login with `two_factor_code`
+    pub requires_2fa: bool,
+}
+
+/// Result of a login: tokens, or a challenge for a two-factor code
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AuthResponse),
+    TwoFactorRequired(TwoFactorChallenge),
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::webhook_events;
+
+/// What was done with a webhook delivery
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookOutcome {
+    /// The event was applied to the local data it refers to
+    Applied,
+    /// Not an event we handle; stored for inspection
+    Unrecognized,
+    /// No local data refers to the event's subject; stored for inspection
+    Unmatched,
+}
+
+impl WebhookOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookOutcome::Applied => "applied",
+            WebhookOutcome::Unrecognized => "unrecognized",
+            WebhookOutcome::Unmatched => "unmatched",
+        }
+    }
+}
+
+/// Webhook delivery that could not be applied
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = webhook_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    /// Provider that sent the event, e.g. `splitwise`
+    pub provider_type: String,
+    pub event_type: Option<String>,
+    /// ID of the event's subject on the provider, e.g. the expense ID
+    pub external_id: Option<String>,
+    /// `unrecognized` or `unmatched`
+    pub status: String,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_events)]
+pub struct NewWebhookEvent {
+    pub provider_type: String,
+    pub event_type: Option<String>,
+    pub external_id: Option<String>,
+    pub status: String,
+    pub payload: serde_json::Value,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    pub outcome: WebhookOutcome,
+}
@@ -1,19 +1,30 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
 use crate::{
     DbPool,
     errors::ApiError,
-    models::account::{Account, NewAccount, UpdateAccount},
+    models::{
+        AuditEntityType, NewTransaction, Pagination, Transaction,
+        account::{Account, NewAccount, UpdateAccount},
+    },
+    repositories::audit_log,
     schema::{accounts, transactions},
+    types::{CurrencyCode, Granularity, TransactionStatus},
 };
 
-/// Create a new account
+/// Create a new account together with its opening balance transaction
+///
+/// The account and the opening balance (whose `account_id` is set to the new
+/// account's) are created in one database transaction, so a failure leaves
+/// neither behind.
 pub async fn create_account(
     pool: &DbPool,
     user_id: Uuid,
     new_account: NewAccount,
+    opening_balance: Option<NewTransaction>,
 ) -> Result<Account, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
@@ -21,13 +32,51 @@ pub async fn create_account(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(accounts::table)
-            .values(&new_account)
-            .get_result(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to create account for user {}: {}", user_id, e);
-                ApiError::from(e)
-            })
+        conn.transaction(|conn| {
+            let account: Account = diesel::insert_into(accounts::table)
+                .values(&new_account)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create account for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                user_id,
+                AuditEntityType::Account,
+                account.id,
+                None,
+                Some(&account),
+            )?;
+
+            if let Some(opening_balance) = opening_balance {
+                let opening_balance = NewTransaction {
+                    account_id: account.id,
+                    ..opening_balance
+                };
+                let transaction: Transaction = diesel::insert_into(transactions::table)
+                    .values(&opening_balance)
+                    .get_result(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to create opening balance of account {}: {}",
+                            account.id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+                audit_log::record(
+                    conn,
+                    user_id,
+                    AuditEntityType::Transaction,
+                    transaction.id,
+                    None,
+                    Some(&transaction),
+                )?;
+            }
+
+            Ok(account)
+        })
     })
     .await
     .map_err(|e| {
@@ -83,6 +132,130 @@ pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Account>,
     })?
 }
 
+/// List accounts for a user created or modified at or after `since`
+pub async fn list_updated_since(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<Vec<Account>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        accounts::table
+            .filter(accounts::user_id.eq(user_id))
+            .filter(accounts::updated_at.ge(since))
+            .order(accounts::created_at.desc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list updated accounts for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List a page of a user's accounts, optionally only those created or modified
+/// at or after `since`, in the order of [`list_by_user`]
+pub async fn list_page_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+    pagination: Pagination,
+) -> Result<Vec<Account>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = accounts::table
+            .filter(accounts::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(accounts::updated_at.ge(since));
+        }
+        query
+            .order((accounts::created_at.desc(), accounts::id.asc()))
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list accounts for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count a user's accounts, optionally only those created or modified at or after `since`
+pub async fn count_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = accounts::table
+            .filter(accounts::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(accounts::updated_at.ge(since));
+        }
+        query.count().get_result(&mut conn).map_err(|e| {
+            tracing::error!("Failed to count accounts for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the currencies any user's accounts are held in
+pub async fn list_currencies_in_use(pool: &DbPool) -> Result<Vec<CurrencyCode>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        accounts::table
+            .select(accounts::currency)
+            .distinct()
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list account currencies: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Update account
 pub async fn update_account(
     pool: &DbPool,
@@ -95,52 +268,133 @@ pub async fn update_account(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        // Apply updates one at a time
-        if let Some(name) = updates.name {
-            diesel::update(accounts::table.find(account_id))
-                .set(accounts::name.eq(name))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update account name {}: {}", account_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(account_type) = updates.account_type {
-            diesel::update(accounts::table.find(account_id))
-                .set(accounts::type_.eq(account_type))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update account type {}: {}", account_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(currency) = updates.currency {
-            diesel::update(accounts::table.find(account_id))
-                .set(accounts::currency.eq(currency))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update account currency {}: {}", account_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(notes) = updates.notes {
-            diesel::update(accounts::table.find(account_id))
-                .set(accounts::notes.eq(notes))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update account notes {}: {}", account_id, e);
-                    ApiError::from(e)
-                })?;
-        }
+        conn.transaction(|conn| {
+            // Claim the next version first, so a concurrent update of the same version fails
+            bump_version(conn, account_id, updates.expected_version)?;
+            let before: Account = accounts::table.find(account_id).first(conn).map_err(|e| {
+                tracing::error!("Failed to find account by id {}: {}", account_id, e);
+                ApiError::from(e)
+            })?;
 
-        // Return the updated account
-        accounts::table
-            .find(account_id)
-            .first(&mut conn)
-            .map_err(|e| {
+            // Apply updates one at a time
+            if let Some(name) = updates.name {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::name.eq(name))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update account name {}: {}", account_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(account_type) = updates.account_type {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::type_.eq(account_type))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update account type {}: {}", account_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(currency) = updates.currency {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::currency.eq(currency))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update account currency {}: {}", account_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(notes) = updates.notes {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::notes.eq(notes))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update account notes {}: {}", account_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(allow_overdraft) = updates.allow_overdraft {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::allow_overdraft.eq(allow_overdraft))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update account allow_overdraft {}: {}",
+                            account_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(overdraft_limit) = updates.overdraft_limit {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::overdraft_limit.eq(overdraft_limit))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update account overdraft_limit {}: {}",
+                            account_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(credit_limit) = updates.credit_limit {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::credit_limit.eq(credit_limit))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update account credit_limit {}: {}",
+                            account_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+
+            if let Some(interest_rate) = updates.interest_rate {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::interest_rate.eq(interest_rate))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update account interest_rate {}: {}",
+                            account_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(original_principal) = updates.original_principal {
+                diesel::update(accounts::table.find(account_id))
+                    .set(accounts::original_principal.eq(original_principal))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update account original_principal {}: {}",
+                            account_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+
+            // Return the updated account
+            let account: Account = accounts::table.find(account_id).first(conn).map_err(|e| {
                 tracing::error!("Failed to fetch updated account {}: {}", account_id, e);
                 ApiError::from(e)
-            })
+            })?;
+            audit_log::record(
+                conn,
+                account.user_id,
+                AuditEntityType::Account,
+                account_id,
+                Some(&before),
+                Some(&account),
+            )?;
+            Ok(account)
+        })
     })
     .await
     .map_err(|e| {
@@ -149,7 +403,42 @@ pub async fn update_account(
     })?
 }
 
+/// Increment an account's version, provided it is still at `expected_version` (if given)
+///
+/// Fails with [`ApiError::VersionConflict`] when the account has moved past `expected_version`.
+fn bump_version(
+    conn: &mut PgConnection,
+    account_id: Uuid,
+    expected_version: Option<i32>,
+) -> Result<(), ApiError> {
+    let next_version = accounts::version.eq(accounts::version + 1);
+    let updated = match expected_version {
+        Some(version) => diesel::update(
+            accounts::table
+                .find(account_id)
+                .filter(accounts::version.eq(version)),
+        )
+        .set(next_version)
+        .execute(conn),
+        None => diesel::update(accounts::table.find(account_id))
+            .set(next_version)
+            .execute(conn),
+    }
+    .map_err(|e| {
+        tracing::error!("Failed to update version of account {}: {}", account_id, e);
+        ApiError::from(e)
+    })?;
+
+    if updated == 0 && expected_version.is_some() {
+        return Err(ApiError::VersionConflict(None));
+    }
+    Ok(())
+}
+
 /// Delete account
+///
+/// The account's deleted transactions (and their splits) are permanently
+/// deleted with it, in the same DB transaction.
 pub async fn delete_account(pool: &DbPool, account_id: Uuid) -> Result<(), ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
@@ -157,13 +446,30 @@ pub async fn delete_account(pool: &DbPool, account_id: Uuid) -> Result<(), ApiEr
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(accounts::table.find(account_id))
-            .execute(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to delete account {}: {}", account_id, e);
-                ApiError::from(e)
-            })
-            .map(|_| ())
+        conn.transaction(|conn| {
+            let account: Account = accounts::table.find(account_id).first(conn)?;
+
+            diesel::delete(
+                transactions::table
+                    .filter(transactions::account_id.eq(account_id))
+                    .filter(transactions::deleted_at.is_not_null()),
+            )
+            .execute(conn)?;
+
+            diesel::delete(accounts::table.find(account_id)).execute(conn)?;
+            audit_log::record(
+                conn,
+                account.user_id,
+                AuditEntityType::Account,
+                account_id,
+                Some(&account),
+                None,
+            )
+        })
+        .map_err(|e| {
+            tracing::error!("Failed to delete account {}: {}", account_id, e);
+            e
+        })
     })
     .await
     .map_err(|e| {
@@ -172,31 +478,272 @@ pub async fn delete_account(pool: &DbPool, account_id: Uuid) -> Result<(), ApiEr
     })?
 }
 
-/// Calculate account balance from transactions
+/// Calculate an account's balance from its transactions
+///
+/// Balances are never stored: this sum over the non-deleted transactions is the
+/// single source of truth, so edits, deletions and transfers (whose legs are
+/// ordinary signed transactions) are reflected without any bookkeeping. The
+/// opening balance is itself a transaction. The cleared balance counts posted
+/// transactions only; the available balance adds pending ones.
+pub fn compute_balance(
+    conn: &mut PgConnection,
+    account_id: Uuid,
+    include_pending: bool,
+) -> Result<BigDecimal, ApiError> {
+    use diesel::dsl::sum;
+
+    let mut query = transactions::table
+        .filter(transactions::account_id.eq(account_id))
+        .filter(transactions::deleted_at.is_null())
+        .into_boxed();
+    query = if include_pending {
+        query.filter(transactions::status.ne(TransactionStatus::Void))
+    } else {
+        query.filter(transactions::status.eq(TransactionStatus::Posted))
+    };
+
+    let balance: Option<BigDecimal> = query
+        .select(sum(transactions::amount))
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to calculate balance for account {}: {}",
+                account_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+
+    // If no transactions, balance is 0
+    Ok(balance.unwrap_or_else(|| BigDecimal::from(0)))
+}
+
+/// Calculate the cleared account balance from posted transactions
 pub async fn calculate_balance(pool: &DbPool, account_id: Uuid) -> Result<BigDecimal, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
+    tokio::task::spawn_blocking(move || compute_balance(&mut conn, account_id, false))
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::Internal
+        })?
+}
+
+/// Calculate the available account balance from posted and pending transactions
+pub async fn calculate_available_balance(
+    pool: &DbPool,
+    account_id: Uuid,
+) -> Result<BigDecimal, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || compute_balance(&mut conn, account_id, true))
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::Internal
+        })?
+}
+
+/// Summarize an account's posted transactions over a period
+///
+/// Returns `(opening_balance, income, expenses, net_change)`: the balance before
+/// `start`, the sums of positive and negative amounts between `start` and `end`
+/// (inclusive) excluding transfers, and the sum of all amounts in the period,
+/// transfers included. Missing bounds leave the period open on that side.
+pub async fn summarize_period(
+    pool: &DbPool,
+    account_id: Uuid,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<(BigDecimal, BigDecimal, BigDecimal, BigDecimal), ApiError> {
+    use diesel::dsl::{sql, sum};
+    use diesel::sql_types::Numeric;
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
     tokio::task::spawn_blocking(move || {
-        use diesel::dsl::sum;
+        // Both queries read the same snapshot, so opening balance and period totals agree
+        conn.build_transaction()
+            .read_only()
+            .repeatable_read()
+            .run(|conn| {
+                let posted = || {
+                    transactions::table
+                        .filter(transactions::account_id.eq(account_id))
+                        .filter(transactions::status.eq(TransactionStatus::Posted))
+                        .filter(transactions::deleted_at.is_null())
+                        .into_boxed()
+                };
+
+                let opening: Option<BigDecimal> = match start {
+                    Some(start) => posted()
+                        .filter(transactions::date.lt(start))
+                        .select(sum(transactions::amount))
+                        .first(conn)?,
+                    None => None,
+                };
+
+                let mut period = posted();
+                if let Some(start) = start {
+                    period = period.filter(transactions::date.ge(start));
+                }
+                if let Some(end) = end {
+                    period = period.filter(transactions::date.le(end));
+                }
+                let (income, expenses, net_change) = period
+                    .select((
+                        sql::<Numeric>(
+                            "coalesce(sum(amount) filter (where amount > 0 and transfer_id is null), 0)",
+                        ),
+                        sql::<Numeric>(
+                            "coalesce(sum(amount) filter (where amount < 0 and transfer_id is null), 0)",
+                        ),
+                        sql::<Numeric>("coalesce(sum(amount), 0)"),
+                    ))
+                    .first::<(BigDecimal, BigDecimal, BigDecimal)>(conn)?;
+
+                Ok((
+                    opening.unwrap_or_else(|| BigDecimal::from(0)),
+                    income,
+                    expenses,
+                    net_change,
+                ))
+            })
+            .map_err(|e: diesel::result::Error| {
+                tracing::error!("Failed to summarize account {}: {}", account_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Date, in UTC, of an account's first posted transaction, if it has any
+pub async fn first_posted_date(
+    pool: &DbPool,
+    account_id: Uuid,
+) -> Result<Option<NaiveDate>, ApiError> {
+    use diesel::dsl::min;
 
-        let balance: Option<BigDecimal> = transactions::table
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
             .filter(transactions::account_id.eq(account_id))
-            .select(sum(transactions::amount))
-            .first(&mut conn)
+            .filter(transactions::status.eq(TransactionStatus::Posted))
+            .filter(transactions::deleted_at.is_null())
+            .select(min(transactions::date))
+            .first::<Option<DateTime<Utc>>>(&mut conn)
+            .map(|date| date.map(|date| date.date_naive()))
             .map_err(|e| {
                 tracing::error!(
-                    "Failed to calculate balance for account {}: {}",
+                    "Failed to find first transaction of account {}: {}",
                     account_id,
                     e
                 );
                 ApiError::from(e)
-            })?;
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
 
-        // If no transactions, balance is 0
-        Ok(balance.unwrap_or_else(|| BigDecimal::from(0)))
+/// Posted balance at the end of an interval, as computed by [`balance_history`]
+#[derive(Debug, QueryableByName)]
+struct BalanceRow {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    date: NaiveDate,
+    #[diesel(sql_type = diesel::sql_types::Numeric)]
+    balance: BigDecimal,
+}
+
+/// Running posted balance of an account at the end of each interval from `start` to `end`
+///
+/// Each interval is dated by its first day in UTC, and the first starts at the
+/// interval containing `start`. Transactions before it are folded into the
+/// first interval, and those after `end` are left out, so the last balance is
+/// the balance at the end of `end`. Intervals without transactions carry the
+/// previous balance. Balances are summed in the database with a window
+/// function, so transactions are never loaded.
+pub async fn balance_history(
+    pool: &DbPool,
+    account_id: Uuid,
+    start: NaiveDate,
+    end: NaiveDate,
+    granularity: Granularity,
+) -> Result<Vec<(NaiveDate, BigDecimal)>, ApiError> {
+    use diesel::sql_types::{Date, Text, Uuid as SqlUuid};
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::sql_query(
+            "WITH changes AS ( \
+                 SELECT greatest( \
+                            date_trunc($2, date AT TIME ZONE 'UTC'), \
+                            date_trunc($2, $3::timestamp) \
+                        ) AS bucket, \
+                        sum(amount) AS change \
+                 FROM transactions \
+                 WHERE account_id = $1 \
+                   AND status = 'posted' \
+                   AND deleted_at IS NULL \
+                   AND date < ($4 + 1)::timestamp AT TIME ZONE 'UTC' \
+                 GROUP BY 1 \
+             ), \
+             buckets AS ( \
+                 SELECT generate_series( \
+                            date_trunc($2, $3::timestamp), \
+                            date_trunc($2, $4::timestamp), \
+                            ('1 ' || $2)::interval \
+                        ) AS bucket \
+             ) \
+             SELECT buckets.bucket::date AS date, \
+                    coalesce(sum(changes.change) OVER (ORDER BY buckets.bucket), 0) AS balance \
+             FROM buckets \
+             LEFT JOIN changes ON changes.bucket = buckets.bucket \
+             ORDER BY buckets.bucket",
+        )
+        .bind::<SqlUuid, _>(account_id)
+        .bind::<Text, _>(granularity.date_trunc_field())
+        .bind::<Date, _>(start)
+        .bind::<Date, _>(end)
+        .load::<BalanceRow>(&mut conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|row| (row.date, row.balance))
+                .collect()
+        })
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to compute balance history of account {}: {}",
+                account_id,
+                e
+            );
+            ApiError::from(e)
+        })
     })
     .await
     .map_err(|e| {
@@ -205,7 +752,7 @@ pub async fn calculate_balance(pool: &DbPool, account_id: Uuid) -> Result<BigDec
     })?
 }
 
-/// Check if account has any transactions
+/// Check if account has any transactions that are not deleted
 pub async fn has_transactions(pool: &DbPool, account_id: Uuid) -> Result<bool, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
@@ -217,6 +764,7 @@ pub async fn has_transactions(pool: &DbPool, account_id: Uuid) -> Result<bool, A
 
         let count: i64 = transactions::table
             .filter(transactions::account_id.eq(account_id))
+            .filter(transactions::deleted_at.is_null())
             .select(count(transactions::id))
             .first(&mut conn)
             .map_err(|e| {
@@ -236,3 +784,26 @@ pub async fn has_transactions(pool: &DbPool, account_id: Uuid) -> Result<bool, A
         ApiError::Internal
     })?
 }
+
+/// Lock an account row for the rest of the surrounding DB transaction and
+/// return its current available balance (posted and pending transactions)
+///
+/// Writers that validate the resulting balance (e.g. overdraft checks) must hold
+/// this lock while inserting or updating transactions, so that concurrent writes
+/// to the same account are serialized and cannot both pass the check.
+pub fn lock_and_calculate_balance(
+    conn: &mut PgConnection,
+    account_id: Uuid,
+) -> Result<BigDecimal, ApiError> {
+    accounts::table
+        .find(account_id)
+        .select(accounts::id)
+        .for_update()
+        .first::<Uuid>(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to lock account {}: {}", account_id, e);
+            ApiError::from(e)
+        })?;
+
+    compute_balance(conn, account_id, true)
+}
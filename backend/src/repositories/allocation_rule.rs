@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::allocation_rule::{
+        AllocationRule, AllocationRuleDestination, NewAllocationRule, NewAllocationRuleDestination,
+    },
+    schema::{allocation_rule_destinations, allocation_rules},
+};
+
+/// An allocation rule with its destinations
+pub type RuleWithDestinations = (AllocationRule, Vec<AllocationRuleDestination>);
+
+/// Create an allocation rule together with its destinations
+///
+/// `destinations` pairs each destination account with its percentage; the rule
+/// and its destinations are inserted in one DB transaction.
+pub async fn create_rule(
+    pool: &DbPool,
+    new_rule: NewAllocationRule,
+    destinations: Vec<(Uuid, BigDecimal)>,
+) -> Result<RuleWithDestinations, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let rule: AllocationRule = diesel::insert_into(allocation_rules::table)
+                .values(&new_rule)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create allocation rule for user {}: {}",
+                        new_rule.user_id,
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+
+            let destinations: Vec<NewAllocationRuleDestination> = destinations
+                .into_iter()
+                .map(|(account_id, percentage)| NewAllocationRuleDestination {
+                    rule_id: rule.id,
+                    account_id,
+                    percentage,
+                })
+                .collect();
+            let mut destinations: Vec<AllocationRuleDestination> =
+                diesel::insert_into(allocation_rule_destinations::table)
+                    .values(&destinations)
+                    .get_results(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to create destinations of allocation rule {}: {}",
+                            rule.id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            destinations.sort_by_key(|destination| destination.account_id);
+
+            Ok((rule, destinations))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find allocation rule by ID, with its destinations
+pub async fn find_by_id(pool: &DbPool, rule_id: Uuid) -> Result<RuleWithDestinations, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let rule: AllocationRule = allocation_rules::table
+            .find(rule_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find allocation rule by id {}: {}", rule_id, e);
+                ApiError::from(e)
+            })?;
+
+        let mut rules = with_destinations(&mut conn, vec![rule])?;
+        Ok(rules.remove(0))
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List all allocation rules of a user, with their destinations
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<RuleWithDestinations>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let rules = allocation_rules::table
+            .filter(allocation_rules::user_id.eq(user_id))
+            .order(allocation_rules::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list allocation rules for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+
+        with_destinations(&mut conn, rules)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the allocation rules for income landing in an account, oldest first
+pub async fn list_by_source_account(
+    pool: &DbPool,
+    account_id: Uuid,
+) -> Result<Vec<RuleWithDestinations>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let rules = allocation_rules::table
+            .filter(allocation_rules::source_account_id.eq(account_id))
+            .order(allocation_rules::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list allocation rules for account {}: {}",
+                    account_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+
+        with_destinations(&mut conn, rules)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete an allocation rule (its destinations are removed by cascade)
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(allocation_rules::table.find(rule_id))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete allocation rule {}: {}", rule_id, e);
+                ApiError::from(e)
+            })
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Load the destinations of `rules`, in ascending account ID order
+fn with_destinations(
+    conn: &mut PgConnection,
+    rules: Vec<AllocationRule>,
+) -> Result<Vec<RuleWithDestinations>, ApiError> {
+    let rule_ids: Vec<Uuid> = rules.iter().map(|rule| rule.id).collect();
+    let destinations: Vec<AllocationRuleDestination> = allocation_rule_destinations::table
+        .filter(allocation_rule_destinations::rule_id.eq_any(&rule_ids))
+        .order(allocation_rule_destinations::account_id.asc())
+        .load(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to load allocation rule destinations: {}", e);
+            ApiError::from(e)
+        })?;
+
+    let mut by_rule: HashMap<Uuid, Vec<AllocationRuleDestination>> = HashMap::new();
+    for destination in destinations {
+        by_rule
+            .entry(destination.rule_id)
+            .or_default()
+            .push(destination);
+    }
+
+    Ok(rules
+        .into_iter()
+        .map(|rule| {
+            let destinations = by_rule.remove(&rule.id).unwrap_or_default();
+            (rule, destinations)
+        })
+        .collect())
+}
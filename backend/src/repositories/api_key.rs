@@ -203,6 +203,33 @@ pub async fn update_scopes(
     })?
 }
 
+/// Update API key rate limit
+pub async fn update_rate_limit(
+    pool: &DbPool,
+    id: Uuid,
+    requests_per_minute: i32,
+) -> Result<ApiKey, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(api_keys::table.find(id))
+            .set(api_keys::requests_per_minute.eq(requests_per_minute))
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to update API key rate limit {}: {}", id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Revoke an API key (set status to revoked)
 pub async fn revoke(pool: &DbPool, id: Uuid) -> Result<ApiKey, ApiError> {
     let mut conn = pool.get().map_err(|e| {
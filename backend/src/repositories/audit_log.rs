@@ -0,0 +1,230 @@
+use chrono::{SubsecRound, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{AuditAction, AuditEntityType, AuditLogEntry, NewAuditLogEntry, Pagination},
+    schema::audit_log,
+};
+
+/// Fields left out of diffs: identifiers are part of the entry itself, and
+/// timestamps and versions change with every write
+const IGNORED_FIELDS: [&str; 5] = ["id", "user_id", "created_at", "updated_at", "version"];
+
+/// Record a change to an entity in its owner's audit log
+///
+/// `before` and `after` are the entity as stored before and after the change:
+/// `None` before a creation and after a deletion. Only fields whose values
+/// differ are recorded, so an update that changed nothing writes no entry.
+///
+/// Must be called inside the DB transaction making the change, so the entry is
+/// written if and only if the change is. Entries of the same user are
+/// serialized with an advisory lock to keep their hash chain linear.
+pub fn record<T: Serialize>(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    entity_type: AuditEntityType,
+    entity_id: Uuid,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), ApiError> {
+    let action = match (before, after) {
+        (None, Some(_)) => AuditAction::Create,
+        (Some(_), Some(_)) => AuditAction::Update,
+        (Some(_), None) => AuditAction::Delete,
+        (None, None) => return Ok(()),
+    };
+    let changes = diff(to_json(before)?, to_json(after)?);
+    if action == AuditAction::Update && changes.is_empty() {
+        return Ok(());
+    }
+    let changes = Value::Object(changes);
+
+    diesel::sql_query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+        .bind::<diesel::sql_types::Text, _>(format!("audit_log:{}", user_id))
+        .execute(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to lock audit log of user {}: {}", user_id, e);
+            ApiError::from(e)
+        })?;
+
+    let previous_hash: Option<String> = audit_log::table
+        .filter(audit_log::user_id.eq(user_id))
+        .order(audit_log::sequence.desc())
+        .select(audit_log::hash)
+        .first(conn)
+        .optional()
+        .map_err(|e| {
+            tracing::error!("Failed to find last audit entry of user {}: {}", user_id, e);
+            ApiError::from(e)
+        })?;
+
+    // Stored with microsecond precision, so hash exactly what is stored
+    let created_at = Utc::now().trunc_subsecs(6);
+    let hash = AuditLogEntry::compute_hash(
+        previous_hash.as_deref(),
+        user_id,
+        entity_type.as_str(),
+        entity_id,
+        action.as_str(),
+        &changes,
+        created_at,
+    );
+
+    diesel::insert_into(audit_log::table)
+        .values(&NewAuditLogEntry {
+            user_id,
+            entity_type: entity_type.as_str().to_string(),
+            entity_id,
+            action: action.as_str().to_string(),
+            changes,
+            previous_hash,
+            hash,
+            created_at,
+        })
+        .execute(conn)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to record audit entry for {} {}: {}",
+                entity_type.as_str(),
+                entity_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+
+    Ok(())
+}
+
+fn to_json<T: Serialize>(entity: Option<&T>) -> Result<Option<Map<String, Value>>, ApiError> {
+    entity
+        .map(|entity| match serde_json::to_value(entity) {
+            Ok(Value::Object(fields)) => Ok(fields),
+            Ok(_) => Err(ApiError::Internal),
+            Err(e) => {
+                tracing::error!("Failed to serialize entity for audit log: {}", e);
+                Err(ApiError::Internal)
+            }
+        })
+        .transpose()
+}
+
+/// Fields that differ between `before` and `after`, as `{"old": ..., "new": ...}`
+///
+/// A creation lists the new values of its non-null fields and a deletion the
+/// old ones.
+fn diff(
+    before: Option<Map<String, Value>>,
+    after: Option<Map<String, Value>>,
+) -> Map<String, Value> {
+    let before = before.unwrap_or_default();
+    let after = after.unwrap_or_default();
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut changes = Map::new();
+    for field in fields {
+        if IGNORED_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        let old = before.get(field).filter(|value| !value.is_null());
+        let new = after.get(field).filter(|value| !value.is_null());
+        if old == new {
+            continue;
+        }
+
+        let mut change = Map::new();
+        if !before.is_empty() {
+            change.insert("old".to_string(), old.cloned().unwrap_or(Value::Null));
+        }
+        if !after.is_empty() {
+            change.insert("new".to_string(), new.cloned().unwrap_or(Value::Null));
+        }
+        changes.insert(field.clone(), json!(change));
+    }
+    changes
+}
+
+/// Entries of `user_id` about `entity_types`, optionally only about `entity_id`
+fn filtered_query<'a>(
+    user_id: Uuid,
+    entity_types: &[AuditEntityType],
+    entity_id: Option<Uuid>,
+) -> audit_log::BoxedQuery<'a, diesel::pg::Pg> {
+    let entity_types: Vec<&'static str> = entity_types.iter().map(|t| t.as_str()).collect();
+    let mut query = audit_log::table
+        .filter(audit_log::user_id.eq(user_id))
+        .filter(audit_log::entity_type.eq_any(entity_types))
+        .into_boxed();
+    if let Some(entity_id) = entity_id {
+        query = query.filter(audit_log::entity_id.eq(entity_id));
+    }
+    query
+}
+
+/// List a user's audit entries, newest first
+///
+/// Only entries about `entity_types` are listed, optionally only those about
+/// `entity_id`.
+pub async fn list(
+    pool: &DbPool,
+    user_id: Uuid,
+    entity_types: Vec<AuditEntityType>,
+    entity_id: Option<Uuid>,
+    pagination: Pagination,
+) -> Result<Vec<AuditLogEntry>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        filtered_query(user_id, &entity_types, entity_id)
+            .order(audit_log::sequence.desc())
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list audit entries of user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count the audit entries [`list`] pages through
+pub async fn count(
+    pool: &DbPool,
+    user_id: Uuid,
+    entity_types: Vec<AuditEntityType>,
+    entity_id: Option<Uuid>,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        filtered_query(user_id, &entity_types, entity_id)
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to count audit entries of user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
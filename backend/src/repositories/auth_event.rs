@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{AuthEvent, NewAuthEvent},
+    schema::auth_events,
+};
+
+/// Record a new authentication event
+pub async fn create(pool: &DbPool, new_event: NewAuthEvent) -> Result<AuthEvent, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(auth_events::table)
+            .values(&new_event)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to create auth event: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find the most recent authentication events for a user
+pub async fn find_recent_by_user_id(
+    pool: &DbPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<AuthEvent>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        auth_events::table
+            .filter(auth_events::user_id.eq(user_id))
+            .order(auth_events::created_at.desc())
+            .limit(limit)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find auth events for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete all authentication events created before the cutoff
+///
+/// Returns the number of deleted events.
+pub async fn delete_older_than(pool: &DbPool, cutoff: DateTime<Utc>) -> Result<usize, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(auth_events::table.filter(auth_events::created_at.lt(cutoff)))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to purge auth events: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -1,4 +1,4 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
@@ -6,9 +6,11 @@ use crate::{
     DbPool,
     errors::ApiError,
     models::{
+        AuditEntityType, Pagination,
         budget::{Budget, NewBudget, UpdateBudget},
         budget_range::{BudgetRange, NewBudgetRange},
     },
+    repositories::audit_log,
     schema::{budget_ranges, budgets},
 };
 
@@ -24,13 +26,24 @@ pub async fn create_budget(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(budgets::table)
-            .values(&new_budget)
-            .get_result(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to create budget for user {}: {}", user_id, e);
-                ApiError::from(e)
-            })
+        conn.transaction(|conn| {
+            let budget: Budget = diesel::insert_into(budgets::table)
+                .values(&new_budget)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create budget for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                user_id,
+                AuditEntityType::Budget,
+                budget.id,
+                None,
+                Some(&budget),
+            )?;
+            Ok(budget)
+        })
     })
     .await
     .map_err(|e| {
@@ -86,6 +99,102 @@ pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Budget>, A
     })?
 }
 
+/// List budgets for a user created or modified at or after `since`
+pub async fn list_updated_since(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<Vec<Budget>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        budgets::table
+            .filter(budgets::user_id.eq(user_id))
+            .filter(budgets::updated_at.ge(since))
+            .order(budgets::created_at.desc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list updated budgets for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List a page of a user's budgets, optionally only those created or modified
+/// at or after `since`, in the order of [`list_by_user`]
+pub async fn list_page_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+    pagination: Pagination,
+) -> Result<Vec<Budget>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = budgets::table
+            .filter(budgets::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(budgets::updated_at.ge(since));
+        }
+        query
+            .order((budgets::created_at.desc(), budgets::id.asc()))
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list budgets for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count a user's budgets, optionally only those created or modified at or after `since`
+pub async fn count_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = budgets::table
+            .filter(budgets::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(budgets::updated_at.ge(since));
+        }
+        query.count().get_result(&mut conn).map_err(|e| {
+            tracing::error!("Failed to count budgets for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Update budget
 pub async fn update_budget(
     pool: &DbPool,
@@ -98,34 +207,182 @@ pub async fn update_budget(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        // Apply updates one at a time
-        if let Some(name) = updates.name {
-            diesel::update(budgets::table.find(budget_id))
-                .set(budgets::name.eq(name))
-                .execute(&mut conn)
+        conn.transaction(|conn| {
+            // Claim the next version first, so a concurrent update of the same version fails
+            bump_version(conn, budget_id, updates.expected_version)?;
+            let before = find_for_audit(conn, budget_id)?;
+
+            // Apply updates one at a time
+            if let Some(name) = updates.name {
+                diesel::update(budgets::table.find(budget_id))
+                    .set(budgets::name.eq(name))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update budget name {}: {}", budget_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(filters) = updates.filters {
+                // New filters reactivate a budget deactivated with its category
+                diesel::update(budgets::table.find(budget_id))
+                    .set((
+                        budgets::filters.eq(filters),
+                        budgets::deactivated_at.eq(None::<DateTime<Utc>>),
+                    ))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update budget filters {}: {}", budget_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(alert_thresholds) = updates.alert_thresholds {
+                diesel::update(budgets::table.find(budget_id))
+                    .set(budgets::alert_thresholds.eq(alert_thresholds))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update budget alert thresholds {}: {}",
+                            budget_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+
+            // Return the updated budget
+            let budget: Budget = budgets::table.find(budget_id).first(conn).map_err(|e| {
+                tracing::error!("Failed to fetch updated budget {}: {}", budget_id, e);
+                ApiError::from(e)
+            })?;
+            record_update(conn, &before, &budget)?;
+            Ok(budget)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Increment a budget's version, provided it is still at `expected_version` (if given)
+///
+/// Fails with [`ApiError::VersionConflict`] when the budget has moved past `expected_version`.
+fn bump_version(
+    conn: &mut PgConnection,
+    budget_id: Uuid,
+    expected_version: Option<i32>,
+) -> Result<(), ApiError> {
+    let next_version = budgets::version.eq(budgets::version + 1);
+    let updated = match expected_version {
+        Some(version) => diesel::update(
+            budgets::table
+                .find(budget_id)
+                .filter(budgets::version.eq(version)),
+        )
+        .set(next_version)
+        .execute(conn),
+        None => diesel::update(budgets::table.find(budget_id))
+            .set(next_version)
+            .execute(conn),
+    }
+    .map_err(|e| {
+        tracing::error!("Failed to update version of budget {}: {}", budget_id, e);
+        ApiError::from(e)
+    })?;
+
+    if updated == 0 && expected_version.is_some() {
+        return Err(ApiError::VersionConflict(None));
+    }
+    Ok(())
+}
+
+/// Lock a budget and read it as stored before a change, for its audit entry
+fn find_for_audit(conn: &mut PgConnection, budget_id: Uuid) -> Result<Budget, ApiError> {
+    budgets::table
+        .find(budget_id)
+        .for_update()
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to find budget by id {}: {}", budget_id, e);
+            ApiError::from(e)
+        })
+}
+
+/// Record an update of a budget, made with `conn` inside a DB transaction
+fn record_update(conn: &mut PgConnection, before: &Budget, after: &Budget) -> Result<(), ApiError> {
+    audit_log::record(
+        conn,
+        after.user_id,
+        AuditEntityType::Budget,
+        after.id,
+        Some(before),
+        Some(after),
+    )
+}
+
+/// Mute or unmute a budget's alerts
+pub async fn set_alerts_muted(
+    pool: &DbPool,
+    budget_id: Uuid,
+    muted: bool,
+) -> Result<Budget, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let before = find_for_audit(conn, budget_id)?;
+            let budget: Budget = diesel::update(budgets::table.find(budget_id))
+                .set((
+                    budgets::alerts_muted.eq(muted),
+                    budgets::version.eq(budgets::version + 1),
+                ))
+                .get_result(conn)
                 .map_err(|e| {
-                    tracing::error!("Failed to update budget name {}: {}", budget_id, e);
+                    tracing::error!("Failed to set alert muting of budget {}: {}", budget_id, e);
                     ApiError::from(e)
                 })?;
-        }
-        if let Some(filters) = updates.filters {
-            diesel::update(budgets::table.find(budget_id))
-                .set(budgets::filters.eq(filters))
-                .execute(&mut conn)
+            record_update(conn, &before, &budget)?;
+            Ok(budget)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Snooze a budget's alerts until the given time, or end the snooze with `None`
+pub async fn set_alerts_snoozed_until(
+    pool: &DbPool,
+    budget_id: Uuid,
+    until: Option<DateTime<Utc>>,
+) -> Result<Budget, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let before = find_for_audit(conn, budget_id)?;
+            let budget: Budget = diesel::update(budgets::table.find(budget_id))
+                .set((
+                    budgets::alerts_snoozed_until.eq(until),
+                    budgets::version.eq(budgets::version + 1),
+                ))
+                .get_result(conn)
                 .map_err(|e| {
-                    tracing::error!("Failed to update budget filters {}: {}", budget_id, e);
+                    tracing::error!("Failed to snooze alerts of budget {}: {}", budget_id, e);
                     ApiError::from(e)
                 })?;
-        }
-
-        // Return the updated budget
-        budgets::table
-            .find(budget_id)
-            .first(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to fetch updated budget {}: {}", budget_id, e);
-                ApiError::from(e)
-            })
+            record_update(conn, &before, &budget)?;
+            Ok(budget)
+        })
     })
     .await
     .map_err(|e| {
@@ -142,13 +399,23 @@ pub async fn delete_budget(pool: &DbPool, budget_id: Uuid) -> Result<(), ApiErro
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(budgets::table.find(budget_id))
-            .execute(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to delete budget {}: {}", budget_id, e);
-                ApiError::from(e)
-            })
-            .map(|_| ())
+        conn.transaction(|conn| {
+            let budget = find_for_audit(conn, budget_id)?;
+            diesel::delete(budgets::table.find(budget_id))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to delete budget {}: {}", budget_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                budget.user_id,
+                AuditEntityType::Budget,
+                budget_id,
+                Some(&budget),
+                None,
+            )
+        })
     })
     .await
     .map_err(|e| {
@@ -225,6 +492,44 @@ pub async fn get_active_range(
     })?
 }
 
+/// Get the ranges active on a date for several budgets
+///
+/// When a budget has overlapping active ranges, only the one that started last is
+/// returned.
+pub async fn list_active_ranges(
+    pool: &DbPool,
+    budget_ids: Vec<Uuid>,
+    date: NaiveDate,
+) -> Result<Vec<BudgetRange>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        budget_ranges::table
+            .filter(budget_ranges::budget_id.eq_any(budget_ids))
+            .filter(budget_ranges::start_date.le(date))
+            .filter(
+                budget_ranges::end_date
+                    .is_null()
+                    .or(budget_ranges::end_date.ge(date)),
+            )
+            .distinct_on(budget_ranges::budget_id)
+            .order((budget_ranges::budget_id, budget_ranges::start_date.desc()))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list active budget ranges on {}: {}", date, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// List all ranges for a budget
 pub async fn list_ranges_for_budget(
     pool: &DbPool,
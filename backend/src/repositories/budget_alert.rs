@@ -0,0 +1,131 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{BudgetAlert, NewBudgetAlert},
+    schema::{budget_alerts, budgets},
+};
+
+/// Record budget alerts, skipping crossings that were already recorded
+///
+/// Returns the alerts created.
+pub async fn create_alerts(
+    pool: &DbPool,
+    alerts: Vec<NewBudgetAlert>,
+) -> Result<Vec<BudgetAlert>, ApiError> {
+    if alerts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(budget_alerts::table)
+            .values(&alerts)
+            .on_conflict((budget_alerts::budget_range_id, budget_alerts::threshold))
+            .do_nothing()
+            .returning(BudgetAlert::as_returning())
+            .get_results(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to record budget alerts: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find budget alert by ID, with the name of its budget
+pub async fn find_by_id(pool: &DbPool, alert_id: Uuid) -> Result<(BudgetAlert, String), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        budget_alerts::table
+            .inner_join(budgets::table)
+            .filter(budget_alerts::id.eq(alert_id))
+            .select((BudgetAlert::as_select(), budgets::name))
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find budget alert by id {}: {}", alert_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List a user's budget alerts, newest first, with the names of their budgets
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    unread_only: bool,
+) -> Result<Vec<(BudgetAlert, String)>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = budget_alerts::table
+            .inner_join(budgets::table)
+            .filter(budget_alerts::user_id.eq(user_id))
+            .select((BudgetAlert::as_select(), budgets::name))
+            .order((budget_alerts::triggered_at.desc(), budget_alerts::id.asc()))
+            .into_boxed();
+        if unread_only {
+            query = query.filter(budget_alerts::read_at.is_null());
+        }
+
+        query.load(&mut conn).map_err(|e| {
+            tracing::error!("Failed to list budget alerts for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Mark a budget alert read, keeping the time of an earlier acknowledgement
+pub async fn mark_read(pool: &DbPool, alert_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            budget_alerts::table
+                .find(alert_id)
+                .filter(budget_alerts::read_at.is_null()),
+        )
+        .set(budget_alerts::read_at.eq(diesel::dsl::now))
+        .execute(&mut conn)
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::error!("Failed to mark budget alert {} read: {}", alert_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
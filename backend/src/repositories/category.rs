@@ -1,11 +1,19 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{
     DbPool,
     errors::ApiError,
-    models::category::{Category, NewCategory, UpdateCategory},
-    schema::categories,
+    models::{
+        AuditEntityType, Budget, Pagination, Transaction,
+        category::{Category, CategoryReassignment, NewCategory, UpdateCategory},
+    },
+    repositories::audit_log,
+    schema::{
+        allocation_rules, budgets, categories, category_rules, recurring_transactions, transactions,
+    },
 };
 
 /// Create a new category
@@ -20,13 +28,24 @@ pub async fn create_category(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(categories::table)
-            .values(&new_category)
-            .get_result(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to create category for user {}: {}", user_id, e);
-                ApiError::from(e)
-            })
+        conn.transaction(|conn| {
+            let category: Category = diesel::insert_into(categories::table)
+                .values(&new_category)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create category for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                user_id,
+                AuditEntityType::Category,
+                category.id,
+                None,
+                Some(&category),
+            )?;
+            Ok(category)
+        })
     })
     .await
     .map_err(|e| {
@@ -82,62 +101,29 @@ pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Category>,
     })?
 }
 
-/// Update category
-pub async fn update_category(
+/// List categories for a user created or modified at or after `since`
+pub async fn list_updated_since(
     pool: &DbPool,
-    category_id: Uuid,
-    updates: UpdateCategory,
-) -> Result<Category, ApiError> {
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<Vec<Category>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        // Apply updates one at a time
-        if let Some(name) = updates.name {
-            diesel::update(categories::table.find(category_id))
-                .set(categories::name.eq(name))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update category name {}: {}", category_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(icon) = updates.icon {
-            diesel::update(categories::table.find(category_id))
-                .set(categories::icon.eq(icon))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update category icon {}: {}", category_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(color) = updates.color {
-            diesel::update(categories::table.find(category_id))
-                .set(categories::color.eq(color))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update category color {}: {}", category_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(parent_id) = updates.parent_id {
-            diesel::update(categories::table.find(category_id))
-                .set(categories::parent_id.eq(parent_id))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update category parent_id {}: {}", category_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-
-        // Return the updated category
         categories::table
-            .find(category_id)
-            .first(&mut conn)
+            .filter(categories::user_id.eq(user_id))
+            .filter(categories::updated_at.ge(since))
+            .order(categories::name.asc())
+            .load(&mut conn)
             .map_err(|e| {
-                tracing::error!("Failed to fetch updated category {}: {}", category_id, e);
+                tracing::error!(
+                    "Failed to list updated categories for user {}: {}",
+                    user_id,
+                    e
+                );
                 ApiError::from(e)
             })
     })
@@ -148,21 +134,35 @@ pub async fn update_category(
     })?
 }
 
-/// Delete category
-pub async fn delete_category(pool: &DbPool, category_id: Uuid) -> Result<(), ApiError> {
+/// List a page of a user's categories, optionally only those created or modified
+/// at or after `since`, in the order of [`list_by_user`]
+pub async fn list_page_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+    pagination: Pagination,
+) -> Result<Vec<Category>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(categories::table.find(category_id))
-            .execute(&mut conn)
+        let mut query = categories::table
+            .filter(categories::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(categories::updated_at.ge(since));
+        }
+        query
+            .order((categories::name.asc(), categories::id.asc()))
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(&mut conn)
             .map_err(|e| {
-                tracing::error!("Failed to delete category {}: {}", category_id, e);
+                tracing::error!("Failed to list categories for user {}: {}", user_id, e);
                 ApiError::from(e)
             })
-            .map(|_| ())
     })
     .await
     .map_err(|e| {
@@ -170,3 +170,307 @@ pub async fn delete_category(pool: &DbPool, category_id: Uuid) -> Result<(), Api
         ApiError::Internal
     })?
 }
+
+/// Count a user's categories, optionally only those created or modified at or after `since`
+pub async fn count_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = categories::table
+            .filter(categories::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(categories::updated_at.ge(since));
+        }
+        query.count().get_result(&mut conn).map_err(|e| {
+            tracing::error!("Failed to count categories for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Update category
+pub async fn update_category(
+    pool: &DbPool,
+    category_id: Uuid,
+    updates: UpdateCategory,
+) -> Result<Category, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let before = find_for_audit(conn, category_id)?;
+
+            // Apply updates one at a time
+            if let Some(name) = updates.name {
+                diesel::update(categories::table.find(category_id))
+                    .set(categories::name.eq(name))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update category name {}: {}", category_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(icon) = updates.icon {
+                diesel::update(categories::table.find(category_id))
+                    .set(categories::icon.eq(icon))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update category icon {}: {}", category_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(color) = updates.color {
+                diesel::update(categories::table.find(category_id))
+                    .set(categories::color.eq(color))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update category color {}: {}", category_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(parent_id) = updates.parent_id {
+                diesel::update(categories::table.find(category_id))
+                    .set(categories::parent_id.eq(parent_id))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to update category parent_id {}: {}",
+                            category_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+            }
+
+            // Return the updated category
+            let category: Category =
+                categories::table
+                    .find(category_id)
+                    .first(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to fetch updated category {}: {}", category_id, e);
+                        ApiError::from(e)
+                    })?;
+            audit_log::record(
+                conn,
+                category.user_id,
+                AuditEntityType::Category,
+                category_id,
+                Some(&before),
+                Some(&category),
+            )?;
+            Ok(category)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete category, moving what refers to it to the category `reassign_to`
+///
+/// With `reassign_to`, transactions (deleted ones included, so they come back
+/// in that category), budgets filtering on the category, recurring
+/// transactions, allocation and category rules and subcategories all move
+/// there. Without it, transactions and recurring transactions become
+/// uncategorized, budgets filtering on the category are deactivated,
+/// subcategories become top level and rules of the category are deleted.
+/// Everything happens in one DB transaction with the deletion.
+pub async fn delete_category(
+    pool: &DbPool,
+    category_id: Uuid,
+    reassign_to: Option<Uuid>,
+) -> Result<CategoryReassignment, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let category = find_for_audit(conn, category_id)?;
+            if let Some(target_id) = reassign_to {
+                find_for_audit(conn, target_id)?;
+            }
+
+            let reassignment = reassign(conn, &category, reassign_to)?;
+
+            diesel::delete(categories::table.find(category_id))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to delete category {}: {}", category_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                category.user_id,
+                AuditEntityType::Category,
+                category_id,
+                Some(&category),
+                None,
+            )?;
+            Ok(reassignment)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Move everything referring to `category` to the category `target_id`, or
+/// detach it from the category if there is none
+fn reassign(
+    conn: &mut PgConnection,
+    category: &Category,
+    target_id: Option<Uuid>,
+) -> Result<CategoryReassignment, ApiError> {
+    let category_id = category.id;
+    let log_error = |what: &str, e: diesel::result::Error| {
+        tracing::error!(
+            "Failed to reassign {} of category {}: {}",
+            what,
+            category_id,
+            e
+        );
+        ApiError::from(e)
+    };
+
+    let moved: Vec<Transaction> =
+        diesel::update(transactions::table.filter(transactions::category_id.eq(category_id)))
+            .set((
+                transactions::category_id.eq(target_id),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_results(conn)
+            .map_err(|e| log_error("transactions", e))?;
+    let mut reassignment = CategoryReassignment::default();
+    for transaction in moved {
+        let before = Transaction {
+            category_id: Some(category_id),
+            ..transaction.clone()
+        };
+        audit_log::record(
+            conn,
+            transaction.user_id,
+            AuditEntityType::Transaction,
+            transaction.id,
+            Some(&before),
+            Some(&transaction),
+        )?;
+        if transaction.deleted_at.is_none() {
+            reassignment.transaction_ids.push(transaction.id);
+        }
+    }
+
+    // Budget filters name categories in JSON, which no foreign key follows
+    let filtered: Vec<Budget> = budgets::table
+        .filter(budgets::user_id.eq(category.user_id))
+        .filter(
+            budgets::filters
+                .retrieve_as_text("category_id")
+                .eq(category_id.to_string()),
+        )
+        .for_update()
+        .load(conn)
+        .map_err(|e| log_error("budgets", e))?;
+    for before in filtered {
+        // Without a target, dropping the filter would widen the budget to all
+        // spending, so it is deactivated instead
+        let mut filters = before.filters.clone();
+        let deactivated_at = match (target_id, filters.as_object_mut()) {
+            (Some(target_id), _) => {
+                filters["category_id"] = Value::String(target_id.to_string());
+                before.deactivated_at
+            }
+            (None, fields) => {
+                if let Some(fields) = fields {
+                    fields.remove("category_id");
+                }
+                Some(Utc::now())
+            }
+        };
+        let budget: Budget = diesel::update(budgets::table.find(before.id))
+            .set((
+                budgets::filters.eq(filters),
+                budgets::deactivated_at.eq(deactivated_at),
+                budgets::version.eq(budgets::version + 1),
+            ))
+            .get_result(conn)
+            .map_err(|e| log_error("budgets", e))?;
+        audit_log::record(
+            conn,
+            budget.user_id,
+            AuditEntityType::Budget,
+            budget.id,
+            Some(&before),
+            Some(&budget),
+        )?;
+        if target_id.is_none() {
+            reassignment.deactivated_budget_ids.push(budget.id);
+        }
+        reassignment.budget_ids.push(budget.id);
+    }
+
+    // Without a target, the foreign keys uncategorize recurring transactions,
+    // delete rules and make subcategories top level
+    let Some(target_id) = target_id else {
+        return Ok(reassignment);
+    };
+    diesel::update(
+        recurring_transactions::table.filter(recurring_transactions::category_id.eq(category_id)),
+    )
+    .set(recurring_transactions::category_id.eq(target_id))
+    .execute(conn)
+    .map_err(|e| log_error("recurring transactions", e))?;
+    diesel::update(allocation_rules::table.filter(allocation_rules::category_id.eq(category_id)))
+        .set(allocation_rules::category_id.eq(target_id))
+        .execute(conn)
+        .map_err(|e| log_error("allocation rules", e))?;
+    diesel::update(category_rules::table.filter(category_rules::category_id.eq(category_id)))
+        .set(category_rules::category_id.eq(target_id))
+        .execute(conn)
+        .map_err(|e| log_error("category rules", e))?;
+    // The target itself may be a subcategory; it is left without a parent
+    diesel::update(
+        categories::table
+            .filter(categories::parent_id.eq(category_id))
+            .filter(categories::id.ne(target_id)),
+    )
+    .set(categories::parent_id.eq(target_id))
+    .execute(conn)
+    .map_err(|e| log_error("subcategories", e))?;
+
+    Ok(reassignment)
+}
+
+/// Lock a category and read it as stored before a change, for its audit entry
+fn find_for_audit(conn: &mut PgConnection, category_id: Uuid) -> Result<Category, ApiError> {
+    categories::table
+        .find(category_id)
+        .for_update()
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to find category by id {}: {}", category_id, e);
+            ApiError::from(e)
+        })
+}
@@ -0,0 +1,140 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{CategoryRule, NewCategoryRule, UpdateCategoryRule},
+    schema::category_rules,
+};
+
+/// Create a category rule
+pub async fn create_rule(
+    pool: &DbPool,
+    new_rule: NewCategoryRule,
+) -> Result<CategoryRule, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(category_rules::table)
+            .values(&new_rule)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create category rule for user {}: {}",
+                    new_rule.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find category rule by ID
+pub async fn find_by_id(pool: &DbPool, rule_id: Uuid) -> Result<CategoryRule, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        category_rules::table
+            .find(rule_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find category rule by id {}: {}", rule_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List all category rules of a user in the order they are tried
+pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<CategoryRule>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        category_rules::table
+            .filter(category_rules::user_id.eq(user_id))
+            .order((
+                category_rules::priority.asc(),
+                category_rules::created_at.asc(),
+                category_rules::id.asc(),
+            ))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list category rules for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Update a category rule
+pub async fn update_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    updates: UpdateCategoryRule,
+) -> Result<CategoryRule, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(category_rules::table.find(rule_id))
+            .set(&updates)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to update category rule {}: {}", rule_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete a category rule
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(category_rules::table.find(rule_id))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete category rule {}: {}", rule_id, e);
+                ApiError::from(e)
+            })
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
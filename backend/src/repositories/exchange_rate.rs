@@ -0,0 +1,223 @@
+//! Exchange rates fetched from the provider, stored per currency pair and
+//! day, and the rates users set by hand for their own conversions
+
+use chrono::NaiveDate;
+use diesel::{prelude::*, upsert::excluded};
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        CustomExchangeRate, ExchangeRateRecord, NewCustomExchangeRate, NewExchangeRateRecord,
+    },
+    schema::{custom_exchange_rates, exchange_rates},
+    types::CurrencyCode,
+};
+
+/// Find the rate from `base` to `quote` on `date`, or on the nearest earlier
+/// day with a stored rate
+pub async fn find_on_or_before(
+    pool: &DbPool,
+    base: CurrencyCode,
+    quote: CurrencyCode,
+    date: NaiveDate,
+) -> Result<Option<ExchangeRateRecord>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        exchange_rates::table
+            .filter(exchange_rates::base_currency.eq(base))
+            .filter(exchange_rates::quote_currency.eq(quote))
+            .filter(exchange_rates::rate_date.le(date))
+            .order(exchange_rates::rate_date.desc())
+            .select(ExchangeRateRecord::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find exchange rate from {} to {} on {}: {}",
+                    base.as_str(),
+                    quote.as_str(),
+                    date,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Store fetched rates, replacing rates already stored for the same pair and day
+pub async fn upsert_rates(
+    pool: &DbPool,
+    rates: Vec<NewExchangeRateRecord>,
+) -> Result<(), ApiError> {
+    if rates.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(exchange_rates::table)
+            .values(&rates)
+            .on_conflict((
+                exchange_rates::base_currency,
+                exchange_rates::quote_currency,
+                exchange_rates::rate_date,
+            ))
+            .do_update()
+            .set((
+                exchange_rates::rate.eq(excluded(exchange_rates::rate)),
+                exchange_rates::fetched_at.eq(diesel::dsl::now),
+            ))
+            .execute(&mut conn)
+            .map(|_| ())
+            .map_err(|e| {
+                tracing::error!("Failed to store {} exchange rates: {}", rates.len(), e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find a user's manual rate from `base` to `quote` on `date`, or on the
+/// nearest earlier day with one
+pub async fn find_manual_on_or_before(
+    pool: &DbPool,
+    user_id: Uuid,
+    base: CurrencyCode,
+    quote: CurrencyCode,
+    date: NaiveDate,
+) -> Result<Option<CustomExchangeRate>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        custom_exchange_rates::table
+            .filter(custom_exchange_rates::user_id.eq(user_id))
+            .filter(custom_exchange_rates::base_currency.eq(base))
+            .filter(custom_exchange_rates::quote_currency.eq(quote))
+            .filter(custom_exchange_rates::rate_date.le(date))
+            .order(custom_exchange_rates::rate_date.desc())
+            .select(CustomExchangeRate::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find manual exchange rate of user {} from {} to {} on {}: {}",
+                    user_id,
+                    base.as_str(),
+                    quote.as_str(),
+                    date,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Store a user's manual rate, replacing their rate for the same pair and day
+pub async fn upsert_manual_rate(
+    pool: &DbPool,
+    rate: NewCustomExchangeRate,
+) -> Result<CustomExchangeRate, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(custom_exchange_rates::table)
+            .values(&rate)
+            .on_conflict((
+                custom_exchange_rates::user_id,
+                custom_exchange_rates::base_currency,
+                custom_exchange_rates::quote_currency,
+                custom_exchange_rates::rate_date,
+            ))
+            .do_update()
+            .set((
+                custom_exchange_rates::rate.eq(excluded(custom_exchange_rates::rate)),
+                custom_exchange_rates::updated_at.eq(diesel::dsl::now),
+            ))
+            .returning(CustomExchangeRate::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to store manual exchange rate of user {} from {} to {} on {}: {}",
+                    rate.user_id,
+                    rate.base_currency.as_str(),
+                    rate.quote_currency.as_str(),
+                    rate.rate_date,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete a user's manual rate from `base` to `quote` on `date`
+///
+/// Returns whether a manual rate was deleted.
+pub async fn delete_manual_rate(
+    pool: &DbPool,
+    user_id: Uuid,
+    base: CurrencyCode,
+    quote: CurrencyCode,
+    date: NaiveDate,
+) -> Result<bool, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(custom_exchange_rates::table.find((user_id, base, quote, date)))
+            .execute(&mut conn)
+            .map(|deleted| deleted > 0)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to delete manual exchange rate of user {} from {} to {} on {}: {}",
+                    user_id,
+                    base.as_str(),
+                    quote.as_str(),
+                    date,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -0,0 +1,110 @@
+//! Idempotency keys of create requests
+//!
+//! These run on a caller's connection, so claiming a key can share a database
+//! transaction with checking for an expired one.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    errors::ApiError,
+    models::idempotency_key::{IdempotencyKey, IdempotencyScope, NewIdempotencyKey},
+    schema::idempotency_keys,
+};
+
+/// Find a key with the outcome of the request that used it
+pub fn find(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: &str,
+) -> Result<Option<IdempotencyKey>, ApiError> {
+    idempotency_keys::table
+        .find((user_id, scope.as_str(), key))
+        .select(IdempotencyKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| {
+            tracing::error!("Failed to find idempotency key for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+}
+
+/// Record a key as claimed by a request
+///
+/// Returns `false` if the key was recorded first by another request.
+pub fn record(conn: &mut PgConnection, new_key: &NewIdempotencyKey) -> Result<bool, ApiError> {
+    diesel::insert_into(idempotency_keys::table)
+        .values(new_key)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|inserted| inserted > 0)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to record idempotency key for user {}: {}",
+                new_key.user_id,
+                e
+            );
+            ApiError::from(e)
+        })
+}
+
+/// Store the response of the request that recorded a key
+pub fn store_response(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: &str,
+    status: i16,
+    body: &str,
+) -> Result<(), ApiError> {
+    diesel::update(idempotency_keys::table.find((user_id, scope.as_str(), key)))
+        .set((
+            idempotency_keys::response_status.eq(status),
+            idempotency_keys::response_body.eq(body),
+        ))
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to store idempotent response for user {}: {}",
+                user_id,
+                e
+            );
+            ApiError::from(e)
+        })
+}
+
+/// Delete a key, e.g. so a failed request can be retried with it
+pub fn delete(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: &str,
+) -> Result<(), ApiError> {
+    diesel::delete(idempotency_keys::table.find((user_id, scope.as_str(), key)))
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to delete idempotency key for user {}: {}",
+                user_id,
+                e
+            );
+            ApiError::from(e)
+        })
+}
+
+/// Delete the keys recorded before `cutoff`
+pub fn delete_older_than(
+    conn: &mut PgConnection,
+    cutoff: DateTime<Utc>,
+) -> Result<usize, ApiError> {
+    diesel::delete(idempotency_keys::table.filter(idempotency_keys::created_at.lt(cutoff)))
+        .execute(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to delete expired idempotency keys: {}", e);
+            ApiError::from(e)
+        })
+}
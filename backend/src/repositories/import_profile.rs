@@ -0,0 +1,136 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{ImportProfile, NewImportProfile, UpdateImportProfile},
+    schema::import_profiles,
+};
+
+/// Create an import profile
+pub async fn create_profile(
+    pool: &DbPool,
+    new_profile: NewImportProfile,
+) -> Result<ImportProfile, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(import_profiles::table)
+            .values(&new_profile)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create import profile for user {}: {}",
+                    new_profile.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find import profile by ID
+pub async fn find_by_id(pool: &DbPool, profile_id: Uuid) -> Result<ImportProfile, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        import_profiles::table
+            .find(profile_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find import profile by id {}: {}", profile_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List all import profiles of a user by name
+pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<ImportProfile>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        import_profiles::table
+            .filter(import_profiles::user_id.eq(user_id))
+            .order((import_profiles::name.asc(), import_profiles::id.asc()))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list import profiles for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Update an import profile
+pub async fn update_profile(
+    pool: &DbPool,
+    profile_id: Uuid,
+    updates: UpdateImportProfile,
+) -> Result<ImportProfile, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(import_profiles::table.find(profile_id))
+            .set(&updates)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to update import profile {}: {}", profile_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete an import profile
+pub async fn delete_profile(pool: &DbPool, profile_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(import_profiles::table.find(profile_id))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete import profile {}: {}", profile_id, e);
+                ApiError::from(e)
+            })
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -1,11 +1,29 @@
 // Repository modules for database operations
 pub mod account;
+pub mod allocation_rule;
 pub mod api_key;
+pub mod audit_log;
+pub mod auth_event;
 pub mod budget;
+pub mod budget_alert;
 pub mod category;
+pub mod category_rule;
+pub mod exchange_rate;
+pub mod idempotency_key;
+pub mod import_profile;
+pub mod notification;
+pub mod notification_preference;
+pub mod password_reset_token;
 pub mod person;
 pub mod person_split_config;
+pub mod reconciliation;
+pub mod recurring;
+pub mod refresh_token;
+pub mod split_group;
 pub mod split_provider;
 pub mod split_sync_record;
+pub mod tag;
 pub mod transaction;
+pub mod two_factor;
 pub mod user;
+pub mod webhook_event;
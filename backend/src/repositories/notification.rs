@@ -0,0 +1,167 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewNotification, Notification, Pagination},
+    schema::notifications,
+};
+
+/// Store an in-app notification
+pub async fn create_notification(
+    pool: &DbPool,
+    new_notification: NewNotification,
+) -> Result<Notification, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(notifications::table)
+            .values(&new_notification)
+            .returning(Notification::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create notification for user {}: {}",
+                    new_notification.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find notification by ID
+pub async fn find_by_id(pool: &DbPool, notification_id: Uuid) -> Result<Notification, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        notifications::table
+            .find(notification_id)
+            .select(Notification::as_select())
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find notification by id {}: {}",
+                    notification_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List a page of a user's in-app notifications, newest first
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    unread_only: bool,
+    pagination: Pagination,
+) -> Result<Vec<Notification>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .select(Notification::as_select())
+            .order((notifications::created_at.desc(), notifications::id.asc()))
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .into_boxed();
+        if unread_only {
+            query = query.filter(notifications::read_at.is_null());
+        }
+
+        query.load(&mut conn).map_err(|e| {
+            tracing::error!("Failed to list notifications for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count a user's in-app notifications
+pub async fn count_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    unread_only: bool,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .into_boxed();
+        if unread_only {
+            query = query.filter(notifications::read_at.is_null());
+        }
+
+        query.count().get_result(&mut conn).map_err(|e| {
+            tracing::error!("Failed to count notifications for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Mark a notification read, keeping the time it was first read
+pub async fn mark_read(pool: &DbPool, notification_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            notifications::table
+                .find(notification_id)
+                .filter(notifications::read_at.is_null()),
+        )
+        .set(notifications::read_at.eq(diesel::dsl::now))
+        .execute(&mut conn)
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to mark notification {} read: {}",
+                notification_id,
+                e
+            );
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
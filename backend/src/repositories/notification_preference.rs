@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewNotificationPreference, NotificationPreference},
+    schema::notification_preferences,
+};
+
+/// List a user's notification preferences
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<NotificationPreference>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        notification_preferences::table
+            .filter(notification_preferences::user_id.eq(user_id))
+            .select(NotificationPreference::as_select())
+            .order((
+                notification_preferences::event_type.asc(),
+                notification_preferences::channel.asc(),
+            ))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list notification preferences for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create or replace notification preferences, all or none of them
+pub async fn upsert_preferences(
+    pool: &DbPool,
+    preferences: Vec<NewNotificationPreference>,
+) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(notification_preferences::table)
+            .values(&preferences)
+            .on_conflict((
+                notification_preferences::user_id,
+                notification_preferences::event_type,
+                notification_preferences::channel,
+            ))
+            .do_update()
+            .set((
+                notification_preferences::enabled.eq(excluded(notification_preferences::enabled)),
+                notification_preferences::webhook_url
+                    .eq(excluded(notification_preferences::webhook_url)),
+                notification_preferences::webhook_secret
+                    .eq(excluded(notification_preferences::webhook_secret)),
+            ))
+            .execute(&mut conn)
+            .map(|_| ())
+            .map_err(|e| {
+                tracing::error!("Failed to save notification preferences: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
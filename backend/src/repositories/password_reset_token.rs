@@ -0,0 +1,134 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewPasswordResetToken, PasswordResetToken},
+    schema::{password_reset_tokens, refresh_tokens, users},
+};
+
+/// Create a password reset token
+pub async fn create_token(
+    pool: &DbPool,
+    new_token: NewPasswordResetToken,
+) -> Result<PasswordResetToken, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(password_reset_tokens::table)
+            .values(&new_token)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create password reset token for user {}: {}",
+                    new_token.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find a password reset token by the hash of the token
+pub async fn find_by_hash(
+    pool: &DbPool,
+    token_hash: String,
+) -> Result<Option<PasswordResetToken>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!("Failed to find password reset token: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Use a password reset token to set the user's password, in one DB transaction
+///
+/// Marks the token and every other outstanding reset token of the user used,
+/// stores the new password hash and revokes every refresh token of the user.
+/// Returns `false` without changing anything when the token was already used
+/// or has expired, e.g. by a concurrent reset.
+pub async fn reset_password(
+    pool: &DbPool,
+    token_id: Uuid,
+    user_id: Uuid,
+    password_hash: String,
+) -> Result<bool, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let now = Utc::now();
+            let used = diesel::update(
+                password_reset_tokens::table
+                    .find(token_id)
+                    .filter(password_reset_tokens::used_at.is_null())
+                    .filter(password_reset_tokens::expires_at.gt(now)),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .execute(conn)?;
+            if used == 0 {
+                return Ok(false);
+            }
+
+            // Other links sent before the reset must not work after it
+            diesel::update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::user_id.eq(user_id))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .execute(conn)?;
+
+            diesel::update(users::table.find(user_id))
+                .set(users::password_hash.eq(password_hash))
+                .execute(conn)?;
+
+            diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::user_id.eq(user_id))
+                    .filter(refresh_tokens::revoked.eq(false)),
+            )
+            .set(refresh_tokens::revoked.eq(true))
+            .execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e: diesel::result::Error| {
+            tracing::error!("Failed to reset password for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
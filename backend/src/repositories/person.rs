@@ -1,11 +1,18 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
 use crate::{
     DbPool,
     errors::ApiError,
-    models::person::{NewPerson, Person, UpdatePerson},
+    models::{
+        AuditEntityType, Pagination,
+        person::{NewPerson, Person, UpdatePerson},
+    },
+    repositories::audit_log,
     schema::people,
+    types::TransactionStatus,
 };
 
 /// Create a new person
@@ -20,13 +27,24 @@ pub async fn create_person(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(people::table)
-            .values(&new_person)
-            .get_result(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to create person for user {}: {}", user_id, e);
-                ApiError::from(e)
-            })
+        conn.transaction(|conn| {
+            let person: Person = diesel::insert_into(people::table)
+                .values(&new_person)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create person for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                user_id,
+                AuditEntityType::Person,
+                person.id,
+                None,
+                Some(&person),
+            )?;
+            Ok(person)
+        })
     })
     .await
     .map_err(|e| {
@@ -55,8 +73,12 @@ pub async fn find_by_id(pool: &DbPool, person_id: Uuid) -> Result<Person, ApiErr
     })?
 }
 
-/// List all people for a user
-pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Person>, ApiError> {
+/// List a page of people for a user
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    pagination: Pagination,
+) -> Result<Vec<Person>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
@@ -66,6 +88,8 @@ pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Person>, A
         people::table
             .filter(people::user_id.eq(user_id))
             .order(people::name.asc())
+            .limit(pagination.limit)
+            .offset(pagination.offset)
             .load(&mut conn)
             .map_err(|e| {
                 tracing::error!("Failed to list people for user {}: {}", user_id, e);
@@ -79,6 +103,68 @@ pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<Person>, A
     })?
 }
 
+/// List a page of people for a user created or modified at or after `since`
+pub async fn list_updated_since(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+    pagination: Pagination,
+) -> Result<Vec<Person>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        people::table
+            .filter(people::user_id.eq(user_id))
+            .filter(people::updated_at.ge(since))
+            .order(people::name.asc())
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list updated people for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count a user's people, optionally only those created or modified at or after `since`
+pub async fn count_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = people::table
+            .filter(people::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(people::updated_at.ge(since));
+        }
+        query.count().get_result(&mut conn).map_err(|e| {
+            tracing::error!("Failed to count people for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Update person
 pub async fn update_person(
     pool: &DbPool,
@@ -91,48 +177,61 @@ pub async fn update_person(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        // Apply updates one at a time
-        if let Some(name) = updates.name {
-            diesel::update(people::table.find(person_id))
-                .set(people::name.eq(name))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update person name {}: {}", person_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(email) = updates.email {
-            diesel::update(people::table.find(person_id))
-                .set(people::email.eq(email))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update person email {}: {}", person_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(phone) = updates.phone {
-            diesel::update(people::table.find(person_id))
-                .set(people::phone.eq(phone))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update person phone {}: {}", person_id, e);
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(notes) = updates.notes {
-            diesel::update(people::table.find(person_id))
-                .set(people::notes.eq(notes))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!("Failed to update person notes {}: {}", person_id, e);
-                    ApiError::from(e)
-                })?;
-        }
+        conn.transaction(|conn| {
+            let before = find_for_audit(conn, person_id)?;
 
-        // Return the updated person
-        people::table.find(person_id).first(&mut conn).map_err(|e| {
-            tracing::error!("Failed to fetch updated person {}: {}", person_id, e);
-            ApiError::from(e)
+            // Apply updates one at a time
+            if let Some(name) = updates.name {
+                diesel::update(people::table.find(person_id))
+                    .set(people::name.eq(name))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update person name {}: {}", person_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(email) = updates.email {
+                diesel::update(people::table.find(person_id))
+                    .set(people::email.eq(email))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update person email {}: {}", person_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(phone) = updates.phone {
+                diesel::update(people::table.find(person_id))
+                    .set(people::phone.eq(phone))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update person phone {}: {}", person_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+            if let Some(notes) = updates.notes {
+                diesel::update(people::table.find(person_id))
+                    .set(people::notes.eq(notes))
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!("Failed to update person notes {}: {}", person_id, e);
+                        ApiError::from(e)
+                    })?;
+            }
+
+            // Return the updated person
+            let person: Person = people::table.find(person_id).first(conn).map_err(|e| {
+                tracing::error!("Failed to fetch updated person {}: {}", person_id, e);
+                ApiError::from(e)
+            })?;
+            audit_log::record(
+                conn,
+                person.user_id,
+                AuditEntityType::Person,
+                person_id,
+                Some(&before),
+                Some(&person),
+            )?;
+            Ok(person)
         })
     })
     .await
@@ -150,13 +249,23 @@ pub async fn delete_person(pool: &DbPool, person_id: Uuid) -> Result<(), ApiErro
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(people::table.find(person_id))
-            .execute(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to delete person {}: {}", person_id, e);
-                ApiError::from(e)
-            })
-            .map(|_| ())
+        conn.transaction(|conn| {
+            let person = find_for_audit(conn, person_id)?;
+            diesel::delete(people::table.find(person_id))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to delete person {}: {}", person_id, e);
+                    ApiError::from(e)
+                })?;
+            audit_log::record(
+                conn,
+                person.user_id,
+                AuditEntityType::Person,
+                person_id,
+                Some(&person),
+                None,
+            )
+        })
     })
     .await
     .map_err(|e| {
@@ -165,6 +274,18 @@ pub async fn delete_person(pool: &DbPool, person_id: Uuid) -> Result<(), ApiErro
     })?
 }
 
+/// Lock a person and read it as stored before a change, for its audit entry
+fn find_for_audit(conn: &mut PgConnection, person_id: Uuid) -> Result<Person, ApiError> {
+    people::table
+        .find(person_id)
+        .for_update()
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to find person by id {}: {}", person_id, e);
+            ApiError::from(e)
+        })
+}
+
 /// Helper to build PersonResponse with split config populated
 pub async fn build_person_response_with_config(
     pool: &DbPool,
@@ -221,3 +342,157 @@ pub async fn list_splits_for_person(
         ApiError::Internal
     })?
 }
+
+/// Get all unsettled splits for a person, oldest first
+///
+/// Splits settled locally (`settled_at` set) and splits of void or deleted
+/// transactions no longer count towards the debt.
+pub async fn list_unsettled_splits_for_person(
+    pool: &DbPool,
+    person_id: Uuid,
+) -> Result<Vec<crate::models::TransactionSplit>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        use crate::schema::{transaction_splits, transactions};
+
+        transaction_splits::table
+            .inner_join(transactions::table)
+            .filter(transaction_splits::person_id.eq(person_id))
+            .filter(transaction_splits::settled_at.is_null())
+            .filter(transactions::status.ne(TransactionStatus::Void))
+            .filter(transactions::deleted_at.is_null())
+            .order(transaction_splits::created_at.asc())
+            .select(crate::models::TransactionSplit::as_select())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to get unsettled splits for person {}: {}",
+                    person_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// A person's split together with the transaction it belongs to, for the debt ledger
+pub type LedgerSplit = (
+    Uuid,
+    DateTime<Utc>,
+    String,
+    BigDecimal,
+    Option<DateTime<Utc>>,
+);
+
+/// List every split a person has in the user's transactions, settled or not
+///
+/// Returns `(transaction_id, date, title, amount, settled_at)` ordered by
+/// transaction date. Splits of void and deleted transactions are left out, as
+/// they never count towards the debt.
+pub async fn list_ledger_splits_for_person(
+    pool: &DbPool,
+    user_id: Uuid,
+    person_id: Uuid,
+) -> Result<Vec<LedgerSplit>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        use crate::schema::{transaction_splits, transactions};
+
+        transaction_splits::table
+            .inner_join(transactions::table)
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transaction_splits::person_id.eq(person_id))
+            .filter(transactions::status.ne(TransactionStatus::Void))
+            .filter(transactions::deleted_at.is_null())
+            .order((
+                transactions::date.asc(),
+                transaction_splits::created_at.asc(),
+            ))
+            .select((
+                transactions::id,
+                transactions::date,
+                transactions::title,
+                transaction_splits::amount,
+                transaction_splits::settled_at,
+            ))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list ledger splits for person {}: {}",
+                    person_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Sum the unsettled split amounts per person for a user in a single grouped query
+///
+/// Returns `(person_id, person_name, total)` ordered by name, omitting people
+/// without unsettled splits. Splits of void and deleted transactions are left out. Pass `person_id` to restrict the totals to one person.
+pub async fn sum_unsettled_splits_by_person(
+    pool: &DbPool,
+    user_id: Uuid,
+    person_id: Option<Uuid>,
+) -> Result<Vec<(Uuid, String, BigDecimal)>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        use crate::schema::{transaction_splits, transactions};
+        use diesel::dsl::sum;
+
+        let mut query = transaction_splits::table
+            .inner_join(people::table)
+            .inner_join(transactions::table)
+            .filter(people::user_id.eq(user_id))
+            .filter(transaction_splits::settled_at.is_null())
+            .filter(transactions::status.ne(TransactionStatus::Void))
+            .filter(transactions::deleted_at.is_null())
+            .group_by((people::id, people::name))
+            .select((people::id, people::name, sum(transaction_splits::amount)))
+            .order(people::name.asc())
+            .into_boxed();
+
+        if let Some(person_id) = person_id {
+            query = query.filter(people::id.eq(person_id));
+        }
+
+        let totals: Vec<(Uuid, String, Option<BigDecimal>)> =
+            query.load(&mut conn).map_err(|e| {
+                tracing::error!("Failed to sum unsettled splits for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })?;
+
+        Ok(totals
+            .into_iter()
+            .map(|(id, name, total)| (id, name, total.unwrap_or_else(|| BigDecimal::from(0))))
+            .collect())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
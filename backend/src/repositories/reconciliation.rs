@@ -0,0 +1,114 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{AuditEntityType, NewReconciliation, NewTransaction, Reconciliation, Transaction},
+    repositories::audit_log,
+    schema::{reconciliations, transactions},
+};
+
+/// Record a reconciliation, with the transaction booking its difference if given
+///
+/// Both are inserted in one database transaction, and the reconciliation
+/// refers to the adjustment.
+pub async fn create_reconciliation(
+    pool: &DbPool,
+    new_reconciliation: NewReconciliation,
+    adjustment: Option<NewTransaction>,
+) -> Result<(Reconciliation, Option<Transaction>), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let account_id = new_reconciliation.account_id;
+        conn.transaction(|conn| {
+            let adjustment: Option<Transaction> = adjustment
+                .map(|adjustment| {
+                    diesel::insert_into(transactions::table)
+                        .values(&adjustment)
+                        .get_result(conn)
+                })
+                .transpose()
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create reconciliation adjustment of account {}: {}",
+                        account_id,
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+            if let Some(transaction) = &adjustment {
+                audit_log::record(
+                    conn,
+                    transaction.user_id,
+                    AuditEntityType::Transaction,
+                    transaction.id,
+                    None,
+                    Some(transaction),
+                )?;
+            }
+
+            let new_reconciliation = NewReconciliation {
+                adjustment_transaction_id: adjustment.as_ref().map(|t| t.id),
+                ..new_reconciliation
+            };
+            let reconciliation = diesel::insert_into(reconciliations::table)
+                .values(&new_reconciliation)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create reconciliation of account {}: {}",
+                        account_id,
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+
+            Ok((reconciliation, adjustment))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find the latest reconciliation of an account, by statement date
+pub async fn find_latest(
+    pool: &DbPool,
+    account_id: Uuid,
+) -> Result<Option<Reconciliation>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        reconciliations::table
+            .filter(reconciliations::account_id.eq(account_id))
+            .order((
+                reconciliations::statement_date.desc(),
+                reconciliations::created_at.desc(),
+            ))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find latest reconciliation of account {}: {}",
+                    account_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -0,0 +1,334 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        AuditEntityType, NewRecurringTransaction, NewTransaction, RecurringTransaction,
+        Transaction, UpdateRecurringTransaction,
+    },
+    repositories::audit_log,
+    schema::{recurring_transactions, transactions},
+};
+
+/// Create a recurring transaction
+pub async fn create_rule(
+    pool: &DbPool,
+    new_rule: NewRecurringTransaction,
+) -> Result<RecurringTransaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(recurring_transactions::table)
+            .values(&new_rule)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create recurring transaction for user {}: {}",
+                    new_rule.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find recurring transaction by ID
+pub async fn find_by_id(pool: &DbPool, rule_id: Uuid) -> Result<RecurringTransaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        recurring_transactions::table
+            .find(rule_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find recurring transaction by id {}: {}",
+                    rule_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List all recurring transactions of a user, soonest next run first
+pub async fn list_by_user(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<RecurringTransaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        recurring_transactions::table
+            .filter(recurring_transactions::user_id.eq(user_id))
+            .order((
+                recurring_transactions::next_run_date.asc(),
+                recurring_transactions::created_at.asc(),
+            ))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list recurring transactions for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Update a recurring transaction
+///
+/// The schedule columns are only written when `updates.schedule` is set, so an
+/// update racing a materialization cannot rewind the schedule's progress.
+pub async fn update_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    updates: UpdateRecurringTransaction,
+) -> Result<RecurringTransaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            if let Some(schedule) = updates.schedule {
+                diesel::update(recurring_transactions::table.find(rule_id))
+                    .set((
+                        recurring_transactions::frequency.eq(schedule.frequency),
+                        recurring_transactions::interval.eq(schedule.interval),
+                        recurring_transactions::start_date.eq(schedule.start_date),
+                        recurring_transactions::run_count.eq(0),
+                        recurring_transactions::next_run_date.eq(schedule.start_date),
+                    ))
+                    .execute(conn)?;
+            }
+
+            diesel::update(recurring_transactions::table.find(rule_id))
+                .set((
+                    recurring_transactions::account_id.eq(updates.account_id),
+                    recurring_transactions::category_id.eq(updates.category_id),
+                    recurring_transactions::title.eq(updates.title),
+                    recurring_transactions::amount.eq(updates.amount),
+                    recurring_transactions::notes.eq(updates.notes),
+                ))
+                .get_result(conn)
+        })
+        .map_err(|e| {
+            tracing::error!("Failed to update recurring transaction {}: {}", rule_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete a recurring transaction
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(recurring_transactions::table.find(rule_id))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete recurring transaction {}: {}", rule_id, e);
+                ApiError::from(e)
+            })
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the users with recurring transactions due by `today`
+pub async fn list_due_user_ids(pool: &DbPool, today: NaiveDate) -> Result<Vec<Uuid>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        recurring_transactions::table
+            .filter(recurring_transactions::next_run_date.le(today))
+            .select(recurring_transactions::user_id)
+            .distinct()
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list users with due recurring transactions: {}",
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the IDs of a user's recurring transactions due by `today`
+pub async fn list_due_ids(
+    pool: &DbPool,
+    user_id: Uuid,
+    today: NaiveDate,
+) -> Result<Vec<Uuid>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        recurring_transactions::table
+            .filter(recurring_transactions::user_id.eq(user_id))
+            .filter(recurring_transactions::next_run_date.le(today))
+            .order(recurring_transactions::next_run_date.asc())
+            .select(recurring_transactions::id)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list due recurring transactions for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create the transactions of a recurring transaction's occurrences due by
+/// `today`, at most `limit` of them, and advance its schedule past them
+///
+/// The rule is locked while its occurrences are read, created and recorded in
+/// one DB transaction, so concurrent or repeated runs never create an
+/// occurrence twice. A rule deleted in the meantime creates nothing.
+pub async fn materialize_due(
+    pool: &DbPool,
+    rule_id: Uuid,
+    today: NaiveDate,
+    limit: usize,
+) -> Result<Vec<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let rule: Option<RecurringTransaction> = recurring_transactions::table
+                .find(rule_id)
+                .for_update()
+                .first(conn)
+                .optional()
+                .map_err(|e| {
+                    tracing::error!("Failed to lock recurring transaction {}: {}", rule_id, e);
+                    ApiError::from(e)
+                })?;
+            let Some(rule) = rule else {
+                return Ok(Vec::new());
+            };
+
+            let dates = rule.due_dates(today, limit);
+            if dates.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let new_transactions: Vec<NewTransaction> = dates
+                .iter()
+                .map(|date| rule.to_transaction(*date))
+                .collect();
+            let created: Vec<Transaction> = diesel::insert_into(transactions::table)
+                .values(&new_transactions)
+                .get_results(conn)
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create transactions of recurring transaction {}: {}",
+                        rule_id,
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+            for transaction in &created {
+                audit_log::record(
+                    conn,
+                    transaction.user_id,
+                    AuditEntityType::Transaction,
+                    transaction.id,
+                    None,
+                    Some(transaction),
+                )?;
+            }
+
+            // At most `limit` occurrences were created, which fits an i32 count
+            let run_count = rule.run_count + created.len() as i32;
+            let next_run_date = rule.occurrence(run_count).ok_or_else(|| {
+                tracing::error!(
+                    "Recurring transaction {} has no occurrence number {}",
+                    rule_id,
+                    run_count
+                );
+                ApiError::Internal
+            })?;
+            diesel::update(recurring_transactions::table.find(rule_id))
+                .set((
+                    recurring_transactions::run_count.eq(run_count),
+                    recurring_transactions::next_run_date.eq(next_run_date),
+                ))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to advance recurring transaction {}: {}", rule_id, e);
+                    ApiError::from(e)
+                })?;
+
+            Ok(created)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
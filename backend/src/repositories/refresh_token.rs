@@ -0,0 +1,137 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewRefreshToken, RefreshToken},
+    schema::refresh_tokens,
+};
+
+/// Create a refresh token
+pub async fn create_token(
+    pool: &DbPool,
+    new_token: NewRefreshToken,
+) -> Result<RefreshToken, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(refresh_tokens::table)
+            .values(&new_token)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to create refresh token for user {}: {}",
+                    new_token.user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find a refresh token by the hash of the token
+pub async fn find_by_hash(
+    pool: &DbPool,
+    token_hash: String,
+) -> Result<Option<RefreshToken>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        refresh_tokens::table
+            .filter(refresh_tokens::token_hash.eq(token_hash))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!("Failed to find refresh token: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Revoke a refresh token and create its replacement, in one DB transaction
+///
+/// Returns `None` without creating the replacement when the token was already
+/// revoked, e.g. by a concurrent refresh with the same token.
+pub async fn rotate_token(
+    pool: &DbPool,
+    token_id: Uuid,
+    new_token: NewRefreshToken,
+) -> Result<Option<RefreshToken>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let revoked = diesel::update(
+                refresh_tokens::table
+                    .find(token_id)
+                    .filter(refresh_tokens::revoked.eq(false)),
+            )
+            .set(refresh_tokens::revoked.eq(true))
+            .execute(conn)?;
+            if revoked == 0 {
+                return Ok(None);
+            }
+
+            diesel::insert_into(refresh_tokens::table)
+                .values(&new_token)
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e: diesel::result::Error| {
+            tracing::error!("Failed to rotate refresh token {}: {}", token_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Revoke every refresh token of a family
+pub async fn revoke_family(pool: &DbPool, family_id: Uuid) -> Result<usize, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            refresh_tokens::table
+                .filter(refresh_tokens::family_id.eq(family_id))
+                .filter(refresh_tokens::revoked.eq(false)),
+        )
+        .set(refresh_tokens::revoked.eq(true))
+        .execute(&mut conn)
+        .map_err(|e| {
+            tracing::error!("Failed to revoke refresh token family {}: {}", family_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::split_group::{NewSplitGroup, NewSplitGroupMember, SplitGroup, SplitGroupMember},
+    schema::{split_group_members, split_groups},
+};
+
+/// A split group with its members
+pub type GroupWithMembers = (SplitGroup, Vec<SplitGroupMember>);
+
+/// Create a split group together with its members
+///
+/// `members` pairs each person with their percentage; the group and its members
+/// are inserted in one DB transaction.
+pub async fn create_group(
+    pool: &DbPool,
+    new_group: NewSplitGroup,
+    members: Vec<(Uuid, BigDecimal)>,
+) -> Result<GroupWithMembers, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let group: SplitGroup = diesel::insert_into(split_groups::table)
+                .values(&new_group)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create split group for user {}: {}",
+                        new_group.user_id,
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+
+            let members = insert_members(conn, group.id, members)?;
+
+            Ok((group, members))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find split group by ID, with its members
+pub async fn find_by_id(pool: &DbPool, group_id: Uuid) -> Result<GroupWithMembers, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let group: SplitGroup = split_groups::table
+            .find(group_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find split group by id {}: {}", group_id, e);
+                ApiError::from(e)
+            })?;
+
+        let mut groups = with_members(&mut conn, vec![group])?;
+        Ok(groups.remove(0))
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List all split groups of a user, with their members
+pub async fn list_by_user(pool: &DbPool, user_id: Uuid) -> Result<Vec<GroupWithMembers>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let groups = split_groups::table
+            .filter(split_groups::user_id.eq(user_id))
+            .order(split_groups::name.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list split groups for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })?;
+
+        with_members(&mut conn, groups)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Rename a split group and/or replace its members, in one DB transaction
+pub async fn update_group(
+    pool: &DbPool,
+    group_id: Uuid,
+    name: Option<String>,
+    members: Option<Vec<(Uuid, BigDecimal)>>,
+) -> Result<GroupWithMembers, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            // Touch the group even when only members change, so `updated_at` moves
+            let group: SplitGroup = match name {
+                Some(name) => diesel::update(split_groups::table.find(group_id))
+                    .set(split_groups::name.eq(name))
+                    .get_result(conn),
+                None => diesel::update(split_groups::table.find(group_id))
+                    .set(split_groups::updated_at.eq(diesel::dsl::now))
+                    .get_result(conn),
+            }
+            .map_err(|e| {
+                tracing::error!("Failed to update split group {}: {}", group_id, e);
+                ApiError::from(e)
+            })?;
+
+            let members = match members {
+                Some(members) => {
+                    diesel::delete(
+                        split_group_members::table
+                            .filter(split_group_members::group_id.eq(group_id)),
+                    )
+                    .execute(conn)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Failed to remove members of split group {}: {}",
+                            group_id,
+                            e
+                        );
+                        ApiError::from(e)
+                    })?;
+                    insert_members(conn, group_id, members)?
+                }
+                None => with_members(conn, vec![group.clone()])?.remove(0).1,
+            };
+
+            Ok((group, members))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete a split group (its members are removed by cascade)
+pub async fn delete_group(pool: &DbPool, group_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::delete(split_groups::table.find(group_id))
+            .execute(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete split group {}: {}", group_id, e);
+                ApiError::from(e)
+            })
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Insert the members of a group, returned in ascending person ID order
+fn insert_members(
+    conn: &mut PgConnection,
+    group_id: Uuid,
+    members: Vec<(Uuid, BigDecimal)>,
+) -> Result<Vec<SplitGroupMember>, ApiError> {
+    let members: Vec<NewSplitGroupMember> = members
+        .into_iter()
+        .map(|(person_id, percentage)| NewSplitGroupMember {
+            group_id,
+            person_id,
+            percentage,
+        })
+        .collect();
+    let mut members: Vec<SplitGroupMember> = diesel::insert_into(split_group_members::table)
+        .values(&members)
+        .get_results(conn)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to create members of split group {}: {}",
+                group_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+    members.sort_by_key(|member| member.person_id);
+
+    Ok(members)
+}
+
+/// Load the members of `groups`, in ascending person ID order
+fn with_members(
+    conn: &mut PgConnection,
+    groups: Vec<SplitGroup>,
+) -> Result<Vec<GroupWithMembers>, ApiError> {
+    let group_ids: Vec<Uuid> = groups.iter().map(|group| group.id).collect();
+    let members: Vec<SplitGroupMember> = split_group_members::table
+        .filter(split_group_members::group_id.eq_any(&group_ids))
+        .order(split_group_members::person_id.asc())
+        .load(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to load split group members: {}", e);
+            ApiError::from(e)
+        })?;
+
+    let mut by_group: HashMap<Uuid, Vec<SplitGroupMember>> = HashMap::new();
+    for member in members {
+        by_group.entry(member.group_id).or_default().push(member);
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|group| {
+            let members = by_group.remove(&group.id).unwrap_or_default();
+            (group, members)
+        })
+        .collect())
+}
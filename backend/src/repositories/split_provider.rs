@@ -1,9 +1,11 @@
 use crate::{
     DbPool,
+    db::DbConnection,
     errors::ApiError,
     models::{NewSplitProvider, SplitProvider},
     schema::split_providers,
 };
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
 use diesel::prelude::*;
 use uuid::Uuid;
 
@@ -105,6 +107,8 @@ pub async fn upsert_provider(
             .set((
                 split_providers::credentials.eq(&new_provider.credentials),
                 split_providers::is_active.eq(&new_provider.is_active),
+                split_providers::consecutive_auth_failures.eq(0),
+                split_providers::disabled_by_user.eq(false),
                 split_providers::updated_at.eq(diesel::dsl::now),
             ))
             .get_result::<SplitProvider>(&mut conn)
@@ -186,3 +190,228 @@ pub async fn update_active_status(
         ApiError::from(e)
     })
 }
+
+/// Enable or disable a provider at the user's request
+///
+/// Enabling also clears the provider's auth failures, so it gets a fresh
+/// allowance of attempts with its current credentials.
+pub async fn set_enabled(
+    pool: &DbPool,
+    id: Uuid,
+    user_id: Uuid,
+    enabled: bool,
+) -> Result<SplitProvider, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::InternalWithMessage("Failed to get database connection".to_string())
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let target = split_providers::table
+            .filter(split_providers::id.eq(id))
+            .filter(split_providers::user_id.eq(user_id));
+        if enabled {
+            diesel::update(target)
+                .set((
+                    split_providers::is_active.eq(true),
+                    split_providers::disabled_by_user.eq(false),
+                    split_providers::consecutive_auth_failures.eq(0),
+                    split_providers::updated_at.eq(diesel::dsl::now),
+                ))
+                .get_result::<SplitProvider>(&mut conn)
+        } else {
+            diesel::update(target)
+                .set((
+                    split_providers::is_active.eq(false),
+                    split_providers::disabled_by_user.eq(true),
+                    split_providers::updated_at.eq(diesel::dsl::now),
+                ))
+                .get_result::<SplitProvider>(&mut conn)
+        }
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::InternalWithMessage("Task execution error".to_string())
+    })?
+    .map_err(|e| {
+        tracing::error!("Failed to update provider {} status: {}", id, e);
+        ApiError::from(e)
+    })
+}
+
+/// Count a request rejected for bad credentials
+///
+/// Once `max_failures` requests in a row have been rejected, the provider is
+/// marked inactive, which flags it for reconnection.
+pub async fn record_auth_failure(
+    pool: &DbPool,
+    id: Uuid,
+    max_failures: i32,
+) -> Result<SplitProvider, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::InternalWithMessage("Failed to get database connection".to_string())
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let provider = diesel::update(split_providers::table.find(id))
+                .set(
+                    split_providers::consecutive_auth_failures
+                        .eq(split_providers::consecutive_auth_failures + 1),
+                )
+                .get_result::<SplitProvider>(conn)?;
+
+            if provider.is_active && provider.consecutive_auth_failures >= max_failures {
+                return diesel::update(split_providers::table.find(id))
+                    .set((
+                        split_providers::is_active.eq(false),
+                        split_providers::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .get_result::<SplitProvider>(conn);
+            }
+
+            Ok(provider)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::InternalWithMessage("Task execution error".to_string())
+    })?
+    .map_err(|e| {
+        tracing::error!("Failed to record auth failure of provider {}: {}", id, e);
+        ApiError::from(e)
+    })
+}
+
+/// Clear a provider's auth failures after a request was accepted
+pub async fn reset_auth_failures(pool: &DbPool, id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::InternalWithMessage("Failed to get database connection".to_string())
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            split_providers::table
+                .filter(split_providers::id.eq(id))
+                .filter(split_providers::consecutive_auth_failures.gt(0)),
+        )
+        .set(split_providers::consecutive_auth_failures.eq(0))
+        .execute(&mut conn)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::InternalWithMessage("Task execution error".to_string())
+    })?
+    .map_err(|e| {
+        tracing::error!("Failed to reset auth failures of provider {}: {}", id, e);
+        ApiError::from(e)
+    })?;
+
+    Ok(())
+}
+
+/// A split provider row locked with `SELECT … FOR UPDATE`
+///
+/// The lock is held by an open database transaction on a dedicated connection
+/// until [`LockedSplitProvider::update_credentials`] commits or
+/// [`LockedSplitProvider::release`] rolls back, so it can span awaits such as a
+/// credentials refresh. Dropping it instead rolls back too: the pool discards
+/// connections left inside a transaction.
+pub struct LockedSplitProvider {
+    conn: DbConnection,
+    pub provider: SplitProvider,
+}
+
+/// Lock a split provider row for the duration of a credentials refresh
+pub async fn lock_for_update(pool: &DbPool, id: Uuid) -> Result<LockedSplitProvider, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::InternalWithMessage("Failed to get database connection".to_string())
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        AnsiTransactionManager::begin_transaction(&mut *conn)?;
+        let provider = split_providers::table
+            .find(id)
+            .for_update()
+            .first::<SplitProvider>(&mut *conn);
+        match provider {
+            Ok(provider) => Ok(LockedSplitProvider { conn, provider }),
+            Err(e) => {
+                let _ = AnsiTransactionManager::rollback_transaction(&mut *conn);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::InternalWithMessage("Task execution error".to_string())
+    })?
+    .map_err(|e| {
+        tracing::error!("Failed to lock provider {}: {}", id, e);
+        ApiError::from(e)
+    })
+}
+
+impl LockedSplitProvider {
+    /// Replace the provider's stored credentials and release the lock
+    pub async fn update_credentials(
+        self,
+        credentials: serde_json::Value,
+    ) -> Result<SplitProvider, ApiError> {
+        let Self { mut conn, provider } = self;
+        let id = provider.id;
+
+        tokio::task::spawn_blocking(move || {
+            let updated = diesel::update(split_providers::table.find(id))
+                .set((
+                    split_providers::credentials.eq(credentials),
+                    split_providers::updated_at.eq(diesel::dsl::now),
+                ))
+                .get_result::<SplitProvider>(&mut *conn);
+            match updated {
+                Ok(updated) => {
+                    AnsiTransactionManager::commit_transaction(&mut *conn)?;
+                    Ok(updated)
+                }
+                Err(e) => {
+                    let _ = AnsiTransactionManager::rollback_transaction(&mut *conn);
+                    Err(e)
+                }
+            }
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::InternalWithMessage("Task execution error".to_string())
+        })?
+        .map_err(|e| {
+            tracing::error!("Failed to update credentials of provider {}: {}", id, e);
+            ApiError::from(e)
+        })
+    }
+
+    /// Release the lock without changing the provider
+    pub async fn release(self) -> Result<(), ApiError> {
+        let Self { mut conn, provider } = self;
+
+        tokio::task::spawn_blocking(move || {
+            AnsiTransactionManager::rollback_transaction(&mut *conn)
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::InternalWithMessage("Task execution error".to_string())
+        })?
+        .map_err(|e| {
+            tracing::error!("Failed to release lock on provider {}: {}", provider.id, e);
+            ApiError::from(e)
+        })
+    }
+}
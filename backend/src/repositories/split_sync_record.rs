@@ -4,9 +4,10 @@ use uuid::Uuid;
 use crate::DbPool;
 use crate::errors::{ApiError, ApiResult};
 use crate::models::split_sync_record::{
-    NewSplitSyncRecord, SplitSyncRecord, UpdateSplitSyncRecord,
+    NewOrphanedExternalExpense, NewSplitSyncRecord, OrphanedExternalExpense, SplitSyncRecord,
+    UpdateSplitSyncRecord,
 };
-use crate::schema::split_sync_records;
+use crate::schema::{orphaned_external_expenses, split_sync_records};
 
 /// Repository for split sync record database operations
 pub struct SplitSyncRecordRepository;
@@ -70,6 +71,32 @@ impl SplitSyncRecordRepository {
         Ok(records)
     }
 
+    /// Find the sync records of several transaction splits with their provider types
+    pub fn find_with_provider_type_by_split_ids(
+        pool: &DbPool,
+        transaction_split_ids: &[Uuid],
+    ) -> ApiResult<Vec<(SplitSyncRecord, String)>> {
+        use crate::schema::split_providers;
+
+        if transaction_split_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        let records = split_sync_records::table
+            .inner_join(split_providers::table)
+            .filter(split_sync_records::transaction_split_id.eq_any(transaction_split_ids))
+            .order(split_sync_records::created_at.asc())
+            .select((SplitSyncRecord::as_select(), split_providers::provider_type))
+            .load::<(SplitSyncRecord, String)>(&mut conn)?;
+
+        Ok(records)
+    }
+
     /// Find sync record by ID
     pub fn find_by_id(pool: &DbPool, id: Uuid) -> ApiResult<Option<SplitSyncRecord>> {
         let mut conn = pool.get().map_err(|e| {
@@ -127,6 +154,9 @@ impl SplitSyncRecordRepository {
                 update
                     .retry_count
                     .map(|v| split_sync_records::retry_count.eq(v)),
+                update
+                    .external_user_id
+                    .map(|v| split_sync_records::external_user_id.eq(v)),
                 split_sync_records::updated_at.eq(diesel::dsl::now),
             ))
             .get_result::<SplitSyncRecord>(&mut conn)?;
@@ -179,4 +209,97 @@ impl SplitSyncRecordRepository {
 
         Ok(records)
     }
+
+    /// Find synced or drifted records of active providers due for reconciliation
+    ///
+    /// Records checked longest ago come first, so successive batches cycle through
+    /// all linked expenses.
+    pub fn find_for_reconciliation(
+        pool: &DbPool,
+        batch_size: i64,
+    ) -> ApiResult<Vec<SplitSyncRecord>> {
+        use crate::schema::split_providers;
+
+        let mut conn = pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        let records = split_sync_records::table
+            .inner_join(split_providers::table)
+            .filter(split_providers::is_active.eq(true))
+            .filter(split_sync_records::sync_status.eq_any(["synced", "drifted"]))
+            .filter(split_sync_records::external_expense_id.is_not_null())
+            .order(split_sync_records::last_sync_at.asc().nulls_first())
+            .limit(batch_size)
+            .select(SplitSyncRecord::as_select())
+            .load::<SplitSyncRecord>(&mut conn)?;
+
+        Ok(records)
+    }
+
+    /// Count a user's sync records by sync status
+    pub fn count_by_status_for_user(pool: &DbPool, user_id: Uuid) -> ApiResult<Vec<(String, i64)>> {
+        use crate::schema::split_providers;
+
+        let mut conn = pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        let counts = split_sync_records::table
+            .inner_join(split_providers::table)
+            .filter(split_providers::user_id.eq(user_id))
+            .group_by(split_sync_records::sync_status)
+            .select((split_sync_records::sync_status, diesel::dsl::count_star()))
+            .load::<(String, i64)>(&mut conn)?;
+
+        Ok(counts)
+    }
+
+    /// Record an external expense left behind by a force-deleted transaction
+    ///
+    /// Recording the same expense again keeps the first record.
+    pub fn record_orphaned_expense(
+        pool: &DbPool,
+        new_orphan: NewOrphanedExternalExpense,
+    ) -> ApiResult<()> {
+        let mut conn = pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        diesel::insert_into(orphaned_external_expenses::table)
+            .values(&new_orphan)
+            .on_conflict((
+                orphaned_external_expenses::split_provider_id,
+                orphaned_external_expenses::external_expense_id,
+            ))
+            .do_nothing()
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    /// List the orphaned external expenses of a user's providers
+    pub fn list_orphaned_for_user(
+        pool: &DbPool,
+        user_id: Uuid,
+    ) -> ApiResult<Vec<OrphanedExternalExpense>> {
+        use crate::schema::split_providers;
+
+        let mut conn = pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        let orphans = orphaned_external_expenses::table
+            .inner_join(split_providers::table)
+            .filter(split_providers::user_id.eq(user_id))
+            .order(orphaned_external_expenses::created_at.asc())
+            .select(OrphanedExternalExpense::as_select())
+            .load::<OrphanedExternalExpense>(&mut conn)?;
+
+        Ok(orphans)
+    }
 }
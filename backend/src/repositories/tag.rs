@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewTag, NewTransactionTag, Tag},
+    schema::{tags, transaction_tags},
+};
+
+/// Replace a transaction's tags with `names`, creating the user's missing tags
+///
+/// `names` must already be normalized. Runs on the caller's connection so the
+/// tags are written in the same DB transaction as the transaction itself.
+pub fn set_transaction_tags(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    names: &[String],
+) -> Result<(), ApiError> {
+    replace_tags(conn, user_id, transaction_id, names).map_err(|e| {
+        tracing::error!(
+            "Failed to set tags of transaction {}: {}",
+            transaction_id,
+            e
+        );
+        ApiError::from(e)
+    })
+}
+
+fn replace_tags(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    names: &[String],
+) -> QueryResult<()> {
+    let new_tags: Vec<NewTag> = names
+        .iter()
+        .map(|name| NewTag {
+            user_id,
+            name: name.clone(),
+        })
+        .collect();
+    diesel::insert_into(tags::table)
+        .values(&new_tags)
+        .on_conflict((tags::user_id, tags::name))
+        .do_nothing()
+        .execute(conn)?;
+
+    let tag_ids: Vec<Uuid> = tags::table
+        .filter(tags::user_id.eq(user_id))
+        .filter(tags::name.eq_any(names))
+        .select(tags::id)
+        .load(conn)?;
+
+    diesel::delete(
+        transaction_tags::table.filter(transaction_tags::transaction_id.eq(transaction_id)),
+    )
+    .execute(conn)?;
+
+    let links: Vec<NewTransactionTag> = tag_ids
+        .into_iter()
+        .map(|tag_id| NewTransactionTag {
+            transaction_id,
+            tag_id,
+        })
+        .collect();
+    diesel::insert_into(transaction_tags::table)
+        .values(&links)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Tag names of each of the given transactions, sorted by name
+///
+/// Transactions without tags are not in the returned map.
+pub async fn names_by_transaction(
+    pool: &DbPool,
+    transaction_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, Vec<String>>, ApiError> {
+    if transaction_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    let rows: Vec<(Uuid, String)> = tokio::task::spawn_blocking(move || {
+        transaction_tags::table
+            .inner_join(tags::table)
+            .filter(transaction_tags::transaction_id.eq_any(transaction_ids))
+            .select((transaction_tags::transaction_id, tags::name))
+            .order(tags::name.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to load transaction tags: {}", e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })??;
+
+    let mut names: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for (transaction_id, name) in rows {
+        names.entry(transaction_id).or_default().push(name);
+    }
+    Ok(names)
+}
+
+#[derive(Debug, QueryableByName)]
+struct TagUsageRow {
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    user_id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Varchar)]
+    name: String,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    created_at: DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    usage_count: i64,
+}
+
+/// List a user's tags by name, with the number of transactions using each
+///
+/// Deleted transactions are not counted.
+pub async fn list_with_usage(pool: &DbPool, user_id: Uuid) -> Result<Vec<(Tag, i64)>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    let rows: Vec<TagUsageRow> = tokio::task::spawn_blocking(move || {
+        diesel::sql_query(
+            "SELECT tags.id, tags.user_id, tags.name, tags.created_at, count(transactions.id) AS usage_count \
+             FROM tags \
+             LEFT JOIN transaction_tags ON transaction_tags.tag_id = tags.id \
+             LEFT JOIN transactions ON transactions.id = transaction_tags.transaction_id \
+                  AND transactions.deleted_at IS NULL \
+             WHERE tags.user_id = $1 \
+             GROUP BY tags.id \
+             ORDER BY tags.name",
+        )
+        .bind::<diesel::sql_types::Uuid, _>(user_id)
+        .load(&mut conn)
+        .map_err(|e| {
+            tracing::error!("Failed to list tags for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })??;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let tag = Tag {
+                id: row.id,
+                user_id: row.user_id,
+                name: row.name,
+                created_at: row.created_at,
+            };
+            (tag, row.usage_count)
+        })
+        .collect())
+}
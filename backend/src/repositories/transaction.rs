@@ -1,16 +1,28 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use diesel::pg::{Pg, PgRowByRowLoadingMode};
 use diesel::prelude::*;
+use std::collections::HashMap;
 use std::str::FromStr;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::{
     DbPool,
     errors::ApiError,
     models::{
-        transaction::{NewTransaction, Transaction, TransactionFilter, UpdateTransaction},
+        AuditEntityType, Pagination, PurgeCounts,
+        split_sync_record::SyncStatus,
+        tag::TagMode,
+        transaction::{
+            NewTransaction, Transaction, TransactionFilter, TransactionSortKey, TransactionType,
+            UpdateTransaction,
+        },
         transaction_split::{NewTransactionSplit, TransactionSplit},
     },
-    schema::{transaction_splits, transactions},
+    repositories::{account, audit_log, tag},
+    schema::{split_sync_records, tags, transaction_splits, transaction_tags, transactions},
+    types::{CurrencyCode, TransactionStatus},
 };
 
 /// Create a new transaction
@@ -25,11 +37,1670 @@ pub async fn create_transaction(
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(transactions::table)
-            .values(&new_transaction)
+        conn.transaction(|conn| {
+            let transaction =
+                insert_transactions(conn, user_id, std::slice::from_ref(&new_transaction))?
+                    .pop()
+                    .ok_or(ApiError::Internal)?;
+            Ok(transaction)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create several transactions at once, all or none, after checking the
+/// resulting balance of their accounts
+///
+/// Every affected account is locked and its balance read inside the same DB
+/// transaction as the insert. `check` is called for each new transaction in
+/// order with the balance of its account before it is applied, so earlier
+/// transactions of the batch count against later ones; its error aborts the
+/// whole insert. Its results are returned in the order of the transactions.
+pub async fn create_transactions_checked<F, T>(
+    pool: &DbPool,
+    user_id: Uuid,
+    new_transactions: Vec<NewTransaction>,
+    mut check: F,
+) -> Result<(Vec<Transaction>, Vec<T>), ApiError>
+where
+    F: FnMut(&NewTransaction, &BigDecimal) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            // Lock accounts in a fixed order so concurrent batches cannot deadlock
+            let mut account_ids: Vec<Uuid> =
+                new_transactions.iter().map(|t| t.account_id).collect();
+            account_ids.sort();
+            account_ids.dedup();
+
+            let mut balances = HashMap::new();
+            for account_id in account_ids {
+                let balance = account::lock_and_calculate_balance(conn, account_id)?;
+                balances.insert(account_id, balance);
+            }
+
+            let mut checked = Vec::with_capacity(new_transactions.len());
+            for new_transaction in &new_transactions {
+                let balance = balances
+                    .get_mut(&new_transaction.account_id)
+                    .ok_or(ApiError::Internal)?;
+                checked.push(check(new_transaction, balance)?);
+                if new_transaction.status != TransactionStatus::Void {
+                    *balance += &new_transaction.amount;
+                }
+            }
+
+            let transactions = insert_transactions(conn, user_id, &new_transactions)?;
+            Ok((transactions, checked))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create a new transaction after checking the account's resulting balance
+///
+/// The account row is locked and its balance read inside the same DB
+/// transaction as the insert, so `check` always sees the balance the new
+/// transaction is applied to. `check` receives the current balance and its
+/// error aborts the insert.
+pub async fn create_transaction_checked<F, T>(
+    pool: &DbPool,
+    user_id: Uuid,
+    new_transaction: NewTransaction,
+    check: F,
+) -> Result<(Transaction, T), ApiError>
+where
+    F: FnOnce(&BigDecimal) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let balance = account::lock_and_calculate_balance(conn, new_transaction.account_id)?;
+            let checked = check(&balance)?;
+
+            let transaction =
+                insert_transactions(conn, user_id, std::slice::from_ref(&new_transaction))?
+                    .pop()
+                    .ok_or(ApiError::Internal)?;
+
+            Ok((transaction, checked))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Insert transactions with their tags and record them in their owner's audit
+/// log, inside the caller's DB transaction
+fn insert_transactions(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    new_transactions: &[NewTransaction],
+) -> Result<Vec<Transaction>, ApiError> {
+    if new_transactions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let created: Vec<Transaction> = diesel::insert_into(transactions::table)
+        .values(new_transactions)
+        .get_results(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to create transactions for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })?;
+    for (new_transaction, transaction) in new_transactions.iter().zip(&created) {
+        if !new_transaction.tags.is_empty() {
+            tag::set_transaction_tags(conn, user_id, transaction.id, &new_transaction.tags)?;
+        }
+        record_audit(conn, None, Some(transaction))?;
+    }
+    Ok(created)
+}
+
+/// Record a change to a transaction in its owner's audit log
+///
+/// Creations have no `before` and deletions no `after`. Soft deletions are
+/// recorded as deletions and restores as creations.
+fn record_audit(
+    conn: &mut PgConnection,
+    before: Option<&Transaction>,
+    after: Option<&Transaction>,
+) -> Result<(), ApiError> {
+    let Some(transaction) = after.or(before) else {
+        return Ok(());
+    };
+    audit_log::record(
+        conn,
+        transaction.user_id,
+        AuditEntityType::Transaction,
+        transaction.id,
+        before,
+        after,
+    )
+}
+
+/// Record soft-deleted transactions, as returned by the deletion, in their
+/// owners' audit logs
+fn record_deleted(conn: &mut PgConnection, deleted: Vec<Transaction>) -> Result<(), ApiError> {
+    for transaction in deleted {
+        let before = Transaction {
+            deleted_at: None,
+            ..transaction
+        };
+        record_audit(conn, Some(&before), None)?;
+    }
+    Ok(())
+}
+
+/// Find transaction by ID; deleted transactions are not found
+pub async fn find_by_id(pool: &DbPool, transaction_id: Uuid) -> Result<Transaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .find(transaction_id)
+            .filter(transactions::deleted_at.is_null())
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find transaction by id {}: {}", transaction_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find transaction by ID, whether deleted or not
+pub async fn find_by_id_with_deleted(
+    pool: &DbPool,
+    transaction_id: Uuid,
+) -> Result<Transaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .find(transaction_id)
+            .first(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find transaction by id {}: {}", transaction_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Find a user's transactions imported with any of the given external IDs
+///
+/// Deleted transactions are included, so deleted imports are not imported again.
+pub async fn find_by_external_ids(
+    pool: &DbPool,
+    user_id: Uuid,
+    external_ids: Vec<String>,
+) -> Result<Vec<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::external_id.eq_any(external_ids))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to find transactions by external id for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List a user's transactions in any of the given accounts dated between `start` and `end`
+///
+/// Both bounds are inclusive. Deleted transactions are left out.
+pub async fn list_by_accounts_between(
+    pool: &DbPool,
+    user_id: Uuid,
+    account_ids: Vec<Uuid>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::account_id.eq_any(account_ids))
+            .filter(transactions::deleted_at.is_null())
+            .filter(transactions::date.between(start, end))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list transactions between {} and {} for user {}: {}",
+                    start,
+                    end,
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count a user's transactions per category among those with a matching title
+///
+/// Titles are compared lowercased with surrounding whitespace trimmed and inner
+/// whitespace collapsed; `normalized_title` must already be normalized that way.
+/// The most used category comes first, ties going to the most recently used.
+pub async fn count_categories_by_title(
+    pool: &DbPool,
+    user_id: Uuid,
+    normalized_title: String,
+) -> Result<Vec<(Uuid, i64)>, ApiError> {
+    use diesel::dsl::{count_star, max, sql};
+    use diesel::sql_types::{Bool, Text};
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::deleted_at.is_null())
+            .filter(transactions::category_id.is_not_null())
+            .filter(
+                sql::<Bool>("lower(regexp_replace(btrim(title), '\\s+', ' ', 'g')) = ")
+                    .bind::<Text, _>(normalized_title),
+            )
+            .group_by(transactions::category_id)
+            .select((transactions::category_id.assume_not_null(), count_star()))
+            .order((count_star().desc(), max(transactions::date).desc()))
+            .load::<(Uuid, i64)>(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to count categories by title for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the IDs and titles of a user's uncategorized transactions
+///
+/// Deleted transactions and transfers, which never have a category, are left out.
+pub async fn list_uncategorized_titles(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<(Uuid, String)>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::deleted_at.is_null())
+            .filter(transactions::category_id.is_null())
+            .filter(transactions::transfer_id.is_null())
+            .select((transactions::id, transactions::title))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list uncategorized transactions for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Set the category of uncategorized transactions, all in one DB transaction
+///
+/// `assignments` are `(transaction_id, category_id)` pairs. Transactions given
+/// a category in the meantime are skipped. Returns the IDs of the updated
+/// transactions.
+pub async fn assign_categories(
+    pool: &DbPool,
+    assignments: Vec<(Uuid, Uuid)>,
+) -> Result<Vec<Uuid>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let mut updated = Vec::new();
+            for (transaction_id, category_id) in &assignments {
+                let transaction: Option<Transaction> = diesel::update(
+                    transactions::table
+                        .find(transaction_id)
+                        .filter(transactions::category_id.is_null()),
+                )
+                .set((
+                    transactions::category_id.eq(category_id),
+                    transactions::version.eq(transactions::version + 1),
+                ))
+                .get_result(conn)
+                .optional()
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to assign categories to {} transactions: {}",
+                        assignments.len(),
+                        e
+                    );
+                    ApiError::from(e)
+                })?;
+                if let Some(transaction) = transaction {
+                    let before = Transaction {
+                        category_id: None,
+                        ..transaction.clone()
+                    };
+                    record_audit(conn, Some(&before), Some(&transaction))?;
+                    updated.push(*transaction_id);
+                }
+            }
+            Ok(updated)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Sum a user's posted expenses per account, category and day
+///
+/// Days are UTC dates from `start` through `end`, or onwards when `end` is
+/// `None`. Returns `(account_id, category_id, day, total)` with negative totals.
+/// Transfers between accounts are not expenses and are left out.
+pub async fn sum_expenses_by_day(
+    pool: &DbPool,
+    user_id: Uuid,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+) -> Result<Vec<(Uuid, Option<Uuid>, NaiveDate, BigDecimal)>, ApiError> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Bool, Date, Nullable, Numeric, Timestamptz, Uuid as SqlUuid};
+
+    const DAY: &str = "(transactions.date at time zone 'UTC')::date";
+
+    let start = start.and_time(NaiveTime::MIN).and_utc();
+    // Exclusive upper bound: the start of the day after `end`
+    let before = end
+        .and_then(|end| end.succ_opt())
+        .map(|end| end.and_time(NaiveTime::MIN).and_utc());
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::status.eq(TransactionStatus::Posted))
+            .filter(transactions::amount.lt(BigDecimal::from(0)))
+            .filter(transactions::transfer_id.is_null())
+            .filter(transactions::deleted_at.is_null())
+            .filter(transactions::date.ge(start))
+            .filter(
+                sql::<Bool>("transactions.date < coalesce(")
+                    .bind::<Nullable<Timestamptz>, _>(before)
+                    .sql(", 'infinity'::timestamptz)"),
+            )
+            // Diesel cannot mix columns and SQL expressions in a grouped query
+            .group_by(sql::<Date>(&format!(
+                "transactions.account_id, transactions.category_id, {}",
+                DAY
+            )))
+            .select((
+                sql::<SqlUuid>("transactions.account_id"),
+                sql::<Nullable<SqlUuid>>("transactions.category_id"),
+                sql::<Date>(DAY),
+                sql::<Numeric>("sum(transactions.amount)"),
+            ))
+            .load::<(Uuid, Option<Uuid>, NaiveDate, BigDecimal)>(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to sum expenses by day for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Sum a user's posted income and expenses per month and account currency
+///
+/// Months are UTC calendar months from the one containing `start`, each
+/// returned as its first day. Returns `(month, currency, income, expense)` with
+/// a negative `expense`. Transfers between accounts are left out.
+pub async fn sum_cashflow_by_month(
+    pool: &DbPool,
+    user_id: Uuid,
+    start: NaiveDate,
+) -> Result<Vec<(NaiveDate, CurrencyCode, BigDecimal, BigDecimal)>, ApiError> {
+    use crate::schema::{accounts, sql_types::CurrencyCode as SqlCurrencyCode};
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Date, Numeric};
+
+    const MONTH: &str = "date_trunc('month', transactions.date at time zone 'UTC')::date";
+
+    let start = start.and_time(NaiveTime::MIN).and_utc();
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .inner_join(accounts::table)
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::status.eq(TransactionStatus::Posted))
+            .filter(transactions::transfer_id.is_null())
+            .filter(transactions::deleted_at.is_null())
+            .filter(transactions::date.ge(start))
+            // Diesel cannot mix columns and SQL expressions in a grouped query
+            .group_by(sql::<Date>(&format!("{}, accounts.currency", MONTH)))
+            .select((
+                sql::<Date>(MONTH),
+                sql::<SqlCurrencyCode>("accounts.currency"),
+                sql::<Numeric>(
+                    "coalesce(sum(transactions.amount) filter (where transactions.amount > 0), 0)",
+                ),
+                sql::<Numeric>(
+                    "coalesce(sum(transactions.amount) filter (where transactions.amount < 0), 0)",
+                ),
+            ))
+            .load::<(NaiveDate, CurrencyCode, BigDecimal, BigDecimal)>(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to sum cashflow by month for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count the income and expense transactions in a category
+///
+/// `exclude_id` leaves out a transaction, e.g. the one being updated. Returns
+/// `(income, expenses)`.
+pub async fn count_directions_in_category(
+    pool: &DbPool,
+    category_id: Uuid,
+    exclude_id: Option<Uuid>,
+) -> Result<(i64, i64), ApiError> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::BigInt;
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut query = transactions::table
+            .filter(transactions::category_id.eq(category_id))
+            .filter(transactions::status.ne(TransactionStatus::Void))
+            .filter(transactions::deleted_at.is_null())
+            .into_boxed();
+        if let Some(exclude_id) = exclude_id {
+            query = query.filter(transactions::id.ne(exclude_id));
+        }
+        query
+            .select((
+                sql::<BigInt>("count(*) filter (where amount > 0)"),
+                sql::<BigInt>("count(*) filter (where amount < 0)"),
+            ))
+            .get_result::<(i64, i64)>(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to count transactions in category {}: {}",
+                    category_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// A user's transactions matching `filters`, without ordering or pagination
+fn filtered_query(
+    user_id: Uuid,
+    filters: &TransactionFilter,
+) -> Result<transactions::BoxedQuery<'static, Pg>, ApiError> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Bool, Text};
+
+    let mut query = transactions::table
+        .filter(transactions::user_id.eq(user_id))
+        .into_boxed();
+
+    if !filters.include_deleted {
+        query = query.filter(transactions::deleted_at.is_null());
+    }
+
+    if let Some(account_id) = filters.account_id {
+        query = query.filter(transactions::account_id.eq(account_id));
+    }
+
+    if let Some(category_id) = filters.category_id {
+        query = query.filter(transactions::category_id.eq(category_id));
+    }
+
+    if filters.uncategorized {
+        query = query
+            .filter(transactions::category_id.is_null())
+            .filter(transactions::transfer_id.is_null());
+    }
+
+    if let Some(start_date) = filters.start_date {
+        query = query.filter(transactions::date.ge(start_date));
+    }
+
+    if let Some(end_date) = filters.end_date {
+        query = query.filter(transactions::date.le(end_date));
+    }
+
+    if let Some(updated_since) = filters.updated_since {
+        query = query.filter(transactions::updated_at.ge(updated_since));
+    }
+
+    if let Some(status) = filters.status {
+        query = query.filter(transactions::status.eq(status));
+    }
+
+    if let Some(min_amount) = filters.min_amount {
+        let min_bd = BigDecimal::from_str(&min_amount.to_string()).map_err(|e| {
+            tracing::error!("Failed to convert min_amount to BigDecimal: {}", e);
+            ApiError::Validation("Invalid min_amount".to_string())
+        })?;
+        query = query.filter(transactions::amount.ge(min_bd));
+    }
+
+    if let Some(max_amount) = filters.max_amount {
+        let max_bd = BigDecimal::from_str(&max_amount.to_string()).map_err(|e| {
+            tracing::error!("Failed to convert max_amount to BigDecimal: {}", e);
+            ApiError::Validation("Invalid max_amount".to_string())
+        })?;
+        query = query.filter(transactions::amount.le(max_bd));
+    }
+
+    match filters.transaction_type {
+        Some(TransactionType::Income) => {
+            query = query.filter(transactions::amount.gt(BigDecimal::from(0)));
+        }
+        Some(TransactionType::Expense) => {
+            query = query.filter(transactions::amount.lt(BigDecimal::from(0)));
+        }
+        Some(TransactionType::Transfer) => {
+            query = query.filter(transactions::transfer_id.is_not_null());
+        }
+        None => {}
+    }
+
+    if let Some(merchant) = &filters.merchant {
+        query = query.filter(
+            sql::<Bool>("lower(merchant) = lower(")
+                .bind::<Text, _>(merchant.trim().to_string())
+                .sql(")"),
+        );
+    }
+
+    if let Some(search) = &filters.search {
+        let search_pattern = format!("%{}%", search);
+        query = query.filter(
+            transactions::title
+                .ilike(search_pattern.clone())
+                .or(transactions::notes.ilike(search_pattern)),
+        );
+    }
+
+    if let Some(reimbursable) = filters.reimbursable {
+        query = query.filter(transactions::reimbursable.eq(reimbursable));
+    }
+
+    match filters.reimbursed {
+        Some(true) => query = query.filter(transactions::reimbursed_at.is_not_null()),
+        Some(false) => query = query.filter(transactions::reimbursed_at.is_null()),
+        None => {}
+    }
+
+    let tag_names = filters.tag_names().map_err(ApiError::Validation)?;
+    if !tag_names.is_empty() {
+        let tagged_with = |names: Vec<String>| {
+            transaction_tags::table
+                .inner_join(tags::table)
+                .filter(tags::user_id.eq(user_id))
+                .filter(tags::name.eq_any(names))
+                .select(transaction_tags::transaction_id)
+        };
+        match filters.tag_mode {
+            TagMode::Any => {
+                query = query.filter(transactions::id.eq_any(tagged_with(tag_names)));
+            }
+            TagMode::All => {
+                for name in tag_names {
+                    query = query.filter(transactions::id.eq_any(tagged_with(vec![name])));
+                }
+            }
+        }
+    }
+
+    Ok(query)
+}
+
+/// List transactions for a user with optional filters, in the order of `filters.sort`
+pub async fn list_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<Vec<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        sorted_page_query(user_id, &filters)?
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list transactions for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// The transactions matching the filters, in the order of `filters.sort`,
+/// limited to the page given by `filters.limit` and `filters.offset`
+fn sorted_page_query(
+    user_id: Uuid,
+    filters: &TransactionFilter,
+) -> Result<transactions::BoxedQuery<'static, Pg>, ApiError> {
+    let sort = filters.sort_order().map_err(ApiError::Validation)?;
+    let mut query = filtered_query(user_id, filters)?;
+
+    // The id breaks ties, so pages don't shuffle between requests
+    query = match (sort.key, sort.descending) {
+        (TransactionSortKey::Date, false) => {
+            query.order((transactions::date.asc(), transactions::id.asc()))
+        }
+        (TransactionSortKey::Date, true) => {
+            query.order((transactions::date.desc(), transactions::id.desc()))
+        }
+        (TransactionSortKey::Amount, false) => {
+            query.order((transactions::amount.asc(), transactions::id.asc()))
+        }
+        (TransactionSortKey::Amount, true) => {
+            query.order((transactions::amount.desc(), transactions::id.desc()))
+        }
+        (TransactionSortKey::Title, false) => {
+            query.order((transactions::title.asc(), transactions::id.asc()))
+        }
+        (TransactionSortKey::Title, true) => {
+            query.order((transactions::title.desc(), transactions::id.desc()))
+        }
+    };
+
+    // Apply pagination (clamped by the Pagination extractor for API requests)
+    if let Some(limit) = filters.limit {
+        query = query.limit(limit);
+    }
+    if let Some(offset) = filters.offset {
+        query = query.offset(offset);
+    }
+
+    Ok(query)
+}
+
+/// Number of transactions sent at a time by [`stream_transactions`]
+const STREAM_BATCH_SIZE: usize = 500;
+
+/// Stream a user's transactions matching the filters, in the order of `filters.sort`
+///
+/// Only the page given by the filters' `limit` and `offset` is read, if set.
+/// Rows are read from the database one at a time and sent in batches, so the
+/// whole result set is never held in memory. Reading stops early when the
+/// receiver is dropped; a database error is sent as the last item.
+pub async fn stream_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<mpsc::Receiver<Result<Vec<Transaction>, ApiError>>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+    let query = sorted_page_query(user_id, &filters)?;
+
+    let (sender, receiver) = mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let rows = match query.load_iter::<Transaction, PgRowByRowLoadingMode>(&mut conn) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!("Failed to stream transactions for user {}: {}", user_id, e);
+                let _ = sender.blocking_send(Err(ApiError::from(e)));
+                return;
+            }
+        };
+
+        let mut batch = Vec::with_capacity(STREAM_BATCH_SIZE);
+        for row in rows {
+            match row {
+                Ok(transaction) => batch.push(transaction),
+                Err(e) => {
+                    tracing::error!("Failed to stream transactions for user {}: {}", user_id, e);
+                    let _ = sender.blocking_send(Err(ApiError::from(e)));
+                    return;
+                }
+            }
+            if batch.len() == STREAM_BATCH_SIZE
+                && sender
+                    .blocking_send(Ok(std::mem::take(&mut batch)))
+                    .is_err()
+            {
+                // The receiver is gone, e.g. the client disconnected
+                return;
+            }
+        }
+        if !batch.is_empty() {
+            let _ = sender.blocking_send(Ok(batch));
+        }
+    });
+
+    Ok(receiver)
+}
+
+/// Count a user's transactions matching the filters, ignoring pagination
+pub async fn count_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<i64, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        filtered_query(user_id, &filters)?
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to count transactions for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Count and total a user's transactions matching the filters, per account
+///
+/// Pagination in `filters` is ignored, so the totals cover every matching
+/// transaction. Returns `(account_id, count, income, expenses)`, where income
+/// sums the positive amounts and expenses the negative ones.
+pub async fn summarize_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<Vec<(Uuid, i64, BigDecimal, BigDecimal)>, ApiError> {
+    use diesel::dsl::{count_star, sql};
+    use diesel::sql_types::Numeric;
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        // Boxed queries cannot be grouped, so the filtered set is selected as a subquery
+        let matching = filtered_query(user_id, &filters)?.select(transactions::id);
+        transactions::table
+            .filter(transactions::id.eq_any(matching))
+            .group_by(transactions::account_id)
+            .select((
+                transactions::account_id,
+                count_star(),
+                sql::<Numeric>("coalesce(sum(amount) filter (where amount > 0), 0)"),
+                sql::<Numeric>("coalesce(sum(amount) filter (where amount < 0), 0)"),
+            ))
+            .load::<(Uuid, i64, BigDecimal, BigDecimal)>(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to summarize transactions for user {}: {}",
+                    user_id,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// A transaction after an update
+#[derive(Debug)]
+pub struct UpdatedTransaction {
+    pub transaction: Transaction,
+    /// The resulting splits and whether they must be re-synced, when the
+    /// update replaced them
+    pub splits: Option<(Vec<TransactionSplit>, bool)>,
+}
+
+/// Update transaction
+///
+/// All field updates, and the replacement of the splits, are applied atomically.
+pub async fn update_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    updates: UpdateTransaction,
+) -> Result<UpdatedTransaction, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| apply_updates(conn, transaction_id, updates))
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Update transaction after checking the resulting balance of `account_id`
+///
+/// Like [`create_transaction_checked`], the account row is locked for the
+/// duration of the update. `check` receives the account's current balance and
+/// the transaction as it is stored before the update.
+pub async fn update_transaction_checked<F, T>(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    updates: UpdateTransaction,
+    account_id: Uuid,
+    check: F,
+) -> Result<(UpdatedTransaction, T), ApiError>
+where
+    F: FnOnce(&BigDecimal, &Transaction) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let balance = account::lock_and_calculate_balance(conn, account_id)?;
+            let current: Transaction = transactions::table
+                .find(transaction_id)
+                .filter(transactions::deleted_at.is_null())
+                .for_update()
+                .first(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to lock transaction {}: {}", transaction_id, e);
+                    ApiError::from(e)
+                })?;
+            let checked = check(&balance, &current)?;
+
+            let updated = apply_updates(conn, transaction_id, updates)?;
+            Ok((updated, checked))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Increment a transaction's version, provided it is still at `expected_version` (if given)
+///
+/// Fails with [`ApiError::VersionConflict`] when the transaction has moved past `expected_version`.
+fn bump_version(
+    conn: &mut PgConnection,
+    transaction_id: Uuid,
+    expected_version: Option<i32>,
+) -> Result<(), ApiError> {
+    let next_version = transactions::version.eq(transactions::version + 1);
+    let updated = match expected_version {
+        Some(version) => diesel::update(
+            transactions::table
+                .find(transaction_id)
+                .filter(transactions::version.eq(version)),
+        )
+        .set(next_version)
+        .execute(conn),
+        None => diesel::update(transactions::table.find(transaction_id))
+            .set(next_version)
+            .execute(conn),
+    }
+    .map_err(|e| {
+        tracing::error!(
+            "Failed to update version of transaction {}: {}",
+            transaction_id,
+            e
+        );
+        ApiError::from(e)
+    })?;
+
+    if updated == 0 && expected_version.is_some() {
+        return Err(ApiError::VersionConflict(None));
+    }
+    Ok(())
+}
+
+/// Apply field updates to a transaction one at a time and return the result
+fn apply_updates(
+    conn: &mut PgConnection,
+    transaction_id: Uuid,
+    updates: UpdateTransaction,
+) -> Result<UpdatedTransaction, ApiError> {
+    // Claim the next version first, so a concurrent update of the same version fails
+    bump_version(conn, transaction_id, updates.expected_version)?;
+    let before: Transaction = transactions::table
+        .find(transaction_id)
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!("Failed to find transaction by id {}: {}", transaction_id, e);
+            ApiError::from(e)
+        })?;
+
+    // Apply updates one at a time
+    if let Some(account_id) = updates.account_id {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::account_id.eq(account_id))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction account_id {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(category_id) = updates.category_id {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::category_id.eq(category_id))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction category_id {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(title) = updates.title {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::title.eq(title))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction title {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(amount) = updates.amount {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::amount.eq(amount))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction amount {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(date) = updates.date {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::date.eq(date))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction date {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(notes) = updates.notes {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::notes.eq(notes))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction notes {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+
+    if let Some(merchant) = updates.merchant {
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::merchant.eq(merchant))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction merchant {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+    if let Some(reimbursable) = updates.reimbursable {
+        let target = diesel::update(transactions::table.find(transaction_id));
+        let result = if reimbursable {
+            target
+                .set(transactions::reimbursable.eq(true))
+                .execute(conn)
+        } else {
+            // A transaction that is no longer reimbursable has not been reimbursed either
+            target
+                .set((
+                    transactions::reimbursable.eq(false),
+                    transactions::reimbursed_at.eq(None::<DateTime<Utc>>),
+                ))
+                .execute(conn)
+        };
+        result.map_err(|e| {
+            tracing::error!(
+                "Failed to update transaction reimbursable {}: {}",
+                transaction_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+    }
+    if let (Some(latitude), Some(longitude)) = (updates.latitude, updates.longitude) {
+        // Set together to satisfy the check constraint on the location columns
+        diesel::update(transactions::table.find(transaction_id))
+            .set((
+                transactions::latitude.eq(latitude),
+                transactions::longitude.eq(longitude),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction location {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+
+    if let (Some(original_currency), Some(original_amount), Some(exchange_rate)) = (
+        updates.original_currency,
+        updates.original_amount,
+        updates.exchange_rate,
+    ) {
+        // Set together to satisfy the check constraint on the original currency columns
+        diesel::update(transactions::table.find(transaction_id))
+            .set((
+                transactions::original_currency.eq(original_currency),
+                transactions::original_amount.eq(original_amount),
+                transactions::exchange_rate.eq(exchange_rate),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update transaction original currency {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+    }
+
+    if let Some(names) = updates.tags {
+        tag::set_transaction_tags(conn, before.user_id, transaction_id, &names)?;
+    }
+    let splits = updates
+        .splits
+        .map(|splits| reconcile_splits(conn, transaction_id, splits))
+        .transpose()?;
+
+    // Return the updated transaction
+    let transaction: Transaction = transactions::table
+        .find(transaction_id)
+        .first(conn)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch updated transaction {}: {}",
+                transaction_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+    record_audit(conn, Some(&before), Some(&transaction))?;
+    Ok(UpdatedTransaction {
+        transaction,
+        splits,
+    })
+}
+
+/// Delete transaction by setting its deletion time
+///
+/// The transaction and its splits are kept so it can be restored; deleting a
+/// deleted transaction changes nothing.
+pub async fn delete_transaction(pool: &DbPool, transaction_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let deleted: Vec<Transaction> = diesel::update(
+                transactions::table
+                    .find(transaction_id)
+                    .filter(transactions::deleted_at.is_null()),
+            )
+            .set((
+                transactions::deleted_at.eq(diesel::dsl::now),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_results(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete transaction {}: {}", transaction_id, e);
+                ApiError::from(e)
+            })?;
+            record_deleted(conn, deleted)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Restore a deleted transaction
+///
+/// Returns `None` when the transaction is not deleted.
+pub async fn restore_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+) -> Result<Option<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let restored: Option<Transaction> = diesel::update(
+                transactions::table
+                    .find(transaction_id)
+                    .filter(transactions::deleted_at.is_not_null()),
+            )
+            .set((
+                transactions::deleted_at.eq(None::<DateTime<Utc>>),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!("Failed to restore transaction {}: {}", transaction_id, e);
+                ApiError::from(e)
+            })?;
+            if let Some(transaction) = &restored {
+                record_audit(conn, None, Some(transaction))?;
+            }
+            Ok(restored)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// List the IDs of the legs of a transfer that are not deleted
+pub async fn list_transfer_leg_ids(
+    pool: &DbPool,
+    transfer_id: Uuid,
+) -> Result<Vec<Uuid>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        transactions::table
+            .filter(transactions::transfer_id.eq(transfer_id))
+            .filter(transactions::deleted_at.is_null())
+            .select(transactions::id)
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to list legs of transfer {}: {}", transfer_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Delete all legs of a transfer at once
+///
+/// Returns the IDs of the deleted transactions.
+pub async fn delete_transfer(pool: &DbPool, transfer_id: Uuid) -> Result<Vec<Uuid>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let deleted: Vec<Transaction> = diesel::update(
+                transactions::table
+                    .filter(transactions::transfer_id.eq(transfer_id))
+                    .filter(transactions::deleted_at.is_null()),
+            )
+            .set((
+                transactions::deleted_at.eq(diesel::dsl::now),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_results(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete transfer {}: {}", transfer_id, e);
+                ApiError::from(e)
+            })?;
+            let ids = deleted.iter().map(|t| t.id).collect();
+            record_deleted(conn, deleted)?;
+            Ok(ids)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Restore all deleted legs of a transfer at once
+///
+/// Returns the restored transactions.
+pub async fn restore_transfer(
+    pool: &DbPool,
+    transfer_id: Uuid,
+) -> Result<Vec<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let restored: Vec<Transaction> = diesel::update(
+                transactions::table
+                    .filter(transactions::transfer_id.eq(transfer_id))
+                    .filter(transactions::deleted_at.is_not_null()),
+            )
+            .set((
+                transactions::deleted_at.eq(None::<DateTime<Utc>>),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_results(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to restore transfer {}: {}", transfer_id, e);
+                ApiError::from(e)
+            })?;
+            for transaction in &restored {
+                record_audit(conn, None, Some(transaction))?;
+            }
+            Ok(restored)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Permanently remove up to `limit` transactions deleted before `cutoff`, with
+/// their splits, sync records and tags, in one DB transaction
+///
+/// Only transactions with `deleted_at` set before `cutoff` are selected and
+/// locked, and the final delete checks it again, so live transactions are never
+/// touched. Rows locked by a concurrent restore are skipped and left to a later
+/// batch. Returns the rows removed per table; no transactions means nothing is
+/// left to purge.
+pub async fn purge_deleted(
+    pool: &DbPool,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<PurgeCounts, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let ids: Vec<Uuid> = transactions::table
+                .filter(transactions::deleted_at.lt(cutoff))
+                .order(transactions::deleted_at.asc())
+                .limit(limit)
+                .select(transactions::id)
+                .for_update()
+                .skip_locked()
+                .load(conn)?;
+            if ids.is_empty() {
+                return Ok(PurgeCounts::default());
+            }
+
+            let split_ids = transaction_splits::table
+                .filter(transaction_splits::transaction_id.eq_any(&ids))
+                .select(transaction_splits::id);
+            let split_sync_records = diesel::delete(
+                split_sync_records::table
+                    .filter(split_sync_records::transaction_split_id.eq_any(split_ids)),
+            )
+            .execute(conn)?;
+            let transaction_splits = diesel::delete(
+                transaction_splits::table.filter(transaction_splits::transaction_id.eq_any(&ids)),
+            )
+            .execute(conn)?;
+            let transaction_tags = diesel::delete(
+                transaction_tags::table.filter(transaction_tags::transaction_id.eq_any(&ids)),
+            )
+            .execute(conn)?;
+            let transactions = diesel::delete(
+                transactions::table
+                    .filter(transactions::id.eq_any(&ids))
+                    .filter(transactions::deleted_at.lt(cutoff)),
+            )
+            .execute(conn)?;
+
+            // The rows are locked, so this only fails if the selection above is wrong
+            if transactions != ids.len() {
+                tracing::error!(
+                    "Purge selected {} transactions but {} were still deleted; rolling back",
+                    ids.len(),
+                    transactions
+                );
+                return Err(ApiError::Internal);
+            }
+
+            Ok(PurgeCounts {
+                transactions,
+                transaction_splits,
+                split_sync_records,
+                transaction_tags,
+            })
+        })
+        .map_err(|e: ApiError| {
+            tracing::error!("Failed to purge deleted transactions: {}", e);
+            e
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create, update and delete transactions in one DB transaction, all or none,
+/// then check the resulting balance of every affected account
+///
+/// Every affected account is locked in a fixed order before anything is
+/// written, then the updated and deleted transactions; a transaction deleted in
+/// the meantime aborts everything as not found. Once all changes are written
+/// each account's balance is recalculated once and `check` is called with the
+/// account, its balance before and its balance after; its error aborts
+/// everything. Returns the created and the updated transactions, in request
+/// order.
+pub async fn apply_bulk<F>(
+    pool: &DbPool,
+    user_id: Uuid,
+    creates: Vec<NewTransaction>,
+    updates: Vec<(Uuid, UpdateTransaction)>,
+    deletes: Vec<Uuid>,
+    mut check: F,
+) -> Result<(Vec<Transaction>, Vec<Transaction>), ApiError>
+where
+    F: FnMut(Uuid, &BigDecimal, &BigDecimal) -> Result<(), ApiError> + Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let mut existing_ids: Vec<Uuid> = updates
+                .iter()
+                .map(|(id, _)| *id)
+                .chain(deletes.iter().copied())
+                .collect();
+            existing_ids.sort();
+            existing_ids.dedup();
+
+            let existing_account_ids: Vec<Uuid> = transactions::table
+                .filter(transactions::id.eq_any(&existing_ids))
+                .select(transactions::account_id)
+                .load(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to load transactions for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+
+            // Lock accounts in a fixed order so concurrent writes cannot deadlock
+            let mut account_ids: Vec<Uuid> = creates
+                .iter()
+                .map(|t| t.account_id)
+                .chain(updates.iter().filter_map(|(_, u)| u.account_id))
+                .chain(existing_account_ids)
+                .collect();
+            account_ids.sort();
+            account_ids.dedup();
+
+            let mut balances = Vec::with_capacity(account_ids.len());
+            for account_id in &account_ids {
+                let balance = account::lock_and_calculate_balance(conn, *account_id)?;
+                balances.push((*account_id, balance));
+            }
+
+            let locked: Vec<Transaction> = transactions::table
+                .filter(transactions::id.eq_any(&existing_ids))
+                .filter(transactions::deleted_at.is_null())
+                .order(transactions::id)
+                .for_update()
+                .load(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to lock transactions for user {}: {}", user_id, e);
+                    ApiError::from(e)
+                })?;
+            if locked.len() != existing_ids.len() {
+                return Err(ApiError::NotFound("Transaction not found".to_string()));
+            }
+            // Moved to another account since the accounts were locked
+            if locked
+                .iter()
+                .any(|t| account_ids.binary_search(&t.account_id).is_err())
+            {
+                return Err(ApiError::Conflict(
+                    "Transaction was modified during the request".to_string(),
+                ));
+            }
+
+            let created = insert_transactions(conn, user_id, &creates)?;
+
+            let mut updated = Vec::with_capacity(updates.len());
+            for (transaction_id, update) in updates {
+                updated.push(apply_updates(conn, transaction_id, update)?.transaction);
+            }
+
+            let deleted: Vec<Transaction> = diesel::update(
+                transactions::table
+                    .filter(transactions::id.eq_any(&deletes))
+                    .filter(transactions::deleted_at.is_null()),
+            )
+            .set((
+                transactions::deleted_at.eq(diesel::dsl::now),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_results(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to delete transactions for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })?;
+            record_deleted(conn, deleted)?;
+
+            for (account_id, before) in balances {
+                let after = account::lock_and_calculate_balance(conn, account_id)?;
+                check(account_id, &before, &after)?;
+            }
+
+            Ok((created, updated))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Create a transaction split
+pub async fn create_split(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    split: NewTransactionSplit,
+) -> Result<TransactionSplit, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(transaction_splits::table)
+            .values(&split)
             .get_result(&mut conn)
             .map_err(|e| {
-                tracing::error!("Failed to create transaction for user {}: {}", user_id, e);
+                tracing::error!(
+                    "Failed to create split for transaction {}: {}",
+                    transaction_id,
+                    e
+                );
                 ApiError::from(e)
             })
     })
@@ -40,19 +1711,19 @@ pub async fn create_transaction(
     })?
 }
 
-/// Find transaction by ID
-pub async fn find_by_id(pool: &DbPool, transaction_id: Uuid) -> Result<Transaction, ApiError> {
+/// Find a split by ID
+pub async fn find_split_by_id(pool: &DbPool, split_id: Uuid) -> Result<TransactionSplit, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        transactions::table
-            .find(transaction_id)
+        transaction_splits::table
+            .find(split_id)
             .first(&mut conn)
             .map_err(|e| {
-                tracing::error!("Failed to find transaction by id {}: {}", transaction_id, e);
+                tracing::error!("Failed to find split {}: {}", split_id, e);
                 ApiError::from(e)
             })
     })
@@ -63,79 +1734,105 @@ pub async fn find_by_id(pool: &DbPool, transaction_id: Uuid) -> Result<Transacti
     })?
 }
 
-/// List transactions for a user with optional filters
-pub async fn list_transactions(
+/// Record that a reimbursable transaction was paid back now
+///
+/// Returns `None` when the transaction is not reimbursable, was already
+/// reimbursed or is deleted.
+pub async fn mark_reimbursed(
     pool: &DbPool,
-    user_id: Uuid,
-    filters: TransactionFilter,
-) -> Result<Vec<Transaction>, ApiError> {
+    transaction_id: Uuid,
+) -> Result<Option<Transaction>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        let mut query = transactions::table
-            .filter(transactions::user_id.eq(user_id))
-            .into_boxed();
-
-        // Apply filters
-        if let Some(account_id) = filters.account_id {
-            query = query.filter(transactions::account_id.eq(account_id));
-        }
-
-        if let Some(category_id) = filters.category_id {
-            query = query.filter(transactions::category_id.eq(category_id));
-        }
-
-        if let Some(start_date) = filters.start_date {
-            query = query.filter(transactions::date.ge(start_date));
-        }
-
-        if let Some(end_date) = filters.end_date {
-            query = query.filter(transactions::date.le(end_date));
-        }
-
-        if let Some(min_amount) = filters.min_amount {
-            let min_bd = BigDecimal::from_str(&min_amount.to_string()).map_err(|e| {
-                tracing::error!("Failed to convert min_amount to BigDecimal: {}", e);
-                ApiError::Validation("Invalid min_amount".to_string())
-            })?;
-            query = query.filter(transactions::amount.ge(min_bd));
-        }
-
-        if let Some(max_amount) = filters.max_amount {
-            let max_bd = BigDecimal::from_str(&max_amount.to_string()).map_err(|e| {
-                tracing::error!("Failed to convert max_amount to BigDecimal: {}", e);
-                ApiError::Validation("Invalid max_amount".to_string())
+        conn.transaction(|conn| {
+            let reimbursed: Option<Transaction> = diesel::update(
+                transactions::table
+                    .find(transaction_id)
+                    .filter(transactions::reimbursable.eq(true))
+                    .filter(transactions::reimbursed_at.is_null())
+                    .filter(transactions::deleted_at.is_null()),
+            )
+            .set((
+                transactions::reimbursed_at.eq(diesel::dsl::now),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to mark transaction {} reimbursed: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
             })?;
-            query = query.filter(transactions::amount.le(max_bd));
-        }
-
-        if let Some(search) = filters.search {
-            let search_pattern = format!("%{}%", search);
-            query = query.filter(
-                transactions::title
-                    .ilike(search_pattern.clone())
-                    .or(transactions::notes.ilike(search_pattern)),
-            );
-        }
-
-        // Apply ordering
-        query = query.order(transactions::date.desc());
+            if let Some(transaction) = &reimbursed {
+                let before = Transaction {
+                    reimbursed_at: None,
+                    ..transaction.clone()
+                };
+                record_audit(conn, Some(&before), Some(transaction))?;
+            }
+            Ok(reimbursed)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
 
-        // Apply pagination
-        let limit = filters.limit.unwrap_or(50).min(100); // TODO: Make default limit (50) and max (100) configurable
-        let offset = filters.offset.unwrap_or(0);
+/// Move a transaction from status `from` to status `to`
+///
+/// Returns `None` when the transaction is not in status `from`, so concurrent
+/// transitions of the same transaction cannot both succeed.
+pub async fn transition_status(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    from: TransactionStatus,
+    to: TransactionStatus,
+) -> Result<Option<Transaction>, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
 
-        query
-            .limit(limit)
-            .offset(offset)
-            .load(&mut conn)
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let updated: Option<Transaction> = diesel::update(
+                transactions::table
+                    .find(transaction_id)
+                    .filter(transactions::status.eq(from))
+                    .filter(transactions::deleted_at.is_null()),
+            )
+            .set((
+                transactions::status.eq(to),
+                transactions::version.eq(transactions::version + 1),
+            ))
+            .get_result(conn)
+            .optional()
             .map_err(|e| {
-                tracing::error!("Failed to list transactions for user {}: {}", user_id, e);
+                tracing::error!(
+                    "Failed to update status of transaction {}: {}",
+                    transaction_id,
+                    e
+                );
                 ApiError::from(e)
-            })
+            })?;
+            if let Some(transaction) = &updated {
+                let before = Transaction {
+                    status: from,
+                    ..transaction.clone()
+                };
+                record_audit(conn, Some(&before), Some(transaction))?;
+            }
+            Ok(updated)
+        })
     })
     .await
     .map_err(|e| {
@@ -144,110 +1841,147 @@ pub async fn list_transactions(
     })?
 }
 
-/// Update transaction
-pub async fn update_transaction(
+/// Post a pending transaction together with the transfers posting it triggers
+///
+/// `account_ids` are locked, in a fixed order, before the transaction row.
+/// `transfers` plans the transfers from the transaction as it is stored before
+/// posting, and `check` is called for each of them in order with the balance of
+/// its account, which must be one of `account_ids`; its error aborts the
+/// posting. Returns `None` when the transaction is not pending.
+pub async fn post_with_transfers<F, G>(
     pool: &DbPool,
     transaction_id: Uuid,
-    updates: UpdateTransaction,
-) -> Result<Transaction, ApiError> {
+    mut account_ids: Vec<Uuid>,
+    transfers: G,
+    mut check: F,
+) -> Result<Option<(Transaction, Vec<Transaction>)>, ApiError>
+where
+    F: FnMut(&NewTransaction, &BigDecimal) -> Result<(), ApiError> + Send + 'static,
+    G: FnOnce(&Transaction) -> Result<Vec<NewTransaction>, ApiError> + Send + 'static,
+{
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        // Apply updates one at a time
-        if let Some(account_id) = updates.account_id {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::account_id.eq(account_id))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!(
-                        "Failed to update transaction account_id {}: {}",
-                        transaction_id,
-                        e
-                    );
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(category_id) = updates.category_id {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::category_id.eq(category_id))
-                .execute(&mut conn)
-                .map_err(|e| {
-                    tracing::error!(
-                        "Failed to update transaction category_id {}: {}",
-                        transaction_id,
-                        e
-                    );
-                    ApiError::from(e)
-                })?;
-        }
-        if let Some(title) = updates.title {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::title.eq(title))
-                .execute(&mut conn)
+        conn.transaction(|conn| {
+            account_ids.sort();
+            account_ids.dedup();
+            let mut balances = HashMap::new();
+            for account_id in account_ids {
+                let balance = account::lock_and_calculate_balance(conn, account_id)?;
+                balances.insert(account_id, balance);
+            }
+
+            let current: Option<Transaction> = transactions::table
+                .find(transaction_id)
+                .filter(transactions::status.eq(TransactionStatus::Pending))
+                .filter(transactions::deleted_at.is_null())
+                .for_update()
+                .first(conn)
+                .optional()
                 .map_err(|e| {
-                    tracing::error!(
-                        "Failed to update transaction title {}: {}",
-                        transaction_id,
-                        e
-                    );
+                    tracing::error!("Failed to lock transaction {}: {}", transaction_id, e);
                     ApiError::from(e)
                 })?;
-        }
-        if let Some(amount) = updates.amount {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::amount.eq(amount))
-                .execute(&mut conn)
+            let Some(current) = current else {
+                return Ok(None);
+            };
+
+            let new_transactions = transfers(&current)?;
+            for new_transaction in &new_transactions {
+                let balance = balances
+                    .get_mut(&new_transaction.account_id)
+                    .ok_or(ApiError::Internal)?;
+                check(new_transaction, balance)?;
+                *balance += &new_transaction.amount;
+            }
+
+            let posted: Transaction = diesel::update(transactions::table.find(transaction_id))
+                .set((
+                    transactions::status.eq(TransactionStatus::Posted),
+                    transactions::version.eq(transactions::version + 1),
+                ))
+                .get_result(conn)
                 .map_err(|e| {
-                    tracing::error!(
-                        "Failed to update transaction amount {}: {}",
-                        transaction_id,
-                        e
-                    );
+                    tracing::error!("Failed to post transaction {}: {}", transaction_id, e);
                     ApiError::from(e)
                 })?;
-        }
-        if let Some(date) = updates.date {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::date.eq(date))
-                .execute(&mut conn)
+            record_audit(conn, Some(&current), Some(&posted))?;
+
+            let created = insert_transactions(conn, posted.user_id, &new_transactions)?;
+
+            Ok(Some((posted, created)))
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Move a transaction from status `from` to status `to` after checking the
+/// resulting balance of `account_id`
+///
+/// Like [`update_transaction_checked`], the account row is locked for the
+/// duration of the change, and `check` receives the account's current balance
+/// and the transaction as it is stored before the change. Returns `None` when
+/// the transaction is not in status `from`.
+pub async fn transition_status_checked<F, T>(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    from: TransactionStatus,
+    to: TransactionStatus,
+    account_id: Uuid,
+    check: F,
+) -> Result<Option<(Transaction, T)>, ApiError>
+where
+    F: FnOnce(&BigDecimal, &Transaction) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let balance = account::lock_and_calculate_balance(conn, account_id)?;
+            let current: Option<Transaction> = transactions::table
+                .find(transaction_id)
+                .filter(transactions::status.eq(from))
+                .filter(transactions::deleted_at.is_null())
+                .for_update()
+                .first(conn)
+                .optional()
                 .map_err(|e| {
-                    tracing::error!(
-                        "Failed to update transaction date {}: {}",
-                        transaction_id,
-                        e
-                    );
+                    tracing::error!("Failed to lock transaction {}: {}", transaction_id, e);
                     ApiError::from(e)
                 })?;
-        }
-        if let Some(notes) = updates.notes {
-            diesel::update(transactions::table.find(transaction_id))
-                .set(transactions::notes.eq(notes))
-                .execute(&mut conn)
+            let Some(current) = current else {
+                return Ok(None);
+            };
+            let checked = check(&balance, &current)?;
+
+            let updated: Transaction = diesel::update(transactions::table.find(transaction_id))
+                .set((
+                    transactions::status.eq(to),
+                    transactions::version.eq(transactions::version + 1),
+                ))
+                .get_result(conn)
                 .map_err(|e| {
                     tracing::error!(
-                        "Failed to update transaction notes {}: {}",
+                        "Failed to update status of transaction {}: {}",
                         transaction_id,
                         e
                     );
                     ApiError::from(e)
                 })?;
-        }
-
-        // Return the updated transaction
-        transactions::table
-            .find(transaction_id)
-            .first(&mut conn)
-            .map_err(|e| {
-                tracing::error!(
-                    "Failed to fetch updated transaction {}: {}",
-                    transaction_id,
-                    e
-                );
-                ApiError::from(e)
-            })
+            record_audit(conn, Some(&current), Some(&updated))?;
+            Ok(Some((updated, checked)))
+        })
     })
     .await
     .map_err(|e| {
@@ -256,21 +1990,31 @@ pub async fn update_transaction(
     })?
 }
 
-/// Delete transaction
-pub async fn delete_transaction(pool: &DbPool, transaction_id: Uuid) -> Result<(), ApiError> {
+/// Mark splits as settled locally
+///
+/// Splits that are already settled keep their original `settled_at`.
+pub async fn settle_splits(
+    pool: &DbPool,
+    split_ids: Vec<Uuid>,
+    settled_at: DateTime<Utc>,
+) -> Result<Vec<TransactionSplit>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(transactions::table.find(transaction_id))
-            .execute(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to delete transaction {}: {}", transaction_id, e);
-                ApiError::from(e)
-            })
-            .map(|_| ())
+        diesel::update(
+            transaction_splits::table
+                .filter(transaction_splits::id.eq_any(&split_ids))
+                .filter(transaction_splits::settled_at.is_null()),
+        )
+        .set(transaction_splits::settled_at.eq(settled_at))
+        .get_results(&mut conn)
+        .map_err(|e| {
+            tracing::error!("Failed to settle splits {:?}: {}", split_ids, e);
+            ApiError::from(e)
+        })
     })
     .await
     .map_err(|e| {
@@ -279,24 +2023,113 @@ pub async fn delete_transaction(pool: &DbPool, transaction_id: Uuid) -> Result<(
     })?
 }
 
-/// Create a transaction split
-pub async fn create_split(
+/// Replace the splits of a transaction with `splits`, matching existing splits by person
+///
+/// Matching splits get their amount updated, unmatched people are inserted and
+/// existing splits for people no longer listed are deleted, as part of the DB
+/// transaction of `conn`. Settled splits cannot be changed or removed.
+///
+/// Returns the resulting splits and whether the transaction is linked to an
+/// external expense that must be re-synced. When it is, the sync records of the
+/// remaining splits are reset to `pending`.
+fn reconcile_splits(
+    conn: &mut PgConnection,
+    transaction_id: Uuid,
+    splits: Vec<NewTransactionSplit>,
+) -> Result<(Vec<TransactionSplit>, bool), ApiError> {
+    let mut remaining: Vec<TransactionSplit> = transaction_splits::table
+        .filter(transaction_splits::transaction_id.eq(transaction_id))
+        .order(transaction_splits::created_at.asc())
+        .for_update()
+        .load(conn)?;
+
+    let existing_ids: Vec<Uuid> = remaining.iter().map(|split| split.id).collect();
+    let linked_records: i64 = split_sync_records::table
+        .filter(split_sync_records::transaction_split_id.eq_any(&existing_ids))
+        .filter(split_sync_records::external_expense_id.is_not_null())
+        .count()
+        .get_result(conn)?;
+
+    let mut changed = false;
+    for new_split in &splits {
+        let Some(pos) = remaining
+            .iter()
+            .position(|split| split.person_id == new_split.person_id)
+        else {
+            diesel::insert_into(transaction_splits::table)
+                .values(new_split)
+                .execute(conn)?;
+            changed = true;
+            continue;
+        };
+
+        let current = remaining.remove(pos);
+        if current.amount == new_split.amount {
+            continue;
+        }
+        if current.settled_at.is_some() {
+            return Err(ApiError::Conflict(
+                "Settled splits cannot be changed".to_string(),
+            ));
+        }
+        diesel::update(transaction_splits::table.find(current.id))
+            .set(transaction_splits::amount.eq(&new_split.amount))
+            .execute(conn)?;
+        changed = true;
+    }
+
+    // Whatever was not matched is no longer part of the transaction
+    if remaining.iter().any(|split| split.settled_at.is_some()) {
+        return Err(ApiError::Conflict(
+            "Settled splits cannot be removed".to_string(),
+        ));
+    }
+    if !remaining.is_empty() {
+        let removed_ids: Vec<Uuid> = remaining.iter().map(|split| split.id).collect();
+        diesel::delete(
+            transaction_splits::table.filter(transaction_splits::id.eq_any(removed_ids)),
+        )
+        .execute(conn)?;
+        changed = true;
+    }
+
+    let resync_required = changed && linked_records > 0;
+    if resync_required {
+        diesel::update(
+            split_sync_records::table
+                .filter(split_sync_records::transaction_split_id.eq_any(&existing_ids))
+                .filter(split_sync_records::external_expense_id.is_not_null()),
+        )
+        .set(split_sync_records::sync_status.eq(SyncStatus::Pending.as_str()))
+        .execute(conn)?;
+    }
+
+    let splits = transaction_splits::table
+        .filter(transaction_splits::transaction_id.eq(transaction_id))
+        .order(transaction_splits::created_at.asc())
+        .load(conn)?;
+
+    Ok((splits, resync_required))
+}
+
+/// Get all splits for a transaction
+pub async fn list_splits_for_transaction(
     pool: &DbPool,
     transaction_id: Uuid,
-    split: NewTransactionSplit,
-) -> Result<TransactionSplit, ApiError> {
+) -> Result<Vec<TransactionSplit>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(transaction_splits::table)
-            .values(&split)
-            .get_result(&mut conn)
+        transaction_splits::table
+            .filter(transaction_splits::transaction_id.eq(transaction_id))
+            .order(transaction_splits::created_at.asc())
+            .load(&mut conn)
             .map_err(|e| {
                 tracing::error!(
-                    "Failed to create split for transaction {}: {}",
+                    "Failed to get splits for transaction {}: {}",
                     transaction_id,
                     e
                 );
@@ -310,11 +2143,15 @@ pub async fn create_split(
     })?
 }
 
-/// Get all splits for a transaction
-pub async fn list_splits_for_transaction(
+/// List the splits of several transactions, oldest first
+pub async fn list_splits_for_transactions(
     pool: &DbPool,
-    transaction_id: Uuid,
+    transaction_ids: Vec<Uuid>,
 ) -> Result<Vec<TransactionSplit>, ApiError> {
+    if transaction_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
@@ -322,13 +2159,13 @@ pub async fn list_splits_for_transaction(
 
     tokio::task::spawn_blocking(move || {
         transaction_splits::table
-            .filter(transaction_splits::transaction_id.eq(transaction_id))
+            .filter(transaction_splits::transaction_id.eq_any(&transaction_ids))
             .order(transaction_splits::created_at.asc())
             .load(&mut conn)
             .map_err(|e| {
                 tracing::error!(
-                    "Failed to get splits for transaction {}: {}",
-                    transaction_id,
+                    "Failed to get splits for {} transactions: {}",
+                    transaction_ids.len(),
                     e
                 );
                 ApiError::from(e)
@@ -341,30 +2178,43 @@ pub async fn list_splits_for_transaction(
     })?
 }
 
-/// Delete all splits for a transaction
-pub async fn delete_splits_for_transaction(
+/// List a page of a user's transactions a person has a split in, newest first
+///
+/// Each transaction is paired with the person's split of it, loaded with a
+/// single join.
+pub async fn list_by_person(
     pool: &DbPool,
-    transaction_id: Uuid,
-) -> Result<(), ApiError> {
+    user_id: Uuid,
+    person_id: Uuid,
+    pagination: Pagination,
+) -> Result<Vec<(Transaction, TransactionSplit)>, ApiError> {
     let mut conn = pool.get().map_err(|e| {
         tracing::error!("Failed to get DB connection: {}", e);
         ApiError::Internal
     })?;
 
     tokio::task::spawn_blocking(move || {
-        diesel::delete(
-            transaction_splits::table.filter(transaction_splits::transaction_id.eq(transaction_id)),
-        )
-        .execute(&mut conn)
-        .map_err(|e| {
-            tracing::error!(
-                "Failed to delete splits for transaction {}: {}",
-                transaction_id,
-                e
-            );
-            ApiError::from(e)
-        })
-        .map(|_| ())
+        transactions::table
+            .inner_join(transaction_splits::table)
+            .filter(transactions::user_id.eq(user_id))
+            .filter(transactions::deleted_at.is_null())
+            .filter(transaction_splits::person_id.eq(person_id))
+            .order((
+                transactions::date.desc(),
+                transaction_splits::created_at.asc(),
+            ))
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .select((Transaction::as_select(), TransactionSplit::as_select()))
+            .load(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to list transactions for person {}: {}",
+                    person_id,
+                    e
+                );
+                ApiError::from(e)
+            })
     })
     .await
     .map_err(|e| {
@@ -0,0 +1,148 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::NewRecoveryCode,
+    schema::{two_factor_recovery_codes, users},
+};
+
+/// Store a new TOTP secret and recovery codes for a user, in one DB transaction
+///
+/// Replaces the secret and recovery codes of any earlier setup. Two-factor
+/// authentication stays disabled until a code from the secret is verified.
+pub async fn start_setup(
+    pool: &DbPool,
+    user_id: Uuid,
+    totp_secret_encrypted: String,
+    recovery_codes: Vec<NewRecoveryCode>,
+) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            diesel::update(users::table.find(user_id))
+                .set(users::totp_secret_encrypted.eq(totp_secret_encrypted))
+                .execute(conn)?;
+
+            diesel::delete(
+                two_factor_recovery_codes::table
+                    .filter(two_factor_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+
+            diesel::insert_into(two_factor_recovery_codes::table)
+                .values(&recovery_codes)
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e: diesel::result::Error| {
+            tracing::error!("Failed to store 2FA setup for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Enable two-factor authentication for a user
+pub async fn enable(pool: &DbPool, user_id: Uuid) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(users::table.find(user_id))
+            .set(users::two_factor_enabled.eq(true))
+            .execute(&mut conn)
+            .map(|_| ())
+            .map_err(|e| {
+                tracing::error!("Failed to enable 2FA for user {}: {}", user_id, e);
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Record the time step of a TOTP code accepted for a user
+///
+/// Returns `false` when a code from this or a later step was already accepted,
+/// e.g. the same code replayed or used by a concurrent login.
+pub async fn use_totp_step(pool: &DbPool, user_id: Uuid, step: i64) -> Result<bool, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            users::table.find(user_id).filter(
+                users::totp_last_step
+                    .is_null()
+                    .or(users::totp_last_step.lt(step)),
+            ),
+        )
+        .set(users::totp_last_step.eq(step))
+        .execute(&mut conn)
+        .map(|used| used > 0)
+        .map_err(|e| {
+            tracing::error!("Failed to record TOTP step for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Use up an unused recovery code of a user, found by its hash
+///
+/// Returns `false` when the user has no such unused code, including when it
+/// was just used by a concurrent login.
+pub async fn use_recovery_code(
+    pool: &DbPool,
+    user_id: Uuid,
+    code_hash: String,
+) -> Result<bool, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::update(
+            two_factor_recovery_codes::table
+                .filter(two_factor_recovery_codes::user_id.eq(user_id))
+                .filter(two_factor_recovery_codes::code_hash.eq(code_hash))
+                .filter(two_factor_recovery_codes::used_at.is_null()),
+        )
+        .set(two_factor_recovery_codes::used_at.eq(Utc::now()))
+        .execute(&mut conn)
+        .map(|used| used > 0)
+        .map_err(|e| {
+            tracing::error!("Failed to use recovery code of user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -4,8 +4,14 @@ use uuid::Uuid;
 use crate::{
     DbPool,
     errors::ApiError,
-    models::user::{NewUser, UpdateUser, User},
-    schema::users,
+    models::{
+        AuditEntityType,
+        account::{Account, NewAccount},
+        category::{Category, NewCategory},
+        user::{NewUser, UpdateUser, User},
+    },
+    repositories::audit_log,
+    schema::{accounts, categories, refresh_tokens, users},
 };
 
 /// Create a new user
@@ -31,6 +37,79 @@ pub async fn create_user(pool: &DbPool, new_user: NewUser) -> Result<User, ApiEr
     })?
 }
 
+/// Create a new user together with their starter accounts and categories
+///
+/// The user and the rows returned by `seed` (which receives the new user's ID)
+/// are inserted in one DB transaction, so a failure to seed leaves no user behind.
+pub async fn create_user_with_starter_data<F>(
+    pool: &DbPool,
+    new_user: NewUser,
+    seed: F,
+) -> Result<User, ApiError>
+where
+    F: FnOnce(Uuid) -> (Vec<NewAccount>, Vec<NewCategory>) + Send + 'static,
+{
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            let user: User = diesel::insert_into(users::table)
+                .values(&new_user)
+                .get_result(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create user: {}", e);
+                    ApiError::from(e)
+                })?;
+
+            let (new_accounts, new_categories) = seed(user.id);
+            let accounts: Vec<Account> = diesel::insert_into(accounts::table)
+                .values(&new_accounts)
+                .get_results(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create starter accounts for {}: {}", user.id, e);
+                    ApiError::from(e)
+                })?;
+            for account in &accounts {
+                audit_log::record(
+                    conn,
+                    user.id,
+                    AuditEntityType::Account,
+                    account.id,
+                    None,
+                    Some(account),
+                )?;
+            }
+            let categories: Vec<Category> = diesel::insert_into(categories::table)
+                .values(&new_categories)
+                .get_results(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to create starter categories for {}: {}", user.id, e);
+                    ApiError::from(e)
+                })?;
+            for category in &categories {
+                audit_log::record(
+                    conn,
+                    user.id,
+                    AuditEntityType::Category,
+                    category.id,
+                    None,
+                    Some(category),
+                )?;
+            }
+
+            Ok(user)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Find user by ID
 pub async fn find_by_id(pool: &DbPool, user_id: Uuid) -> Result<User, ApiError> {
     let mut conn = pool.get().map_err(|e| {
@@ -153,6 +232,49 @@ pub async fn update_user(
     })?
 }
 
+/// Set a user's password hash and revoke their refresh tokens, in one DB transaction
+///
+/// Refresh tokens of `keep_family` stay valid so the session changing the
+/// password isn't logged out. Returns the number of tokens revoked.
+pub async fn change_password(
+    pool: &DbPool,
+    user_id: Uuid,
+    password_hash: String,
+    keep_family: Option<Uuid>,
+) -> Result<usize, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            diesel::update(users::table.find(user_id))
+                .set(users::password_hash.eq(password_hash))
+                .execute(conn)?;
+
+            // The nil UUID is never a family, so without one to keep all are revoked
+            diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::user_id.eq(user_id))
+                    .filter(refresh_tokens::revoked.eq(false))
+                    .filter(refresh_tokens::family_id.ne(keep_family.unwrap_or(Uuid::nil()))),
+            )
+            .set(refresh_tokens::revoked.eq(true))
+            .execute(conn)
+        })
+        .map_err(|e: diesel::result::Error| {
+            tracing::error!("Failed to change password for user {}: {}", user_id, e);
+            ApiError::from(e)
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
 /// Delete user
 pub async fn delete_user(pool: &DbPool, user_id: Uuid) -> Result<(), ApiError> {
     let mut conn = pool.get().map_err(|e| {
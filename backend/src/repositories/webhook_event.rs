@@ -0,0 +1,34 @@
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{NewWebhookEvent, WebhookEvent},
+    schema::webhook_events,
+};
+use diesel::prelude::*;
+
+/// Store a webhook delivery that could not be applied, for later inspection
+pub async fn record(pool: &DbPool, new_event: NewWebhookEvent) -> Result<WebhookEvent, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(webhook_events::table)
+            .values(&new_event)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to store {} webhook event: {}",
+                    new_event.provider_type,
+                    e
+                );
+                ApiError::from(e)
+            })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
@@ -13,9 +13,21 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "budget_period"))]
     pub struct BudgetPeriod;
 
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "category_rule_match_type"))]
+    pub struct CategoryRuleMatchType;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "currency_code"))]
     pub struct CurrencyCode;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "recurrence_frequency"))]
+    pub struct RecurrenceFrequency;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "transaction_status"))]
+    pub struct TransactionStatus;
 }
 
 diesel::table! {
@@ -34,6 +46,34 @@ diesel::table! {
         notes -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        allow_overdraft -> Bool,
+        overdraft_limit -> Nullable<Numeric>,
+        credit_limit -> Nullable<Numeric>,
+        version -> Int4,
+        interest_rate -> Nullable<Numeric>,
+        original_principal -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    allocation_rule_destinations (id) {
+        id -> Uuid,
+        rule_id -> Uuid,
+        account_id -> Uuid,
+        percentage -> Numeric,
+    }
+}
+
+diesel::table! {
+    allocation_rules (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        source_account_id -> Uuid,
+        category_id -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -56,6 +96,52 @@ diesel::table! {
         last_used_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        requests_per_minute -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Uuid,
+        sequence -> Int8,
+        user_id -> Uuid,
+        #[max_length = 30]
+        entity_type -> Varchar,
+        entity_id -> Uuid,
+        #[max_length = 10]
+        action -> Varchar,
+        changes -> Jsonb,
+        #[max_length = 64]
+        previous_hash -> Nullable<Varchar>,
+        #[max_length = 64]
+        hash -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    auth_events (id) {
+        id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        #[max_length = 30]
+        event_type -> Varchar,
+        #[max_length = 45]
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    budget_alerts (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        budget_id -> Uuid,
+        budget_range_id -> Uuid,
+        threshold -> Float8,
+        percentage_used -> Float8,
+        triggered_at -> Timestamptz,
+        read_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -84,6 +170,11 @@ diesel::table! {
         filters -> Jsonb,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        alerts_muted -> Bool,
+        alerts_snoozed_until -> Nullable<Timestamptz>,
+        version -> Int4,
+        alert_thresholds -> Array<Float8>,
+        deactivated_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -103,6 +194,138 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CategoryRuleMatchType;
+
+    category_rules (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        category_id -> Uuid,
+        match_type -> CategoryRuleMatchType,
+        #[max_length = 255]
+        pattern -> Varchar,
+        priority -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CurrencyCode;
+
+    custom_exchange_rates (user_id, base_currency, quote_currency, rate_date) {
+        user_id -> Uuid,
+        base_currency -> CurrencyCode,
+        quote_currency -> CurrencyCode,
+        rate_date -> Date,
+        rate -> Numeric,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CurrencyCode;
+
+    exchange_rates (base_currency, quote_currency, rate_date) {
+        base_currency -> CurrencyCode,
+        quote_currency -> CurrencyCode,
+        rate_date -> Date,
+        rate -> Numeric,
+        fetched_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (user_id, scope, key) {
+        user_id -> Uuid,
+        #[max_length = 50]
+        scope -> Varchar,
+        #[max_length = 255]
+        key -> Varchar,
+        created_at -> Timestamptz,
+        #[max_length = 64]
+        request_hash -> Nullable<Varchar>,
+        response_status -> Nullable<Int2>,
+        response_body -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    import_profiles (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        mapping -> Jsonb,
+        #[max_length = 50]
+        date_format -> Nullable<Varchar>,
+        #[max_length = 30]
+        amount_sign -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    notification_preferences (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 30]
+        event_type -> Varchar,
+        #[max_length = 20]
+        channel -> Varchar,
+        enabled -> Bool,
+        webhook_url -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        #[max_length = 64]
+        webhook_secret -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 30]
+        event_type -> Varchar,
+        #[max_length = 255]
+        title -> Varchar,
+        body -> Text,
+        data -> Jsonb,
+        created_at -> Timestamptz,
+        read_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    orphaned_external_expenses (id) {
+        id -> Uuid,
+        split_provider_id -> Uuid,
+        #[max_length = 255]
+        external_expense_id -> Varchar,
+        transaction_id -> Uuid,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     people (id) {
         id -> Uuid,
@@ -131,6 +354,75 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    reconciliations (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        account_id -> Uuid,
+        statement_date -> Date,
+        statement_balance -> Numeric,
+        balance -> Numeric,
+        adjustment_transaction_id -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RecurrenceFrequency;
+
+    recurring_transactions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        account_id -> Uuid,
+        category_id -> Nullable<Uuid>,
+        #[max_length = 255]
+        title -> Varchar,
+        amount -> Numeric,
+        notes -> Nullable<Text>,
+        frequency -> RecurrenceFrequency,
+        interval -> Int4,
+        start_date -> Date,
+        run_count -> Int4,
+        next_run_date -> Date,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    refresh_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        family_id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        revoked -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    split_group_members (id) {
+        id -> Uuid,
+        group_id -> Uuid,
+        person_id -> Uuid,
+        percentage -> Numeric,
+    }
+}
+
+diesel::table! {
+    split_groups (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     split_providers (id) {
         id -> Uuid,
@@ -141,6 +433,8 @@ diesel::table! {
         is_active -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        consecutive_auth_failures -> Int4,
+        disabled_by_user -> Bool,
     }
 }
 
@@ -158,6 +452,18 @@ diesel::table! {
         retry_count -> Int4,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        #[max_length = 255]
+        external_user_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 50]
+        name -> Varchar,
+        created_at -> Timestamptz,
     }
 }
 
@@ -169,10 +475,22 @@ diesel::table! {
         amount -> Numeric,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        settled_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    transaction_tags (transaction_id, tag_id) {
+        transaction_id -> Uuid,
+        tag_id -> Uuid,
     }
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::TransactionStatus;
+    use super::sql_types::CurrencyCode;
+
     transactions (id) {
         id -> Uuid,
         user_id -> Uuid,
@@ -185,6 +503,32 @@ diesel::table! {
         notes -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        #[max_length = 255]
+        external_id -> Nullable<Varchar>,
+        status -> TransactionStatus,
+        original_currency -> Nullable<CurrencyCode>,
+        original_amount -> Nullable<Numeric>,
+        exchange_rate -> Nullable<Numeric>,
+        #[max_length = 255]
+        merchant -> Nullable<Varchar>,
+        latitude -> Nullable<Float8>,
+        longitude -> Nullable<Float8>,
+        version -> Int4,
+        transfer_id -> Nullable<Uuid>,
+        deleted_at -> Nullable<Timestamptz>,
+        reimbursable -> Bool,
+        reimbursed_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    two_factor_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        code_hash -> Varchar,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -201,37 +545,111 @@ diesel::table! {
         name -> Varchar,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        totp_secret_encrypted -> Nullable<Text>,
+        two_factor_enabled -> Bool,
+        totp_last_step -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    webhook_events (id) {
+        id -> Uuid,
+        #[max_length = 50]
+        provider_type -> Varchar,
+        #[max_length = 100]
+        event_type -> Nullable<Varchar>,
+        #[max_length = 255]
+        external_id -> Nullable<Varchar>,
+        #[max_length = 20]
+        status -> Varchar,
+        payload -> Jsonb,
+        created_at -> Timestamptz,
     }
 }
 
 diesel::joinable!(accounts -> users (user_id));
+diesel::joinable!(allocation_rule_destinations -> accounts (account_id));
+diesel::joinable!(allocation_rule_destinations -> allocation_rules (rule_id));
+diesel::joinable!(allocation_rules -> categories (category_id));
+diesel::joinable!(allocation_rules -> users (user_id));
 diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(audit_log -> users (user_id));
+diesel::joinable!(auth_events -> users (user_id));
+diesel::joinable!(budget_alerts -> budget_ranges (budget_range_id));
+diesel::joinable!(budget_alerts -> budgets (budget_id));
+diesel::joinable!(budget_alerts -> users (user_id));
 diesel::joinable!(budget_ranges -> budgets (budget_id));
 diesel::joinable!(budgets -> users (user_id));
 diesel::joinable!(categories -> users (user_id));
+diesel::joinable!(category_rules -> categories (category_id));
+diesel::joinable!(category_rules -> users (user_id));
+diesel::joinable!(custom_exchange_rates -> users (user_id));
+diesel::joinable!(idempotency_keys -> users (user_id));
+diesel::joinable!(import_profiles -> users (user_id));
+diesel::joinable!(notification_preferences -> users (user_id));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(orphaned_external_expenses -> split_providers (split_provider_id));
+diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(people -> users (user_id));
 diesel::joinable!(person_split_configs -> people (person_id));
 diesel::joinable!(person_split_configs -> split_providers (split_provider_id));
+diesel::joinable!(reconciliations -> accounts (account_id));
+diesel::joinable!(reconciliations -> transactions (adjustment_transaction_id));
+diesel::joinable!(reconciliations -> users (user_id));
+diesel::joinable!(recurring_transactions -> accounts (account_id));
+diesel::joinable!(recurring_transactions -> categories (category_id));
+diesel::joinable!(recurring_transactions -> users (user_id));
+diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(split_group_members -> people (person_id));
+diesel::joinable!(split_group_members -> split_groups (group_id));
+diesel::joinable!(split_groups -> users (user_id));
 diesel::joinable!(split_providers -> users (user_id));
 diesel::joinable!(split_sync_records -> split_providers (split_provider_id));
 diesel::joinable!(split_sync_records -> transaction_splits (transaction_split_id));
+diesel::joinable!(tags -> users (user_id));
 diesel::joinable!(transaction_splits -> people (person_id));
 diesel::joinable!(transaction_splits -> transactions (transaction_id));
+diesel::joinable!(transaction_tags -> tags (tag_id));
+diesel::joinable!(transaction_tags -> transactions (transaction_id));
 diesel::joinable!(transactions -> accounts (account_id));
 diesel::joinable!(transactions -> categories (category_id));
 diesel::joinable!(transactions -> users (user_id));
+diesel::joinable!(two_factor_recovery_codes -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     accounts,
+    allocation_rule_destinations,
+    allocation_rules,
     api_keys,
+    audit_log,
+    auth_events,
+    budget_alerts,
     budget_ranges,
     budgets,
     categories,
+    category_rules,
+    custom_exchange_rates,
+    exchange_rates,
+    idempotency_keys,
+    import_profiles,
+    notification_preferences,
+    notifications,
+    orphaned_external_expenses,
+    password_reset_tokens,
     people,
     person_split_configs,
+    reconciliations,
+    recurring_transactions,
+    refresh_tokens,
+    split_group_members,
+    split_groups,
     split_providers,
     split_sync_records,
+    tags,
     transaction_splits,
+    transaction_tags,
     transactions,
+    two_factor_recovery_codes,
     users,
+    webhook_events,
 );
@@ -1,4 +1,5 @@
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, RoundingMode, Signed};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
 use std::str::FromStr;
 use uuid::Uuid;
 use validator::Validate;
@@ -7,12 +8,35 @@ use crate::{
     DbPool,
     errors::ApiError,
     models::{
-        AccountResponse, CreateAccountRequest, NewAccount, NewTransaction, UpdateAccountRequest,
+        Account, AccountResponse, AccountSummaryQuery, AccountSummaryResponse, AmortizationPayment,
+        AmortizationQuery, AmortizationResponse, BalanceHistoryQuery, BalancePoint,
+        CreateAccountRequest, NewAccount, NewTransaction, Pagination, SyncQuery,
+        UpdateAccountRequest,
     },
     repositories,
+    types::{AccountType, Granularity, Money, TransactionStatus},
 };
 
+/// Most points a balance history may have, about ten years of days
+pub const MAX_BALANCE_HISTORY_POINTS: i64 = 3660;
+
+/// Most monthly payments an amortization schedule may have, 100 years
+pub const MAX_AMORTIZATION_PAYMENTS: u32 = 1200;
+
+/// Result of projecting an account balance forward by a new or changed transaction
+#[derive(Debug, Clone)]
+pub struct BalanceProjection {
+    /// Available account balance after the transaction is applied
+    pub projected_balance: BigDecimal,
+    /// Set when the projection breaches the overdraft/credit limit but the account allows it
+    pub warning: Option<String>,
+}
+
 /// Create a new account
+///
+/// An `initial_balance` is recorded as an opening balance transaction, created
+/// together with the account. For loans and mortgages it is the principal
+/// owed, so it is recorded as a negative balance.
 pub async fn create_account(
     pool: &DbPool,
     user_id: Uuid,
@@ -34,6 +58,20 @@ pub async fn create_account(
         None
     };
 
+    let overdraft_limit = convert_limit(request.overdraft_limit, "overdraft limit")?;
+    let credit_limit = convert_limit(request.credit_limit, "credit limit")?;
+    let interest_rate = convert_limit(request.interest_rate, "interest rate")?;
+    let original_principal = convert_limit(request.original_principal, "original principal")?;
+
+    // Loans are owed, like credit card balances
+    let initial_balance = initial_balance.map(|balance| {
+        if request.account_type.is_loan() {
+            -balance
+        } else {
+            balance
+        }
+    });
+
     // Create account with currency defaulting to EUR if not provided
     let new_account = NewAccount {
         user_id,
@@ -41,48 +79,53 @@ pub async fn create_account(
         account_type: request.account_type,
         currency: request.currency.unwrap_or(crate::types::CurrencyCode::Eur),
         notes: request.notes.clone(),
+        allow_overdraft: request.allow_overdraft.unwrap_or(true),
+        overdraft_limit,
+        credit_limit,
+        interest_rate,
+        original_principal,
     };
 
-    let account = repositories::account::create_account(pool, user_id, new_account).await?;
-
-    tracing::info!("Created account {} for user {}", account.id, user_id);
-
-    // If initial balance provided, create an initial transaction
-    if let Some(balance) = initial_balance {
-        if balance != BigDecimal::from(0) {
-            let initial_transaction = NewTransaction {
-                user_id,
-                account_id: account.id,
-                category_id: None,
-                title: "Initial Balance".to_string(), // TODO: Consider making this configurable or translatable
-                amount: balance,
-                date: chrono::Utc::now(),
-                notes: Some("Initial account balance".to_string()), // TODO: Consider making this configurable or translatable
-            };
+    // If initial balance provided, create an initial transaction (its account is filled in on creation)
+    let opening_balance = initial_balance
+        .filter(|balance| *balance != BigDecimal::from(0))
+        .map(|balance| NewTransaction {
+            user_id,
+            account_id: Uuid::nil(),
+            category_id: None,
+            title: "Initial Balance".to_string(), // TODO: Consider making this configurable or translatable
+            amount: balance,
+            date: chrono::Utc::now(),
+            notes: Some("Initial account balance".to_string()), // TODO: Consider making this configurable or translatable
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
+        });
+    let has_opening_balance = opening_balance.is_some();
 
-            repositories::transaction::create_transaction(pool, user_id, initial_transaction)
-                .await?;
+    let account =
+        repositories::account::create_account(pool, user_id, new_account, opening_balance).await?;
 
-            tracing::info!(
-                "Created initial balance transaction for account {}",
-                account.id
-            );
-        }
+    tracing::info!("Created account {} for user {}", account.id, user_id);
+    if has_opening_balance {
+        tracing::info!(
+            "Created initial balance transaction for account {}",
+            account.id
+        );
     }
 
     // Calculate current balance
-    let balance = calculate_account_balance(pool, account.id).await?;
+    let balances = calculate_account_balances(pool, account.id).await?;
 
-    Ok(AccountResponse {
-        id: account.id,
-        user_id: account.user_id,
-        name: account.name,
-        account_type: account.account_type,
-        currency: account.currency,
-        balance: balance.to_string().parse::<f64>().unwrap_or(0.0),
-        is_active: true, // TODO: Add is_active field to database schema for account archiving
-        notes: account.notes,
-    })
+    Ok(build_account_response(account, &balances))
 }
 
 /// Get an account with its current balance
@@ -106,40 +149,283 @@ pub async fn get_account(
     }
 
     // Calculate current balance
-    let balance = calculate_account_balance(pool, account_id).await?;
+    let balances = calculate_account_balances(pool, account_id).await?;
 
-    Ok(AccountResponse {
-        id: account.id,
-        user_id: account.user_id,
-        name: account.name,
-        account_type: account.account_type,
-        currency: account.currency,
-        balance: balance.to_string().parse::<f64>().unwrap_or(0.0),
-        is_active: true, // TODO: Add is_active field to database schema
-        notes: account.notes,
+    Ok(build_account_response(account, &balances))
+}
+
+/// Summarize an account's posted transactions over a period
+///
+/// Transfers are excluded from income and expense but included in the net change.
+pub async fn get_account_summary(
+    pool: &DbPool,
+    account_id: Uuid,
+    user_id: Uuid,
+    query: AccountSummaryQuery,
+) -> Result<AccountSummaryResponse, ApiError> {
+    if let (Some(start), Some(end)) = (query.start, query.end)
+        && start > end
+    {
+        return Err(ApiError::BadRequest(
+            "start must not be after end".to_string(),
+        ));
+    }
+
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+
+    // Verify ownership
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to summarize account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let (opening_balance, income, expenses, net_change) =
+        repositories::account::summarize_period(pool, account_id, query.start, query.end).await?;
+    let closing_balance = &opening_balance + &net_change;
+    let currency = account.currency;
+
+    Ok(AccountSummaryResponse {
+        account_id,
+        currency,
+        start: query.start,
+        end: query.end,
+        opening_balance: Money::new(opening_balance, currency),
+        total_income: Money::new(income, currency),
+        total_expense: Money::new(-expenses, currency),
+        net_change: Money::new(net_change, currency),
+        closing_balance: Money::new(closing_balance, currency),
+    })
+}
+
+/// Posted balance of an account at the end of each interval of a period
+///
+/// The period runs from `query.start`, or the account's first posted
+/// transaction, to `query.end`, or today. An account without transactions has
+/// one zero point when no start is given.
+pub async fn get_balance_history(
+    pool: &DbPool,
+    account_id: Uuid,
+    user_id: Uuid,
+    query: BalanceHistoryQuery,
+) -> Result<Vec<BalancePoint>, ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+
+    // Verify ownership
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to read the balance history of account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let end = query.end.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let start = match query.start {
+        Some(start) => start,
+        None => repositories::account::first_posted_date(pool, account_id)
+            .await?
+            .unwrap_or(end)
+            .min(end),
+    };
+    if start > end {
+        return Err(ApiError::BadRequest(
+            "start must not be after end".to_string(),
+        ));
+    }
+    if interval_count(start, end, query.granularity) > MAX_BALANCE_HISTORY_POINTS {
+        return Err(ApiError::BadRequest(format!(
+            "Balance history is limited to {} points; use a shorter period or a larger granularity",
+            MAX_BALANCE_HISTORY_POINTS
+        )));
+    }
+
+    let points =
+        repositories::account::balance_history(pool, account_id, start, end, query.granularity)
+            .await?;
+
+    Ok(points
+        .into_iter()
+        .map(|(date, balance)| BalancePoint {
+            date,
+            balance: Money::new(balance, account.currency),
+        })
+        .collect())
+}
+
+/// Project the monthly payments paying off a loan or mortgage
+///
+/// Starts from the principal owed now (the negated posted balance) and the
+/// account's annual interest rate. Each month one twelfth of the rate is
+/// charged on the remaining principal, rounded to the currency's minor units,
+/// and `query.payment` pays the interest first and then principal. The first
+/// payment falls a month from today.
+pub async fn get_amortization(
+    pool: &DbPool,
+    account_id: Uuid,
+    user_id: Uuid,
+    query: AmortizationQuery,
+) -> Result<AmortizationResponse, ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+
+    // Verify ownership
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to read the amortization of account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    if !account.account_type.is_loan() {
+        return Err(ApiError::Validation(
+            "Amortization is only available for loan and mortgage accounts".to_string(),
+        ));
+    }
+    let interest_rate = account.interest_rate.clone().ok_or_else(|| {
+        ApiError::Validation(
+            "Set the account's interest_rate to project its amortization".to_string(),
+        )
+    })?;
+    if !query.payment.is_positive() {
+        return Err(ApiError::BadRequest(
+            "payment must be greater than 0".to_string(),
+        ));
+    }
+
+    let balance = repositories::account::calculate_balance(pool, account_id).await?;
+    let principal = -balance;
+    let (schedule, total_interest) =
+        amortization_schedule(&account, &principal, &interest_rate, &query.payment)?;
+
+    let currency = account.currency;
+    let zero = BigDecimal::from(0);
+    Ok(AmortizationResponse {
+        account_id,
+        currency,
+        principal: Money::new(principal.max(zero), currency),
+        interest_rate: interest_rate.normalized().to_string(),
+        payment: Money::new(query.payment, currency),
+        total_interest: Money::new(total_interest, currency),
+        payoff_date: schedule.last().map(|payment| payment.date),
+        schedule,
     })
 }
 
+/// Monthly payments of `payment` paying off `principal`, and the interest paid
+fn amortization_schedule(
+    account: &Account,
+    principal: &BigDecimal,
+    interest_rate: &BigDecimal,
+    payment: &BigDecimal,
+) -> Result<(Vec<AmortizationPayment>, BigDecimal), ApiError> {
+    let currency = account.currency;
+    let round =
+        |value: BigDecimal| value.with_scale_round(currency.minor_units(), RoundingMode::HalfEven);
+    let monthly_rate = interest_rate / BigDecimal::from(1200);
+    let today = chrono::Utc::now().date_naive();
+    let zero = BigDecimal::from(0);
+
+    let mut remaining = principal.clone();
+    let mut total_interest = zero.clone();
+    let mut schedule = Vec::new();
+    while remaining > zero {
+        let number = schedule.len() as u32 + 1;
+        if number > MAX_AMORTIZATION_PAYMENTS {
+            return Err(ApiError::BadRequest(format!(
+                "The loan would take more than {} payments to pay off; use a larger payment",
+                MAX_AMORTIZATION_PAYMENTS
+            )));
+        }
+
+        let interest = round(&remaining * &monthly_rate);
+        if *payment <= interest {
+            return Err(ApiError::BadRequest(format!(
+                "payment of {} does not cover the monthly interest of {}",
+                payment, interest
+            )));
+        }
+        let amount = payment.clone().min(&remaining + &interest);
+        let repaid = &amount - &interest;
+        remaining -= &repaid;
+        total_interest += &interest;
+
+        let date = today
+            .checked_add_months(Months::new(number))
+            .ok_or(ApiError::Internal)?;
+        schedule.push(AmortizationPayment {
+            number,
+            date,
+            payment: Money::new(amount, currency),
+            interest: Money::new(interest, currency),
+            principal: Money::new(repaid, currency),
+            remaining: Money::new(remaining.clone(), currency),
+        });
+    }
+
+    Ok((schedule, total_interest))
+}
+
+/// Number of intervals of a granularity that the days from `start` to `end` touch
+fn interval_count(start: NaiveDate, end: NaiveDate, granularity: Granularity) -> i64 {
+    match granularity {
+        Granularity::Day => (end - start).num_days() + 1,
+        Granularity::Week => {
+            let monday = |date: NaiveDate| {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            };
+            (monday(end) - monday(start)).num_days() / 7 + 1
+        }
+        Granularity::Month => {
+            (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64 + 1
+        }
+    }
+}
+
+/// Count a user's accounts, optionally only those modified at or after `since`
+pub async fn count_accounts(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    repositories::account::count_by_user(pool, user_id, since).await
+}
+
 /// List all accounts for a user with their balances
-pub async fn list_accounts(pool: &DbPool, user_id: Uuid) -> Result<Vec<AccountResponse>, ApiError> {
-    // Fetch all user accounts
-    let accounts = repositories::account::list_by_user(pool, user_id).await?;
+///
+/// When `query.updated_since` is set, only accounts modified since then are returned.
+/// With `pagination`, only that page is loaded.
+pub async fn list_accounts(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: SyncQuery,
+    pagination: Option<Pagination>,
+) -> Result<Vec<AccountResponse>, ApiError> {
+    // Fetch user accounts
+    let accounts = match (pagination, query.updated_since) {
+        (Some(pagination), since) => {
+            repositories::account::list_page_by_user(pool, user_id, since, pagination).await?
+        }
+        (None, Some(since)) => {
+            repositories::account::list_updated_since(pool, user_id, since).await?
+        }
+        (None, None) => repositories::account::list_by_user(pool, user_id).await?,
+    };
 
     // Calculate balance for each account
     let mut responses = Vec::new();
     for account in accounts {
-        let balance = calculate_account_balance(pool, account.id).await?;
-
-        responses.push(AccountResponse {
-            id: account.id,
-            user_id: account.user_id,
-            name: account.name,
-            account_type: account.account_type,
-            currency: account.currency,
-            balance: balance.to_string().parse::<f64>().unwrap_or(0.0),
-            is_active: true, // TODO: Add is_active field to database schema
-            notes: account.notes,
-        });
+        let balances = calculate_account_balances(pool, account.id).await?;
+
+        responses.push(build_account_response(account, &balances));
     }
 
     Ok(responses)
@@ -176,26 +462,29 @@ pub async fn update_account(
         account_type: request.account_type,
         currency: request.currency,
         notes: request.notes,
+        allow_overdraft: request.allow_overdraft,
+        overdraft_limit: convert_limit(request.overdraft_limit, "overdraft limit")?,
+        credit_limit: convert_limit(request.credit_limit, "credit limit")?,
+        interest_rate: convert_limit(request.interest_rate, "interest rate")?,
+        original_principal: convert_limit(request.original_principal, "original principal")?,
+        expected_version: request.version,
     };
 
     // Update account
-    let updated = repositories::account::update_account(pool, account_id, updates).await?;
+    let updated = match repositories::account::update_account(pool, account_id, updates).await {
+        Err(ApiError::VersionConflict(None)) => {
+            let current = get_account(pool, account_id, user_id).await?;
+            return Err(ApiError::version_conflict(&current));
+        }
+        result => result?,
+    };
 
     tracing::info!("Updated account {} for user {}", account_id, user_id);
 
     // Calculate current balance
-    let balance = calculate_account_balance(pool, account_id).await?;
-
-    Ok(AccountResponse {
-        id: updated.id,
-        user_id: updated.user_id,
-        name: updated.name,
-        account_type: updated.account_type,
-        currency: updated.currency,
-        balance: balance.to_string().parse::<f64>().unwrap_or(0.0),
-        is_active: true, // TODO: Add is_active field to database schema if account archiving is needed
-        notes: updated.notes,
-    })
+    let balances = calculate_account_balances(pool, account_id).await?;
+
+    Ok(build_account_response(updated, &balances))
 }
 
 /// Delete an account (only if it has no transactions)
@@ -238,10 +527,146 @@ pub async fn delete_account(
     Ok(())
 }
 
-/// Helper function to calculate account balance
-async fn calculate_account_balance(
+/// Cleared and available balance of an account
+struct AccountBalances {
+    /// Sum of posted transactions
+    cleared: BigDecimal,
+    /// Cleared balance plus pending transactions
+    available: BigDecimal,
+}
+
+/// Helper function to calculate account balances
+async fn calculate_account_balances(
     pool: &DbPool,
     account_id: Uuid,
-) -> Result<BigDecimal, ApiError> {
-    repositories::account::calculate_balance(pool, account_id).await
+) -> Result<AccountBalances, ApiError> {
+    Ok(AccountBalances {
+        cleared: repositories::account::calculate_balance(pool, account_id).await?,
+        available: repositories::account::calculate_available_balance(pool, account_id).await?,
+    })
+}
+
+/// Project the account balance after applying `delta` and check it against the
+/// account's overdraft limit (or credit limit for credit cards).
+///
+/// Only outflows (negative deltas) are checked, so deposits into an account that
+/// is already past its limit are always accepted. When the limit is breached the
+/// transaction is rejected with a validation error unless `allow_overdraft` is set,
+/// in which case the projection carries a warning instead.
+///
+/// `current_balance` is the available balance, so pending transactions count
+/// against the limit. It must be read while the account row is locked (see
+/// [`repositories::account::lock_and_calculate_balance`]) so that concurrent
+/// writes cannot both pass the check.
+pub fn project_balance(
+    account: &Account,
+    current_balance: &BigDecimal,
+    delta: &BigDecimal,
+) -> Result<BalanceProjection, ApiError> {
+    let projected_balance = current_balance + delta;
+
+    let zero = BigDecimal::from(0);
+    let breach = if *delta < zero {
+        limit_breach_message(account, &projected_balance)
+    } else {
+        None
+    };
+
+    let warning = match breach {
+        Some(message) if !account.allow_overdraft => {
+            tracing::warn!(
+                "Rejected transaction on account {}: {}",
+                account.id,
+                message
+            );
+            return Err(ApiError::Validation(message));
+        }
+        other => other,
+    };
+
+    Ok(BalanceProjection {
+        projected_balance,
+        warning,
+    })
+}
+
+/// Describe how a projected balance breaches the account's limit, if it does
+fn limit_breach_message(account: &Account, projected_balance: &BigDecimal) -> Option<String> {
+    let zero = BigDecimal::from(0);
+
+    if account.account_type == AccountType::CreditCard {
+        // Credit cards carry a negative balance; only the credit limit applies
+        let credit_limit = account.credit_limit.as_ref()?;
+        let available = credit_limit + projected_balance;
+        if available < zero {
+            return Some(format!(
+                "Transaction would exceed the credit limit of {:.2} (available credit after transaction: {:.2})",
+                credit_limit, available
+            ));
+        }
+        return None;
+    }
+    if account.account_type.is_loan() {
+        // A loan's negative balance is what is owed, not an overdraft
+        return None;
+    }
+
+    let overdraft_limit = account.overdraft_limit.clone().unwrap_or(zero);
+    if *projected_balance < -&overdraft_limit {
+        return Some(format!(
+            "Transaction would bring the account balance to {:.2}, beyond the overdraft limit of {:.2}",
+            projected_balance, overdraft_limit
+        ));
+    }
+
+    None
+}
+
+/// Build the API response for an account with its computed balances
+fn build_account_response(account: Account, balances: &AccountBalances) -> AccountResponse {
+    let to_f64 = |value: &BigDecimal| value.to_string().parse::<f64>().unwrap_or(0.0);
+
+    // Pending charges already use up credit
+    let available_credit = match (&account.account_type, &account.credit_limit) {
+        (AccountType::CreditCard, Some(limit)) => Some(to_f64(&(limit + &balances.available))),
+        _ => None,
+    };
+    let outstanding_principal = account
+        .account_type
+        .is_loan()
+        .then(|| to_f64(&-&balances.cleared));
+
+    AccountResponse {
+        id: account.id,
+        user_id: account.user_id,
+        name: account.name,
+        account_type: account.account_type,
+        currency: account.currency,
+        balance: to_f64(&balances.cleared),
+        available_balance: to_f64(&balances.available),
+        is_active: true, // TODO: Add is_active field to database schema for account archiving
+        notes: account.notes,
+        allow_overdraft: account.allow_overdraft,
+        overdraft_limit: account.overdraft_limit.as_ref().map(to_f64),
+        credit_limit: account.credit_limit.as_ref().map(to_f64),
+        available_credit,
+        interest_rate: account.interest_rate.as_ref().map(to_f64),
+        original_principal: account.original_principal.as_ref().map(to_f64),
+        outstanding_principal,
+        version: account.version,
+        created_at: account.created_at,
+        updated_at: account.updated_at,
+    }
+}
+
+/// Convert an optional limit or rate from the request into a BigDecimal
+fn convert_limit(limit: Option<f64>, label: &str) -> Result<Option<BigDecimal>, ApiError> {
+    limit
+        .map(|value| {
+            BigDecimal::from_str(&value.to_string()).map_err(|e| {
+                tracing::error!("Failed to convert {}: {}", label, e);
+                ApiError::Validation(format!("Invalid {}", label))
+            })
+        })
+        .transpose()
 }
@@ -0,0 +1,340 @@
+//! Income allocation rules
+//!
+//! An allocation rule splits income landing in a source account across other
+//! accounts by percentage, e.g. 30% to savings and 25% to taxes. When a posted
+//! income transaction is created in (or a pending one is posted to) the source
+//! account, each destination's share is moved with a transfer: an outgoing
+//! transaction in the source account and an incoming one in the destination.
+//! The transfers are saved in the same DB transaction as the income, and the
+//! outgoing legs are checked against the source account's limits.
+//!
+//! If several rules match, only the most specific one is applied: a rule for
+//! the income's category wins over one for all income, and older rules win over
+//! newer ones. Transfers created by a rule never trigger rules themselves.
+
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        Account, AllocationRuleResponse, CreateAllocationRuleRequest, NewAllocationRule,
+        NewTransaction, Transaction,
+        allocation_rule::{allocate, percentage_to_decimal},
+    },
+    repositories::{self, allocation_rule::RuleWithDestinations},
+    services::account_service,
+    types::TransactionStatus,
+};
+
+/// Create an allocation rule
+///
+/// The source, destination accounts and category must belong to the user, and
+/// destination accounts must share the source account's currency.
+pub async fn create_rule(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateAllocationRuleRequest,
+) -> Result<AllocationRuleResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Allocation rule validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let source = repositories::account::find_by_id(pool, request.source_account_id).await?;
+    if source.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to allocate income of account {} owned by {}",
+            user_id,
+            source.id,
+            source.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Account does not belong to user".to_string(),
+        ));
+    }
+
+    if let Some(category_id) = request.category_id {
+        let category = repositories::category::find_by_id(pool, category_id).await?;
+        if category.user_id != user_id {
+            tracing::warn!(
+                "User {} attempted to use category {} owned by {}",
+                user_id,
+                category_id,
+                category.user_id
+            );
+            return Err(ApiError::Forbidden(
+                "Category does not belong to user".to_string(),
+            ));
+        }
+    }
+
+    let mut destinations = Vec::with_capacity(request.destinations.len());
+    for destination in request.destinations {
+        let account = repositories::account::find_by_id(pool, destination.account_id).await?;
+        if account.user_id != user_id {
+            tracing::warn!(
+                "User {} attempted to allocate income to account {} owned by {}",
+                user_id,
+                account.id,
+                account.user_id
+            );
+            return Err(ApiError::Forbidden(
+                "Account does not belong to user".to_string(),
+            ));
+        }
+        if account.currency != source.currency {
+            return Err(ApiError::Validation(format!(
+                "Destination account {} must use the source account's currency ({})",
+                account.id,
+                source.currency.as_str()
+            )));
+        }
+
+        // Checked by the request validation
+        let percentage = percentage_to_decimal(destination.percentage)
+            .ok_or_else(|| ApiError::Validation("Invalid percentage".to_string()))?;
+        destinations.push((account.id, percentage));
+    }
+
+    let new_rule = NewAllocationRule {
+        user_id,
+        name: request.name,
+        source_account_id: source.id,
+        category_id: request.category_id,
+    };
+    let (rule, destinations) =
+        repositories::allocation_rule::create_rule(pool, new_rule, destinations).await?;
+
+    tracing::info!("Created allocation rule {} for user {}", rule.id, user_id);
+
+    Ok(AllocationRuleResponse::new(rule, destinations))
+}
+
+/// List all allocation rules of a user
+pub async fn list_rules(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<AllocationRuleResponse>, ApiError> {
+    let rules = repositories::allocation_rule::list_by_user(pool, user_id).await?;
+
+    Ok(rules
+        .into_iter()
+        .map(|(rule, destinations)| AllocationRuleResponse::new(rule, destinations))
+        .collect())
+}
+
+/// Get an allocation rule
+pub async fn get_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+) -> Result<AllocationRuleResponse, ApiError> {
+    let (rule, destinations) = repositories::allocation_rule::find_by_id(pool, rule_id).await?;
+    if rule.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to access allocation rule {} owned by {}",
+            user_id,
+            rule_id,
+            rule.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(AllocationRuleResponse::new(rule, destinations))
+}
+
+/// Delete an allocation rule
+///
+/// Transfers the rule already created are kept.
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+    let (rule, _) = repositories::allocation_rule::find_by_id(pool, rule_id).await?;
+    if rule.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to delete allocation rule {} owned by {}",
+            user_id,
+            rule_id,
+            rule.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    repositories::allocation_rule::delete_rule(pool, rule_id).await?;
+
+    tracing::info!("Deleted allocation rule {} for user {}", rule_id, user_id);
+
+    Ok(())
+}
+
+/// The allocation rules of a source account, used to plan the transfers that
+/// allocate income landing in it
+///
+/// Planning is separate from saving so that the income and its transfers can be
+/// created in one DB transaction.
+#[derive(Debug, Clone)]
+pub struct AccountAllocation {
+    account: Account,
+    /// Oldest first
+    rules: Vec<RuleWithDestinations>,
+}
+
+impl AccountAllocation {
+    /// Group rules by source account, skipping rules whose source is not in
+    /// `accounts`
+    pub fn by_account(
+        rules: Vec<RuleWithDestinations>,
+        accounts: &HashMap<Uuid, Account>,
+    ) -> HashMap<Uuid, AccountAllocation> {
+        let mut allocations: HashMap<Uuid, AccountAllocation> = HashMap::new();
+        for (rule, destinations) in rules {
+            let Some(account) = accounts.get(&rule.source_account_id) else {
+                continue;
+            };
+            allocations
+                .entry(account.id)
+                .or_insert_with(|| AccountAllocation {
+                    account: account.clone(),
+                    rules: Vec::new(),
+                })
+                .rules
+                .push((rule, destinations));
+        }
+        allocations
+    }
+
+    /// Accounts the transfers of these rules can touch, the source included
+    pub fn account_ids(&self) -> Vec<Uuid> {
+        let mut account_ids: Vec<Uuid> = self
+            .rules
+            .iter()
+            .flat_map(|(_, destinations)| destinations.iter().map(|d| d.account_id))
+            .collect();
+        account_ids.push(self.account.id);
+        account_ids
+    }
+
+    /// Transfers allocating new income by the matching rule
+    ///
+    /// None are planned for expenses, transactions that are not posted, or when
+    /// no rule matches.
+    pub fn transfers(&self, income: &NewTransaction) -> Vec<NewTransaction> {
+        if income.status != TransactionStatus::Posted {
+            return Vec::new();
+        }
+        self.plan(
+            income.user_id,
+            income.category_id,
+            &income.amount,
+            income.date,
+            &income.title,
+        )
+    }
+
+    /// Transfers allocating a pending income transaction that is being posted
+    pub fn transfers_on_post(&self, income: &Transaction) -> Vec<NewTransaction> {
+        self.plan(
+            income.user_id,
+            income.category_id,
+            &income.amount,
+            income.date,
+            &income.title,
+        )
+    }
+
+    /// Check a planned transfer against the source account's limits
+    ///
+    /// Only the outgoing legs leave the source account, and incoming legs can
+    /// never breach a limit, so only the former are checked.
+    pub fn check_transfer(
+        &self,
+        transfer: &NewTransaction,
+        balance: &BigDecimal,
+    ) -> Result<(), ApiError> {
+        if transfer.account_id != self.account.id {
+            return Ok(());
+        }
+        account_service::project_balance(&self.account, balance, &transfer.amount).map(|_| ())
+    }
+
+    fn plan(
+        &self,
+        user_id: Uuid,
+        category_id: Option<Uuid>,
+        amount: &BigDecimal,
+        date: DateTime<Utc>,
+        title: &str,
+    ) -> Vec<NewTransaction> {
+        if !amount.is_positive() {
+            return Vec::new();
+        }
+
+        // Prefer a rule for the income's category
+        let matching = self
+            .rules
+            .iter()
+            .filter(|(rule, _)| rule.category_id.is_some() && rule.category_id == category_id)
+            .chain(
+                self.rules
+                    .iter()
+                    .filter(|(rule, _)| rule.category_id.is_none()),
+            )
+            .next();
+        let Some((rule, destinations)) = matching else {
+            return Vec::new();
+        };
+
+        let percentages: Vec<(Uuid, BigDecimal)> = destinations
+            .iter()
+            .map(|destination| (destination.account_id, destination.percentage.clone()))
+            .collect();
+        let shares = allocate(amount, &percentages, self.account.currency.minor_units());
+
+        let transfer = |account_id: Uuid, amount: BigDecimal, transfer_id: Uuid| NewTransaction {
+            user_id,
+            account_id,
+            category_id: None,
+            title: rule.name.clone(),
+            amount,
+            date,
+            notes: Some(format!("Allocated from \"{}\"", title)),
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: Some(transfer_id),
+            reimbursable: false,
+            tags: Vec::new(),
+        };
+        // Both legs of each transfer share a transfer ID
+        shares
+            .into_iter()
+            .filter(|(_, share)| !share.is_zero())
+            .flat_map(|(account_id, share)| {
+                let transfer_id = Uuid::new_v4();
+                [
+                    transfer(self.account.id, -share.clone(), transfer_id),
+                    transfer(account_id, share, transfer_id),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Load the allocation rules of an account
+pub async fn for_account(pool: &DbPool, account: &Account) -> Result<AccountAllocation, ApiError> {
+    let rules = repositories::allocation_rule::list_by_source_account(pool, account.id).await?;
+    Ok(AccountAllocation {
+        account: account.clone(),
+        rules,
+    })
+}
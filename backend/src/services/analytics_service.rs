@@ -1,24 +1,33 @@
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use std::collections::{HashMap, hash_map::Entry};
 use uuid::Uuid;
 
 use crate::{
     DbPool,
     errors::ApiError,
-    models::{TransactionFilter, TransactionResponse},
+    models::{BudgetStatus, Category, TagMode, TransactionFilter, TransactionResponse},
     repositories,
-    services::exchange_rate_service::ExchangeRateService,
+    services::{
+        exchange_rate_service::{ExchangeRateService, PRIMARY_CURRENCY},
+        transaction_service,
+    },
+    types::{CurrencyCode, Granularity, Money, TransactionStatus},
 };
 
+/// Most months a net worth trend or cashflow report may cover
+pub const MAX_TREND_MONTHS: u32 = 120;
+
 /// Net worth calculation result
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct NetWorth {
+    /// Sum of all account balances, in the primary currency
     pub total: String,
     pub accounts: Vec<AccountBalance>,
 }
 
-#[derive(Debug, serde::Serialize)]
+/// Balance of one account, converted into the primary currency
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct AccountBalance {
     pub account_id: Uuid,
     pub account_name: String,
@@ -33,22 +42,144 @@ pub struct SpendingTrendPoint {
 }
 
 /// Category breakdown item
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 pub struct CategoryBreakdown {
     pub category_id: Option<Uuid>,
     pub category_name: Option<String>,
+    /// Icon of the category, for rendering without looking the category up
+    pub category_icon: Option<String>,
+    /// Color of the category, e.g. `#4CAF50`
+    pub category_color: Option<String>,
     pub total: String,
+    /// Currency of `total`
+    pub currency: CurrencyCode,
+    /// Whether `total` was converted into the breakdown's base currency
+    pub converted: bool,
+    /// Share of all spending in the same currency
     pub percentage: f64,
 }
 
+/// Spending at one merchant, in one currency
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct MerchantSpending {
+    /// Merchant name as most recently recorded (merchants are matched case-insensitively)
+    pub merchant: String,
+    /// Total spent at the merchant
+    pub total: Money,
+    /// Currency of `total`
+    pub currency: CurrencyCode,
+    /// Number of expenses at the merchant
+    pub transaction_count: i64,
+}
+
+/// Merchant spending report query parameters
+#[derive(Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MerchantSpendingQuery {
+    /// Only count spending at or after this time
+    pub start_date: Option<DateTime<Utc>>,
+    /// Only count spending at or before this time
+    pub end_date: Option<DateTime<Utc>>,
+}
+
 /// Dashboard summary with all key metrics
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
 pub struct DashboardSummary {
     pub net_worth: String,
     pub recent_transactions: Vec<TransactionResponse>,
-    pub budget_statuses: Vec<super::budget_service::BudgetStatus>,
+    pub budget_statuses: Vec<BudgetStatus>,
+    pub category_breakdown: Vec<CategoryBreakdown>,
+    pub top_spending_categories: Vec<CategoryBreakdown>,
+    /// Number of transactions without a category, to triage with `?uncategorized=true`
+    pub uncategorized_count: i64,
+    /// Currency the category breakdown was converted into, or `None` when grouped by currency
+    pub breakdown_currency: Option<CurrencyCode>,
+}
+
+/// Category section of the dashboard
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct DashboardCategories {
     pub category_breakdown: Vec<CategoryBreakdown>,
     pub top_spending_categories: Vec<CategoryBreakdown>,
+    /// Number of transactions without a category, to triage with `?uncategorized=true`
+    pub uncategorized_count: i64,
+    /// Currency the category breakdown was converted into, or `None` when grouped by currency
+    pub breakdown_currency: Option<CurrencyCode>,
+}
+
+/// Dashboard query parameters
+#[derive(Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DashboardQuery {
+    /// Currency to convert the category breakdown into (default: the primary currency)
+    pub base_currency: Option<CurrencyCode>,
+    /// Report category totals per currency instead of converting them
+    #[serde(default)]
+    pub group_by_currency: bool,
+    /// Leave reimbursed transactions out of the category spending totals
+    #[serde(default)]
+    pub exclude_reimbursed: bool,
+}
+
+/// Net worth trend query parameters
+#[derive(Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NetWorthTrendQuery {
+    /// Number of months, ending with the current one (default: 12, at most 120)
+    pub months: Option<u32>,
+    /// Currency to convert balances into (default: the primary currency)
+    pub currency: Option<CurrencyCode>,
+}
+
+/// Net worth at the end of each month
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct NetWorthTrend {
+    /// Currency of every `net_worth`
+    pub currency: CurrencyCode,
+    /// One point per month, oldest first
+    pub points: Vec<NetWorthPoint>,
+}
+
+/// Net worth at the end of a month
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct NetWorthPoint {
+    /// The month, as `YYYY-MM`
+    pub month: String,
+    /// Posted balances of all accounts at the end of the month (today for the
+    /// current month), converted at that day's rates
+    pub net_worth: Money,
+}
+
+/// Cashflow query parameters
+#[derive(Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CashflowQuery {
+    /// Number of months, ending with the current one (default: 6, at most 120)
+    pub months: Option<u32>,
+    /// Currency to convert amounts into (default: the primary currency)
+    pub currency: Option<CurrencyCode>,
+}
+
+/// Money in and out of the user's accounts per month
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Cashflow {
+    /// Currency of every amount
+    pub currency: CurrencyCode,
+    /// One entry per month, oldest first
+    pub months: Vec<CashflowMonth>,
+}
+
+/// Money in and out of the user's accounts in a month, excluding transfers
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct CashflowMonth {
+    /// The month, as `YYYY-MM`
+    pub month: String,
+    /// Sum of posted incoming transactions
+    pub income: Money,
+    /// Sum of posted outgoing transactions, as a positive amount
+    pub expense: Money,
+    /// `income - expense`
+    pub net: Money,
 }
 
 /// Calculate net worth (sum of all account balances converted to primary currency)
@@ -57,11 +188,13 @@ pub async fn calculate_net_worth(pool: &DbPool, user_id: Uuid) -> Result<NetWort
     let accounts = repositories::account::list_by_user(pool, user_id).await?;
 
     // Initialize exchange rate service
-    let exchange_service = ExchangeRateService::new()?;
+    let exchange_service = ExchangeRateService::new()?.with_manual_rates(pool.clone(), user_id);
 
     let mut account_balances = Vec::new();
     let mut total = BigDecimal::from(0);
 
+    // Loans, mortgages and credit cards carry what is owed as a negative
+    // balance, so they reduce the total
     for account in accounts {
         let balance = repositories::account::calculate_balance(pool, account.id).await?;
 
@@ -85,6 +218,204 @@ pub async fn calculate_net_worth(pool: &DbPool, user_id: Uuid) -> Result<NetWort
     })
 }
 
+/// Net worth at the end of each of the last `query.months` months
+///
+/// Each account's posted balance is converted at the rate on the month's last
+/// day. Accounts created after a month ended count as zero for that month,
+/// even if they have transactions dated before they were created.
+pub async fn get_net_worth_trend(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: NetWorthTrendQuery,
+) -> Result<NetWorthTrend, ApiError> {
+    let currency = query.currency.unwrap_or(PRIMARY_CURRENCY);
+    let month_ends = recent_month_ends(query.months.unwrap_or(12))?;
+    let start = month_ends[0].with_day(1).ok_or(ApiError::Internal)?;
+    let today = Utc::now().date_naive();
+
+    let mut totals = vec![BigDecimal::from(0); month_ends.len()];
+    let mut converter = DailyRateConverter::new(user_id, currency);
+
+    for account in repositories::account::list_by_user(pool, user_id).await? {
+        let created = account.created_at.date_naive();
+        if created > today {
+            continue;
+        }
+
+        let balances = repositories::account::balance_history(
+            pool,
+            account.id,
+            start,
+            today,
+            Granularity::Month,
+        )
+        .await?;
+
+        for (index, (_, balance)) in balances.into_iter().enumerate() {
+            let Some(&month_end) = month_ends.get(index) else {
+                break;
+            };
+            if created > month_end {
+                continue;
+            }
+            totals[index] += converter
+                .convert(pool, balance, account.currency, month_end)
+                .await?;
+        }
+    }
+
+    let points = month_ends
+        .iter()
+        .zip(totals)
+        .map(|(month_end, total)| NetWorthPoint {
+            month: month_end.format("%Y-%m").to_string(),
+            net_worth: Money::new(total, currency),
+        })
+        .collect();
+
+    Ok(NetWorthTrend { currency, points })
+}
+
+/// Income and expense in each of the last `query.months` months
+///
+/// Posted transactions are summed per month and account currency in one
+/// grouped query, leaving out transfers between accounts, and the sums are
+/// converted at the rate on the month's last day.
+pub async fn get_cashflow(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: CashflowQuery,
+) -> Result<Cashflow, ApiError> {
+    let currency = query.currency.unwrap_or(PRIMARY_CURRENCY);
+    let month_ends = recent_month_ends(query.months.unwrap_or(6))?;
+    let start = month_ends[0].with_day(1).ok_or(ApiError::Internal)?;
+
+    let zero = || BigDecimal::from(0);
+    let mut totals = vec![(zero(), zero()); month_ends.len()];
+    let mut converter = DailyRateConverter::new(user_id, currency);
+
+    let sums = repositories::transaction::sum_cashflow_by_month(pool, user_id, start).await?;
+    for (month, account_currency, income, expense) in sums {
+        let Some(index) = month_ends
+            .iter()
+            .position(|month_end| month_end.with_day(1) == Some(month))
+        else {
+            continue;
+        };
+        let month_end = month_ends[index];
+        totals[index].0 += converter
+            .convert(pool, income, account_currency, month_end)
+            .await?;
+        totals[index].1 += converter
+            .convert(pool, -expense, account_currency, month_end)
+            .await?;
+    }
+
+    let months = month_ends
+        .iter()
+        .zip(totals)
+        .map(|(month_end, (income, expense))| CashflowMonth {
+            month: month_end.format("%Y-%m").to_string(),
+            net: Money::new(&income - &expense, currency),
+            income: Money::new(income, currency),
+            expense: Money::new(expense, currency),
+        })
+        .collect();
+
+    Ok(Cashflow { currency, months })
+}
+
+/// Last day counted in each of the last `months` months, the current one
+/// (counted up to today) last
+fn recent_month_ends(months: u32) -> Result<Vec<NaiveDate>, ApiError> {
+    if !(1..=MAX_TREND_MONTHS).contains(&months) {
+        return Err(ApiError::BadRequest(format!(
+            "months must be between 1 and {}",
+            MAX_TREND_MONTHS
+        )));
+    }
+
+    let today = Utc::now().date_naive();
+    let current_month = today.with_day(1).ok_or(ApiError::Internal)?;
+    let start = current_month
+        .checked_sub_months(Months::new(months - 1))
+        .ok_or(ApiError::Internal)?;
+
+    Ok((0..months)
+        .map(|offset| {
+            let month = start + Months::new(offset);
+            (month + Months::new(1))
+                .pred_opt()
+                .unwrap_or(month)
+                .min(today)
+        })
+        .collect())
+}
+
+/// Converts amounts into one currency at the rates of given days
+///
+/// Rates are looked up once per currency and day, preferring the manual rates
+/// of `user_id`, and the exchange rate service is only created once an amount
+/// in another currency is converted. A failed lookup is remembered too, so
+/// later amounts in the same currency and day fail without asking again.
+struct DailyRateConverter {
+    user_id: Uuid,
+    currency: CurrencyCode,
+    service: Option<ExchangeRateService>,
+    rates: HashMap<(CurrencyCode, NaiveDate), Option<BigDecimal>>,
+}
+
+impl DailyRateConverter {
+    fn new(user_id: Uuid, currency: CurrencyCode) -> Self {
+        Self {
+            user_id,
+            currency,
+            service: None,
+            rates: HashMap::new(),
+        }
+    }
+
+    async fn convert(
+        &mut self,
+        pool: &DbPool,
+        amount: BigDecimal,
+        from: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<BigDecimal, ApiError> {
+        if from == self.currency {
+            return Ok(amount);
+        }
+
+        let rate = match self.rates.entry((from, date)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let service = match &self.service {
+                    Some(service) => service,
+                    None => self.service.insert(
+                        ExchangeRateService::new()?.with_manual_rates(pool.clone(), self.user_id),
+                    ),
+                };
+                match service.get_rate_on(pool, from, self.currency, date).await {
+                    Ok(rate) => entry.insert(Some(rate.rate)),
+                    Err(e) => {
+                        entry.insert(None);
+                        return Err(e);
+                    }
+                }
+            }
+        };
+        match rate {
+            Some(rate) => Ok(amount * &*rate),
+            None => Err(ApiError::External(format!(
+                "No exchange rate from {} to {} on {}",
+                from.as_str(),
+                self.currency.as_str(),
+                date
+            ))),
+        }
+    }
+}
+
 /// Get spending trend over a date range
 /// Groups transactions by date and calculates daily spending
 pub async fn get_spending_trend(
@@ -97,42 +428,59 @@ pub async fn get_spending_trend(
     let filter = TransactionFilter {
         account_id: None,
         category_id: None,
+        uncategorized: false,
+        include_deleted: false,
         start_date: Some(start_date),
         end_date: Some(end_date),
         min_amount: None,
         max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: Some(TransactionStatus::Posted),
         search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: None,
+        sort: None,
         limit: None,
         offset: None,
     };
 
     let transactions = repositories::transaction::list_transactions(pool, user_id, filter).await?;
 
-    // Initialize exchange rate service
-    let exchange_service = ExchangeRateService::new()?;
+    // Look up account currencies once instead of per transaction
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+    let mut converter = DailyRateConverter::new(user_id, PRIMARY_CURRENCY);
 
     // Group by date
     let mut daily_spending: HashMap<String, BigDecimal> = HashMap::new();
 
     for transaction in transactions {
-        // Only count expenses (negative amounts)
-        if transaction.amount < BigDecimal::from(0) {
-            let date_key = transaction.date.format("%Y-%m-%d").to_string();
-            let spending = transaction.amount.abs();
+        // Only count expenses (negative amounts); transfers are not spending
+        if transaction.amount >= BigDecimal::from(0) || transaction.transfer_id.is_some() {
+            continue;
+        }
+        let Some(&currency) = account_currencies.get(&transaction.account_id) else {
+            continue;
+        };
 
-            // Get account to find currency
-            let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
+        let date = transaction.date.date_naive();
+        let spending = transaction.amount.abs();
 
-            // Convert to primary currency
-            let converted_spending = exchange_service
-                .convert_to_primary_currency(&spending, account.currency)
-                .await?;
+        // Convert to primary currency at the rate on the transaction's date
+        let converted_spending = converter.convert(pool, spending, currency, date).await?;
 
-            daily_spending
-                .entry(date_key)
-                .and_modify(|total| *total += converted_spending.clone())
-                .or_insert(converted_spending);
-        }
+        daily_spending
+            .entry(date.format("%Y-%m-%d").to_string())
+            .and_modify(|total| *total += converted_spending.clone())
+            .or_insert(converted_spending);
     }
 
     // Convert to sorted vector
@@ -150,71 +498,140 @@ pub async fn get_spending_trend(
 }
 
 /// Get category breakdown for spending
+///
+/// With a `base_currency`, spending is converted into it. Spending in currencies
+/// without an available rate is not converted and is reported as separate items
+/// in its own currency. Without a `base_currency`, totals are grouped per currency.
+/// With `exclude_reimbursed`, spending that was paid back is left out.
 pub async fn get_category_breakdown(
     pool: &DbPool,
     user_id: Uuid,
     start_date: DateTime<Utc>,
     end_date: DateTime<Utc>,
+    base_currency: Option<CurrencyCode>,
+    exclude_reimbursed: bool,
 ) -> Result<Vec<CategoryBreakdown>, ApiError> {
     // Get transactions in date range
     let filter = TransactionFilter {
         account_id: None,
         category_id: None,
+        uncategorized: false,
+        include_deleted: false,
         start_date: Some(start_date),
         end_date: Some(end_date),
         min_amount: None,
         max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: Some(TransactionStatus::Posted),
         search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: exclude_reimbursed.then_some(false),
+        sort: None,
         limit: None,
         offset: None,
     };
 
     let transactions = repositories::transaction::list_transactions(pool, user_id, filter).await?;
 
-    // Initialize exchange rate service
-    let exchange_service = ExchangeRateService::new()?;
+    // Look up account currencies once instead of per transaction
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
 
-    // Group by category
-    let mut category_totals: HashMap<Option<Uuid>, BigDecimal> = HashMap::new();
-    let mut total_spending = BigDecimal::from(0);
+    // Group expenses (negative amounts) by category and original currency;
+    // transfers between accounts are not spending
+    let zero = BigDecimal::from(0);
+    let mut spending_by_currency: HashMap<(Option<Uuid>, CurrencyCode), BigDecimal> =
+        HashMap::new();
 
     for transaction in &transactions {
-        // Only count expenses (negative amounts)
-        if transaction.amount < BigDecimal::from(0) {
-            let spending = transaction.amount.abs();
-
-            // Get account to find currency
-            let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
+        if transaction.amount >= zero || transaction.transfer_id.is_some() {
+            continue;
+        }
+        let Some(&currency) = account_currencies.get(&transaction.account_id) else {
+            continue;
+        };
 
-            // Convert to primary currency
-            let converted_spending = exchange_service
-                .convert_to_primary_currency(&spending, account.currency)
-                .await?;
+        *spending_by_currency
+            .entry((transaction.category_id, currency))
+            .or_insert_with(|| BigDecimal::from(0)) += transaction.amount.abs();
+    }
 
-            total_spending += converted_spending.clone();
+    // Convert into the base currency where a rate is available
+    let mut conversion_rates: HashMap<CurrencyCode, Option<BigDecimal>> = HashMap::new();
+    if let Some(base) = base_currency {
+        let exchange_service = ExchangeRateService::new()?.with_manual_rates(pool.clone(), user_id);
+        let one = BigDecimal::from(1);
 
-            category_totals
-                .entry(transaction.category_id)
-                .and_modify(|total| *total += converted_spending.clone())
-                .or_insert(converted_spending);
+        for &(_, currency) in spending_by_currency.keys() {
+            if conversion_rates.contains_key(&currency) {
+                continue;
+            }
+            let rate = match exchange_service
+                .convert_currency(&one, currency, base)
+                .await
+            {
+                Ok(rate) => Some(rate),
+                Err(e) => {
+                    tracing::warn!(
+                        "No exchange rate from {} to {}, reporting spending unconverted: {}",
+                        currency.as_str(),
+                        base.as_str(),
+                        e
+                    );
+                    None
+                }
+            };
+            conversion_rates.insert(currency, rate);
         }
     }
 
-    // Get category names
+    // (category, currency, converted) -> total
+    let mut category_totals: HashMap<(Option<Uuid>, CurrencyCode, bool), BigDecimal> =
+        HashMap::new();
+    for ((category_id, currency), spending) in spending_by_currency {
+        let (currency, converted, amount) = match (base_currency, conversion_rates.get(&currency)) {
+            (Some(base), Some(Some(rate))) => (base, true, spending * rate),
+            _ => (currency, false, spending),
+        };
+        *category_totals
+            .entry((category_id, currency, converted))
+            .or_insert_with(|| BigDecimal::from(0)) += amount;
+    }
+
+    // Percentages are relative to all spending reported in the same currency
+    let mut currency_totals: HashMap<(CurrencyCode, bool), BigDecimal> = HashMap::new();
+    for ((_, currency, converted), total) in &category_totals {
+        *currency_totals
+            .entry((*currency, *converted))
+            .or_insert_with(|| BigDecimal::from(0)) += total;
+    }
+
+    // Look up category names, icons and colors once instead of per category
+    let categories: HashMap<Uuid, Category> = repositories::category::list_by_user(pool, user_id)
+        .await?
+        .into_iter()
+        .map(|category| (category.id, category))
+        .collect();
+
     let mut breakdown = Vec::new();
 
-    for (category_id, total) in category_totals {
-        let category_name = if let Some(id) = category_id {
-            match repositories::category::find_by_id(pool, id).await {
-                Ok(cat) => Some(cat.name),
-                Err(_) => None,
-            }
-        } else {
-            None
-        };
+    for ((category_id, currency, converted), total) in category_totals {
+        let category = category_id.and_then(|id| categories.get(&id));
+        let category_name = category.map(|c| c.name.clone());
+        let category_icon = category.and_then(|c| c.icon.clone());
+        let category_color = category.and_then(|c| c.color.clone());
 
-        let percentage = if total_spending > BigDecimal::from(0) {
-            let ratio = &total / &total_spending;
+        let currency_total = &currency_totals[&(currency, converted)];
+        let percentage = if *currency_total > zero {
+            let ratio = &total / currency_total;
             ratio.to_string().parse::<f64>().unwrap_or(0.0) * 100.0
         } else {
             0.0
@@ -223,101 +640,283 @@ pub async fn get_category_breakdown(
         breakdown.push(CategoryBreakdown {
             category_id,
             category_name,
+            category_icon,
+            category_color,
             total: total.to_string(),
+            currency,
+            converted,
             percentage,
         });
     }
 
-    // Sort by total (descending)
+    // Converted totals first, then by currency and total (descending)
     breakdown.sort_by(|a, b| {
         let a_total = BigDecimal::from_str(&a.total).unwrap_or_default();
         let b_total = BigDecimal::from_str(&b.total).unwrap_or_default();
-        b_total.cmp(&a_total)
+        b.converted
+            .cmp(&a.converted)
+            .then_with(|| a.currency.as_str().cmp(b.currency.as_str()))
+            .then_with(|| b_total.cmp(&a_total))
     });
 
     Ok(breakdown)
 }
 
+/// Get spending grouped by merchant
+///
+/// Only posted expenses with a merchant are counted, leaving out transfers. Totals are reported per
+/// currency without conversion, largest first.
+pub async fn get_merchant_spending(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: MerchantSpendingQuery,
+) -> Result<Vec<MerchantSpending>, ApiError> {
+    let filter = TransactionFilter {
+        account_id: None,
+        category_id: None,
+        uncategorized: false,
+        include_deleted: false,
+        start_date: query.start_date,
+        end_date: query.end_date,
+        min_amount: None,
+        max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: Some(TransactionStatus::Posted),
+        search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: None,
+        sort: None,
+        limit: None,
+        offset: None,
+    };
+
+    let transactions = repositories::transaction::list_transactions(pool, user_id, filter).await?;
+
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+
+    // Transactions come newest first, so the first spelling seen is the most recent
+    let zero = BigDecimal::from(0);
+    let mut spending: HashMap<(String, CurrencyCode), MerchantSpending> = HashMap::new();
+    for transaction in transactions {
+        let Some(merchant) = transaction.merchant else {
+            continue;
+        };
+        if transaction.amount >= zero || transaction.transfer_id.is_some() {
+            continue;
+        }
+        let Some(&currency) = account_currencies.get(&transaction.account_id) else {
+            continue;
+        };
+
+        let entry = spending
+            .entry((merchant.to_lowercase(), currency))
+            .or_insert_with(|| MerchantSpending {
+                merchant,
+                total: Money::new(BigDecimal::from(0), currency),
+                currency,
+                transaction_count: 0,
+            });
+        entry.total = Money::new(
+            entry.total.as_decimal() + transaction.amount.abs(),
+            currency,
+        );
+        entry.transaction_count += 1;
+    }
+
+    let mut report: Vec<MerchantSpending> = spending.into_values().collect();
+    report.sort_by(|a, b| {
+        a.currency
+            .as_str()
+            .cmp(b.currency.as_str())
+            .then_with(|| b.total.as_decimal().cmp(a.total.as_decimal()))
+            .then_with(|| a.merchant.cmp(&b.merchant))
+    });
+
+    Ok(report)
+}
+
 /// Get dashboard summary with all key metrics
 /// Uses tokio::join! to run queries in parallel
 pub async fn get_dashboard_summary(
     pool: &DbPool,
     user_id: Uuid,
+    query: DashboardQuery,
 ) -> Result<DashboardSummary, ApiError> {
-    // Calculate date range for last 30 days
-    let end_date = Utc::now();
-    let start_date = end_date - chrono::Duration::days(30); // TODO: Make time range configurable (30 days hardcoded)
-
-    // Run queries in parallel using tokio::join!
-    let (net_worth_result, recent_transactions_result, budgets_result, category_breakdown_result) = tokio::join!(
+    // Each section is also served on its own; run them in parallel
+    let (net_worth_result, recent_transactions_result, budgets_result, categories_result) = tokio::join!(
         calculate_net_worth(pool, user_id),
         get_recent_transactions(pool, user_id),
         get_all_budget_statuses(pool, user_id),
-        get_category_breakdown(pool, user_id, start_date, end_date)
+        get_dashboard_categories(pool, user_id, query)
     );
 
     // Handle results
     let net_worth = net_worth_result?;
     let recent_transactions = recent_transactions_result?;
     let budget_statuses = budgets_result?;
-    let category_breakdown = category_breakdown_result?;
-
-    // Get top 5 spending categories
-    let top_spending_categories = category_breakdown.iter().take(5).cloned().collect(); // TODO: Make top N configurable
+    let categories = categories_result?;
 
     Ok(DashboardSummary {
         net_worth: net_worth.total,
         recent_transactions,
         budget_statuses,
+        category_breakdown: categories.category_breakdown,
+        top_spending_categories: categories.top_spending_categories,
+        uncategorized_count: categories.uncategorized_count,
+        breakdown_currency: categories.breakdown_currency,
+    })
+}
+
+/// Category breakdown of the last 30 days and the uncategorized count
+pub async fn get_dashboard_categories(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: DashboardQuery,
+) -> Result<DashboardCategories, ApiError> {
+    let breakdown_currency = if query.group_by_currency {
+        None
+    } else {
+        Some(query.base_currency.unwrap_or(PRIMARY_CURRENCY))
+    };
+
+    // Calculate date range for last 30 days
+    let end_date = Utc::now();
+    let start_date = end_date - chrono::Duration::days(30); // TODO: Make time range configurable (30 days hardcoded)
+
+    let (category_breakdown_result, uncategorized_result) = tokio::join!(
+        get_category_breakdown(
+            pool,
+            user_id,
+            start_date,
+            end_date,
+            breakdown_currency,
+            query.exclude_reimbursed,
+        ),
+        count_uncategorized(pool, user_id)
+    );
+    let category_breakdown = category_breakdown_result?;
+    let uncategorized_count = uncategorized_result?;
+
+    // Get top 5 spending categories
+    let top_spending_categories = category_breakdown.iter().take(5).cloned().collect(); // TODO: Make top N configurable
+
+    Ok(DashboardCategories {
         category_breakdown,
         top_spending_categories,
+        uncategorized_count,
+        breakdown_currency,
     })
 }
 
-/// Helper: Get recent transactions (last 10)
-async fn get_recent_transactions(
+/// Get recent transactions (last 10)
+pub async fn get_recent_transactions(
     pool: &DbPool,
     user_id: Uuid,
 ) -> Result<Vec<TransactionResponse>, ApiError> {
     let filter = TransactionFilter {
         account_id: None,
         category_id: None,
+        uncategorized: false,
+        include_deleted: false,
         start_date: None,
         end_date: None,
         min_amount: None,
         max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: None,
         search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: None,
+        sort: None,
         limit: Some(10), // TODO: Make recent transaction limit configurable
         offset: None,
     };
 
     let transactions = repositories::transaction::list_transactions(pool, user_id, filter).await?;
-
-    Ok(transactions
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+
+    let mut responses: Vec<TransactionResponse> = transactions
         .into_iter()
-        .map(TransactionResponse::from)
-        .collect())
+        .map(|transaction| {
+            let currency = account_currencies.get(&transaction.account_id).copied();
+            let mut response = TransactionResponse::from(transaction);
+            if let Some(currency) = currency {
+                response.set_currency(currency);
+            }
+            response
+        })
+        .collect();
+    transaction_service::attach_tags(pool, &mut responses).await?;
+
+    Ok(responses)
 }
 
-/// Helper: Get all budget statuses for user
-async fn get_all_budget_statuses(
+/// Helper: Count transactions without a category
+async fn count_uncategorized(pool: &DbPool, user_id: Uuid) -> Result<i64, ApiError> {
+    let filter = TransactionFilter {
+        account_id: None,
+        category_id: None,
+        uncategorized: true,
+        include_deleted: false,
+        start_date: None,
+        end_date: None,
+        min_amount: None,
+        max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: None,
+        search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: None,
+        sort: None,
+        limit: None,
+        offset: None,
+    };
+
+    repositories::transaction::count_transactions(pool, user_id, filter).await
+}
+
+/// Get the current status of every budget with an active range
+pub async fn get_all_budget_statuses(
     pool: &DbPool,
     user_id: Uuid,
-) -> Result<Vec<super::budget_service::BudgetStatus>, ApiError> {
+) -> Result<Vec<BudgetStatus>, ApiError> {
     let budgets = repositories::budget::list_by_user(pool, user_id).await?;
 
-    let mut statuses = Vec::new();
-
-    for budget in budgets {
-        // Try to calculate status, skip if no active range
-        match super::budget_service::calculate_budget_status(pool, budget.id, user_id).await {
-            Ok(status) => statuses.push(status),
-            Err(ApiError::NotFound(_)) => continue, // Skip budgets without active ranges
-            Err(e) => return Err(e),
-        }
-    }
-
-    Ok(statuses)
+    let mut statuses = super::budget_service::calculate_budget_statuses(
+        pool,
+        user_id,
+        &budgets,
+        Utc::now().date_naive(),
+    )
+    .await?;
+
+    // Skip budgets without active ranges
+    Ok(budgets
+        .iter()
+        .filter_map(|budget| statuses.remove(&budget.id))
+        .collect())
 }
 
 // Re-export BigDecimal::from_str for use in this module
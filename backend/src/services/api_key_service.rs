@@ -76,6 +76,7 @@ pub async fn create_api_key(
         scopes: scopes_json,
         status: ApiKeyStatus::Active,
         expires_at,
+        requests_per_minute: request.requests_per_minute,
     };
 
     let api_key = api_key_repo::create(pool, new_api_key).await?;
@@ -94,6 +95,7 @@ pub async fn create_api_key(
         scopes: request.scopes,
         status: api_key.status,
         expires_at: api_key.expires_at,
+        requests_per_minute: api_key.requests_per_minute,
         created_at: api_key.created_at,
     })
 }
@@ -225,6 +227,10 @@ pub async fn update_api_key(
         updated_key = api_key_repo::update_scopes(pool, id, scopes_json).await?;
     }
 
+    if let Some(requests_per_minute) = request.requests_per_minute {
+        updated_key = api_key_repo::update_rate_limit(pool, id, requests_per_minute).await?;
+    }
+
     tracing::info!("API key updated successfully: {}", id);
 
     ApiKeyResponse::from_api_key(updated_key).map_err(|e| {
@@ -0,0 +1,172 @@
+use std::convert::Infallible;
+use std::time::Duration as StdDuration;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    config::AuthEventConfig,
+    db::DbPool,
+    errors::ApiError,
+    middleware::client_ip::ClientIp,
+    models::{AuthEventQuery, AuthEventResponse, AuthEventType, NewAuthEvent},
+    repositories::{auth_event as auth_event_repo, user},
+};
+
+/// Default number of events returned by the history endpoint
+const DEFAULT_EVENT_LIMIT: i64 = 50;
+
+/// How often the retention purge runs
+const PURGE_INTERVAL: StdDuration = StdDuration::from_secs(24 * 60 * 60);
+
+/// Client details captured alongside each authentication event
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    /// Take the client IP resolved by [`resolve_client_ip`] and the user agent
+    ///
+    /// The IP is only as good as the trusted proxy configuration behind it, so
+    /// forwarded headers from other clients never end up in the security log.
+    ///
+    /// [`resolve_client_ip`]: crate::middleware::client_ip::resolve_client_ip
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Infallible> {
+        let ip_address = parts
+            .extensions
+            .get::<ClientIp>()
+            .map(|ClientIp(ip)| ip.to_string());
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(|v| v.to_string());
+
+        Ok(Self {
+            ip_address,
+            user_agent,
+        })
+    }
+}
+
+/// Record an authentication event
+///
+/// Auditing is best-effort: failures are logged but never propagated, so a
+/// broken audit write cannot lock users out.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `user_id` - User the event belongs to, if known
+/// * `event_type` - Type of authentication event
+/// * `client` - Client IP and user agent
+pub async fn record(
+    pool: &DbPool,
+    user_id: Option<Uuid>,
+    event_type: AuthEventType,
+    client: &ClientInfo,
+) {
+    let new_event = NewAuthEvent {
+        user_id,
+        event_type: event_type.as_str().to_string(),
+        ip_address: client.ip_address.clone(),
+        user_agent: client.user_agent.clone(),
+    };
+
+    if let Err(e) = auth_event_repo::create(pool, new_event).await {
+        tracing::error!(
+            "Failed to record {} auth event for user {:?}: {}",
+            event_type.as_str(),
+            user_id,
+            e
+        );
+    }
+}
+
+/// Record a failed login attempt
+///
+/// The attempt is attributed to the account owning `email` when one exists so
+/// that the owner can see it in their sign-in history.
+pub async fn record_login_failure(pool: &DbPool, email: &str, client: &ClientInfo) {
+    let user_id = user::find_by_email(pool, email).await.ok().map(|u| u.id);
+    record(pool, user_id, AuthEventType::LoginFailed, client).await;
+}
+
+/// List the most recent authentication events for a user
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `user_id` - User whose events to list
+/// * `query` - Query parameters (limit)
+///
+/// # Returns
+/// * `Result<Vec<AuthEventResponse>, ApiError>` - Events, newest first
+///
+/// # Errors
+/// - Validation errors if the limit is out of range
+/// - Internal errors for database failures
+pub async fn list_recent(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: AuthEventQuery,
+) -> Result<Vec<AuthEventResponse>, ApiError> {
+    query.validate().map_err(|e| {
+        tracing::warn!("Validation error listing auth events: {}", e);
+        ApiError::Validation(format!("Invalid query parameters: {}", e))
+    })?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_EVENT_LIMIT);
+    let events = auth_event_repo::find_recent_by_user_id(pool, user_id, limit).await?;
+
+    Ok(events.into_iter().map(AuthEventResponse::from).collect())
+}
+
+/// Delete authentication events older than the retention period
+///
+/// # Returns
+/// * `Result<usize, ApiError>` - Number of purged events
+pub async fn purge_expired(pool: &DbPool, retention_days: i64) -> Result<usize, ApiError> {
+    let cutoff = Utc::now() - Duration::days(retention_days);
+    let purged = auth_event_repo::delete_older_than(pool, cutoff).await?;
+
+    if purged > 0 {
+        tracing::info!(
+            "Purged {} auth events older than {} days",
+            purged,
+            retention_days
+        );
+    }
+
+    Ok(purged)
+}
+
+/// Spawn a background task that periodically purges expired auth events
+///
+/// Does nothing when retention is disabled (`retention_days == 0`).
+pub fn spawn_retention_purge(pool: DbPool, config: AuthEventConfig) {
+    if config.retention_days == 0 {
+        tracing::info!("Auth event retention disabled - events are kept indefinitely");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge_expired(&pool, config.retention_days).await {
+                tracing::error!("Auth event retention purge failed: {}", e);
+            }
+        }
+    });
+}
@@ -1,23 +1,46 @@
+use chrono::{Duration, Utc};
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    auth::{jwt, password},
-    config::JwtConfig,
+    auth::{jwt, password, refresh_token, reset_token},
+    config::{JwtConfig, OnboardingConfig},
     db::DbPool,
     errors::ApiError,
-    models::user::{AuthResponse, CreateUserRequest, LoginRequest, NewUser, UserResponse},
-    repositories::user,
+    models::{
+        account::NewAccount,
+        category::{self, NewCategory},
+        password_reset_token::{
+            ForgotPasswordRequest, NewPasswordResetToken, ResetPasswordRequest,
+        },
+        refresh_token::{NewRefreshToken, RefreshTokenRequest},
+        user::{
+            AuthResponse, ChangePasswordRequest, CreateUserRequest, LoginRequest, LoginResponse,
+            NewUser, RegistrationPreferences, TwoFactorChallenge, User, UserResponse,
+        },
+    },
+    repositories::{self, user},
+    services::{email_service::EmailSender, two_factor_service},
+    types::AccountType,
 };
 
+/// How long a password reset token can be used for
+const RESET_TOKEN_TTL_HOURS: i64 = 1;
+
 /// Register a new user
 ///
+/// Starter data (a cash account and default categories) is created in the same
+/// DB transaction as the user, as chosen in the request's preferences or, where
+/// they are omitted, by the onboarding configuration.
+///
 /// # Arguments
 /// * `pool` - Database connection pool
 /// * `config` - JWT configuration
+/// * `onboarding` - Default starter data for new users
 /// * `request` - User registration request
 ///
 /// # Returns
-/// * `Result<AuthResponse, ApiError>` - Auth response with user and token
+/// * `Result<AuthResponse, ApiError>` - Auth response with user, access token and refresh token
 ///
 /// # Errors
 /// - Validation errors if request data is invalid
@@ -26,6 +49,7 @@ use crate::{
 pub async fn register(
     pool: &DbPool,
     config: &JwtConfig,
+    onboarding: &OnboardingConfig,
     request: CreateUserRequest,
 ) -> Result<AuthResponse, ApiError> {
     // Validate request
@@ -75,38 +99,81 @@ pub async fn register(
         name: request.name,
     };
 
-    let user = user::create_user(pool, new_user).await?;
+    let preferences = request.preferences.unwrap_or_default();
+    let onboarding = onboarding.clone();
+    let user = user::create_user_with_starter_data(pool, new_user, move |user_id| {
+        starter_data(user_id, &preferences, &onboarding)
+    })
+    .await?;
 
     tracing::info!("User registered successfully: {}", user.id);
 
-    // Generate JWT token
-    let token = jwt::generate_token(&user, config)?;
+    start_session(pool, config, user).await
+}
 
-    Ok(AuthResponse {
-        token,
-        user: UserResponse::from(user),
-    })
+/// Build the starter accounts and categories for a new user
+fn starter_data(
+    user_id: Uuid,
+    preferences: &RegistrationPreferences,
+    onboarding: &OnboardingConfig,
+) -> (Vec<NewAccount>, Vec<NewCategory>) {
+    let mut accounts = Vec::new();
+    if preferences
+        .create_cash_account
+        .unwrap_or(onboarding.create_cash_account)
+    {
+        accounts.push(NewAccount {
+            user_id,
+            name: onboarding.cash_account_name.clone(),
+            account_type: AccountType::Cash,
+            currency: preferences
+                .default_currency
+                .unwrap_or(onboarding.default_currency),
+            notes: None,
+            // Starts empty, so the first expense would otherwise be rejected
+            allow_overdraft: true,
+            overdraft_limit: None,
+            credit_limit: None,
+            interest_rate: None,
+            original_principal: None,
+        });
+    }
+
+    let categories = if preferences
+        .create_default_categories
+        .unwrap_or(onboarding.create_default_categories)
+    {
+        category::default_set(user_id)
+    } else {
+        Vec::new()
+    };
+
+    (accounts, categories)
 }
 
 /// Login a user
 ///
+/// Users with two-factor authentication enabled also need a code: without one,
+/// a correct password gets a challenge instead of tokens.
+///
 /// # Arguments
 /// * `pool` - Database connection pool
 /// * `config` - JWT configuration
 /// * `request` - Login request
 ///
 /// # Returns
-/// * `Result<AuthResponse, ApiError>` - Auth response with user and token
+/// * `Result<LoginResponse, ApiError>` - Auth response with user, access token and
+///   refresh token, or a two-factor challenge
 ///
 /// # Errors
 /// - Validation errors if request data is invalid
-/// - Unauthorized errors if credentials are invalid
+/// - Unauthorized errors if credentials or the two-factor code are invalid
 /// - Internal errors for database failures
 pub async fn login(
     pool: &DbPool,
     config: &JwtConfig,
     request: LoginRequest,
-) -> Result<AuthResponse, ApiError> {
+) -> Result<LoginResponse, ApiError> {
     // Validate request
     request.validate().map_err(|e| {
         tracing::warn!("Validation error during login: {}", e);
@@ -134,17 +201,364 @@ pub async fn login(
         ));
     }
 
+    if user.two_factor_enabled {
+        let Some(code) = request.two_factor_code else {
+            tracing::info!("Two-factor code required for user: {}", user.id);
+            return Ok(LoginResponse::TwoFactorRequired(TwoFactorChallenge {
+                requires_2fa: true,
+            }));
+        };
+        if !two_factor_service::verify_login_code(pool, &user, &code).await? {
+            tracing::warn!("Invalid two-factor code for user: {}", user.id);
+            return Err(ApiError::Unauthorized(
+                "Invalid two-factor code".to_string(),
+            ));
+        }
+    }
+
     tracing::info!("User logged in successfully: {}", user.id);
 
+    start_session(pool, config, user)
+        .await
+        .map(LoginResponse::Authenticated)
+}
+
+/// Exchange a refresh token for a new access token
+///
+/// The refresh token is rotated: it is revoked and the response carries its
+/// replacement, from the same family. A revoked token being used again means
+/// it has leaked (or the client retried with a stale token), so the whole
+/// family is revoked and the user has to log in again.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `config` - JWT configuration
+/// * `request` - Refresh request
+///
+/// # Returns
+/// * `Result<AuthResponse, ApiError>` - Auth response with user and new tokens
+///
+/// # Errors
+/// - Unauthorized errors if the token is unknown, expired or revoked
+/// - Internal errors for database failures
+pub async fn refresh(
+    pool: &DbPool,
+    config: &JwtConfig,
+    request: RefreshTokenRequest,
+) -> Result<AuthResponse, ApiError> {
+    let invalid = || ApiError::Unauthorized("Invalid or expired refresh token".to_string());
+
+    let token_hash = refresh_token::hash_refresh_token(&request.refresh_token);
+    let stored = repositories::refresh_token::find_by_hash(pool, token_hash)
+        .await?
+        .ok_or_else(|| {
+            tracing::warn!("Refresh attempt with unknown token");
+            invalid()
+        })?;
+
+    if stored.revoked {
+        revoke_reused_family(pool, stored.family_id, stored.user_id).await?;
+        return Err(invalid());
+    }
+    if stored.expires_at <= Utc::now() {
+        tracing::warn!(
+            "Refresh attempt with expired token for user {}",
+            stored.user_id
+        );
+        return Err(invalid());
+    }
+
+    let user = user::find_by_id(pool, stored.user_id).await?;
+
+    let (token, new_token) = new_refresh_token(config, user.id, stored.family_id);
+    if repositories::refresh_token::rotate_token(pool, stored.id, new_token)
+        .await?
+        .is_none()
+    {
+        // Revoked by a concurrent refresh with the same token
+        revoke_reused_family(pool, stored.family_id, stored.user_id).await?;
+        return Err(invalid());
+    }
+
+    tracing::info!("Refreshed access token for user {}", user.id);
+
+    Ok(AuthResponse {
+        token: jwt::generate_session_token(&user, config, stored.family_id)?,
+        refresh_token: token,
+        user: UserResponse::from(user),
+    })
+}
+
+/// End a session by revoking its refresh token family
+///
+/// Access tokens are stateless and stay valid until they expire, but once the
+/// family is revoked the session's refresh token can no longer be exchanged.
+/// Tokens issued without a session (API keys, or JWTs from before sessions
+/// were tracked) have nothing to revoke.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `user_id` - User ID from the token
+/// * `session_id` - Refresh token family the access token was issued with
+///
+/// # Errors
+/// - Internal errors for database failures
+pub async fn logout(
+    pool: &DbPool,
+    user_id: Uuid,
+    session_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let Some(family_id) = session_id else {
+        return Ok(());
+    };
+
+    let revoked = repositories::refresh_token::revoke_family(pool, family_id).await?;
+    tracing::info!(
+        "Logout for user {} revoked {} refresh tokens",
+        user_id,
+        revoked
+    );
+    Ok(())
+}
+
+/// Start a password reset for the account with the request's email
+///
+/// Succeeds whether or not an account has the email, so callers can't use it
+/// to find out which emails are registered. For an existing account a
+/// single-use token, valid for an hour, is stored hashed and emailed.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `email_sender` - Transport for the reset email
+/// * `request` - Forgot password request
+///
+/// # Errors
+/// - Validation errors if the email is malformed
+/// - Internal errors for database failures
+pub async fn forgot_password(
+    pool: &DbPool,
+    email_sender: &dyn EmailSender,
+    request: ForgotPasswordRequest,
+) -> Result<(), ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Validation error during forgot password: {}", e);
+        ApiError::Validation(format!("Invalid forgot password data: {}", e))
+    })?;
+
+    let user = match user::find_by_email(pool, &request.email).await {
+        Ok(user) => user,
+        Err(ApiError::Database(diesel::result::Error::NotFound)) => {
+            tracing::info!("Password reset requested for unknown email");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let token = reset_token::generate_reset_token();
+    repositories::password_reset_token::create_token(
+        pool,
+        NewPasswordResetToken {
+            user_id: user.id,
+            token_hash: reset_token::hash_reset_token(&token),
+            expires_at: Utc::now() + Duration::hours(RESET_TOKEN_TTL_HOURS),
+        },
+    )
+    .await?;
+
+    tracing::info!("Issued password reset token for user {}", user.id);
+
+    // A failed send must look the same as an unknown email to the caller
+    if let Err(e) = email_sender.send_password_reset(&user.email, &token).await {
+        tracing::error!(
+            "Failed to send password reset email for user {}: {}",
+            user.id,
+            e
+        );
+    }
+
+    Ok(())
+}
+
+/// Set a new password using a password reset token
+///
+/// The token is used up together with every other outstanding reset token of
+/// the user, and every refresh token of the user is revoked so existing
+/// sessions have to log in again with the new password.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `request` - Reset password request
+///
+/// # Returns
+/// * `Result<Uuid, ApiError>` - ID of the user whose password was reset
+///
+/// # Errors
+/// - Validation errors if the new password doesn't meet the registration rules
+/// - BadRequest if the token is unknown, expired or already used
+/// - Internal errors for database or hashing failures
+pub async fn reset_password(
+    pool: &DbPool,
+    request: ResetPasswordRequest,
+) -> Result<Uuid, ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Validation error during password reset: {}", e);
+        ApiError::Validation(format!("Invalid password reset data: {}", e))
+    })?;
+
+    let invalid = || ApiError::BadRequest("Invalid or expired password reset token".to_string());
+
+    let token_hash = reset_token::hash_reset_token(&request.token);
+    let stored = repositories::password_reset_token::find_by_hash(pool, token_hash)
+        .await?
+        .ok_or_else(|| {
+            tracing::warn!("Password reset attempt with unknown token");
+            invalid()
+        })?;
+
+    if stored.used_at.is_some() || stored.expires_at <= Utc::now() {
+        tracing::warn!(
+            "Password reset attempt with used or expired token for user {}",
+            stored.user_id
+        );
+        return Err(invalid());
+    }
+
+    let password_hash = password::hash_password(&request.new_password)?;
+    if !repositories::password_reset_token::reset_password(
+        pool,
+        stored.id,
+        stored.user_id,
+        password_hash,
+    )
+    .await?
+    {
+        // Used by a concurrent reset with the same token
+        return Err(invalid());
+    }
+
+    tracing::info!("Password reset for user {}", stored.user_id);
+
+    Ok(stored.user_id)
+}
+
+/// Change the password of a logged-in user
+///
+/// Every refresh token of the user is revoked except those from the session
+/// of `request.refresh_token`, so other sessions have to log in again.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `user_id` - User ID from the JWT token
+/// * `request` - Change password request
+///
+/// # Errors
+/// - Validation errors if the new password doesn't meet the registration rules
+///   or is the same as the current one
+/// - Unauthorized if the current password is wrong
+/// - Internal errors for database or hashing failures
+pub async fn change_password(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: ChangePasswordRequest,
+) -> Result<(), ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Validation error during password change: {}", e);
+        ApiError::Validation(format!("Invalid change password data: {}", e))
+    })?;
+
+    let user = user::find_by_id(pool, user_id).await?;
+
+    if !password::verify_password(&request.current_password, &user.password_hash)? {
+        tracing::warn!(
+            "Password change with wrong current password for user {}",
+            user_id
+        );
+        return Err(ApiError::Unauthorized(
+            "Current password is incorrect".to_string(),
+        ));
+    }
+    if request.new_password == request.current_password {
+        return Err(ApiError::Validation(
+            "New password must be different from the current password".to_string(),
+        ));
+    }
+
+    // Only a live token of this user can keep its session
+    let keep_family = match request.refresh_token {
+        Some(token) => {
+            let token_hash = refresh_token::hash_refresh_token(&token);
+            repositories::refresh_token::find_by_hash(pool, token_hash)
+                .await?
+                .filter(|stored| stored.user_id == user_id && !stored.revoked)
+                .map(|stored| stored.family_id)
+        }
+        None => None,
+    };
+
+    let password_hash = password::hash_password(&request.new_password)?;
+    let revoked = user::change_password(pool, user_id, password_hash, keep_family).await?;
+
+    tracing::info!(
+        "Password changed for user {}, revoked {} refresh tokens",
+        user_id,
+        revoked
+    );
+
+    Ok(())
+}
+
+/// Issue an access token and a refresh token starting a new family
+async fn start_session(
+    pool: &DbPool,
+    config: &JwtConfig,
+    user: User,
+) -> Result<AuthResponse, ApiError> {
+    let family_id = Uuid::new_v4();
+
     // Generate JWT token
-    let token = jwt::generate_token(&user, config)?;
+    let token = jwt::generate_session_token(&user, config, family_id)?;
+
+    let (refresh_token, new_token) = new_refresh_token(config, user.id, family_id);
+    repositories::refresh_token::create_token(pool, new_token).await?;
 
     Ok(AuthResponse {
         token,
+        refresh_token,
         user: UserResponse::from(user),
     })
 }
 
+/// Generate a refresh token, returning it with the row storing its hash
+fn new_refresh_token(
+    config: &JwtConfig,
+    user_id: Uuid,
+    family_id: Uuid,
+) -> (String, NewRefreshToken) {
+    let token = refresh_token::generate_refresh_token();
+    let new_token = NewRefreshToken {
+        user_id,
+        family_id,
+        token_hash: refresh_token::hash_refresh_token(&token),
+        expires_at: Utc::now() + Duration::days(config.refresh_expiration_days),
+    };
+    (token, new_token)
+}
+
+/// Revoke a family of refresh tokens after one of its revoked tokens was reused
+async fn revoke_reused_family(
+    pool: &DbPool,
+    family_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), ApiError> {
+    let revoked = repositories::refresh_token::revoke_family(pool, family_id).await?;
+    tracing::warn!(
+        "Reuse of revoked refresh token for user {}, revoked {} tokens of its family",
+        user_id,
+        revoked
+    );
+    Ok(())
+}
+
 /// Get current user information
 ///
 /// # Arguments
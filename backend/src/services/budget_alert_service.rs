@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        BudgetAlert, BudgetAlertQuery, BudgetAlertResponse, BudgetStatus, NewBudgetAlert,
+        NotificationEventType,
+    },
+    repositories,
+    services::{
+        event_service::{ChangeEvent, EventBus},
+        notification_service::{NotificationMessage, Notifier},
+    },
+};
+
+/// Tolerance when comparing the share of the limit spent with a threshold, so
+/// spending exactly at a threshold counts as crossing it
+const THRESHOLD_EPSILON: f64 = 1e-9;
+
+/// Record the thresholds crossed by freshly computed budget statuses
+///
+/// Each threshold raises one alert per budget range: crossings that were
+/// already recorded are skipped, so this may run on every status computation.
+/// Budgets whose alerts are muted or snoozed raise none. Each new alert is
+/// published as a [`ChangeEvent::BudgetAlertFired`] and sent to the user
+/// through the channels they enabled for budget alerts.
+///
+/// `pool` must be the primary, as this writes.
+pub async fn record_crossings(
+    pool: &DbPool,
+    events: &EventBus,
+    notifier: &Notifier,
+    user_id: Uuid,
+    statuses: &[BudgetStatus],
+) -> Result<(), ApiError> {
+    let statuses: Vec<&BudgetStatus> = statuses
+        .iter()
+        .filter(|status| !status.alerts_suppressed)
+        .collect();
+    if statuses.is_empty() {
+        return Ok(());
+    }
+
+    let budgets: HashMap<Uuid, (String, Vec<f64>)> =
+        repositories::budget::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|budget| (budget.id, (budget.name, budget.alert_thresholds)))
+            .collect();
+
+    let mut alerts = Vec::new();
+    for status in statuses {
+        let Some((_, budget_thresholds)) = budgets.get(&status.budget_id) else {
+            continue;
+        };
+        let used = status.percentage_used / 100.0;
+        for threshold in budget_thresholds {
+            if used + THRESHOLD_EPSILON >= *threshold {
+                alerts.push(NewBudgetAlert {
+                    user_id,
+                    budget_id: status.budget_id,
+                    budget_range_id: status.range_id,
+                    threshold: *threshold,
+                    percentage_used: status.percentage_used,
+                });
+            }
+        }
+    }
+
+    let created = repositories::budget_alert::create_alerts(pool, alerts).await?;
+    if !created.is_empty() {
+        tracing::info!(
+            "Recorded {} budget alerts for user {}",
+            created.len(),
+            user_id
+        );
+    }
+    for alert in created {
+        events.publish(
+            user_id,
+            ChangeEvent::BudgetAlertFired {
+                budget_id: alert.budget_id,
+                alert_id: alert.id,
+            },
+        );
+        let budget_name = budgets
+            .get(&alert.budget_id)
+            .map(|(name, _)| name.as_str())
+            .unwrap_or_default();
+        notifier.dispatch(user_id, alert_notification(&alert, budget_name));
+    }
+
+    Ok(())
+}
+
+/// The notification sent when a budget alert fires
+fn alert_notification(alert: &BudgetAlert, budget_name: &str) -> NotificationMessage {
+    NotificationMessage {
+        event_type: NotificationEventType::BudgetAlert,
+        title: format!(
+            "{} reached {:.0}% of its limit",
+            budget_name,
+            alert.threshold * 100.0
+        ),
+        body: format!(
+            "Spending in budget {} is at {:.1}% of its limit, crossing the {:.0}% alert threshold.",
+            budget_name,
+            alert.percentage_used,
+            alert.threshold * 100.0
+        ),
+        data: serde_json::json!({
+            "alert_id": alert.id,
+            "budget_id": alert.budget_id,
+            "budget_range_id": alert.budget_range_id,
+            "threshold": alert.threshold,
+            "percentage_used": alert.percentage_used,
+        }),
+    }
+}
+
+/// List a user's budget alerts, newest first
+pub async fn list_alerts(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: BudgetAlertQuery,
+) -> Result<Vec<BudgetAlertResponse>, ApiError> {
+    let alerts = repositories::budget_alert::list_by_user(pool, user_id, query.unread).await?;
+
+    Ok(alerts
+        .into_iter()
+        .map(|(alert, budget_name)| BudgetAlertResponse::new(alert, budget_name))
+        .collect())
+}
+
+/// Mark a budget alert read
+pub async fn acknowledge_alert(
+    pool: &DbPool,
+    alert_id: Uuid,
+    user_id: Uuid,
+) -> Result<BudgetAlertResponse, ApiError> {
+    let (alert, _) = repositories::budget_alert::find_by_id(pool, alert_id).await?;
+    if alert.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to acknowledge budget alert {} owned by {}",
+            user_id,
+            alert_id,
+            alert.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Budget alert does not belong to user".to_string(),
+        ));
+    }
+
+    repositories::budget_alert::mark_read(pool, alert_id).await?;
+
+    let (alert, budget_name) = repositories::budget_alert::find_by_id(pool, alert_id).await?;
+    Ok(BudgetAlertResponse::new(alert, budget_name))
+}
@@ -1,5 +1,6 @@
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
 use std::str::FromStr;
 use uuid::Uuid;
 use validator::Validate;
@@ -8,23 +9,15 @@ use crate::{
     DbPool,
     errors::ApiError,
     models::{
-        BudgetRangeResponse, BudgetResponse, CreateBudgetRangeRequest, CreateBudgetRequest,
-        NewBudget, NewBudgetRange, TransactionFilter, UpdateBudgetRequest,
+        Budget, BudgetListQuery, BudgetRange, BudgetRangeResponse, BudgetResponse, BudgetStatus,
+        CreateBudgetRangeRequest, CreateBudgetRequest, MuteBudgetRequest, NewBudget,
+        NewBudgetRange, Pagination, SnoozeBudgetRequest, SyncQuery, UpdateBudgetRequest,
     },
     repositories,
-    services::exchange_rate_service::ExchangeRateService,
+    services::exchange_rate_service::{ExchangeRateService, PRIMARY_CURRENCY},
+    types::CurrencyCode,
 };
 
-/// Budget status information
-#[derive(Debug, serde::Serialize)]
-pub struct BudgetStatus {
-    pub budget_id: Uuid,
-    pub current_spending: String,
-    pub limit_amount: String,
-    pub percentage_used: f64,
-    pub is_over_budget: bool,
-}
-
 /// Create a new budget
 pub async fn create_budget(
     pool: &DbPool,
@@ -42,6 +35,7 @@ pub async fn create_budget(
         user_id,
         name: request.name.clone(),
         filters: request.filters.clone(),
+        alert_thresholds: normalize_thresholds(request.alert_thresholds),
     };
 
     let budget = repositories::budget::create_budget(pool, user_id, new_budget).await?;
@@ -51,6 +45,13 @@ pub async fn create_budget(
     Ok(budget.into())
 }
 
+/// Sort alert thresholds, dropping duplicates
+fn normalize_thresholds(mut thresholds: Vec<f64>) -> Vec<f64> {
+    thresholds.sort_by(f64::total_cmp);
+    thresholds.dedup();
+    thresholds
+}
+
 /// Get a budget with current spending status
 pub async fn get_budget(
     pool: &DbPool,
@@ -76,11 +77,53 @@ pub async fn get_budget(
     Ok(budget.into())
 }
 
+/// Count a user's budgets, optionally only those modified at or after `since`
+pub async fn count_budgets(
+    pool: &DbPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, ApiError> {
+    repositories::budget::count_by_user(pool, user_id, since).await
+}
+
 /// List all budgets for a user
-pub async fn list_budgets(pool: &DbPool, user_id: Uuid) -> Result<Vec<BudgetResponse>, ApiError> {
-    let budgets = repositories::budget::list_by_user(pool, user_id).await?;
+///
+/// When `query.updated_since` is set, only budgets modified since then are returned.
+/// With `list_query.include_status`, each budget carries its current-period status.
+/// With `pagination`, only that page is loaded.
+pub async fn list_budgets(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: SyncQuery,
+    list_query: BudgetListQuery,
+    pagination: Option<Pagination>,
+) -> Result<Vec<BudgetResponse>, ApiError> {
+    let budgets = match (pagination, query.updated_since) {
+        (Some(pagination), since) => {
+            repositories::budget::list_page_by_user(pool, user_id, since, pagination).await?
+        }
+        (None, Some(since)) => {
+            repositories::budget::list_updated_since(pool, user_id, since).await?
+        }
+        (None, None) => repositories::budget::list_by_user(pool, user_id).await?,
+    };
+
+    let mut statuses = if list_query.include_status {
+        calculate_budget_statuses(pool, user_id, &budgets, Utc::now().date_naive()).await?
+    } else {
+        HashMap::new()
+    };
 
-    let responses = budgets.into_iter().map(|budget| budget.into()).collect();
+    let responses = budgets
+        .into_iter()
+        .map(|budget| {
+            let status = statuses.remove(&budget.id);
+            BudgetResponse {
+                status,
+                ..budget.into()
+            }
+        })
+        .collect();
 
     Ok(responses)
 }
@@ -116,16 +159,97 @@ pub async fn update_budget(
     let updates = crate::models::UpdateBudget {
         name: request.name,
         filters: request.filters,
+        alert_thresholds: request.alert_thresholds.map(normalize_thresholds),
+        expected_version: request.version,
     };
 
     // Update budget
-    let updated = repositories::budget::update_budget(pool, budget_id, updates).await?;
+    let updated = match repositories::budget::update_budget(pool, budget_id, updates).await {
+        Err(ApiError::VersionConflict(None)) => {
+            let current = get_budget(pool, budget_id, user_id).await?;
+            return Err(ApiError::version_conflict(&current));
+        }
+        result => result?,
+    };
 
     tracing::info!("Updated budget {} for user {}", budget_id, user_id);
 
     Ok(updated.into())
 }
 
+/// Mute or unmute a budget's alerts
+pub async fn mute_budget(
+    pool: &DbPool,
+    budget_id: Uuid,
+    user_id: Uuid,
+    request: MuteBudgetRequest,
+) -> Result<BudgetResponse, ApiError> {
+    let budget = repositories::budget::find_by_id(pool, budget_id).await?;
+    if budget.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to mute budget {} owned by {}",
+            user_id,
+            budget_id,
+            budget.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Budget does not belong to user".to_string(),
+        ));
+    }
+
+    let updated = repositories::budget::set_alerts_muted(pool, budget_id, request.muted).await?;
+
+    tracing::info!(
+        "Set alerts of budget {} to {} for user {}",
+        budget_id,
+        if request.muted { "muted" } else { "unmuted" },
+        user_id
+    );
+
+    Ok(updated.into())
+}
+
+/// Snooze a budget's alerts until a future time, or end the snooze
+///
+/// The snooze expires on its own once `until` has passed.
+pub async fn snooze_budget(
+    pool: &DbPool,
+    budget_id: Uuid,
+    user_id: Uuid,
+    request: SnoozeBudgetRequest,
+) -> Result<BudgetResponse, ApiError> {
+    if request.until.is_some_and(|until| until <= Utc::now()) {
+        return Err(ApiError::Validation(
+            "Snooze end must be in the future".to_string(),
+        ));
+    }
+
+    let budget = repositories::budget::find_by_id(pool, budget_id).await?;
+    if budget.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to snooze budget {} owned by {}",
+            user_id,
+            budget_id,
+            budget.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Budget does not belong to user".to_string(),
+        ));
+    }
+
+    let updated =
+        repositories::budget::set_alerts_snoozed_until(pool, budget_id, request.until).await?;
+
+    tracing::info!(
+        "Snoozed alerts of budget {} until {:?} for user {}",
+        budget_id,
+        request.until,
+        user_id
+    );
+
+    Ok(updated.into())
+}
+
 /// Delete a budget
 pub async fn delete_budget(pool: &DbPool, budget_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
     // Fetch and verify ownership
@@ -208,11 +332,15 @@ pub async fn add_range(
     Ok(range.into())
 }
 
-/// Calculate budget status for current period
-pub async fn calculate_budget_status(
+/// Compute a budget's status for the range containing `as_of`
+///
+/// Returns 404 if none of the budget's ranges covers `as_of`, and 409 if the
+/// budget was deactivated when its category was deleted.
+pub async fn compute_status(
     pool: &DbPool,
     budget_id: Uuid,
     user_id: Uuid,
+    as_of: NaiveDate,
 ) -> Result<BudgetStatus, ApiError> {
     // Verify budget ownership
     let budget = repositories::budget::find_by_id(pool, budget_id).await?;
@@ -221,90 +349,142 @@ pub async fn calculate_budget_status(
             "Budget does not belong to user".to_string(),
         ));
     }
+    if budget.deactivated_at.is_some() {
+        return Err(ApiError::Conflict(
+            "Budget is deactivated; update its filters to reactivate it".to_string(),
+        ));
+    }
 
-    // Get active range for today
-    let today = Utc::now().date_naive();
-    let range = repositories::budget::get_active_range(pool, budget_id, today).await?;
-
-    let range = match range {
-        Some(r) => r,
-        None => {
-            return Err(ApiError::NotFound(
-                "No active budget range for current date".to_string(),
-            ));
-        }
-    };
+    calculate_budget_statuses(pool, user_id, std::slice::from_ref(&budget), as_of)
+        .await?
+        .remove(&budget_id)
+        .ok_or_else(|| ApiError::NotFound(format!("No budget range covers {}", as_of)))
+}
 
-    // Parse budget filters to create transaction filter
-    let mut filter = TransactionFilter {
-        account_id: None,
-        category_id: None,
-        start_date: Some(range.start_date.and_hms_opt(0, 0, 0).unwrap().and_utc()), // Start of day (00:00:00)
-        end_date: range
-            .end_date
-            .map(|d| d.and_hms_opt(23, 59, 59).unwrap().and_utc()), // End of day (23:59:59) if set
-        min_amount: None,
-        max_amount: None,
-        search: None,
-        limit: None,
-        offset: None,
+/// Calculate the status of several budgets of a user for the ranges containing `as_of`
+///
+/// Spending is loaded with one grouped query covering all of the budgets' active
+/// ranges and converted to the primary currency once per account currency.
+/// Deactivated budgets and budgets without a range active on `as_of` have no
+/// status in the returned map.
+pub async fn calculate_budget_statuses(
+    pool: &DbPool,
+    user_id: Uuid,
+    budgets: &[Budget],
+    as_of: NaiveDate,
+) -> Result<HashMap<Uuid, BudgetStatus>, ApiError> {
+    let budgets: Vec<&Budget> = budgets
+        .iter()
+        .filter(|budget| budget.deactivated_at.is_none())
+        .collect();
+    let budget_ids = budgets.iter().map(|budget| budget.id).collect();
+    let ranges: HashMap<Uuid, BudgetRange> =
+        repositories::budget::list_active_ranges(pool, budget_ids, as_of)
+            .await?
+            .into_iter()
+            .map(|range| (range.budget_id, range))
+            .collect();
+
+    // One query spanning every active range; an open-ended range spans all later days
+    let Some(start) = ranges.values().map(|range| range.start_date).min() else {
+        return Ok(HashMap::new());
     };
-
-    // Apply budget filters from JSON
-    if let Some(account_id) = budget.filters.get("account_id").and_then(|v| v.as_str()) {
-        if let Ok(uuid) = Uuid::parse_str(account_id) {
-            filter.account_id = Some(uuid);
+    let end = ranges
+        .values()
+        .map(|range| range.end_date)
+        .collect::<Option<Vec<_>>>()
+        .and_then(|ends| ends.into_iter().max());
+    let expenses =
+        repositories::transaction::sum_expenses_by_day(pool, user_id, start, end).await?;
+
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+
+    // Only needed once spending in another currency is converted
+    let mut exchange_service = None;
+    let now = Utc::now();
+
+    let mut statuses = HashMap::new();
+    for budget in budgets {
+        let Some(range) = ranges.get(&budget.id) else {
+            continue;
+        };
+
+        // Apply budget filters from JSON
+        let filter_id = |key: &str| {
+            budget
+                .filters
+                .get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|id| Uuid::parse_str(id).ok())
+        };
+        let account_id = filter_id("account_id");
+        let category_id = filter_id("category_id");
+
+        // Sum spending per account currency (expense totals are negative)
+        let mut spending_by_currency: HashMap<CurrencyCode, BigDecimal> = HashMap::new();
+        for (expense_account_id, expense_category_id, day, total) in &expenses {
+            if *day < range.start_date
+                || range.end_date.is_some_and(|end_date| *day > end_date)
+                || account_id.is_some_and(|id| id != *expense_account_id)
+                || category_id.is_some_and(|id| Some(id) != *expense_category_id)
+            {
+                continue;
+            }
+            let Some(currency) = account_currencies.get(expense_account_id) else {
+                continue;
+            };
+            *spending_by_currency
+                .entry(*currency)
+                .or_insert_with(|| BigDecimal::from(0)) -= total;
         }
-    }
-    if let Some(category_id) = budget.filters.get("category_id").and_then(|v| v.as_str()) {
-        if let Ok(uuid) = Uuid::parse_str(category_id) {
-            filter.category_id = Some(uuid);
-        }
-    }
 
-    // Get transactions matching the filter
-    let transactions = repositories::transaction::list_transactions(pool, user_id, filter).await?;
-
-    // Initialize exchange rate service for currency conversion
-    let exchange_service = ExchangeRateService::new()?;
-
-    // Sum spending (only negative amounts for expenses), converting to primary currency
-    let mut current_spending = BigDecimal::from(0);
+        // Convert spending to primary currency
+        let mut spending_abs = BigDecimal::from(0);
+        for (currency, spending) in spending_by_currency {
+            if currency == PRIMARY_CURRENCY {
+                spending_abs += spending;
+                continue;
+            }
+            let service = match &exchange_service {
+                Some(service) => service,
+                None => exchange_service
+                    .insert(ExchangeRateService::new()?.with_manual_rates(pool.clone(), user_id)),
+            };
+            spending_abs += service
+                .convert_to_primary_currency(&spending, currency)
+                .await?;
+        }
 
-    for transaction in transactions
-        .iter()
-        .filter(|t| t.amount < BigDecimal::from(0))
-    {
-        // Get the account to find its currency
-        let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
-
-        // Convert transaction amount to primary currency
-        let amount_abs = transaction.amount.abs();
-        let converted_amount = exchange_service
-            .convert_to_primary_currency(&amount_abs, account.currency)
-            .await?;
-
-        current_spending += converted_amount;
+        // Calculate percentage
+        let percentage_used = if range.limit_amount > BigDecimal::from(0) {
+            let ratio = &spending_abs / &range.limit_amount;
+            ratio.to_string().parse::<f64>().unwrap_or(0.0) * 100.0
+        } else {
+            0.0
+        };
+
+        let is_over_budget = spending_abs > range.limit_amount;
+
+        statuses.insert(
+            budget.id,
+            BudgetStatus {
+                budget_id: budget.id,
+                range_id: range.id,
+                current_spending: spending_abs.to_string(),
+                limit_amount: range.limit_amount.to_string(),
+                percentage_used,
+                is_over_budget,
+                alerts_suppressed: budget.alerts_suppressed(now),
+                range_start: range.start_date,
+                range_end: range.end_date,
+            },
+        );
     }
 
-    // current_spending is already positive (we used abs() above)
-    let spending_abs = current_spending;
-
-    // Calculate percentage
-    let percentage_used = if range.limit_amount > BigDecimal::from(0) {
-        let ratio = &spending_abs / &range.limit_amount;
-        ratio.to_string().parse::<f64>().unwrap_or(0.0) * 100.0
-    } else {
-        0.0
-    };
-
-    let is_over_budget = spending_abs > range.limit_amount;
-
-    Ok(BudgetStatus {
-        budget_id,
-        current_spending: spending_abs.to_string(),
-        limit_amount: range.limit_amount.to_string(),
-        percentage_used,
-        is_over_budget,
-    })
+    Ok(statuses)
 }
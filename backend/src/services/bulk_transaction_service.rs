@@ -0,0 +1,418 @@
+//! Creating, updating and deleting transactions in one request
+//!
+//! Every item is checked first; if any item is invalid nothing is applied and
+//! the response says which items failed. Otherwise all changes are written in a
+//! single DB transaction (see [`repositories::transaction::apply_bulk`]), and
+//! the resulting balance of each account that transactions are created on or
+//! updated on is checked once against its limit, after all changes.
+//!
+//! Tags are saved in the same DB transaction as their transactions. Splits are
+//! not supported: they are synced to split providers one transaction at a time.
+
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        Account, BulkItemResult, BulkItemStatus, BulkOperation, BulkTransactionRequest,
+        BulkTransactionResponse, BulkUpdateItem, CreateTransactionRequest, NewTransaction,
+        TransactionResponse, UpdateTransaction, tag::normalize_tags,
+    },
+    repositories,
+    services::{account_service, transaction_service},
+};
+
+/// Apply a bulk request, all or none
+///
+/// Returns the response and the IDs of every deleted transaction, including the
+/// other legs of deleted transfers.
+pub async fn apply_bulk(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: BulkTransactionRequest,
+) -> Result<(BulkTransactionResponse, Vec<Uuid>), ApiError> {
+    let mut accounts = HashMap::new();
+    // Skipped until everything is known to be valid
+    let mut results = Vec::new();
+
+    let mut creates = Vec::with_capacity(request.create.len());
+    for (index, item) in request.create.into_iter().enumerate() {
+        match prepare_create(pool, user_id, &mut accounts, item).await {
+            Ok(new_transaction) => {
+                creates.push(new_transaction);
+                results.push(skipped(BulkOperation::Create, index));
+            }
+            Err(e) => results.push(failed(BulkOperation::Create, index, e)?),
+        }
+    }
+
+    // A transaction may only be changed once per request
+    let mut seen = HashSet::new();
+
+    let mut updates = Vec::with_capacity(request.update.len());
+    for (index, item) in request.update.into_iter().enumerate() {
+        let prepared = if seen.insert(item.id) {
+            prepare_update(pool, user_id, &mut accounts, item).await
+        } else {
+            Err(duplicate())
+        };
+        match prepared {
+            Ok(update) => {
+                updates.push(update);
+                results.push(skipped(BulkOperation::Update, index));
+            }
+            Err(e) => results.push(failed(BulkOperation::Update, index, e)?),
+        }
+    }
+
+    let mut deletes = Vec::with_capacity(request.delete.len());
+    for (index, transaction_id) in request.delete.into_iter().enumerate() {
+        let prepared = if seen.insert(transaction_id) {
+            prepare_delete(pool, user_id, transaction_id).await
+        } else {
+            Err(duplicate())
+        };
+        match prepared {
+            Ok(leg_ids) => {
+                deletes.push((transaction_id, leg_ids));
+                results.push(skipped(BulkOperation::Delete, index));
+            }
+            Err(e) => results.push(failed(BulkOperation::Delete, index, e)?),
+        }
+    }
+
+    let invalid = results
+        .iter()
+        .filter(|r| r.status == BulkItemStatus::Failed)
+        .count();
+    if invalid > 0 {
+        tracing::warn!(
+            "Rejected bulk request with {} invalid items for user {}",
+            invalid,
+            user_id
+        );
+        let response = BulkTransactionResponse {
+            success: false,
+            results,
+        };
+        return Ok((response, Vec::new()));
+    }
+
+    let update_count = updates.len();
+    let mut deleted_ids: Vec<Uuid> = deletes
+        .iter()
+        .flat_map(|(_, leg_ids)| leg_ids.iter().copied())
+        .collect();
+    deleted_ids.sort();
+    deleted_ids.dedup();
+
+    let checked_accounts = accounts.clone();
+    let (created, updated) = repositories::transaction::apply_bulk(
+        pool,
+        user_id,
+        creates,
+        updates,
+        deleted_ids.clone(),
+        move |account_id, before, after| match checked_accounts.get(&account_id) {
+            Some(account) => {
+                account_service::project_balance(account, before, &(after - before)).map(|_| ())
+            }
+            // Only touched by deletes, which are never checked
+            None => Ok(()),
+        },
+    )
+    .await?;
+
+    let response_for = |transaction| -> TransactionResponse {
+        let mut response = TransactionResponse::from(transaction);
+        if let Some(account) = accounts.get(&response.account_id) {
+            response.set_currency(account.currency);
+        }
+        response
+    };
+
+    let mut created: Vec<TransactionResponse> = created.into_iter().map(response_for).collect();
+    let mut updated: Vec<TransactionResponse> = updated.into_iter().map(response_for).collect();
+    transaction_service::attach_tags(pool, &mut created).await?;
+    transaction_service::attach_tags(pool, &mut updated).await?;
+
+    let created_count = created.len();
+    let mut results = Vec::with_capacity(results.len());
+    for (index, response) in created.into_iter().enumerate() {
+        results.push(applied(
+            BulkOperation::Create,
+            index,
+            BulkItemStatus::Created,
+            response.id,
+            Some(response),
+        ));
+    }
+    for (index, response) in updated.into_iter().enumerate() {
+        results.push(applied(
+            BulkOperation::Update,
+            index,
+            BulkItemStatus::Updated,
+            response.id,
+            Some(response),
+        ));
+    }
+    for (index, (transaction_id, _)) in deletes.into_iter().enumerate() {
+        results.push(applied(
+            BulkOperation::Delete,
+            index,
+            BulkItemStatus::Deleted,
+            transaction_id,
+            None,
+        ));
+    }
+
+    tracing::info!(
+        "Applied bulk request for user {}: {} created, {} updated, {} deleted",
+        user_id,
+        created_count,
+        update_count,
+        deleted_ids.len()
+    );
+
+    Ok((
+        BulkTransactionResponse {
+            success: true,
+            results,
+        },
+        deleted_ids,
+    ))
+}
+
+/// Check an item to create and convert it for storage
+async fn prepare_create(
+    pool: &DbPool,
+    user_id: Uuid,
+    accounts: &mut HashMap<Uuid, Account>,
+    request: CreateTransactionRequest,
+) -> Result<NewTransaction, ApiError> {
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    if request.splits.is_some()
+        || request.split_equally_with.is_some()
+        || request.split_group_id.is_some()
+        || request.split_strategy.is_some()
+    {
+        return Err(splits_unsupported());
+    }
+    let tags = request
+        .tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(ApiError::Validation)?
+        .unwrap_or_default();
+
+    let account = owned_account(pool, user_id, accounts, request.account_id).await?;
+    if let Some(category_id) = request.category_id {
+        check_category(pool, user_id, category_id).await?;
+    }
+
+    let (original_currency, original_amount, exchange_rate) =
+        transaction_service::original_currency_values(
+            &account,
+            request.original_currency,
+            request.original_amount,
+            request.exchange_rate,
+        )?;
+
+    Ok(NewTransaction {
+        user_id,
+        account_id: account.id,
+        category_id: request.category_id,
+        title: request.title,
+        amount: request.amount.into_decimal(),
+        date: request.date,
+        notes: request.notes,
+        external_id: None,
+        status: request.status.unwrap_or_default(),
+        original_currency,
+        original_amount,
+        exchange_rate,
+        merchant: request.merchant.map(|m| m.trim().to_string()),
+        latitude: request.latitude,
+        longitude: request.longitude,
+        transfer_id: None,
+        reimbursable: request.reimbursable,
+        tags,
+    })
+}
+
+/// Check an item to update and convert it for storage
+async fn prepare_update(
+    pool: &DbPool,
+    user_id: Uuid,
+    accounts: &mut HashMap<Uuid, Account>,
+    item: BulkUpdateItem,
+) -> Result<(Uuid, UpdateTransaction), ApiError> {
+    let request = item.changes;
+    request
+        .validate()
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    if request.splits.is_some() {
+        return Err(splits_unsupported());
+    }
+
+    let transaction = repositories::transaction::find_by_id(pool, item.id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to update transaction {} owned by {}",
+            user_id,
+            item.id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+    if request.version.is_some_and(|v| v != transaction.version) {
+        return Err(ApiError::Conflict(
+            "Transaction was modified since the given version".to_string(),
+        ));
+    }
+
+    transaction_service::check_transfer_leg_update(&transaction, &request)?;
+
+    // The resulting account, whose currency the response is formatted in
+    let account_id = request.account_id.unwrap_or(transaction.account_id);
+    let account = owned_account(pool, user_id, accounts, account_id).await?;
+    if let Some(Some(category_id)) = request.category_id {
+        check_category(pool, user_id, category_id).await?;
+    }
+    transaction_service::check_resulting_conversion(&transaction, &request)?;
+
+    let updates = transaction_service::update_values(&account, request)?;
+    Ok((item.id, updates))
+}
+
+/// Check a transaction to delete and return the IDs to delete with it
+async fn prepare_delete(
+    pool: &DbPool,
+    user_id: Uuid,
+    transaction_id: Uuid,
+) -> Result<Vec<Uuid>, ApiError> {
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to delete transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    match transaction.transfer_id {
+        Some(transfer_id) => {
+            repositories::transaction::list_transfer_leg_ids(pool, transfer_id).await
+        }
+        None => Ok(vec![transaction_id]),
+    }
+}
+
+/// Find one of the user's accounts, remembering it for later items
+async fn owned_account(
+    pool: &DbPool,
+    user_id: Uuid,
+    accounts: &mut HashMap<Uuid, Account>,
+    account_id: Uuid,
+) -> Result<Account, ApiError> {
+    if let Some(account) = accounts.get(&account_id) {
+        return Ok(account.clone());
+    }
+
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+    if account.user_id != user_id {
+        return Err(ApiError::Forbidden(
+            "Account does not belong to user".to_string(),
+        ));
+    }
+    accounts.insert(account_id, account.clone());
+    Ok(account)
+}
+
+/// Check that a category belongs to the user
+async fn check_category(pool: &DbPool, user_id: Uuid, category_id: Uuid) -> Result<(), ApiError> {
+    let category = repositories::category::find_by_id(pool, category_id).await?;
+    if category.user_id != user_id {
+        return Err(ApiError::Forbidden(
+            "Category does not belong to user".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn splits_unsupported() -> ApiError {
+    ApiError::Validation("Splits are not supported in bulk requests".to_string())
+}
+
+fn duplicate() -> ApiError {
+    ApiError::Validation("Transaction appears more than once in the request".to_string())
+}
+
+/// Result of an invalid item
+///
+/// Errors that are not about the item, such as a lost database connection,
+/// fail the whole request instead.
+fn failed(
+    operation: BulkOperation,
+    index: usize,
+    error: ApiError,
+) -> Result<BulkItemResult, ApiError> {
+    let message = match error {
+        ApiError::Database(diesel::result::Error::NotFound) => "Resource not found".to_string(),
+        ApiError::NotFound(_)
+        | ApiError::Forbidden(_)
+        | ApiError::Unauthorized(_)
+        | ApiError::Validation(_)
+        | ApiError::BadRequest(_)
+        | ApiError::Conflict(_) => error.to_string(),
+        error => return Err(error),
+    };
+
+    Ok(BulkItemResult {
+        operation,
+        index,
+        status: BulkItemStatus::Failed,
+        transaction_id: None,
+        transaction: None,
+        error: Some(message),
+    })
+}
+
+/// Result of a valid item that was not applied
+fn skipped(operation: BulkOperation, index: usize) -> BulkItemResult {
+    BulkItemResult {
+        operation,
+        index,
+        status: BulkItemStatus::Skipped,
+        transaction_id: None,
+        transaction: None,
+        error: None,
+    }
+}
+
+/// Result of an applied item
+fn applied(
+    operation: BulkOperation,
+    index: usize,
+    status: BulkItemStatus,
+    transaction_id: Uuid,
+    transaction: Option<TransactionResponse>,
+) -> BulkItemResult {
+    BulkItemResult {
+        operation,
+        index,
+        status,
+        transaction_id: Some(transaction_id),
+        transaction,
+        error: None,
+    }
+}
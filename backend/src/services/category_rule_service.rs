@@ -0,0 +1,275 @@
+//! Category rules
+//!
+//! A category rule assigns its category to transactions whose title matches
+//! its pattern, e.g. anything containing "starbucks" is Coffee. Patterns match
+//! ignoring case, either as text the title contains or starts with, or as a
+//! regular expression. A user's rules are tried from the lowest priority up
+//! (oldest first among equal priorities) and the first matching rule wins.
+//!
+//! Rules categorize new transactions created without a category (see
+//! [`apply_category_rules`]) and can be run over existing uncategorized
+//! transactions with [`apply_to_uncategorized`].
+
+use regex::{Regex, RegexBuilder};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        CategoryRule, CategoryRuleResponse, CreateCategoryRuleRequest, NewCategoryRule,
+        UpdateCategoryRule, UpdateCategoryRuleRequest,
+    },
+    repositories,
+    types::CategoryRuleMatchType,
+};
+
+/// A rule's pattern, ready to be matched against titles
+enum TitleMatcher {
+    /// Lowercased text the title must contain
+    Contains(String),
+    /// Lowercased text the title must start with
+    StartsWith(String),
+    Regex(Regex),
+}
+
+impl TitleMatcher {
+    /// Compile a pattern, rejecting invalid regular expressions
+    fn new(match_type: CategoryRuleMatchType, pattern: &str) -> Result<Self, ApiError> {
+        Ok(match match_type {
+            CategoryRuleMatchType::Contains => Self::Contains(pattern.to_lowercase()),
+            CategoryRuleMatchType::StartsWith => Self::StartsWith(pattern.to_lowercase()),
+            CategoryRuleMatchType::Regex => Self::Regex(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| ApiError::Validation(format!("Invalid regex pattern: {}", e)))?,
+            ),
+        })
+    }
+
+    /// Whether a title matches; `lowercase_title` is the same title lowercased
+    fn matches(&self, title: &str, lowercase_title: &str) -> bool {
+        match self {
+            Self::Contains(text) => lowercase_title.contains(text.as_str()),
+            Self::StartsWith(text) => lowercase_title.starts_with(text.as_str()),
+            Self::Regex(regex) => regex.is_match(title),
+        }
+    }
+}
+
+/// A user's rules compiled in the order they are tried
+///
+/// Loading them once lets many titles be matched without a query per title.
+pub struct CompiledRules(Vec<(Uuid, TitleMatcher)>);
+
+impl CompiledRules {
+    /// Load and compile a user's rules, skipping any that no longer compile
+    pub async fn load(pool: &DbPool, user_id: Uuid) -> Result<Self, ApiError> {
+        let rules = repositories::category_rule::list_by_user(pool, user_id).await?;
+
+        let matchers = rules
+            .into_iter()
+            .filter_map(
+                |rule| match TitleMatcher::new(rule.match_type, &rule.pattern) {
+                    Ok(matcher) => Some((rule.category_id, matcher)),
+                    Err(e) => {
+                        tracing::warn!("Skipping category rule {}: {}", rule.id, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Ok(Self(matchers))
+    }
+
+    /// Category of the first rule matching a title
+    pub fn category_for(&self, title: &str) -> Option<Uuid> {
+        let lowercase_title = title.to_lowercase();
+        self.0
+            .iter()
+            .find(|(_, matcher)| matcher.matches(title, &lowercase_title))
+            .map(|(category_id, _)| *category_id)
+    }
+}
+
+/// Create a category rule
+///
+/// The category must belong to the user and a regex pattern must be valid.
+pub async fn create_rule(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateCategoryRuleRequest,
+) -> Result<CategoryRuleResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Category rule validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+    TitleMatcher::new(request.match_type, &request.pattern)?;
+
+    verify_category(pool, user_id, request.category_id).await?;
+
+    let new_rule = NewCategoryRule {
+        user_id,
+        category_id: request.category_id,
+        match_type: request.match_type,
+        pattern: request.pattern,
+        priority: request.priority,
+    };
+    let rule = repositories::category_rule::create_rule(pool, new_rule).await?;
+
+    tracing::info!("Created category rule {} for user {}", rule.id, user_id);
+
+    Ok(rule.into())
+}
+
+/// List all category rules of a user in the order they are tried
+pub async fn list_rules(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<CategoryRuleResponse>, ApiError> {
+    let rules = repositories::category_rule::list_by_user(pool, user_id).await?;
+
+    Ok(rules.into_iter().map(Into::into).collect())
+}
+
+/// Get a category rule
+pub async fn get_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+) -> Result<CategoryRuleResponse, ApiError> {
+    let rule = find_owned(pool, rule_id, user_id).await?;
+
+    Ok(rule.into())
+}
+
+/// Update a category rule
+pub async fn update_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+    request: UpdateCategoryRuleRequest,
+) -> Result<CategoryRuleResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Category rule validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let rule = find_owned(pool, rule_id, user_id).await?;
+
+    let updates = UpdateCategoryRule {
+        category_id: request.category_id.unwrap_or(rule.category_id),
+        match_type: request.match_type.unwrap_or(rule.match_type),
+        pattern: request.pattern.unwrap_or(rule.pattern),
+        priority: request.priority.unwrap_or(rule.priority),
+    };
+    TitleMatcher::new(updates.match_type, &updates.pattern)?;
+
+    if let Some(category_id) = request.category_id {
+        verify_category(pool, user_id, category_id).await?;
+    }
+
+    let rule = repositories::category_rule::update_rule(pool, rule_id, updates).await?;
+
+    tracing::info!("Updated category rule {} for user {}", rule_id, user_id);
+
+    Ok(rule.into())
+}
+
+/// Delete a category rule
+///
+/// Transactions it already categorized keep their category.
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+    find_owned(pool, rule_id, user_id).await?;
+
+    repositories::category_rule::delete_rule(pool, rule_id).await?;
+
+    tracing::info!("Deleted category rule {} for user {}", rule_id, user_id);
+
+    Ok(())
+}
+
+/// Category of the user's first rule matching a transaction title, if any
+pub async fn apply_category_rules(
+    pool: &DbPool,
+    user_id: Uuid,
+    title: &str,
+) -> Result<Option<Uuid>, ApiError> {
+    let rules = CompiledRules::load(pool, user_id).await?;
+
+    Ok(rules.category_for(title))
+}
+
+/// Run the user's rules over their uncategorized transactions
+///
+/// Each transaction matching a rule is given the category of the first
+/// matching rule; transfers are left alone. Returns the IDs of the
+/// transactions that were updated.
+pub async fn apply_to_uncategorized(pool: &DbPool, user_id: Uuid) -> Result<Vec<Uuid>, ApiError> {
+    let rules = CompiledRules::load(pool, user_id).await?;
+    if rules.0.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let transactions = repositories::transaction::list_uncategorized_titles(pool, user_id).await?;
+    let assignments: Vec<(Uuid, Uuid)> = transactions
+        .into_iter()
+        .filter_map(|(transaction_id, title)| {
+            rules
+                .category_for(&title)
+                .map(|category_id| (transaction_id, category_id))
+        })
+        .collect();
+    if assignments.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let updated = repositories::transaction::assign_categories(pool, assignments).await?;
+
+    tracing::info!(
+        "Categorized {} transactions by rule for user {}",
+        updated.len(),
+        user_id
+    );
+
+    Ok(updated)
+}
+
+/// Find a category rule, checking that it belongs to the user
+async fn find_owned(pool: &DbPool, rule_id: Uuid, user_id: Uuid) -> Result<CategoryRule, ApiError> {
+    let rule = repositories::category_rule::find_by_id(pool, rule_id).await?;
+    if rule.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to access category rule {} owned by {}",
+            user_id,
+            rule_id,
+            rule.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(rule)
+}
+
+/// Check that a category belongs to the user
+async fn verify_category(pool: &DbPool, user_id: Uuid, category_id: Uuid) -> Result<(), ApiError> {
+    let category = repositories::category::find_by_id(pool, category_id).await?;
+    if category.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to use category {} owned by {}",
+            user_id,
+            category_id,
+            category.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Category does not belong to user".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -2,18 +2,21 @@
 //!
 //! This module provides CSV parsing functionality for bank statement imports.
 //! It uses a trait-based design to allow for future extension to other formats (PDF, etc.).
+//! Large uploads can be parsed incrementally with [`CsvRecordStream`] as they arrive,
+//! in the default format or with the columns given by a [`CsvFormat`].
 
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, NaiveDateTime, Utc};
-use csv::Reader;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use csv::{Reader, ReaderBuilder};
 use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::config::ImportConfig;
 use crate::models::{
-    ParsedTransaction,
+    AmountSign, CsvColumnMapping, CsvFormat, CsvMapping, ParsedTransaction,
     parser_error::{ParserError, ValidationError},
 };
+use crate::services::ofx_parser_service::OfxStatementParser;
 use crate::types::CurrencyCode;
 
 /// Generic trait for parsing financial statements
@@ -111,7 +114,10 @@ impl StatementParser for CSVStatementParser {
 impl CSVStatementParser {
     /// Parse a single CSV record into a ParsedTransaction
     ///
-    /// Expected CSV format: id,time,merchant,type,amount,card
+    /// Expected CSV format: id,time,merchant,type,amount,card[,category[,account]]
+    ///
+    /// The optional category and account columns hold names, which are resolved
+    /// to the user's categories and accounts after parsing.
     ///
     /// # Arguments
     ///
@@ -180,6 +186,17 @@ impl CSVStatementParser {
             .trim()
             .to_string();
 
+        // Parse optional category (column 6) and account (column 7) names
+        let optional_name = |column: usize| {
+            record
+                .get(column)
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+        };
+        let category_name = optional_name(6);
+        let account_name = optional_name(7);
+
         // Store original amount before adjustment
         let original_amount_value = amount.clone();
 
@@ -212,6 +229,10 @@ impl CSVStatementParser {
             notes,
             original_currency,
             original_amount,
+            category_name,
+            account_name,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: false,
@@ -282,6 +303,376 @@ impl CSVStatementParser {
     }
 }
 
+/// Fields of the CSV statement format, in column order
+pub const CSV_FIELDS: [&str; 8] = [
+    "id", "time", "merchant", "type", "amount", "card", "category", "account",
+];
+
+/// Line number of a CSV record and the transaction parsed from it
+pub type ParsedRecord = (usize, Result<ParsedTransaction, ParserError>);
+
+/// Incremental CSV statement parser for uploads read in chunks
+///
+/// Chunks are fed with [`CsvRecordStream::push`] as they arrive. Each call
+/// parses the records the chunk completes and keeps only the trailing partial
+/// record, so memory use depends on the chunk and record size rather than the
+/// file size. Records use the same format and line numbers as
+/// [`CSVStatementParser::parse`], but a record that fails to parse is returned
+/// as an error instead of failing the whole file.
+///
+/// A stream created with [`CsvRecordStream::with_format`] reads the columns
+/// its mapping names instead. If the header lacks any of them, no record is
+/// returned and [`CsvRecordStream::header_error`] says which are missing.
+pub struct CsvRecordStream {
+    parser: CSVStatementParser,
+    /// Bytes received but not yet parsed
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been scanned for record ends
+    scanned: usize,
+    in_quotes: bool,
+    /// Whether a quote at the current position opens a quoted field
+    at_field_start: bool,
+    /// Header fields, once the header has been read
+    header: Option<Vec<String>>,
+    /// Format of a file with mapped columns, and their positions once the header has been read
+    format: Option<CsvFormat>,
+    columns: Option<MappedColumns>,
+    header_error: Option<ParserError>,
+    /// Line number of the next record
+    next_line: usize,
+}
+
+impl Default for CsvRecordStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvRecordStream {
+    pub fn new() -> Self {
+        Self {
+            parser: CSVStatementParser,
+            buffer: Vec::new(),
+            scanned: 0,
+            in_quotes: false,
+            at_field_start: true,
+            header: None,
+            format: None,
+            columns: None,
+            header_error: None,
+            next_line: 2, // +2 for header and 0-indexing
+        }
+    }
+
+    /// Stream a file whose columns are given by a mapping
+    pub fn with_format(format: CsvFormat) -> Self {
+        Self {
+            format: Some(format),
+            ..Self::new()
+        }
+    }
+
+    /// Why the header does not fit the stream's mapping, once it has been read
+    pub fn header_error(&self) -> Option<&ParserError> {
+        self.header_error.as_ref()
+    }
+
+    /// Feed the next chunk of the file and return the records it completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<ParsedRecord> {
+        self.buffer.extend_from_slice(chunk);
+
+        // Find the last line break outside a quoted field
+        let mut end = None;
+        for (offset, byte) in self.buffer[self.scanned..].iter().enumerate() {
+            match (self.in_quotes, byte) {
+                (true, b'"') => {
+                    // A quote right after the closing one is an escaped quote
+                    self.in_quotes = false;
+                    self.at_field_start = true;
+                }
+                (true, _) => {}
+                (false, b'"') if self.at_field_start => self.in_quotes = true,
+                (false, b',') => self.at_field_start = true,
+                (false, b'\n') => {
+                    self.at_field_start = true;
+                    end = Some(self.scanned + offset + 1);
+                }
+                (false, _) => self.at_field_start = false,
+            }
+        }
+        self.scanned = self.buffer.len();
+
+        let Some(end) = end else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.buffer.drain(..end).collect();
+        self.scanned -= end;
+        self.parse_records(&complete)
+    }
+
+    /// Number of bytes held for the record that is still being received
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// How the header's columns map to transaction fields, once it has been read
+    ///
+    /// Columns past the last field of the default format, or not named by the
+    /// stream's mapping, are mapped to no field.
+    pub fn column_mapping(&self) -> Option<Vec<CsvColumnMapping>> {
+        let header = self.header.as_ref()?;
+        Some(
+            header
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let field = match &self.format {
+                        Some(format) => format
+                            .mapping
+                            .fields()
+                            .into_iter()
+                            .find(|(_, mapped)| header_matches(name, mapped))
+                            .map(|(field, _)| field),
+                        None => CSV_FIELDS.get(index).copied(),
+                    };
+                    CsvColumnMapping {
+                        index,
+                        header: name.clone(),
+                        field: field.map(str::to_string),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Parse the last record once the whole file has been received
+    pub fn finish(&mut self) -> Vec<ParsedRecord> {
+        let rest = std::mem::take(&mut self.buffer);
+        self.parse_records(&rest)
+    }
+
+    fn parse_records(&mut self, data: &[u8]) -> Vec<ParsedRecord> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(data);
+        let mut parsed = Vec::new();
+
+        for result in reader.records() {
+            if self.header.is_none() {
+                // An unreadable header is reported as the first record
+                match result {
+                    Ok(header) => {
+                        let header: Vec<String> =
+                            header.iter().map(|name| name.trim().to_string()).collect();
+                        if let Some(format) = &self.format {
+                            match MappedColumns::resolve(&format.mapping, &header) {
+                                Ok(columns) => self.columns = Some(columns),
+                                Err(e) => self.header_error = Some(e),
+                            }
+                        }
+                        self.header = Some(header);
+                    }
+                    Err(e) => {
+                        self.header = Some(Vec::new());
+                        parsed.push((
+                            1,
+                            Err(ParserError::CsvError {
+                                line: 1,
+                                error: e.to_string(),
+                            }),
+                        ));
+                    }
+                }
+                continue;
+            }
+            if self.header_error.is_some() {
+                break;
+            }
+
+            let line = self.next_line;
+            self.next_line += 1;
+            let header_len = self.header.as_ref().map(Vec::len);
+            let transaction = match result {
+                Ok(record) if Some(record.len()) != header_len => Err(ParserError::CsvError {
+                    line,
+                    error: format!(
+                        "found record with {} fields, but the header has {} fields",
+                        record.len(),
+                        header_len.unwrap_or_default()
+                    ),
+                }),
+                Ok(record) => match (&self.columns, &self.format) {
+                    (Some(columns), Some(format)) => columns.parse_record(&record, line, format),
+                    _ => self.parser.parse_record(&record, line),
+                },
+                Err(e) => Err(ParserError::CsvError {
+                    line,
+                    error: e.to_string(),
+                }),
+            };
+            parsed.push((line, transaction));
+        }
+
+        parsed
+    }
+}
+
+/// Positions of the columns a [`CsvMapping`] names
+struct MappedColumns {
+    date: usize,
+    description: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    notes: Option<usize>,
+    category: Option<usize>,
+    account: Option<usize>,
+}
+
+impl MappedColumns {
+    /// Find the mapped columns in the header, failing with every missing one
+    fn resolve(mapping: &CsvMapping, header: &[String]) -> Result<Self, ParserError> {
+        let mut missing = Vec::new();
+        let mut find = |mapped: &str| {
+            let column = header.iter().position(|name| header_matches(name, mapped));
+            if column.is_none() {
+                missing.push(format!("'{}'", mapped.trim()));
+            }
+            column
+        };
+
+        let date = find(&mapping.date);
+        let description = find(&mapping.description);
+        let amount = mapping.amount.as_deref().and_then(&mut find);
+        let debit = mapping.debit.as_deref().and_then(&mut find);
+        let credit = mapping.credit.as_deref().and_then(&mut find);
+        let notes = mapping.notes.as_deref().and_then(&mut find);
+        let category = mapping.category.as_deref().and_then(&mut find);
+        let account = mapping.account.as_deref().and_then(&mut find);
+
+        match (date, description) {
+            (Some(date), Some(description)) if missing.is_empty() => Ok(Self {
+                date,
+                description,
+                amount,
+                debit,
+                credit,
+                notes,
+                category,
+                account,
+            }),
+            _ => Err(ParserError::MissingColumns { columns: missing }),
+        }
+    }
+
+    /// Parse a record of a file with mapped columns
+    fn parse_record(
+        &self,
+        record: &csv::StringRecord,
+        line: usize,
+        format: &CsvFormat,
+    ) -> Result<ParsedTransaction, ParserError> {
+        let optional = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let required = |column: usize, field: &'static str| {
+            optional(Some(column)).ok_or(ParserError::MissingField { field, line })
+        };
+
+        let date = parse_mapped_date(required(self.date, "date")?, format.date_format(), line)?;
+        let title = optional(Some(self.description))
+            .unwrap_or_default()
+            .to_string();
+
+        let amount = match format.amount_sign {
+            AmountSign::NegativeDebits => {
+                let column = self.amount.ok_or(ParserError::MissingField {
+                    field: "amount",
+                    line,
+                })?;
+                parse_mapped_amount(required(column, "amount")?, line)?
+            }
+            AmountSign::DebitCreditColumns => {
+                let debit = optional(self.debit)
+                    .map(|value| parse_mapped_amount(value, line))
+                    .transpose()?;
+                let credit = optional(self.credit)
+                    .map(|value| parse_mapped_amount(value, line))
+                    .transpose()?;
+                if debit.is_none() && credit.is_none() {
+                    return Err(ParserError::MissingField {
+                        field: "amount",
+                        line,
+                    });
+                }
+                credit.unwrap_or_default().abs() - debit.unwrap_or_default().abs()
+            }
+        };
+
+        Ok(ParsedTransaction {
+            temp_id: Uuid::new_v4().to_string(),
+            title,
+            amount,
+            date,
+            notes: optional(self.notes).map(str::to_string),
+            original_currency: None,
+            original_amount: None,
+            category_name: optional(self.category).map(str::to_string),
+            account_name: optional(self.account).map(str::to_string),
+            category_id: None,
+            account_id: None,
+            is_valid: true,
+            validation_errors: None,
+            is_potential_duplicate: false,
+            duplicate_match: None,
+        })
+    }
+}
+
+/// Whether a header field is the column a mapping names, ignoring case and whitespace
+fn header_matches(name: &str, mapped: &str) -> bool {
+    name.trim().to_lowercase() == mapped.trim().to_lowercase()
+}
+
+/// Parse a date in a mapping's format; a date without a time is midnight UTC
+fn parse_mapped_date(value: &str, format: &str, line: usize) -> Result<DateTime<Utc>, ParserError> {
+    NaiveDateTime::parse_from_str(value, format)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, format).map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map(|date| date.and_utc())
+        .map_err(|_| ParserError::InvalidDate {
+            value: value.to_string(),
+            format: format.to_string(),
+            line,
+        })
+}
+
+/// Parse an amount of a mapped column
+///
+/// Currency symbols, thousands separators and spaces are ignored, and an
+/// amount in parentheses is negative, e.g. `($1,234.50)` is -1234.50.
+fn parse_mapped_amount(value: &str, line: usize) -> Result<BigDecimal, ParserError> {
+    let (negative, number) = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(number) => (true, number),
+        None => (false, value),
+    };
+    let number: String = number
+        .chars()
+        .filter(|c| !matches!(c, '€' | '£' | '$' | ',') && !c.is_whitespace())
+        .collect();
+
+    let amount = BigDecimal::from_str(&number).map_err(|_| ParserError::InvalidAmount {
+        value: value.to_string(),
+        line,
+    })?;
+    Ok(if negative { -amount } else { amount })
+}
+
 /// Parser factory for creating appropriate parser based on file type
 pub struct ParserFactory;
 
@@ -302,6 +693,7 @@ impl ParserFactory {
     pub fn get_parser(file_extension: &str) -> Result<Box<dyn StatementParser>, ParserError> {
         match file_extension.to_lowercase().as_str() {
             ".csv" => Ok(Box::new(CSVStatementParser)),
+            ".ofx" | ".qfx" => Ok(Box::new(OfxStatementParser)),
             _ => Err(ParserError::UnsupportedFileType {
                 extension: file_extension.to_string(),
             }),
@@ -310,6 +702,6 @@ impl ParserFactory {
 
     /// Get list of all supported extensions
     pub fn supported_extensions() -> Vec<&'static str> {
-        vec![".csv"] // Add ".pdf" when implemented
+        vec![".csv", ".ofx", ".qfx"] // Add ".pdf" when implemented
     }
 }
@@ -1,4 +1,5 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -7,16 +8,87 @@ use crate::{
     errors::ApiError,
     models::{NewTransaction, NewTransactionSplit},
     repositories,
+    types::TransactionStatus,
 };
 
 /// Debt information for a person
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct PersonDebt {
     pub person_id: Uuid,
     pub person_name: String,
     pub debt_amount: String, // Positive means they owe you, negative means you owe them
 }
 
+/// One change to the debt with a person, with the balance after it
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct PersonLedgerEntry {
+    pub transaction_id: Uuid,
+    pub date: DateTime<Utc>,
+    pub description: String,
+    pub delta: String,
+    pub balance: String, // Same sign convention as `PersonDebt::debt_amount`
+}
+
+/// Build the chronological ledger of every change to the debt with a person
+///
+/// Each split adds its amount on the transaction's date; a split marked as
+/// settled is taken off again when it was settled, so the final balance
+/// always matches `calculate_debt_for_person`.
+pub async fn get_ledger(
+    pool: &DbPool,
+    person_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<PersonLedgerEntry>, ApiError> {
+    // Verify person ownership
+    let person = repositories::person::find_by_id(pool, person_id).await?;
+    if person.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to read the ledger of person {} owned by {}",
+            user_id,
+            person_id,
+            person.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Person does not belong to user".to_string(),
+        ));
+    }
+
+    let splits =
+        repositories::person::list_ledger_splits_for_person(pool, user_id, person_id).await?;
+
+    let mut changes = Vec::with_capacity(splits.len());
+    for (transaction_id, date, title, amount, settled_at) in splits {
+        if let Some(settled_at) = settled_at {
+            changes.push((
+                settled_at,
+                transaction_id,
+                format!("Settled: {}", title),
+                -&amount,
+            ));
+        }
+        changes.push((date, transaction_id, title, amount));
+    }
+    // Stable sort keeps a transaction's split ahead of a settlement at the same instant
+    changes.sort_by_key(|(date, ..)| *date);
+
+    let mut balance = BigDecimal::from(0);
+    let entries = changes
+        .into_iter()
+        .map(|(date, transaction_id, description, delta)| {
+            balance += &delta;
+            PersonLedgerEntry {
+                transaction_id,
+                date,
+                description,
+                delta: delta.to_string(),
+                balance: balance.to_string(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
 /// Calculate debt for a specific person
 /// Returns positive if they owe you, negative if you owe them
 pub async fn calculate_debt_for_person(
@@ -38,55 +110,57 @@ pub async fn calculate_debt_for_person(
         ));
     }
 
-    // Get all splits for this person
-    let splits = repositories::person::list_splits_for_person(pool, person_id).await?;
-
-    // Sum all split amounts
+    // Sum all unsettled split amounts
     // Positive amounts mean they owe you (you paid for them)
     // Negative amounts mean you owe them (they paid for you)
-    let total_debt: BigDecimal = splits.iter().map(|split| split.amount.clone()).sum();
+    let total_debt =
+        repositories::person::sum_unsettled_splits_by_person(pool, user_id, Some(person_id))
+            .await?
+            .into_iter()
+            .next()
+            .map(|(_, _, total)| total)
+            .unwrap_or_else(|| BigDecimal::from(0));
 
     Ok(total_debt.to_string())
 }
 
 /// Get all debts for a user (all people they've shared expenses with)
+///
+/// Totals for every person are computed in a single grouped query, so the number
+/// of queries does not grow with the number of people.
 pub async fn get_all_debts_for_user(
     pool: &DbPool,
     user_id: Uuid,
 ) -> Result<Vec<PersonDebt>, ApiError> {
-    // Get all user's people
-    let people = repositories::person::list_by_user(pool, user_id).await?;
-
-    let mut debts = Vec::new();
-
-    for person in people {
-        // Get splits for this person
-        let splits = repositories::person::list_splits_for_person(pool, person.id).await?;
-
-        // Calculate total debt
-        let total_debt: BigDecimal = splits.iter().map(|split| split.amount.clone()).sum();
+    let totals = repositories::person::sum_unsettled_splits_by_person(pool, user_id, None).await?;
 
-        // Only include if there's an actual debt (non-zero)
-        if total_debt != BigDecimal::from(0) {
-            debts.push(PersonDebt {
-                person_id: person.id,
-                person_name: person.name,
-                debt_amount: total_debt.to_string(),
-            });
-        }
-    }
+    // Only include people with an actual debt (non-zero)
+    let debts = totals
+        .into_iter()
+        .filter(|(_, _, total)| *total != BigDecimal::from(0))
+        .map(|(person_id, person_name, total)| PersonDebt {
+            person_id,
+            person_name,
+            debt_amount: total.to_string(),
+        })
+        .collect();
 
     Ok(debts)
 }
 
 /// Settle debt with a person
 /// Creates a settlement transaction to record the payment
+///
+/// When `settle_splits` is set, the oldest unsettled splits fully covered by the
+/// payment are marked as settled, and only the uncovered remainder is recorded
+/// as an offsetting split. The debt is reduced by `amount` either way.
 pub async fn settle_debt(
     pool: &DbPool,
     person_id: Uuid,
     user_id: Uuid,
     amount: f64,
     account_id: Uuid,
+    settle_splits: bool,
 ) -> Result<(), ApiError> {
     // Verify person ownership
     let person = repositories::person::find_by_id(pool, person_id).await?;
@@ -134,24 +208,45 @@ pub async fn settle_debt(
         amount: settlement_amount.clone(),
         date: chrono::Utc::now(),
         notes: Some(format!("Settlement of debt with {}", person.name)),
+        external_id: None,
+        status: TransactionStatus::Posted,
+        original_currency: None,
+        original_amount: None,
+        exchange_rate: None,
+        merchant: None,
+        latitude: None,
+        longitude: None,
+        transfer_id: None,
+        reimbursable: false,
+        tags: Vec::new(),
     };
 
     let transaction =
         repositories::transaction::create_transaction(pool, user_id, settlement_transaction)
             .await?;
 
-    // Create a split with negative amount to offset the debt
+    // Settle the oldest splits the payment covers in full
+    let zero = BigDecimal::from(0);
+    let covered = if settle_splits {
+        settle_oldest_splits(pool, person_id, &settlement_amount).await?
+    } else {
+        zero.clone()
+    };
+
+    // Create a split with negative amount to offset the rest of the debt
     // If they paid you (positive amount), create negative split to reduce their debt
     // If you paid them (negative amount), create positive split to reduce your debt to them
-    let split_amount = -settlement_amount;
+    let split_amount = covered - settlement_amount;
 
-    let new_split = NewTransactionSplit {
-        transaction_id: transaction.id,
-        person_id,
-        amount: split_amount,
-    };
+    if split_amount != zero {
+        let new_split = NewTransactionSplit {
+            transaction_id: transaction.id,
+            person_id,
+            amount: split_amount,
+        };
 
-    repositories::transaction::create_split(pool, transaction.id, new_split).await?;
+        repositories::transaction::create_split(pool, transaction.id, new_split).await?;
+    }
 
     tracing::info!(
         "Settled debt of {} with person {} for user {}",
@@ -162,3 +257,47 @@ pub async fn settle_debt(
 
     Ok(())
 }
+
+/// Mark the oldest unsettled splits with the same sign as `payment` as settled,
+/// stopping at the first split the remaining payment no longer covers in full
+///
+/// Returns the total amount of the settled splits (with the sign of `payment`).
+async fn settle_oldest_splits(
+    pool: &DbPool,
+    person_id: Uuid,
+    payment: &BigDecimal,
+) -> Result<BigDecimal, ApiError> {
+    let zero = BigDecimal::from(0);
+    let payment_is_positive = *payment > zero;
+    let splits = repositories::person::list_unsettled_splits_for_person(pool, person_id).await?;
+
+    let mut covered = BigDecimal::from(0);
+    let mut split_ids = Vec::new();
+    for split in splits {
+        // Only splits owed in the direction of the payment can be settled by it
+        if (split.amount > zero) != payment_is_positive || split.amount == zero {
+            continue;
+        }
+        let next = &covered + &split.amount;
+        if next.abs() > payment.abs() {
+            break;
+        }
+        covered = next;
+        split_ids.push(split.id);
+    }
+
+    if !split_ids.is_empty() {
+        let settled =
+            repositories::transaction::settle_splits(pool, split_ids, chrono::Utc::now()).await?;
+        // Only count splits this call actually settled
+        covered = settled.iter().map(|split| split.amount.clone()).sum();
+        tracing::info!(
+            "Settled {} splits totalling {} for person {}",
+            settled.len(),
+            covered,
+            person_id
+        );
+    }
+
+    Ok(covered)
+}
@@ -0,0 +1,57 @@
+//! Outgoing email
+//!
+//! Emails are sent through an [`EmailSender`] held in the application state,
+//! so the transport can be swapped out (or stubbed in tests) without touching
+//! the services that send them.
+
+use async_trait::async_trait;
+
+use crate::errors::ApiError;
+
+/// Transport for the emails the application sends
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    /// Send a password reset token to `email`
+    async fn send_password_reset(&self, email: &str, token: &str) -> Result<(), ApiError>;
+
+    /// Send a notification, such as a budget alert, to `email`
+    async fn send_notification(
+        &self,
+        email: &str,
+        subject: &str,
+        body: &str,
+    ) -> Result<(), ApiError>;
+}
+
+/// Sender that writes emails to the log instead of delivering them
+///
+/// Used until a mail transport is configured. The token is only logged at
+/// debug level, so it stays out of production logs.
+pub struct LogEmailSender;
+
+#[async_trait]
+impl EmailSender for LogEmailSender {
+    async fn send_password_reset(&self, email: &str, token: &str) -> Result<(), ApiError> {
+        tracing::info!(
+            "Password reset email for {} (no mail transport configured)",
+            email
+        );
+        tracing::debug!("Password reset token for {}: {}", email, token);
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        email: &str,
+        subject: &str,
+        body: &str,
+    ) -> Result<(), ApiError> {
+        tracing::info!(
+            "Notification email '{}' for {} (no mail transport configured)",
+            subject,
+            email
+        );
+        tracing::debug!("Notification email body for {}: {}", email, body);
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Change notifications for real-time clients
+//!
+//! Mutation handlers publish a [`ChangeEvent`] to the [`EventBus`] in
+//! `AppState` after a change succeeds. Clients subscribed to
+//! `GET /api/v1/events` receive the events of their own user as server-sent
+//! events and refetch only the affected data.
+//!
+//! Each user with an open stream has their own broadcast channel, created by
+//! their first subscription and removed when their last stream is dropped, so
+//! publishing only wakes the streams of the user it concerns. A subscriber
+//! that falls more than [`EVENT_BUFFER_SIZE`] events behind misses the oldest
+//! ones and is sent a `lagged` event instead, after which it should refetch
+//! everything.
+
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::sync::broadcast;
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Events buffered for slow subscribers before the oldest are dropped
+pub const EVENT_BUFFER_SIZE: usize = 256;
+
+/// Change to a user's data, sent as the `data` of a server-sent event named
+/// after its `type`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    TransactionCreated { transaction_id: Uuid },
+    TransactionUpdated { transaction_id: Uuid },
+    TransactionDeleted { transaction_id: Uuid },
+    BudgetChanged { budget_id: Uuid },
+    BudgetDeleted { budget_id: Uuid },
+    BudgetAlertFired { budget_id: Uuid, alert_id: Uuid },
+}
+
+impl ChangeEvent {
+    /// Name of the server-sent event, the same as the serialized `type`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TransactionCreated { .. } => "transaction_created",
+            Self::TransactionUpdated { .. } => "transaction_updated",
+            Self::TransactionDeleted { .. } => "transaction_deleted",
+            Self::BudgetChanged { .. } => "budget_changed",
+            Self::BudgetDeleted { .. } => "budget_deleted",
+            Self::BudgetAlertFired { .. } => "budget_alert_fired",
+        }
+    }
+}
+
+/// Item of a user's event stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamItem {
+    Event(ChangeEvent),
+    /// This many events were dropped because the subscriber fell behind
+    Lagged(u64),
+}
+
+/// Broadcast channels of change events, one per subscribed user
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    capacity: usize,
+    senders: Arc<Mutex<HashMap<Uuid, broadcast::Sender<ChangeEvent>>>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(EVENT_BUFFER_SIZE)
+    }
+}
+
+impl EventBus {
+    /// Create a bus buffering up to `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            senders: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Notify the user's subscribers of a change
+    ///
+    /// Events published while nobody is subscribed are dropped.
+    pub fn publish(&self, user_id: Uuid, event: ChangeEvent) {
+        let mut senders = self.senders.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = senders.get(&user_id) {
+            // Sending only fails when the last subscriber is gone
+            if sender.send(event).is_err() {
+                senders.remove(&user_id);
+            }
+        }
+    }
+
+    /// Whether the user has a channel, i.e. at least one open stream
+    pub fn has_subscribers(&self, user_id: Uuid) -> bool {
+        let senders = self.senders.lock().unwrap_or_else(|e| e.into_inner());
+        senders.contains_key(&user_id)
+    }
+
+    /// Stream the events of one user, starting now
+    ///
+    /// The stream never ends on its own; dropping it unsubscribes, and removes
+    /// the user's channel if it was their last.
+    pub fn subscribe(&self, user_id: Uuid) -> impl Stream<Item = StreamItem> + use<> {
+        let receiver = {
+            let mut senders = self.senders.lock().unwrap_or_else(|e| e.into_inner());
+            senders
+                .entry(user_id)
+                .or_insert_with(|| broadcast::channel(self.capacity).0)
+                .subscribe()
+        };
+
+        let events = BroadcastStream::new(receiver).filter_map(move |received| match received {
+            Ok(event) => Some(StreamItem::Event(event)),
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                tracing::warn!(
+                    "Event stream of user {} lagged behind by {} events",
+                    user_id,
+                    missed
+                );
+                Some(StreamItem::Lagged(missed))
+            }
+        });
+
+        Subscription {
+            events: Some(Box::pin(events)),
+            senders: self.senders.clone(),
+            user_id,
+        }
+    }
+}
+
+/// Event stream of one user that removes the user's channel when it is the
+/// last one dropped
+struct Subscription {
+    events: Option<Pin<Box<dyn Stream<Item = StreamItem> + Send>>>,
+    senders: Arc<Mutex<HashMap<Uuid, broadcast::Sender<ChangeEvent>>>>,
+    user_id: Uuid,
+}
+
+impl Stream for Subscription {
+    type Item = StreamItem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamItem>> {
+        match self.events.as_mut() {
+            Some(events) => events.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut senders = self.senders.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop the receiver first so it no longer counts
+        self.events = None;
+        if senders
+            .get(&self.user_id)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            senders.remove(&self.user_id);
+        }
+    }
+}
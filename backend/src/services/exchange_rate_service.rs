@@ -1,12 +1,20 @@
+use async_trait::async_trait;
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::str::FromStr;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, LazyLock, OnceLock};
+use tokio::sync::{Mutex, OnceCell, RwLock};
+use uuid::Uuid;
 
+use crate::DbPool;
+use crate::config::{ExchangeRateConfig, ExchangeRateProviderKind, ExchangeRateRefreshConfig};
 use crate::errors::ApiError;
+use crate::models::{CustomExchangeRate, ExchangeRateRecord, NewExchangeRateRecord};
+use crate::repositories;
 use crate::types::CurrencyCode;
 
 /// Primary currency for the application
@@ -28,32 +36,587 @@ struct ExchangeRateResponse {
 struct CachedRates {
     rates: HashMap<CurrencyCode, BigDecimal>,
     timestamp: std::time::Instant,
+    /// When the rates were fetched from the provider
+    fetched_at: DateTime<Utc>,
+}
+
+/// Per-base-currency rate cache
+type RateCache = Arc<RwLock<HashMap<CurrencyCode, CachedRates>>>;
+
+/// Outcome of an in-flight fetch, shared with every caller waiting on it
+type SharedFetch = Arc<OnceCell<Result<HashMap<CurrencyCode, BigDecimal>, String>>>;
+
+/// Rate cache shared by all service instances
+///
+/// Services are constructed per request, so the cache must live for the whole
+/// process for cached rates to be reused across requests.
+static SHARED_CACHE: LazyLock<RateCache> = LazyLock::new(|| Arc::new(RwLock::new(HashMap::new())));
+
+/// Coalescer shared by all service instances
+static SHARED_COALESCER: LazyLock<Arc<RateFetchCoalescer>> =
+    LazyLock::new(|| Arc::new(RateFetchCoalescer::new()));
+
+/// Provider chain installed at startup from the configured order
+static SHARED_PROVIDER: OnceLock<Arc<dyn ExchangeRateProvider>> = OnceLock::new();
+
+/// Source of exchange rates
+#[async_trait]
+pub trait ExchangeRateProvider: Send + Sync {
+    /// Name the provider is logged under
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Fetch the current rates from `base_currency` to every supported currency
+    async fn fetch_rates(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError>;
+
+    /// Fetch the rates from `base_currency` on a past day
+    ///
+    /// Providers without historical rates keep this default, and lookups fall
+    /// back to the nearest earlier stored rate.
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        Err(ApiError::External(format!(
+            "Historical rates for {} on {} are not available",
+            base_currency.as_str(),
+            date
+        )))
+    }
+
+    /// Fetch the rate from `base_currency` to `quote_currency` on `date`
+    ///
+    /// Today's and later dates get the current rate.
+    async fn fetch_rate(
+        &self,
+        base_currency: CurrencyCode,
+        quote_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<BigDecimal, ApiError> {
+        let rates = if date >= Utc::now().date_naive() {
+            self.fetch_rates(base_currency).await?
+        } else {
+            self.fetch_historical_rates(base_currency, date).await?
+        };
+        rates.get(&quote_currency).cloned().ok_or_else(|| {
+            ApiError::External(format!(
+                "No exchange rate from {} to {} on {}",
+                base_currency.as_str(),
+                quote_currency.as_str(),
+                date
+            ))
+        })
+    }
+}
+
+/// Parse rates keyed by currency code, skipping unsupported currencies
+fn parse_rates(rates: HashMap<String, f64>) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+    let mut parsed = HashMap::new();
+    for currency in CurrencyCode::ALL {
+        if let Some(&rate) = rates.get(currency.as_str()) {
+            // Convert f64 to BigDecimal properly to preserve decimal places
+            let rate_str = rate.to_string();
+            let rate_decimal = BigDecimal::from_str(&rate_str).map_err(|e| {
+                tracing::error!("Failed to convert rate {} to BigDecimal: {}", rate, e);
+                ApiError::Internal
+            })?;
+            parsed.insert(currency, rate_decimal);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Provider backed by exchangerate-api.com
+pub struct ExchangeRateApiProvider {
+    api_key: String,
+}
+
+impl ExchangeRateApiProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+
+    /// Fetch and parse the rates at `url`
+    async fn fetch_from(&self, url: &str) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let response = reqwest::get(url).await.map_err(|e| {
+            tracing::error!("Failed to fetch exchange rates: {}", e);
+            ApiError::Internal
+        })?;
+
+        if !response.status().is_success() {
+            tracing::error!(
+                "Exchange rate API returned error status: {}",
+                response.status()
+            );
+            return Err(ApiError::Internal);
+        }
+
+        let data: ExchangeRateResponse = response.json().await.map_err(|e| {
+            tracing::error!("Failed to parse exchange rate response: {}", e);
+            ApiError::Internal
+        })?;
+
+        if data.result != "success" {
+            tracing::error!("Exchange rate API returned error: {:?}", data.error_type);
+            return Err(ApiError::Internal);
+        }
+
+        let conversion_rates = data.conversion_rates.ok_or_else(|| {
+            tracing::error!("No conversion rates in API response");
+            ApiError::Internal
+        })?;
+
+        parse_rates(conversion_rates)
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for ExchangeRateApiProvider {
+    fn name(&self) -> &'static str {
+        ExchangeRateProviderKind::ExchangeRateApi.as_str()
+    }
+
+    async fn fetch_rates(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let url = format!(
+            "https://v6.exchangerate-api.com/v6/{}/latest/{}",
+            self.api_key,
+            base_currency.as_str()
+        );
+        self.fetch_from(&url).await
+    }
+
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let url = format!(
+            "https://v6.exchangerate-api.com/v6/{}/history/{}/{}",
+            self.api_key,
+            base_currency.as_str(),
+            date.format("%Y/%-m/%-d")
+        );
+        self.fetch_from(&url).await
+    }
+}
+
+/// Frankfurter API response structure
+#[derive(Debug, Deserialize)]
+struct FrankfurterResponse {
+    rates: HashMap<String, f64>,
+}
+
+/// Provider backed by frankfurter.app, serving European Central Bank rates
+///
+/// Needs no API key. Rates are published on working days, and a day without
+/// them gets the rates of the working day before.
+pub struct FrankfurterProvider {
+    base_url: String,
+}
+
+impl FrankfurterProvider {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://api.frankfurter.app".to_string(),
+        }
+    }
+
+    /// Fetch and parse the rates from `base_currency` at `path`
+    async fn fetch_from(
+        &self,
+        path: &str,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let url = format!("{}/{}?from={}", self.base_url, path, base_currency.as_str());
+        let response = reqwest::get(&url).await.map_err(|e| {
+            tracing::error!("Failed to fetch Frankfurter exchange rates: {}", e);
+            ApiError::Internal
+        })?;
+
+        if !response.status().is_success() {
+            tracing::error!(
+                "Frankfurter API returned error status: {}",
+                response.status()
+            );
+            return Err(ApiError::Internal);
+        }
+
+        let data: FrankfurterResponse = response.json().await.map_err(|e| {
+            tracing::error!("Failed to parse Frankfurter response: {}", e);
+            ApiError::Internal
+        })?;
+
+        // Frankfurter leaves out the base currency's rate to itself
+        let mut rates = parse_rates(data.rates)?;
+        rates.insert(base_currency, BigDecimal::from(1));
+        Ok(rates)
+    }
+}
+
+impl Default for FrankfurterProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for FrankfurterProvider {
+    fn name(&self) -> &'static str {
+        ExchangeRateProviderKind::Frankfurter.as_str()
+    }
+
+    async fn fetch_rates(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.fetch_from("latest", base_currency).await
+    }
+
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.fetch_from(&date.format("%Y-%m-%d").to_string(), base_currency)
+            .await
+    }
+}
+
+/// Providers tried in order until one serves the rates
+///
+/// A provider that fails or returns no rates falls back to the next one, and
+/// the last failure is returned when none can serve them.
+pub struct ExchangeRateProviderChain {
+    providers: Vec<Arc<dyn ExchangeRateProvider>>,
+}
+
+impl ExchangeRateProviderChain {
+    pub fn new(providers: Vec<Arc<dyn ExchangeRateProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Build the chain of `kinds`, in order
+    ///
+    /// exchangerate-api is left out without `EXCHANGE_RATE_API_KEY`. Fails
+    /// when no provider is left.
+    pub fn from_kinds(kinds: &[ExchangeRateProviderKind]) -> Result<Self, ApiError> {
+        let mut providers: Vec<Arc<dyn ExchangeRateProvider>> = Vec::new();
+        for kind in kinds {
+            match kind {
+                ExchangeRateProviderKind::ExchangeRateApi => {
+                    match env::var("EXCHANGE_RATE_API_KEY") {
+                        Ok(api_key) if !api_key.is_empty() => {
+                            providers.push(Arc::new(ExchangeRateApiProvider::new(api_key)))
+                        }
+                        _ => tracing::warn!(
+                            "Skipping exchange rate provider {}: EXCHANGE_RATE_API_KEY is not set",
+                            kind.as_str()
+                        ),
+                    }
+                }
+                ExchangeRateProviderKind::Frankfurter => {
+                    providers.push(Arc::new(FrankfurterProvider::new()))
+                }
+            }
+        }
+
+        if providers.is_empty() {
+            tracing::error!("No exchange rate provider is available");
+            return Err(ApiError::Internal);
+        }
+        Ok(Self::new(providers))
+    }
+
+    /// Call `fetch` on each provider in turn until one returns a usable result
+    async fn first_served<T, F, Fut>(
+        &self,
+        what: &str,
+        fetch: F,
+        is_usable: fn(&T) -> bool,
+    ) -> Result<T, ApiError>
+    where
+        F: Fn(Arc<dyn ExchangeRateProvider>) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match fetch(provider.clone()).await {
+                Ok(result) if is_usable(&result) => {
+                    tracing::debug!("{} served by {}", what, provider.name());
+                    return Ok(result);
+                }
+                Ok(_) => tracing::warn!("{} not available from {}", what, provider.name()),
+                Err(e) => {
+                    tracing::warn!("{} failed from {}: {}", what, provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ApiError::External(format!("{} not available from any provider", what))
+        }))
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for ExchangeRateProviderChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    async fn fetch_rates(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let what = format!("Exchange rates for base {}", base_currency.as_str());
+        self.first_served(
+            &what,
+            |provider| async move { provider.fetch_rates(base_currency).await },
+            |rates| !rates.is_empty(),
+        )
+        .await
+    }
+
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let what = format!(
+            "Exchange rates for base {} on {}",
+            base_currency.as_str(),
+            date
+        );
+        self.first_served(
+            &what,
+            |provider| async move { provider.fetch_historical_rates(base_currency, date).await },
+            |rates| !rates.is_empty(),
+        )
+        .await
+    }
+
+    async fn fetch_rate(
+        &self,
+        base_currency: CurrencyCode,
+        quote_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<BigDecimal, ApiError> {
+        let what = format!(
+            "Exchange rate from {} to {} on {}",
+            base_currency.as_str(),
+            quote_currency.as_str(),
+            date
+        );
+        self.first_served(
+            &what,
+            |provider| async move {
+                provider
+                    .fetch_rate(base_currency, quote_currency, date)
+                    .await
+            },
+            |_| true,
+        )
+        .await
+    }
+}
+
+/// Install the provider chain of `kinds` for every service built with
+/// [`ExchangeRateService::new`]
+///
+/// Called once at startup; later calls are ignored. Until then, services use
+/// the default order.
+pub fn configure_providers(kinds: &[ExchangeRateProviderKind]) -> Result<(), ApiError> {
+    let chain = ExchangeRateProviderChain::from_kinds(kinds)?;
+    let _ = SHARED_PROVIDER.set(Arc::new(chain));
+    Ok(())
+}
+
+/// Rate between two currencies and when it was fetched
+#[derive(Debug, Clone)]
+pub struct ExchangeRate {
+    pub rate: BigDecimal,
+    /// When the rate was fetched from the provider; `None` for a currency's rate to itself
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl From<ExchangeRateRecord> for ExchangeRate {
+    fn from(record: ExchangeRateRecord) -> Self {
+        Self {
+            rate: record.rate,
+            fetched_at: Some(record.fetched_at),
+        }
+    }
+}
+
+impl From<CustomExchangeRate> for ExchangeRate {
+    fn from(record: CustomExchangeRate) -> Self {
+        Self {
+            rate: record.rate,
+            fetched_at: Some(record.updated_at),
+        }
+    }
+}
+
+/// Rate between two currencies on a day
+#[derive(Debug, Clone)]
+pub struct DatedExchangeRate {
+    pub rate: BigDecimal,
+    /// Day the rate is for, which may be earlier than the day asked for
+    pub date: NaiveDate,
+    /// When the rate was fetched from the provider; `None` for a currency's rate to itself
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl From<ExchangeRateRecord> for DatedExchangeRate {
+    fn from(record: ExchangeRateRecord) -> Self {
+        Self {
+            rate: record.rate,
+            date: record.rate_date,
+            fetched_at: Some(record.fetched_at),
+        }
+    }
+}
+
+impl From<CustomExchangeRate> for DatedExchangeRate {
+    fn from(record: CustomExchangeRate) -> Self {
+        Self {
+            rate: record.rate,
+            date: record.rate_date,
+            fetched_at: Some(record.updated_at),
+        }
+    }
+}
+
+/// Single-flight coalescing of upstream rate fetches
+///
+/// Concurrent requests for the same base currency share one upstream fetch:
+/// the first caller runs the fetch and every other caller awaits its result.
+/// Once the fetch completes the entry is dropped, so later calls fetch again
+/// (the rate cache is responsible for reuse after that).
+#[derive(Default)]
+pub struct RateFetchCoalescer {
+    in_flight: Mutex<HashMap<CurrencyCode, SharedFetch>>,
+}
+
+impl RateFetchCoalescer {
+    /// Create an empty coalescer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `fetch` for `base_currency` unless a fetch for it is already in flight,
+    /// in which case wait for and share that fetch's result
+    ///
+    /// If the caller running the fetch is cancelled, one of the waiting callers
+    /// takes over and runs its own `fetch`.
+    pub async fn fetch<F, Fut>(
+        &self,
+        base_currency: CurrencyCode,
+        fetch: F,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<HashMap<CurrencyCode, BigDecimal>, ApiError>>,
+    {
+        let cell = {
+            let mut in_flight = self.in_flight.lock().await;
+            in_flight
+                .entry(base_currency)
+                .or_insert_with(|| Arc::new(OnceCell::new()))
+                .clone()
+        };
+
+        let result = cell
+            .get_or_init(|| async { fetch().await.map_err(|e| e.to_string()) })
+            .await
+            .clone();
+
+        // Forget the completed fetch unless a newer one has already replaced it
+        {
+            let mut in_flight = self.in_flight.lock().await;
+            if in_flight
+                .get(&base_currency)
+                .is_some_and(|current| Arc::ptr_eq(current, &cell))
+            {
+                in_flight.remove(&base_currency);
+            }
+        }
+
+        result.map_err(|e| {
+            tracing::error!(
+                "Exchange rate fetch for base {} failed: {}",
+                base_currency.as_str(),
+                e
+            );
+            ApiError::Internal
+        })
+    }
 }
 
 /// Exchange rate service with caching
-/// Fetches rates from exchangerate-api.com and caches them for 1 hour
-/// Maintains separate caches for different base currencies
+/// Fetches rates from the configured provider chain and caches them for 24 hours
+/// Maintains separate caches for different base currencies, shared process-wide,
+/// and coalesces concurrent fetches for the same base currency
+#[derive(Clone)]
 pub struct ExchangeRateService {
-    cache: Arc<RwLock<HashMap<CurrencyCode, CachedRates>>>,
-    api_key: String,
+    cache: RateCache,
+    coalescer: Arc<RateFetchCoalescer>,
+    provider: Arc<dyn ExchangeRateProvider>,
     cache_duration: std::time::Duration,
+    /// Where manual rates are looked up and whose they are; without it only
+    /// fetched rates are used
+    manual_rates: Option<(DbPool, Uuid)>,
 }
 
 impl ExchangeRateService {
     /// Create a new exchange rate service
+    ///
+    /// Uses the provider chain installed by [`configure_providers`], or the
+    /// default order when none was.
     pub fn new() -> Result<Self, ApiError> {
-        let api_key = env::var("EXCHANGE_RATE_API_KEY").map_err(|_| {
-            tracing::error!("EXCHANGE_RATE_API_KEY environment variable not set");
-            ApiError::Internal
-        })?;
+        let provider = match SHARED_PROVIDER.get() {
+            Some(provider) => provider.clone(),
+            None => Arc::new(ExchangeRateProviderChain::from_kinds(
+                &ExchangeRateConfig::default().providers,
+            )?),
+        };
 
         Ok(Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            api_key,
+            cache: SHARED_CACHE.clone(),
+            coalescer: SHARED_COALESCER.clone(),
+            provider,
             cache_duration: std::time::Duration::from_secs(86400), // 24 hours
+            manual_rates: None,
         })
     }
 
+    /// Create a service backed by `provider`, with its own cache and coalescer
+    pub fn with_provider(provider: Arc<dyn ExchangeRateProvider>) -> Self {
+        Self {
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            coalescer: Arc::new(RateFetchCoalescer::new()),
+            provider,
+            cache_duration: std::time::Duration::from_secs(86400), // 24 hours
+            manual_rates: None,
+        }
+    }
+
+    /// Look up the manual rates `user_id` set in `pool`, preferring them over
+    /// fetched rates
+    pub fn with_manual_rates(mut self, pool: DbPool, user_id: Uuid) -> Self {
+        self.manual_rates = Some((pool, user_id));
+        self
+    }
+
     /// Get exchange rates with specified base currency
     /// Uses cached rates if available and not expired
     /// Maintains separate caches for each base currency
@@ -61,6 +624,235 @@ impl ExchangeRateService {
         &self,
         base_currency: CurrencyCode,
     ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        Ok(self.get_cached_rates(base_currency).await?.rates)
+    }
+
+    /// Get the rate from one currency to another, with when it was fetched
+    ///
+    /// Uses the same cache as [`Self::get_exchange_rates`] for the `from` base.
+    ///
+    /// With [`Self::with_manual_rates`], the user's manual rate set for today
+    /// is used instead, and their latest earlier manual rate when no provider
+    /// has the pair.
+    pub async fn get_rate(
+        &self,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+    ) -> Result<ExchangeRate, ApiError> {
+        if from_currency == to_currency {
+            return Ok(ExchangeRate {
+                rate: BigDecimal::from(1),
+                fetched_at: None,
+            });
+        }
+
+        let today = Utc::now().date_naive();
+        let manual = self
+            .find_manual_rate(from_currency, to_currency, today)
+            .await?;
+        if let Some(record) = manual.as_ref()
+            && record.rate_date == today
+        {
+            return Ok(record.clone().into());
+        }
+
+        match self.fetched_rate(from_currency, to_currency).await {
+            Ok(rate) => Ok(rate),
+            // A pair no provider has may have been set by hand on an earlier day
+            Err(e) => manual.map(ExchangeRate::from).ok_or(e),
+        }
+    }
+
+    /// Get today's rate from the cache or the provider
+    async fn fetched_rate(
+        &self,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+    ) -> Result<ExchangeRate, ApiError> {
+        let cached = self.get_cached_rates(from_currency).await?;
+        if let Some(rate) = cached.rates.get(&to_currency) {
+            return Ok(ExchangeRate {
+                rate: rate.clone(),
+                fetched_at: Some(cached.fetched_at),
+            });
+        }
+
+        // The provider that served the base's rates may lack this currency
+        let rate = self
+            .provider
+            .fetch_rate(from_currency, to_currency, Utc::now().date_naive())
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "No exchange rate found for {} to {}: {}",
+                    from_currency.as_str(),
+                    to_currency.as_str(),
+                    e
+                );
+                ApiError::Internal
+            })?;
+
+        Ok(ExchangeRate {
+            rate,
+            fetched_at: Some(Utc::now()),
+        })
+    }
+
+    /// Find the latest manual rate on or before `date` of the user given to
+    /// [`Self::with_manual_rates`], if any
+    async fn find_manual_rate(
+        &self,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<Option<CustomExchangeRate>, ApiError> {
+        let Some((pool, user_id)) = &self.manual_rates else {
+            return Ok(None);
+        };
+        repositories::exchange_rate::find_manual_on_or_before(
+            pool,
+            *user_id,
+            from_currency,
+            to_currency,
+            date,
+        )
+        .await
+    }
+
+    /// Get the rate from one currency to another on `date`
+    ///
+    /// Rates are stored per day: a stored rate for the day, the user's manual
+    /// rate before a fetched one, is used as is. Otherwise the day's rates are
+    /// fetched (today's through the same cache as [`Self::get_rate`]) and
+    /// stored. When the provider cannot serve them, the nearest earlier stored
+    /// or manual rate is used, and failing that the current rate, or the latest
+    /// manual rate when no provider has the pair. Days in the future get the
+    /// current rate.
+    pub async fn get_rate_on(
+        &self,
+        pool: &DbPool,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<DatedExchangeRate, ApiError> {
+        let today = Utc::now().date_naive();
+        let date = date.min(today);
+
+        if from_currency == to_currency {
+            return Ok(DatedExchangeRate {
+                rate: BigDecimal::from(1),
+                date,
+                fetched_at: None,
+            });
+        }
+
+        let manual = self
+            .find_manual_rate(from_currency, to_currency, date)
+            .await?;
+        if let Some(record) = manual.as_ref()
+            && record.rate_date == date
+        {
+            return Ok(record.clone().into());
+        }
+        let stored =
+            repositories::exchange_rate::find_on_or_before(pool, from_currency, to_currency, date)
+                .await?;
+        if let Some(record) = stored.as_ref()
+            && record.rate_date == date
+        {
+            return Ok(record.clone().into());
+        }
+
+        if date < today {
+            match self.fetch_and_store(pool, from_currency, date).await {
+                Ok(rates) => {
+                    if let Some(rate) = rates.get(&to_currency) {
+                        return Ok(DatedExchangeRate {
+                            rate: rate.clone(),
+                            date,
+                            fetched_at: Some(Utc::now()),
+                        });
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to fetch exchange rates for base {} on {}: {}",
+                    from_currency.as_str(),
+                    date,
+                    e
+                ),
+            }
+
+            // The nearest earlier rate, manual over fetched on the same day
+            let earlier = match (stored, manual.clone()) {
+                (Some(stored), Some(manual)) if stored.rate_date > manual.rate_date => {
+                    Some(stored.into())
+                }
+                (_, Some(manual)) => Some(manual.into()),
+                (stored, None) => stored.map(DatedExchangeRate::from),
+            };
+            if let Some(rate) = earlier {
+                return Ok(rate);
+            }
+            tracing::warn!(
+                "No exchange rate from {} to {} on or before {}, using the current rate",
+                from_currency.as_str(),
+                to_currency.as_str(),
+                date
+            );
+        }
+
+        let current = match self.fetched_rate(from_currency, to_currency).await {
+            Ok(current) => current,
+            // A pair no provider has may have been set by hand
+            Err(e) => return manual.map(DatedExchangeRate::from).ok_or(e),
+        };
+        self.fetch_and_store(pool, from_currency, today).await?;
+        Ok(DatedExchangeRate {
+            rate: current.rate,
+            date: today,
+            fetched_at: current.fetched_at,
+        })
+    }
+
+    /// Fetch the rates from a base currency on `date` and store them
+    ///
+    /// Today's rates come from the rate cache.
+    async fn fetch_and_store(
+        &self,
+        pool: &DbPool,
+        base_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        let rates = if date == Utc::now().date_naive() {
+            self.get_exchange_rates(base_currency).await?
+        } else {
+            tracing::info!(
+                "Fetching exchange rates from API for base {} on {}",
+                base_currency.as_str(),
+                date
+            );
+            self.provider
+                .fetch_historical_rates(base_currency, date)
+                .await?
+        };
+
+        let records = rates
+            .iter()
+            .filter(|(quote, _)| **quote != base_currency)
+            .map(|(quote, rate)| NewExchangeRateRecord {
+                base_currency,
+                quote_currency: *quote,
+                rate_date: date,
+                rate: rate.clone(),
+            })
+            .collect();
+        repositories::exchange_rate::upsert_rates(pool, records).await?;
+
+        Ok(rates)
+    }
+
+    /// Get the cached rates for a base currency, fetching them when missing or expired
+    async fn get_cached_rates(&self, base_currency: CurrencyCode) -> Result<CachedRates, ApiError> {
         // Check cache first
         {
             let cache_read = self.cache.read().await;
@@ -70,17 +862,51 @@ impl ExchangeRateService {
                         "Using cached exchange rates for base {}",
                         base_currency.as_str()
                     );
-                    return Ok(cached.rates.clone());
+                    return Ok(cached.clone());
                 }
             }
         }
 
-        // Fetch fresh rates
+        // Fetch fresh rates, sharing the upstream call with concurrent requests
+        let rates = self
+            .coalescer
+            .fetch(base_currency, || async {
+                // A fetch that finished while we were waiting may have filled the cache
+                if let Some(cached) = self.cache.read().await.get(&base_currency)
+                    && cached.timestamp.elapsed() < self.cache_duration
+                {
+                    return Ok(cached.rates.clone());
+                }
+
+                self.fetch_into_cache(base_currency).await
+            })
+            .await?;
+
+        // Callers that shared the fetch read its time from the cache it filled
+        let fetched_at = self
+            .cache
+            .read()
+            .await
+            .get(&base_currency)
+            .map_or_else(Utc::now, |cached| cached.fetched_at);
+
+        Ok(CachedRates {
+            rates,
+            timestamp: std::time::Instant::now(),
+            fetched_at,
+        })
+    }
+
+    /// Fetch the rates for a base currency from the provider and cache them
+    async fn fetch_into_cache(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
         tracing::info!(
             "Fetching fresh exchange rates from API for base {}",
             base_currency.as_str()
         );
-        let rates = self.fetch_rates(base_currency).await?;
+        let rates = self.provider.fetch_rates(base_currency).await?;
 
         // Update cache for this specific base currency
         {
@@ -90,6 +916,7 @@ impl ExchangeRateService {
                 CachedRates {
                     rates: rates.clone(),
                     timestamp: std::time::Instant::now(),
+                    fetched_at: Utc::now(),
                 },
             );
         }
@@ -97,71 +924,83 @@ impl ExchangeRateService {
         Ok(rates)
     }
 
-    /// Fetch exchange rates from the API
-    async fn fetch_rates(
+    /// Refresh the rates between every currency an account is held in and the
+    /// primary currency
+    ///
+    /// Each base currency's rates are fetched even when cached, replacing the
+    /// cached rates, and today's rate for every pair of currencies in use is
+    /// stored. A base whose fetch fails is skipped. Returns the pairs refreshed.
+    pub async fn refresh_rates_in_use(
         &self,
-        base_currency: CurrencyCode,
-    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
-        let url = format!(
-            "https://v6.exchangerate-api.com/v6/{}/latest/{}",
-            self.api_key,
-            base_currency.as_str()
-        );
-
-        let response = reqwest::get(&url).await.map_err(|e| {
-            tracing::error!("Failed to fetch exchange rates: {}", e);
-            ApiError::Internal
-        })?;
-
-        if !response.status().is_success() {
-            tracing::error!(
-                "Exchange rate API returned error status: {}",
-                response.status()
-            );
-            return Err(ApiError::Internal);
-        }
-
-        let data: ExchangeRateResponse = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse exchange rate response: {}", e);
-            ApiError::Internal
-        })?;
-
-        if data.result != "success" {
-            tracing::error!("Exchange rate API returned error: {:?}", data.error_type);
-            return Err(ApiError::Internal);
-        }
+        pool: &DbPool,
+    ) -> Result<Vec<(CurrencyCode, CurrencyCode)>, ApiError> {
+        let mut currencies = repositories::account::list_currencies_in_use(pool).await?;
+        currencies.push(PRIMARY_CURRENCY);
+        currencies.sort_by_key(|currency| currency.as_str());
+        currencies.dedup();
 
-        let conversion_rates = data.conversion_rates.ok_or_else(|| {
-            tracing::error!("No conversion rates in API response");
-            ApiError::Internal
-        })?;
+        let today = Utc::now().date_naive();
+        let mut refreshed = Vec::new();
+        for &base_currency in &currencies {
+            let quotes = currencies.iter().filter(|quote| **quote != base_currency);
+            let rates = match self
+                .coalescer
+                .fetch(base_currency, || self.fetch_into_cache(base_currency))
+                .await
+            {
+                Ok(rates) => rates,
+                Err(e) => {
+                    for quote in quotes {
+                        tracing::error!(
+                            "Refreshing exchange rate from {} to {} failed: {}",
+                            base_currency.as_str(),
+                            quote.as_str(),
+                            e
+                        );
+                    }
+                    continue;
+                }
+            };
 
-        // Convert to our format - iterate through all supported currency codes
-        let mut rates = HashMap::new();
-
-        let supported_currencies = [
-            CurrencyCode::Eur,
-            CurrencyCode::Usd,
-            CurrencyCode::Gbp,
-            CurrencyCode::Jpy,
-            CurrencyCode::Cad,
-            CurrencyCode::Aud,
-            CurrencyCode::Inr,
-        ];
-
-        for currency in supported_currencies {
-            if let Some(&rate) = conversion_rates.get(currency.as_str()) {
-                // Convert f64 to BigDecimal properly to preserve decimal places
-                let rate_str = rate.to_string();
-                let rate_decimal = BigDecimal::from_str(&rate_str).map_err(|e| {
-                    tracing::error!("Failed to convert rate {} to BigDecimal: {}", rate, e);
-                    ApiError::Internal
-                })?;
-                rates.insert(currency, rate_decimal);
+            let mut records = Vec::new();
+            for &quote in quotes {
+                match rates.get(&quote) {
+                    Some(rate) => records.push(NewExchangeRateRecord {
+                        base_currency,
+                        quote_currency: quote,
+                        rate_date: today,
+                        rate: rate.clone(),
+                    }),
+                    None => tracing::warn!(
+                        "Refreshing exchange rate from {} to {} failed: the provider has no rate",
+                        base_currency.as_str(),
+                        quote.as_str()
+                    ),
+                }
             }
+            let pairs: Vec<_> = records
+                .iter()
+                .map(|record| (record.base_currency, record.quote_currency))
+                .collect();
+            if let Err(e) = repositories::exchange_rate::upsert_rates(pool, records).await {
+                tracing::error!(
+                    "Storing refreshed exchange rates for base {} failed: {}",
+                    base_currency.as_str(),
+                    e
+                );
+                continue;
+            }
+            for (base, quote) in &pairs {
+                tracing::info!(
+                    "Refreshed exchange rate from {} to {}",
+                    base.as_str(),
+                    quote.as_str()
+                );
+            }
+            refreshed.extend(pairs);
         }
 
-        Ok(rates)
+        Ok(refreshed)
     }
 
     /// Convert an amount from one currency to another
@@ -178,21 +1017,11 @@ impl ExchangeRateService {
             return Ok(amount.clone());
         }
 
-        // Fetch rates with source currency as base for direct conversion
-        let rates = self.get_exchange_rates(from_currency).await?;
-
-        // Get the direct conversion rate from source to target
-        let to_rate = rates.get(&to_currency).ok_or_else(|| {
-            tracing::error!(
-                "No exchange rate found for {} to {}",
-                from_currency.as_str(),
-                to_currency.as_str()
-            );
-            ApiError::Internal
-        })?;
+        // Rates with the source currency as base give a direct conversion
+        let to_rate = self.get_rate(from_currency, to_currency).await?.rate;
 
         // Direct conversion: amount_in_from * rate_to_target
-        let converted_amount = amount * to_rate;
+        let converted_amount = amount * &to_rate;
 
         tracing::debug!(
             "Converted {} {} to {} {} (rate: {})",
@@ -206,6 +1035,28 @@ impl ExchangeRateService {
         Ok(converted_amount)
     }
 
+    /// Convert an amount from one currency to another at the rate on `date`
+    ///
+    /// See [`Self::get_rate_on`] for how the rate is found.
+    pub async fn convert_currency_on(
+        &self,
+        pool: &DbPool,
+        amount: &BigDecimal,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<BigDecimal, ApiError> {
+        if from_currency == to_currency {
+            return Ok(amount.clone());
+        }
+
+        let rate = self
+            .get_rate_on(pool, from_currency, to_currency, date)
+            .await?
+            .rate;
+        Ok(amount * &rate)
+    }
+
     /// Convert an amount to the primary currency
     pub async fn convert_to_primary_currency(
         &self,
@@ -217,6 +1068,37 @@ impl ExchangeRateService {
     }
 }
 
+/// Refresh the rates between the currencies in use every
+/// `config.interval_minutes`, so requests find them cached
+///
+/// Does nothing when disabled, or without an available provider.
+pub fn spawn_refresh(pool: DbPool, config: ExchangeRateRefreshConfig) {
+    if !config.enabled || config.interval_minutes == 0 {
+        tracing::info!("Exchange rate refresh disabled");
+        return;
+    }
+    let service = match ExchangeRateService::new() {
+        Ok(service) => service,
+        Err(_) => {
+            tracing::warn!(
+                "Exchange rate refresh disabled: no exchange rate provider is available"
+            );
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(config.interval_minutes * 60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = service.refresh_rates_in_use(&pool).await {
+                tracing::error!("Exchange rate refresh failed: {}", e);
+            }
+        }
+    });
+}
+
 impl Default for ExchangeRateService {
     fn default() -> Self {
         Self::new().expect("Failed to create ExchangeRateService")
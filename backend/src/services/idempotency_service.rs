@@ -0,0 +1,185 @@
+//! Idempotency keys of create requests
+//!
+//! A request sent with an `Idempotency-Key` claims the key before it runs, and
+//! stores its response once it completes. A retry with the same key gets the
+//! stored response back; a retry while the first request is still running, or
+//! with a different request, is refused. Keys expire after [`KEY_TTL_HOURS`].
+
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+use diesel::Connection;
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::idempotency_key::{IdempotencyScope, NewIdempotencyKey},
+    repositories::idempotency_key,
+};
+
+/// How long a key is kept; a retry after this runs the request again
+pub const KEY_TTL_HOURS: i64 = 24;
+
+/// How often expired keys are purged
+const PURGE_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+
+/// State of a key when a request using it arrives
+#[derive(Debug)]
+pub enum Claim {
+    /// The key was unused: the request goes ahead and its response is stored
+    Claimed,
+    /// The key's request completed with this response
+    Completed { status: u16, body: String },
+}
+
+/// Claim a key for a request, or find the response of the request that used it
+///
+/// Concurrent requests with the same key are serialized by the key's primary
+/// key: only one claims it, and the others are refused with `409 Conflict`
+/// until it completes. Reusing a key for a request with a different
+/// `request_hash` is refused the same way.
+pub async fn claim(
+    pool: &DbPool,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: String,
+    request_hash: String,
+) -> Result<Claim, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            // An expired key that has not been purged yet is free to reuse
+            let cutoff = Utc::now() - Duration::hours(KEY_TTL_HOURS);
+            if let Some(existing) = idempotency_key::find(conn, user_id, scope, &key)?
+                && existing.created_at < cutoff
+            {
+                idempotency_key::delete(conn, user_id, scope, &key)?;
+            }
+
+            let new_key = NewIdempotencyKey {
+                user_id,
+                scope: scope.as_str().to_string(),
+                key: key.clone(),
+                request_hash: Some(request_hash.clone()),
+            };
+            if idempotency_key::record(conn, &new_key)? {
+                return Ok(Claim::Claimed);
+            }
+
+            let existing = idempotency_key::find(conn, user_id, scope, &key)?;
+            let Some(existing) = existing else {
+                return Err(ApiError::Conflict(
+                    "A request with this Idempotency-Key is already in progress".to_string(),
+                ));
+            };
+            if existing.request_hash.as_deref() != Some(request_hash.as_str()) {
+                return Err(ApiError::Conflict(
+                    "Idempotency-Key was already used for a different request".to_string(),
+                ));
+            }
+
+            match existing.response_status {
+                Some(status) => Ok(Claim::Completed {
+                    status: status as u16,
+                    body: existing.response_body.unwrap_or_default(),
+                }),
+                None => Err(ApiError::Conflict(
+                    "A request with this Idempotency-Key is already in progress".to_string(),
+                )),
+            }
+        })
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Store the response of the request that claimed a key
+pub async fn complete(
+    pool: &DbPool,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: String,
+    status: u16,
+    body: String,
+) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        idempotency_key::store_response(&mut conn, user_id, scope, &key, status as i16, &body)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })?
+}
+
+/// Give up a claimed key, so the request can be retried with it
+pub async fn release(
+    pool: &DbPool,
+    user_id: Uuid,
+    scope: IdempotencyScope,
+    key: String,
+) -> Result<(), ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    tokio::task::spawn_blocking(move || idempotency_key::delete(&mut conn, user_id, scope, &key))
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::Internal
+        })?
+}
+
+/// Delete keys older than [`KEY_TTL_HOURS`]
+///
+/// # Returns
+/// * `Result<usize, ApiError>` - Number of purged keys
+pub async fn purge_expired(pool: &DbPool) -> Result<usize, ApiError> {
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+
+    let cutoff = Utc::now() - Duration::hours(KEY_TTL_HOURS);
+    let purged =
+        tokio::task::spawn_blocking(move || idempotency_key::delete_older_than(&mut conn, cutoff))
+            .await
+            .map_err(|e| {
+                tracing::error!("Task join error: {}", e);
+                ApiError::Internal
+            })??;
+
+    if purged > 0 {
+        tracing::info!("Purged {} expired idempotency keys", purged);
+    }
+
+    Ok(purged)
+}
+
+/// Spawn a background task that periodically purges expired idempotency keys
+pub fn spawn_expiry_purge(pool: DbPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge_expired(&pool).await {
+                tracing::error!("Idempotency key purge failed: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,329 @@
+//! Import profiles
+//!
+//! An import profile saves how to read a bank's CSV statements: the header of
+//! the column each transaction field comes from, the format of its dates, and
+//! whether money out is a negative amount or a separate debit column. Imports
+//! can name a profile instead of sending the mapping every time.
+
+use chrono::format::{Item, StrftimeItems};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        AmountSign, CreateImportProfileRequest, CsvFormat, CsvMapping, ImportProfile,
+        ImportProfileResponse, NewImportProfile, UpdateImportProfile, UpdateImportProfileRequest,
+    },
+    repositories,
+};
+
+/// Check that a format maps every field its amount sign needs
+///
+/// The date and description are always required; the amount column is required
+/// with negative debits, and the debit and credit columns with separate
+/// columns. Whether the mapped headers exist is checked against each file.
+pub fn validate_format(format: &CsvFormat) -> Result<(), ApiError> {
+    let mapping = &format.mapping;
+    if mapping
+        .fields()
+        .iter()
+        .any(|(_, header)| header.trim().is_empty())
+    {
+        return Err(ApiError::Validation(
+            "Mapped column headers cannot be empty".to_string(),
+        ));
+    }
+
+    match format.amount_sign {
+        AmountSign::NegativeDebits => {
+            if mapping.amount.is_none() {
+                return Err(ApiError::Validation(
+                    "mapping.amount is required when amount_sign is negative_debits".to_string(),
+                ));
+            }
+            if mapping.debit.is_some() || mapping.credit.is_some() {
+                return Err(ApiError::Validation(
+                    "mapping.debit and mapping.credit require amount_sign debit_credit_columns"
+                        .to_string(),
+                ));
+            }
+        }
+        AmountSign::DebitCreditColumns => {
+            if mapping.debit.is_none() || mapping.credit.is_none() {
+                return Err(ApiError::Validation(
+                    "mapping.debit and mapping.credit are required when amount_sign is debit_credit_columns"
+                        .to_string(),
+                ));
+            }
+            if mapping.amount.is_some() {
+                return Err(ApiError::Validation(
+                    "mapping.amount cannot be used when amount_sign is debit_credit_columns"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    if let Some(date_format) = &format.date_format
+        && StrftimeItems::new(date_format).any(|item| item == Item::Error)
+    {
+        return Err(ApiError::Validation(format!(
+            "Invalid date_format '{}'",
+            date_format
+        )));
+    }
+
+    Ok(())
+}
+
+/// The format an import reads its file with, if it is not the default one
+///
+/// A profile gives a saved format, of which `mapping`, `date_format` and
+/// `amount_sign` replace the parts they are given for; without a profile, a
+/// `mapping` is needed for the other two to apply.
+pub async fn resolve_format(
+    pool: &DbPool,
+    user_id: Uuid,
+    profile_id: Option<Uuid>,
+    mapping: Option<CsvMapping>,
+    date_format: Option<String>,
+    amount_sign: Option<AmountSign>,
+) -> Result<Option<CsvFormat>, ApiError> {
+    let format = match (profile_id, mapping) {
+        (Some(profile_id), mapping) => {
+            let mut format = get_profile(pool, profile_id, user_id).await?.format();
+            if let Some(mapping) = mapping {
+                format.mapping = mapping;
+            }
+            if date_format.is_some() {
+                format.date_format = date_format;
+            }
+            if let Some(amount_sign) = amount_sign {
+                format.amount_sign = amount_sign;
+            }
+            format
+        }
+        (None, Some(mapping)) => CsvFormat {
+            mapping,
+            date_format,
+            amount_sign: amount_sign.unwrap_or_default(),
+        },
+        (None, None) if date_format.is_some() || amount_sign.is_some() => {
+            return Err(ApiError::Validation(
+                "date_format and amount_sign require a mapping or profile_id".to_string(),
+            ));
+        }
+        (None, None) => return Ok(None),
+    };
+    validate_format(&format)?;
+
+    Ok(Some(format))
+}
+
+/// Create an import profile
+///
+/// Profile names are unique per user, ignoring case.
+pub async fn create_profile(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateImportProfileRequest,
+) -> Result<ImportProfileResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Import profile validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+    let format = CsvFormat {
+        mapping: request.mapping,
+        date_format: request.date_format,
+        amount_sign: request.amount_sign,
+    };
+    validate_format(&format)?;
+
+    let name = profile_name(&request.name)?;
+    verify_unique_name(pool, user_id, &name, None).await?;
+
+    let new_profile = NewImportProfile {
+        user_id,
+        name,
+        mapping: mapping_to_json(&format)?,
+        date_format: format.date_format,
+        amount_sign: format.amount_sign.as_str().to_string(),
+    };
+    let profile = repositories::import_profile::create_profile(pool, new_profile).await?;
+
+    tracing::info!("Created import profile {} for user {}", profile.id, user_id);
+
+    to_response(profile)
+}
+
+/// List all import profiles of a user by name
+pub async fn list_profiles(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<ImportProfileResponse>, ApiError> {
+    let profiles = repositories::import_profile::list_by_user(pool, user_id).await?;
+
+    profiles.into_iter().map(to_response).collect()
+}
+
+/// Get an import profile
+pub async fn get_profile(
+    pool: &DbPool,
+    profile_id: Uuid,
+    user_id: Uuid,
+) -> Result<ImportProfileResponse, ApiError> {
+    let profile = find_owned(pool, profile_id, user_id).await?;
+
+    to_response(profile)
+}
+
+/// Update an import profile
+pub async fn update_profile(
+    pool: &DbPool,
+    profile_id: Uuid,
+    user_id: Uuid,
+    request: UpdateImportProfileRequest,
+) -> Result<ImportProfileResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Import profile validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let profile = to_response(find_owned(pool, profile_id, user_id).await?)?;
+
+    let format = CsvFormat {
+        mapping: request.mapping.unwrap_or(profile.mapping),
+        date_format: request.date_format.or(profile.date_format),
+        amount_sign: request.amount_sign.unwrap_or(profile.amount_sign),
+    };
+    validate_format(&format)?;
+
+    let name = match request.name {
+        Some(name) => {
+            let name = profile_name(&name)?;
+            verify_unique_name(pool, user_id, &name, Some(profile_id)).await?;
+            name
+        }
+        None => profile.name,
+    };
+
+    let updates = UpdateImportProfile {
+        name,
+        mapping: mapping_to_json(&format)?,
+        date_format: format.date_format,
+        amount_sign: format.amount_sign.as_str().to_string(),
+    };
+    let profile = repositories::import_profile::update_profile(pool, profile_id, updates).await?;
+
+    tracing::info!("Updated import profile {} for user {}", profile_id, user_id);
+
+    to_response(profile)
+}
+
+/// Delete an import profile
+pub async fn delete_profile(
+    pool: &DbPool,
+    profile_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), ApiError> {
+    find_owned(pool, profile_id, user_id).await?;
+
+    repositories::import_profile::delete_profile(pool, profile_id).await?;
+
+    tracing::info!("Deleted import profile {} for user {}", profile_id, user_id);
+
+    Ok(())
+}
+
+/// Find an import profile, checking that it belongs to the user
+async fn find_owned(
+    pool: &DbPool,
+    profile_id: Uuid,
+    user_id: Uuid,
+) -> Result<ImportProfile, ApiError> {
+    let profile = repositories::import_profile::find_by_id(pool, profile_id).await?;
+    if profile.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to access import profile {} owned by {}",
+            user_id,
+            profile_id,
+            profile.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(profile)
+}
+
+/// Check that no other profile of the user has the name, ignoring case
+async fn verify_unique_name(
+    pool: &DbPool,
+    user_id: Uuid,
+    name: &str,
+    profile_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let taken = repositories::import_profile::list_by_user(pool, user_id)
+        .await?
+        .iter()
+        .any(|profile| {
+            Some(profile.id) != profile_id && profile.name.to_lowercase() == name.to_lowercase()
+        });
+    if taken {
+        return Err(ApiError::Conflict(format!(
+            "An import profile named '{}' already exists",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+/// A profile name without surrounding whitespace, which must leave some text
+fn profile_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::Validation("Name cannot be blank".to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+fn mapping_to_json(format: &CsvFormat) -> Result<serde_json::Value, ApiError> {
+    serde_json::to_value(&format.mapping).map_err(|e| {
+        tracing::error!("Failed to serialize import profile mapping: {}", e);
+        ApiError::Internal
+    })
+}
+
+fn to_response(profile: ImportProfile) -> Result<ImportProfileResponse, ApiError> {
+    let mapping = serde_json::from_value(profile.mapping).map_err(|e| {
+        tracing::error!(
+            "Invalid mapping stored in import profile {}: {}",
+            profile.id,
+            e
+        );
+        ApiError::Internal
+    })?;
+    let amount_sign = profile.amount_sign.parse().map_err(|e| {
+        tracing::error!(
+            "Invalid amount sign stored in import profile {}: {}",
+            profile.id,
+            e
+        );
+        ApiError::Internal
+    })?;
+
+    Ok(ImportProfileResponse {
+        id: profile.id,
+        name: profile.name,
+        mapping,
+        date_format: profile.date_format,
+        amount_sign,
+        created_at: profile.created_at,
+        updated_at: profile.updated_at,
+    })
+}
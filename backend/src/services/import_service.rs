@@ -1,23 +1,182 @@
 //! Import service for handling transaction imports
 //!
 //! This module provides functionality for:
+//! - Resolution of category and account names in parsed statements
 //! - Duplicate detection against existing transactions
 //! - Summary calculation for parsed transactions
 //! - Import validation and orchestration
+//! - Idempotent import of aggregator exports, keyed by external transaction ID
+//! - Streamed CSV imports saved in batches as the file is uploaded, skipping
+//!   rows that duplicate existing transactions, or previewed without saving
+//!   anything
+//! - OFX/QFX statement imports, keyed by the bank's transaction ID
 
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 use std::str::FromStr;
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
     db::DbPool,
     errors::ApiError,
-    models::{DuplicateMatch, ImportSummary, ParsedTransaction, TransactionFilter},
-    services::transaction_service,
-    types::ConfidenceLevel,
+    models::{
+        Account, AccountMatch, AggregatorAccount, AggregatorAccountResult, AggregatorImportData,
+        AggregatorImportError, AggregatorImportRequest, AggregatorTransaction,
+        CreateAccountRequest, CsvFormat, CsvImportBatchError, CsvImportData, CsvImportDuplicate,
+        CsvImportPreview, DuplicateMatch, ImportCategorySource, ImportErrorMode,
+        ImportPreviewTransaction, ImportSummary, NewCategory, NewTransaction, OfxImportData,
+        OfxImportError, ParsedTransaction, TagMode, Transaction, TransactionFilter,
+        UpdateTransaction, parser_error::ParserError,
+    },
+    repositories,
+    services::{
+        account_service,
+        allocation_rule_service::{self, AccountAllocation},
+        category_rule_service::CompiledRules,
+        csv_parser_service::{CSVStatementParser, CsvRecordStream, ParsedRecord, StatementParser},
+        ofx_parser_service::{OfxStatement, OfxStatementParser},
+        transaction_service,
+    },
+    types::{AccountType, ConfidenceLevel, CurrencyCode, TransactionStatus},
 };
 
+/// Icon given to categories created during a statement import
+const IMPORTED_CATEGORY_ICON: &str = "🏷️";
+
+/// Color given to categories created during a statement import
+const IMPORTED_CATEGORY_COLOR: &str = "#9E9E9E";
+
+/// Longest category name accepted when creating categories
+const MAX_CATEGORY_NAME_LENGTH: usize = 100;
+
+/// Longest `external_id` a transaction can be stored with
+const MAX_EXTERNAL_ID_LENGTH: usize = 255;
+
+/// Prefix of the `external_id` of transactions imported from OFX/QFX statements
+const OFX_EXTERNAL_ID_PREFIX: &str = "ofx";
+
+/// Resolve the category and account names of parsed transactions
+///
+/// Names are matched case-insensitively, ignoring surrounding whitespace,
+/// against the user's categories and accounts, which are each loaded once.
+///
+/// Every account name must match an existing account; otherwise the whole
+/// import fails with an error listing each offending line. Unknown categories
+/// are created with a default icon and color when `auto_create_categories` is
+/// set, and mark the transaction invalid otherwise.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `user_id` - User whose categories and accounts are matched
+/// * `transactions` - Mutable slice of parsed transactions, in file order
+/// * `auto_create_categories` - Whether to create missing categories
+///
+/// # Errors
+///
+/// - Validation error if an account name matches no account
+/// - Internal errors for database failures
+pub async fn resolve_names(
+    pool: &DbPool,
+    user_id: Uuid,
+    transactions: &mut [ParsedTransaction],
+    auto_create_categories: bool,
+) -> Result<(), ApiError> {
+    if transactions.iter().any(|t| t.account_name.is_some()) {
+        let mut accounts = HashMap::new();
+        for account in repositories::account::list_by_user(pool, user_id).await? {
+            accounts
+                .entry(normalize_name(&account.name))
+                .or_insert(account.id);
+        }
+
+        let mut errors = Vec::new();
+        for (index, transaction) in transactions.iter_mut().enumerate() {
+            let Some(name) = &transaction.account_name else {
+                continue;
+            };
+            match accounts.get(&normalize_name(name)) {
+                Some(account_id) => transaction.account_id = Some(*account_id),
+                // +2 for the header and 0-indexing, matching the parser's line numbers
+                None => errors.push(format!("line {}: account '{}' not found", index + 2, name)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ApiError::Validation(format!(
+                "Unknown accounts: {}",
+                errors.join("; ")
+            )));
+        }
+    }
+
+    if transactions.iter().any(|t| t.category_name.is_some()) {
+        let mut categories = HashMap::new();
+        for category in repositories::category::list_by_user(pool, user_id).await? {
+            categories
+                .entry(normalize_name(&category.name))
+                .or_insert(category.id);
+        }
+
+        for transaction in transactions.iter_mut() {
+            let Some(name) = transaction.category_name.clone() else {
+                continue;
+            };
+            let key = normalize_name(&name);
+            let error = match categories.get(&key) {
+                Some(category_id) => {
+                    transaction.category_id = Some(*category_id);
+                    continue;
+                }
+                None if !auto_create_categories => format!("Category '{}' not found", name),
+                None if name.chars().count() > MAX_CATEGORY_NAME_LENGTH => format!(
+                    "Category name exceeds {} characters",
+                    MAX_CATEGORY_NAME_LENGTH
+                ),
+                None => {
+                    let category_id = create_imported_category(pool, user_id, &name).await?;
+                    categories.insert(key, category_id);
+                    transaction.category_id = Some(category_id);
+                    continue;
+                }
+            };
+
+            transaction.is_valid = false;
+            transaction
+                .validation_errors
+                .get_or_insert_with(Vec::new)
+                .push(error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a category named in a statement, with the default import icon and color
+async fn create_imported_category(
+    pool: &DbPool,
+    user_id: Uuid,
+    name: &str,
+) -> Result<Uuid, ApiError> {
+    let new_category = NewCategory {
+        user_id,
+        name: name.trim().to_string(),
+        icon: Some(IMPORTED_CATEGORY_ICON.to_string()),
+        color: Some(IMPORTED_CATEGORY_COLOR.to_string()),
+        parent_id: None,
+    };
+    let category = repositories::category::create_category(pool, user_id, new_category).await?;
+    tracing::info!(
+        "Created category {} ('{}') during import for user {}",
+        category.id,
+        category.name,
+        user_id
+    );
+
+    Ok(category.id)
+}
+
 /// Check for potential duplicate transactions against database
 ///
 /// Strategy:
@@ -32,7 +191,7 @@ use crate::{
 ///
 /// * `pool` - Database connection pool
 /// * `user_id` - User ID for filtering transactions
-/// * `account_id` - Account ID for filtering transactions
+/// * `account_id` - Account of transactions without a resolved `account_id`
 /// * `transactions` - Mutable slice of parsed transactions to check
 ///
 /// # Errors
@@ -66,19 +225,40 @@ pub async fn check_duplicates(
         .succ_opt() // Add 1 day
         .unwrap_or_else(|| Utc::now().date_naive());
 
+    // Transactions may name their own account; only filter when they share one
+    let accounts: HashSet<Uuid> = transactions
+        .iter()
+        .map(|t| t.account_id.unwrap_or(account_id))
+        .collect();
+    let account_filter = match accounts.len() {
+        1 => accounts.into_iter().next(),
+        _ => None,
+    };
+
     // Fetch existing transactions for the account in the extended date range
     // Use high limit (1000) for duplicate detection to check against all existing transactions
     let existing = transaction_service::list_transactions(
         pool,
         user_id,
         TransactionFilter {
-            account_id: Some(account_id),
+            account_id: account_filter,
             category_id: None,
+            uncategorized: false,
+            include_deleted: false,
             start_date: Some(start_date.and_hms_opt(0, 0, 0).unwrap().and_utc()),
             end_date: Some(end_date.and_hms_opt(23, 59, 59).unwrap().and_utc()),
             min_amount: None,
             max_amount: None,
+            transaction_type: None,
+            updated_since: None,
+            status: None,
             search: None,
+            merchant: None,
+            tags: None,
+            tag_mode: TagMode::default(),
+            reimbursable: None,
+            reimbursed: None,
+            sort: None,
             limit: Some(1000),
             offset: None,
         },
@@ -89,12 +269,15 @@ pub async fn check_duplicates(
     for parsed in transactions.iter_mut() {
         let mut best_match: Option<(Uuid, ConfidenceLevel, Vec<String>, DateTime<Utc>)> = None;
 
+        let parsed_account_id = parsed.account_id.unwrap_or(account_id);
+
         for existing_tx in &existing {
-            let existing_amount =
-                BigDecimal::from_str(&existing_tx.amount).map_err(|_| ApiError::Internal)?;
+            if existing_tx.account_id != parsed_account_id {
+                continue;
+            }
 
             // Check amount match first (most distinctive)
-            if parsed.amount != existing_amount {
+            if parsed.amount != *existing_tx.amount.as_decimal() {
                 continue;
             }
 
@@ -202,3 +385,1239 @@ pub fn calculate_summary(transactions: &[ParsedTransaction]) -> ImportSummary {
         invalid,
     }
 }
+
+/// Local account for an external account (or why there is none) and how it was matched
+type AccountResolution = (Result<Account, String>, Option<AccountMatch>);
+
+/// Outcome of importing a single aggregator transaction
+enum ImportOutcome {
+    Created,
+    Updated,
+    Skipped,
+}
+
+/// Import accounts and transactions from a Plaid-style aggregator export
+///
+/// External accounts are mapped to local accounts through the explicit
+/// `account_mapping` first, then by case-insensitive name among the user's
+/// accounts. Unmatched accounts are created when requested.
+///
+/// Each transaction is keyed by its aggregator ID (stored as `external_id`), so
+/// re-importing the same export is idempotent: unchanged transactions are
+/// skipped and changed ones are updated in place. Aggregator amounts are
+/// positive for money leaving the account and are negated on import.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `user_id` - User importing the export
+/// * `request` - Aggregator accounts, transactions and mapping options
+///
+/// # Errors
+///
+/// - Validation errors for malformed requests or mappings to unknown accounts
+/// - Internal errors for database failures
+///
+/// Problems with individual accounts or transactions are reported in the
+/// returned data instead of failing the whole import.
+pub async fn import_aggregator(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: AggregatorImportRequest,
+) -> Result<AggregatorImportData, ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Aggregator import validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    for external_id in request.account_mapping.keys() {
+        if !request
+            .accounts
+            .iter()
+            .any(|a| &a.account_id == external_id)
+        {
+            return Err(ApiError::Validation(format!(
+                "Mapping references unknown external account {}",
+                external_id
+            )));
+        }
+    }
+
+    // Resolve every external account to a local account up front
+    let mut local_accounts = repositories::account::list_by_user(pool, user_id).await?;
+    let mut resolved: HashMap<String, Result<Account, String>> = HashMap::new();
+    let mut account_results = Vec::new();
+
+    for external in &request.accounts {
+        let (resolution, matched_by) =
+            resolve_account(pool, user_id, external, &request, &mut local_accounts).await?;
+
+        account_results.push(AggregatorAccountResult {
+            external_account_id: external.account_id.clone(),
+            account_id: resolution.as_ref().ok().map(|a| a.id),
+            matched_by,
+            error: resolution.as_ref().err().cloned(),
+        });
+        resolved.insert(external.account_id.clone(), resolution);
+    }
+
+    let external_ids = request
+        .transactions
+        .iter()
+        .map(|t| t.transaction_id.clone())
+        .collect();
+    let existing: HashMap<String, Transaction> =
+        repositories::transaction::find_by_external_ids(pool, user_id, external_ids)
+            .await?
+            .into_iter()
+            .filter_map(|t| t.external_id.clone().map(|id| (id, t)))
+            .collect();
+
+    let mut data = AggregatorImportData {
+        created: 0,
+        updated: 0,
+        skipped: 0,
+        failed: 0,
+        accounts: account_results,
+        errors: None,
+    };
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for transaction in &request.transactions {
+        // Repeated IDs within one export are imported once
+        if !seen.insert(transaction.transaction_id.as_str()) || transaction.pending {
+            data.skipped += 1;
+            continue;
+        }
+
+        let external_account = request
+            .accounts
+            .iter()
+            .find(|a| a.account_id == transaction.account_id);
+        let outcome = match (external_account, resolved.get(&transaction.account_id)) {
+            (Some(external), Some(Ok(account))) => {
+                import_transaction(
+                    pool,
+                    user_id,
+                    transaction,
+                    external,
+                    account,
+                    existing.get(&transaction.transaction_id),
+                )
+                .await
+            }
+            (_, Some(Err(reason))) => Err(reason.clone()),
+            _ => Err(format!(
+                "Unknown external account {}",
+                transaction.account_id
+            )),
+        };
+
+        match outcome {
+            Ok(ImportOutcome::Created) => data.created += 1,
+            Ok(ImportOutcome::Updated) => data.updated += 1,
+            Ok(ImportOutcome::Skipped) => data.skipped += 1,
+            Err(error) => {
+                data.failed += 1;
+                errors.push(AggregatorImportError {
+                    transaction_id: transaction.transaction_id.clone(),
+                    error,
+                });
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        data.errors = Some(errors);
+    }
+
+    tracing::info!(
+        "Aggregator import for user {}: {} created, {} updated, {} skipped, {} failed",
+        user_id,
+        data.created,
+        data.updated,
+        data.skipped,
+        data.failed
+    );
+
+    Ok(data)
+}
+
+/// Resolve an external account to a local account
+///
+/// Returns the local account, or the reason its transactions cannot be
+/// imported, together with how the account was matched.
+async fn resolve_account(
+    pool: &DbPool,
+    user_id: Uuid,
+    external: &AggregatorAccount,
+    request: &AggregatorImportRequest,
+    local_accounts: &mut Vec<Account>,
+) -> Result<AccountResolution, ApiError> {
+    let currency = match external.balances.iso_currency_code.as_deref() {
+        Some(code) => match CurrencyCode::from_str(code) {
+            Ok(currency) => Some(currency),
+            Err(e) => return Ok((Err(e.to_string()), None)),
+        },
+        None => None,
+    };
+
+    let matched = if let Some(account_id) = request.account_mapping.get(&external.account_id) {
+        let account = local_accounts
+            .iter()
+            .find(|a| a.id == *account_id)
+            .cloned()
+            .ok_or_else(|| {
+                ApiError::Validation(format!("Mapped account {} not found", account_id))
+            })?;
+        Some((account, AccountMatch::Mapping))
+    } else {
+        let names = [Some(&external.name), external.official_name.as_ref()];
+        let candidates: Vec<&Account> = local_accounts
+            .iter()
+            .filter(|a| {
+                names
+                    .iter()
+                    .flatten()
+                    .any(|name| a.name.trim().eq_ignore_ascii_case(name.trim()))
+            })
+            .collect();
+        match candidates.as_slice() {
+            [account] => Some(((*account).clone(), AccountMatch::Name)),
+            [] => None,
+            _ => {
+                return Ok((
+                    Err(format!(
+                        "Several accounts are named {}; provide an explicit mapping",
+                        external.name
+                    )),
+                    None,
+                ));
+            }
+        }
+    };
+
+    if let Some((account, matched_by)) = matched {
+        if let Some(currency) = currency
+            && currency != account.currency
+        {
+            return Ok((
+                Err(format!(
+                    "Currency mismatch: external account uses {}, local account uses {}",
+                    currency.as_str(),
+                    account.currency.as_str()
+                )),
+                Some(matched_by),
+            ));
+        }
+        return Ok((Ok(account), Some(matched_by)));
+    }
+
+    if !request.create_missing_accounts {
+        return Ok((Err("No matching local account".to_string()), None));
+    }
+
+    let Some(account_type) = map_account_type(
+        external.account_type.as_deref(),
+        external.subtype.as_deref(),
+    ) else {
+        return Ok((
+            Err(format!(
+                "Unsupported account type {}",
+                external.account_type.as_deref().unwrap_or("unknown")
+            )),
+            None,
+        ));
+    };
+
+    let created = match account_service::create_account(
+        pool,
+        user_id,
+        CreateAccountRequest {
+            name: external.name.clone(),
+            account_type,
+            currency,
+            initial_balance: None,
+            notes: None,
+            allow_overdraft: None,
+            overdraft_limit: None,
+            credit_limit: None,
+            interest_rate: None,
+            original_principal: None,
+        },
+    )
+    .await
+    {
+        Ok(created) => created,
+        Err(e) => return Ok((Err(e.to_string()), None)),
+    };
+    let account = repositories::account::find_by_id(pool, created.id).await?;
+
+    tracing::info!(
+        "Created account {} for external account {}",
+        account.id,
+        external.account_id
+    );
+
+    local_accounts.push(account.clone());
+    Ok((Ok(account), Some(AccountMatch::Created)))
+}
+
+/// Map an aggregator account type and subtype to a local account type
+fn map_account_type(account_type: Option<&str>, subtype: Option<&str>) -> Option<AccountType> {
+    match (account_type, subtype) {
+        (Some("depository"), Some("savings")) => Some(AccountType::Savings),
+        (Some("depository") | None, _) => Some(AccountType::Checking),
+        (Some("credit"), _) => Some(AccountType::CreditCard),
+        (Some("investment" | "brokerage"), _) => Some(AccountType::Investment),
+        (Some("loan"), Some("mortgage")) => Some(AccountType::Mortgage),
+        (Some("loan"), _) => Some(AccountType::Loan),
+        _ => None,
+    }
+}
+
+/// Create or update a single aggregator transaction
+///
+/// Only fields owned by the aggregator (account, title, amount and date) are
+/// updated, so local categories and notes survive re-imports.
+async fn import_transaction(
+    pool: &DbPool,
+    user_id: Uuid,
+    transaction: &AggregatorTransaction,
+    external: &AggregatorAccount,
+    account: &Account,
+    existing: Option<&Transaction>,
+) -> Result<ImportOutcome, String> {
+    let currency = transaction
+        .iso_currency_code
+        .as_deref()
+        .or(external.balances.iso_currency_code.as_deref())
+        .ok_or_else(|| "Missing currency".to_string())?;
+    let currency = CurrencyCode::from_str(currency).map_err(|e| e.to_string())?;
+    if currency != account.currency {
+        return Err(format!(
+            "Currency mismatch: transaction uses {}, account uses {}",
+            currency.as_str(),
+            account.currency.as_str()
+        ));
+    }
+
+    let amount = BigDecimal::from_str(&(-transaction.amount).to_string())
+        .map_err(|_| "Invalid amount".to_string())?
+        .round(2);
+    let title = transaction
+        .merchant_name
+        .clone()
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| transaction.name.clone());
+    let date = transaction.datetime.unwrap_or_else(|| {
+        transaction
+            .date
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc()
+    });
+
+    let Some(current) = existing else {
+        let new_transaction = NewTransaction {
+            user_id,
+            account_id: account.id,
+            category_id: None,
+            title,
+            amount: amount.clone(),
+            date,
+            notes: None,
+            external_id: Some(transaction.transaction_id.clone()),
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: transaction
+                .merchant_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(str::to_string),
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
+        };
+        let account = account.clone();
+        repositories::transaction::create_transaction_checked(
+            pool,
+            user_id,
+            new_transaction,
+            move |balance| account_service::project_balance(&account, balance, &amount),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        return Ok(ImportOutcome::Created);
+    };
+
+    if current.account_id == account.id
+        && current.title == title
+        && current.amount == amount
+        && current.date == date
+    {
+        return Ok(ImportOutcome::Skipped);
+    }
+
+    let updates = UpdateTransaction {
+        account_id: Some(account.id),
+        category_id: None,
+        title: Some(title),
+        amount: Some(amount.clone()),
+        date: Some(date),
+        notes: None,
+        original_currency: None,
+        original_amount: None,
+        exchange_rate: None,
+        merchant: None,
+        latitude: None,
+        longitude: None,
+        reimbursable: None,
+        expected_version: None,
+        splits: None,
+        tags: None,
+    };
+    let target = account.clone();
+    repositories::transaction::update_transaction_checked(
+        pool,
+        current.id,
+        updates,
+        target.id,
+        move |balance, current| {
+            let delta = if current.account_id == target.id {
+                &amount - &current.amount
+            } else {
+                amount
+            };
+            account_service::project_balance(&target, balance, &delta)
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(ImportOutcome::Updated)
+}
+
+/// Import the transactions of an OFX/QFX statement into an account
+///
+/// Each transaction is keyed by the account and the bank's `FITID` (stored as
+/// `external_id`), so re-importing a statement, or one overlapping it, skips
+/// the transactions imported before. Transactions failing validation are
+/// reported and not saved; the others are saved together in one database
+/// transaction, checked against the account's limit, and their income is
+/// allocated by the account's allocation rules.
+///
+/// # Errors
+///
+/// - NotFound or Forbidden error if the account is not one of the user's accounts
+/// - Validation error if the statement's currency is not the account's, or the
+///   new transactions would breach the account's limit
+/// - Internal errors for database failures
+pub async fn import_ofx(
+    pool: &DbPool,
+    user_id: Uuid,
+    account_id: Uuid,
+    statement: OfxStatement,
+) -> Result<OfxImportData, ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+    if account.user_id != user_id {
+        return Err(ApiError::Forbidden(
+            "Account does not belong to user".to_string(),
+        ));
+    }
+
+    if let Some(currency) = statement.currency
+        && currency != account.currency
+    {
+        return Err(ApiError::Validation(format!(
+            "Statement is in {} but account '{}' uses {}",
+            currency.as_str(),
+            account.name,
+            account.currency.as_str()
+        )));
+    }
+
+    let external_id =
+        |fit_id: &str| format!("{}:{}:{}", OFX_EXTERNAL_ID_PREFIX, account.id, fit_id);
+    let external_ids = statement
+        .transactions
+        .iter()
+        .map(|t| external_id(&t.fit_id))
+        .collect();
+    let mut imported: HashSet<String> =
+        repositories::transaction::find_by_external_ids(pool, user_id, external_ids)
+            .await?
+            .into_iter()
+            .filter_map(|t| t.external_id)
+            .collect();
+
+    let mut data = OfxImportData {
+        total: statement.transactions.len(),
+        ..Default::default()
+    };
+    let mut errors = Vec::new();
+    let mut new_transactions = Vec::new();
+
+    for transaction in &statement.transactions {
+        // Repeated IDs within one statement are imported once
+        let external_id = external_id(&transaction.fit_id);
+        if !imported.insert(external_id.clone()) {
+            data.skipped += 1;
+            continue;
+        }
+
+        let parsed = ParsedTransaction::from(transaction);
+        let mut messages: Vec<String> = OfxStatementParser
+            .validate(&parsed)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        if external_id.len() > MAX_EXTERNAL_ID_LENGTH {
+            messages.push("FITID is too long".to_string());
+        }
+        if !messages.is_empty() {
+            errors.push(OfxImportError {
+                fit_id: transaction.fit_id.clone(),
+                error: messages.join(", "),
+            });
+            continue;
+        }
+
+        new_transactions.push(NewTransaction {
+            user_id,
+            account_id: account.id,
+            category_id: None,
+            title: parsed.title,
+            amount: parsed.amount,
+            date: parsed.date,
+            notes: parsed.notes,
+            external_id: Some(external_id),
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
+        });
+    }
+
+    if !new_transactions.is_empty() {
+        // Each transaction is followed by the transfers allocating it, saved with it
+        let allocation = allocation_rule_service::for_account(pool, &account).await?;
+        let rows = new_transactions.len();
+        let new_transactions = new_transactions
+            .into_iter()
+            .flat_map(|new_transaction| {
+                let transfers = allocation.transfers(&new_transaction);
+                std::iter::once(new_transaction).chain(transfers)
+            })
+            .collect();
+
+        let checked_account = account.clone();
+        repositories::transaction::create_transactions_checked(
+            pool,
+            user_id,
+            new_transactions,
+            move |new_transaction, balance| {
+                if new_transaction.transfer_id.is_some() {
+                    return allocation.check_transfer(new_transaction, balance);
+                }
+                account_service::project_balance(&checked_account, balance, &new_transaction.amount)
+                    .map(|_| ())
+            },
+        )
+        .await?;
+        data.created = rows;
+    }
+
+    data.failed = errors.len();
+    if !errors.is_empty() {
+        data.errors = Some(errors);
+    }
+
+    tracing::info!(
+        "OFX import for user {} into account {}: {} created, {} skipped, {} failed",
+        user_id,
+        account.id,
+        data.created,
+        data.skipped,
+        data.failed
+    );
+
+    Ok(data)
+}
+
+/// Options of a streamed CSV import
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    /// Account of rows that do not name one
+    pub account_id: Uuid,
+    /// Whether to create categories named in the file that do not exist yet
+    pub auto_create_categories: bool,
+    /// Whether to stop at the first failed batch or skip it
+    pub on_error: ImportErrorMode,
+    /// Number of rows saved per database transaction
+    pub batch_size: usize,
+    /// Whether to only report what the import would save
+    pub dry_run: bool,
+    /// Columns of a file that is not in the default format
+    pub format: Option<CsvFormat>,
+    /// Days either side of a row's date searched for a transaction it duplicates
+    pub duplicate_window_days: i64,
+    /// Whether to save rows that duplicate existing transactions
+    pub force: bool,
+}
+
+/// CSV import that saves transactions in batches while the file is streamed in
+///
+/// Chunks of the file are fed with [`CsvImport::push`] as they are received, and
+/// every `batch_size` rows are saved in their own database transaction, so
+/// neither the file nor its transactions are ever held in memory as a whole and
+/// account locks are only held for one batch at a time.
+///
+/// A batch is saved entirely or not at all: a row that cannot be parsed or
+/// validated, names an unknown account or category, or would breach an account's
+/// limit fails its whole batch. With [`ImportErrorMode::Abort`] the import stops
+/// at the first failed batch; batches saved before it are kept. With
+/// [`ImportErrorMode::Continue`] failed batches are skipped.
+///
+/// A file read with a [`CsvFormat`] must have every column its mapping names,
+/// or the import fails before any row is saved; its amounts are in the currency
+/// of their account.
+///
+/// Names are resolved like [`resolve_names`], against the user's accounts and
+/// categories loaded once when the import starts. Rows without a category are
+/// given the category of the user's first matching category rule. Amounts must
+/// be in the currency of the row's account.
+///
+/// Unless `force` is set, a row is skipped if it is a likely duplicate, as
+/// decided by [`is_likely_duplicate`], of a transaction that existed before the
+/// import. Each existing transaction is the duplicate of at most one row, so a
+/// file with the same payment twice against one saved copy imports the other.
+/// Rows of the file are not checked against each other.
+///
+/// A dry run goes through the same steps without saving anything: categories
+/// are not created, and limits are checked against the balances the previous
+/// batches would have left. Its result has the same counts and errors as the
+/// import, and a preview of each transaction that would be created, flagged
+/// like [`check_duplicates`] if it may already exist. Income allocation is not
+/// simulated, so a limit breached only because of allocated income is missed.
+pub struct CsvImport<'a> {
+    pool: &'a DbPool,
+    user_id: Uuid,
+    options: CsvImportOptions,
+    stream: CsvRecordStream,
+    /// The user's accounts by ID, and their IDs by normalized name
+    accounts: HashMap<Uuid, Account>,
+    account_names: HashMap<String, Uuid>,
+    /// The user's category IDs by normalized name
+    categories: HashMap<String, Uuid>,
+    rules: CompiledRules,
+    /// Allocation rules by source account, by which imported income is allocated
+    allocations: HashMap<Uuid, AccountAllocation>,
+    /// Rows read but not saved yet
+    batch: Vec<ParsedRecord>,
+    /// Balances of the accounts a dry run has used, after its previous batches
+    balances: HashMap<Uuid, BigDecimal>,
+    preview: Vec<ImportPreviewTransaction>,
+    /// Transactions created by the import, which later rows may not duplicate
+    imported: HashSet<Uuid>,
+    /// Existing transactions already matched as the duplicate of a row
+    matched: HashSet<Uuid>,
+    duplicates: Vec<CsvImportDuplicate>,
+    data: CsvImportData,
+    errors: Vec<CsvImportBatchError>,
+}
+
+/// A row ready to be saved, with its account and category resolved
+type ImportRow = (usize, ParsedTransaction, Option<ImportCategorySource>);
+
+impl<'a> CsvImport<'a> {
+    /// Start an import into the user's accounts
+    ///
+    /// # Errors
+    ///
+    /// - NotFound error if `options.account_id` is not one of the user's accounts
+    /// - Internal errors for database failures
+    pub async fn start(
+        pool: &'a DbPool,
+        user_id: Uuid,
+        options: CsvImportOptions,
+    ) -> Result<CsvImport<'a>, ApiError> {
+        let accounts: HashMap<Uuid, Account> = repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account))
+            .collect();
+        if !accounts.contains_key(&options.account_id) {
+            return Err(ApiError::NotFound("Account not found".to_string()));
+        }
+
+        let mut account_names = HashMap::new();
+        for account in accounts.values() {
+            account_names
+                .entry(normalize_name(&account.name))
+                .or_insert(account.id);
+        }
+
+        let mut categories = HashMap::new();
+        for category in repositories::category::list_by_user(pool, user_id).await? {
+            categories
+                .entry(normalize_name(&category.name))
+                .or_insert(category.id);
+        }
+
+        let allocations = AccountAllocation::by_account(
+            repositories::allocation_rule::list_by_user(pool, user_id).await?,
+            &accounts,
+        );
+
+        let rules = CompiledRules::load(pool, user_id).await?;
+        let stream = match &options.format {
+            Some(format) => CsvRecordStream::with_format(format.clone()),
+            None => CsvRecordStream::new(),
+        };
+
+        Ok(Self {
+            pool,
+            user_id,
+            options,
+            stream,
+            accounts,
+            account_names,
+            categories,
+            rules,
+            allocations,
+            batch: Vec::new(),
+            balances: HashMap::new(),
+            preview: Vec::new(),
+            imported: HashSet::new(),
+            matched: HashSet::new(),
+            duplicates: Vec::new(),
+            data: CsvImportData::default(),
+            errors: Vec::new(),
+        })
+    }
+
+    /// Feed the next chunk of the file, saving every batch it completes
+    ///
+    /// Chunks pushed after the import was aborted are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - Validation error if the header lacks a column of the mapping
+    /// - Internal errors for database failures
+    pub async fn push(&mut self, chunk: &[u8]) -> Result<(), ApiError> {
+        if self.data.aborted {
+            return Ok(());
+        }
+
+        let records = self.stream.push(chunk);
+        self.check_header()?;
+        self.add_records(records).await
+    }
+
+    /// Whether the import stopped at a failed batch
+    pub fn is_aborted(&self) -> bool {
+        self.data.aborted
+    }
+
+    /// Save the remaining rows once the whole file has been received
+    ///
+    /// # Errors
+    ///
+    /// - Validation error if the file has no rows, or its header lacks a column
+    ///   of the mapping
+    /// - Internal errors for database failures
+    pub async fn finish(mut self) -> Result<CsvImportData, ApiError> {
+        if !self.data.aborted {
+            let records = self.stream.finish();
+            self.check_header()?;
+            self.add_records(records).await?;
+            if !self.batch.is_empty() {
+                self.save_batch().await?;
+            }
+        }
+
+        if self.data.total == 0 {
+            return Err(ApiError::Validation(ParserError::EmptyFile.to_string()));
+        }
+
+        if !self.errors.is_empty() {
+            self.data.errors = Some(self.errors);
+        }
+        if !self.duplicates.is_empty() {
+            self.data.duplicates = Some(self.duplicates);
+        }
+        if self.options.dry_run {
+            self.data.preview = Some(CsvImportPreview {
+                columns: self.stream.column_mapping().unwrap_or_default(),
+                transactions: self.preview,
+            });
+        }
+
+        tracing::info!(
+            "CSV import{} for user {}: {} rows, {} created in {} batches, {} duplicates skipped, {} failed{}",
+            if self.options.dry_run { " dry run" } else { "" },
+            self.user_id,
+            self.data.total,
+            self.data.created,
+            self.data.batches,
+            self.data.skipped_duplicates,
+            self.data.failed,
+            if self.data.aborted { ", aborted" } else { "" }
+        );
+
+        Ok(self.data)
+    }
+
+    /// Fail the import if the header does not fit the mapping
+    fn check_header(&self) -> Result<(), ApiError> {
+        match self.stream.header_error() {
+            Some(e) => Err(ApiError::Validation(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    async fn add_records(&mut self, records: Vec<ParsedRecord>) -> Result<(), ApiError> {
+        for record in records {
+            if self.data.aborted {
+                break;
+            }
+            self.data.total += 1;
+            self.batch.push(record);
+            if self.batch.len() >= self.options.batch_size {
+                self.save_batch().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save the rows read since the last batch in one database transaction
+    async fn save_batch(&mut self) -> Result<(), ApiError> {
+        let records = std::mem::take(&mut self.batch);
+        let batch = self.data.batches + self.errors.len() + 1;
+        let first_line = records.first().map(|(line, _)| *line).unwrap_or_default();
+        let last_line = records.last().map(|(line, _)| *line).unwrap_or_default();
+
+        let mut rows = Vec::with_capacity(records.len());
+        let mut errors = Vec::new();
+        for (line, record) in records {
+            match self.build_transaction(line, record).await? {
+                Ok((parsed, category_source)) => rows.push((line, parsed, category_source)),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        let row_count = rows.len() + errors.len();
+        let mut duplicates = Vec::new();
+        let result = if !errors.is_empty() {
+            Err(errors)
+        } else {
+            let rows = if self.options.force {
+                rows
+            } else {
+                let (rows, skipped) = self.skip_duplicates(rows).await?;
+                duplicates = skipped;
+                rows
+            };
+            if self.options.dry_run {
+                self.preview_batch(rows).await?
+            } else {
+                self.create_batch(rows).await?
+            }
+        };
+
+        match result {
+            Ok(created) => {
+                self.data.created += created;
+                self.data.skipped_duplicates += duplicates.len();
+                self.duplicates.extend(duplicates);
+                self.data.batches += 1;
+                tracing::info!(
+                    "CSV import for user {}: {} batch {} (lines {}-{}), {} rows so far",
+                    self.user_id,
+                    if self.options.dry_run {
+                        "checked"
+                    } else {
+                        "saved"
+                    },
+                    batch,
+                    first_line,
+                    last_line,
+                    self.data.total
+                );
+            }
+            Err(errors) => {
+                tracing::warn!(
+                    "CSV import for user {}: batch {} (lines {}-{}) failed with {} errors",
+                    self.user_id,
+                    batch,
+                    first_line,
+                    last_line,
+                    errors.len()
+                );
+                self.data.failed += row_count;
+                self.data.aborted = self.options.on_error == ImportErrorMode::Abort;
+                self.errors.push(CsvImportBatchError {
+                    batch,
+                    first_line,
+                    last_line,
+                    errors,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Split off the rows of a batch that duplicate existing transactions
+    ///
+    /// Each existing transaction is matched by the first row that duplicates it
+    /// and is not a candidate for later rows, in this or later batches.
+    async fn skip_duplicates(
+        &mut self,
+        rows: Vec<ImportRow>,
+    ) -> Result<(Vec<ImportRow>, Vec<CsvImportDuplicate>), ApiError> {
+        let (Some(start), Some(end)) = (
+            rows.iter().map(|(_, parsed, _)| parsed.date).min(),
+            rows.iter().map(|(_, parsed, _)| parsed.date).max(),
+        ) else {
+            return Ok((rows, Vec::new()));
+        };
+        let window = Duration::days(self.options.duplicate_window_days + 1);
+        let account_ids: Vec<Uuid> = rows
+            .iter()
+            .filter_map(|(_, parsed, _)| parsed.account_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut existing: Vec<Transaction> = repositories::transaction::list_by_accounts_between(
+            self.pool,
+            self.user_id,
+            account_ids,
+            start - window,
+            end + window,
+        )
+        .await?
+        .into_iter()
+        .filter(|transaction| {
+            !self.imported.contains(&transaction.id) && !self.matched.contains(&transaction.id)
+        })
+        .collect();
+
+        let mut kept = Vec::with_capacity(rows.len());
+        let mut duplicates = Vec::new();
+        for (line, parsed, category_source) in rows {
+            let matched = existing.iter().position(|transaction| {
+                is_likely_duplicate(&parsed, transaction, self.options.duplicate_window_days)
+            });
+            match matched {
+                Some(index) => {
+                    let transaction = existing.remove(index);
+                    self.matched.insert(transaction.id);
+                    duplicates.push(CsvImportDuplicate {
+                        line,
+                        title: parsed.title,
+                        transaction_id: transaction.id,
+                    });
+                }
+                None => kept.push((line, parsed, category_source)),
+            }
+        }
+
+        Ok((kept, duplicates))
+    }
+
+    /// Create a batch's transactions, or describe why the batch failed
+    async fn create_batch(
+        &mut self,
+        rows: Vec<ImportRow>,
+    ) -> Result<Result<usize, Vec<String>>, ApiError> {
+        let lines: Vec<usize> = rows.iter().map(|(line, ..)| *line).collect();
+        // Each row is followed by the transfers allocating it, saved with it
+        let mut new_transactions = Vec::with_capacity(rows.len());
+        for (_, parsed, _) in rows {
+            let new_transaction = self.new_transaction(parsed);
+            let transfers = self
+                .allocations
+                .get(&new_transaction.account_id)
+                .map(|allocation| allocation.transfers(&new_transaction))
+                .unwrap_or_default();
+            new_transactions.push(new_transaction);
+            new_transactions.extend(transfers);
+        }
+
+        let accounts = self.accounts.clone();
+        let mut index = 0;
+        let mut line = 0;
+        let created = repositories::transaction::create_transactions_checked(
+            self.pool,
+            self.user_id,
+            new_transactions,
+            move |new_transaction, balance| {
+                // Transfers are checked under the line of the row they allocate
+                if new_transaction.transfer_id.is_none() {
+                    line = lines[index];
+                    index += 1;
+                }
+                let account = accounts
+                    .get(&new_transaction.account_id)
+                    .ok_or(ApiError::Internal)?;
+                check_row_limit(account, line, balance, &new_transaction.amount)
+            },
+        )
+        .await;
+
+        match created {
+            Ok((created, _)) => {
+                let rows: Vec<Uuid> = created
+                    .iter()
+                    .filter(|transaction| transaction.transfer_id.is_none())
+                    .map(|transaction| transaction.id)
+                    .collect();
+                let count = rows.len();
+                self.imported.extend(rows);
+                Ok(Ok(count))
+            }
+            Err(e) => Ok(Err(vec![e.to_string()])),
+        }
+    }
+
+    /// Check a batch like [`Self::create_batch`] and add its rows to the preview
+    async fn preview_batch(
+        &mut self,
+        rows: Vec<ImportRow>,
+    ) -> Result<Result<usize, Vec<String>>, ApiError> {
+        // Balances are only kept if the whole batch would be saved
+        let mut balances = self.balances.clone();
+        for (line, parsed, _) in &rows {
+            let account_id = parsed.account_id.unwrap_or(self.options.account_id);
+            let balance = match balances.entry(account_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    repositories::account::calculate_available_balance(self.pool, account_id)
+                        .await?,
+                ),
+            };
+            if let Err(e) =
+                check_row_limit(&self.accounts[&account_id], *line, balance, &parsed.amount)
+            {
+                return Ok(Err(vec![e.to_string()]));
+            }
+            *balance += &parsed.amount;
+        }
+        self.balances = balances;
+
+        let count = rows.len();
+        let mut transactions: Vec<ParsedTransaction> =
+            rows.iter().map(|(_, parsed, _)| parsed.clone()).collect();
+        check_duplicates(
+            self.pool,
+            self.user_id,
+            self.options.account_id,
+            &mut transactions,
+        )
+        .await?;
+
+        self.preview.extend(rows.into_iter().zip(transactions).map(
+            |((line, _, category_source), transaction)| ImportPreviewTransaction {
+                line,
+                transaction,
+                category_source,
+            },
+        ));
+
+        Ok(Ok(count))
+    }
+
+    /// Resolve a parsed row's account and category, or describe why it cannot be imported
+    async fn build_transaction(
+        &mut self,
+        line: usize,
+        record: Result<ParsedTransaction, ParserError>,
+    ) -> Result<Result<(ParsedTransaction, Option<ImportCategorySource>), String>, ApiError> {
+        let mut parsed = match record {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(Err(e.to_string())),
+        };
+
+        let validation_errors = CSVStatementParser.validate(&parsed);
+        if !validation_errors.is_empty() {
+            let messages: Vec<String> = validation_errors.iter().map(|e| e.to_string()).collect();
+            return Ok(Err(format!("line {}: {}", line, messages.join(", "))));
+        }
+
+        let account_id = match &parsed.account_name {
+            Some(name) => match self.account_names.get(&normalize_name(name)) {
+                Some(account_id) => *account_id,
+                None => return Ok(Err(format!("line {}: account '{}' not found", line, name))),
+            },
+            None => self.options.account_id,
+        };
+        let account = &self.accounts[&account_id];
+
+        let currency = match parsed.original_currency.as_deref() {
+            Some(code) => match CurrencyCode::from_str(code) {
+                Ok(currency) => currency,
+                Err(e) => return Ok(Err(format!("line {}: {}", line, e))),
+            },
+            // Mapped files have no currency column
+            None if self.options.format.is_some() => account.currency,
+            None => CurrencyCode::Eur,
+        };
+        if currency != account.currency {
+            return Ok(Err(format!(
+                "line {}: amount is in {} but account '{}' uses {}",
+                line,
+                currency.as_str(),
+                account.name,
+                account.currency.as_str()
+            )));
+        }
+
+        let (category_id, category_source) = match &parsed.category_name {
+            Some(name) => {
+                let key = normalize_name(name);
+                match self.categories.get(&key) {
+                    Some(category_id) => (Some(*category_id), Some(ImportCategorySource::File)),
+                    None if !self.options.auto_create_categories => {
+                        return Ok(Err(format!("line {}: category '{}' not found", line, name)));
+                    }
+                    None if name.chars().count() > MAX_CATEGORY_NAME_LENGTH => {
+                        return Ok(Err(format!(
+                            "line {}: category name exceeds {} characters",
+                            line, MAX_CATEGORY_NAME_LENGTH
+                        )));
+                    }
+                    // A dry run leaves the category to be created by the import
+                    None if self.options.dry_run => (None, Some(ImportCategorySource::Created)),
+                    None => {
+                        let category_id =
+                            create_imported_category(self.pool, self.user_id, name).await?;
+                        self.categories.insert(key, category_id);
+                        (Some(category_id), Some(ImportCategorySource::Created))
+                    }
+                }
+            }
+            None => match self.rules.category_for(&parsed.title) {
+                Some(category_id) => (Some(category_id), Some(ImportCategorySource::Rule)),
+                None => (None, None),
+            },
+        };
+
+        parsed.account_id = Some(account_id);
+        parsed.category_id = category_id;
+        Ok(Ok((parsed, category_source)))
+    }
+
+    /// The transaction to create for a resolved row
+    fn new_transaction(&self, parsed: ParsedTransaction) -> NewTransaction {
+        NewTransaction {
+            user_id: self.user_id,
+            account_id: parsed.account_id.unwrap_or(self.options.account_id),
+            category_id: parsed.category_id,
+            title: parsed.title,
+            amount: parsed.amount,
+            date: parsed.date,
+            notes: parsed.notes,
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// Check that a row keeps its account within its limits, naming the row's line
+fn check_row_limit(
+    account: &Account,
+    line: usize,
+    balance: &BigDecimal,
+    amount: &BigDecimal,
+) -> Result<(), ApiError> {
+    account_service::project_balance(account, balance, amount)
+        .map(|_| ())
+        .map_err(|e| match e {
+            ApiError::Validation(message) => {
+                ApiError::Validation(format!("line {}: {}", line, message))
+            }
+            other => other,
+        })
+}
+
+/// Whether an imported row is likely the same transaction as an existing one
+///
+/// It is if both are in the same account with the same amount, are dated at
+/// most `window_days` days apart, and have similar titles: ignoring case and
+/// punctuation, one title contains the other's words in order, or at least half the words of
+/// the shorter title are in the other. The row's account must be resolved.
+///
+/// This only compares one pair; an existing transaction found to be the
+/// duplicate of a row must be removed from the candidates of the other rows.
+pub fn is_likely_duplicate(
+    candidate: &ParsedTransaction,
+    existing: &Transaction,
+    window_days: i64,
+) -> bool {
+    candidate.account_id == Some(existing.account_id)
+        && candidate.amount == existing.amount
+        && (candidate.date.date_naive() - existing.date.date_naive())
+            .num_days()
+            .abs()
+            <= window_days
+        && titles_similar(&candidate.title, &existing.title)
+}
+
+/// Whether two transaction titles likely describe the same payee
+fn titles_similar(a: &str, b: &str) -> bool {
+    let words = |title: &str| -> Vec<String> {
+        title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+
+    // Padded so that only whole words match
+    let (joined_a, joined_b) = (format!(" {} ", a.join(" ")), format!(" {} ", b.join(" ")));
+    if joined_a.contains(&joined_b) || joined_b.contains(&joined_a) {
+        return true;
+    }
+
+    let (shorter, longer) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let shared = shorter.iter().filter(|word| longer.contains(word)).count();
+    shared * 2 >= shorter.len()
+}
+
+/// Normalize an account or category name for case-insensitive matching
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
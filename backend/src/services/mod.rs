@@ -1,14 +1,32 @@
 // Service modules
 pub mod account_service;
+pub mod allocation_rule_service;
 pub mod analytics_service;
 pub mod api_key_service;
+pub mod auth_event_service;
 pub mod auth_service;
+pub mod budget_alert_service;
 pub mod budget_service;
+pub mod bulk_transaction_service;
+pub mod category_rule_service;
 pub mod csv_parser_service;
 pub mod debt_service;
+pub mod email_service;
+pub mod event_service;
 pub mod exchange_rate_service;
+pub mod idempotency_service;
+pub mod import_profile_service;
 pub mod import_service;
+pub mod notification_service;
+pub mod ofx_parser_service;
+pub mod purge_service;
+pub mod reconciliation_service;
+pub mod recurring_service;
+pub mod split_group_service;
 pub mod split_provider;
 pub mod split_sync_service;
 pub mod splitwise_oauth;
+pub mod splitwise_webhook_service;
 pub mod transaction_service;
+pub mod transfer_service;
+pub mod two_factor_service;
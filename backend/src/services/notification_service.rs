@@ -0,0 +1,645 @@
+//! Notifications
+//!
+//! Events users may want to hear about, such as budget alerts, are delivered
+//! through the channels each user turned on for that kind of event in their
+//! notification preferences: in-app (listed at `/api/v1/notifications`),
+//! email (through the [`EmailSender`]) or a webhook. Channels without a
+//! preference use their default, which is on only for in-app.
+//!
+//! Delivery runs in background tasks, one per channel, each retried with
+//! exponential backoff, so a slow or failing channel neither delays the
+//! request that raised the event nor the other channels.
+//!
+//! Webhook URLs are user input the server sends requests to, so their hosts
+//! must resolve to public addresses only, checked when they are saved and
+//! again before every delivery, and redirects are not followed. Deliveries
+//! are signed with a secret generated for each webhook URL.
+
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::{Url, header::CONTENT_TYPE, redirect::Policy};
+use serde_json::{Value as JsonValue, json};
+use sha2::Sha256;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    auth::refresh_token::generate_token,
+    config::NotificationConfig,
+    errors::ApiError,
+    models::{
+        NewNotification, NewNotificationPreference, NotificationChannelKind, NotificationEventType,
+        NotificationPreference, NotificationPreferenceResponse, NotificationQuery,
+        NotificationResponse, Pagination, UpdateNotificationPreferencesRequest,
+    },
+    repositories,
+    services::email_service::EmailSender,
+};
+
+/// Attempts at delivering a notification through one channel
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled before each further one
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Time allowed for a webhook to respond
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying `sha256=` and the hex HMAC-SHA256 of a webhook body
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Length of the secrets webhook deliveries are signed with
+const WEBHOOK_SECRET_LENGTH: usize = 64;
+
+/// A notification to deliver, independent of the channel carrying it
+#[derive(Debug, Clone)]
+pub struct NotificationMessage {
+    pub event_type: NotificationEventType,
+    /// One line summary, used as email subject
+    pub title: String,
+    pub body: String,
+    /// Details of the event for programmatic use, e.g. the IDs it concerns
+    pub data: JsonValue,
+}
+
+/// The user a notification is delivered to
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    pub user_id: Uuid,
+    pub email: String,
+    /// URL the user configured for webhook notifications of the event type
+    pub webhook_url: Option<String>,
+    /// Secret webhook deliveries to `webhook_url` are signed with
+    pub webhook_secret: Option<String>,
+}
+
+/// A way of delivering notifications to users
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// The channel preferences refer to this channel by
+    fn kind(&self) -> NotificationChannelKind;
+
+    /// Deliver `message` to `recipient`
+    ///
+    /// Errors are retried, so delivering must be safe to repeat.
+    async fn deliver(
+        &self,
+        recipient: &Recipient,
+        message: &NotificationMessage,
+    ) -> Result<(), ApiError>;
+}
+
+/// Stores notifications for the user to list in the app
+pub struct InAppChannel {
+    pool: DbPool,
+}
+
+impl InAppChannel {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for InAppChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::InApp
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &Recipient,
+        message: &NotificationMessage,
+    ) -> Result<(), ApiError> {
+        repositories::notification::create_notification(
+            &self.pool,
+            NewNotification {
+                user_id: recipient.user_id,
+                event_type: message.event_type.as_str().to_string(),
+                title: message.title.clone(),
+                body: message.body.clone(),
+                data: message.data.clone(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Emails notifications to the user's address
+pub struct EmailChannel {
+    sender: Arc<dyn EmailSender>,
+}
+
+impl EmailChannel {
+    pub fn new(sender: Arc<dyn EmailSender>) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::Email
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &Recipient,
+        message: &NotificationMessage,
+    ) -> Result<(), ApiError> {
+        self.sender
+            .send_notification(&recipient.email, &message.title, &message.body)
+            .await
+    }
+}
+
+/// POSTs notifications as JSON to the URL the user configured
+///
+/// The body has the `event_type`, `title`, `body` and `data` of the
+/// notification and the time it was sent, and is signed in the
+/// [`WEBHOOK_SIGNATURE_HEADER`]. Any response other than a 2xx, redirects
+/// included, counts as a failure.
+pub struct WebhookChannel {
+    allow_private_urls: bool,
+}
+
+impl WebhookChannel {
+    /// A webhook channel; `allow_private_urls` lets webhooks reach addresses
+    /// that are not publicly routable
+    pub fn new(allow_private_urls: bool) -> Self {
+        Self { allow_private_urls }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::Webhook
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &Recipient,
+        message: &NotificationMessage,
+    ) -> Result<(), ApiError> {
+        let (Some(url), Some(secret)) = (&recipient.webhook_url, &recipient.webhook_secret) else {
+            return Err(ApiError::Validation(
+                "No webhook URL is configured".to_string(),
+            ));
+        };
+
+        // Checked again on every delivery, as the host may resolve elsewhere by now
+        let target = resolve_webhook_target(url, self.allow_private_urls).await?;
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .redirect(Policy::none())
+            // Connect to the addresses just checked, not to a fresh DNS answer
+            .resolve_to_addrs(&target.host, &target.addrs)
+            .build()
+            .map_err(|e| {
+                tracing::error!("Failed to build webhook client: {}", e);
+                ApiError::Internal
+            })?;
+
+        let body = serde_json::to_vec(&json!({
+            "event_type": message.event_type,
+            "title": message.title,
+            "body": message.body,
+            "data": message.data,
+            "sent_at": Utc::now(),
+        }))
+        .map_err(|e| {
+            tracing::error!("Failed to serialize webhook notification: {}", e);
+            ApiError::Internal
+        })?;
+        let signature = sign_webhook_body(secret, &body)?;
+
+        let response = client
+            .post(target.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::warn!("Notification webhook request failed: {}", e);
+                ApiError::Internal
+            })?;
+        if !response.status().is_success() {
+            tracing::warn!(
+                "Notification webhook responded with status {}",
+                response.status()
+            );
+            return Err(ApiError::Internal);
+        }
+
+        Ok(())
+    }
+}
+
+/// A webhook URL with the checked addresses of its host
+struct WebhookTarget {
+    url: Url,
+    host: String,
+    addrs: Vec<SocketAddr>,
+}
+
+/// Parse a webhook URL and resolve its host
+///
+/// The URL must be absolute http(s), and unless `allow_private` is set,
+/// every address its host resolves to must be publicly routable.
+async fn resolve_webhook_target(url: &str, allow_private: bool) -> Result<WebhookTarget, ApiError> {
+    let parsed = Url::parse(url)
+        .map_err(|_| ApiError::Validation(format!("Invalid webhook_url '{}'", url)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ApiError::Validation(
+            "webhook_url must be an http or https URL".to_string(),
+        ));
+    }
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let Some(host) = parsed.host_str() else {
+        return Err(ApiError::Validation(
+            "webhook_url must be an http or https URL".to_string(),
+        ));
+    };
+    // IPv6 literals come bracketed
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host.as_str(), port))
+            .await
+            .map(|addrs| addrs.collect())
+            .unwrap_or_default(),
+    };
+    if addrs.is_empty() {
+        return Err(ApiError::Validation(format!(
+            "The host of webhook_url '{}' could not be resolved",
+            host
+        )));
+    }
+    if !allow_private && addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        tracing::warn!("Rejected webhook URL {} resolving to {:?}", url, addrs);
+        return Err(ApiError::Validation(
+            "webhook_url must not point to a loopback, private, link-local or reserved address"
+                .to_string(),
+        ));
+    }
+
+    Ok(WebhookTarget {
+        url: parsed,
+        host,
+        addrs,
+    })
+}
+
+/// Whether `ip` is publicly routable
+///
+/// Stands in for the unstable `IpAddr::is_global`: unspecified, loopback,
+/// private, shared (carrier-grade NAT), link-local, documentation,
+/// benchmarking, reserved and multicast addresses are not, nor are IPv6
+/// unique local and NAT64 ones. IPv4-mapped IPv6 addresses are judged by
+/// their IPv4 address.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b == 18 || b == 19))
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ipv4));
+            }
+            let [first, second, ..] = ip.segments();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && second == 0x0db8)
+                || (first == 0x0064 && second == 0xff9b))
+        }
+    }
+}
+
+/// `sha256=` and the hex HMAC-SHA256 of `body` under `secret`
+fn sign_webhook_body(secret: &str, body: &[u8]) -> Result<String, ApiError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| {
+        tracing::error!("Invalid webhook secret: {}", e);
+        ApiError::Internal
+    })?;
+    mac.update(body);
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    Ok(format!("sha256={}", signature))
+}
+
+/// Delivers notifications through users' configured channels
+#[derive(Clone)]
+pub struct Notifier {
+    pool: DbPool,
+    channels: Arc<Vec<Arc<dyn NotificationChannel>>>,
+}
+
+impl Notifier {
+    /// A notifier with the in-app, email and webhook channels
+    ///
+    /// `pool` must be the primary, as the in-app channel writes.
+    pub fn new(pool: DbPool, email: Arc<dyn EmailSender>, config: &NotificationConfig) -> Self {
+        Self {
+            channels: Arc::new(vec![
+                Arc::new(InAppChannel::new(pool.clone())),
+                Arc::new(EmailChannel::new(email)),
+                Arc::new(WebhookChannel::new(config.allow_private_webhook_urls)),
+            ]),
+            pool,
+        }
+    }
+
+    /// Deliver `message` to a user in the background
+    ///
+    /// Looks up the user's preferences, then delivers through each channel
+    /// enabled for the message's event type in its own task. Failures are
+    /// logged, never returned.
+    pub fn dispatch(&self, user_id: Uuid, message: NotificationMessage) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.deliver(user_id, message).await {
+                tracing::error!(
+                    "Failed to dispatch notification to user {}: {:?}",
+                    user_id,
+                    e
+                );
+            }
+        });
+    }
+
+    async fn deliver(&self, user_id: Uuid, message: NotificationMessage) -> Result<(), ApiError> {
+        let stored =
+            repositories::notification_preference::list_by_user(&self.pool, user_id).await?;
+        let preferences: Vec<NotificationPreferenceResponse> = resolve_preferences(&stored)
+            .into_iter()
+            .filter(|preference| preference.event_type == message.event_type && preference.enabled)
+            .collect();
+        if preferences.is_empty() {
+            return Ok(());
+        }
+
+        let user = repositories::user::find_by_id(&self.pool, user_id).await?;
+        let message = Arc::new(message);
+        for preference in preferences {
+            let Some(channel) = self
+                .channels
+                .iter()
+                .find(|channel| channel.kind() == preference.channel)
+                .cloned()
+            else {
+                continue;
+            };
+            let recipient = Recipient {
+                user_id,
+                email: user.email.clone(),
+                webhook_url: preference.webhook_url,
+                webhook_secret: preference.webhook_secret,
+            };
+            let message = Arc::clone(&message);
+            tokio::spawn(async move {
+                deliver_with_retries(channel.as_ref(), &recipient, &message).await;
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Deliver through one channel, retrying with exponential backoff
+async fn deliver_with_retries(
+    channel: &dyn NotificationChannel,
+    recipient: &Recipient,
+    message: &NotificationMessage,
+) {
+    let mut delay = INITIAL_RETRY_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        match channel.deliver(recipient, message).await {
+            Ok(()) => return,
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                tracing::warn!(
+                    "Attempt {} at delivering {} notification to user {} via {} failed, retrying in {:?}: {:?}",
+                    attempt,
+                    message.event_type.as_str(),
+                    recipient.user_id,
+                    channel.kind().as_str(),
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Giving up delivering {} notification to user {} via {} after {} attempts: {:?}",
+                    message.event_type.as_str(),
+                    recipient.user_id,
+                    channel.kind().as_str(),
+                    MAX_ATTEMPTS,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Every event type and channel with the user's setting, or the default
+fn resolve_preferences(stored: &[NotificationPreference]) -> Vec<NotificationPreferenceResponse> {
+    NotificationEventType::ALL
+        .into_iter()
+        .flat_map(|event_type| {
+            NotificationChannelKind::ALL
+                .into_iter()
+                .map(move |channel| (event_type, channel))
+        })
+        .map(|(event_type, channel)| {
+            let preference = stored.iter().find(|preference| {
+                preference.event_type == event_type.as_str()
+                    && preference.channel == channel.as_str()
+            });
+            NotificationPreferenceResponse {
+                event_type,
+                channel,
+                enabled: preference
+                    .map(|preference| preference.enabled)
+                    .unwrap_or_else(|| channel.enabled_by_default()),
+                webhook_url: preference.and_then(|preference| preference.webhook_url.clone()),
+                webhook_secret: preference.and_then(|preference| preference.webhook_secret.clone()),
+            }
+        })
+        .collect()
+}
+
+/// A user's notification preferences for every event type and channel
+pub async fn get_preferences(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<NotificationPreferenceResponse>, ApiError> {
+    let stored = repositories::notification_preference::list_by_user(pool, user_id).await?;
+
+    Ok(resolve_preferences(&stored))
+}
+
+/// Turn channels on or off for event types
+///
+/// A webhook preference without a `webhook_url` keeps the URL saved before,
+/// so the webhook can be turned off and on again without repeating it. A new
+/// signing secret is generated whenever the URL changes.
+pub async fn update_preferences(
+    pool: &DbPool,
+    config: &NotificationConfig,
+    user_id: Uuid,
+    request: UpdateNotificationPreferencesRequest,
+) -> Result<Vec<NotificationPreferenceResponse>, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Notification preferences validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let stored = repositories::notification_preference::list_by_user(pool, user_id).await?;
+    let mut seen = HashSet::new();
+    let mut preferences = Vec::with_capacity(request.preferences.len());
+    for input in request.preferences {
+        if !seen.insert((input.event_type, input.channel)) {
+            return Err(ApiError::Validation(format!(
+                "The {} channel of {} is listed more than once",
+                input.channel.as_str(),
+                input.event_type.as_str()
+            )));
+        }
+
+        let saved = stored.iter().find(|preference| {
+            preference.event_type == input.event_type.as_str()
+                && preference.channel == input.channel.as_str()
+        });
+        let (webhook_url, webhook_secret) = match (input.channel, input.webhook_url) {
+            (NotificationChannelKind::Webhook, Some(url)) => {
+                resolve_webhook_target(&url, config.allow_private_webhook_urls).await?;
+                let secret = saved
+                    .filter(|preference| preference.webhook_url.as_deref() == Some(url.as_str()))
+                    .and_then(|preference| preference.webhook_secret.clone())
+                    .unwrap_or_else(|| generate_token(WEBHOOK_SECRET_LENGTH));
+                (Some(url), Some(secret))
+            }
+            (NotificationChannelKind::Webhook, None) => saved
+                .map(|preference| {
+                    (
+                        preference.webhook_url.clone(),
+                        preference.webhook_secret.clone(),
+                    )
+                })
+                .unwrap_or_default(),
+            (_, Some(_)) => {
+                return Err(ApiError::Validation(
+                    "webhook_url can only be set for the webhook channel".to_string(),
+                ));
+            }
+            (_, None) => (None, None),
+        };
+        if input.channel == NotificationChannelKind::Webhook
+            && input.enabled
+            && webhook_url.is_none()
+        {
+            return Err(ApiError::Validation(
+                "webhook_url is required to enable the webhook channel".to_string(),
+            ));
+        }
+
+        preferences.push(NewNotificationPreference {
+            user_id,
+            event_type: input.event_type.as_str().to_string(),
+            channel: input.channel.as_str().to_string(),
+            enabled: input.enabled,
+            webhook_url,
+            webhook_secret,
+        });
+    }
+
+    repositories::notification_preference::upsert_preferences(pool, preferences).await?;
+
+    get_preferences(pool, user_id).await
+}
+
+/// List a page of a user's in-app notifications, newest first
+pub async fn list_notifications(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: &NotificationQuery,
+    pagination: Pagination,
+) -> Result<Vec<NotificationResponse>, ApiError> {
+    let notifications =
+        repositories::notification::list_by_user(pool, user_id, query.unread, pagination).await?;
+
+    Ok(notifications.into_iter().map(Into::into).collect())
+}
+
+/// Count the in-app notifications [`list_notifications`] pages through
+pub async fn count_notifications(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: &NotificationQuery,
+) -> Result<i64, ApiError> {
+    repositories::notification::count_by_user(pool, user_id, query.unread).await
+}
+
+/// Mark an in-app notification read
+pub async fn mark_read(
+    pool: &DbPool,
+    notification_id: Uuid,
+    user_id: Uuid,
+) -> Result<NotificationResponse, ApiError> {
+    let notification = repositories::notification::find_by_id(pool, notification_id).await?;
+    if notification.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to read notification {} owned by {}",
+            user_id,
+            notification_id,
+            notification.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Notification does not belong to user".to_string(),
+        ));
+    }
+
+    repositories::notification::mark_read(pool, notification_id).await?;
+
+    let notification = repositories::notification::find_by_id(pool, notification_id).await?;
+    Ok(notification.into())
+}
@@ -0,0 +1,264 @@
+//! OFX/QFX statement parser service
+//!
+//! Parses bank statements in OFX format, as exported by most banks, and its
+//! Quicken variant QFX. Both the SGML syntax of OFX 1.x, where elements holding
+//! a value have no closing tag, and the XML syntax of OFX 2.x are accepted.
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::config::ImportConfig;
+use crate::models::{
+    ParsedTransaction,
+    parser_error::{ParserError, ValidationError},
+};
+use crate::services::csv_parser_service::{CSVStatementParser, StatementParser};
+use crate::types::CurrencyCode;
+
+/// Transactions of an OFX statement, with the statement's currency
+#[derive(Debug, Clone)]
+pub struct OfxStatement {
+    /// Default currency of the statement (`CURDEF`), if given
+    pub currency: Option<CurrencyCode>,
+    pub transactions: Vec<OfxTransaction>,
+}
+
+/// A statement transaction (`STMTTRN`)
+#[derive(Debug, Clone)]
+pub struct OfxTransaction {
+    /// Identifier the bank assigned to the transaction (`FITID`)
+    pub fit_id: String,
+    /// Date posted (`DTPOSTED`)
+    pub date: DateTime<Utc>,
+    /// Signed amount (`TRNAMT`), negative for money leaving the account
+    pub amount: BigDecimal,
+    /// Payee (`NAME`)
+    pub name: Option<String>,
+    /// Free-form description (`MEMO`)
+    pub memo: Option<String>,
+}
+
+impl OfxTransaction {
+    /// Title of the transaction: its payee, or its memo when it has none
+    pub fn title(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.memo.clone())
+            .unwrap_or_default()
+    }
+
+    /// Notes of the transaction: its memo when the title is the payee
+    pub fn notes(&self) -> Option<String> {
+        self.name.as_ref().and(self.memo.clone())
+    }
+}
+
+impl From<&OfxTransaction> for ParsedTransaction {
+    fn from(transaction: &OfxTransaction) -> Self {
+        Self {
+            temp_id: Uuid::new_v4().to_string(),
+            title: transaction.title(),
+            amount: transaction.amount.clone(),
+            date: transaction.date,
+            notes: transaction.notes(),
+            original_currency: None,
+            original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
+            is_valid: true,
+            validation_errors: None,
+            is_potential_duplicate: false,
+            duplicate_match: None,
+        }
+    }
+}
+
+/// OFX/QFX statement parser implementation
+pub struct OfxStatementParser;
+
+impl StatementParser for OfxStatementParser {
+    fn parse(
+        &self,
+        content: &[u8],
+        config: &ImportConfig,
+    ) -> Result<Vec<ParsedTransaction>, ParserError> {
+        let statement = self.parse_statement(content, config)?;
+
+        Ok(statement
+            .transactions
+            .iter()
+            .map(ParsedTransaction::from)
+            .collect())
+    }
+
+    fn validate(&self, transaction: &ParsedTransaction) -> Vec<ValidationError> {
+        // Parsed transactions are checked the same way whatever their format
+        CSVStatementParser.validate(transaction)
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec![".ofx", ".qfx"]
+    }
+
+    fn name(&self) -> &'static str {
+        "OFX/QFX Statement Parser"
+    }
+}
+
+impl OfxStatementParser {
+    /// Parse an OFX statement, keeping the bank's transaction IDs
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParserError::InvalidOfx` describing the first problem found if
+    /// the file is not OFX, a transaction is not closed, or a transaction's
+    /// `FITID`, `DTPOSTED` or `TRNAMT` is missing or invalid.
+    pub fn parse_statement(
+        &self,
+        content: &[u8],
+        config: &ImportConfig,
+    ) -> Result<OfxStatement, ParserError> {
+        let content = String::from_utf8_lossy(content);
+
+        // Anything before the root element is the OFX 1.x header or an XML prolog
+        let start = find_tag(&content, "<OFX>")
+            .ok_or_else(|| invalid("no <OFX> element found".to_string()))?;
+        let body = &content[start..];
+
+        let currency = match element_value(body, "CURDEF") {
+            Some(code) => Some(
+                CurrencyCode::from_str(&code)
+                    .map_err(|_| invalid(format!("unsupported statement currency '{}'", code)))?,
+            ),
+            None => None,
+        };
+
+        let mut transactions = Vec::new();
+        let mut rest = body;
+        while let Some(open) = find_tag(rest, "<STMTTRN>") {
+            let number = transactions.len() + 1;
+            if transactions.len() >= config.max_transactions {
+                return Err(ParserError::TooManyTransactions {
+                    found: number,
+                    max: config.max_transactions,
+                });
+            }
+
+            let after_open = &rest[open + "<STMTTRN>".len()..];
+            let close = find_tag(after_open, "</STMTTRN>")
+                .ok_or_else(|| invalid(format!("transaction {} is not closed", number)))?;
+            transactions.push(parse_transaction(&after_open[..close], number)?);
+            rest = &after_open[close + "</STMTTRN>".len()..];
+        }
+
+        if transactions.is_empty() {
+            return Err(ParserError::EmptyFile);
+        }
+
+        Ok(OfxStatement {
+            currency,
+            transactions,
+        })
+    }
+}
+
+/// Parse the elements of one `STMTTRN` aggregate
+fn parse_transaction(block: &str, number: usize) -> Result<OfxTransaction, ParserError> {
+    let required = |tag: &str| {
+        element_value(block, tag)
+            .ok_or_else(|| invalid(format!("transaction {} has no {}", number, tag)))
+    };
+
+    let fit_id = required("FITID")?;
+
+    let date_value = required("DTPOSTED")?;
+    let date = parse_date(&date_value).ok_or_else(|| {
+        invalid(format!(
+            "transaction {} has an invalid DTPOSTED '{}'",
+            number, date_value
+        ))
+    })?;
+
+    let amount_value = required("TRNAMT")?;
+    // Some banks write the decimal separator as a comma
+    let amount = BigDecimal::from_str(&amount_value.replace(',', ".")).map_err(|_| {
+        invalid(format!(
+            "transaction {} has an invalid TRNAMT '{}'",
+            number, amount_value
+        ))
+    })?;
+
+    Ok(OfxTransaction {
+        fit_id,
+        date,
+        amount,
+        name: element_value(block, "NAME"),
+        memo: element_value(block, "MEMO"),
+    })
+}
+
+/// Parse an OFX date: `YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`
+///
+/// The time defaults to midnight, and the offset in hours to UTC.
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let (value, offset) = match value.split_once('[') {
+        Some((value, zone)) => {
+            let hours = zone.trim_end_matches(']').split(':').next()?;
+            (value, hours.parse::<f64>().ok()?)
+        }
+        None => (value, 0.0),
+    };
+    // Fractional seconds are dropped
+    let value = value.split('.').next()?;
+
+    let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    let time = match value.get(8..) {
+        Some("") | None => NaiveTime::MIN,
+        Some(time) => NaiveTime::parse_from_str(time, "%H%M%S").ok()?,
+    };
+
+    let local = NaiveDateTime::new(date, time);
+    let utc = local - chrono::Duration::seconds((offset * 3600.0) as i64);
+    Some(utc.and_utc())
+}
+
+/// Value of the first `<TAG>value` element, with entities decoded
+///
+/// The value ends at the next tag, so the closing tag is optional as in OFX 1.x.
+/// Empty values count as missing.
+fn element_value(content: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = find_tag(content, &open)? + open.len();
+    let rest = &content[start..];
+    let end = rest.find('<').unwrap_or(rest.len());
+
+    let value = rest[..end].trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&nbsp;", " ")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Byte offset of a tag, matched case-insensitively
+fn find_tag(content: &str, tag: &str) -> Option<usize> {
+    content
+        .as_bytes()
+        .windows(tag.len())
+        .position(|window| window.eq_ignore_ascii_case(tag.as_bytes()))
+}
+
+fn invalid(error: String) -> ParserError {
+    ParserError::InvalidOfx { error }
+}
@@ -0,0 +1,84 @@
+//! Permanent removal of soft-deleted transactions
+//!
+//! Deleted transactions can be restored until they are older than the
+//! configured retention, after which the daily purge, or
+//! `POST /api/v1/admin/purge`, removes them for good together with their
+//! splits, sync records and tags. Purging runs in batches of
+//! `batch_size` transactions, one DB transaction each, so no batch holds its
+//! locks for long.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    config::PurgeConfig,
+    db::DbPool,
+    errors::ApiError,
+    models::{PurgeCounts, PurgeResponse},
+    repositories,
+};
+
+/// How often the scheduled purge runs
+const PURGE_INTERVAL: StdDuration = StdDuration::from_secs(24 * 60 * 60);
+
+/// Purge transactions deleted longer ago than the retention period
+///
+/// Nothing is purged when retention is disabled (`retention_days == 0`).
+pub async fn purge_deleted(pool: &DbPool, config: &PurgeConfig) -> Result<PurgeResponse, ApiError> {
+    if config.retention_days == 0 {
+        return Ok(PurgeResponse {
+            cutoff: None,
+            purged: PurgeCounts::default(),
+        });
+    }
+
+    let cutoff = Utc::now() - Duration::days(config.retention_days);
+    let mut purged = PurgeCounts::default();
+    loop {
+        let batch =
+            repositories::transaction::purge_deleted(pool, cutoff, config.batch_size).await?;
+        if batch.transactions == 0 {
+            break;
+        }
+        purged.add(batch);
+    }
+
+    if !purged.is_empty() {
+        tracing::info!(
+            "Purged {} transactions deleted more than {} days ago ({} splits, {} sync records, {} tags)",
+            purged.transactions,
+            config.retention_days,
+            purged.transaction_splits,
+            purged.split_sync_records,
+            purged.transaction_tags
+        );
+    }
+
+    Ok(PurgeResponse {
+        cutoff: Some(cutoff),
+        purged,
+    })
+}
+
+/// Spawn a background task that purges expired deleted transactions daily
+///
+/// Does nothing when retention is disabled (`retention_days == 0`).
+pub fn spawn_retention_purge(pool: DbPool, config: PurgeConfig) {
+    if config.retention_days == 0 {
+        tracing::info!(
+            "Soft-delete retention disabled - deleted transactions are kept indefinitely"
+        );
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge_deleted(&pool, &config).await {
+                tracing::error!("Soft-delete retention purge failed: {}", e);
+            }
+        }
+    });
+}
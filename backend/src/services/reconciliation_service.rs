@@ -0,0 +1,171 @@
+//! Reconciling accounts against bank statements
+//!
+//! A reconciliation compares the balance on a statement with the account's
+//! posted balance at the end of the statement date and records the result as a
+//! checkpoint. The transactions returned are those since the previous
+//! checkpoint, so a discrepancy only has to be looked for in what is new.
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        NewReconciliation, NewTransaction, ReconcileAccountRequest, ReconciliationResponse,
+        TagMode, TransactionFilter, TransactionResponse,
+    },
+    repositories,
+    services::transaction_service,
+    types::{Money, TransactionStatus},
+};
+
+/// Title of the transactions booking a reconciliation's difference
+pub const ADJUSTMENT_TITLE: &str = "Reconciliation adjustment";
+
+/// First instant of a day, in UTC
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Last instant of a day, in UTC, to the microsecond timestamps are stored with
+fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    start_of_day(date) + Duration::days(1) - Duration::microseconds(1)
+}
+
+/// Reconcile an account's posted balance with a bank statement
+///
+/// The statement date may be neither in the future nor before the account's
+/// previous reconciliation. With `create_adjustment`, a non-zero difference is
+/// booked as a posted transaction at the end of the statement date.
+pub async fn reconcile_account(
+    pool: &DbPool,
+    account_id: Uuid,
+    user_id: Uuid,
+    request: ReconcileAccountRequest,
+) -> Result<ReconciliationResponse, ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+
+    // Verify ownership
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to reconcile account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let statement_date = request.statement_date;
+    if statement_date > Utc::now().date_naive() {
+        return Err(ApiError::Validation(
+            "statement_date must not be in the future".to_string(),
+        ));
+    }
+
+    let previous = repositories::reconciliation::find_latest(pool, account_id).await?;
+    if let Some(previous) = &previous
+        && statement_date < previous.statement_date
+    {
+        return Err(ApiError::Validation(format!(
+            "statement_date must not be before the last reconciliation on {}",
+            previous.statement_date
+        )));
+    }
+
+    let end = end_of_day(statement_date);
+    let (_, _, _, balance) =
+        repositories::account::summarize_period(pool, account_id, None, Some(end)).await?;
+    let statement_balance = request.statement_balance.into_decimal();
+    let difference: BigDecimal = &statement_balance - &balance;
+
+    let filters = TransactionFilter {
+        account_id: Some(account_id),
+        category_id: None,
+        uncategorized: false,
+        include_deleted: false,
+        start_date: previous
+            .as_ref()
+            .map(|previous| end_of_day(previous.statement_date) + Duration::microseconds(1)),
+        end_date: Some(end),
+        min_amount: None,
+        max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: Some(TransactionStatus::Posted),
+        search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: None,
+        reimbursed: None,
+        sort: Some("date".to_string()),
+        limit: None,
+        offset: None,
+    };
+    let transactions = transaction_service::list_transactions(pool, user_id, filters).await?;
+
+    let adjustment = (request.create_adjustment && !difference.is_zero()).then(|| NewTransaction {
+        user_id,
+        account_id,
+        category_id: None,
+        title: ADJUSTMENT_TITLE.to_string(),
+        amount: difference.clone(),
+        date: end,
+        notes: Some(format!("Difference to the statement of {}", statement_date)),
+        external_id: None,
+        status: TransactionStatus::Posted,
+        original_currency: None,
+        original_amount: None,
+        exchange_rate: None,
+        merchant: None,
+        latitude: None,
+        longitude: None,
+        transfer_id: None,
+        reimbursable: false,
+        tags: Vec::new(),
+    });
+
+    let new_reconciliation = NewReconciliation {
+        user_id,
+        account_id,
+        statement_date,
+        statement_balance,
+        balance,
+        adjustment_transaction_id: None,
+    };
+    let (reconciliation, adjustment) =
+        repositories::reconciliation::create_reconciliation(pool, new_reconciliation, adjustment)
+            .await?;
+
+    tracing::info!(
+        "Reconciled account {} on {} with a difference of {}",
+        account_id,
+        statement_date,
+        difference
+    );
+
+    let currency = account.currency;
+    let adjustment = adjustment.map(|transaction| {
+        let mut response = TransactionResponse::from(transaction);
+        response.set_currency(currency);
+        response
+    });
+
+    Ok(ReconciliationResponse {
+        id: reconciliation.id,
+        account_id,
+        statement_date,
+        statement_balance: Money::new(reconciliation.statement_balance, currency),
+        balance: Money::new(reconciliation.balance, currency),
+        difference: Money::new(difference, currency),
+        previous_statement_date: previous.as_ref().map(|previous| previous.statement_date),
+        previous_statement_balance: previous
+            .map(|previous| Money::new(previous.statement_balance, currency)),
+        transactions,
+        adjustment,
+        created_at: reconciliation.created_at,
+    })
+}
@@ -0,0 +1,318 @@
+//! Recurring transactions
+//!
+//! A recurring transaction is a template (account, category, title, amount and
+//! notes) with a schedule, e.g. rent every month or a subscription every year.
+//! Every occurrence up to today is created as a posted transaction by
+//! [`materialize_due_recurring`], which a background task runs periodically
+//! (see [`spawn_materialization`]).
+//!
+//! Occurrences are counted from the schedule's start date, so month-end dates
+//! are clamped per month: a schedule from Jan 31 runs on Feb 28, then Mar 31.
+//! Occurrences missed while the server was down are all created on the next
+//! run, and each rule's progress is saved together with its transactions, so a
+//! restart never creates an occurrence twice.
+//!
+//! Like allocation transfers, created transactions are not checked against the
+//! account's overdraft or credit limit: the payment happens either way.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    config::RecurringConfig,
+    errors::ApiError,
+    models::{
+        CreateRecurringTransactionRequest, NewRecurringTransaction, RecurrenceSchedule,
+        RecurringTransaction, RecurringTransactionResponse, Transaction,
+        UpdateRecurringTransaction, UpdateRecurringTransactionRequest,
+    },
+    repositories,
+    services::event_service::{ChangeEvent, EventBus},
+};
+
+/// Occurrences of one rule created per DB transaction while catching up
+pub const MATERIALIZE_BATCH_SIZE: usize = 100;
+
+/// Create a recurring transaction
+///
+/// The account and category must belong to the user.
+pub async fn create_rule(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateRecurringTransactionRequest,
+) -> Result<RecurringTransactionResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Recurring transaction validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    verify_account(pool, user_id, request.account_id).await?;
+    if let Some(category_id) = request.category_id {
+        verify_category(pool, user_id, category_id).await?;
+    }
+
+    let new_rule = NewRecurringTransaction {
+        user_id,
+        account_id: request.account_id,
+        category_id: request.category_id,
+        title: request.title,
+        amount: request.amount.into_decimal(),
+        notes: request.notes,
+        frequency: request.frequency,
+        interval: request.interval,
+        start_date: request.next_run_date,
+        next_run_date: request.next_run_date,
+    };
+    let rule = repositories::recurring::create_rule(pool, new_rule).await?;
+
+    tracing::info!(
+        "Created recurring transaction {} for user {}",
+        rule.id,
+        user_id
+    );
+
+    Ok(rule.into())
+}
+
+/// List all recurring transactions of a user
+pub async fn list_rules(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<RecurringTransactionResponse>, ApiError> {
+    let rules = repositories::recurring::list_by_user(pool, user_id).await?;
+
+    Ok(rules.into_iter().map(Into::into).collect())
+}
+
+/// Get a recurring transaction
+pub async fn get_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+) -> Result<RecurringTransactionResponse, ApiError> {
+    let rule = find_owned(pool, rule_id, user_id).await?;
+
+    Ok(rule.into())
+}
+
+/// Update a recurring transaction
+///
+/// Changing the frequency, interval or next run date restarts the schedule
+/// from the resulting next run date. Transactions already created are kept.
+pub async fn update_rule(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+    request: UpdateRecurringTransactionRequest,
+) -> Result<RecurringTransactionResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Recurring transaction validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let rule = find_owned(pool, rule_id, user_id).await?;
+
+    if let Some(account_id) = request.account_id {
+        verify_account(pool, user_id, account_id).await?;
+    }
+    if let Some(Some(category_id)) = request.category_id {
+        verify_category(pool, user_id, category_id).await?;
+    }
+
+    let reschedule = request.frequency.is_some()
+        || request.interval.is_some()
+        || request.next_run_date.is_some();
+    let schedule = reschedule.then(|| RecurrenceSchedule {
+        frequency: request.frequency.unwrap_or(rule.frequency),
+        interval: request.interval.unwrap_or(rule.interval),
+        start_date: request.next_run_date.unwrap_or(rule.next_run_date),
+    });
+
+    let updates = UpdateRecurringTransaction {
+        account_id: request.account_id.unwrap_or(rule.account_id),
+        category_id: request.category_id.unwrap_or(rule.category_id),
+        title: request.title.unwrap_or(rule.title),
+        amount: request
+            .amount
+            .map_or(rule.amount, |amount| amount.into_decimal()),
+        notes: request.notes.unwrap_or(rule.notes),
+        schedule,
+    };
+    let rule = repositories::recurring::update_rule(pool, rule_id, updates).await?;
+
+    tracing::info!(
+        "Updated recurring transaction {} for user {}",
+        rule_id,
+        user_id
+    );
+
+    Ok(rule.into())
+}
+
+/// Delete a recurring transaction
+///
+/// Transactions already created are kept.
+pub async fn delete_rule(pool: &DbPool, rule_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+    find_owned(pool, rule_id, user_id).await?;
+
+    repositories::recurring::delete_rule(pool, rule_id).await?;
+
+    tracing::info!(
+        "Deleted recurring transaction {} for user {}",
+        rule_id,
+        user_id
+    );
+
+    Ok(())
+}
+
+/// Create the transactions of every occurrence of the user's recurring
+/// transactions due by `now`, and advance their schedules
+///
+/// Returns the created transactions. Running it again creates nothing until
+/// the next occurrence is due.
+pub async fn materialize_due_recurring(
+    pool: &DbPool,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Vec<Transaction>, ApiError> {
+    let today = now.date_naive();
+    let mut created = Vec::new();
+
+    for rule_id in repositories::recurring::list_due_ids(pool, user_id, today).await? {
+        // Catch up in batches, so a long outage does not create one huge insert
+        loop {
+            let batch = repositories::recurring::materialize_due(
+                pool,
+                rule_id,
+                today,
+                MATERIALIZE_BATCH_SIZE,
+            )
+            .await?;
+            let done = batch.len() < MATERIALIZE_BATCH_SIZE;
+            created.extend(batch);
+            if done {
+                break;
+            }
+        }
+    }
+
+    if !created.is_empty() {
+        tracing::info!(
+            "Created {} recurring transactions for user {}",
+            created.len(),
+            user_id
+        );
+    }
+
+    Ok(created)
+}
+
+/// Spawn a background task that periodically materializes the due recurring
+/// transactions of all users
+///
+/// The first run happens at startup, catching up on occurrences missed while
+/// the server was down. Created transactions are published to `events`. Does
+/// nothing when disabled (`interval_minutes == 0`).
+pub fn spawn_materialization(pool: DbPool, events: EventBus, config: RecurringConfig) {
+    if config.interval_minutes == 0 {
+        tracing::info!("Recurring transactions disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_minutes * 60));
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+            let user_ids =
+                match repositories::recurring::list_due_user_ids(&pool, now.date_naive()).await {
+                    Ok(user_ids) => user_ids,
+                    Err(e) => {
+                        tracing::error!("Listing due recurring transactions failed: {}", e);
+                        continue;
+                    }
+                };
+
+            for user_id in user_ids {
+                match materialize_due_recurring(&pool, user_id, now).await {
+                    Ok(created) => {
+                        for transaction in created {
+                            events.publish(
+                                user_id,
+                                ChangeEvent::TransactionCreated {
+                                    transaction_id: transaction.id,
+                                },
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Recurring transactions of user {} failed: {}", user_id, e)
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Find a recurring transaction, checking that it belongs to the user
+async fn find_owned(
+    pool: &DbPool,
+    rule_id: Uuid,
+    user_id: Uuid,
+) -> Result<RecurringTransaction, ApiError> {
+    let rule = repositories::recurring::find_by_id(pool, rule_id).await?;
+    if rule.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to access recurring transaction {} owned by {}",
+            user_id,
+            rule_id,
+            rule.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(rule)
+}
+
+/// Check that an account belongs to the user
+async fn verify_account(pool: &DbPool, user_id: Uuid, account_id: Uuid) -> Result<(), ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to schedule transactions for account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Account does not belong to user".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that a category belongs to the user
+async fn verify_category(pool: &DbPool, user_id: Uuid, category_id: Uuid) -> Result<(), ApiError> {
+    let category = repositories::category::find_by_id(pool, category_id).await?;
+    if category.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to use category {} owned by {}",
+            user_id,
+            category_id,
+            category.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Category does not belong to user".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Split groups
+//!
+//! A split group is a named set of people, each with the percentage of a
+//! transaction they owe by default, e.g. a household whose members each pay a
+//! third. Creating a transaction with a `split_group_id` expands the group into
+//! one split per member; the percentages left over are the user's own share.
+
+use bigdecimal::BigDecimal;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        CreateSplitGroupRequest, NewSplitGroup, SplitGroupMemberInput, SplitGroupResponse,
+        UpdateSplitGroupRequest, allocation_rule::percentage_to_decimal, split_group::group_shares,
+    },
+    repositories,
+    types::CurrencyCode,
+};
+
+/// Create a split group
+///
+/// Every member must be one of the user's people.
+pub async fn create_group(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateSplitGroupRequest,
+) -> Result<SplitGroupResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Split group validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let members = resolve_members(pool, user_id, request.members).await?;
+
+    let new_group = NewSplitGroup {
+        user_id,
+        name: request.name,
+    };
+    let (group, members) =
+        repositories::split_group::create_group(pool, new_group, members).await?;
+
+    tracing::info!("Created split group {} for user {}", group.id, user_id);
+
+    Ok(SplitGroupResponse::new(group, members))
+}
+
+/// List all split groups of a user
+pub async fn list_groups(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<Vec<SplitGroupResponse>, ApiError> {
+    let groups = repositories::split_group::list_by_user(pool, user_id).await?;
+
+    Ok(groups
+        .into_iter()
+        .map(|(group, members)| SplitGroupResponse::new(group, members))
+        .collect())
+}
+
+/// Get a split group
+pub async fn get_group(
+    pool: &DbPool,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<SplitGroupResponse, ApiError> {
+    let (group, members) = find_owned(pool, group_id, user_id).await?;
+
+    Ok(SplitGroupResponse::new(group, members))
+}
+
+/// Rename a split group and/or replace its members
+///
+/// Transactions already split with the group keep their splits.
+pub async fn update_group(
+    pool: &DbPool,
+    group_id: Uuid,
+    user_id: Uuid,
+    request: UpdateSplitGroupRequest,
+) -> Result<SplitGroupResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Split group validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    find_owned(pool, group_id, user_id).await?;
+
+    let members = match request.members {
+        Some(members) => Some(resolve_members(pool, user_id, members).await?),
+        None => None,
+    };
+    let (group, members) =
+        repositories::split_group::update_group(pool, group_id, request.name, members).await?;
+
+    tracing::info!("Updated split group {} for user {}", group_id, user_id);
+
+    Ok(SplitGroupResponse::new(group, members))
+}
+
+/// Delete a split group
+///
+/// Transactions already split with the group keep their splits.
+pub async fn delete_group(pool: &DbPool, group_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+    find_owned(pool, group_id, user_id).await?;
+
+    repositories::split_group::delete_group(pool, group_id).await?;
+
+    tracing::info!("Deleted split group {} for user {}", group_id, user_id);
+
+    Ok(())
+}
+
+/// Expand a split group into the members' shares of a transaction amount
+///
+/// Returns `(person_id, amount)` pairs in ascending person ID order, rounded to
+/// the minor units of `currency` (see [`group_shares`]).
+pub async fn expand_group(
+    pool: &DbPool,
+    group_id: Uuid,
+    user_id: Uuid,
+    amount: &BigDecimal,
+    currency: CurrencyCode,
+) -> Result<Vec<(Uuid, BigDecimal)>, ApiError> {
+    let (_, members) = find_owned(pool, group_id, user_id).await?;
+
+    let percentages: Vec<(Uuid, BigDecimal)> = members
+        .into_iter()
+        .map(|member| (member.person_id, member.percentage))
+        .collect();
+
+    Ok(group_shares(
+        &amount.abs(),
+        user_id,
+        &percentages,
+        currency.minor_units(),
+    ))
+}
+
+/// Find a split group, checking that it belongs to the user
+async fn find_owned(
+    pool: &DbPool,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<repositories::split_group::GroupWithMembers, ApiError> {
+    let (group, members) = repositories::split_group::find_by_id(pool, group_id).await?;
+    if group.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to access split group {} owned by {}",
+            user_id,
+            group_id,
+            group.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Split group does not belong to user".to_string(),
+        ));
+    }
+
+    Ok((group, members))
+}
+
+/// Check the members' people belong to the user and convert their percentages
+async fn resolve_members(
+    pool: &DbPool,
+    user_id: Uuid,
+    members: Vec<SplitGroupMemberInput>,
+) -> Result<Vec<(Uuid, BigDecimal)>, ApiError> {
+    let mut resolved = Vec::with_capacity(members.len());
+    for member in members {
+        let person = repositories::person::find_by_id(pool, member.person_id).await?;
+        if person.user_id != user_id {
+            tracing::warn!(
+                "User {} attempted to add person {} owned by {} to a split group",
+                user_id,
+                person.id,
+                person.user_id
+            );
+            return Err(ApiError::Forbidden(
+                "Person does not belong to user".to_string(),
+            ));
+        }
+
+        // Checked by the request validation
+        let percentage = percentage_to_decimal(member.percentage)
+            .ok_or_else(|| ApiError::Validation("Invalid percentage".to_string()))?;
+        resolved.push((person.id, percentage));
+    }
+
+    Ok(resolved)
+}
@@ -1,13 +1,14 @@
 pub mod splitwise;
 pub mod types;
 
-pub use splitwise::SplitwiseProvider;
+pub use splitwise::{SplitwiseProvider, SplitwiseWebhookEvent};
 pub use types::{
-    CreateExternalExpense, ExpenseUser, ExternalExpenseResult, SplitProviderError,
+    CreateExternalExpense, ExpenseUser, ExternalExpense, ExternalExpenseResult, SplitProviderError,
     UpdateExternalExpense,
 };
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 
 /// Trait for split provider implementations (Splitwise, SplitPro, etc.)
@@ -64,6 +65,50 @@ pub trait SplitProvider: Send + Sync {
         request: UpdateExternalExpense,
     ) -> Result<ExternalExpenseResult, SplitProviderError>;
 
+    /// Fetch an expense from the external platform
+    ///
+    /// Used to detect expenses that were changed on the platform after syncing.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - Provider-specific credentials
+    /// * `external_expense_id` - ID of the expense on the external platform
+    ///
+    /// # Errors
+    ///
+    /// Returns `SplitProviderError` if:
+    /// - Authentication fails
+    /// - Expense not found
+    /// - Rate limit is exceeded
+    async fn get_expense(
+        &self,
+        credentials: &Value,
+        external_expense_id: &str,
+    ) -> Result<ExternalExpense, SplitProviderError>;
+
+    /// List the expenses dated within a range on the external platform
+    ///
+    /// Used to compare the expenses on the platform with local splits.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - Provider-specific credentials
+    /// * `start` - Earliest expense date, inclusive
+    /// * `end` - Latest expense date, inclusive
+    ///
+    /// # Errors
+    ///
+    /// Returns `SplitProviderError` if:
+    /// - Authentication fails
+    /// - Rate limit is exceeded
+    /// - API request fails
+    async fn list_expenses(
+        &self,
+        credentials: &Value,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ExternalExpense>, SplitProviderError>;
+
     /// Delete an expense from the external platform
     ///
     /// # Arguments
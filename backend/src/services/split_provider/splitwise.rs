@@ -5,8 +5,8 @@ use serde::Deserialize;
 use serde_json::{Value, json};
 
 use super::{
-    CreateExternalExpense, ExpenseUser, ExternalExpenseResult, SplitProvider, SplitProviderError,
-    UpdateExternalExpense,
+    CreateExternalExpense, ExpenseUser, ExternalExpense, ExternalExpenseResult, SplitProvider,
+    SplitProviderError, UpdateExternalExpense,
 };
 
 /// Splitwise API provider implementation
@@ -273,6 +273,83 @@ impl SplitProvider for SplitwiseProvider {
         })
     }
 
+    async fn get_expense(
+        &self,
+        credentials: &Value,
+        external_expense_id: &str,
+    ) -> Result<ExternalExpense, SplitProviderError> {
+        let access_token = Self::get_access_token(credentials)?;
+
+        let response = self
+            .http_client
+            .get(format!(
+                "{}/get_expense/{}",
+                Self::BASE_URL,
+                external_expense_id
+            ))
+            .bearer_auth(&access_token)
+            .send()
+            .await
+            .map_err(|e| SplitProviderError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read response body".to_string());
+
+        if !status.is_success() {
+            return Err(Self::map_status_error(status, &body));
+        }
+
+        let json_response: SplitwiseGetExpenseResponse = serde_json::from_str(&body)
+            .map_err(|e| SplitProviderError::InvalidResponse(e.to_string()))?;
+
+        Ok(json_response.expense.into())
+    }
+
+    async fn list_expenses(
+        &self,
+        credentials: &Value,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ExternalExpense>, SplitProviderError> {
+        let access_token = Self::get_access_token(credentials)?;
+
+        // `limit=0` returns every expense in the range instead of the first 20
+        let response = self
+            .http_client
+            .get(format!("{}/get_expenses", Self::BASE_URL))
+            .query(&[
+                ("dated_after", start.to_rfc3339()),
+                ("dated_before", end.to_rfc3339()),
+                ("limit", "0".to_string()),
+            ])
+            .bearer_auth(&access_token)
+            .send()
+            .await
+            .map_err(|e| SplitProviderError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read response body".to_string());
+
+        if !status.is_success() {
+            return Err(Self::map_status_error(status, &body));
+        }
+
+        let json_response: SplitwiseListExpensesResponse = serde_json::from_str(&body)
+            .map_err(|e| SplitProviderError::InvalidResponse(e.to_string()))?;
+
+        Ok(json_response
+            .expenses
+            .into_iter()
+            .map(ExternalExpense::from)
+            .collect())
+    }
+
     async fn delete_expense(
         &self,
         credentials: &Value,
@@ -406,6 +483,68 @@ struct SplitwiseExpense {
     id: i64,
 }
 
+#[derive(Debug, Deserialize)]
+struct SplitwiseGetExpenseResponse {
+    expense: SplitwiseExpenseDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitwiseExpenseDetails {
+    id: i64,
+    cost: String,
+    deleted_at: Option<String>,
+    users: Vec<SplitwiseExpenseShare>,
+}
+
+impl From<SplitwiseExpenseDetails> for ExternalExpense {
+    fn from(expense: SplitwiseExpenseDetails) -> Self {
+        Self {
+            external_expense_id: expense.id.to_string(),
+            cost: expense.cost,
+            users: expense
+                .users
+                .into_iter()
+                .map(|user| ExpenseUser {
+                    external_user_id: user.user_id.to_string(),
+                    paid_share: user.paid_share,
+                    owed_share: user.owed_share,
+                })
+                .collect(),
+            deleted: expense.deleted_at.is_some(),
+        }
+    }
+}
+
+/// Expense change pushed to the Splitwise webhook
+///
+/// `event_type` is `expense.created`, `expense.updated` or `expense.deleted`,
+/// and the expense is as Splitwise returns it from `get_expense`.
+#[derive(Debug, Deserialize)]
+pub struct SplitwiseWebhookEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    expense: SplitwiseExpenseDetails,
+}
+
+impl SplitwiseWebhookEvent {
+    /// The expense as it is on Splitwise after the event
+    pub fn into_expense(self) -> ExternalExpense {
+        self.expense.into()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitwiseListExpensesResponse {
+    expenses: Vec<SplitwiseExpenseDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitwiseExpenseShare {
+    user_id: i64,
+    paid_share: String,
+    owed_share: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct SplitwiseDeleteResponse {
     success: bool,
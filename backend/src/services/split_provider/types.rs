@@ -56,6 +56,19 @@ pub struct ExternalExpenseResult {
     pub external_url: Option<String>,
 }
 
+/// Expense as currently stored on an external platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalExpense {
+    /// ID of the expense on the external platform
+    pub external_expense_id: String,
+    /// Total cost (e.g., "100.00")
+    pub cost: String,
+    /// All users involved in the expense with their shares
+    pub users: Vec<ExpenseUser>,
+    /// Whether the expense was deleted on the platform
+    pub deleted: bool,
+}
+
 /// Errors that can occur when interacting with split providers
 #[derive(Debug, Error)]
 pub enum SplitProviderError {
@@ -65,6 +78,10 @@ pub enum SplitProviderError {
     #[error("Access token expired")]
     TokenExpired,
 
+    /// Credentials were rejected even after refreshing them; the provider must be reconnected
+    #[error("Authorization expired, reconnect the provider")]
+    AuthExpired,
+
     #[error("Rate limit exceeded. Retry after: {0:?}")]
     RateLimited(Option<DateTime<Utc>>),
 
@@ -82,6 +99,10 @@ pub enum SplitProviderError {
 
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
+
+    /// Refreshed credentials could not be saved, so the old ones may already be revoked
+    #[error("Failed to store credentials: {0}")]
+    CredentialsStorage(String),
 }
 
 impl SplitProviderError {
@@ -99,7 +120,9 @@ impl SplitProviderError {
     pub fn requires_reauth(&self) -> bool {
         matches!(
             self,
-            SplitProviderError::AuthenticationFailed(_) | SplitProviderError::TokenExpired
+            SplitProviderError::AuthenticationFailed(_)
+                | SplitProviderError::TokenExpired
+                | SplitProviderError::AuthExpired
         )
     }
 }
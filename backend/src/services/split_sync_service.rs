@@ -1,33 +1,68 @@
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
 use crate::DbPool;
+use crate::config::SplitReconciliationConfig;
 use crate::errors::{ApiError, ApiResult};
 use crate::models::account::Account;
 use crate::models::person_split_config::PersonSplitConfig;
 use crate::models::split_provider::SplitProvider as SplitProviderModel;
 use crate::models::split_sync_record::{
-    NewSplitSyncRecord, SplitSyncRecord, SyncStatus, UpdateSplitSyncRecord,
+    NewOrphanedExternalExpense, NewSplitSyncRecord, SplitSyncRecord, SyncReportError,
+    SyncReportExpense, SyncReportResponse, SyncReportSplit, SyncStatus, UpdateSplitSyncRecord,
 };
 use crate::models::transaction::Transaction;
 use crate::models::transaction_split::TransactionSplit;
+use crate::repositories;
 use crate::repositories::split_sync_record::SplitSyncRecordRepository;
 use crate::schema::{
-    accounts, person_split_configs, split_providers, transaction_splits, transactions,
+    accounts, people, person_split_configs, split_providers, split_sync_records,
+    transaction_splits, transactions,
 };
 use crate::services::split_provider::{
-    CreateExternalExpense, ExpenseUser, SplitProvider, SplitwiseProvider, UpdateExternalExpense,
+    CreateExternalExpense, ExpenseUser, ExternalExpense, SplitProvider, SplitProviderError,
+    SplitwiseProvider, UpdateExternalExpense,
 };
+use crate::types::TransactionStatus;
 use crate::utils::encryption;
 
 /// Maximum number of retry attempts for failed syncs
 const MAX_RETRY_COUNT: i32 = 5;
 
+/// Pause between provider requests during reconciliation
+const RECONCILE_REQUEST_SPACING: Duration = Duration::from_millis(250);
+
+/// Largest multiple of the reconciliation interval to wait after being rate limited
+const MAX_RECONCILE_BACKOFF: u32 = 8;
+
+/// Requests in a row a provider may reject for bad credentials before it is disabled
+pub const MAX_CONSECUTIVE_AUTH_FAILURES: i32 = 3;
+
+/// A provider's expenses within a range, with the IDs of all its expenses linked to a split
+type ProviderExpenses = (Vec<ExternalExpense>, HashSet<String>);
+
+/// Outcome of one reconciliation run
+///
+/// Splits are counted individually, since the splits of one expense can differ.
+#[derive(Debug, Default)]
+pub struct ReconcileOutcome {
+    pub synced: usize,
+    pub drifted: usize,
+    pub failed: usize,
+    /// Providers flagged for reconnection because their credentials were rejected or unusable
+    pub providers_flagged: usize,
+    /// The run stopped early because a provider rate limited it
+    pub rate_limited: bool,
+}
+
 /// Service for syncing transaction splits to external split providers
 #[derive(Clone)]
 pub struct SplitSyncService {
@@ -48,6 +83,14 @@ impl SplitSyncService {
         // let splitpro = Arc::new(SplitProProvider::new());
         // providers.insert("splitpro".to_string(), splitpro);
 
+        Self::with_providers(pool, providers)
+    }
+
+    /// Create a SplitSyncService with the given provider implementations, keyed by provider type
+    pub fn with_providers(
+        pool: DbPool,
+        providers: HashMap<String, Arc<dyn SplitProvider>>,
+    ) -> Self {
         Self {
             pool,
             providers: Arc::new(providers),
@@ -208,6 +251,91 @@ impl SplitSyncService {
         Ok(())
     }
 
+    /// Delete the external expenses linked to a transaction before it is deleted locally
+    ///
+    /// Each linked expense is deleted from its provider once. If a deletion fails,
+    /// an error is returned so the transaction is not deleted and the expense is not
+    /// silently orphaned; with `force`, the expense is recorded as orphaned for later
+    /// cleanup instead. Expenses already deleted are marked so a retry skips them.
+    pub async fn delete_transaction_expenses(
+        &self,
+        transaction_id: Uuid,
+        force: bool,
+    ) -> ApiResult<()> {
+        let records =
+            SplitSyncRecordRepository::find_by_transaction_id(&self.pool, transaction_id)?;
+
+        // Several splits of a transaction share one expense per provider
+        let mut expenses: HashMap<(Uuid, String), Vec<Uuid>> = HashMap::new();
+        for record in records {
+            if record.status() == SyncStatus::Deleted {
+                continue;
+            }
+            if let Some(external_expense_id) = record.external_expense_id {
+                expenses
+                    .entry((record.split_provider_id, external_expense_id))
+                    .or_default()
+                    .push(record.id);
+            }
+        }
+
+        for ((provider_id, external_expense_id), record_ids) in expenses {
+            match self.delete_expense(provider_id, &external_expense_id).await {
+                Ok(()) => {
+                    for record_id in record_ids {
+                        let update = UpdateSplitSyncRecord {
+                            external_expense_id: None,
+                            sync_status: Some(SyncStatus::Deleted.as_str().to_string()),
+                            last_sync_at: Some(Utc::now()),
+                            last_error: None,
+                            retry_count: None,
+                            external_user_id: None,
+                        };
+                        if let Err(e) =
+                            SplitSyncRecordRepository::update(&self.pool, record_id, update)
+                        {
+                            tracing::error!("Failed to update sync record {}: {}", record_id, e);
+                        }
+                    }
+                }
+                Err(e) if force => {
+                    tracing::warn!(
+                        "Orphaning expense {} on provider {} of force-deleted transaction {}: {}",
+                        external_expense_id,
+                        provider_id,
+                        transaction_id,
+                        e
+                    );
+                    SplitSyncRecordRepository::record_orphaned_expense(
+                        &self.pool,
+                        NewOrphanedExternalExpense {
+                            split_provider_id: provider_id,
+                            external_expense_id,
+                            transaction_id,
+                            last_error: Some(e.to_string()),
+                        },
+                    )?;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to delete expense {} from provider {} for transaction {}: {}",
+                        external_expense_id,
+                        provider_id,
+                        transaction_id,
+                        e
+                    );
+                    return Err(ApiError::External(format!(
+                        "Failed to delete linked external expense {}: {}. \
+                         Retry later, or delete with ?force=true to keep the external expense",
+                        external_expense_id, e
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Retry a failed sync
     pub async fn retry_failed_sync(&self, sync_record_id: Uuid) -> ApiResult<SplitSyncRecord> {
         let record = SplitSyncRecordRepository::find_by_id(&self.pool, sync_record_id)?
@@ -256,6 +384,503 @@ impl SplitSyncService {
         Ok(updated_record)
     }
 
+    /// Compare a batch of synced expenses with the provider and record drift
+    ///
+    /// Expenses are fetched one at a time with a short pause in between. A provider
+    /// whose credentials are rejected has its remaining expenses skipped, and is
+    /// marked inactive, which flags it for reconnection, once it has rejected
+    /// [`MAX_CONSECUTIVE_AUTH_FAILURES`] requests in a row. The run stops early
+    /// when a provider rate limits it.
+    pub async fn reconcile_batch(&self, batch_size: i64) -> ApiResult<ReconcileOutcome> {
+        let pool = self.pool.clone();
+        let records = tokio::task::spawn_blocking(move || {
+            SplitSyncRecordRepository::find_for_reconciliation(&pool, batch_size)
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Task join error: {}", e);
+            ApiError::Internal
+        })??;
+
+        // One external expense covers all splits of a transaction for a provider
+        let mut expenses: Vec<((Uuid, String), Vec<SplitSyncRecord>)> = Vec::new();
+        for record in records {
+            let Some(external_expense_id) = record.external_expense_id.clone() else {
+                continue;
+            };
+            let key = (record.split_provider_id, external_expense_id);
+            match expenses.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(record),
+                None => expenses.push((key, vec![record])),
+            }
+        }
+
+        let mut outcome = ReconcileOutcome::default();
+        let mut providers: HashMap<Uuid, (SplitProviderModel, serde_json::Value)> = HashMap::new();
+        let mut skipped_providers: HashSet<Uuid> = HashSet::new();
+
+        for ((provider_id, external_expense_id), records) in expenses {
+            if skipped_providers.contains(&provider_id) {
+                continue;
+            }
+
+            if let Entry::Vacant(entry) = providers.entry(provider_id) {
+                let mut conn = self.pool.get().map_err(|e| {
+                    tracing::error!("Failed to get DB connection: {}", e);
+                    ApiError::Internal
+                })?;
+                let provider_model = split_providers::table
+                    .find(provider_id)
+                    .first::<SplitProviderModel>(&mut conn)?;
+
+                match Self::decrypt_provider_credentials(&provider_model) {
+                    Ok(credentials) => {
+                        entry.insert((provider_model, credentials));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Unusable credentials for provider {}: {}", provider_id, e);
+                        self.flag_for_reconnect(&provider_model).await;
+                        outcome.providers_flagged += 1;
+                        skipped_providers.insert(provider_id);
+                        continue;
+                    }
+                }
+            }
+            let (provider_model, credentials) = &providers[&provider_id];
+
+            let Some(provider) = self.providers.get(&provider_model.provider_type) else {
+                skipped_providers.insert(provider_id);
+                continue;
+            };
+
+            let fetched = provider
+                .get_expense(credentials, &external_expense_id)
+                .await;
+            let disabled = self.record_auth_result(provider_model, &fetched).await;
+            tokio::time::sleep(RECONCILE_REQUEST_SPACING).await;
+
+            // Problems with the expense as a whole apply to all of its splits
+            let whole_expense = |status: SyncStatus, error: Option<String>| {
+                records
+                    .iter()
+                    .map(|record| (record.id, status.clone(), error.clone()))
+                    .collect::<Vec<_>>()
+            };
+            let results = match fetched {
+                Ok(expense) => match self.detect_drift(provider_id, &records, &expense).await {
+                    Ok(mut drifts) => records
+                        .iter()
+                        .map(|record| match drifts.remove(&record.id) {
+                            Some(drift) => (record.id, SyncStatus::Drifted, Some(drift)),
+                            None => (record.id, SyncStatus::Synced, None),
+                        })
+                        .collect(),
+                    Err(e) => whole_expense(SyncStatus::Failed, Some(e.to_string())),
+                },
+                Err(SplitProviderError::NotFound(_)) => whole_expense(
+                    SyncStatus::Drifted,
+                    Some("Expense no longer exists on the provider".to_string()),
+                ),
+                Err(SplitProviderError::RateLimited(retry_after)) => {
+                    tracing::warn!(
+                        "Provider {} rate limited reconciliation (retry after {:?})",
+                        provider_id,
+                        retry_after
+                    );
+                    outcome.rate_limited = true;
+                    break;
+                }
+                Err(e) if e.requires_reauth() => {
+                    tracing::warn!("Provider {} rejected credentials: {}", provider_id, e);
+                    if disabled {
+                        outcome.providers_flagged += 1;
+                    }
+                    skipped_providers.insert(provider_id);
+                    continue;
+                }
+                Err(e) => whole_expense(SyncStatus::Failed, Some(e.to_string())),
+            };
+
+            for (record_id, status, last_error) in results {
+                match status {
+                    SyncStatus::Synced => outcome.synced += 1,
+                    SyncStatus::Drifted => outcome.drifted += 1,
+                    _ => outcome.failed += 1,
+                }
+
+                let update = UpdateSplitSyncRecord {
+                    external_expense_id: None,
+                    sync_status: Some(status.as_str().to_string()),
+                    last_sync_at: Some(Utc::now()),
+                    last_error,
+                    retry_count: None,
+                    external_user_id: None,
+                };
+                if let Err(e) = SplitSyncRecordRepository::update(&self.pool, record_id, update) {
+                    tracing::error!("Failed to record reconciliation of {}: {}", record_id, e);
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Compare the user's splits dated within a range with each person's provider
+    ///
+    /// Every person with a split config is compared with the expenses on their
+    /// own provider. Nothing is written: sync records, auth failures and
+    /// credentials are left as they are. A provider that cannot be read is
+    /// listed in `errors` and its people are left out, without failing the
+    /// whole report.
+    pub async fn sync_report(
+        &self,
+        user_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> ApiResult<SyncReportResponse> {
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+
+        let configs = person_split_configs::table
+            .inner_join(people::table)
+            .filter(people::user_id.eq(user_id))
+            .order(people::name.asc())
+            .select(PersonSplitConfig::as_select())
+            .load::<PersonSplitConfig>(&mut conn)?;
+
+        let mut report = SyncReportResponse::default();
+        // Each provider is read once, however many people use it
+        let mut fetched: HashMap<Uuid, Result<ProviderExpenses, String>> = HashMap::new();
+        let amount = |value: &str| BigDecimal::from_str(value).ok();
+
+        for config in configs {
+            let provider_id = config.split_provider_id;
+            if let Entry::Vacant(entry) = fetched.entry(provider_id) {
+                let result = self
+                    .list_provider_expenses(provider_id, start, end)
+                    .await
+                    .map_err(|e| {
+                        tracing::warn!(
+                            "Failed to read provider {} for sync report: {}",
+                            provider_id,
+                            e
+                        );
+                        e.to_string()
+                    });
+                entry.insert(result);
+            }
+            let (expenses, linked) = match &fetched[&provider_id] {
+                Ok(fetched) => fetched,
+                Err(error) => {
+                    report.errors.push(SyncReportError {
+                        person_id: config.person_id,
+                        split_provider_id: provider_id,
+                        error: error.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            let splits = transaction_splits::table
+                .inner_join(transactions::table)
+                .left_join(
+                    split_sync_records::table.on(split_sync_records::transaction_split_id
+                        .eq(transaction_splits::id)
+                        .and(split_sync_records::split_provider_id.eq(provider_id))),
+                )
+                .filter(transaction_splits::person_id.eq(config.person_id))
+                .filter(transactions::user_id.eq(user_id))
+                .filter(transactions::date.between(start, end))
+                .filter(transactions::status.ne(TransactionStatus::Void))
+                .filter(transactions::deleted_at.is_null())
+                .order(transactions::date.asc())
+                .select((
+                    TransactionSplit::as_select(),
+                    transactions::amount,
+                    split_sync_records::all_columns.nullable(),
+                ))
+                .load::<(TransactionSplit, BigDecimal, Option<SplitSyncRecord>)>(&mut conn)?;
+
+            for (split, transaction_amount, record) in splits {
+                let entry = |external_expense_id: Option<String>, reason: String| SyncReportSplit {
+                    person_id: config.person_id,
+                    split_provider_id: provider_id,
+                    transaction_id: split.transaction_id,
+                    transaction_split_id: split.id,
+                    external_expense_id,
+                    reason,
+                };
+
+                let record = record.filter(|record| record.status() != SyncStatus::Deleted);
+                let Some(external_expense_id) = record
+                    .as_ref()
+                    .and_then(|record| record.external_expense_id.clone())
+                else {
+                    let reason = match record.and_then(|record| record.last_error) {
+                        Some(error) => format!("Sync failed: {}", error),
+                        None => "Split was never synced".to_string(),
+                    };
+                    report.missing_on_external_splits.push(entry(None, reason));
+                    continue;
+                };
+
+                let Some(expense) = expenses
+                    .iter()
+                    .find(|expense| expense.external_expense_id == external_expense_id)
+                else {
+                    report.missing_on_external_splits.push(entry(
+                        Some(external_expense_id),
+                        "Expense not found on the provider within the range".to_string(),
+                    ));
+                    continue;
+                };
+                if expense.deleted {
+                    report.missing_on_external_splits.push(entry(
+                        Some(external_expense_id),
+                        "Expense was deleted on the provider".to_string(),
+                    ));
+                    continue;
+                }
+
+                let cost = transaction_amount.abs();
+                let owed = expense
+                    .users
+                    .iter()
+                    .find(|user| user.external_user_id == config.external_user_id)
+                    .and_then(|user| amount(&user.owed_share));
+                if amount(&expense.cost) != Some(cost.clone()) {
+                    report.drifted_splits.push(entry(
+                        Some(external_expense_id),
+                        format!(
+                            "Provider cost {} differs from transaction amount {}",
+                            expense.cost, cost
+                        ),
+                    ));
+                } else if owed != Some(split.amount.abs()) {
+                    report.drifted_splits.push(entry(
+                        Some(external_expense_id),
+                        format!(
+                            "Share of provider user {} differs from split amount {}",
+                            config.external_user_id,
+                            split.amount.abs()
+                        ),
+                    ));
+                } else {
+                    report.in_sync += 1;
+                }
+            }
+
+            // Expenses shared with the person that no split of any person was synced to
+            let zero = BigDecimal::from(0);
+            for expense in expenses {
+                if expense.deleted || linked.contains(&expense.external_expense_id) {
+                    continue;
+                }
+                let Some(user) = expense.users.iter().find(|user| {
+                    user.external_user_id == config.external_user_id
+                        && amount(&user.owed_share).is_some_and(|owed| owed > zero)
+                }) else {
+                    continue;
+                };
+                report.missing_on_local_expenses.push(SyncReportExpense {
+                    person_id: config.person_id,
+                    split_provider_id: provider_id,
+                    external_expense_id: expense.external_expense_id.clone(),
+                    cost: expense.cost.clone(),
+                    owed_share: user.owed_share.clone(),
+                });
+            }
+        }
+
+        report.drifted = report.drifted_splits.len() as i64;
+        report.missing_on_external = report.missing_on_external_splits.len() as i64;
+        report.missing_on_local = report.missing_on_local_expenses.len() as i64;
+
+        Ok(report)
+    }
+
+    /// List a provider's expenses within a range, with the IDs of all its expenses
+    /// linked to a split
+    async fn list_provider_expenses(
+        &self,
+        provider_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> ApiResult<ProviderExpenses> {
+        let (_, provider, credentials) = self.load_active_provider(provider_id).await?;
+
+        let expenses = provider
+            .list_expenses(&credentials, start, end)
+            .await
+            .map_err(|e| ApiError::External(format!("Failed to list expenses: {}", e)))?;
+
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+        let linked = split_sync_records::table
+            .filter(split_sync_records::split_provider_id.eq(provider_id))
+            .select(split_sync_records::external_expense_id)
+            .load::<Option<String>>(&mut conn)?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok((expenses, linked))
+    }
+
+    /// Describe how each split's share of an external expense differs from the local split
+    ///
+    /// Returns the drift of each drifted sync record, keyed by record ID; records
+    /// without an entry are in sync. A deleted expense, a different cost or
+    /// unexpected participants drift every split of the expense, while a wrong
+    /// share only drifts the split it belongs to.
+    async fn detect_drift(
+        &self,
+        provider_id: Uuid,
+        records: &[SplitSyncRecord],
+        expense: &ExternalExpense,
+    ) -> ApiResult<HashMap<Uuid, String>> {
+        let whole_expense = |drift: String| {
+            records
+                .iter()
+                .map(|record| (record.id, drift.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        if expense.deleted {
+            return Ok(whole_expense(
+                "Expense was deleted on the provider".to_string(),
+            ));
+        }
+
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
+        let split_ids: Vec<Uuid> = records.iter().map(|r| r.transaction_split_id).collect();
+        let transaction_id = transaction_splits::table
+            .filter(transaction_splits::id.eq_any(&split_ids))
+            .select(transaction_splits::transaction_id)
+            .first::<Uuid>(&mut conn)?;
+
+        let (transaction, splits_with_configs) =
+            self.fetch_transaction_and_splits(transaction_id).await?;
+        let splits = self
+            .group_splits_by_provider(splits_with_configs)
+            .remove(&provider_id)
+            .unwrap_or_default();
+
+        let amount = |value: &str| BigDecimal::from_str(value).ok();
+
+        let cost = transaction.amount.abs();
+        if amount(&expense.cost) != Some(cost.clone()) {
+            return Ok(whole_expense(format!(
+                "Provider cost {} differs from transaction amount {}",
+                expense.cost, cost
+            )));
+        }
+
+        // Everyone except the payer paid nothing and must be one of the split people
+        let participants = expense
+            .users
+            .iter()
+            .filter(|user| amount(&user.paid_share) == Some(BigDecimal::from(0)))
+            .count();
+        if participants != splits.len() {
+            return Ok(whole_expense(format!(
+                "Provider expense has {} participants but the transaction has {} splits",
+                participants,
+                splits.len()
+            )));
+        }
+
+        let mut drifts = HashMap::new();
+        for record in records {
+            let Some((split, config)) = splits
+                .iter()
+                .find(|(split, _)| split.id == record.transaction_split_id)
+            else {
+                drifts.insert(
+                    record.id,
+                    "Split's person is no longer linked to this provider".to_string(),
+                );
+                continue;
+            };
+
+            // Records synced before the user was tracked fall back to the current mapping
+            let external_user_id = record
+                .external_user_id
+                .as_deref()
+                .unwrap_or(&config.external_user_id);
+            if external_user_id != config.external_user_id {
+                drifts.insert(
+                    record.id,
+                    format!(
+                        "Split was synced as provider user {} but its person is now linked to {}",
+                        external_user_id, config.external_user_id
+                    ),
+                );
+                continue;
+            }
+
+            let owed = expense
+                .users
+                .iter()
+                .find(|user| user.external_user_id == external_user_id)
+                .and_then(|user| amount(&user.owed_share));
+            if owed != Some(split.amount.abs()) {
+                drifts.insert(
+                    record.id,
+                    format!(
+                        "Share of provider user {} differs from split amount {}",
+                        external_user_id,
+                        split.amount.abs()
+                    ),
+                );
+            }
+        }
+
+        Ok(drifts)
+    }
+
+    /// Decrypt the stored credentials of a provider
+    fn decrypt_provider_credentials(
+        provider_model: &SplitProviderModel,
+    ) -> ApiResult<serde_json::Value> {
+        let encrypted = provider_model
+            .credentials
+            .get("encrypted")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ApiError::InternalWithMessage("Invalid credentials format".to_string())
+            })?;
+
+        encryption::decrypt_credentials(encrypted).map_err(|e| {
+            ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e))
+        })
+    }
+
+    /// Mark a provider inactive so the user is asked to reconnect it
+    async fn flag_for_reconnect(&self, provider_model: &SplitProviderModel) {
+        if let Err(e) = repositories::split_provider::update_active_status(
+            &self.pool,
+            provider_model.id,
+            provider_model.user_id,
+            false,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to flag provider {} for reconnection: {}",
+                provider_model.id,
+                e
+            );
+        }
+    }
+
     /// Fetch transaction and all its splits with person configs
     async fn fetch_transaction_and_splits(
         &self,
@@ -328,38 +953,8 @@ impl SplitSyncService {
         splits: Vec<(TransactionSplit, PersonSplitConfig)>,
         retry_count: i32,
     ) -> ApiResult<()> {
-        // Fetch provider
-        let mut conn = self.pool.get().map_err(|e| {
-            tracing::error!("Failed to get DB connection: {}", e);
-            ApiError::Internal
-        })?;
-        let provider_model = split_providers::table
-            .find(provider_id)
-            .first::<SplitProviderModel>(&mut conn)?;
-
-        // Get provider implementation
-        let provider = self
-            .providers
-            .get(&provider_model.provider_type)
-            .ok_or_else(|| {
-                ApiError::BadRequest(format!(
-                    "Unknown provider type: {}",
-                    provider_model.provider_type
-                ))
-            })?;
-
-        // Decrypt credentials
-        let encrypted = provider_model
-            .credentials
-            .get("encrypted")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ApiError::InternalWithMessage("Invalid credentials format".to_string())
-            })?;
-
-        let credentials = encryption::decrypt_credentials(encrypted).map_err(|e| {
-            ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e))
-        })?;
+        let (provider_model, provider, credentials) =
+            self.load_active_provider(provider_id).await?;
 
         // Get the payer's external user ID from the provider credentials
         // (the authenticated user who paid the full amount)
@@ -377,6 +972,10 @@ impl SplitSyncService {
             })?;
 
         // Fetch account to get currency code
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get DB connection: {}", e);
+            ApiError::Internal
+        })?;
         let account = accounts::table
             .find(transaction.account_id)
             .first::<Account>(&mut conn)?;
@@ -396,13 +995,23 @@ impl SplitSyncService {
         };
 
         // Call provider to create expense
-        match provider.create_expense(&credentials, request).await {
+        let result = self
+            .call_with_refreshed_credentials(
+                &provider_model,
+                provider,
+                |provider, credentials| async move {
+                    provider.create_expense(&credentials, request).await
+                },
+            )
+            .await;
+        self.record_auth_result(&provider_model, &result).await;
+        match result {
             Ok(result) => {
                 // Upsert sync records for all splits in this group
-                for (split, _) in splits {
+                for (split, config) in splits {
                     self.upsert_sync_record(
                         split.id,
-                        provider_id,
+                        &config,
                         Some(result.external_expense_id.clone()),
                         SyncStatus::Synced,
                         None,
@@ -414,10 +1023,10 @@ impl SplitSyncService {
             }
             Err(e) => {
                 // Upsert failed sync records
-                for (split, _) in splits {
+                for (split, config) in splits {
                     self.upsert_sync_record(
                         split.id,
-                        provider_id,
+                        &config,
                         None,
                         SyncStatus::Failed,
                         Some(e.to_string()),
@@ -457,38 +1066,8 @@ impl SplitSyncService {
             .external_expense_id
             .ok_or_else(|| ApiError::BadRequest("No external expense ID found".to_string()))?;
 
-        // Fetch provider
-        let mut conn = self.pool.get().map_err(|e| {
-            tracing::error!("Failed to get DB connection: {}", e);
-            ApiError::Internal
-        })?;
-        let provider_model = split_providers::table
-            .find(provider_id)
-            .first::<SplitProviderModel>(&mut conn)?;
-
-        // Get provider implementation
-        let provider = self
-            .providers
-            .get(&provider_model.provider_type)
-            .ok_or_else(|| {
-                ApiError::BadRequest(format!(
-                    "Unknown provider type: {}",
-                    provider_model.provider_type
-                ))
-            })?;
-
-        // Decrypt credentials
-        let encrypted = provider_model
-            .credentials
-            .get("encrypted")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ApiError::InternalWithMessage("Invalid credentials format".to_string())
-            })?;
-
-        let credentials = encryption::decrypt_credentials(encrypted).map_err(|e| {
-            ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e))
-        })?;
+        let (provider_model, provider, credentials) =
+            self.load_active_provider(provider_id).await?;
 
         // Get the payer's external user ID from the provider credentials
         let payer_external_id = credentials
@@ -517,13 +1096,22 @@ impl SplitSyncService {
         };
 
         // Call provider to update expense
-        match provider
-            .update_expense(&credentials, &external_expense_id, request)
-            .await
-        {
+        let result = self
+            .call_with_refreshed_credentials(
+                &provider_model,
+                provider,
+                |provider, credentials| async move {
+                    provider
+                        .update_expense(&credentials, &external_expense_id, request)
+                        .await
+                },
+            )
+            .await;
+        self.record_auth_result(&provider_model, &result).await;
+        match result {
             Ok(_) => {
                 // Update all sync records for this provider
-                for (split, _) in splits {
+                for (split, config) in splits {
                     if let Ok(Some(record)) = SplitSyncRecordRepository::find_by_split_and_provider(
                         &self.pool,
                         split.id,
@@ -535,6 +1123,7 @@ impl SplitSyncService {
                             last_sync_at: Some(Utc::now()),
                             last_error: None,
                             retry_count: None,
+                            external_user_id: Some(config.external_user_id),
                         };
 
                         if let Err(e) =
@@ -565,6 +1154,7 @@ impl SplitSyncService {
                             last_sync_at: Some(Utc::now()),
                             last_error: Some(e.to_string()),
                             retry_count: Some(record.retry_count + 1),
+                            external_user_id: None,
                         };
 
                         if let Err(e) =
@@ -589,19 +1179,55 @@ impl SplitSyncService {
 
     /// Delete an expense from a provider
     async fn delete_expense(&self, provider_id: Uuid, external_expense_id: &str) -> ApiResult<()> {
-        // Fetch provider
-        let mut conn = self.pool.get().map_err(|e| {
-            tracing::error!("Failed to get DB connection: {}", e);
-            ApiError::Internal
-        })?;
-        let provider_model = split_providers::table
-            .find(provider_id)
-            .first::<SplitProviderModel>(&mut conn)?;
+        let (provider_model, provider, _) = self.load_active_provider(provider_id).await?;
+
+        // Call provider to delete expense
+        let result = self
+            .call_with_refreshed_credentials(
+                &provider_model,
+                provider,
+                |provider, credentials| async move {
+                    provider
+                        .delete_expense(&credentials, external_expense_id)
+                        .await
+                },
+            )
+            .await;
+        self.record_auth_result(&provider_model, &result).await;
+        result.map_err(|e| ApiError::External(format!("Failed to delete expense: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load an active provider with its implementation and decrypted credentials
+    ///
+    /// Inactive providers are refused before any request is made, so a provider
+    /// with revoked credentials is not called again until it is reconnected or
+    /// enabled.
+    async fn load_active_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> ApiResult<(
+        SplitProviderModel,
+        Arc<dyn SplitProvider>,
+        serde_json::Value,
+    )> {
+        let provider_model = repositories::split_provider::find_by_id(&self.pool, provider_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Provider not found".to_string()))?;
+
+        if !provider_model.is_active {
+            tracing::debug!("Skipping sync with inactive provider {}", provider_id);
+            return Err(ApiError::BadRequest(
+                "Provider is disconnected. Please reconnect or enable it.".to_string(),
+            ));
+        }
 
         // Get provider implementation
         let provider = self
             .providers
             .get(&provider_model.provider_type)
+            .cloned()
             .ok_or_else(|| {
                 ApiError::BadRequest(format!(
                     "Unknown provider type: {}",
@@ -609,35 +1235,197 @@ impl SplitSyncService {
                 ))
             })?;
 
-        // Decrypt credentials
-        let encrypted = provider_model
-            .credentials
-            .get("encrypted")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ApiError::InternalWithMessage("Invalid credentials format".to_string())
-            })?;
+        let credentials = Self::decrypt_provider_credentials(&provider_model)?;
 
-        let credentials = encryption::decrypt_credentials(encrypted).map_err(|e| {
-            ApiError::InternalWithMessage(format!("Failed to decrypt credentials: {}", e))
-        })?;
+        Ok((provider_model, provider, credentials))
+    }
 
-        // Call provider to delete expense
-        provider
-            .delete_expense(&credentials, external_expense_id)
+    /// Make a request to a provider after refreshing its credentials if needed
+    ///
+    /// The provider row is locked while its credentials are refreshed and
+    /// stored, and the credentials are re-read under the lock, so concurrent
+    /// syncs don't refresh with a token another one has already rotated.
+    /// Refreshed credentials are encrypted and stored before the request is
+    /// made; if that fails the request fails with
+    /// [`SplitProviderError::CredentialsStorage`] rather than losing the new
+    /// token. A provider that rejects the refresh, or still rejects the request
+    /// with the refreshed credentials, is marked inactive and the request fails
+    /// with [`SplitProviderError::AuthExpired`]. Rejections of credentials that
+    /// did not need refreshing count towards [`MAX_CONSECUTIVE_AUTH_FAILURES`]
+    /// as usual.
+    async fn call_with_refreshed_credentials<T, F, Fut>(
+        &self,
+        provider_model: &SplitProviderModel,
+        provider: Arc<dyn SplitProvider>,
+        call: F,
+    ) -> Result<T, SplitProviderError>
+    where
+        F: FnOnce(Arc<dyn SplitProvider>, serde_json::Value) -> Fut,
+        Fut: Future<Output = Result<T, SplitProviderError>>,
+    {
+        let locked = repositories::split_provider::lock_for_update(&self.pool, provider_model.id)
+            .await
+            .map_err(|e| SplitProviderError::CredentialsStorage(e.to_string()))?;
+        let credentials = match Self::decrypt_provider_credentials(&locked.provider) {
+            Ok(credentials) => credentials,
+            Err(e) => {
+                Self::release_lock(locked).await;
+                return Err(SplitProviderError::CredentialsStorage(e.to_string()));
+            }
+        };
+
+        let (credentials, refreshed) = match provider.refresh_credentials(&credentials).await {
+            Ok(Some(new_credentials)) => {
+                Self::store_credentials(locked, &new_credentials).await?;
+                (new_credentials, true)
+            }
+            Ok(None) => {
+                Self::release_lock(locked).await;
+                (credentials, false)
+            }
+            Err(e) if e.requires_reauth() => {
+                Self::release_lock(locked).await;
+                tracing::warn!(
+                    "Provider {} rejected the credentials refresh: {}",
+                    provider_model.id,
+                    e
+                );
+                self.flag_for_reconnect(provider_model).await;
+                return Err(SplitProviderError::AuthExpired);
+            }
+            Err(e) => {
+                Self::release_lock(locked).await;
+                return Err(e);
+            }
+        };
+
+        match call(provider, credentials).await {
+            Err(e) if refreshed && e.requires_reauth() => {
+                tracing::warn!(
+                    "Provider {} rejected refreshed credentials: {}",
+                    provider_model.id,
+                    e
+                );
+                self.flag_for_reconnect(provider_model).await;
+                Err(SplitProviderError::AuthExpired)
+            }
+            result => result,
+        }
+    }
+
+    /// Encrypt and store refreshed credentials of a locked provider, releasing the lock
+    async fn store_credentials(
+        locked: repositories::split_provider::LockedSplitProvider,
+        credentials: &serde_json::Value,
+    ) -> Result<(), SplitProviderError> {
+        let provider_id = locked.provider.id;
+        let encrypted = match encryption::encrypt_credentials(credentials) {
+            Ok(encrypted) => encrypted,
+            Err(e) => {
+                Self::release_lock(locked).await;
+                tracing::error!(
+                    "Failed to encrypt refreshed credentials of provider {}: {}",
+                    provider_id,
+                    e
+                );
+                return Err(SplitProviderError::CredentialsStorage(e.to_string()));
+            }
+        };
+
+        locked
+            .update_credentials(serde_json::json!({ "encrypted": encrypted }))
             .await
-            .map_err(|e| ApiError::External(format!("Failed to delete expense: {}", e)))?;
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to store refreshed credentials of provider {}: {}",
+                    provider_id,
+                    e
+                );
+                SplitProviderError::CredentialsStorage(e.to_string())
+            })?;
 
         Ok(())
     }
 
+    /// Release a provider lock taken for a credentials refresh
+    ///
+    /// A failure is only logged: the connection is then discarded by the pool,
+    /// which releases the lock anyway.
+    async fn release_lock(locked: repositories::split_provider::LockedSplitProvider) {
+        let provider_id = locked.provider.id;
+        if let Err(e) = locked.release().await {
+            tracing::warn!("Failed to release lock on provider {}: {}", provider_id, e);
+        }
+    }
+
+    /// Track whether a provider accepted the credentials of a request
+    ///
+    /// A rejection counts towards [`MAX_CONSECUTIVE_AUTH_FAILURES`], after which
+    /// the provider is disabled and flagged for reconnection; any other outcome
+    /// resets the count. Returns whether the provider was disabled.
+    async fn record_auth_result<T>(
+        &self,
+        provider_model: &SplitProviderModel,
+        result: &Result<T, SplitProviderError>,
+    ) -> bool {
+        match result {
+            Err(e) if e.requires_reauth() => {
+                match repositories::split_provider::record_auth_failure(
+                    &self.pool,
+                    provider_model.id,
+                    MAX_CONSECUTIVE_AUTH_FAILURES,
+                )
+                .await
+                {
+                    Ok(updated) => {
+                        let disabled = provider_model.is_active && !updated.is_active;
+                        if disabled {
+                            tracing::warn!(
+                                "Disabled provider {} after {} consecutive auth failures",
+                                updated.id,
+                                updated.consecutive_auth_failures
+                            );
+                        }
+                        disabled
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to record auth failure of provider {}: {}",
+                            provider_model.id,
+                            e
+                        );
+                        false
+                    }
+                }
+            }
+            _ => {
+                if provider_model.consecutive_auth_failures > 0
+                    && let Err(e) = repositories::split_provider::reset_auth_failures(
+                        &self.pool,
+                        provider_model.id,
+                    )
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to reset auth failures of provider {}: {}",
+                        provider_model.id,
+                        e
+                    );
+                }
+                false
+            }
+        }
+    }
+
     /// Upsert a sync record: update if exists, create if not
     ///
-    /// This avoids unique constraint violations when retrying failed syncs.
+    /// The record belongs to the provider of the person's `config`, and records the
+    /// provider user the split was synced as. This avoids unique constraint
+    /// violations when retrying failed syncs.
     fn upsert_sync_record(
         &self,
         split_id: Uuid,
-        provider_id: Uuid,
+        config: &PersonSplitConfig,
         external_expense_id: Option<String>,
         status: SyncStatus,
         last_error: Option<String>,
@@ -647,7 +1435,7 @@ impl SplitSyncService {
         match SplitSyncRecordRepository::find_by_split_and_provider(
             &self.pool,
             split_id,
-            provider_id,
+            config.split_provider_id,
         ) {
             Ok(Some(existing)) => {
                 // Update existing record
@@ -657,6 +1445,7 @@ impl SplitSyncService {
                     last_sync_at: Some(Utc::now()),
                     last_error,
                     retry_count: Some(retry_count),
+                    external_user_id: Some(config.external_user_id.clone()),
                 };
                 if let Err(e) = SplitSyncRecordRepository::update(&self.pool, existing.id, update) {
                     tracing::error!("Failed to update sync record for split {}: {}", split_id, e);
@@ -666,12 +1455,13 @@ impl SplitSyncService {
                 // Create new record
                 let new_record = NewSplitSyncRecord {
                     transaction_split_id: split_id,
-                    split_provider_id: provider_id,
+                    split_provider_id: config.split_provider_id,
                     external_expense_id,
                     sync_status: status.as_str().to_string(),
                     last_sync_at: Some(Utc::now()),
                     last_error,
                     retry_count,
+                    external_user_id: Some(config.external_user_id.clone()),
                 };
                 if let Err(e) = SplitSyncRecordRepository::create(&self.pool, new_record) {
                     tracing::error!("Failed to create sync record for split {}: {}", split_id, e);
@@ -728,3 +1518,40 @@ impl SplitSyncService {
         Ok(users)
     }
 }
+
+/// Spawn a background task that periodically reconciles synced expenses
+///
+/// Does nothing when reconciliation is disabled (`interval_minutes == 0`). After a
+/// run is rate limited, the pause before the next run doubles, up to
+/// `MAX_RECONCILE_BACKOFF` intervals.
+pub fn spawn_reconciliation(service: SplitSyncService, config: SplitReconciliationConfig) {
+    if config.interval_minutes == 0 {
+        tracing::info!("Split reconciliation disabled");
+        return;
+    }
+
+    let interval = Duration::from_secs(config.interval_minutes * 60);
+    tokio::spawn(async move {
+        let mut backoff = 1;
+        loop {
+            tokio::time::sleep(interval * backoff).await;
+            match service.reconcile_batch(config.batch_size).await {
+                Ok(outcome) => {
+                    tracing::info!(
+                        "Split reconciliation: {} synced, {} drifted, {} failed, {} providers flagged",
+                        outcome.synced,
+                        outcome.drifted,
+                        outcome.failed,
+                        outcome.providers_flagged
+                    );
+                    backoff = if outcome.rate_limited {
+                        (backoff * 2).min(MAX_RECONCILE_BACKOFF)
+                    } else {
+                        1
+                    };
+                }
+                Err(e) => tracing::error!("Split reconciliation failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,382 @@
+//! Splitwise webhook handling
+//!
+//! Splitwise pushes expense changes made directly on Splitwise to
+//! `POST /api/v1/integrations/splitwise/webhook`. Each delivery is signed with
+//! the shared `SPLITWISE_WEBHOOK_SECRET`, and changes to expenses that local
+//! splits were synced to are applied to those splits, which keeps the debts of
+//! the people involved in sync. Deliveries that cannot be applied are stored
+//! in `webhook_events` for inspection.
+
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Signed};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::DbPool;
+use crate::errors::ApiError;
+use crate::models::AuditEntityType;
+use crate::models::split_sync_record::{SplitSyncRecord, SyncStatus};
+use crate::models::transaction::Transaction;
+use crate::models::transaction_split::TransactionSplit;
+use crate::models::{NewWebhookEvent, WebhookOutcome};
+use crate::repositories::{self, audit_log};
+use crate::schema::{
+    person_split_configs, split_providers, split_sync_records, transaction_splits, transactions,
+};
+use crate::services::split_provider::{ExternalExpense, SplitwiseWebhookEvent};
+
+/// Header carrying the hex-encoded HMAC-SHA256 of the request body
+pub const SIGNATURE_HEADER: &str = "x-splitwise-signature";
+
+const PROVIDER_TYPE: &str = "splitwise";
+
+const EXPENSE_EVENTS: [&str; 3] = ["expense.created", "expense.updated", "expense.deleted"];
+
+/// Outcome of one webhook delivery
+#[derive(Debug)]
+pub struct WebhookResult {
+    pub outcome: WebhookOutcome,
+    /// Transactions whose splits or sync state changed, with their owners
+    pub changed_transactions: Vec<(Uuid, Uuid)>,
+}
+
+impl WebhookResult {
+    fn stored(outcome: WebhookOutcome) -> Self {
+        Self {
+            outcome,
+            changed_transactions: Vec::new(),
+        }
+    }
+}
+
+/// Check `signature` is the hex-encoded HMAC-SHA256 of `body` under `secret`
+///
+/// The comparison takes the same time wherever the signatures differ.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = decode_hex(signature.trim()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Apply a verified webhook delivery
+///
+/// Expense events are applied to every local split synced to the expense:
+/// deleting the expense deletes the splits, and otherwise each split takes its
+/// person's owed share on Splitwise and is dropped if the person left the
+/// expense. Settled splits are never changed; their sync record is flagged
+/// instead. Anything else, and expenses no split was synced to, is stored in
+/// `webhook_events`.
+pub async fn handle_delivery(pool: &DbPool, body: &[u8]) -> Result<WebhookResult, ApiError> {
+    let payload: Value = match serde_json::from_slice(body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Splitwise webhook body is not JSON: {}", e);
+            let payload = Value::String(String::from_utf8_lossy(body).into_owned());
+            store(pool, None, None, WebhookOutcome::Unrecognized, payload).await?;
+            return Ok(WebhookResult::stored(WebhookOutcome::Unrecognized));
+        }
+    };
+
+    let event_type = payload
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let event = match event_type.as_deref() {
+        Some(event_type) if EXPENSE_EVENTS.contains(&event_type) => {
+            serde_json::from_value::<SplitwiseWebhookEvent>(payload.clone()).ok()
+        }
+        _ => None,
+    };
+    let Some(event) = event else {
+        tracing::warn!("Unrecognized Splitwise webhook event {:?}", event_type);
+        store(
+            pool,
+            event_type,
+            None,
+            WebhookOutcome::Unrecognized,
+            payload,
+        )
+        .await?;
+        return Ok(WebhookResult::stored(WebhookOutcome::Unrecognized));
+    };
+
+    let deleted = event.event_type == "expense.deleted";
+    let expense = event.into_expense();
+    let external_expense_id = expense.external_expense_id.clone();
+
+    let mut conn = pool.get().map_err(|e| {
+        tracing::error!("Failed to get DB connection: {}", e);
+        ApiError::Internal
+    })?;
+    let changed_transactions = tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| apply_expense(conn, &expense, deleted))
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })??;
+
+    if changed_transactions.is_empty() {
+        tracing::warn!(
+            "Splitwise webhook for expense {} matches no synced split",
+            external_expense_id
+        );
+        store(
+            pool,
+            event_type,
+            Some(external_expense_id),
+            WebhookOutcome::Unmatched,
+            payload,
+        )
+        .await?;
+        return Ok(WebhookResult::stored(WebhookOutcome::Unmatched));
+    }
+
+    tracing::info!(
+        "Applied Splitwise {} of expense {} to {} transaction(s)",
+        event_type.unwrap_or_default(),
+        external_expense_id,
+        changed_transactions.len()
+    );
+    Ok(WebhookResult {
+        outcome: WebhookOutcome::Applied,
+        changed_transactions,
+    })
+}
+
+async fn store(
+    pool: &DbPool,
+    event_type: Option<String>,
+    external_id: Option<String>,
+    status: WebhookOutcome,
+    payload: Value,
+) -> Result<(), ApiError> {
+    repositories::webhook_event::record(
+        pool,
+        NewWebhookEvent {
+            provider_type: PROVIDER_TYPE.to_string(),
+            event_type,
+            external_id,
+            status: status.as_str().to_string(),
+            payload,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// A transaction with its splits, as recorded in the audit log when a
+/// webhook changes the splits
+#[derive(Serialize)]
+struct AuditedTransaction {
+    #[serde(flatten)]
+    transaction: Transaction,
+    splits: Vec<AuditedSplit>,
+}
+
+#[derive(Serialize)]
+struct AuditedSplit {
+    person_id: Uuid,
+    amount: BigDecimal,
+    settled_at: Option<DateTime<Utc>>,
+}
+
+impl AuditedTransaction {
+    fn load(conn: &mut PgConnection, transaction_id: Uuid) -> Result<Self, ApiError> {
+        let transaction: Transaction = transactions::table.find(transaction_id).first(conn)?;
+        let splits = transaction_splits::table
+            .filter(transaction_splits::transaction_id.eq(transaction_id))
+            .order(transaction_splits::person_id.asc())
+            .load::<TransactionSplit>(conn)?
+            .into_iter()
+            .map(|split| AuditedSplit {
+                person_id: split.person_id,
+                amount: split.amount,
+                settled_at: split.settled_at,
+            })
+            .collect();
+        Ok(Self {
+            transaction,
+            splits,
+        })
+    }
+}
+
+/// Apply an expense to the splits synced to it, returning the changed
+/// transactions with their owners
+///
+/// Transactions whose splits changed get a new version, so stale edits made
+/// with the old one are refused, and an entry in their owner's audit log.
+fn apply_expense(
+    conn: &mut PgConnection,
+    expense: &ExternalExpense,
+    deleted: bool,
+) -> Result<Vec<(Uuid, Uuid)>, ApiError> {
+    let records: Vec<(SplitSyncRecord, Uuid)> = split_sync_records::table
+        .inner_join(split_providers::table)
+        .filter(split_providers::provider_type.eq(PROVIDER_TYPE))
+        .filter(split_sync_records::external_expense_id.eq(&expense.external_expense_id))
+        .select((SplitSyncRecord::as_select(), split_providers::user_id))
+        .for_update()
+        .load(conn)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to find splits synced to expense {}: {}",
+                expense.external_expense_id,
+                e
+            );
+            ApiError::from(e)
+        })?;
+
+    let amount = |value: &str| BigDecimal::from_str(value).ok();
+    let mut changed: Vec<(Uuid, Uuid)> = Vec::new();
+    // Transactions whose splits changed, as they were before the first change
+    let mut split_changes: Vec<AuditedTransaction> = Vec::new();
+    let mut snapshot = |conn: &mut PgConnection, transaction_id: Uuid| {
+        if split_changes
+            .iter()
+            .any(|before| before.transaction.id == transaction_id)
+        {
+            return Ok(());
+        }
+        split_changes.push(AuditedTransaction::load(conn, transaction_id)?);
+        Ok::<_, ApiError>(())
+    };
+
+    for (record, user_id) in records {
+        let split: TransactionSplit = transaction_splits::table
+            .find(record.transaction_split_id)
+            .for_update()
+            .first(conn)?;
+        let transaction: Transaction =
+            transactions::table.find(split.transaction_id).first(conn)?;
+
+        let external_user_id = match record.external_user_id.clone() {
+            Some(external_user_id) => Some(external_user_id),
+            None => person_split_configs::table
+                .filter(person_split_configs::person_id.eq(split.person_id))
+                .filter(person_split_configs::split_provider_id.eq(record.split_provider_id))
+                .select(person_split_configs::external_user_id)
+                .first::<String>(conn)
+                .optional()?,
+        };
+        let owed = if deleted || expense.deleted {
+            None
+        } else {
+            expense
+                .users
+                .iter()
+                .find(|user| Some(&user.external_user_id) == external_user_id.as_ref())
+                .and_then(|user| amount(&user.owed_share))
+        };
+
+        let (status, last_error) = match owed {
+            // The person no longer shares the expense
+            None if split.settled_at.is_none() => {
+                snapshot(conn, transaction.id)?;
+                diesel::delete(transaction_splits::table.find(split.id)).execute(conn)?;
+                changed.push((user_id, transaction.id));
+                continue;
+            }
+            None if deleted || expense.deleted => (
+                SyncStatus::Deleted,
+                Some("Expense was deleted on the provider".to_string()),
+            ),
+            None => (
+                SyncStatus::Drifted,
+                Some("Settled split's person left the provider expense".to_string()),
+            ),
+            Some(owed) if owed == split.amount.abs() => (SyncStatus::Synced, None),
+            Some(_) if split.settled_at.is_some() => (
+                SyncStatus::Drifted,
+                Some("Settled split differs from the provider share".to_string()),
+            ),
+            Some(owed) => {
+                let owed = if split.amount.is_negative() {
+                    -owed
+                } else {
+                    owed
+                };
+                snapshot(conn, transaction.id)?;
+                diesel::update(transaction_splits::table.find(split.id))
+                    .set(transaction_splits::amount.eq(owed))
+                    .execute(conn)?;
+                (SyncStatus::Synced, None)
+            }
+        };
+
+        // Shares are applied, but the transaction's own amount is left to the user
+        let cost = transaction.amount.abs();
+        let (status, last_error) =
+            if status == SyncStatus::Synced && amount(&expense.cost) != Some(cost.clone()) {
+                (
+                    SyncStatus::Drifted,
+                    Some(format!(
+                        "Provider cost {} differs from transaction amount {}",
+                        expense.cost, cost
+                    )),
+                )
+            } else {
+                (status, last_error)
+            };
+
+        diesel::update(split_sync_records::table.find(record.id))
+            .set((
+                split_sync_records::sync_status.eq(status.as_str()),
+                split_sync_records::last_sync_at.eq(Some(Utc::now())),
+                split_sync_records::last_error.eq(last_error),
+            ))
+            .execute(conn)?;
+        changed.push((user_id, transaction.id));
+    }
+
+    for before in split_changes {
+        let transaction_id = before.transaction.id;
+        diesel::update(transactions::table.find(transaction_id))
+            .set(transactions::version.eq(transactions::version + 1))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to update version of transaction {}: {}",
+                    transaction_id,
+                    e
+                );
+                ApiError::from(e)
+            })?;
+        let after = AuditedTransaction::load(conn, transaction_id)?;
+        audit_log::record(
+            conn,
+            before.transaction.user_id,
+            AuditEntityType::Transaction,
+            transaction_id,
+            Some(&before),
+            Some(&after),
+        )?;
+    }
+
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
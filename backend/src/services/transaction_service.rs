@@ -1,5 +1,7 @@
-use bigdecimal::BigDecimal;
-use std::str::FromStr;
+use bigdecimal::{BigDecimal, Signed};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -7,29 +9,50 @@ use crate::{
     DbPool,
     errors::ApiError,
     models::{
-        CreateTransactionRequest, NewTransaction, NewTransactionSplit, TransactionFilter,
-        TransactionResponse, UpdateTransactionRequest,
+        Account, CategoryReimbursementTotal, CategorySuggestionResponse, CreateTransactionRequest,
+        NewTransaction, NewTransactionSplit, OutstandingReimbursementsResponse, Pagination,
+        PersonTransactionResponse, ReimbursementQuery, ReimbursementTotal, SplitSyncState,
+        Transaction, TransactionExportRow, TransactionFilter, TransactionResponse,
+        TransactionSplit, TransactionSplitResponse, TransactionTotals, UpdateTransaction,
+        UpdateTransactionRequest,
+        tag::{TagMode, normalize_tags},
+        transaction::{rate_to_decimal, validate_conversion},
+        transaction_split::{split_by_strategy, split_equally, validate_splits_sum},
     },
-    repositories,
+    repositories::{self, split_sync_record::SplitSyncRecordRepository},
+    services::{
+        account_service::{self, BalanceProjection},
+        allocation_rule_service, split_group_service,
+    },
+    types::{CurrencyCode, Money, TransactionStatus},
 };
 
+/// Original currency, original amount and exchange rate of a foreign-currency transaction
+type OriginalCurrency = (Option<CurrencyCode>, Option<BigDecimal>, Option<BigDecimal>);
+
 /// Create a new transaction with optional splits
+///
+/// Suspicious inputs are returned as `warnings`; with `strict` they reject the
+/// transaction instead.
 pub async fn create_transaction(
     pool: &DbPool,
     user_id: Uuid,
     request: CreateTransactionRequest,
+    strict: bool,
 ) -> Result<TransactionResponse, ApiError> {
     // Validate request
     request.validate().map_err(|e| {
         tracing::warn!("Transaction validation failed: {}", e);
         ApiError::Validation(e.to_string())
     })?;
+    let tags = request
+        .tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(ApiError::Validation)?;
 
-    // Convert amount to BigDecimal
-    let amount = BigDecimal::from_str(&request.amount.to_string()).map_err(|e| {
-        tracing::error!("Failed to convert amount: {}", e);
-        ApiError::Validation("Invalid amount".to_string())
-    })?;
+    let amount = request.amount.as_decimal().clone();
 
     // Verify account ownership
     let account = repositories::account::find_by_id(pool, request.account_id).await?;
@@ -61,19 +84,87 @@ pub async fn create_transaction(
         }
     }
 
+    // Expand the split group before anything is saved, so an unusable group
+    // does not leave the transaction behind
+    let group_shares = match request.split_group_id {
+        Some(group_id) => Some(
+            split_group_service::expand_group(pool, group_id, user_id, &amount, account.currency)
+                .await?,
+        ),
+        None => None,
+    };
+
+    let (original_currency, original_amount, exchange_rate) = original_currency_values(
+        &account,
+        request.original_currency,
+        request.original_amount,
+        request.exchange_rate,
+    )?;
+
+    let mut warnings = ValidationWarnings::default();
+    warnings.check_date(request.date);
+    warnings
+        .check_category_direction(pool, &amount, request.category_id, None)
+        .await?;
+    let warnings = warnings.finish(strict)?;
+
     // Create transaction
     let new_transaction = NewTransaction {
         user_id,
         account_id: request.account_id,
         category_id: request.category_id,
         title: request.title.clone(),
-        amount,
+        amount: amount.clone(),
         date: request.date,
         notes: request.notes.clone(),
+        external_id: None,
+        status: request.status.unwrap_or_default(),
+        original_currency,
+        original_amount,
+        exchange_rate,
+        merchant: request.merchant.as_deref().map(|m| m.trim().to_string()),
+        latitude: request.latitude,
+        longitude: request.longitude,
+        transfer_id: None,
+        reimbursable: request.reimbursable,
+        tags: tags.unwrap_or_default(),
     };
+    let tags = new_transaction.tags.clone();
 
-    let transaction =
-        repositories::transaction::create_transaction(pool, user_id, new_transaction).await?;
+    // Void transactions never affect the balance
+    let delta = if new_transaction.status == TransactionStatus::Void {
+        BigDecimal::from(0)
+    } else {
+        amount
+    };
+
+    // Move shares of the income to other accounts by the matching allocation rule
+    let allocation = allocation_rule_service::for_account(pool, &account).await?;
+    let transfers = allocation.transfers(&new_transaction);
+    let mut new_transactions = Vec::with_capacity(transfers.len() + 1);
+    new_transactions.push(new_transaction);
+    new_transactions.extend(transfers);
+
+    // Check the resulting balance against the account's overdraft/credit limit
+    // while the account is locked, so parallel writes cannot both pass the check.
+    // The income comes first, so its allocation transfers are checked after it.
+    let currency = account.currency;
+    let (mut created, mut projections) = repositories::transaction::create_transactions_checked(
+        pool,
+        user_id,
+        new_transactions,
+        move |new_transaction, balance| {
+            if new_transaction.transfer_id.is_some() {
+                allocation.check_transfer(new_transaction, balance)?;
+                return Ok(None);
+            }
+            account_service::project_balance(&account, balance, &delta).map(Some)
+        },
+    )
+    .await?;
+    let transaction = created.remove(0);
+    let projection = projections.remove(0).ok_or(ApiError::Internal)?;
+    let allocations = created;
 
     tracing::info!(
         "Created transaction {} for user {}",
@@ -81,17 +172,50 @@ pub async fn create_transaction(
         user_id
     );
 
+    // Resolve the requested splits to (person, amount) pairs
+    let split_inputs = if let Some(split_inputs) = request.splits {
+        let mut amounts = Vec::with_capacity(split_inputs.len());
+        for split_input in split_inputs {
+            amounts.push((split_input.person_id, split_input.amount.into_decimal()));
+        }
+        Some(amounts)
+    } else if let Some(person_ids) = request.split_equally_with {
+        // The user takes part in the equal split but their own share is not a split
+        let mut participants = person_ids;
+        participants.push(user_id);
+        let shares = split_equally(
+            &transaction.amount.abs(),
+            &participants,
+            currency.minor_units(),
+        )
+        .into_iter()
+        .filter(|(person_id, _)| *person_id != user_id)
+        .collect();
+        Some(shares)
+    } else if let (Some(strategy), Some(participants)) =
+        (request.split_strategy, request.participants)
+    {
+        Some(split_by_strategy(
+            &transaction.amount.abs(),
+            strategy,
+            &participants,
+            currency.minor_units(),
+        ))
+    } else {
+        group_shares
+    };
+
     // Handle splits if provided
-    let splits = if let Some(split_inputs) = request.splits {
+    let splits = if let Some(split_inputs) = split_inputs {
         let mut created_splits = Vec::new();
-        for split_input in split_inputs {
+        for (person_id, split_amount) in split_inputs {
             // Verify person ownership
-            let person = repositories::person::find_by_id(pool, split_input.person_id).await?;
+            let person = repositories::person::find_by_id(pool, person_id).await?;
             if person.user_id != user_id {
                 tracing::warn!(
                     "User {} attempted to split with person {} owned by {}",
                     user_id,
-                    split_input.person_id,
+                    person_id,
                     person.user_id
                 );
                 return Err(ApiError::Unauthorized(
@@ -99,15 +223,9 @@ pub async fn create_transaction(
                 ));
             }
 
-            let split_amount =
-                BigDecimal::from_str(&split_input.amount.to_string()).map_err(|e| {
-                    tracing::error!("Failed to convert split amount: {}", e);
-                    ApiError::Validation("Invalid split amount".to_string())
-                })?;
-
             let new_split = NewTransactionSplit {
                 transaction_id: transaction.id,
-                person_id: split_input.person_id,
+                person_id,
                 amount: split_amount,
             };
 
@@ -123,6 +241,11 @@ pub async fn create_transaction(
     // Build response
     let mut response = TransactionResponse::from(transaction);
     response.splits = splits.map(|s| s.into_iter().map(|split| split.into()).collect());
+    response.tags = tags;
+    response.allocations = allocations.iter().map(|t| t.id).collect();
+    response.warnings = warnings;
+    response.set_currency(currency);
+    apply_projection(&mut response, projection, currency);
 
     Ok(response)
 }
@@ -148,11 +271,13 @@ pub async fn get_transaction(
     }
 
     // Fetch splits
-    let splits = repositories::transaction::list_splits_for_transaction(pool, transaction_id)
-        .await?
-        .into_iter()
-        .map(|split| split.into())
-        .collect::<Vec<_>>();
+    let splits = split_responses(
+        pool,
+        repositories::transaction::list_splits_for_transaction(pool, transaction_id).await?,
+    )
+    .await?;
+
+    let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
 
     let mut response = TransactionResponse::from(transaction);
     response.splits = if splits.is_empty() {
@@ -160,16 +285,68 @@ pub async fn get_transaction(
     } else {
         Some(splits)
     };
+    response.set_currency(account.currency);
+    attach_tags(pool, std::slice::from_mut(&mut response)).await?;
 
     Ok(response)
 }
 
-/// List transactions with filters
-pub async fn list_transactions(
+/// Fill in the tags of transaction responses
+pub async fn attach_tags(
+    pool: &DbPool,
+    responses: &mut [TransactionResponse],
+) -> Result<(), ApiError> {
+    let ids = responses.iter().map(|response| response.id).collect();
+    let mut tags = repositories::tag::names_by_transaction(pool, ids).await?;
+    for response in responses {
+        response.tags = tags.remove(&response.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Convert splits to responses carrying each split's sync state per provider
+///
+/// The sync states of all splits are loaded in one query.
+async fn split_responses(
+    pool: &DbPool,
+    splits: Vec<TransactionSplit>,
+) -> Result<Vec<TransactionSplitResponse>, ApiError> {
+    let split_ids: Vec<Uuid> = splits.iter().map(|split| split.id).collect();
+    let pool = pool.clone();
+    let records = tokio::task::spawn_blocking(move || {
+        SplitSyncRecordRepository::find_with_provider_type_by_split_ids(&pool, &split_ids)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Task join error: {}", e);
+        ApiError::Internal
+    })??;
+
+    let mut sync_states: HashMap<Uuid, Vec<SplitSyncState>> = HashMap::new();
+    for (record, provider_type) in records {
+        sync_states
+            .entry(record.transaction_split_id)
+            .or_default()
+            .push(SplitSyncState::new(record, provider_type));
+    }
+
+    Ok(splits
+        .into_iter()
+        .map(|split| {
+            let sync = sync_states.remove(&split.id).unwrap_or_default();
+            let mut response = TransactionSplitResponse::from(split);
+            response.sync = sync;
+            response
+        })
+        .collect())
+}
+
+/// Validate transaction filters and check the account and category they name belong to the user
+async fn check_filters(
     pool: &DbPool,
     user_id: Uuid,
-    filters: TransactionFilter,
-) -> Result<Vec<TransactionResponse>, ApiError> {
+    filters: &TransactionFilter,
+) -> Result<(), ApiError> {
     // Validate filters
     filters.validate().map_err(|e| {
         tracing::warn!("Transaction filter validation failed: {}", e);
@@ -180,7 +357,7 @@ pub async fn list_transactions(
     if let Some(account_id) = filters.account_id {
         let account = repositories::account::find_by_id(pool, account_id).await?;
         if account.user_id != user_id {
-            return Err(ApiError::Unauthorized(
+            return Err(ApiError::Forbidden(
                 "Account does not belong to user".to_string(),
             ));
         }
@@ -190,52 +367,412 @@ pub async fn list_transactions(
     if let Some(category_id) = filters.category_id {
         let category = repositories::category::find_by_id(pool, category_id).await?;
         if category.user_id != user_id {
-            return Err(ApiError::Unauthorized(
+            return Err(ApiError::Forbidden(
                 "Category does not belong to user".to_string(),
             ));
         }
     }
 
+    Ok(())
+}
+
+/// Count a user's transactions matching the filters, ignoring pagination
+pub async fn count_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<i64, ApiError> {
+    repositories::transaction::count_transactions(pool, user_id, filters).await
+}
+
+/// List transactions with filters
+pub async fn list_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<Vec<TransactionResponse>, ApiError> {
+    check_filters(pool, user_id, &filters).await?;
+
     // List transactions
     let transactions = repositories::transaction::list_transactions(pool, user_id, filters).await?;
 
+    // Amounts are formatted in the currency of their account
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+
+    // Load the splits of the whole page at once
+    let transaction_ids = transactions.iter().map(|t| t.id).collect();
+    let splits =
+        repositories::transaction::list_splits_for_transactions(pool, transaction_ids).await?;
+    let owners: Vec<Uuid> = splits.iter().map(|split| split.transaction_id).collect();
+    let mut splits_by_transaction: HashMap<Uuid, Vec<TransactionSplitResponse>> = HashMap::new();
+    for (transaction_id, split) in owners.into_iter().zip(split_responses(pool, splits).await?) {
+        splits_by_transaction
+            .entry(transaction_id)
+            .or_default()
+            .push(split);
+    }
+
     // Convert to responses with splits
     let mut responses = Vec::new();
     for transaction in transactions {
-        let transaction_id = transaction.id;
+        let currency = account_currencies.get(&transaction.account_id).copied();
+        let splits = splits_by_transaction.remove(&transaction.id);
         let mut response = TransactionResponse::from(transaction);
 
-        // Fetch splits for this transaction
-        let splits = repositories::transaction::list_splits_for_transaction(pool, transaction_id)
+        response.splits = splits;
+        if let Some(currency) = currency {
+            response.set_currency(currency);
+        }
+
+        responses.push(response);
+    }
+    attach_tags(pool, &mut responses).await?;
+
+    Ok(responses)
+}
+
+/// List a page of the transactions a person has a split in, with their split
+///
+/// The person must belong to the user.
+pub async fn list_person_transactions(
+    pool: &DbPool,
+    person_id: Uuid,
+    user_id: Uuid,
+    pagination: Pagination,
+) -> Result<Vec<PersonTransactionResponse>, ApiError> {
+    // Verify person ownership
+    let person = repositories::person::find_by_id(pool, person_id).await?;
+    if person.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to list transactions of person {} owned by {}",
+            user_id,
+            person_id,
+            person.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Person does not belong to user".to_string(),
+        ));
+    }
+
+    let rows =
+        repositories::transaction::list_by_person(pool, user_id, person_id, pagination).await?;
+
+    // Amounts are formatted in the currency of their account
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|(transaction, split)| {
+            let currency = account_currencies.get(&transaction.account_id).copied();
+            let mut transaction = TransactionResponse::from(transaction);
+            let mut split = TransactionSplitResponse::from(split);
+            if let Some(currency) = currency {
+                transaction.set_currency(currency);
+                split.amount.set_currency(currency);
+            }
+            PersonTransactionResponse { transaction, split }
+        })
+        .collect())
+}
+
+/// Columns of the transaction CSV export, matching [`TransactionExportRow`]
+const EXPORT_COLUMNS: [&str; 7] = [
+    "date", "account", "category", "title", "amount", "currency", "notes",
+];
+
+/// Stream every transaction matching the filters as CSV, with a header row
+///
+/// Pagination is ignored; see [`transactions_csv`].
+pub async fn export_transactions_csv(
+    pool: &DbPool,
+    user_id: Uuid,
+    mut filters: TransactionFilter,
+) -> Result<impl Stream<Item = Result<Vec<u8>, ApiError>> + use<>, ApiError> {
+    filters.limit = None;
+    filters.offset = None;
+    transactions_csv(pool, user_id, filters).await
+}
+
+/// Stream the transactions matching the filters as CSV, with a header row
+///
+/// Only the page given by the filters' `limit` and `offset` is written, if
+/// set. Each item is a chunk of CSV; an error while reading the transactions
+/// ends the stream, cutting the download short.
+pub async fn transactions_csv(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<impl Stream<Item = Result<Vec<u8>, ApiError>> + use<>, ApiError> {
+    check_filters(pool, user_id, &filters).await?;
+
+    let accounts: HashMap<Uuid, (String, CurrencyCode)> =
+        repositories::account::list_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|account| (account.id, (account.name, account.currency)))
+            .collect();
+    let categories: HashMap<Uuid, String> = repositories::category::list_by_user(pool, user_id)
+        .await?
+        .into_iter()
+        .map(|category| (category.id, category.name))
+        .collect();
+
+    let mut header = csv::Writer::from_writer(Vec::new());
+    header.write_record(EXPORT_COLUMNS).map_err(|e| {
+        tracing::error!("Failed to write transaction export header: {}", e);
+        ApiError::Internal
+    })?;
+    let header = header.into_inner().map_err(|e| {
+        tracing::error!("Failed to write transaction export header: {}", e);
+        ApiError::Internal
+    })?;
+
+    let batches = repositories::transaction::stream_transactions(pool, user_id, filters).await?;
+    let rows = ReceiverStream::new(batches).map(move |batch| {
+        batch.and_then(|transactions| export_rows_to_csv(&transactions, &accounts, &categories))
+    });
+
+    Ok(tokio_stream::once(Ok(header)).chain(rows))
+}
+
+/// Serialize a batch of exported transactions as CSV, without a header row
+fn export_rows_to_csv(
+    transactions: &[Transaction],
+    accounts: &HashMap<Uuid, (String, CurrencyCode)>,
+    categories: &HashMap<Uuid, String>,
+) -> Result<Vec<u8>, ApiError> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    for transaction in transactions {
+        let Some((account, currency)) = accounts.get(&transaction.account_id) else {
+            tracing::error!(
+                "Account {} of transaction {} not found for export",
+                transaction.account_id,
+                transaction.id
+            );
+            return Err(ApiError::Internal);
+        };
+        let row = TransactionExportRow {
+            date: transaction.date,
+            account: account.clone(),
+            category: transaction
+                .category_id
+                .and_then(|id| categories.get(&id).cloned()),
+            title: transaction.title.clone(),
+            amount: transaction.amount.to_string(),
+            currency: *currency,
+            notes: transaction.notes.clone(),
+        };
+        writer.serialize(row).map_err(|e| {
+            tracing::error!(
+                "Failed to write transaction {} as CSV: {}",
+                transaction.id,
+                e
+            );
+            ApiError::Internal
+        })?;
+    }
+    writer.into_inner().map_err(|e| {
+        tracing::error!("Failed to finish transaction export: {}", e);
+        ApiError::Internal
+    })
+}
+
+/// Count and total the transactions matching the filters, per account currency
+///
+/// Pagination is ignored, so the totals cover every matching transaction.
+/// Currencies are ordered by code.
+pub async fn summarize_transactions(
+    pool: &DbPool,
+    user_id: Uuid,
+    filters: TransactionFilter,
+) -> Result<Vec<TransactionTotals>, ApiError> {
+    let per_account =
+        repositories::transaction::summarize_transactions(pool, user_id, filters).await?;
+
+    let account_currencies: HashMap<Uuid, CurrencyCode> =
+        repositories::account::list_by_user(pool, user_id)
             .await?
             .into_iter()
-            .map(|split| split.into())
-            .collect::<Vec<_>>();
+            .map(|account| (account.id, account.currency))
+            .collect();
 
-        response.splits = if splits.is_empty() {
-            None
-        } else {
-            Some(splits)
+    let mut by_currency: HashMap<CurrencyCode, TransactionTotals> = HashMap::new();
+    for (account_id, count, income, expenses) in per_account {
+        let Some(&currency) = account_currencies.get(&account_id) else {
+            continue;
         };
+        let entry = by_currency
+            .entry(currency)
+            .or_insert_with(|| TransactionTotals {
+                currency,
+                count: 0,
+                income: BigDecimal::from(0),
+                expenses: BigDecimal::from(0),
+            });
+        entry.count += count;
+        entry.income += income;
+        entry.expenses += expenses.abs();
+    }
 
-        responses.push(response);
+    let mut totals: Vec<TransactionTotals> = by_currency.into_values().collect();
+    totals.sort_by(|a, b| a.currency.as_str().cmp(b.currency.as_str()));
+    Ok(totals)
+}
+
+/// Reimbursable transactions that were not paid back yet, with the amounts owed
+///
+/// Void transactions are not owed. Totals are ordered by currency code, and
+/// category totals by category then currency.
+pub async fn outstanding_reimbursements(
+    pool: &DbPool,
+    user_id: Uuid,
+    query: ReimbursementQuery,
+) -> Result<OutstandingReimbursementsResponse, ApiError> {
+    let filters = TransactionFilter {
+        account_id: None,
+        category_id: query.category_id,
+        uncategorized: false,
+        include_deleted: false,
+        start_date: query.start_date,
+        end_date: query.end_date,
+        min_amount: None,
+        max_amount: None,
+        transaction_type: None,
+        updated_since: None,
+        status: None,
+        search: None,
+        merchant: None,
+        tags: None,
+        tag_mode: TagMode::default(),
+        reimbursable: Some(true),
+        reimbursed: Some(false),
+        sort: None,
+        limit: None,
+        offset: None,
+    };
+    let mut transactions = list_transactions(pool, user_id, filters).await?;
+    transactions.retain(|transaction| transaction.status != TransactionStatus::Void);
+
+    let mut by_currency: HashMap<CurrencyCode, (i64, BigDecimal)> = HashMap::new();
+    let mut by_category: HashMap<(Option<Uuid>, CurrencyCode), (i64, BigDecimal)> = HashMap::new();
+    for transaction in &transactions {
+        let Some(currency) = transaction.amount.currency() else {
+            continue;
+        };
+        let owed = -transaction.amount.as_decimal();
+        for (count, amount) in [
+            by_currency.entry(currency).or_default(),
+            by_category
+                .entry((transaction.category_id, currency))
+                .or_default(),
+        ] {
+            *count += 1;
+            *amount += &owed;
+        }
     }
 
-    Ok(responses)
+    let mut totals: Vec<ReimbursementTotal> = by_currency
+        .into_iter()
+        .map(|(currency, (count, amount))| ReimbursementTotal {
+            currency,
+            count,
+            amount: Money::new(amount, currency),
+        })
+        .collect();
+    totals.sort_by(|a, b| a.currency.as_str().cmp(b.currency.as_str()));
+
+    let mut by_category: Vec<CategoryReimbursementTotal> = by_category
+        .into_iter()
+        .map(
+            |((category_id, currency), (count, amount))| CategoryReimbursementTotal {
+                category_id,
+                currency,
+                count,
+                amount: Money::new(amount, currency),
+            },
+        )
+        .collect();
+    by_category.sort_by(|a, b| {
+        (a.category_id, a.currency.as_str()).cmp(&(b.category_id, b.currency.as_str()))
+    });
+
+    Ok(OutstandingReimbursementsResponse {
+        totals,
+        by_category,
+        transactions,
+    })
+}
+
+/// Suggest a category for a new transaction from past transactions with the same title
+///
+/// Titles match case-insensitively, ignoring differences in whitespace. The
+/// confidence is the share of the matching transactions in the suggested category.
+pub async fn suggest_category(
+    pool: &DbPool,
+    user_id: Uuid,
+    title: &str,
+) -> Result<CategorySuggestionResponse, ApiError> {
+    let normalized_title = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    let counts =
+        repositories::transaction::count_categories_by_title(pool, user_id, normalized_title)
+            .await?;
+    let matching_transactions: i64 = counts.iter().map(|(_, count)| count).sum();
+
+    Ok(match counts.first() {
+        Some(&(category_id, count)) => CategorySuggestionResponse {
+            category_id: Some(category_id),
+            confidence: count as f64 / matching_transactions as f64,
+            matching_transactions,
+        },
+        None => CategorySuggestionResponse {
+            category_id: None,
+            confidence: 0.0,
+            matching_transactions: 0,
+        },
+    })
 }
 
 /// Update a transaction
+///
+/// Only the changed date, amount and category are checked for warnings, so
+/// unrelated edits of a flagged transaction are not rejected with `strict`.
+/// The amount and account of a transfer leg can't be changed.
 pub async fn update_transaction(
     pool: &DbPool,
     transaction_id: Uuid,
     user_id: Uuid,
-    request: UpdateTransactionRequest,
+    mut request: UpdateTransactionRequest,
+    strict: bool,
 ) -> Result<TransactionResponse, ApiError> {
     // Validate request
     request.validate().map_err(|e| {
         tracing::warn!("Transaction update validation failed: {}", e);
         ApiError::Validation(e.to_string())
     })?;
+    let tags = request
+        .tags
+        .take()
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(ApiError::Validation)?;
 
     // Fetch and verify ownership
     let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
@@ -248,19 +785,23 @@ pub async fn update_transaction(
         );
         return Err(ApiError::Forbidden("Access denied".to_string()));
     }
+    check_transfer_leg_update(&transaction, &request)?;
 
     // If updating account, verify new account ownership
-    if let Some(account_id) = request.account_id {
+    let new_account = if let Some(account_id) = request.account_id {
         let account = repositories::account::find_by_id(pool, account_id).await?;
         if account.user_id != user_id {
             return Err(ApiError::Unauthorized(
                 "Account does not belong to user".to_string(),
             ));
         }
-    }
+        Some(account)
+    } else {
+        None
+    };
 
     // If updating category, verify new category ownership
-    if let Some(category_id) = request.category_id {
+    if let Some(Some(category_id)) = request.category_id {
         let category = repositories::category::find_by_id(pool, category_id).await?;
         if category.user_id != user_id {
             return Err(ApiError::Unauthorized(
@@ -269,29 +810,116 @@ pub async fn update_transaction(
         }
     }
 
-    // Convert amount if provided
-    let amount = if let Some(amt) = request.amount {
-        Some(BigDecimal::from_str(&amt.to_string()).map_err(|e| {
-            tracing::error!("Failed to convert amount: {}", e);
-            ApiError::Validation("Invalid amount".to_string())
-        })?)
+    // If replacing splits, verify people and check the splits against the resulting amount
+    let new_splits = if let Some(split_inputs) = request.splits.take() {
+        if request.amount.is_none() {
+            let split_amounts: Vec<BigDecimal> = split_inputs
+                .iter()
+                .map(|s| s.amount.as_decimal().clone())
+                .collect();
+            validate_splits_sum(&split_amounts, &transaction.amount).map_err(|e| {
+                tracing::warn!("Transaction update split validation failed: {}", e);
+                ApiError::Validation(e.to_string())
+            })?;
+        }
+
+        let mut new_splits = Vec::with_capacity(split_inputs.len());
+        for split_input in split_inputs {
+            let person = repositories::person::find_by_id(pool, split_input.person_id).await?;
+            if person.user_id != user_id {
+                tracing::warn!(
+                    "User {} attempted to split with person {} owned by {}",
+                    user_id,
+                    split_input.person_id,
+                    person.user_id
+                );
+                return Err(ApiError::Unauthorized(
+                    "Person does not belong to user".to_string(),
+                ));
+            }
+
+            new_splits.push(NewTransactionSplit {
+                transaction_id,
+                person_id: split_input.person_id,
+                amount: split_input.amount.into_decimal(),
+            });
+        }
+        Some(new_splits)
     } else {
         None
     };
 
-    // Create update struct
-    let updates = crate::models::UpdateTransaction {
-        account_id: request.account_id,
-        category_id: request.category_id,
-        title: request.title,
-        amount,
-        date: request.date,
-        notes: request.notes,
+    check_resulting_conversion(&transaction, &request)?;
+
+    let mut warnings = ValidationWarnings::default();
+    if let Some(date) = request.date {
+        warnings.check_date(date);
+    }
+    if request.amount.is_some() || request.category_id.is_some() {
+        let resulting_amount = request
+            .amount
+            .as_ref()
+            .map_or(&transaction.amount, |amount| amount.as_decimal());
+        warnings
+            .check_category_direction(
+                pool,
+                resulting_amount,
+                request.category_id.unwrap_or(transaction.category_id),
+                Some(transaction_id),
+            )
+            .await?;
+    }
+    let warnings = warnings.finish(strict)?;
+
+    // The resulting account, whose currency the response is formatted in
+    let account = match new_account {
+        Some(ref account) => account.clone(),
+        None => repositories::account::find_by_id(pool, transaction.account_id).await?,
     };
 
-    // Update transaction
-    let updated =
-        repositories::transaction::update_transaction(pool, transaction_id, updates).await?;
+    let mut updates = update_values(&account, request)?;
+    updates.splits = new_splits;
+    updates.tags = tags;
+    let amount = updates.amount.clone();
+
+    // If the amount or account changes, check the target account's resulting balance.
+    // The delta is computed from the locked row so concurrent edits are accounted for.
+    let result = if amount.is_some() || new_account.is_some() {
+        let target = match new_account {
+            Some(account) if account.id != transaction.account_id => account,
+            _ => repositories::account::find_by_id(pool, transaction.account_id).await?,
+        };
+        repositories::transaction::update_transaction_checked(
+            pool,
+            transaction_id,
+            updates,
+            target.id,
+            move |balance, current| {
+                let new_amount = amount.unwrap_or_else(|| current.amount.clone());
+                let delta = if current.status == TransactionStatus::Void {
+                    BigDecimal::from(0)
+                } else if current.account_id == target.id {
+                    new_amount - &current.amount
+                } else {
+                    new_amount
+                };
+                account_service::project_balance(&target, balance, &delta)
+            },
+        )
+        .await
+        .map(|(updated, projection)| (updated, Some(projection)))
+    } else {
+        repositories::transaction::update_transaction(pool, transaction_id, updates)
+            .await
+            .map(|updated| (updated, None))
+    };
+    let (updated, projection) = match result {
+        Err(ApiError::VersionConflict(None)) => {
+            let current = get_transaction(pool, transaction_id, user_id).await?;
+            return Err(ApiError::version_conflict(&current));
+        }
+        result => result?,
+    };
 
     tracing::info!(
         "Updated transaction {} for user {}",
@@ -299,15 +927,46 @@ pub async fn update_transaction(
         user_id
     );
 
-    Ok(TransactionResponse::from(updated))
+    let mut response = TransactionResponse::from(updated.transaction);
+    response.warnings = warnings;
+    if let Some(projection) = projection {
+        apply_projection(&mut response, projection, account.currency);
+    }
+    if let Some((splits, resync_required)) = updated.splits {
+        response.splits = Some(split_responses(pool, splits).await?);
+        response.needs_resync = resync_required;
+    }
+    response.set_currency(account.currency);
+    attach_tags(pool, std::slice::from_mut(&mut response)).await?;
+
+    Ok(response)
+}
+
+/// IDs of the transactions deleted together with a transaction: both legs of
+/// a transfer, otherwise just the transaction itself
+pub async fn linked_transaction_ids(
+    pool: &DbPool,
+    transaction: &TransactionResponse,
+) -> Result<Vec<Uuid>, ApiError> {
+    match transaction.transfer_id {
+        Some(transfer_id) => {
+            repositories::transaction::list_transfer_leg_ids(pool, transfer_id).await
+        }
+        None => Ok(vec![transaction.id]),
+    }
 }
 
 /// Delete a transaction
+///
+/// Deleted transactions are kept, with their splits, until restored with
+/// [`restore_transaction`], but no longer count anywhere. Deleting either leg
+/// of a transfer deletes both atomically. Returns the IDs of the deleted
+/// transactions.
 pub async fn delete_transaction(
     pool: &DbPool,
     transaction_id: Uuid,
     user_id: Uuid,
-) -> Result<(), ApiError> {
+) -> Result<Vec<Uuid>, ApiError> {
     // Fetch and verify ownership
     let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
     if transaction.user_id != user_id {
@@ -320,10 +979,19 @@ pub async fn delete_transaction(
         return Err(ApiError::Forbidden("Access denied".to_string()));
     }
 
-    // Delete splits first
-    repositories::transaction::delete_splits_for_transaction(pool, transaction_id).await?;
+    if let Some(transfer_id) = transaction.transfer_id {
+        let deleted = repositories::transaction::delete_transfer(pool, transfer_id).await?;
+
+        tracing::info!(
+            "Deleted transfer {} ({} transactions) for user {}",
+            transfer_id,
+            deleted.len(),
+            user_id
+        );
+
+        return Ok(deleted);
+    }
 
-    // Delete transaction
     repositories::transaction::delete_transaction(pool, transaction_id).await?;
 
     tracing::info!(
@@ -332,5 +1000,532 @@ pub async fn delete_transaction(
         user_id
     );
 
+    Ok(vec![transaction_id])
+}
+
+/// Restore a deleted transaction, with its splits
+///
+/// Restoring either leg of a transfer restores both. Restored transactions are
+/// not checked against the account's overdraft or credit limit: they record
+/// money that already moved. Returns the IDs of the restored transactions.
+pub async fn restore_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<Uuid>, ApiError> {
+    // Fetch and verify ownership
+    let transaction =
+        repositories::transaction::find_by_id_with_deleted(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to restore transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let not_deleted =
+        || ApiError::Conflict("Only deleted transactions can be restored".to_string());
+    if transaction.deleted_at.is_none() {
+        return Err(not_deleted());
+    }
+
+    let restored = match transaction.transfer_id {
+        Some(transfer_id) => repositories::transaction::restore_transfer(pool, transfer_id).await?,
+        None => repositories::transaction::restore_transaction(pool, transaction_id)
+            .await?
+            .into_iter()
+            .collect(),
+    };
+    // Lost a race with a concurrent restore of the same transaction
+    if restored.is_empty() {
+        return Err(not_deleted());
+    }
+
+    tracing::info!(
+        "Restored transaction {} ({} transactions) for user {}",
+        transaction_id,
+        restored.len(),
+        user_id
+    );
+
+    Ok(restored.into_iter().map(|t| t.id).collect())
+}
+
+/// Mark a single split of a transaction as settled locally
+///
+/// Settled splits are excluded from the person's debt, which lets users who
+/// don't sync with an external provider settle individual shared expenses.
+pub async fn settle_split(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    split_id: Uuid,
+    user_id: Uuid,
+) -> Result<TransactionSplitResponse, ApiError> {
+    // Fetch and verify ownership
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to settle split on transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let split = repositories::transaction::find_split_by_id(pool, split_id).await?;
+    if split.transaction_id != transaction_id {
+        return Err(ApiError::NotFound(
+            "Split not found for this transaction".to_string(),
+        ));
+    }
+    if split.settled_at.is_some() {
+        return Err(ApiError::Conflict("Split is already settled".to_string()));
+    }
+
+    let settled =
+        repositories::transaction::settle_splits(pool, vec![split_id], chrono::Utc::now())
+            .await?
+            .into_iter()
+            .next()
+            // Lost a race with a concurrent settle of the same split
+            .ok_or_else(|| ApiError::Conflict("Split is already settled".to_string()))?;
+
+    tracing::info!(
+        "Settled split {} of transaction {} for user {}",
+        split_id,
+        transaction_id,
+        user_id
+    );
+
+    Ok(settled.into())
+}
+
+/// Post a pending transaction
+///
+/// Posting moves the amount from the available balance into the cleared
+/// balance and makes it count toward budgets. Only pending transactions can be
+/// posted.
+pub async fn post_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<TransactionResponse, ApiError> {
+    // Fetch and verify ownership
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to post transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let not_pending = || ApiError::Conflict("Only pending transactions can be posted".to_string());
+    if transaction.status != TransactionStatus::Pending {
+        return Err(not_pending());
+    }
+
+    // Allocate the income by the matching allocation rule as it is posted
+    let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
+    let allocation = allocation_rule_service::for_account(pool, &account).await?;
+    let planner = allocation.clone();
+    let account_id = account.id;
+    let (posted, allocations) = repositories::transaction::post_with_transfers(
+        pool,
+        transaction_id,
+        allocation.account_ids(),
+        move |current| {
+            if current.account_id != account_id {
+                return Err(ApiError::Conflict(
+                    "Transaction was moved to another account".to_string(),
+                ));
+            }
+            Ok(planner.transfers_on_post(current))
+        },
+        move |transfer, balance| allocation.check_transfer(transfer, balance),
+    )
+    .await?
+    // Lost a race with a concurrent post of the same transaction
+    .ok_or_else(not_pending)?;
+
+    tracing::info!("Posted transaction {} for user {}", transaction_id, user_id);
+
+    let mut response = TransactionResponse::from(posted);
+    response.allocations = allocations.iter().map(|t| t.id).collect();
+    response.set_currency(account.currency);
+    attach_tags(pool, std::slice::from_mut(&mut response)).await?;
+
+    Ok(response)
+}
+
+/// Void a pending or posted transaction
+///
+/// A void transaction is kept, and still listed, but no longer counts toward
+/// balances, budgets or debts. Unlike deleting, this leaves an audit trail and
+/// can be undone with [`unvoid_transaction`]. Transfer legs can't be voided, as
+/// the other leg would still count; the transfer is deleted instead.
+pub async fn void_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<TransactionResponse, ApiError> {
+    // Fetch and verify ownership
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to void transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    if transaction.status == TransactionStatus::Void {
+        return Err(ApiError::Conflict(
+            "Transaction is already void".to_string(),
+        ));
+    }
+    if transaction.transfer_id.is_some() {
+        return Err(transfer_leg_conflict());
+    }
+
+    let voided = repositories::transaction::transition_status(
+        pool,
+        transaction_id,
+        transaction.status,
+        TransactionStatus::Void,
+    )
+    .await?
+    // Lost a race with a concurrent status change
+    .ok_or_else(|| ApiError::Conflict("Transaction status changed while voiding it".to_string()))?;
+
+    tracing::info!("Voided transaction {} for user {}", transaction_id, user_id);
+
+    let account = repositories::account::find_by_id(pool, voided.account_id).await?;
+    let mut response = TransactionResponse::from(voided);
+    response.set_currency(account.currency);
+    attach_tags(pool, std::slice::from_mut(&mut response)).await?;
+
+    Ok(response)
+}
+
+/// Restore a void transaction as posted
+///
+/// The amount counts toward the balance again, so it is checked against the
+/// account's overdraft/credit limit like a new transaction.
+pub async fn unvoid_transaction(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<TransactionResponse, ApiError> {
+    // Fetch and verify ownership
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to unvoid transaction {} owned by {}",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    let not_void = || ApiError::Conflict("Only void transactions can be unvoided".to_string());
+    if transaction.status != TransactionStatus::Void {
+        return Err(not_void());
+    }
+
+    let account = repositories::account::find_by_id(pool, transaction.account_id).await?;
+    let currency = account.currency;
+    let (restored, projection) = repositories::transaction::transition_status_checked(
+        pool,
+        transaction_id,
+        TransactionStatus::Void,
+        TransactionStatus::Posted,
+        account.id,
+        move |balance, current| {
+            account_service::project_balance(&account, balance, &current.amount)
+        },
+    )
+    .await?
+    // Lost a race with a concurrent unvoid of the same transaction
+    .ok_or_else(not_void)?;
+
+    tracing::info!(
+        "Unvoided transaction {} for user {}",
+        transaction_id,
+        user_id
+    );
+
+    let mut response = TransactionResponse::from(restored);
+    response.set_currency(currency);
+    apply_projection(&mut response, projection, currency);
+    attach_tags(pool, std::slice::from_mut(&mut response)).await?;
+
+    Ok(response)
+}
+
+/// Record that a reimbursable transaction was paid back
+///
+/// Marking a transaction that was already reimbursed keeps the earlier time.
+pub async fn mark_reimbursed(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<TransactionResponse, ApiError> {
+    // Fetch and verify ownership
+    let transaction = repositories::transaction::find_by_id(pool, transaction_id).await?;
+    if transaction.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to mark transaction {} owned by {} reimbursed",
+            user_id,
+            transaction_id,
+            transaction.user_id
+        );
+        return Err(ApiError::Forbidden("Access denied".to_string()));
+    }
+
+    if !transaction.reimbursable {
+        return Err(ApiError::Conflict(
+            "Only reimbursable transactions can be marked reimbursed".to_string(),
+        ));
+    }
+    if transaction.reimbursed_at.is_some() {
+        return get_transaction(pool, transaction_id, user_id).await;
+    }
+
+    repositories::transaction::mark_reimbursed(pool, transaction_id)
+        .await?
+        // Lost a race with a concurrent change of the same transaction
+        .ok_or_else(|| {
+            ApiError::Conflict("Transaction changed while marking it reimbursed".to_string())
+        })?;
+
+    tracing::info!(
+        "Marked transaction {} reimbursed for user {}",
+        transaction_id,
+        user_id
+    );
+
+    get_transaction(pool, transaction_id, user_id).await
+}
+
+/// Inputs that are suspicious but valid, collected while validating a transaction
+#[derive(Debug, Default)]
+struct ValidationWarnings(Vec<String>);
+
+impl ValidationWarnings {
+    /// Warn about a transaction dated in the future
+    fn check_date(&mut self, date: DateTime<Utc>) {
+        if date > Utc::now() {
+            self.0
+                .push(format!("Transaction is dated in the future ({})", date));
+        }
+    }
+
+    /// Warn about an income in a category otherwise only used for expenses, or
+    /// the other way around
+    ///
+    /// `exclude_id` leaves the transaction being updated out of the category's history.
+    async fn check_category_direction(
+        &mut self,
+        pool: &DbPool,
+        amount: &BigDecimal,
+        category_id: Option<Uuid>,
+        exclude_id: Option<Uuid>,
+    ) -> Result<(), ApiError> {
+        let Some(category_id) = category_id else {
+            return Ok(());
+        };
+        let (income, expenses) =
+            repositories::transaction::count_directions_in_category(pool, category_id, exclude_id)
+                .await?;
+        if amount.is_positive() && income == 0 && expenses > 0 {
+            self.0.push(
+                "Amount is positive (income) but the category is only used for expenses"
+                    .to_string(),
+            );
+        } else if amount.is_negative() && expenses == 0 && income > 0 {
+            self.0.push(
+                "Amount is negative (expense) but the category is only used for income".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// The collected warnings, or a validation error listing them when `strict`
+    fn finish(self, strict: bool) -> Result<Vec<String>, ApiError> {
+        if strict && !self.0.is_empty() {
+            tracing::warn!("Strict transaction validation failed: {:?}", self.0);
+            return Err(ApiError::Validation(format!(
+                "Transaction raised warnings: {}",
+                self.0.join("; ")
+            )));
+        }
+        Ok(self.0)
+    }
+}
+
+/// Attach the projected account balance (and any limit warning) to a response
+fn apply_projection(
+    response: &mut TransactionResponse,
+    projection: BalanceProjection,
+    currency: CurrencyCode,
+) {
+    response.projected_balance = Some(Money::new(projection.projected_balance, currency));
+    response.balance_warning = projection.warning;
+}
+
+/// Refuse to move money on one leg of a transfer only
+///
+/// Changing the amount or account of a leg, or voiding it, would leave the
+/// other leg moving a different amount; the transfer is deleted and created
+/// again instead.
+pub(crate) fn check_transfer_leg_update(
+    transaction: &Transaction,
+    request: &UpdateTransactionRequest,
+) -> Result<(), ApiError> {
+    if transaction.transfer_id.is_none() {
+        return Ok(());
+    }
+
+    let amount_changed = request
+        .amount
+        .as_ref()
+        .is_some_and(|amount| amount.as_decimal() != &transaction.amount);
+    let account_changed = request
+        .account_id
+        .is_some_and(|account_id| account_id != transaction.account_id);
+    if amount_changed || account_changed {
+        return Err(transfer_leg_conflict());
+    }
+
     Ok(())
 }
+
+fn transfer_leg_conflict() -> ApiError {
+    ApiError::Conflict(
+        "The amount, account and status of a transfer can't be changed on one leg; \
+         delete the transfer and create it again"
+            .to_string(),
+    )
+}
+
+/// Check the resulting amount of an update against the resulting original
+/// currency details
+pub(crate) fn check_resulting_conversion(
+    transaction: &Transaction,
+    request: &UpdateTransactionRequest,
+) -> Result<(), ApiError> {
+    if request.amount.is_some() || request.original_amount.is_some() {
+        let resulting_amount = request
+            .amount
+            .as_ref()
+            .map_or(&transaction.amount, |amount| amount.as_decimal());
+        let original = match (
+            &request.original_amount,
+            request.exchange_rate.and_then(rate_to_decimal),
+        ) {
+            (Some(original_amount), Some(exchange_rate)) => {
+                Some((original_amount.as_decimal().clone(), exchange_rate))
+            }
+            _ => transaction
+                .original_amount
+                .clone()
+                .zip(transaction.exchange_rate.clone()),
+        };
+        if let Some((original_amount, exchange_rate)) = original {
+            validate_conversion(resulting_amount, &original_amount, &exchange_rate).map_err(
+                |e| {
+                    tracing::warn!("Transaction update conversion validation failed: {}", e);
+                    ApiError::Validation(e.to_string())
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert an update request for storage, given the account the transaction
+/// ends up on; splits are left out
+pub(crate) fn update_values(
+    account: &Account,
+    request: UpdateTransactionRequest,
+) -> Result<UpdateTransaction, ApiError> {
+    let tags = request
+        .tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(ApiError::Validation)?;
+    let (original_currency, original_amount, exchange_rate) = if request.original_currency.is_some()
+    {
+        original_currency_values(
+            account,
+            request.original_currency,
+            request.original_amount,
+            request.exchange_rate,
+        )?
+    } else {
+        (None, None, None)
+    };
+
+    Ok(UpdateTransaction {
+        account_id: request.account_id,
+        category_id: request.category_id,
+        title: request.title,
+        amount: request.amount.map(Money::into_decimal),
+        date: request.date,
+        notes: request.notes,
+        original_currency,
+        original_amount,
+        exchange_rate,
+        merchant: request
+            .merchant
+            .map(|merchant| merchant.map(|m| m.trim().to_string())),
+        latitude: request.latitude,
+        longitude: request.longitude,
+        reimbursable: request.reimbursable,
+        expected_version: request.version,
+        splits: None,
+        tags,
+    })
+}
+
+/// Convert the original currency details of a request for storage
+///
+/// The request validation guarantees the values are given together; the original
+/// currency must differ from the account currency.
+pub(crate) fn original_currency_values(
+    account: &Account,
+    original_currency: Option<CurrencyCode>,
+    original_amount: Option<Money>,
+    exchange_rate: Option<f64>,
+) -> Result<OriginalCurrency, ApiError> {
+    if original_currency == Some(account.currency) {
+        return Err(ApiError::Validation(
+            "Original currency must differ from the account currency".to_string(),
+        ));
+    }
+
+    let exchange_rate = exchange_rate
+        .map(|rate| {
+            rate_to_decimal(rate)
+                .ok_or_else(|| ApiError::Validation("Invalid exchange rate".to_string()))
+        })
+        .transpose()?;
+
+    Ok((
+        original_currency,
+        original_amount.map(Money::into_decimal),
+        exchange_rate,
+    ))
+}
@@ -0,0 +1,197 @@
+//! Transfers between accounts
+//!
+//! A transfer is recorded as two transactions sharing a `transfer_id`: an
+//! outgoing leg on the source account and an incoming leg on the destination
+//! account. Transfers move money without earning or spending it, so they are
+//! left out of income and expense reports. Deleting or restoring either leg
+//! does the same to both
+//! (see [`transaction_service::delete_transaction`](super::transaction_service::delete_transaction)).
+//!
+//! Between currencies the incoming leg is converted at the given rate, or the
+//! exchange rate on the transfer date, and records the original amount and the
+//! rate used.
+
+use std::collections::HashMap;
+
+use bigdecimal::{RoundingMode, Zero};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    errors::ApiError,
+    models::{
+        Account, CreateTransferRequest, NewTransaction, TransactionResponse, TransferResponse,
+        transaction::rate_to_decimal,
+    },
+    repositories,
+    services::{account_service, exchange_rate_service::ExchangeRateService},
+    types::TransactionStatus,
+};
+
+/// Transfer money from one of the user's accounts to another
+///
+/// The outgoing leg is checked against the source account's overdraft or
+/// credit limit, and both legs are created together or not at all.
+pub async fn create_transfer(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: CreateTransferRequest,
+) -> Result<TransferResponse, ApiError> {
+    // Validate request
+    request.validate().map_err(|e| {
+        tracing::warn!("Transfer validation failed: {}", e);
+        ApiError::Validation(e.to_string())
+    })?;
+
+    let from_account = find_owned_account(pool, user_id, request.from_account_id).await?;
+    let to_account = find_owned_account(pool, user_id, request.to_account_id).await?;
+
+    let amount = request.amount.into_decimal();
+
+    // Amount of the incoming leg, and the rate it was converted at
+    let (to_amount, exchange_rate) = if from_account.currency == to_account.currency {
+        if request.exchange_rate.is_some() {
+            return Err(ApiError::Validation(
+                "Exchange rate can only be given for transfers between currencies".to_string(),
+            ));
+        }
+        (amount.clone(), None)
+    } else {
+        let rate = match request.exchange_rate {
+            Some(rate) => rate_to_decimal(rate)
+                .ok_or_else(|| ApiError::Validation("Invalid exchange rate".to_string()))?,
+            None => {
+                ExchangeRateService::new()?
+                    .with_manual_rates(pool.clone(), user_id)
+                    .get_rate_on(
+                        pool,
+                        from_account.currency,
+                        to_account.currency,
+                        request.date.date_naive(),
+                    )
+                    .await?
+                    .rate
+            }
+        };
+        let to_amount = (&amount * &rate)
+            .with_scale_round(to_account.currency.minor_units(), RoundingMode::HalfEven);
+        if to_amount.is_zero() {
+            return Err(ApiError::Validation(
+                "Transfer amount is too small to convert".to_string(),
+            ));
+        }
+        (to_amount, Some(rate))
+    };
+    let converted = exchange_rate.is_some();
+
+    let transfer_id = Uuid::new_v4();
+    let legs = vec![
+        NewTransaction {
+            user_id,
+            account_id: from_account.id,
+            category_id: None,
+            title: format!("Transfer to {}", to_account.name),
+            amount: -amount.clone(),
+            date: request.date,
+            notes: request.notes.clone(),
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: Some(transfer_id),
+            reimbursable: false,
+            tags: Vec::new(),
+        },
+        NewTransaction {
+            user_id,
+            account_id: to_account.id,
+            category_id: None,
+            title: format!("Transfer from {}", from_account.name),
+            amount: to_amount,
+            date: request.date,
+            notes: request.notes,
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: converted.then_some(from_account.currency),
+            original_amount: converted.then(|| amount.clone()),
+            exchange_rate,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: Some(transfer_id),
+            reimbursable: false,
+            tags: Vec::new(),
+        },
+    ];
+
+    // Check the outgoing leg against the source account's limit while it is locked
+    let accounts: HashMap<Uuid, Account> = [from_account.clone(), to_account.clone()]
+        .into_iter()
+        .map(|account| (account.id, account))
+        .collect();
+    let (created, _) = repositories::transaction::create_transactions_checked(
+        pool,
+        user_id,
+        legs,
+        move |transaction, balance| {
+            let account = accounts
+                .get(&transaction.account_id)
+                .ok_or(ApiError::Internal)?;
+            account_service::project_balance(account, balance, &transaction.amount).map(|_| ())
+        },
+    )
+    .await?;
+
+    let leg = |account: &Account| -> Result<TransactionResponse, ApiError> {
+        let transaction = created
+            .iter()
+            .find(|t| t.account_id == account.id)
+            .cloned()
+            .ok_or(ApiError::Internal)?;
+        let mut response = TransactionResponse::from(transaction);
+        response.set_currency(account.currency);
+        Ok(response)
+    };
+    let response = TransferResponse {
+        transfer_id,
+        from_transaction: leg(&from_account)?,
+        to_transaction: leg(&to_account)?,
+    };
+
+    tracing::info!(
+        "Created transfer {} from account {} to account {} for user {}",
+        transfer_id,
+        from_account.id,
+        to_account.id,
+        user_id
+    );
+
+    Ok(response)
+}
+
+/// Find an account, checking that it belongs to the user
+async fn find_owned_account(
+    pool: &DbPool,
+    user_id: Uuid,
+    account_id: Uuid,
+) -> Result<Account, ApiError> {
+    let account = repositories::account::find_by_id(pool, account_id).await?;
+    if account.user_id != user_id {
+        tracing::warn!(
+            "User {} attempted to transfer with account {} owned by {}",
+            user_id,
+            account_id,
+            account.user_id
+        );
+        return Err(ApiError::Forbidden(
+            "Account does not belong to user".to_string(),
+        ));
+    }
+
+    Ok(account)
+}
@@ -0,0 +1,193 @@
+//! TOTP two-factor authentication
+//!
+//! Setup stores an encrypted secret and a fresh set of recovery codes, but login
+//! only starts asking for a code once one from the secret has been verified, so
+//! an abandoned setup can't lock a user out.
+
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    DbPool,
+    auth::{password, totp},
+    errors::ApiError,
+    models::{
+        NewRecoveryCode, SetupTwoFactorRequest, TwoFactorSetupResponse, User,
+        VerifyTwoFactorRequest,
+    },
+    repositories::{self, user},
+    utils,
+};
+
+/// Start two-factor setup for a user
+///
+/// Replaces the secret and recovery codes of any earlier, unverified setup.
+///
+/// # Returns
+/// * `Result<TwoFactorSetupResponse, ApiError>` - The secret, its `otpauth://`
+///   URI and the recovery codes, which are only ever returned here
+///
+/// # Errors
+/// - Unauthorized if the current password is wrong
+/// - Conflict if two-factor authentication is already enabled
+/// - Internal errors if the encryption key isn't configured or for database failures
+pub async fn setup(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: SetupTwoFactorRequest,
+) -> Result<TwoFactorSetupResponse, ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Validation error during 2FA setup: {}", e);
+        ApiError::Validation(format!("Invalid 2FA setup data: {}", e))
+    })?;
+
+    let user = user::find_by_id(pool, user_id).await?;
+    check_password(&user, &request.current_password)?;
+    if user.two_factor_enabled {
+        return Err(ApiError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    let secret = totp::generate_secret();
+    let otpauth_uri = totp::otpauth_uri(&secret, &user.email)?;
+    let secret_encrypted =
+        utils::encrypt_credentials(&json!({ "secret": secret })).map_err(|e| {
+            ApiError::InternalWithMessage(format!("Failed to encrypt TOTP secret: {}", e))
+        })?;
+
+    let recovery_codes = totp::generate_recovery_codes();
+    let new_codes = recovery_codes
+        .iter()
+        .map(|code| NewRecoveryCode {
+            user_id,
+            code_hash: totp::hash_recovery_code(code),
+        })
+        .collect();
+
+    repositories::two_factor::start_setup(pool, user_id, secret_encrypted, new_codes).await?;
+
+    tracing::info!("Started 2FA setup for user {}", user_id);
+
+    Ok(TwoFactorSetupResponse {
+        secret,
+        otpauth_uri,
+        recovery_codes,
+    })
+}
+
+/// Enable two-factor authentication by confirming a code from the setup secret
+///
+/// # Errors
+/// - BadRequest if setup hasn't been started
+/// - Unauthorized if the current password or the code is wrong, or the code
+///   was already used
+/// - Conflict if two-factor authentication is already enabled
+pub async fn verify(
+    pool: &DbPool,
+    user_id: Uuid,
+    request: VerifyTwoFactorRequest,
+) -> Result<(), ApiError> {
+    request.validate().map_err(|e| {
+        tracing::warn!("Validation error during 2FA verification: {}", e);
+        ApiError::Validation(format!("Invalid 2FA verification data: {}", e))
+    })?;
+
+    let user = user::find_by_id(pool, user_id).await?;
+    check_password(&user, &request.current_password)?;
+    if user.two_factor_enabled {
+        return Err(ApiError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+    let secret = stored_secret(&user)?.ok_or_else(|| {
+        ApiError::BadRequest("Two-factor authentication setup has not been started".to_string())
+    })?;
+
+    if !accept_code(pool, &user, &secret, &request.code).await? {
+        tracing::warn!("Invalid 2FA verification code for user {}", user_id);
+        return Err(ApiError::Unauthorized(
+            "Invalid two-factor code".to_string(),
+        ));
+    }
+
+    repositories::two_factor::enable(pool, user_id).await?;
+
+    tracing::info!("Enabled 2FA for user {}", user_id);
+
+    Ok(())
+}
+
+/// Check the code given at login by a user with two-factor authentication enabled
+///
+/// Accepts a current code from the authenticator app that hasn't been used
+/// yet, or an unused recovery code, which is used up. Six-digit codes are only
+/// checked against the app, anything else only against the recovery codes.
+pub async fn verify_login_code(pool: &DbPool, user: &User, code: &str) -> Result<bool, ApiError> {
+    if totp::is_totp_code(code) {
+        return match stored_secret(user)? {
+            Some(secret) => accept_code(pool, user, &secret, code).await,
+            None => Ok(false),
+        };
+    }
+
+    let used =
+        repositories::two_factor::use_recovery_code(pool, user.id, totp::hash_recovery_code(code))
+            .await?;
+    if used {
+        tracing::info!("User {} logged in with a recovery code", user.id);
+    }
+    Ok(used)
+}
+
+/// Check a code from the authenticator app, using up its time step
+///
+/// A code is only accepted once: it and any earlier code fail afterwards,
+/// even while they'd still be current.
+async fn accept_code(
+    pool: &DbPool,
+    user: &User,
+    secret: &str,
+    code: &str,
+) -> Result<bool, ApiError> {
+    let Some(step) = totp::verify_code(secret, code)? else {
+        return Ok(false);
+    };
+    if user.totp_last_step.is_some_and(|last| last >= step) {
+        tracing::warn!("Replayed TOTP code for user {}", user.id);
+        return Ok(false);
+    }
+    repositories::two_factor::use_totp_step(pool, user.id, step).await
+}
+
+/// Check the current password of a user changing their two-factor settings
+fn check_password(user: &User, current_password: &str) -> Result<(), ApiError> {
+    if !password::verify_password(current_password, &user.password_hash)? {
+        tracing::warn!(
+            "2FA change with wrong current password for user {}",
+            user.id
+        );
+        return Err(ApiError::Unauthorized(
+            "Current password is incorrect".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Decrypt the TOTP secret of a user, if setup has been started
+fn stored_secret(user: &User) -> Result<Option<String>, ApiError> {
+    let Some(encrypted) = &user.totp_secret_encrypted else {
+        return Ok(None);
+    };
+    let decrypted = utils::decrypt_credentials(encrypted).map_err(|e| {
+        ApiError::InternalWithMessage(format!("Failed to decrypt TOTP secret: {}", e))
+    })?;
+    decrypted["secret"]
+        .as_str()
+        .map(|secret| Some(secret.to_string()))
+        .ok_or_else(|| {
+            tracing::error!("Stored TOTP secret of user {} has no secret", user.id);
+            ApiError::Internal
+        })
+}
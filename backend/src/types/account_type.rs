@@ -1,9 +1,11 @@
 use diesel::deserialize::{self, FromSql};
 use diesel::pg::Pg;
 use diesel::serialize::{self, Output, ToSql};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::io::Write;
 
+use super::variants::UnknownVariant;
+
 #[derive(
     Debug,
     Clone,
@@ -11,7 +13,7 @@ use std::io::Write;
     PartialEq,
     Eq,
     Serialize,
-    Deserialize,
+    utoipa::ToSchema,
     diesel::AsExpression,
     diesel::FromSqlRow,
 )]
@@ -23,30 +25,84 @@ pub enum AccountType {
     CreditCard,
     Investment,
     Cash,
+    Loan,
+    Mortgage,
+}
+
+impl AccountType {
+    /// Every account type
+    pub const ALL: [AccountType; 7] = [
+        AccountType::Checking,
+        AccountType::Savings,
+        AccountType::CreditCard,
+        AccountType::Investment,
+        AccountType::Cash,
+        AccountType::Loan,
+        AccountType::Mortgage,
+    ];
+
+    /// Name of the account type as used in requests and responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Checking => "CHECKING",
+            AccountType::Savings => "SAVINGS",
+            AccountType::CreditCard => "CREDIT_CARD",
+            AccountType::Investment => "INVESTMENT",
+            AccountType::Cash => "CASH",
+            AccountType::Loan => "LOAN",
+            AccountType::Mortgage => "MORTGAGE",
+        }
+    }
+
+    /// Whether the account is a loan, whose negative balance is the principal
+    /// still owed
+    pub fn is_loan(&self) -> bool {
+        matches!(self, AccountType::Loan | AccountType::Mortgage)
+    }
+}
+
+impl std::str::FromStr for AccountType {
+    type Err = UnknownVariant;
+
+    /// Parse an account type from its name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        super::variants::parse(
+            "account type",
+            &s.trim().to_ascii_uppercase(),
+            &AccountType::ALL,
+            AccountType::as_str,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "account type",
+            &AccountType::ALL,
+            AccountType::as_str,
+        )
+    }
 }
 
 impl ToSql<crate::schema::sql_types::AccountType, Pg> for AccountType {
     fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
-        match *self {
-            AccountType::Checking => out.write_all(b"CHECKING")?,
-            AccountType::Savings => out.write_all(b"SAVINGS")?,
-            AccountType::CreditCard => out.write_all(b"CREDIT_CARD")?,
-            AccountType::Investment => out.write_all(b"INVESTMENT")?,
-            AccountType::Cash => out.write_all(b"CASH")?,
-        }
+        out.write_all(self.as_str().as_bytes())?;
         Ok(serialize::IsNull::No)
     }
 }
 
 impl FromSql<crate::schema::sql_types::AccountType, Pg> for AccountType {
+    /// Fails, rather than panics, on a label added to the database enum that
+    /// this build does not know
     fn from_sql(bytes: diesel::pg::PgValue) -> deserialize::Result<Self> {
-        match bytes.as_bytes() {
-            b"CHECKING" => Ok(AccountType::Checking),
-            b"SAVINGS" => Ok(AccountType::Savings),
-            b"CREDIT_CARD" => Ok(AccountType::CreditCard),
-            b"INVESTMENT" => Ok(AccountType::Investment),
-            b"CASH" => Ok(AccountType::Cash),
-            _ => Err("Unrecognized enum variant for AccountType".into()),
-        }
+        let label = std::str::from_utf8(bytes.as_bytes())?;
+        Ok(super::variants::parse(
+            "account type",
+            label,
+            &AccountType::ALL,
+            AccountType::as_str,
+        )?)
     }
 }
@@ -1,7 +1,7 @@
 use diesel::deserialize::{self, FromSql};
 use diesel::pg::Pg;
 use diesel::serialize::{self, Output, ToSql};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::io::Write;
 
 #[derive(
@@ -11,7 +11,7 @@ use std::io::Write;
     PartialEq,
     Eq,
     Serialize,
-    Deserialize,
+    utoipa::ToSchema,
     diesel::AsExpression,
     diesel::FromSqlRow,
 )]
@@ -25,6 +25,39 @@ pub enum BudgetPeriod {
     Yearly,
 }
 
+impl BudgetPeriod {
+    /// Every budget period
+    pub const ALL: [BudgetPeriod; 5] = [
+        BudgetPeriod::Daily,
+        BudgetPeriod::Weekly,
+        BudgetPeriod::Monthly,
+        BudgetPeriod::Quarterly,
+        BudgetPeriod::Yearly,
+    ];
+
+    /// Name of the budget period as used in requests and responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "DAILY",
+            BudgetPeriod::Weekly => "WEEKLY",
+            BudgetPeriod::Monthly => "MONTHLY",
+            BudgetPeriod::Quarterly => "QUARTERLY",
+            BudgetPeriod::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BudgetPeriod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "budget period",
+            &BudgetPeriod::ALL,
+            BudgetPeriod::as_str,
+        )
+    }
+}
+
 impl ToSql<crate::schema::sql_types::BudgetPeriod, Pg> for BudgetPeriod {
     fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
         match *self {
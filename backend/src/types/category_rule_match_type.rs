@@ -0,0 +1,79 @@
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::io::Write;
+
+/// How a category rule's pattern is matched against a transaction title
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    utoipa::ToSchema,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = crate::schema::sql_types::CategoryRuleMatchType)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CategoryRuleMatchType {
+    /// The title contains the pattern
+    Contains,
+    /// The title starts with the pattern
+    StartsWith,
+    /// The title matches the pattern as a regular expression
+    Regex,
+}
+
+impl CategoryRuleMatchType {
+    /// Every match type
+    pub const ALL: [CategoryRuleMatchType; 3] = [
+        CategoryRuleMatchType::Contains,
+        CategoryRuleMatchType::StartsWith,
+        CategoryRuleMatchType::Regex,
+    ];
+
+    /// Name of the match type as used in requests and responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CategoryRuleMatchType::Contains => "CONTAINS",
+            CategoryRuleMatchType::StartsWith => "STARTS_WITH",
+            CategoryRuleMatchType::Regex => "REGEX",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CategoryRuleMatchType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "match type",
+            &CategoryRuleMatchType::ALL,
+            CategoryRuleMatchType::as_str,
+        )
+    }
+}
+
+impl ToSql<crate::schema::sql_types::CategoryRuleMatchType, Pg> for CategoryRuleMatchType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            CategoryRuleMatchType::Contains => out.write_all(b"CONTAINS")?,
+            CategoryRuleMatchType::StartsWith => out.write_all(b"STARTS_WITH")?,
+            CategoryRuleMatchType::Regex => out.write_all(b"REGEX")?,
+        }
+        Ok(serialize::IsNull::No)
+    }
+}
+
+impl FromSql<crate::schema::sql_types::CategoryRuleMatchType, Pg> for CategoryRuleMatchType {
+    fn from_sql(bytes: diesel::pg::PgValue) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"CONTAINS" => Ok(CategoryRuleMatchType::Contains),
+            b"STARTS_WITH" => Ok(CategoryRuleMatchType::StartsWith),
+            b"REGEX" => Ok(CategoryRuleMatchType::Regex),
+            _ => Err("Unrecognized enum variant for CategoryRuleMatchType".into()),
+        }
+    }
+}
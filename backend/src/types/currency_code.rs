@@ -1,9 +1,11 @@
 use diesel::deserialize::{self, FromSql};
 use diesel::pg::Pg;
 use diesel::serialize::{self, Output, ToSql};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::io::Write;
 
+use super::variants::UnknownVariant;
+
 #[derive(
     Debug,
     Clone,
@@ -12,7 +14,7 @@ use std::io::Write;
     Eq,
     Hash,
     Serialize,
-    Deserialize,
+    utoipa::ToSchema,
     diesel::AsExpression,
     diesel::FromSqlRow,
 )]
@@ -29,6 +31,17 @@ pub enum CurrencyCode {
 }
 
 impl CurrencyCode {
+    /// Every currency
+    pub const ALL: [CurrencyCode; 7] = [
+        CurrencyCode::Usd,
+        CurrencyCode::Eur,
+        CurrencyCode::Gbp,
+        CurrencyCode::Inr,
+        CurrencyCode::Jpy,
+        CurrencyCode::Aud,
+        CurrencyCode::Cad,
+    ];
+
     /// Convert currency code to string representation
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -41,34 +54,61 @@ impl CurrencyCode {
             CurrencyCode::Cad => "CAD",
         }
     }
+
+    /// Most decimal places of any currency, and of stored amounts
+    pub const MAX_MINOR_UNITS: i64 = 2;
+
+    /// Number of decimal places amounts in this currency are shown with
+    pub fn minor_units(&self) -> i64 {
+        match self {
+            CurrencyCode::Jpy => 0,
+            _ => 2,
+        }
+    }
+}
+
+impl std::str::FromStr for CurrencyCode {
+    type Err = UnknownVariant;
+
+    /// Parse an ISO 4217 code, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        super::variants::parse(
+            "currency",
+            &s.trim().to_ascii_uppercase(),
+            &CurrencyCode::ALL,
+            CurrencyCode::as_str,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "currency",
+            &CurrencyCode::ALL,
+            CurrencyCode::as_str,
+        )
+    }
 }
 
 impl ToSql<crate::schema::sql_types::CurrencyCode, Pg> for CurrencyCode {
     fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
-        match *self {
-            CurrencyCode::Usd => out.write_all(b"USD")?,
-            CurrencyCode::Eur => out.write_all(b"EUR")?,
-            CurrencyCode::Gbp => out.write_all(b"GBP")?,
-            CurrencyCode::Inr => out.write_all(b"INR")?,
-            CurrencyCode::Jpy => out.write_all(b"JPY")?,
-            CurrencyCode::Aud => out.write_all(b"AUD")?,
-            CurrencyCode::Cad => out.write_all(b"CAD")?,
-        }
+        out.write_all(self.as_str().as_bytes())?;
         Ok(serialize::IsNull::No)
     }
 }
 
 impl FromSql<crate::schema::sql_types::CurrencyCode, Pg> for CurrencyCode {
+    /// Fails, rather than panics, on a label added to the database enum that
+    /// this build does not know
     fn from_sql(bytes: diesel::pg::PgValue) -> deserialize::Result<Self> {
-        match bytes.as_bytes() {
-            b"USD" => Ok(CurrencyCode::Usd),
-            b"EUR" => Ok(CurrencyCode::Eur),
-            b"GBP" => Ok(CurrencyCode::Gbp),
-            b"INR" => Ok(CurrencyCode::Inr),
-            b"JPY" => Ok(CurrencyCode::Jpy),
-            b"AUD" => Ok(CurrencyCode::Aud),
-            b"CAD" => Ok(CurrencyCode::Cad),
-            _ => Err("Unrecognized enum variant for CurrencyCode".into()),
-        }
+        let label = std::str::from_utf8(bytes.as_bytes())?;
+        Ok(super::variants::parse(
+            "currency",
+            label,
+            &CurrencyCode::ALL,
+            CurrencyCode::as_str,
+        )?)
     }
 }
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Length of the intervals a time series is grouped into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Granularity {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// Every granularity
+    pub const ALL: [Granularity; 3] = [Granularity::Day, Granularity::Week, Granularity::Month];
+
+    /// Name of the granularity as used in requests and responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Day => "DAY",
+            Granularity::Week => "WEEK",
+            Granularity::Month => "MONTH",
+        }
+    }
+
+    /// Field of Postgres' `date_trunc` truncating to the start of an interval
+    pub fn date_trunc_field(&self) -> &'static str {
+        match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Granularity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "granularity",
+            &Granularity::ALL,
+            Granularity::as_str,
+        )
+    }
+}
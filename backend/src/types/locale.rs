@@ -0,0 +1,108 @@
+//! Locale-aware display of monetary amounts
+//!
+//! Amounts in responses are canonical decimal strings (`"-1234.56"`). Clients that
+//! render amounts directly can additionally ask for them formatted with a
+//! locale's digit grouping and decimal separator (`"-1.234,56"` for `de-DE`).
+//! The formatted string is for display only; the canonical amount stays
+//! authoritative.
+
+use serde::{Deserialize, Serialize};
+
+use super::Money;
+
+/// Locale amounts can be formatted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "en-GB")]
+    EnGb,
+    #[serde(rename = "de-DE")]
+    DeDe,
+    #[serde(rename = "fr-FR")]
+    FrFr,
+    #[serde(rename = "it-IT")]
+    ItIt,
+    #[serde(rename = "ja-JP")]
+    JaJp,
+}
+
+impl Locale {
+    /// BCP 47 language tag of the locale
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::EnUs => "en-US",
+            Locale::EnGb => "en-GB",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+            Locale::ItIt => "it-IT",
+            Locale::JaJp => "ja-JP",
+        }
+    }
+
+    /// Digit group separator and decimal separator
+    fn separators(&self) -> (&'static str, &'static str) {
+        match self {
+            Locale::EnUs | Locale::EnGb | Locale::JaJp => (",", "."),
+            Locale::DeDe | Locale::ItIt => (".", ","),
+            // Narrow no-break space
+            Locale::FrFr => ("\u{202f}", ","),
+        }
+    }
+
+    /// Format an amount for display, e.g. `"-1,234.56"` for `en-US`
+    ///
+    /// The amount is rounded to its currency's minor units like the canonical
+    /// string, and digits are grouped in thousands.
+    pub fn format(&self, money: &Money) -> String {
+        let canonical = money.to_string();
+        let (sign, digits) = match canonical.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", canonical.as_str()),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+        let (group_separator, decimal_separator) = self.separators();
+
+        let mut formatted = sign.to_string();
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                formatted.push_str(group_separator);
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push_str(decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    /// Parse a language tag, ignoring case and accepting `_` for `-`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().replace('_', "-").to_ascii_lowercase().as_str() {
+            "en-us" => Ok(Locale::EnUs),
+            "en-gb" => Ok(Locale::EnGb),
+            "de-de" => Ok(Locale::DeDe),
+            "fr-fr" => Ok(Locale::FrFr),
+            "it-it" => Ok(Locale::ItIt),
+            "ja-jp" => Ok(Locale::JaJp),
+            _ => Err(format!("Unsupported locale: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
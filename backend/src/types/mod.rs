@@ -1,11 +1,26 @@
 mod account_type;
 mod api_key_status;
 mod budget_period;
+mod category_rule_match_type;
 mod confidence_level;
 mod currency_code;
+mod granularity;
+mod locale;
+mod money;
+pub mod nullable;
+mod recurrence_frequency;
+mod transaction_status;
+mod variants;
 
 pub use account_type::AccountType;
 pub use api_key_status::ApiKeyStatus;
 pub use budget_period::BudgetPeriod;
+pub use category_rule_match_type::CategoryRuleMatchType;
 pub use confidence_level::ConfidenceLevel;
 pub use currency_code::CurrencyCode;
+pub use granularity::Granularity;
+pub use locale::Locale;
+pub use money::Money;
+pub use recurrence_frequency::RecurrenceFrequency;
+pub use transaction_status::TransactionStatus;
+pub use variants::UnknownVariant;
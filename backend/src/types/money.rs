@@ -0,0 +1,172 @@
+//! Monetary amounts at the API boundary
+//!
+//! [`Money`] wraps a decimal amount and, when known, its currency. It serializes
+//! as a string rounded to the currency's minor units (e.g. `"-75.50"`, or
+//! `"1500"` for JPY) and deserializes from either a string or a JSON number, so
+//! amounts are never added up as floats. Amounts that don't fit the stored
+//! `DECIMAL(19, 2)` are refused while deserializing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, RoundingMode, Signed, Zero};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::openapi::RefOr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+
+use super::CurrencyCode;
+
+/// Decimal places used when the currency of an amount is not known
+const DEFAULT_MINOR_UNITS: i64 = 2;
+
+/// Most digits before the decimal point an amount may have
+const MAX_INTEGER_DIGITS: i64 = 17;
+
+/// Decimal amount of money, optionally tagged with its currency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+    amount: BigDecimal,
+    currency: Option<CurrencyCode>,
+}
+
+impl Money {
+    /// Amount in a known currency
+    pub fn new(amount: BigDecimal, currency: CurrencyCode) -> Self {
+        Self {
+            amount,
+            currency: Some(currency),
+        }
+    }
+
+    /// The decimal amount
+    pub fn as_decimal(&self) -> &BigDecimal {
+        &self.amount
+    }
+
+    /// Consume the value, returning the decimal amount
+    pub fn into_decimal(self) -> BigDecimal {
+        self.amount
+    }
+
+    /// Currency of the amount, if known
+    pub fn currency(&self) -> Option<CurrencyCode> {
+        self.currency
+    }
+
+    /// Tag the amount with its currency, which determines the serialized precision
+    pub fn set_currency(&mut self, currency: CurrencyCode) {
+        self.currency = Some(currency);
+    }
+
+    /// Whether the amount is zero
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Whether the amount is greater than zero
+    pub fn is_positive(&self) -> bool {
+        self.amount.is_positive()
+    }
+}
+
+impl From<BigDecimal> for Money {
+    /// Amount in an unknown currency, serialized with two decimal places
+    fn from(amount: BigDecimal) -> Self {
+        Self {
+            amount,
+            currency: None,
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minor_units = self
+            .currency
+            .map_or(DEFAULT_MINOR_UNITS, |currency| currency.minor_units());
+        let rounded = self
+            .amount
+            .with_scale_round(minor_units, RoundingMode::HalfEven);
+        // BigDecimal prints zero without its scale
+        if rounded.is_zero() && minor_units > 0 {
+            return write!(f, "0.{}", "0".repeat(minor_units as usize));
+        }
+        write!(f, "{}", rounded)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+struct MoneyVisitor;
+
+impl Visitor<'_> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal amount as a string or number")
+    }
+
+    /// The currency is not known yet, so up to the decimal places of the
+    /// most precise currency are accepted
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Money, E> {
+        let amount = BigDecimal::from_str(value.trim())
+            .map_err(|_| E::custom(format!("invalid amount: {}", value)))?;
+
+        // Counted without trailing zeros, so e.g. "1.500" and "1e3" pass
+        let normalized = amount.normalized();
+        let (_, scale) = normalized.as_bigint_and_exponent();
+        let integer_digits = normalized.digits() as i64 - scale;
+        if integer_digits > MAX_INTEGER_DIGITS || scale > CurrencyCode::MAX_MINOR_UNITS {
+            return Err(E::custom(format!(
+                "amount {} must have at most {} digits before and {} after the decimal point",
+                value,
+                MAX_INTEGER_DIGITS,
+                CurrencyCode::MAX_MINOR_UNITS
+            )));
+        }
+
+        Ok(Money::from(amount))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Money, E> {
+        Ok(Money::from(BigDecimal::from(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Money, E> {
+        Ok(Money::from(BigDecimal::from(value)))
+    }
+
+    /// JSON numbers with a fraction arrive as floats; their shortest decimal
+    /// representation is what the client sent (e.g. `0.1`, not `0.1000000000000000055`)
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Money, E> {
+        if !value.is_finite() {
+            return Err(E::custom("amount must be finite"));
+        }
+        self.visit_str(&value.to_string())
+    }
+}
+
+impl utoipa::PartialSchema for Money {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some(
+                "Decimal amount as a string, e.g. \"-75.50\". Requests also accept JSON numbers.",
+            ))
+            .examples([serde_json::json!("-75.50")])
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for Money {}
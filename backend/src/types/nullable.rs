@@ -0,0 +1,20 @@
+//! Deserialization of optional fields that can be cleared in updates
+//!
+//! With a plain `Option`, an update cannot tell an omitted field from an
+//! explicit `null`. Fields that use [`deserialize`] together with
+//! `#[serde(default)]` deserialize to `Option<Option<T>>` instead:
+//!
+//! - omitted: `None`, leave the field unchanged
+//! - `null`: `Some(None)`, clear the field
+//! - a value: `Some(Some(value))`, set the field
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserialize a field that is present, as `Some` of its possibly null value
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
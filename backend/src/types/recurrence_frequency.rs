@@ -0,0 +1,102 @@
+use chrono::{Days, Months, NaiveDate};
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::io::Write;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    utoipa::ToSchema,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = crate::schema::sql_types::RecurrenceFrequency)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl RecurrenceFrequency {
+    /// Every recurrence frequency
+    pub const ALL: [RecurrenceFrequency; 4] = [
+        RecurrenceFrequency::Daily,
+        RecurrenceFrequency::Weekly,
+        RecurrenceFrequency::Monthly,
+        RecurrenceFrequency::Yearly,
+    ];
+
+    /// Name of the frequency as used in requests and responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurrenceFrequency::Daily => "DAILY",
+            RecurrenceFrequency::Weekly => "WEEKLY",
+            RecurrenceFrequency::Monthly => "MONTHLY",
+            RecurrenceFrequency::Yearly => "YEARLY",
+        }
+    }
+
+    /// Date of occurrence number `index` (the first being 0) of a schedule
+    /// starting at `start` and repeating every `interval` periods
+    ///
+    /// Months and years are counted from `start`, so a day that does not exist
+    /// in the target month is clamped to its last day without shifting later
+    /// occurrences: a monthly schedule from Jan 31 runs on Feb 28, then Mar 31.
+    /// Returns `None` when the date is out of range.
+    pub fn occurrence(&self, start: NaiveDate, interval: u32, index: u32) -> Option<NaiveDate> {
+        let periods = interval.checked_mul(index)?;
+        match self {
+            RecurrenceFrequency::Daily => start.checked_add_days(Days::new(periods.into())),
+            RecurrenceFrequency::Weekly => {
+                start.checked_add_days(Days::new(u64::from(periods) * 7))
+            }
+            RecurrenceFrequency::Monthly => start.checked_add_months(Months::new(periods)),
+            RecurrenceFrequency::Yearly => {
+                start.checked_add_months(Months::new(periods.checked_mul(12)?))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RecurrenceFrequency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        super::variants::deserialize(
+            deserializer,
+            "recurrence frequency",
+            &RecurrenceFrequency::ALL,
+            RecurrenceFrequency::as_str,
+        )
+    }
+}
+
+impl ToSql<crate::schema::sql_types::RecurrenceFrequency, Pg> for RecurrenceFrequency {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            RecurrenceFrequency::Daily => out.write_all(b"DAILY")?,
+            RecurrenceFrequency::Weekly => out.write_all(b"WEEKLY")?,
+            RecurrenceFrequency::Monthly => out.write_all(b"MONTHLY")?,
+            RecurrenceFrequency::Yearly => out.write_all(b"YEARLY")?,
+        }
+        Ok(serialize::IsNull::No)
+    }
+}
+
+impl FromSql<crate::schema::sql_types::RecurrenceFrequency, Pg> for RecurrenceFrequency {
+    fn from_sql(bytes: diesel::pg::PgValue) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"DAILY" => Ok(RecurrenceFrequency::Daily),
+            b"WEEKLY" => Ok(RecurrenceFrequency::Weekly),
+            b"MONTHLY" => Ok(RecurrenceFrequency::Monthly),
+            b"YEARLY" => Ok(RecurrenceFrequency::Yearly),
+            _ => Err("Unrecognized enum variant for RecurrenceFrequency".into()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Clearing state of a transaction
+///
+/// Only posted transactions count toward the cleared balance and budget
+/// spending. Pending transactions are included in the available balance, and
+/// void transactions are ignored everywhere.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = crate::schema::sql_types::TransactionStatus)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    Pending,
+    #[default]
+    Posted,
+    Void,
+}
+
+impl ToSql<crate::schema::sql_types::TransactionStatus, Pg> for TransactionStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            TransactionStatus::Pending => out.write_all(b"pending")?,
+            TransactionStatus::Posted => out.write_all(b"posted")?,
+            TransactionStatus::Void => out.write_all(b"void")?,
+        }
+        Ok(serialize::IsNull::No)
+    }
+}
+
+impl FromSql<crate::schema::sql_types::TransactionStatus, Pg> for TransactionStatus {
+    fn from_sql(bytes: diesel::pg::PgValue) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"pending" => Ok(TransactionStatus::Pending),
+            b"posted" => Ok(TransactionStatus::Posted),
+            b"void" => Ok(TransactionStatus::Void),
+            _ => Err("Unrecognized enum variant for TransactionStatus".into()),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Parsing of enums from their names with helpful errors
+//!
+//! Serde's derived error for an unknown variant does not say which value was
+//! rejected, so enums accepted in requests deserialize through [`deserialize`],
+//! which names both, e.g. `invalid account type 'checking'; expected one of
+//! CHECKING, SAVINGS, CREDIT_CARD, INVESTMENT, CASH`. Their `FromStr` and
+//! Diesel `FromSql` impls fail with the same [`UnknownVariant`] error, so a
+//! value this build does not know is rejected rather than crashing the caller.
+
+use serde::{Deserialize, Deserializer, de::Error};
+use std::fmt;
+
+/// A value that names none of an enum's variants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownVariant {
+    /// What the value names, e.g. `"currency"`
+    pub kind: &'static str,
+    /// The value as given
+    pub value: String,
+    /// Names of the variants that are accepted
+    pub expected: Vec<&'static str>,
+}
+
+impl fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} '{}'; expected one of {}",
+            self.kind,
+            self.value,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
+
+/// Find the one of `variants` named exactly `value`
+///
+/// `kind` names the value in errors, e.g. `"account type"`.
+pub(crate) fn parse<T: Copy>(
+    kind: &'static str,
+    value: &str,
+    variants: &[T],
+    name: fn(&T) -> &'static str,
+) -> Result<T, UnknownVariant> {
+    variants
+        .iter()
+        .find(|variant| name(variant) == value)
+        .copied()
+        .ok_or_else(|| UnknownVariant {
+            kind,
+            value: value.to_string(),
+            expected: variants.iter().map(name).collect(),
+        })
+}
+
+/// Deserialize one of `variants` from its exact name
+pub(crate) fn deserialize<'de, D, T>(
+    deserializer: D,
+    kind: &'static str,
+    variants: &[T],
+    name: fn(&T) -> &'static str,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Copy,
+{
+    let value = String::deserialize(deserializer)?;
+    parse(kind, &value, variants, name).map_err(D::Error::custom)
+}
@@ -4,31 +4,106 @@
 //! - Authentication endpoints (test_auth)
 //! - API key management endpoints (test_api_keys)
 //! - Account management endpoints
+//! - Account period summaries (test_account_summary)
+//! - Account balance history (test_balance_history)
+//! - Reconciling accounts with bank statements (test_reconciliation)
+//! - Loan and mortgage accounts (test_loan_accounts)
+//! - Income allocation rules (test_allocation_rules)
+//! - Recurring transactions (test_recurring)
 //! - Transaction endpoints
+//! - Deleting and restoring transactions (test_soft_delete)
+//! - Transfers between accounts (test_transfers)
+//! - Bulk transaction create/update/delete (test_bulk_transactions)
+//! - Aggregator import endpoint (test_aggregator_import)
+//! - OFX/QFX statement import (test_ofx_import)
+//! - Import profiles and CSV column mappings (test_import_profiles)
 //! - Budget endpoints
+//! - Budget threshold alerts (test_budget_alerts)
 //! - Category endpoints
+//! - Merging categories (test_category_merge)
+//! - Category rules (test_category_rules)
+//! - Transaction tags (test_tags)
+//! - Reimbursable transactions (test_reimbursements)
 //! - People endpoints
 //! - Dashboard endpoints
+//! - Net worth over time (test_net_worth_trend)
+//! - Monthly income and expense (test_cashflow)
+//! - Change notifications (test_events)
+//! - Notification channels and preferences (test_notifications)
+//! - Audit log of changes (test_audit_log)
+//! - Conditional GET requests with ETags (test_conditional_requests)
+//! - OpenAPI documentation endpoints (test_api_docs)
 //! - Split provider integration endpoints (test_split_providers)
 //! - Split sync status endpoints (test_split_sync)
+//! - Splitwise webhook deliveries (test_splitwise_webhook)
+//! - Equal split rounding policy (test_split_rounding)
+//! - Splits computed by strategy (test_split_strategies)
+//! - Exchange rates stored per day (test_historical_exchange_rates)
+//! - Background exchange rate refresh (test_exchange_rate_refresh)
+//! - Fallback between exchange rate providers (test_exchange_rate_providers)
+//! - Manual exchange rates (test_custom_exchange_rates)
+//! - Supported currencies and account types (test_meta)
+//! - List pagination (test_pagination)
+//! - Request IDs in responses (test_request_id)
+//! - Request rate limiting (test_rate_limiting)
+//! - Two-factor authentication (test_two_factor)
 
 #[path = "../common/mod.rs"]
 mod common;
 
+mod test_account_summary;
 mod test_accounts;
+mod test_aggregator_import;
+mod test_allocation_rules;
+mod test_api_docs;
 mod test_api_keys;
+mod test_audit_log;
 mod test_auth;
+mod test_balance_history;
+mod test_budget_alerts;
 mod test_budgets;
+mod test_bulk_transactions;
+mod test_cashflow;
 mod test_categories;
+mod test_category_merge;
+mod test_category_rules;
+mod test_conditional_requests;
 mod test_csv_import;
 mod test_currency_conversion;
+mod test_custom_exchange_rates;
 mod test_dashboard;
 mod test_duplicate_detection;
+mod test_events;
+mod test_exchange_rate_coalescing;
+mod test_exchange_rate_conversion;
+mod test_exchange_rate_providers;
+mod test_exchange_rate_refresh;
 mod test_exchange_rates;
+mod test_historical_exchange_rates;
 mod test_import_api;
+mod test_import_profiles;
 mod test_import_service;
+mod test_loan_accounts;
+mod test_meta;
+mod test_net_worth_trend;
+mod test_notifications;
+mod test_ofx_import;
+mod test_pagination;
 mod test_people;
+mod test_rate_limiting;
+mod test_reconciliation;
+mod test_recurring;
+mod test_reimbursements;
+mod test_request_id;
 mod test_scope_enforcement;
+mod test_soft_delete;
+mod test_split_groups;
 mod test_split_providers;
+mod test_split_rounding;
+mod test_split_strategies;
 mod test_split_sync;
+mod test_splitwise_webhook;
+mod test_tags;
 mod test_transactions;
+mod test_transfers;
+mod test_two_factor;
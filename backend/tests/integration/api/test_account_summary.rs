@@ -0,0 +1,169 @@
+//! Integration tests for account period summaries.
+//!
+//! This module tests GET /api/v1/accounts/:id/summary, which reports an
+//! account's opening balance, income, expense, net change and closing balance
+//! over a period.
+//!
+//! Tests cover the period bounds, the treatment of transfers and pending
+//! transactions, and authorization.
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::models::AccountSummaryResponse;
+use serde_json::json;
+use uuid::Uuid;
+
+async fn get_summary(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    query: &str,
+) -> AccountSummaryResponse {
+    let response = get_authenticated(
+        server,
+        &format!("/api/v1/accounts/{}/summary{}", account_id, query),
+        token,
+    )
+    .await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// Test summarizing an account over a period.
+///
+/// Verifies that:
+/// - Transactions before the start make up the opening balance
+/// - Transactions after the end and pending transactions are ignored
+/// - Transfers are excluded from income and expense but included in the net change
+#[tokio::test]
+async fn test_account_summary() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("summary_{}", timestamp)).await;
+
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let salary = create_test_account(&server, &auth.token, "Salary").await;
+
+    // Income landing in the salary account is moved to checking
+    let request = json!({
+        "name": "Pay checking",
+        "source_account_id": salary.id,
+        "destinations": [{"account_id": checking.id, "percentage": 100}]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let token = &auth.token;
+    create_transaction(
+        &server,
+        token,
+        checking.id,
+        "1000.00",
+        "2026-01-05T12:00:00Z",
+    )
+    .await;
+    create_transaction(
+        &server,
+        token,
+        checking.id,
+        "500.00",
+        "2026-02-10T12:00:00Z",
+    )
+    .await;
+    create_transaction(
+        &server,
+        token,
+        checking.id,
+        "-120.50",
+        "2026-02-15T12:00:00Z",
+    )
+    .await;
+    create_transaction(&server, token, salary.id, "300.00", "2026-02-25T12:00:00Z").await;
+    create_transaction(
+        &server,
+        token,
+        checking.id,
+        "-40.00",
+        "2026-03-02T12:00:00Z",
+    )
+    .await;
+
+    let request = json!({
+        "account_id": checking.id,
+        "title": "Pending refund",
+        "amount": "75.00",
+        "date": "2026-02-20T12:00:00Z",
+        "status": "pending"
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+
+    let summary = get_summary(
+        &server,
+        token,
+        checking.id,
+        "?start=2026-02-01T00:00:00Z&end=2026-02-28T23:59:59Z",
+    )
+    .await;
+    assert_eq!(summary.account_id, checking.id);
+    assert_eq!(summary.opening_balance.to_string(), "1000.00");
+    assert_eq!(summary.total_income.to_string(), "500.00");
+    assert_eq!(summary.total_expense.to_string(), "120.50");
+    assert_eq!(summary.net_change.to_string(), "679.50");
+    assert_eq!(summary.closing_balance.to_string(), "1679.50");
+
+    // Without bounds the summary covers all posted transactions
+    let summary = get_summary(&server, token, checking.id, "").await;
+    assert_eq!(summary.opening_balance.to_string(), "0.00");
+    assert_eq!(summary.total_income.to_string(), "1500.00");
+    assert_eq!(summary.total_expense.to_string(), "160.50");
+    assert_eq!(summary.closing_balance.to_string(), "1639.50");
+
+    // The salary's transfer out is not an expense
+    let summary = get_summary(&server, token, salary.id, "").await;
+    assert_eq!(summary.total_income.to_string(), "300.00");
+    assert_eq!(summary.total_expense.to_string(), "0.00");
+    assert_eq!(summary.net_change.to_string(), "0.00");
+    assert_eq!(summary.closing_balance.to_string(), "0.00");
+}
+
+/// Test the summary's error cases.
+///
+/// Verifies that:
+/// - Another user's account returns 403 Forbidden
+/// - A missing account returns 404 Not Found
+/// - A start after the end returns 400 Bad Request
+#[tokio::test]
+async fn test_account_summary_errors() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let owner = register_unique_test_user(&server, &format!("summary_a_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("summary_b_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &owner.token, "Checking").await;
+    let path = format!("/api/v1/accounts/{}/summary", account.id);
+
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}/summary", Uuid::new_v4()),
+        &owner.token,
+    )
+    .await;
+    assert_status(&response, 404);
+
+    let response = get_authenticated(
+        &server,
+        &format!(
+            "{}?start=2026-03-01T00:00:00Z&end=2026-02-01T00:00:00Z",
+            path
+        ),
+        &owner.token,
+    )
+    .await;
+    assert_status(&response, 400);
+}
@@ -2,7 +2,7 @@
 //!
 //! This module tests the account endpoints including:
 //! - GET /api/v1/accounts - List all accounts for user
-//! - POST /api/v1/accounts - Create new account
+//! - POST /api/v1/accounts - Create new account (optionally with an Idempotency-Key)
 //! - GET /api/v1/accounts/:id - Get specific account
 //! - PUT /api/v1/accounts/:id - Update account
 //! - DELETE /api/v1/accounts/:id - Delete account
@@ -427,6 +427,151 @@ async fn test_create_account_invalid_currency() {
     }
 }
 
+/// Test that invalid enum values are rejected with a message listing the valid values.
+///
+/// Verifies that:
+/// - Status code is 422 Unprocessable Entity with the usual JSON error body
+/// - The error names the field and the rejected value
+/// - The error lists every accepted account type and currency
+#[tokio::test]
+async fn test_create_account_invalid_enum_messages() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("enummsg_{}", timestamp)).await;
+
+    let request = json!({
+        "name": "Test Account",
+        "account_type": "checking",
+        "currency": "USD"
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 422);
+    let body: serde_json::Value = extract_json(response);
+    let error = body["error"].as_str().unwrap();
+    assert!(
+        error.contains(
+            "account_type: invalid account type 'checking'; \
+             expected one of CHECKING, SAVINGS, CREDIT_CARD, INVESTMENT, CASH, LOAN, MORTGAGE"
+        ),
+        "unexpected error: {}",
+        error
+    );
+
+    let request = json!({
+        "name": "Test Account",
+        "account_type": "CHECKING",
+        "currency": "usd"
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 422);
+    let body: serde_json::Value = extract_json(response);
+    let error = body["error"].as_str().unwrap();
+    assert!(
+        error.contains(
+            "currency: invalid currency 'usd'; expected one of USD, EUR, GBP, INR, JPY, AUD, CAD"
+        ),
+        "unexpected error: {}",
+        error
+    );
+}
+
+/// Test that retrying an account creation with the same idempotency key does not duplicate it.
+///
+/// Verifies that:
+/// - A retry with the same `Idempotency-Key` returns the original account
+/// - Only one account and one opening balance transaction are created
+/// - Reusing the key for a different request is rejected with 409
+/// - A retry after the account was deleted still replays the stored response
+/// - A different key creates a new account
+#[tokio::test]
+async fn test_create_account_idempotency_key() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("idemuser_{}", timestamp)).await;
+
+    let request = json!({
+        "name": "Savings",
+        "account_type": "SAVINGS",
+        "currency": "USD",
+        "initial_balance": 250.0
+    });
+    let create = |key: &'static str| {
+        server
+            .post("/api/v1/accounts")
+            .add_header("Authorization", format!("Bearer {}", auth.token))
+            .add_header("Idempotency-Key", key)
+            .json(&request)
+    };
+
+    let response = create("create-savings-1").await;
+    assert_status(&response, 201);
+    let original: AccountResponse = extract_json(response);
+    assert_eq!(original.balance, 250.0);
+
+    // Retry, e.g. after a timeout
+    let response = create("create-savings-1").await;
+    assert_status(&response, 201);
+    let retried: AccountResponse = extract_json(response);
+    assert_eq!(retried.id, original.id);
+    assert_eq!(retried.balance, 250.0);
+
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.len(), 1);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}/transactions", original.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let transactions: Vec<serde_json::Value> = extract_json(response);
+    assert_eq!(
+        transactions.len(),
+        1,
+        "only one opening balance transaction"
+    );
+
+    let different = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "USD"
+    });
+    let response = server
+        .post("/api/v1/accounts")
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .add_header("Idempotency-Key", "create-savings-1")
+        .json(&different)
+        .await;
+    assert_status(&response, 409);
+
+    let create_checking = || {
+        server
+            .post("/api/v1/accounts")
+            .add_header("Authorization", format!("Bearer {}", auth.token))
+            .add_header("Idempotency-Key", "create-checking-1")
+            .json(&different)
+    };
+    let response = create_checking().await;
+    assert_status(&response, 201);
+    let checking: AccountResponse = extract_json(response);
+    let path = format!("/api/v1/accounts/{}", checking.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    let response = create_checking().await;
+    assert_status(&response, 201);
+    let replayed: AccountResponse = extract_json(response);
+    assert_eq!(replayed.id, checking.id);
+
+    // A new key is a new request
+    let response = create("create-savings-2").await;
+    assert_status(&response, 201);
+    let other: AccountResponse = extract_json(response);
+    assert_ne!(other.id, original.id);
+}
+
 /// Test that creating account without authentication fails.
 ///
 /// Verifies that:
@@ -724,6 +869,46 @@ async fn test_update_account_partial() {
     assert_eq!(updated_account.notes, Some("Original notes".to_string()));
 }
 
+/// Test that an explicit `null` clears the notes while omitting keeps them.
+///
+/// Verifies that:
+/// - Omitted notes are left unchanged
+/// - `null` clears the notes
+/// - A value sets them again
+#[tokio::test]
+async fn test_update_account_null_clears_notes() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("accountnull_{}", timestamp)).await;
+
+    let create_request = json!({
+        "name": "Savings",
+        "account_type": "SAVINGS",
+        "currency": "EUR",
+        "notes": "Original notes"
+    });
+    let create_response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &create_request).await;
+    assert_status(&create_response, 201);
+    let account: AccountResponse = extract_json(create_response);
+    let path = format!("/api/v1/accounts/{}", account.id);
+
+    let cases = [
+        (json!({ "name": "Renamed" }), Some("Original notes")),
+        (json!({ "notes": null }), None),
+        (json!({ "notes": "New notes" }), Some("New notes")),
+    ];
+    for (update_request, expected_notes) in cases {
+        let update_response = put_authenticated(&server, &path, &auth.token, &update_request).await;
+        assert_status(&update_response, 200);
+
+        let updated: AccountResponse = extract_json(update_response);
+        assert_eq!(updated.name, "Renamed");
+        assert_eq!(updated.notes.as_deref(), expected_notes);
+    }
+}
+
 /// Test that updating a non-existent account fails.
 ///
 /// Verifies that:
@@ -896,6 +1081,43 @@ async fn test_update_account_unauthorized() {
     assert_status(&response, 401);
 }
 
+/// Test that an account update based on an outdated version is rejected.
+///
+/// Verifies that:
+/// - A stale `If-Match` version returns 409 Conflict with the current account
+/// - The account is left unchanged
+#[tokio::test]
+async fn test_update_account_version_conflict() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("acct_version_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    assert_eq!(account.version, 1);
+    let path = format!("/api/v1/accounts/{}", account.id);
+
+    let response =
+        put_authenticated(&server, &path, &auth.token, &json!({"name": "Everyday"})).await;
+    assert_status(&response, 200);
+    let updated: AccountResponse = extract_json(response);
+    assert_eq!(updated.version, 2);
+
+    let response = server
+        .put(&path)
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .add_header("If-Match", "\"1\"")
+        .json(&json!({"name": "Stale"}))
+        .await;
+    assert_status(&response, 409);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["current"]["version"], 2);
+    assert_eq!(body["current"]["name"], "Everyday");
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    let current: AccountResponse = extract_json(response);
+    assert_eq!(current.name, "Everyday");
+    assert_eq!(current.version, 2);
+}
+
 // ============================================================================
 // Delete Account Tests
 // ============================================================================
@@ -0,0 +1,205 @@
+//! Integration tests for the aggregator import endpoint.
+//!
+//! This module tests POST /api/v1/import/aggregator including:
+//! - Account matching by explicit mapping, by name and by creating accounts
+//! - Idempotent re-imports keyed by the external transaction ID
+//! - Currency validation against the mapped account
+
+use crate::common::*;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    AccountMatch, AccountResponse, AggregatorImportResponse, TransactionResponse,
+};
+use serde_json::{Value, json};
+
+fn aggregator_export(transactions: Value) -> Value {
+    json!({
+        "accounts": [{
+            "account_id": "ext-checking",
+            "name": "Everyday Checking",
+            "type": "depository",
+            "subtype": "checking",
+            "balances": { "iso_currency_code": "USD" }
+        }],
+        "transactions": transactions
+    })
+}
+
+fn aggregator_transaction(id: &str, amount: f64, name: &str) -> Value {
+    json!({
+        "transaction_id": id,
+        "account_id": "ext-checking",
+        "amount": amount,
+        "iso_currency_code": "USD",
+        "date": "2026-03-14",
+        "name": name,
+        "pending": false
+    })
+}
+
+/// Test that re-importing the same export does not duplicate transactions.
+///
+/// Verifies that:
+/// - The external account is matched to the local account by name
+/// - Aggregator amounts are negated (positive means money out)
+/// - Pending transactions are skipped
+/// - A second import skips unchanged transactions and updates changed ones
+#[tokio::test]
+async fn test_aggregator_import_is_idempotent() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("aggregator_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Everyday Checking").await;
+
+    let export = aggregator_export(json!([
+        aggregator_transaction("tx-coffee", 4.5, "Coffee Shop"),
+        aggregator_transaction("tx-salary", -2500.0, "Payroll"),
+        {
+            "transaction_id": "tx-pending",
+            "account_id": "ext-checking",
+            "amount": 12.0,
+            "date": "2026-03-15",
+            "name": "Pending Purchase",
+            "pending": true
+        }
+    ]));
+
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    assert_status(&response, 200);
+    let result: AggregatorImportResponse = extract_json(response);
+    assert!(result.success);
+    assert_eq!(result.data.created, 2);
+    assert_eq!(result.data.skipped, 1);
+    assert_eq!(result.data.accounts[0].account_id, Some(account.id));
+    assert_eq!(result.data.accounts[0].matched_by, Some(AccountMatch::Name));
+
+    // Importing the same export again changes nothing
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    let result: AggregatorImportResponse = extract_json(response);
+    assert_eq!(result.data.created, 0);
+    assert_eq!(result.data.updated, 0);
+    assert_eq!(result.data.skipped, 3);
+
+    // A corrected amount at the aggregator updates the existing transaction
+    let corrected = aggregator_export(json!([
+        aggregator_transaction("tx-coffee", 5.25, "Coffee Shop"),
+        aggregator_transaction("tx-salary", -2500.0, "Payroll"),
+    ]));
+    let response = post_authenticated(
+        &server,
+        "/api/v1/import/aggregator",
+        &auth.token,
+        &corrected,
+    )
+    .await;
+    let result: AggregatorImportResponse = extract_json(response);
+    assert_eq!(result.data.created, 0);
+    assert_eq!(result.data.updated, 1);
+    assert_eq!(result.data.skipped, 1);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions?account_id={}", account.id),
+        &auth.token,
+    )
+    .await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 2);
+    let coffee = transactions
+        .iter()
+        .find(|t| t.external_id.as_deref() == Some("tx-coffee"))
+        .expect("Imported transaction should keep its external ID");
+    assert_eq!(coffee.amount.to_string(), "-5.25");
+    assert_eq!(coffee.title, "Coffee Shop");
+}
+
+/// Test account mapping and currency validation.
+///
+/// Verifies that:
+/// - Mapping to an account the user does not own is rejected with 422
+/// - Transactions whose currency differs from the mapped account fail
+#[tokio::test]
+async fn test_aggregator_import_mapping_and_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("aggmap_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("aggmap_other_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Joint Account").await;
+    let foreign = create_test_account(&server, &other.token, "Other Account").await;
+
+    let mut export = aggregator_export(json!([
+        aggregator_transaction("tx-usd", 10.0, "Groceries"),
+        {
+            "transaction_id": "tx-eur",
+            "account_id": "ext-checking",
+            "amount": 20.0,
+            "iso_currency_code": "EUR",
+            "date": "2026-03-14",
+            "name": "Museum"
+        }
+    ]));
+
+    export["account_mapping"] = json!({ "ext-checking": foreign.id });
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    assert_status(&response, 422);
+
+    export["account_mapping"] = json!({ "ext-checking": account.id });
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    assert_status(&response, 200);
+    let result: AggregatorImportResponse = extract_json(response);
+    assert!(!result.success);
+    assert_eq!(
+        result.data.accounts[0].matched_by,
+        Some(AccountMatch::Mapping)
+    );
+    assert_eq!(result.data.created, 1);
+    assert_eq!(result.data.failed, 1);
+    let errors = result
+        .data
+        .errors
+        .expect("Failed transactions should be reported");
+    assert_eq!(errors[0].transaction_id, "tx-eur");
+    assert!(errors[0].error.contains("Currency mismatch"));
+}
+
+/// Test that unmatched accounts are only created when requested.
+///
+/// Verifies that:
+/// - Without create_missing_accounts, transactions of unmatched accounts fail
+/// - With create_missing_accounts, the account is created and used
+#[tokio::test]
+async fn test_aggregator_import_creates_missing_accounts() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("aggcreate_{}", timestamp)).await;
+
+    let mut export = aggregator_export(json!([aggregator_transaction("tx-rent", 1200.0, "Rent")]));
+
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    let result: AggregatorImportResponse = extract_json(response);
+    assert_eq!(result.data.failed, 1);
+    assert_eq!(result.data.accounts[0].account_id, None);
+
+    export["create_missing_accounts"] = json!(true);
+    let response =
+        post_authenticated(&server, "/api/v1/import/aggregator", &auth.token, &export).await;
+    assert_status(&response, 200);
+    let result: AggregatorImportResponse = extract_json(response);
+    assert!(result.success);
+    assert_eq!(result.data.created, 1);
+    assert_eq!(
+        result.data.accounts[0].matched_by,
+        Some(AccountMatch::Created)
+    );
+
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].name, "Everyday Checking");
+    assert_eq!(Some(accounts[0].id), result.data.accounts[0].account_id);
+}
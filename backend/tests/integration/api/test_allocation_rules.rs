@@ -0,0 +1,387 @@
+//! Integration tests for income allocation rules.
+//!
+//! This module tests the allocation rule endpoints and their effect on income:
+//! - GET /api/v1/allocation-rules - List allocation rules
+//! - POST /api/v1/allocation-rules - Create allocation rule
+//! - GET /api/v1/allocation-rules/:id - Get allocation rule
+//! - DELETE /api/v1/allocation-rules/:id - Delete allocation rule
+//! - POST /api/v1/transactions and POST /api/v1/transactions/:id/post - Income is
+//!   split across the destination accounts with transfer transactions
+//!
+//! Tests cover the rounding policy, validation, rule matching and authorization.
+
+use crate::common::*;
+use axum_test::TestServer;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    AccountResponse, AllocationRuleResponse, TransactionResponse, allocation_rule::allocate,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn get_account(server: &TestServer, token: &str, id: Uuid) -> AccountResponse {
+    let response = get_authenticated(server, &format!("/api/v1/accounts/{}", id), token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+async fn create_income(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    amount: &str,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Client invoice",
+        "amount": amount,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test that leftover cents go one at a time to destinations in ascending account ID order.
+#[test]
+fn test_allocate_assigns_leftover_cents_by_account_id() {
+    let mut accounts: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    accounts.sort();
+    let destinations = vec![
+        (accounts[2], decimal("33.33")),
+        (accounts[0], decimal("33.33")),
+        (accounts[1], decimal("33.34")),
+    ];
+
+    let shares = allocate(&decimal("100.01"), &destinations, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (accounts[0], decimal("33.34")),
+            (accounts[1], decimal("33.34")),
+            (accounts[2], decimal("33.33")),
+        ]
+    );
+
+    // Currencies without minor units allocate whole amounts
+    let shares = allocate(&decimal("1000"), &destinations, 0);
+    let total: BigDecimal = shares.iter().map(|(_, share)| share).sum();
+    assert_eq!(total, decimal("1000"));
+}
+
+/// Test creating a rule and allocating posted income.
+///
+/// Verifies that:
+/// - The rule is returned with its destinations
+/// - Income in the source account creates a transfer pair per destination
+/// - The shares add up to the income, leaving the source with nothing
+#[tokio::test]
+async fn test_allocate_income() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alloc_{}", timestamp)).await;
+
+    let income = create_test_account(&server, &auth.token, "Income").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let taxes = create_test_account(&server, &auth.token, "Taxes").await;
+
+    let request = json!({
+        "name": "Freelance split",
+        "source_account_id": income.id,
+        "destinations": [
+            {"account_id": savings.id, "percentage": 66.67},
+            {"account_id": taxes.id, "percentage": 33.33}
+        ]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let rule: AllocationRuleResponse = extract_json(response);
+    assert_eq!(rule.name, "Freelance split");
+    assert_eq!(rule.destinations.len(), 2);
+
+    let transaction = create_income(&server, &auth.token, income.id, "1000.00").await;
+    assert_eq!(transaction.allocations.len(), 4);
+
+    assert_eq!(
+        get_account(&server, &auth.token, income.id).await.balance,
+        0.0
+    );
+    assert_eq!(
+        get_account(&server, &auth.token, savings.id).await.balance,
+        666.7
+    );
+    assert_eq!(
+        get_account(&server, &auth.token, taxes.id).await.balance,
+        333.3
+    );
+
+    // Expenses are not allocated
+    let request = json!({
+        "account_id": income.id,
+        "title": "Software",
+        "amount": "-20.00",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let expense: TransactionResponse = extract_json(response);
+    assert!(expense.allocations.is_empty());
+
+    let response = get_authenticated(&server, "/api/v1/allocation-rules", &auth.token).await;
+    assert_status(&response, 200);
+    let rules: Vec<AllocationRuleResponse> = extract_json(response);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].id, rule.id);
+}
+
+/// Test that pending income is allocated once it is posted.
+#[tokio::test]
+async fn test_allocate_income_when_posted() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alloc_post_{}", timestamp)).await;
+
+    let income = create_test_account(&server, &auth.token, "Income").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "name": "Save everything",
+        "source_account_id": income.id,
+        "destinations": [{"account_id": savings.id, "percentage": 100}]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": income.id,
+        "title": "Pending invoice",
+        "amount": "250.00",
+        "date": Utc::now().to_rfc3339(),
+        "status": "pending"
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let pending: TransactionResponse = extract_json(response);
+    assert!(pending.allocations.is_empty());
+
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}/post", pending.id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+    let posted: TransactionResponse = extract_json(response);
+    assert_eq!(posted.allocations.len(), 2);
+    assert_eq!(
+        get_account(&server, &auth.token, savings.id).await.balance,
+        250.0
+    );
+}
+
+/// Test that income is not saved when its allocation transfers are rejected.
+///
+/// The source account is already overdrawn past its limit, so the outgoing
+/// transfers that allocate the income would leave it there; the income and the
+/// transfers are rejected together.
+#[tokio::test]
+async fn test_allocation_breaching_limit_rolls_back_income() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alloc_limit_{}", timestamp)).await;
+
+    let request = json!({
+        "name": "Overdrawn",
+        "account_type": "CHECKING",
+        "currency": "USD",
+        "initial_balance": -100.0,
+        "allow_overdraft": false,
+        "overdraft_limit": 0.0
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let income: AccountResponse = extract_json(response);
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "name": "Save everything",
+        "source_account_id": income.id,
+        "destinations": [{"account_id": savings.id, "percentage": 100}]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": income.id,
+        "title": "Client invoice",
+        "amount": "40.00",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    assert_eq!(
+        get_account(&server, &auth.token, income.id).await.balance,
+        -100.0
+    );
+    assert_eq!(
+        get_account(&server, &auth.token, savings.id).await.balance,
+        0.0
+    );
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions?account_id={}", income.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = extract_json(response);
+    assert!(
+        !body.to_string().contains("Client invoice"),
+        "rejected income must not be saved"
+    );
+}
+
+/// Test that a rule for the income's category wins over a rule for all income.
+#[tokio::test]
+async fn test_category_rule_takes_precedence() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alloc_cat_{}", timestamp)).await;
+
+    let income = create_test_account(&server, &auth.token, "Income").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let taxes = create_test_account(&server, &auth.token, "Taxes").await;
+    let salary = create_test_category(&server, &auth.token, "Salary").await;
+
+    for request in [
+        json!({
+            "name": "All income",
+            "source_account_id": income.id,
+            "destinations": [{"account_id": savings.id, "percentage": 100}]
+        }),
+        json!({
+            "name": "Salary",
+            "source_account_id": income.id,
+            "category_id": salary.id,
+            "destinations": [{"account_id": taxes.id, "percentage": 100}]
+        }),
+    ] {
+        let response =
+            post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let request = json!({
+        "account_id": income.id,
+        "category_id": salary.id,
+        "title": "Salary",
+        "amount": "100.00",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    assert_eq!(
+        get_account(&server, &auth.token, taxes.id).await.balance,
+        100.0
+    );
+    assert_eq!(
+        get_account(&server, &auth.token, savings.id).await.balance,
+        0.0
+    );
+}
+
+/// Test that invalid rules are rejected with 422.
+///
+/// Verifies that:
+/// - Percentages must add up to 100
+/// - Percentages may have at most two decimal places
+/// - The source account cannot be a destination
+/// - Destinations must share the source account's currency
+#[tokio::test]
+async fn test_create_allocation_rule_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alloc_invalid_{}", timestamp)).await;
+
+    let income = create_test_account(&server, &auth.token, "Income").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let request = json!({"name": "Euro", "account_type": "SAVINGS", "currency": "EUR"});
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let euro: AccountResponse = extract_json(response);
+
+    for destinations in [
+        json!([{"account_id": savings.id, "percentage": 90}]),
+        json!([{"account_id": savings.id, "percentage": 99.995}, {"account_id": euro.id, "percentage": 0.005}]),
+        json!([{"account_id": income.id, "percentage": 100}]),
+        json!([{"account_id": euro.id, "percentage": 100}]),
+        json!([]),
+    ] {
+        let request = json!({
+            "name": "Invalid",
+            "source_account_id": income.id,
+            "destinations": destinations
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/allocation-rules", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+}
+
+/// Test that rules of another user cannot be read or deleted, and deleting works for the owner.
+#[tokio::test]
+async fn test_allocation_rule_authorization_and_delete() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let owner = register_unique_test_user(&server, &format!("alloc_owner_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("alloc_other_{}", timestamp)).await;
+
+    let income = create_test_account(&server, &owner.token, "Income").await;
+    let savings = create_test_account(&server, &owner.token, "Savings").await;
+    let other_account = create_test_account(&server, &other.token, "Other").await;
+
+    // Destinations must belong to the user
+    let request = json!({
+        "name": "Foreign",
+        "source_account_id": income.id,
+        "destinations": [{"account_id": other_account.id, "percentage": 100}]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &owner.token, &request).await;
+    assert_status(&response, 403);
+
+    let request = json!({
+        "name": "Savings",
+        "source_account_id": income.id,
+        "destinations": [{"account_id": savings.id, "percentage": 100}]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/allocation-rules", &owner.token, &request).await;
+    assert_status(&response, 201);
+    let rule: AllocationRuleResponse = extract_json(response);
+    let path = format!("/api/v1/allocation-rules/{}", rule.id);
+
+    assert_status(&get_authenticated(&server, &path, &other.token).await, 403);
+    assert_status(
+        &delete_authenticated(&server, &path, &other.token).await,
+        403,
+    );
+
+    assert_status(&get_authenticated(&server, &path, &owner.token).await, 200);
+    assert_status(
+        &delete_authenticated(&server, &path, &owner.token).await,
+        204,
+    );
+    assert_status(&get_authenticated(&server, &path, &owner.token).await, 404);
+
+    // Income is no longer allocated
+    let transaction = create_income(&server, &owner.token, income.id, "50.00").await;
+    assert!(transaction.allocations.is_empty());
+}
@@ -0,0 +1,88 @@
+//! Integration tests for the OpenAPI documentation endpoints.
+//!
+//! This module tests:
+//! - OpenAPI specification (GET /api/docs/openapi.json)
+//! - Swagger UI (GET /api/docs)
+
+use crate::common::*;
+use serde_json::Value;
+
+/// Test that the OpenAPI specification is served without authentication.
+///
+/// Verifies that:
+/// - Status code is 200 OK
+/// - Response is an OpenAPI 3 document
+/// - Core endpoints are documented
+/// - The bearer security scheme is registered
+#[tokio::test]
+async fn test_openapi_spec_is_public() {
+    let server = create_test_server().await;
+
+    let response = server.get("/api/docs/openapi.json").await;
+
+    assert_status(&response, 200);
+    let spec: Value = extract_json(response);
+
+    assert!(
+        spec["openapi"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("3."),
+        "Spec should be an OpenAPI 3 document"
+    );
+    for path in [
+        "/api/v1/auth/login",
+        "/api/v1/accounts",
+        "/api/v1/transactions",
+        "/api/v1/transactions/{id}",
+        "/api/v1/budgets",
+        "/api/v1/people/{id}/debts",
+        "/api/v1/dashboard",
+    ] {
+        assert!(
+            spec["paths"].get(path).is_some(),
+            "Spec should document {}",
+            path
+        );
+    }
+    assert!(
+        spec["components"]["securitySchemes"]
+            .get("bearer_auth")
+            .is_some(),
+        "Spec should define the bearer_auth security scheme"
+    );
+}
+
+/// Test that protected endpoints declare the bearer security requirement.
+#[tokio::test]
+async fn test_openapi_spec_marks_protected_endpoints() {
+    let server = create_test_server().await;
+
+    let response = server.get("/api/docs/openapi.json").await;
+    let spec: Value = extract_json(response);
+
+    assert!(
+        spec["paths"]["/api/v1/accounts"]["get"]["security"].is_array(),
+        "Protected endpoint should require bearer auth"
+    );
+    assert!(
+        spec["paths"]["/api/v1/auth/login"]["post"]
+            .get("security")
+            .is_none(),
+        "Login should not require authentication"
+    );
+}
+
+/// Test that the Swagger UI is served without authentication.
+#[tokio::test]
+async fn test_swagger_ui_is_public() {
+    let server = create_test_server().await;
+
+    let response = server.get("/api/docs/").await;
+
+    assert_status(&response, 200);
+    assert!(
+        response.text().contains("swagger"),
+        "Response should contain the Swagger UI page"
+    );
+}
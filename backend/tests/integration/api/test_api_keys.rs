@@ -8,6 +8,8 @@
 //! - Revoke API key (DELETE /api/v1/api-keys/:id)
 //! - Authentication with API keys
 //! - Scope enforcement
+//! - API keys can't manage API keys
+//! - Per-key rate limits
 //!
 //! Tests cover both success and error cases with proper validation
 //! of status codes, response bodies, and error messages.
@@ -15,8 +17,8 @@
 use crate::common::*;
 use chrono::Utc;
 use master_of_coin_backend::models::{
-    ApiKeyScopes, CreateApiKeyRequest, CreateApiKeyResponse, ListApiKeysResponse, ScopePermission,
-    UpdateApiKeyRequest,
+    ApiKeyResponse, ApiKeyScopes, CreateApiKeyRequest, CreateApiKeyResponse, ListApiKeysResponse,
+    ScopePermission, UpdateApiKeyRequest,
 };
 use serde_json::json;
 
@@ -51,6 +53,7 @@ async fn test_create_api_key_success() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let response = server
@@ -90,6 +93,7 @@ async fn test_create_api_key_no_scopes() {
         name: "No Scopes Key".to_string(),
         scopes: ApiKeyScopes::default(), // Empty scopes
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let response = server
@@ -116,6 +120,7 @@ async fn test_create_api_key_no_auth() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let response = server.post("/api/v1/api-keys").json(&request).await;
@@ -154,6 +159,7 @@ async fn test_list_api_keys_success() {
                 people: vec![],
             },
             expires_in_days: Some(90),
+            requests_per_minute: None,
         };
 
         let response = server
@@ -207,6 +213,7 @@ async fn test_authenticate_with_api_key() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -255,6 +262,7 @@ async fn test_api_key_insufficient_permissions() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -283,6 +291,68 @@ async fn test_api_key_insufficient_permissions() {
     assert!(error_text.contains("Accounts"));
 }
 
+/// Test that API keys can't manage API keys, whatever their scopes.
+///
+/// Verifies that with an API key:
+/// - Listing, getting, creating and revoking keys return 403
+/// - Raising the key's own rate limit returns 403 and leaves it unchanged
+#[tokio::test]
+async fn test_api_key_cannot_manage_api_keys() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("keymanage_{}", timestamp)).await;
+
+    let all = vec![ScopePermission::Read, ScopePermission::Write];
+    let request = CreateApiKeyRequest {
+        name: "Full Access Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: all.clone(),
+            accounts: all.clone(),
+            budgets: all.clone(),
+            categories: all.clone(),
+            people: all,
+        },
+        expires_in_days: None,
+        requests_per_minute: Some(10),
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+    let path = format!("/api/v1/api-keys/{}", api_key.id);
+
+    let response = get_authenticated(&server, "/api/v1/api-keys", &api_key.key).await;
+    assert_status(&response, 403);
+    let response = get_authenticated(&server, &path, &api_key.key).await;
+    assert_status(&response, 403);
+
+    let unlimited = CreateApiKeyRequest {
+        name: "Unlimited Key".to_string(),
+        requests_per_minute: None,
+        ..request
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &api_key.key, &unlimited).await;
+    assert_status(&response, 403);
+
+    let response = server
+        .patch(&path)
+        .add_header("Authorization", format!("Bearer {}", api_key.key))
+        .json(&json!({ "requests_per_minute": 10000 }))
+        .await;
+    assert_status(&response, 403);
+
+    let response = delete_authenticated(&server, &path, &api_key.key).await;
+    assert_status(&response, 403);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let unchanged: ApiKeyResponse = extract_json(response);
+    assert_eq!(unchanged.requests_per_minute, Some(10));
+    let response = get_authenticated(&server, "/api/v1/api-keys", &auth.token).await;
+    let list: ListApiKeysResponse = extract_json(response);
+    assert_eq!(list.api_keys.len(), 1);
+}
+
 /// Test invalid API key format fails with 401.
 #[tokio::test]
 async fn test_invalid_api_key_format() {
@@ -334,6 +404,7 @@ async fn test_revoke_api_key() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -403,6 +474,7 @@ async fn test_update_api_key_name() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -421,6 +493,7 @@ async fn test_update_api_key_name() {
         name: Some("Updated Name".to_string()),
         expires_in_days: None,
         scopes: None,
+        requests_per_minute: None,
     };
 
     let update_response = server
@@ -473,6 +546,7 @@ async fn test_api_key_scopes() {
             people: vec![],
         },
         expires_in_days: None, // Never expires
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -520,6 +594,7 @@ async fn test_create_api_key_never_expires() {
             people: vec![],
         },
         expires_in_days: None, // Never expires
+        requests_per_minute: None,
     };
 
     let response = server
@@ -574,6 +649,7 @@ async fn test_api_key_ownership() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -625,6 +701,7 @@ async fn test_api_key_format() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let response = server
@@ -647,3 +724,75 @@ async fn test_api_key_format() {
     let key_part = &api_key_response.key[4..];
     assert!(key_part.chars().all(|c| c.is_ascii_alphanumeric()));
 }
+
+// ============================================================================
+// Rate Limit Tests
+// ============================================================================
+
+/// Test that a key's requests per minute are enforced.
+///
+/// Verifies that:
+/// - Each response carries the requests left in `X-RateLimit-Remaining`
+/// - Requests over the limit fail with 429 and a `Retry-After` header
+/// - JWT requests aren't rate limited
+#[tokio::test]
+async fn test_api_key_rate_limit() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("ratelimit_{}", timestamp)).await;
+
+    // One request a minute, so the key's bucket can't refill during the test
+    let request = CreateApiKeyRequest {
+        name: "Rate Limited Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: vec![ScopePermission::Read],
+            accounts: vec![],
+            budgets: vec![],
+            categories: vec![],
+            people: vec![],
+        },
+        expires_in_days: None,
+        requests_per_minute: Some(1),
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+    assert_eq!(api_key.requests_per_minute, Some(1));
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &api_key.key).await;
+    assert_status(&response, 200);
+    assert_eq!(response.header("X-RateLimit-Remaining"), "0");
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &api_key.key).await;
+    assert_status(&response, 429);
+    assert_eq!(response.header("X-RateLimit-Remaining"), "0");
+    let retry_after: u64 = response
+        .header("Retry-After")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&response, 200);
+    assert!(response.maybe_header("X-RateLimit-Remaining").is_none());
+}
+
+/// Test that a rate limit below one request per minute is rejected.
+#[tokio::test]
+async fn test_create_api_key_invalid_rate_limit() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("badlimit_{}", timestamp)).await;
+
+    let request = json!({
+        "name": "Bad Limit Key",
+        "scopes": { "transactions": ["read"] },
+        "requests_per_minute": 0,
+    });
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 422);
+}
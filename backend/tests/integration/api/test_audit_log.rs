@@ -0,0 +1,265 @@
+//! Integration tests for the audit log.
+//!
+//! This module tests:
+//! - GET /api/v1/audit - List the user's audit entries, newest first
+//! - Entries written on create, update and delete, with only changed fields
+//! - Reads and no-op updates writing no entries
+//! - Filtering by entity type and ID, and isolation between users
+//! - The hash chain linking each entry to the one before it
+//! - Entity types hidden from API keys without read access
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    ApiKeyScopes, AuditAction, AuditEntityType, AuditLogResponse, CreateApiKeyRequest,
+    CreateApiKeyResponse, ScopePermission, TransactionResponse,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn list_audit(server: &TestServer, token: &str, query: &str) -> Vec<AuditLogResponse> {
+    let response = get_authenticated(server, &format!("/api/v1/audit{}", query), token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+async fn entity_audit(server: &TestServer, token: &str, entity_id: Uuid) -> Vec<AuditLogResponse> {
+    list_audit(server, token, &format!("?entity_id={}", entity_id)).await
+}
+
+/// Creating, updating and deleting a person each write one entry, and the
+/// update lists only the fields that changed.
+#[tokio::test]
+async fn test_audit_log_records_person_changes() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_person_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth.token, "Alice").await;
+    let path = format!("/api/v1/people/{}", person.id);
+
+    let entries = entity_audit(&server, &auth.token, person.id).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditAction::Create);
+    assert_eq!(entries[0].entity_type, AuditEntityType::Person);
+    assert_eq!(entries[0].changes["name"], json!({"new": "Alice"}));
+
+    let request = json!({"name": "Alice", "email": "alice@example.com"});
+    let response = put_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 200);
+
+    let entries = entity_audit(&server, &auth.token, person.id).await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].action, AuditAction::Update);
+    assert_eq!(
+        entries[0].changes,
+        json!({"email": {"old": null, "new": "alice@example.com"}}),
+        "Only the changed field should be recorded"
+    );
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let entries = entity_audit(&server, &auth.token, person.id).await;
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].action, AuditAction::Delete);
+    assert_eq!(entries[0].changes["name"], json!({"old": "Alice"}));
+    assert_eq!(
+        entries[0].changes["email"],
+        json!({"old": "alice@example.com"})
+    );
+}
+
+/// Updates that change nothing and reads write no entries.
+#[tokio::test]
+async fn test_audit_log_skips_reads_and_noop_updates() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_noop_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth.token, "Bob").await;
+    let path = format!("/api/v1/people/{}", person.id);
+
+    let response = put_authenticated(&server, &path, &auth.token, &json!({"name": "Bob"})).await;
+    assert_status(&response, 200);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let response = get_authenticated(&server, "/api/v1/people", &auth.token).await;
+    assert_status(&response, 200);
+
+    let before = list_audit(&server, &auth.token, "").await;
+    let after = list_audit(&server, &auth.token, "").await;
+    assert_eq!(
+        before.len(),
+        after.len(),
+        "Reading the log should not write"
+    );
+
+    let entries = entity_audit(&server, &auth.token, person.id).await;
+    assert_eq!(entries.len(), 1, "Only the creation should be recorded");
+    assert_eq!(entries[0].action, AuditAction::Create);
+}
+
+/// Soft-deleting and restoring a transaction are recorded as deletion and
+/// creation.
+#[tokio::test]
+async fn test_audit_log_records_transaction_delete_and_restore() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_txn_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Audit Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Groceries",
+        "amount": "-25.00",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    let response = post_authenticated(
+        &server,
+        &format!("{}/restore", path),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let entries = entity_audit(&server, &auth.token, transaction.id).await;
+    let actions: Vec<AuditAction> = entries.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::Create,
+            AuditAction::Delete,
+            AuditAction::Create
+        ]
+    );
+    assert!(
+        entries
+            .iter()
+            .all(|e| e.entity_type == AuditEntityType::Transaction)
+    );
+    assert_eq!(entries[1].changes["title"], json!({"old": "Groceries"}));
+    assert!(
+        entries[1].changes.get("deleted_at").is_none(),
+        "A deletion should record the transaction as it was"
+    );
+}
+
+/// Entries can be filtered by entity type, and users only see their own.
+#[tokio::test]
+async fn test_audit_log_filters_and_isolation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_filter_{}", timestamp)).await;
+    let other =
+        register_unique_test_user(&server, &format!("audit_filter_other_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth.token, "Carol").await;
+    let category = create_test_category(&server, &auth.token, "Audit Category").await;
+
+    let people = list_audit(&server, &auth.token, "?entity_type=person").await;
+    assert!(!people.is_empty());
+    assert!(
+        people
+            .iter()
+            .all(|e| e.entity_type == AuditEntityType::Person)
+    );
+    assert!(people.iter().any(|e| e.entity_id == person.id));
+    assert!(people.iter().all(|e| e.entity_id != category.id));
+
+    let categories = list_audit(&server, &auth.token, "?entity_type=category").await;
+    assert!(categories.iter().any(|e| e.entity_id == category.id));
+
+    assert!(
+        entity_audit(&server, &other.token, person.id)
+            .await
+            .is_empty()
+    );
+    let others = list_audit(&server, &other.token, "").await;
+    assert!(
+        others
+            .iter()
+            .all(|e| e.entity_id != person.id && e.entity_id != category.id)
+    );
+
+    let response = get_authenticated(&server, "/api/v1/audit?entity_type=user", &auth.token).await;
+    assert_status(&response, 400);
+}
+
+/// Each entry's `previous_hash` is the hash of the user's entry before it.
+#[tokio::test]
+async fn test_audit_log_hash_chain() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_chain_{}", timestamp)).await;
+    create_test_person(&server, &auth.token, "Dave").await;
+    create_test_person(&server, &auth.token, "Erin").await;
+
+    let entries = list_audit(&server, &auth.token, "").await;
+    assert!(entries.len() >= 2);
+    for pair in entries.windows(2) {
+        assert!(
+            pair[0].sequence > pair[1].sequence,
+            "Newest entries come first"
+        );
+        assert_eq!(
+            pair[0].previous_hash.as_deref(),
+            Some(pair[1].hash.as_str())
+        );
+    }
+    let first = entries.last().unwrap();
+    assert!(
+        first.previous_hash.is_none(),
+        "The first entry starts the chain"
+    );
+    assert!(entries.iter().all(|e| e.hash.len() == 64));
+}
+
+/// API keys only see entries about entity types they can read.
+#[tokio::test]
+async fn test_audit_log_respects_api_key_scopes() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("audit_scopes_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth.token, "Frank").await;
+    let category = create_test_category(&server, &auth.token, "Scoped Category").await;
+
+    let request = CreateApiKeyRequest {
+        name: "Audit Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: vec![],
+            accounts: vec![],
+            budgets: vec![],
+            categories: vec![ScopePermission::Read],
+            people: vec![],
+        },
+        expires_in_days: None,
+        requests_per_minute: None,
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+
+    let entries = list_audit(&server, &api_key.key, "").await;
+    assert!(entries.iter().any(|e| e.entity_id == category.id));
+    assert!(
+        entries
+            .iter()
+            .all(|e| e.entity_type == AuditEntityType::Category)
+    );
+    assert!(
+        entity_audit(&server, &api_key.key, person.id)
+            .await
+            .is_empty()
+    );
+
+    let response =
+        get_authenticated(&server, "/api/v1/audit?entity_type=person", &api_key.key).await;
+    assert_status(&response, 403);
+}
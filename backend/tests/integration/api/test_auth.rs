@@ -1,20 +1,35 @@
 //! Integration tests for authentication API endpoints.
 //!
 //! This module tests the authentication endpoints including:
-//! - User registration (POST /api/v1/auth/register)
+//! - User registration (POST /api/v1/auth/register), including starter data
 //! - User login (POST /api/v1/auth/login)
 //! - Get current user (GET /api/v1/auth/me)
+//! - Logout (POST /api/v1/auth/logout)
+//! - Sign-in activity (GET /api/v1/auth/events)
+//! - Refresh token rotation (POST /api/v1/auth/refresh)
+//! - Password reset (POST /api/v1/auth/forgot-password, POST /api/v1/auth/reset-password)
+//! - Password change (POST /api/v1/auth/change-password)
 //!
 //! Tests cover both success and error cases with proper validation
 //! of status codes, response bodies, and error messages.
 
 use crate::common::*;
+use async_trait::async_trait;
+use axum_test::TestServer;
 use chrono::Utc;
+use http::HeaderValue;
 use master_of_coin_backend::{
     auth::jwt::decode_token,
-    models::{AuthResponse, CreateUserRequest, LoginRequest, UserResponse},
+    config::OnboardingConfig,
+    models::{
+        AccountResponse, AuthEventResponse, AuthEventType, AuthResponse, CategoryResponse,
+        CreateUserRequest, LoginRequest, UserResponse, category::default_set,
+    },
+    types::{AccountType, CurrencyCode},
 };
+use master_of_coin_backend::{errors::ApiError, services::email_service::EmailSender};
 use serde_json::json;
+use std::sync::{Arc, Mutex};
 
 // ============================================================================
 // Registration Tests
@@ -38,6 +53,7 @@ async fn test_register_success() {
         email: format!("test_{}@example.com", timestamp),
         password: "SecurePass123!".to_string(),
         name: "Test User".to_string(),
+        preferences: None,
     };
 
     let response = server.post("/api/v1/auth/register").json(&request).await;
@@ -78,6 +94,7 @@ async fn test_register_duplicate_email() {
         email: format!("duplicate_{}@example.com", timestamp),
         password: "SecurePass123!".to_string(),
         name: "Test User".to_string(),
+        preferences: None,
     };
 
     // First registration should succeed
@@ -90,6 +107,7 @@ async fn test_register_duplicate_email() {
         email: request.email.clone(), // Same email
         password: "SecurePass123!".to_string(),
         name: "Test User 2".to_string(),
+        preferences: None,
     };
 
     let response2 = server.post("/api/v1/auth/register").json(&request2).await;
@@ -130,6 +148,7 @@ async fn test_register_invalid_email() {
             email: invalid_email.to_string(),
             password: "SecurePass123!".to_string(),
             name: "Test User".to_string(),
+            preferences: None,
         };
 
         let response = server.post("/api/v1/auth/register").json(&request).await;
@@ -164,6 +183,7 @@ async fn test_register_weak_password() {
             email: format!("test_{}_{}@example.com", timestamp, weak_password.len()),
             password: weak_password.to_string(),
             name: "Test User".to_string(),
+            preferences: None,
         };
 
         let response = server.post("/api/v1/auth/register").json(&request).await;
@@ -241,6 +261,188 @@ async fn test_register_missing_fields() {
     assert_status(&response, 422);
 }
 
+/// Test registration with starter preferences.
+///
+/// Verifies that:
+/// - A cash account in the chosen currency is created
+/// - The empty cash account accepts a first expense
+/// - The default categories are created
+#[tokio::test]
+async fn test_register_with_starter_data() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let request = json!({
+        "username": format!("starter_{}", timestamp),
+        "email": format!("starter_{}@example.com", timestamp),
+        "password": "SecurePass123!",
+        "name": "Starter User",
+        "preferences": {
+            "default_currency": "USD",
+            "create_cash_account": true,
+            "create_default_categories": true
+        }
+    });
+    let response = server.post("/api/v1/auth/register").json(&request).await;
+    assert_status(&response, 201);
+    let auth: AuthResponse = extract_json(response);
+
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].name, "Cash");
+    assert_eq!(accounts[0].account_type, AccountType::Cash);
+    assert_eq!(accounts[0].currency, CurrencyCode::Usd);
+    assert!(accounts[0].allow_overdraft);
+
+    let request = json!({
+        "account_id": accounts[0].id,
+        "title": "Coffee",
+        "amount": "-3.50",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let response = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
+    let categories: Vec<CategoryResponse> = extract_json(response);
+    assert_eq!(categories.len(), default_set(auth.user.id).len());
+    assert!(categories.iter().any(|c| c.name == "Groceries"));
+}
+
+/// Test that omitted preferences fall back to the onboarding configuration
+/// and that requests can opt out of configured starter data.
+#[tokio::test]
+async fn test_register_starter_data_defaults() {
+    let server = create_test_server_with_config(|config| {
+        config.onboarding.create_cash_account = true;
+        config.onboarding.default_currency = CurrencyCode::Gbp;
+        config.onboarding.cash_account_name = "Wallet".to_string();
+    })
+    .await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("onboard_{}", timestamp)).await;
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].name, "Wallet");
+    assert_eq!(accounts[0].currency, CurrencyCode::Gbp);
+
+    let response = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
+    let categories: Vec<CategoryResponse> = extract_json(response);
+    assert!(categories.is_empty());
+
+    let request = json!({
+        "username": format!("optout_{}", timestamp),
+        "email": format!("optout_{}@example.com", timestamp),
+        "password": "SecurePass123!",
+        "name": "Opt Out User",
+        "preferences": { "create_cash_account": false }
+    });
+    let response = server.post("/api/v1/auth/register").json(&request).await;
+    assert_status(&response, 201);
+    let auth: AuthResponse = extract_json(response);
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert!(accounts.is_empty());
+}
+
+/// Test that new users get the default categories unless they skip them.
+///
+/// Verifies that:
+/// - With the default onboarding configuration, the default set is seeded
+/// - The seeded categories belong to the new user only
+/// - `?skip_defaults=true` registers a user without any starter data
+#[tokio::test]
+async fn test_register_seeds_default_categories() {
+    let server = create_test_server_with_config(|config| {
+        config.onboarding = OnboardingConfig {
+            create_cash_account: true,
+            ..OnboardingConfig::default()
+        };
+    })
+    .await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("seeded_{}", timestamp)).await;
+    let response = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
+    assert_status(&response, 200);
+    let categories: Vec<CategoryResponse> = extract_json(response);
+    let mut names: Vec<String> = categories.iter().map(|c| c.name.clone()).collect();
+    let mut expected: Vec<String> = default_set(auth.user.id)
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    names.sort();
+    expected.sort();
+    assert_eq!(names, expected);
+    assert!(
+        categories
+            .iter()
+            .all(|c| c.icon.is_some() && c.color.is_some())
+    );
+
+    let request = json!({
+        "username": format!("cleanslate_{}", timestamp),
+        "email": format!("cleanslate_{}@example.com", timestamp),
+        "password": "SecurePass123!",
+        "name": "Clean Slate User",
+        "preferences": { "create_default_categories": true }
+    });
+    let response = server
+        .post("/api/v1/auth/register?skip_defaults=true")
+        .json(&request)
+        .await;
+    assert_status(&response, 201);
+    let clean: AuthResponse = extract_json(response);
+
+    let response = get_authenticated(&server, "/api/v1/categories", &clean.token).await;
+    let categories: Vec<CategoryResponse> = extract_json(response);
+    assert!(categories.is_empty(), "Defaults should be skipped");
+    let response = get_authenticated(&server, "/api/v1/accounts", &clean.token).await;
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert!(accounts.is_empty(), "Starter account should be skipped");
+
+    // The first user's categories are theirs alone
+    let response = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
+    let categories: Vec<CategoryResponse> = extract_json(response);
+    for category in categories {
+        let path = format!("/api/v1/categories/{}", category.id);
+        let response = get_authenticated(&server, &path, &clean.token).await;
+        assert_status(&response, 403);
+    }
+}
+
+/// Test that a failure to seed starter data rolls back the registration.
+///
+/// Verifies that:
+/// - Registration fails when the cash account cannot be created
+/// - No user is left behind, so the same username and email can register again
+#[tokio::test]
+async fn test_register_starter_data_failure_rolls_back() {
+    // Longer than the account name column allows
+    let server = create_test_server_with_config(|config| {
+        config.onboarding.cash_account_name = "x".repeat(300);
+    })
+    .await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let mut request = json!({
+        "username": format!("rollback_{}", timestamp),
+        "email": format!("rollback_{}@example.com", timestamp),
+        "password": "SecurePass123!",
+        "name": "Rollback User",
+        "preferences": { "create_cash_account": true }
+    });
+    let response = server.post("/api/v1/auth/register").json(&request).await;
+    assert_status(&response, 500);
+
+    request["preferences"]["create_cash_account"] = json!(false);
+    let response = server.post("/api/v1/auth/register").json(&request).await;
+    assert_status(&response, 201);
+}
+
 // ============================================================================
 // Login Tests
 // ============================================================================
@@ -266,6 +468,7 @@ async fn test_login_success() {
         email: email.clone(),
         password: password.to_string(),
         name: "Login Test User".to_string(),
+        preferences: None,
     };
 
     let register_response = server
@@ -278,6 +481,7 @@ async fn test_login_success() {
     let login_request = LoginRequest {
         email: email.clone(),
         password: password.to_string(),
+        two_factor_code: None,
     };
 
     let login_response = server.post("/api/v1/auth/login").json(&login_request).await;
@@ -312,6 +516,7 @@ async fn test_login_invalid_email() {
     let login_request = LoginRequest {
         email: format!("nonexistent_{}@example.com", timestamp),
         password: "SomePassword123!".to_string(),
+        two_factor_code: None,
     };
 
     let response = server.post("/api/v1/auth/login").json(&login_request).await;
@@ -344,6 +549,7 @@ async fn test_login_wrong_password() {
         email: email.clone(),
         password: correct_password.to_string(),
         name: "Wrong Pass Test User".to_string(),
+        preferences: None,
     };
 
     let register_response = server
@@ -356,6 +562,7 @@ async fn test_login_wrong_password() {
     let login_request = LoginRequest {
         email: email.clone(),
         password: "WrongPassword123!".to_string(),
+        two_factor_code: None,
     };
 
     let response = server.post("/api/v1/auth/login").json(&login_request).await;
@@ -508,6 +715,7 @@ async fn test_get_me_expired_token() {
     let expired_jwt_config = master_of_coin_backend::config::JwtConfig {
         secret: "test_secret_key_at_least_32_characters_long_for_testing".to_string(),
         expiration_hours: -1, // Negative hours means already expired
+        refresh_expiration_days: 30,
     };
 
     // Generate an expired token
@@ -523,6 +731,9 @@ async fn test_get_me_expired_token() {
         name: auth.user.name.clone(),
         created_at: auth.user.created_at,
         updated_at: Utc::now(),
+        totp_secret_encrypted: None,
+        two_factor_enabled: false,
+        totp_last_step: None,
     };
 
     let expired_token =
@@ -533,6 +744,608 @@ async fn test_get_me_expired_token() {
     assert_status(&response, 401);
 }
 
+// ============================================================================
+// Auth Event Tests
+// ============================================================================
+
+/// Test that the auth lifecycle is recorded in the user's sign-in activity.
+///
+/// Verifies that:
+/// - Registration, failed login, login and logout are all recorded
+/// - Events are returned newest first
+/// - The user agent is captured
+#[tokio::test]
+async fn test_auth_events_record_lifecycle() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let email = format!("events_test_{}@example.com", timestamp);
+    let password = "SecurePass123!";
+
+    let register_request = CreateUserRequest {
+        username: format!("eventsuser_{}", timestamp),
+        email: email.clone(),
+        password: password.to_string(),
+        name: "Events Test User".to_string(),
+        preferences: None,
+    };
+    let response = server
+        .post("/api/v1/auth/register")
+        .json(&register_request)
+        .await;
+    assert_status(&response, 201);
+
+    let wrong_login = LoginRequest {
+        email: email.clone(),
+        password: "WrongPassword123!".to_string(),
+        two_factor_code: None,
+    };
+    let response = server.post("/api/v1/auth/login").json(&wrong_login).await;
+    assert_status(&response, 401);
+
+    let login_request = LoginRequest {
+        email: email.clone(),
+        password: password.to_string(),
+        two_factor_code: None,
+    };
+    let response = server
+        .post("/api/v1/auth/login")
+        .add_header(
+            http::header::USER_AGENT,
+            HeaderValue::from_static("events-test-agent/1.0"),
+        )
+        .json(&login_request)
+        .await;
+    assert_status(&response, 200);
+    let auth: AuthResponse = extract_json(response);
+
+    let response = post_authenticated(&server, "/api/v1/auth/logout", &auth.token, &()).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, "/api/v1/auth/events", &auth.token).await;
+    assert_status(&response, 200);
+    let events: Vec<AuthEventResponse> = extract_json(response);
+
+    let types: Vec<AuthEventType> = events.iter().map(|e| e.event_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            AuthEventType::Logout,
+            AuthEventType::Login,
+            AuthEventType::LoginFailed,
+            AuthEventType::Register,
+        ]
+    );
+    assert_eq!(
+        events[1].user_agent.as_deref(),
+        Some("events-test-agent/1.0")
+    );
+}
+
+/// Register a user and return the client IP recorded for their registration
+async fn recorded_registration_ip(server: &TestServer, username: &str) -> Option<String> {
+    let request = json!({
+        "username": username,
+        "email": format!("{}@example.com", username),
+        "password": "SecurePass123!",
+        "name": "Client IP User"
+    });
+    let response = server
+        .post("/api/v1/auth/register")
+        .add_header(
+            http::HeaderName::from_static("x-forwarded-for"),
+            HeaderValue::from_static("198.51.100.23, 10.0.0.1"),
+        )
+        .json(&request)
+        .await;
+    assert_status(&response, 201);
+    let auth: AuthResponse = extract_json(response);
+
+    let response = get_authenticated(server, "/api/v1/auth/events", &auth.token).await;
+    assert_status(&response, 200);
+    let events: Vec<AuthEventResponse> = extract_json(response);
+    events[0].ip_address.clone()
+}
+
+/// Test which client IP is recorded for auth events.
+///
+/// Verifies that:
+/// - Without trusted proxies, the peer address is recorded and
+///   `X-Forwarded-For` is ignored
+/// - From a trusted proxy, the rightmost untrusted `X-Forwarded-For` entry is
+///   recorded
+#[tokio::test]
+async fn test_auth_events_record_client_ip_from_trusted_proxies_only() {
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let server = create_test_server_with_connect_info(|_| {}).await;
+    let ip = recorded_registration_ip(&server, &format!("peer_ip_{}", timestamp)).await;
+    assert_eq!(ip.as_deref(), Some("127.0.0.1"));
+
+    let server = create_test_server_with_connect_info(|config| {
+        config.server.trusted_proxies =
+            vec!["127.0.0.1".parse().unwrap(), "10.0.0.1".parse().unwrap()];
+    })
+    .await;
+    let ip = recorded_registration_ip(&server, &format!("proxied_ip_{}", timestamp)).await;
+    assert_eq!(ip.as_deref(), Some("198.51.100.23"));
+}
+
+/// Test that users only see their own auth events and the limit is applied.
+#[tokio::test]
+async fn test_auth_events_scoped_to_user_with_limit() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let user1 = register_unique_test_user(&server, &format!("events1_{}", timestamp)).await;
+    let user2 = register_unique_test_user(&server, &format!("events2_{}", timestamp)).await;
+
+    let response = get_authenticated(&server, "/api/v1/auth/events", &user1.token).await;
+    assert_status(&response, 200);
+    let events: Vec<AuthEventResponse> = extract_json(response);
+    assert_eq!(
+        events.len(),
+        1,
+        "Only user1's registration should be listed"
+    );
+
+    let response = post_authenticated(&server, "/api/v1/auth/logout", &user2.token, &()).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, "/api/v1/auth/events?limit=1", &user2.token).await;
+    assert_status(&response, 200);
+    let events: Vec<AuthEventResponse> = extract_json(response);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, AuthEventType::Logout);
+
+    let response = get_authenticated(&server, "/api/v1/auth/events?limit=0", &user2.token).await;
+    assert_status(&response, 422);
+}
+
+/// Test that auth events require authentication.
+#[tokio::test]
+async fn test_auth_events_no_token() {
+    let server = create_test_server().await;
+
+    let response = get_unauthenticated(&server, "/api/v1/auth/events").await;
+    assert_status(&response, 401);
+}
+
+// ============================================================================
+// Refresh Token Tests
+// ============================================================================
+
+/// Test that a refresh token is exchanged for new tokens and rotated.
+///
+/// Verifies that:
+/// - Login returns a refresh token
+/// - Refreshing returns 200 with a working access token and a new refresh token
+/// - The new refresh token can be used in turn
+/// - The refresh is recorded as a `token_refresh` auth event
+#[tokio::test]
+async fn test_refresh_token_rotation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("refresh_{}", timestamp)).await;
+    assert!(!auth.refresh_token.is_empty());
+
+    let request = json!({ "refresh_token": auth.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+    let refreshed: AuthResponse = extract_json(response);
+    assert_eq!(refreshed.user.id, auth.user.id);
+    assert_ne!(refreshed.refresh_token, auth.refresh_token);
+
+    let me_response = get_authenticated(&server, "/api/v1/auth/me", &refreshed.token).await;
+    assert_status(&me_response, 200);
+
+    let request = json!({ "refresh_token": refreshed.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+
+    let response = get_authenticated(&server, "/api/v1/auth/events", &refreshed.token).await;
+    let events: Vec<AuthEventResponse> = extract_json(response);
+    let refreshes = events
+        .iter()
+        .filter(|event| event.event_type == AuthEventType::TokenRefresh)
+        .count();
+    assert_eq!(refreshes, 2);
+}
+
+/// Test that reusing a rotated refresh token revokes its whole family.
+///
+/// Verifies that:
+/// - Reusing a rotated token returns 401
+/// - Its replacement is revoked too and returns 401
+/// - Tokens from another login keep working
+#[tokio::test]
+async fn test_refresh_token_reuse_revokes_family() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("refresh_reuse_{}@example.com", timestamp);
+    let auth = register_test_user(
+        &server,
+        &format!("refresh_reuse_{}", timestamp),
+        &email,
+        "SecurePass123!",
+        "Refresh Reuse User",
+    )
+    .await;
+    let other_login = login_test_user(&server, &email, "SecurePass123!").await;
+
+    let request = json!({ "refresh_token": auth.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+    let refreshed: AuthResponse = extract_json(response);
+
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 401);
+
+    let request = json!({ "refresh_token": refreshed.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 401);
+
+    let request = json!({ "refresh_token": other_login.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+}
+
+/// Test that logging out revokes the session's refresh token.
+///
+/// Verifies that:
+/// - Refreshing with the logged out session's refresh token returns 401
+/// - Its refreshed tokens are revoked too
+/// - Another session of the same user keeps working
+#[tokio::test]
+async fn test_logout_revokes_refresh_token() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("logout_refresh_{}@example.com", timestamp);
+    let auth = register_test_user(
+        &server,
+        &format!("logout_refresh_{}", timestamp),
+        &email,
+        "SecurePass123!",
+        "Logout Refresh User",
+    )
+    .await;
+    let other_login = login_test_user(&server, &email, "SecurePass123!").await;
+
+    let request = json!({ "refresh_token": auth.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+    let refreshed: AuthResponse = extract_json(response);
+
+    let response = post_authenticated(&server, "/api/v1/auth/logout", &refreshed.token, &()).await;
+    assert_status(&response, 204);
+
+    let request = json!({ "refresh_token": refreshed.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 401);
+
+    let request = json!({ "refresh_token": other_login.refresh_token });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 200);
+}
+
+/// Test that an unknown refresh token fails with 401 Unauthorized.
+#[tokio::test]
+async fn test_refresh_token_unknown() {
+    let server = create_test_server().await;
+
+    let request = json!({ "refresh_token": "not-a-real-refresh-token" });
+    let response = server.post("/api/v1/auth/refresh").json(&request).await;
+    assert_status(&response, 401);
+}
+
+// ============================================================================
+// Password Reset Tests
+// ============================================================================
+
+/// Email sender that records password reset tokens instead of sending them
+#[derive(Default)]
+struct RecordingEmailSender {
+    resets: Mutex<Vec<(String, String)>>,
+}
+
+impl RecordingEmailSender {
+    /// The token of the last reset email sent to `email`
+    fn last_token_for(&self, email: &str) -> Option<String> {
+        self.resets
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(to, _)| to == email)
+            .map(|(_, token)| token.clone())
+    }
+}
+
+#[async_trait]
+impl EmailSender for RecordingEmailSender {
+    async fn send_password_reset(&self, email: &str, token: &str) -> Result<(), ApiError> {
+        self.resets
+            .lock()
+            .unwrap()
+            .push((email.to_string(), token.to_string()));
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        _email: &str,
+        _subject: &str,
+        _body: &str,
+    ) -> Result<(), ApiError> {
+        Ok(())
+    }
+}
+
+/// Test the password reset flow end to end.
+///
+/// Verifies that:
+/// - Forgot password returns 200 and emails a token
+/// - The token sets a new password, returning 204
+/// - The new password logs in and the old one doesn't
+/// - Refresh tokens issued before the reset are revoked
+/// - The token can't be used twice
+#[tokio::test]
+async fn test_password_reset_flow() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("reset_{}@example.com", timestamp);
+    let auth = register_test_user(
+        &server,
+        &format!("reset_{}", timestamp),
+        &email,
+        "OldPassword123!",
+        "Reset User",
+    )
+    .await;
+
+    let response = server
+        .post("/api/v1/auth/forgot-password")
+        .json(&json!({ "email": email }))
+        .await;
+    assert_status(&response, 200);
+    let token = sender
+        .last_token_for(&email)
+        .expect("Reset email should be sent");
+
+    let request = json!({ "token": token, "new_password": "NewPassword456!" });
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&request)
+        .await;
+    assert_status(&response, 204);
+
+    login_test_user(&server, &email, "NewPassword456!").await;
+    let old_login = LoginRequest {
+        email: email.clone(),
+        password: "OldPassword123!".to_string(),
+        two_factor_code: None,
+    };
+    let response = server.post("/api/v1/auth/login").json(&old_login).await;
+    assert_status(&response, 401);
+
+    let response = server
+        .post("/api/v1/auth/refresh")
+        .json(&json!({ "refresh_token": auth.refresh_token }))
+        .await;
+    assert_status(&response, 401);
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&request)
+        .await;
+    assert_status(&response, 400);
+}
+
+/// Test that a reset uses up every outstanding reset token of the user.
+///
+/// Verifies that:
+/// - An earlier reset token fails with 400 once a later one was used
+#[tokio::test]
+async fn test_password_reset_invalidates_other_tokens() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("reset_others_{}", timestamp)).await;
+    let email = auth.user.email;
+
+    let mut tokens = Vec::new();
+    for _ in 0..2 {
+        let response = server
+            .post("/api/v1/auth/forgot-password")
+            .json(&json!({ "email": email }))
+            .await;
+        assert_status(&response, 200);
+        tokens.push(sender.last_token_for(&email).unwrap());
+    }
+    assert_ne!(tokens[0], tokens[1]);
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&json!({ "token": tokens[1], "new_password": "NewPassword456!" }))
+        .await;
+    assert_status(&response, 204);
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&json!({ "token": tokens[0], "new_password": "OtherPassword789!" }))
+        .await;
+    assert_status(&response, 400);
+
+    login_test_user(&server, &email, "NewPassword456!").await;
+}
+
+/// Test that forgot password doesn't reveal whether an email is registered.
+#[tokio::test]
+async fn test_forgot_password_unknown_email() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("nobody_{}@example.com", timestamp);
+    let response = server
+        .post("/api/v1/auth/forgot-password")
+        .json(&json!({ "email": email }))
+        .await;
+    assert_status(&response, 200);
+    assert!(sender.last_token_for(&email).is_none());
+}
+
+/// Test that a reset is rejected for a weak password or an unknown token.
+///
+/// Verifies that:
+/// - A password shorter than registration allows fails with 422 and keeps the token usable
+/// - An unknown token fails with 400
+#[tokio::test]
+async fn test_reset_password_rejects_invalid_requests() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("reset_invalid_{}", timestamp)).await;
+    let response = server
+        .post("/api/v1/auth/forgot-password")
+        .json(&json!({ "email": auth.user.email }))
+        .await;
+    assert_status(&response, 200);
+    let token = sender.last_token_for(&auth.user.email).unwrap();
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&json!({ "token": token, "new_password": "short" }))
+        .await;
+    assert_status(&response, 422);
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&json!({ "token": "not-a-real-token", "new_password": "NewPassword456!" }))
+        .await;
+    assert_status(&response, 400);
+
+    let response = server
+        .post("/api/v1/auth/reset-password")
+        .json(&json!({ "token": token, "new_password": "NewPassword456!" }))
+        .await;
+    assert_status(&response, 204);
+}
+
+// ============================================================================
+// Password Change Tests
+// ============================================================================
+
+/// Test changing the password while logged in.
+///
+/// Verifies that:
+/// - The change returns 204
+/// - The new password logs in and the old one doesn't
+/// - The refresh token of the current session keeps working
+/// - Refresh tokens of other sessions are revoked
+#[tokio::test]
+async fn test_change_password_success() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("change_{}@example.com", timestamp);
+    let current = register_test_user(
+        &server,
+        &format!("change_{}", timestamp),
+        &email,
+        "OldPassword123!",
+        "Change User",
+    )
+    .await;
+    let other = login_test_user(&server, &email, "OldPassword123!").await;
+
+    let request = json!({
+        "current_password": "OldPassword123!",
+        "new_password": "NewPassword456!",
+        "refresh_token": current.refresh_token,
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/auth/change-password",
+        &current.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 204);
+
+    login_test_user(&server, &email, "NewPassword456!").await;
+    let old_login = LoginRequest {
+        email: email.clone(),
+        password: "OldPassword123!".to_string(),
+        two_factor_code: None,
+    };
+    let response = server.post("/api/v1/auth/login").json(&old_login).await;
+    assert_status(&response, 401);
+
+    let response = server
+        .post("/api/v1/auth/refresh")
+        .json(&json!({ "refresh_token": current.refresh_token }))
+        .await;
+    assert_status(&response, 200);
+
+    let response = server
+        .post("/api/v1/auth/refresh")
+        .json(&json!({ "refresh_token": other.refresh_token }))
+        .await;
+    assert_status(&response, 401);
+}
+
+/// Test that a password change is rejected for bad input.
+///
+/// Verifies that:
+/// - A wrong current password fails with 401
+/// - An unchanged new password fails with 422
+/// - A weak new password fails with 422
+/// - A rejected change leaves the password as it was
+#[tokio::test]
+async fn test_change_password_rejects_invalid_requests() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let email = format!("change_invalid_{}@example.com", timestamp);
+    let auth = register_test_user(
+        &server,
+        &format!("change_invalid_{}", timestamp),
+        &email,
+        "OldPassword123!",
+        "Change Invalid User",
+    )
+    .await;
+
+    let cases = [
+        ("WrongPassword123!", "NewPassword456!", 401),
+        ("OldPassword123!", "OldPassword123!", 422),
+        ("OldPassword123!", "short", 422),
+    ];
+    for (current_password, new_password, status) in cases {
+        let request = json!({
+            "current_password": current_password,
+            "new_password": new_password,
+        });
+        let response = post_authenticated(
+            &server,
+            "/api/v1/auth/change-password",
+            &auth.token,
+            &request,
+        )
+        .await;
+        assert_status(&response, status);
+    }
+
+    login_test_user(&server, &email, "OldPassword123!").await;
+}
+
 // ============================================================================
 // Integration Flow Test
 // ============================================================================
@@ -560,6 +1373,7 @@ async fn test_full_auth_flow() {
         email: email.clone(),
         password: password.to_string(),
         name: name.to_string(),
+        preferences: None,
     };
 
     let register_response = server
@@ -576,6 +1390,7 @@ async fn test_full_auth_flow() {
     let login_request = LoginRequest {
         email: email.clone(),
         password: password.to_string(),
+        two_factor_code: None,
     };
 
     let login_response = server.post("/api/v1/auth/login").json(&login_request).await;
@@ -662,6 +1477,7 @@ async fn test_all_authentication_methods() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -0,0 +1,201 @@
+//! Integration tests for account balance history.
+//!
+//! This module tests GET /api/v1/accounts/:id/balance-history, which reports
+//! an account's posted balance at the end of each day, week or month.
+//!
+//! Tests cover the running balance across intervals, the period bounds,
+//! validation and authorization.
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{NaiveDate, Utc};
+use master_of_coin_backend::models::BalancePoint;
+use serde_json::json;
+use uuid::Uuid;
+
+async fn get_history(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    query: &str,
+) -> Vec<(NaiveDate, String)> {
+    let response = get_authenticated(
+        server,
+        &format!("/api/v1/accounts/{}/balance-history{}", account_id, query),
+        token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let points: Vec<BalancePoint> = extract_json(response);
+    points
+        .into_iter()
+        .map(|point| (point.date, point.balance.to_string()))
+        .collect()
+}
+
+fn date(value: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+}
+
+/// Test the running balance of an account by day, week and month.
+///
+/// Verifies that:
+/// - Transactions before the start make up the first balance
+/// - Intervals without transactions carry the previous balance
+/// - Transactions after the end, pending and deleted transactions are ignored
+#[tokio::test]
+async fn test_balance_history() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("history_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let token = &auth.token;
+
+    create_transaction(
+        &server,
+        token,
+        account.id,
+        "1000.00",
+        "2026-01-01T09:00:00Z",
+    )
+    .await;
+    create_transaction(&server, token, account.id, "-50.00", "2026-01-03T12:00:00Z").await;
+    create_transaction(&server, token, account.id, "-25.50", "2026-01-03T23:59:59Z").await;
+    create_transaction(&server, token, account.id, "200.00", "2026-01-13T08:00:00Z").await;
+    create_transaction(
+        &server,
+        token,
+        account.id,
+        "-100.00",
+        "2026-02-10T08:00:00Z",
+    )
+    .await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Pending",
+        "amount": "-999.00",
+        "date": "2026-01-02T10:00:00Z",
+        "status": "pending"
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Deleted",
+        "amount": "-999.00",
+        "date": "2026-01-02T10:00:00Z"
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    let deleted: serde_json::Value = response.json();
+    let path = format!("/api/v1/transactions/{}", deleted["id"].as_str().unwrap());
+    let response = delete_authenticated(&server, &path, token).await;
+    assert_status(&response, 204);
+
+    let days = get_history(
+        &server,
+        token,
+        account.id,
+        "?start=2026-01-02&end=2026-01-04",
+    )
+    .await;
+    assert_eq!(
+        days,
+        vec![
+            (date("2026-01-02"), "1000.00".to_string()),
+            (date("2026-01-03"), "924.50".to_string()),
+            (date("2026-01-04"), "924.50".to_string()),
+        ]
+    );
+
+    // 2026-01-01 is a Thursday, so the first week started on 2025-12-29
+    let weeks = get_history(
+        &server,
+        token,
+        account.id,
+        "?start=2026-01-01&end=2026-01-20&granularity=WEEK",
+    )
+    .await;
+    assert_eq!(
+        weeks,
+        vec![
+            (date("2025-12-29"), "924.50".to_string()),
+            (date("2026-01-05"), "924.50".to_string()),
+            (date("2026-01-12"), "1124.50".to_string()),
+            (date("2026-01-19"), "1124.50".to_string()),
+        ]
+    );
+
+    // Without a start, the history begins at the first transaction
+    let months = get_history(
+        &server,
+        token,
+        account.id,
+        "?end=2026-03-15&granularity=MONTH",
+    )
+    .await;
+    assert_eq!(
+        months,
+        vec![
+            (date("2026-01-01"), "1124.50".to_string()),
+            (date("2026-02-01"), "1024.50".to_string()),
+            (date("2026-03-01"), "1024.50".to_string()),
+        ]
+    );
+
+    // The last interval stops at the end
+    let months = get_history(
+        &server,
+        token,
+        account.id,
+        "?start=2026-01-01&end=2026-01-10&granularity=MONTH",
+    )
+    .await;
+    assert_eq!(months, vec![(date("2026-01-01"), "924.50".to_string())]);
+}
+
+/// Test rejected balance history requests.
+///
+/// Verifies that:
+/// - A start after the end returns 400
+/// - An unknown granularity returns 400
+/// - Too many points returns 400
+/// - Another user's account returns 403
+#[tokio::test]
+async fn test_balance_history_rejects_invalid_requests() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("history_bad_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let path = format!("/api/v1/accounts/{}/balance-history", account.id);
+
+    let response = get_authenticated(
+        &server,
+        &format!("{}?start=2026-02-01&end=2026-01-01", path),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 400);
+
+    let response =
+        get_authenticated(&server, &format!("{}?granularity=YEAR", path), &auth.token).await;
+    assert_status(&response, 400);
+
+    let response = get_authenticated(
+        &server,
+        &format!("{}?start=2000-01-01&end=2026-01-01", path),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 400);
+
+    // An account without transactions has a single zero point
+    let history = get_history(&server, &auth.token, account.id, "").await;
+    assert_eq!(history, vec![(Utc::now().date_naive(), "0.00".to_string())]);
+
+    let other = register_unique_test_user(&server, &format!("history_other_{}", timestamp)).await;
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+}
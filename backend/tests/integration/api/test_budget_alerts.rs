@@ -0,0 +1,216 @@
+//! Integration tests for budget threshold alerts
+//!
+//! These tests verify:
+//! - Alert thresholds are validated and stored sorted on budgets
+//! - GET /api/v1/budgets/alerts - List triggered alerts
+//! - POST /api/v1/budgets/alerts/:id/ack - Acknowledge an alert
+//! - Crossings are recorded once per budget range, however often the status is computed
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{Duration, Utc};
+use master_of_coin_backend::models::{AccountResponse, BudgetAlertResponse, BudgetResponse};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Create a budget over all spending with a limit of 100 covering today
+async fn create_budget(server: &TestServer, token: &str, name: &str, thresholds: Value) -> Uuid {
+    let request = json!({ "name": name, "filters": {}, "alert_thresholds": thresholds });
+    let response = post_authenticated(server, "/api/v1/budgets", token, &request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    let today = Utc::now().date_naive();
+    let range_request = json!({
+        "limit_amount": 100.0,
+        "period": "MONTHLY",
+        "start_date": (today - Duration::days(1)).to_string(),
+        "end_date": (today + Duration::days(30)).to_string()
+    });
+    let response = post_authenticated(
+        server,
+        &format!("/api/v1/budgets/{}/ranges", budget.id),
+        token,
+        &range_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    budget.id
+}
+
+async fn spend(server: &TestServer, token: &str, account_id: Uuid, amount: f64) {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Shopping",
+        "amount": -amount,
+        "date": (Utc::now() - Duration::minutes(1)).to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+}
+
+/// Compute a budget's status, which records any crossings
+async fn check_status(server: &TestServer, token: &str, budget_id: Uuid) {
+    let path = format!("/api/v1/budgets/{}/status", budget_id);
+    let response = get_authenticated(server, &path, token).await;
+    assert_status(&response, 200);
+}
+
+async fn list_alerts(server: &TestServer, token: &str, path: &str) -> Vec<BudgetAlertResponse> {
+    let response = get_authenticated(server, path, token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// Test that alert thresholds are validated and stored.
+///
+/// Verifies that:
+/// - Thresholds are sorted and deduplicated
+/// - Thresholds of zero, negative or above 10 return 422
+/// - Updating replaces the thresholds
+#[tokio::test]
+async fn test_budget_alert_thresholds() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("thresholds_{}", timestamp)).await;
+
+    let request = json!({ "name": "Food", "filters": {}, "alert_thresholds": [1.0, 0.8, 0.8] });
+    let response = post_authenticated(&server, "/api/v1/budgets", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+    assert_eq!(budget.alert_thresholds, vec![0.8, 1.0]);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/budgets",
+        &auth.token,
+        &json!({ "name": "Plain", "filters": {} }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let plain: BudgetResponse = extract_json(response);
+    assert!(plain.alert_thresholds.is_empty());
+
+    for thresholds in [json!([0.0]), json!([0.5, -1.0]), json!([11.0])] {
+        let request = json!({ "name": "Bad", "filters": {}, "alert_thresholds": thresholds });
+        let response = post_authenticated(&server, "/api/v1/budgets", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let path = format!("/api/v1/budgets/{}", budget.id);
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({ "alert_thresholds": [1.2, 0.5] }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: BudgetResponse = extract_json(response);
+    assert_eq!(updated.alert_thresholds, vec![0.5, 1.2]);
+}
+
+/// Test recording and acknowledging budget alerts.
+///
+/// Verifies that:
+/// - No alert is recorded below the lowest threshold
+/// - Each crossed threshold is recorded once, however often the status is computed
+/// - Alerts are listed newest first with the budget's name
+/// - Acknowledging marks an alert read, and `?unread=true` leaves it out
+/// - Budgets with muted alerts record none
+/// - Other users can neither see nor acknowledge the alerts
+#[tokio::test]
+async fn test_budget_alerts_recorded_once() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alerts_{}", timestamp)).await;
+
+    // In the primary currency, so no exchange rates are needed
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let budget_id = create_budget(&server, &auth.token, "Everything", json!([0.8, 1.0])).await;
+    let muted_id = create_budget(&server, &auth.token, "Muted", json!([0.5])).await;
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/mute", muted_id),
+        &auth.token,
+        &json!({ "muted": true }),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    spend(&server, &auth.token, account.id, 50.0).await;
+    check_status(&server, &auth.token, budget_id).await;
+    assert!(
+        list_alerts(&server, &auth.token, "/api/v1/budgets/alerts")
+            .await
+            .is_empty()
+    );
+
+    spend(&server, &auth.token, account.id, 30.0).await;
+    check_status(&server, &auth.token, budget_id).await;
+    check_status(&server, &auth.token, budget_id).await;
+    let response =
+        get_authenticated(&server, "/api/v1/budgets?include_status=true", &auth.token).await;
+    assert_status(&response, 200);
+    check_status(&server, &auth.token, muted_id).await;
+
+    let alerts = list_alerts(&server, &auth.token, "/api/v1/budgets/alerts").await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].budget_id, budget_id);
+    assert_eq!(alerts[0].budget_name, "Everything");
+    assert_eq!(alerts[0].threshold, 0.8);
+    assert_eq!(alerts[0].percentage_used, 80.0);
+    assert!(alerts[0].read_at.is_none());
+    let warning_id = alerts[0].id;
+
+    spend(&server, &auth.token, account.id, 25.0).await;
+    check_status(&server, &auth.token, budget_id).await;
+    let alerts = list_alerts(&server, &auth.token, "/api/v1/budgets/alerts").await;
+    let thresholds: Vec<f64> = alerts.iter().map(|alert| alert.threshold).collect();
+    assert_eq!(thresholds, vec![1.0, 0.8]);
+    assert_eq!(alerts[0].percentage_used, 105.0);
+
+    let other = register_unique_test_user(&server, &format!("alerts_other_{}", timestamp)).await;
+    let ack_path = format!("/api/v1/budgets/alerts/{}/ack", warning_id);
+    let response = post_authenticated(&server, &ack_path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+    assert!(
+        list_alerts(&server, &other.token, "/api/v1/budgets/alerts")
+            .await
+            .is_empty()
+    );
+
+    let response = post_authenticated(&server, &ack_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let acknowledged: BudgetAlertResponse = extract_json(response);
+    let read_at = acknowledged.read_at.expect("acknowledged alert is read");
+
+    // Acknowledging again keeps the first acknowledgement
+    let response = post_authenticated(&server, &ack_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let again: BudgetAlertResponse = extract_json(response);
+    assert_eq!(again.read_at, Some(read_at));
+
+    let unread = list_alerts(&server, &auth.token, "/api/v1/budgets/alerts?unread=true").await;
+    assert_eq!(unread.len(), 1);
+    assert_eq!(unread[0].threshold, 1.0);
+
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/alerts/{}/ack", Uuid::new_v4()),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 404);
+}
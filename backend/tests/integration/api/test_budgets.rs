@@ -1,19 +1,24 @@
 //! Integration tests for budget API endpoints.
 //!
 //! This module tests the budget endpoints including:
-//! - GET /api/v1/budgets - List all budgets for user
+//! - GET /api/v1/budgets - List all budgets for user (optionally with their current status)
 //! - POST /api/v1/budgets - Create new budget
 //! - GET /api/v1/budgets/:id - Get specific budget
+//! - GET /api/v1/budgets/:id/status - Get a budget's status for the range containing a day
 //! - PUT /api/v1/budgets/:id - Update budget
 //! - DELETE /api/v1/budgets/:id - Delete budget
 //! - POST /api/v1/budgets/:id/ranges - Add budget range to budget
+//! - POST /api/v1/budgets/:id/mute - Mute or unmute budget alerts
+//! - POST /api/v1/budgets/:id/snooze - Snooze budget alerts
 //!
 //! Tests cover success cases, error cases, authorization, and data isolation.
 
 use crate::common::*;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use master_of_coin_backend::{
-    models::{BudgetRangeResponse, BudgetResponse, CategoryResponse},
+    models::{
+        AccountResponse, BudgetRangeResponse, BudgetResponse, BudgetStatus, CategoryResponse,
+    },
     types::BudgetPeriod,
 };
 use serde_json::json;
@@ -191,6 +196,231 @@ async fn test_list_budgets_isolation() {
     assert_eq!(budgets_b[0].name, "User B Budget");
 }
 
+/// Test listing budgets with their current status inline.
+///
+/// Verifies that:
+/// - Budgets are listed without a status by default
+/// - `?include_status=true` attaches the status of budgets with an active range
+/// - The inline status matches the dashboard's budget status
+#[tokio::test]
+async fn test_list_budgets_include_status() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("statususer_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+
+    // In the primary currency, so the dashboard needs no exchange rates
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    // A budget for the category with a range covering today, and one without a range
+    let budget_request = json!({
+        "name": "Groceries Budget",
+        "filters": { "category_id": category.id.to_string() }
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &budget_request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    let today = Utc::now().date_naive();
+    let range_request = json!({
+        "limit_amount": 100.0,
+        "period": "MONTHLY",
+        "start_date": (today - Duration::days(1)).to_string(),
+        "end_date": (today + Duration::days(30)).to_string()
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/ranges", budget.id),
+        &auth.token,
+        &range_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let unranged_request = json!({ "name": "Unranged Budget", "filters": {} });
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &unranged_request).await;
+    assert_status(&response, 201);
+    let unranged: BudgetResponse = extract_json(response);
+
+    // Spending in the category, outside it, and income
+    let date = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+    for (amount, category_id) in [
+        (-80.0, Some(category.id)),
+        (-45.5, Some(category.id)),
+        (-30.0, None),
+        (500.0, Some(category.id)),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "category_id": category_id,
+            "title": "Shopping",
+            "amount": amount,
+            "date": date
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    // Default response has no status
+    let response = get_authenticated(&server, "/api/v1/budgets", &auth.token).await;
+    assert_status(&response, 200);
+    let budgets: Vec<serde_json::Value> = extract_json(response);
+    assert_eq!(budgets.len(), 2);
+    assert!(budgets.iter().all(|budget| budget.get("status").is_none()));
+
+    let response =
+        get_authenticated(&server, "/api/v1/budgets?include_status=true", &auth.token).await;
+    assert_status(&response, 200);
+    let budgets: Vec<BudgetResponse> = extract_json(response);
+    assert_eq!(budgets.len(), 2);
+
+    let listed = budgets.iter().find(|b| b.id == budget.id).unwrap();
+    let status = listed
+        .status
+        .as_ref()
+        .expect("budget with an active range has a status");
+    assert_eq!(status.budget_id, budget.id);
+    assert_eq!(status.current_spending.parse::<f64>().unwrap(), 125.5);
+    assert_eq!(status.limit_amount.parse::<f64>().unwrap(), 100.0);
+    assert!(status.is_over_budget);
+
+    let listed_unranged = budgets.iter().find(|b| b.id == unranged.id).unwrap();
+    assert!(listed_unranged.status.is_none());
+
+    // Matches the dashboard
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    assert_status(&response, 200);
+    let dashboard: serde_json::Value = extract_json(response);
+    let dashboard_statuses = dashboard["budget_statuses"].as_array().unwrap();
+    assert_eq!(dashboard_statuses.len(), 1);
+    assert_eq!(dashboard_statuses[0], serde_json::to_value(status).unwrap());
+}
+
+/// Test fetching a single budget's status.
+///
+/// Verifies that:
+/// - The status covers the range containing today by default
+/// - `?as_of=` reports on the range containing that day instead
+/// - 404 is returned when no range covers the day
+/// - 403 is returned for another user's budget
+#[tokio::test]
+async fn test_get_budget_status() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("progress_{}", timestamp)).await;
+
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/budgets",
+        &auth.token,
+        &json!({ "name": "Everything", "filters": {} }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    let today = Utc::now().date_naive();
+    let past_start = today - Duration::days(60);
+    let past_end = today - Duration::days(31);
+    let current_start = today - Duration::days(1);
+    let current_end = today + Duration::days(30);
+    for (limit, start, end) in [
+        (50.0, past_start, past_end),
+        (100.0, current_start, current_end),
+    ] {
+        let range_request = json!({
+            "limit_amount": limit,
+            "period": "MONTHLY",
+            "start_date": start.to_string(),
+            "end_date": end.to_string()
+        });
+        let response = post_authenticated(
+            &server,
+            &format!("/api/v1/budgets/{}/ranges", budget.id),
+            &auth.token,
+            &range_request,
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+
+    for (amount, date) in [
+        (-80.0, Utc::now() - Duration::minutes(1)),
+        (-60.0, Utc::now() - Duration::days(40)),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Shopping",
+            "amount": amount,
+            "date": date.to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let path = format!("/api/v1/budgets/{}/status", budget.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let status: BudgetStatus = extract_json(response);
+    assert_eq!(status.budget_id, budget.id);
+    assert_eq!(status.current_spending.parse::<f64>().unwrap(), 80.0);
+    assert_eq!(status.limit_amount.parse::<f64>().unwrap(), 100.0);
+    assert_eq!(status.percentage_used, 80.0);
+    assert!(!status.is_over_budget);
+    assert_eq!(status.range_start, current_start);
+    assert_eq!(status.range_end, Some(current_end));
+
+    let response = get_authenticated(
+        &server,
+        &format!("{}?as_of={}", path, today - Duration::days(45)),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let status: BudgetStatus = extract_json(response);
+    assert_eq!(status.current_spending.parse::<f64>().unwrap(), 60.0);
+    assert_eq!(status.limit_amount.parse::<f64>().unwrap(), 50.0);
+    assert!(status.is_over_budget);
+    assert_eq!(status.range_start, past_start);
+    assert_eq!(status.range_end, Some(past_end));
+
+    // Between the two ranges
+    let response = get_authenticated(
+        &server,
+        &format!("{}?as_of={}", path, today - Duration::days(10)),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 404);
+
+    let other = register_unique_test_user(&server, &format!("progress_other_{}", timestamp)).await;
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+}
+
 // ============================================================================
 // Create Budget Tests
 // ============================================================================
@@ -751,6 +981,49 @@ async fn test_update_budget_unauthorized() {
     assert_status(&response, 401);
 }
 
+/// Test that a budget update based on an outdated version is rejected.
+///
+/// Verifies that:
+/// - An update with the current version succeeds and increments it
+/// - A stale version returns 409 Conflict with the current budget
+#[tokio::test]
+async fn test_update_budget_version_conflict() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("budget_version_{}", timestamp)).await;
+
+    let create_request = json!({"name": "Food", "filters": {}});
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &create_request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+    assert_eq!(budget.version, 1);
+    let path = format!("/api/v1/budgets/{}", budget.id);
+
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({"name": "Groceries", "version": 1}),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: BudgetResponse = extract_json(response);
+    assert_eq!(updated.version, 2);
+
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({"name": "Dining", "version": 1}),
+    )
+    .await;
+    assert_status(&response, 409);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["current"]["version"], 2);
+    assert_eq!(body["current"]["name"], "Groceries");
+}
+
 // ============================================================================
 // Delete Budget Tests
 // ============================================================================
@@ -1213,6 +1486,52 @@ async fn test_add_budget_range_unauthorized() {
     assert_status(&response, 401);
 }
 
+/// Test that a budget range with an invalid period is rejected with the valid periods.
+///
+/// Verifies that:
+/// - Status code is 422 Unprocessable Entity
+/// - The error names the rejected period and lists every accepted one
+#[tokio::test]
+async fn test_add_budget_range_invalid_period_message() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("rangeperiod_{}", timestamp)).await;
+
+    let budget_request = json!({
+        "name": "Groceries",
+        "filters": {}
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &budget_request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    let range_request = json!({
+        "limit_amount": 500.0,
+        "period": "monthly",
+        "start_date": "2024-01-01",
+        "end_date": "2024-01-31"
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/ranges", budget.id),
+        &auth.token,
+        &range_request,
+    )
+    .await;
+    assert_status(&response, 422);
+    let body: serde_json::Value = extract_json(response);
+    let error = body["error"].as_str().unwrap();
+    assert!(
+        error.contains(
+            "period: invalid budget period 'monthly'; \
+             expected one of DAILY, WEEKLY, MONTHLY, QUARTERLY, YEARLY"
+        ),
+        "unexpected error: {}",
+        error
+    );
+}
+
 // ============================================================================
 // Integration Flow Tests
 // ============================================================================
@@ -1438,3 +1757,144 @@ async fn test_full_budget_with_ranges_flow() {
     .await;
     assert_status(&get_response2, 404);
 }
+
+// ============================================================================
+// Alert Muting Tests
+// ============================================================================
+
+/// Test muting and unmuting a budget's alerts.
+///
+/// Verifies that:
+/// - New budgets are not muted
+/// - Muting is reflected in the budget response and persisted
+/// - Unmuting clears the flag
+#[tokio::test]
+async fn test_mute_budget() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("mute_{}", timestamp)).await;
+
+    let create_response = post_authenticated(
+        &server,
+        "/api/v1/budgets",
+        &auth.token,
+        &json!({ "name": "Dining", "filters": {} }),
+    )
+    .await;
+    assert_status(&create_response, 201);
+    let budget: BudgetResponse = extract_json(create_response);
+    assert!(!budget.alerts_muted);
+
+    let mute_url = format!("/api/v1/budgets/{}/mute", budget.id);
+    let response =
+        post_authenticated(&server, &mute_url, &auth.token, &json!({ "muted": true })).await;
+    assert_status(&response, 200);
+    let muted: BudgetResponse = extract_json(response);
+    assert!(muted.alerts_muted);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}", budget.id),
+        &auth.token,
+    )
+    .await;
+    let fetched: BudgetResponse = extract_json(response);
+    assert!(fetched.alerts_muted);
+
+    let response =
+        post_authenticated(&server, &mute_url, &auth.token, &json!({ "muted": false })).await;
+    assert_status(&response, 200);
+    let unmuted: BudgetResponse = extract_json(response);
+    assert!(!unmuted.alerts_muted);
+}
+
+/// Test snoozing a budget's alerts.
+///
+/// Verifies that:
+/// - A future snooze end is stored and returned
+/// - A snooze end in the past is rejected with 422
+/// - `null` ends the snooze
+#[tokio::test]
+async fn test_snooze_budget() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("snooze_{}", timestamp)).await;
+
+    let create_response = post_authenticated(
+        &server,
+        "/api/v1/budgets",
+        &auth.token,
+        &json!({ "name": "Travel", "filters": {} }),
+    )
+    .await;
+    let budget: BudgetResponse = extract_json(create_response);
+    assert!(budget.alerts_snoozed_until.is_none());
+
+    let snooze_url = format!("/api/v1/budgets/{}/snooze", budget.id);
+    let until = Utc::now() + Duration::days(7);
+    let response = post_authenticated(
+        &server,
+        &snooze_url,
+        &auth.token,
+        &json!({ "until": until.to_rfc3339() }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let snoozed: BudgetResponse = extract_json(response);
+    let snoozed_until = snoozed.alerts_snoozed_until.expect("snooze should be set");
+    assert!((snoozed_until - until).num_seconds().abs() < 1);
+    assert!(!snoozed.alerts_muted);
+
+    let past = Utc::now() - Duration::hours(1);
+    let response = post_authenticated(
+        &server,
+        &snooze_url,
+        &auth.token,
+        &json!({ "until": past.to_rfc3339() }),
+    )
+    .await;
+    assert_status(&response, 422);
+
+    let response =
+        post_authenticated(&server, &snooze_url, &auth.token, &json!({ "until": null })).await;
+    assert_status(&response, 200);
+    let cleared: BudgetResponse = extract_json(response);
+    assert!(cleared.alerts_snoozed_until.is_none());
+}
+
+/// Test that users cannot mute or snooze other users' budgets.
+#[tokio::test]
+async fn test_mute_budget_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth_a = register_unique_test_user(&server, &format!("mute_a_{}", timestamp)).await;
+    let auth_b = register_unique_test_user(&server, &format!("mute_b_{}", timestamp)).await;
+
+    let create_response = post_authenticated(
+        &server,
+        "/api/v1/budgets",
+        &auth_a.token,
+        &json!({ "name": "User A Budget", "filters": {} }),
+    )
+    .await;
+    let budget: BudgetResponse = extract_json(create_response);
+
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/mute", budget.id),
+        &auth_b.token,
+        &json!({ "muted": true }),
+    )
+    .await;
+    assert_status(&response, 403);
+
+    let until = Utc::now() + Duration::days(1);
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/snooze", budget.id),
+        &auth_b.token,
+        &json!({ "until": until.to_rfc3339() }),
+    )
+    .await;
+    assert_status(&response, 403);
+}
@@ -0,0 +1,256 @@
+//! Integration tests for the bulk transaction endpoint.
+//!
+//! This module tests POST /api/v1/transactions/bulk including:
+//! - Creating, updating and deleting transactions in one request
+//! - Rejecting the whole request when any item is invalid
+//! - Checking account limits once, after all changes
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    AccountResponse, BulkItemStatus, BulkOperation, BulkTransactionResponse, TransactionResponse,
+    TransferResponse,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Create a transaction on the account and return it
+async fn create_transaction(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    title: &str,
+    amount: f64,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": title,
+        "amount": amount,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Get the account's balance
+async fn account_balance(server: &TestServer, token: &str, account_id: Uuid) -> f64 {
+    let path = format!("/api/v1/accounts/{}", account_id);
+    let account: AccountResponse = extract_json(get_authenticated(server, &path, token).await);
+    account.balance
+}
+
+/// Send a bulk request and return its results
+async fn post_bulk(server: &TestServer, token: &str, request: &Value) -> BulkTransactionResponse {
+    let response = post_authenticated(server, "/api/v1/transactions/bulk", token, request).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// Test creating, updating and deleting transactions in one request.
+///
+/// Verifies that:
+/// - Every item is reported with its operation, index and status
+/// - Created and updated transactions are returned
+/// - The account balance reflects all changes
+#[tokio::test]
+async fn test_bulk_create_update_delete() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("bulk_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let updated = create_transaction(&server, &auth.token, account.id, "Coffe", -3.0).await;
+    let deleted = create_transaction(&server, &auth.token, account.id, "Typo", -99.0).await;
+
+    let date = Utc::now().to_rfc3339();
+    let request = json!({
+        "create": [
+            { "account_id": account.id, "title": "Salary", "amount": 1000, "date": date },
+            { "account_id": account.id, "title": "Rent", "amount": -400, "date": date }
+        ],
+        "update": [{ "id": updated.id, "title": "Coffee", "amount": -4.5 }],
+        "delete": [deleted.id]
+    });
+    let response = post_bulk(&server, &auth.token, &request).await;
+    assert!(response.success);
+
+    let outcomes: Vec<(BulkOperation, usize, BulkItemStatus)> = response
+        .results
+        .iter()
+        .map(|r| (r.operation, r.index, r.status))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            (BulkOperation::Create, 0, BulkItemStatus::Created),
+            (BulkOperation::Create, 1, BulkItemStatus::Created),
+            (BulkOperation::Update, 0, BulkItemStatus::Updated),
+            (BulkOperation::Delete, 0, BulkItemStatus::Deleted),
+        ]
+    );
+    let created = response.results[0].transaction.as_ref().unwrap();
+    assert_eq!(created.title, "Salary");
+    let update = response.results[2].transaction.as_ref().unwrap();
+    assert_eq!(update.id, updated.id);
+    assert_eq!(update.title, "Coffee");
+    assert_eq!(update.amount.to_string(), "-4.50");
+    assert_eq!(response.results[3].transaction_id, Some(deleted.id));
+
+    let path = format!("/api/v1/transactions/{}", deleted.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        595.5
+    );
+}
+
+/// Test that an invalid item keeps the whole request from being applied.
+///
+/// Verifies that:
+/// - Invalid items are reported as failed with an error
+/// - Valid items are reported as skipped
+/// - Nothing is created, updated or deleted
+#[tokio::test]
+async fn test_bulk_invalid_item_applies_nothing() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("bulkbad_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("bulkother_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let other_account = create_test_account(&server, &other.token, "Checking").await;
+    let mine = create_transaction(&server, &auth.token, account.id, "Lunch", -12.0).await;
+    let theirs = create_transaction(&server, &other.token, other_account.id, "Taxi", -8.0).await;
+
+    let date = Utc::now().to_rfc3339();
+    let request = json!({
+        "create": [
+            { "account_id": account.id, "title": "Valid", "amount": -5, "date": date },
+            { "account_id": account.id, "title": "", "amount": -5, "date": date }
+        ],
+        "update": [
+            { "id": mine.id, "title": "Dinner" },
+            { "id": theirs.id, "title": "Mine now" }
+        ],
+        "delete": [mine.id]
+    });
+    let response = post_bulk(&server, &auth.token, &request).await;
+    assert!(!response.success);
+
+    let statuses: Vec<BulkItemStatus> = response.results.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            BulkItemStatus::Skipped,
+            BulkItemStatus::Failed,
+            BulkItemStatus::Skipped,
+            BulkItemStatus::Failed,
+            BulkItemStatus::Failed,
+        ]
+    );
+    for result in &response.results {
+        assert_eq!(
+            result.error.is_some(),
+            result.status == BulkItemStatus::Failed
+        );
+        assert!(result.transaction_id.is_none());
+    }
+    // Updated and deleted in the same request
+    assert_eq!(response.results[4].operation, BulkOperation::Delete);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].title, "Lunch");
+}
+
+/// Test that account limits are checked once against the combined changes.
+///
+/// Verifies that:
+/// - An expense covered by an income later in the same request is accepted
+/// - Changes that leave the account past its limit are rejected with 422 and
+///   nothing is applied
+#[tokio::test]
+async fn test_bulk_checks_limits_after_all_changes() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("bulklimit_{}", timestamp)).await;
+    let request = json!({
+        "name": "Savings",
+        "account_type": "SAVINGS",
+        "currency": "USD",
+        "allow_overdraft": false
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let date = Utc::now().to_rfc3339();
+    let request = json!({
+        "create": [
+            { "account_id": account.id, "title": "Rent", "amount": -50, "date": date },
+            { "account_id": account.id, "title": "Salary", "amount": 100, "date": date }
+        ]
+    });
+    let response = post_bulk(&server, &auth.token, &request).await;
+    assert!(response.success);
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        50.0
+    );
+
+    let request = json!({
+        "create": [
+            { "account_id": account.id, "title": "Car", "amount": -80, "date": date }
+        ]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions/bulk", &auth.token, &request).await;
+    assert_status(&response, 422);
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        50.0
+    );
+}
+
+/// Test that deleting one leg of a transfer in bulk deletes both.
+#[tokio::test]
+async fn test_bulk_delete_transfer_leg() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("bulkxfer_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 40,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transfer: TransferResponse = extract_json(response);
+
+    let request = json!({ "delete": [transfer.from_transaction.id] });
+    let response = post_bulk(&server, &auth.token, &request).await;
+    assert!(response.success);
+
+    assert_eq!(
+        account_balance(&server, &auth.token, checking.id).await,
+        0.0
+    );
+    assert_eq!(account_balance(&server, &auth.token, savings.id).await, 0.0);
+}
@@ -0,0 +1,142 @@
+//! Integration tests for the monthly cashflow report.
+//!
+//! This module tests GET /api/v1/dashboard/cashflow, which reports the user's
+//! income, expense and net per month.
+//!
+//! Tests cover the monthly sums, the exclusion of transfers, pending and
+//! deleted transactions, and the month count limits.
+
+use crate::common::*;
+use chrono::{Datelike, Months, Utc};
+use master_of_coin_backend::services::analytics_service::Cashflow;
+use serde_json::json;
+
+/// Test income and expense per month.
+///
+/// Verifies that:
+/// - Income and expense are summed per month across accounts
+/// - Transfers, pending and deleted transactions are left out
+/// - Months without transactions are reported as zero
+#[tokio::test]
+async fn test_cashflow() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("cashflow_{}", timestamp)).await;
+    let token = &auth.token;
+
+    let checking = create_test_account(&server, token, "Checking").await;
+    let savings = create_test_account(&server, token, "Savings").await;
+
+    let first = |months_ago: u32| {
+        Utc::now()
+            .date_naive()
+            .with_day(1)
+            .unwrap()
+            .checked_sub_months(Months::new(months_ago))
+            .unwrap()
+    };
+    for (account_id, amount, months_ago, status) in [
+        (checking.id, "3000.00", 2, "posted"),
+        (checking.id, "-1200.00", 2, "posted"),
+        (savings.id, "15.25", 2, "posted"),
+        (checking.id, "-80.50", 0, "posted"),
+        (checking.id, "-500.00", 0, "pending"),
+    ] {
+        let request = json!({
+            "account_id": account_id,
+            "title": "Cashflow test",
+            "amount": amount,
+            "date": format!("{}T12:00:00Z", first(months_ago)),
+            "status": status
+        });
+        let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let request = json!({
+        "account_id": savings.id,
+        "title": "Deleted",
+        "amount": "-999.00",
+        "date": format!("{}T12:00:00Z", first(0))
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    let deleted: serde_json::Value = response.json();
+    let path = format!("/api/v1/transactions/{}", deleted["id"].as_str().unwrap());
+    let response = delete_authenticated(&server, &path, token).await;
+    assert_status(&response, 204);
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 700,
+        "date": format!("{}T12:00:00Z", first(2))
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions/transfer", token, &request).await;
+    assert_status(&response, 201);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/cashflow?months=3&currency=USD",
+        token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let cashflow: Cashflow = extract_json(response);
+    assert_eq!(cashflow.currency.as_str(), "USD");
+
+    let months: Vec<(String, String, String, String)> = cashflow
+        .months
+        .into_iter()
+        .map(|month| {
+            (
+                month.month,
+                month.income.to_string(),
+                month.expense.to_string(),
+                month.net.to_string(),
+            )
+        })
+        .collect();
+    let month = |months_ago: u32| first(months_ago).format("%Y-%m").to_string();
+    let row = |months_ago: u32, income: &str, expense: &str, net: &str| {
+        (
+            month(months_ago),
+            income.to_string(),
+            expense.to_string(),
+            net.to_string(),
+        )
+    };
+    assert_eq!(
+        months,
+        vec![
+            row(2, "3015.25", "1200.00", "1815.25"),
+            row(1, "0.00", "0.00", "0.00"),
+            row(0, "0.00", "80.50", "-80.50"),
+        ]
+    );
+}
+
+/// Test that the month count is limited and defaults to six months.
+#[tokio::test]
+async fn test_cashflow_month_limits() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("cashflow_limits_{}", timestamp)).await;
+
+    for months in ["0", "121"] {
+        let path = format!("/api/v1/dashboard/cashflow?months={}", months);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 400);
+    }
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/cashflow?currency=EUR",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let cashflow: Cashflow = extract_json(response);
+    assert_eq!(cashflow.months.len(), 6);
+}
@@ -4,15 +4,19 @@
 //! - GET /api/v1/categories - List all categories for user
 //! - POST /api/v1/categories - Create new category
 //! - PUT /api/v1/categories/:id - Update category
-//! - DELETE /api/v1/categories/:id - Delete category
+//! - DELETE /api/v1/categories/:id - Delete category, uncategorizing or
+//!   reassigning its transactions and budgets
 //!
-//! Note: There is NO single GET endpoint for categories (no GET /api/v1/categories/:id)
+//! Merging categories is tested in test_category_merge.
 //!
 //! Tests cover success cases, error cases, authorization, and data isolation.
 
 use crate::common::*;
+use axum_test::TestServer;
 use chrono::Utc;
-use master_of_coin_backend::models::CategoryResponse;
+use master_of_coin_backend::models::{
+    BudgetResponse, CategoryResponse, DeleteCategoryResponse, TransactionResponse,
+};
 use serde_json::json;
 
 // ============================================================================
@@ -549,7 +553,7 @@ async fn test_update_category_unauthorized() {
 /// Test successful category deletion.
 ///
 /// Verifies that:
-/// - Status code is 204 No Content
+/// - Status code is 200 OK
 /// - Category is actually deleted
 /// - Category no longer appears in list
 #[tokio::test]
@@ -582,7 +586,7 @@ async fn test_delete_category_success() {
         &auth.token,
     )
     .await;
-    assert_status(&delete_response, 204);
+    assert_status(&delete_response, 200);
 
     // Verify category is not in list
     let list_response = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
@@ -702,6 +706,165 @@ async fn test_delete_category_unauthorized() {
     assert_status(&response, 401);
 }
 
+/// Create a transaction and a budget in `category_id`, returning their IDs
+async fn create_categorized_data(
+    server: &TestServer,
+    token: &str,
+    category_id: uuid::Uuid,
+) -> (uuid::Uuid, uuid::Uuid) {
+    let account = create_test_account(server, token, "Delete Checking").await;
+    let request = json!({
+        "account_id": account.id,
+        "category_id": category_id,
+        "title": "Coffee",
+        "amount": "-4.50",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+
+    let request = json!({
+        "name": "Coffee Budget",
+        "filters": {"category_id": category_id}
+    });
+    let response = post_authenticated(server, "/api/v1/budgets", token, &request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    (transaction.id, budget.id)
+}
+
+/// Test deleting a category without `reassign_to` uncategorizes its data.
+///
+/// Verifies that:
+/// - The response counts the affected transactions and budgets
+/// - The transaction is left without a category
+/// - The budget's category filter is cleared and the budget is deactivated
+/// - The deactivated budget has no status until its filters are updated
+#[tokio::test]
+async fn test_delete_category_uncategorizes_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("delete_uncat_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Coffee").await;
+    let (transaction_id, budget_id) =
+        create_categorized_data(&server, &auth.token, category.id).await;
+
+    let path = format!("/api/v1/categories/{}", category.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let deleted: DeleteCategoryResponse = extract_json(response);
+    assert_eq!(deleted.reassigned_to, None);
+    assert_eq!(deleted.affected_transactions, 1);
+    assert_eq!(deleted.affected_budgets, 1);
+    assert_eq!(deleted.deactivated_budgets, 1);
+
+    let path = format!("/api/v1/transactions/{}", transaction_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.category_id, None);
+
+    let path = format!("/api/v1/budgets/{}", budget_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let budget: BudgetResponse = extract_json(response);
+    assert!(
+        budget.filters.get("category_id").is_none(),
+        "The budget should no longer filter on the deleted category"
+    );
+    assert!(budget.deactivated_at.is_some());
+
+    let status_path = format!("/api/v1/budgets/{}/status", budget_id);
+    let response = get_authenticated(&server, &status_path, &auth.token).await;
+    assert_status(&response, 409);
+
+    let request = json!({"filters": {}});
+    let response = put_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let budget: BudgetResponse = extract_json(response);
+    assert!(budget.deactivated_at.is_none());
+}
+
+/// Test deleting a category with `reassign_to` moves its data.
+///
+/// Verifies that:
+/// - The response names the target and counts the affected rows
+/// - The transaction and the budget filter move to the target category
+#[tokio::test]
+async fn test_delete_category_reassigns_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("delete_reassign_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Coffee").await;
+    let target = create_test_category(&server, &auth.token, "Drinks").await;
+    let (transaction_id, budget_id) =
+        create_categorized_data(&server, &auth.token, category.id).await;
+
+    let path = format!(
+        "/api/v1/categories/{}?reassign_to={}",
+        category.id, target.id
+    );
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let deleted: DeleteCategoryResponse = extract_json(response);
+    assert_eq!(deleted.reassigned_to, Some(target.id));
+    assert_eq!(deleted.affected_transactions, 1);
+    assert_eq!(deleted.affected_budgets, 1);
+
+    let path = format!("/api/v1/transactions/{}", transaction_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.category_id, Some(target.id));
+
+    let path = format!("/api/v1/budgets/{}", budget_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    let budget: BudgetResponse = extract_json(response);
+    assert_eq!(budget.filters["category_id"], json!(target.id));
+
+    let path = format!("/api/v1/categories/{}", category.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test `reassign_to` must be another category of the caller.
+///
+/// Verifies that:
+/// - Reassigning to another user's category is forbidden
+/// - Reassigning to the deleted category itself is rejected
+/// - The category and its transaction are left untouched
+#[tokio::test]
+async fn test_delete_category_invalid_reassign_target() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("delete_target_{}", timestamp)).await;
+    let other =
+        register_unique_test_user(&server, &format!("delete_target_other_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Coffee").await;
+    let theirs = create_test_category(&server, &other.token, "Theirs").await;
+    let (transaction_id, _) = create_categorized_data(&server, &auth.token, category.id).await;
+
+    let path = format!(
+        "/api/v1/categories/{}?reassign_to={}",
+        category.id, theirs.id
+    );
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 403);
+
+    let path = format!(
+        "/api/v1/categories/{}?reassign_to={}",
+        category.id, category.id
+    );
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 422);
+
+    let path = format!("/api/v1/transactions/{}", transaction_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.category_id, Some(category.id));
+}
+
 // ============================================================================
 // Integration Flow Test
 // ============================================================================
@@ -801,7 +964,7 @@ async fn test_full_category_crud_flow() {
         &auth.token,
     )
     .await;
-    assert_status(&delete_response, 204);
+    assert_status(&delete_response, 200);
 
     // Step 6: Verify deletion
     let list_response3 = get_authenticated(&server, "/api/v1/categories", &auth.token).await;
@@ -0,0 +1,163 @@
+//! Integration tests for merging categories.
+//!
+//! This module tests POST /api/v1/categories/:id/merge:
+//! - Transactions and budget filters moving to the target category
+//! - Budget ranges of a merged category's budgets still applying
+//! - Deletion of the merged category
+//! - Merging a category into itself or across users
+
+use crate::common::*;
+use chrono::{Duration, Utc};
+use master_of_coin_backend::models::{
+    AccountResponse, BudgetResponse, BudgetStatus, MergeCategoryResponse, TransactionResponse,
+};
+use serde_json::json;
+
+/// Test merging moves transactions and budget filters to the target.
+///
+/// Verifies that:
+/// - The response counts the reassigned transactions and budgets
+/// - The transactions are listed under the target category
+/// - A budget filtering on the merged category filters on the target, and
+///   its range still counts the moved spending
+/// - The merged category is deleted
+#[tokio::test]
+async fn test_merge_category_reassigns_transactions_and_budgets() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("merge_{}", timestamp)).await;
+    // In the budget currency, so spending needs no exchange rates
+    let account_request = json!({
+        "name": "Merge Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+    let food = create_test_category(&server, &auth.token, "Food").await;
+    let dining = create_test_category(&server, &auth.token, "Dining").await;
+
+    for (title, category_id) in [
+        ("Pizza", food.id),
+        ("Burger", food.id),
+        ("Sushi", dining.id),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "category_id": category_id,
+            "title": title,
+            "amount": "-20.00",
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let request = json!({
+        "name": "Eating Out",
+        "filters": {"category_id": food.id}
+    });
+    let response = post_authenticated(&server, "/api/v1/budgets", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+    let today = Utc::now().date_naive();
+    let range_request = json!({
+        "limit_amount": 100.0,
+        "period": "MONTHLY",
+        "start_date": (today - Duration::days(1)).to_string(),
+        "end_date": (today + Duration::days(30)).to_string()
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/ranges", budget.id),
+        &auth.token,
+        &range_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let path = format!("/api/v1/categories/{}/merge", food.id);
+    let request = json!({"target_category_id": dining.id});
+    let response = post_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let merge: MergeCategoryResponse = extract_json(response);
+    assert_eq!(merge.target_category_id, dining.id);
+    assert_eq!(merge.reassigned_transactions, 2);
+    assert_eq!(merge.reassigned_budgets, 1);
+
+    let path = format!("/api/v1/categories/{}/transactions", dining.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 3);
+
+    let path = format!("/api/v1/budgets/{}", budget.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let budget: BudgetResponse = extract_json(response);
+    assert_eq!(budget.filters["category_id"], json!(dining.id));
+
+    let path = format!("/api/v1/budgets/{}/status", budget.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let status: BudgetStatus = extract_json(response);
+    assert_eq!(status.current_spending.parse::<f64>().unwrap(), 60.0);
+
+    let path = format!("/api/v1/categories/{}", food.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test merging a category into itself is rejected.
+#[tokio::test]
+async fn test_merge_category_into_itself() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("merge_self_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Food").await;
+
+    let path = format!("/api/v1/categories/{}/merge", category.id);
+    let request = json!({"target_category_id": category.id});
+    let response = post_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let path = format!("/api/v1/categories/{}", category.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+}
+
+/// Test both categories of a merge must belong to the caller.
+///
+/// Verifies that:
+/// - Merging into another user's category is forbidden
+/// - Merging another user's category is forbidden
+/// - Neither category is changed
+#[tokio::test]
+async fn test_merge_category_of_other_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("merge_own_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("merge_other_{}", timestamp)).await;
+    let mine = create_test_category(&server, &auth.token, "Mine").await;
+    let theirs = create_test_category(&server, &other.token, "Theirs").await;
+
+    let path = format!("/api/v1/categories/{}/merge", mine.id);
+    let request = json!({"target_category_id": theirs.id});
+    let response = post_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 403);
+
+    let path = format!("/api/v1/categories/{}/merge", theirs.id);
+    let request = json!({"target_category_id": mine.id});
+    let response = post_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 403);
+
+    let path = format!("/api/v1/categories/{}", mine.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let path = format!("/api/v1/categories/{}", theirs.id);
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 200);
+}
@@ -0,0 +1,284 @@
+//! Integration tests for category rule endpoints.
+//!
+//! This module tests category rules including:
+//! - POST /api/v1/category-rules - Create category rule
+//! - GET /api/v1/category-rules - List category rules
+//! - GET /api/v1/category-rules/:id - Get category rule
+//! - PUT /api/v1/category-rules/:id - Update category rule
+//! - DELETE /api/v1/category-rules/:id - Delete category rule
+//! - POST /api/v1/category-rules/apply - Categorize existing transactions
+//! - Categorizing new transactions created without a category
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::{
+    models::{ApplyCategoryRulesResponse, CategoryRuleResponse, TransactionResponse},
+    types::CategoryRuleMatchType,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Create a category rule and return it
+async fn create_rule(server: &TestServer, token: &str, request: Value) -> CategoryRuleResponse {
+    let response = post_authenticated(server, "/api/v1/category-rules", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Create a transaction on the account and return it
+async fn create_transaction(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    title: &str,
+    category_id: Option<Uuid>,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "category_id": category_id,
+        "title": title,
+        "amount": -4.5,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test the category rule lifecycle.
+///
+/// Verifies that:
+/// - Creating returns 201 with the priority defaulting to 0
+/// - Rules are listed from the lowest priority up
+/// - Updating keeps omitted fields
+/// - Deleting returns 204 and the rule is gone afterwards
+#[tokio::test]
+async fn test_category_rule_crud() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("catrule_{}", timestamp)).await;
+    let coffee = create_test_category(&server, &auth.token, "Coffee").await;
+    let travel = create_test_category(&server, &auth.token, "Travel").await;
+
+    let rule = create_rule(
+        &server,
+        &auth.token,
+        json!({ "category_id": coffee.id, "match_type": "CONTAINS", "pattern": "STARBUCKS" }),
+    )
+    .await;
+    assert_eq!(rule.category_id, coffee.id);
+    assert_eq!(rule.match_type, CategoryRuleMatchType::Contains);
+    assert_eq!(rule.pattern, "STARBUCKS");
+    assert_eq!(rule.priority, 0);
+
+    let first = create_rule(
+        &server,
+        &auth.token,
+        json!({
+            "category_id": travel.id,
+            "match_type": "STARTS_WITH",
+            "pattern": "uber",
+            "priority": -1
+        }),
+    )
+    .await;
+
+    let response = get_authenticated(&server, "/api/v1/category-rules", &auth.token).await;
+    assert_status(&response, 200);
+    let rules: Vec<CategoryRuleResponse> = extract_json(response);
+    let ids: Vec<Uuid> = rules.iter().map(|rule| rule.id).collect();
+    assert_eq!(ids, vec![first.id, rule.id]);
+
+    let path = format!("/api/v1/category-rules/{}", rule.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+
+    let update = json!({ "match_type": "REGEX", "pattern": "^star(bucks)?\\b" });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 200);
+    let updated: CategoryRuleResponse = extract_json(response);
+    assert_eq!(updated.match_type, CategoryRuleMatchType::Regex);
+    assert_eq!(updated.category_id, coffee.id);
+    assert_eq!(updated.priority, 0);
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test that invalid rules are rejected.
+///
+/// Verifies that:
+/// - An invalid regex, an empty pattern or an unknown match type return 422
+/// - Another user's category returns 403, as does another user's rule
+#[tokio::test]
+async fn test_category_rule_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("catrule_val_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("catrule_oth_{}", timestamp)).await;
+    let category = create_test_category(&server, &auth.token, "Coffee").await;
+    let other_category = create_test_category(&server, &other.token, "Coffee").await;
+
+    for request in [
+        json!({ "category_id": category.id, "match_type": "REGEX", "pattern": "star(" }),
+        json!({ "category_id": category.id, "match_type": "CONTAINS", "pattern": "" }),
+        json!({ "category_id": category.id, "match_type": "ENDS_WITH", "pattern": "x" }),
+    ] {
+        let response =
+            post_authenticated(&server, "/api/v1/category-rules", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let request =
+        json!({ "category_id": other_category.id, "match_type": "CONTAINS", "pattern": "x" });
+    let response =
+        post_authenticated(&server, "/api/v1/category-rules", &auth.token, &request).await;
+    assert_status(&response, 403);
+
+    let rule = create_rule(
+        &server,
+        &other.token,
+        json!({ "category_id": other_category.id, "match_type": "CONTAINS", "pattern": "x" }),
+    )
+    .await;
+    let path = format!("/api/v1/category-rules/{}", rule.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 403);
+}
+
+/// Test that new transactions without a category are categorized by rule.
+///
+/// Verifies that:
+/// - Patterns match ignoring case, and the first rule by priority wins
+/// - A given category is kept
+/// - Titles matching no rule stay uncategorized
+#[tokio::test]
+async fn test_rules_categorize_new_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("catrule_new_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let coffee = create_test_category(&server, &auth.token, "Coffee").await;
+    let snacks = create_test_category(&server, &auth.token, "Snacks").await;
+
+    create_rule(
+        &server,
+        &auth.token,
+        json!({
+            "category_id": snacks.id,
+            "match_type": "REGEX",
+            "pattern": "muffin|cookie",
+            "priority": 10
+        }),
+    )
+    .await;
+    create_rule(
+        &server,
+        &auth.token,
+        json!({ "category_id": coffee.id, "match_type": "CONTAINS", "pattern": "STARBUCKS" }),
+    )
+    .await;
+
+    let transaction = create_transaction(
+        &server,
+        &auth.token,
+        account.id,
+        "Starbucks muffin #123",
+        None,
+    )
+    .await;
+    assert_eq!(transaction.category_id, Some(coffee.id));
+
+    let transaction =
+        create_transaction(&server, &auth.token, account.id, "COOKIE shop", None).await;
+    assert_eq!(transaction.category_id, Some(snacks.id));
+
+    let transaction = create_transaction(
+        &server,
+        &auth.token,
+        account.id,
+        "Starbucks",
+        Some(snacks.id),
+    )
+    .await;
+    assert_eq!(transaction.category_id, Some(snacks.id));
+
+    let transaction = create_transaction(&server, &auth.token, account.id, "Bookshop", None).await;
+    assert_eq!(transaction.category_id, None);
+}
+
+/// Test running the rules over existing uncategorized transactions.
+///
+/// Verifies that:
+/// - Matching uncategorized transactions are given the rule's category and counted
+/// - Categorized and non-matching transactions are left alone
+/// - Running the rules again updates nothing
+#[tokio::test]
+async fn test_apply_rules_to_existing_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("catrule_apply_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let coffee = create_test_category(&server, &auth.token, "Coffee").await;
+    let other = create_test_category(&server, &auth.token, "Other").await;
+
+    let first = create_transaction(&server, &auth.token, account.id, "STARBUCKS 1", None).await;
+    let second =
+        create_transaction(&server, &auth.token, account.id, "starbucks store", None).await;
+    let categorized = create_transaction(
+        &server,
+        &auth.token,
+        account.id,
+        "Starbucks",
+        Some(other.id),
+    )
+    .await;
+    let unmatched = create_transaction(&server, &auth.token, account.id, "Bookshop", None).await;
+
+    create_rule(
+        &server,
+        &auth.token,
+        json!({ "category_id": coffee.id, "match_type": "STARTS_WITH", "pattern": "Starbucks" }),
+    )
+    .await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/category-rules/apply",
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+    let result: ApplyCategoryRulesResponse = extract_json(response);
+    assert_eq!(result.updated, 2);
+
+    for (transaction, expected) in [
+        (first, Some(coffee.id)),
+        (second, Some(coffee.id)),
+        (categorized, Some(other.id)),
+        (unmatched, None),
+    ] {
+        let path = format!("/api/v1/transactions/{}", transaction.id);
+        let fetched: TransactionResponse =
+            extract_json(get_authenticated(&server, &path, &auth.token).await);
+        assert_eq!(fetched.category_id, expected);
+    }
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/category-rules/apply",
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    let result: ApplyCategoryRulesResponse = extract_json(response);
+    assert_eq!(result.updated, 0);
+}
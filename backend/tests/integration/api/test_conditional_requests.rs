@@ -0,0 +1,168 @@
+//! Integration tests for conditional GET requests.
+//!
+//! This module tests ETag/If-None-Match handling on single-resource GETs:
+//! - GET /api/v1/accounts/:id
+//! - GET /api/v1/transactions/:id
+//! - GET /api/v1/budgets/:id
+//! - GET /api/v1/categories/:id
+//! - GET /api/v1/people/:id
+//!
+//! Tests cover 304 responses for unchanged resources and fresh ETags after changes.
+
+use crate::common::*;
+use axum_test::{TestResponse, TestServer};
+use chrono::Utc;
+use http::HeaderValue;
+use http::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use serde_json::{Value, json};
+
+/// Makes an authenticated GET request carrying an `If-None-Match` header.
+async fn get_if_none_match(
+    server: &TestServer,
+    path: &str,
+    token: &str,
+    etag: &HeaderValue,
+) -> TestResponse {
+    server
+        .get(path)
+        .add_header(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        )
+        .add_header(IF_NONE_MATCH, etag.clone())
+        .await
+}
+
+/// Test that repeating a GET with the prior ETag yields 304 for every resource.
+///
+/// Verifies that:
+/// - Single-resource GETs return a weak ETag
+/// - A repeated GET with that ETag returns 304 Not Modified with no body
+#[tokio::test]
+async fn test_repeated_get_with_etag_not_modified() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("etag_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Alice").await;
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+
+    let transaction_request = json!({
+        "account_id": account.id,
+        "title": "Coffee",
+        "amount": -4.5,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &transaction_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transaction: Value = extract_json(response);
+
+    let budget_request = json!({ "name": "Food", "filters": {} });
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &budget_request).await;
+    assert_status(&response, 201);
+    let budget: Value = extract_json(response);
+
+    for path in [
+        format!("/api/v1/accounts/{}", account.id),
+        format!(
+            "/api/v1/transactions/{}",
+            transaction["id"].as_str().unwrap()
+        ),
+        format!("/api/v1/budgets/{}", budget["id"].as_str().unwrap()),
+        format!("/api/v1/categories/{}", category.id),
+        format!("/api/v1/people/{}", person.id),
+    ] {
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 200);
+        let etag = response.header(ETAG);
+        assert!(etag.to_str().unwrap().starts_with("W/\""), "{} ETag", path);
+
+        let response = get_if_none_match(&server, &path, &auth.token, &etag).await;
+        assert_status(&response, 304);
+        assert!(response.as_bytes().is_empty(), "{} 304 body", path);
+        assert_eq!(response.header(ETAG), etag);
+    }
+}
+
+/// Test that changes produce a new ETag.
+///
+/// Verifies that:
+/// - Updating a person or a category invalidates its ETag
+/// - A new transaction invalidates the account ETag, since the balance changed
+#[tokio::test]
+async fn test_etag_changes_after_mutation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("etagchange_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Alice").await;
+
+    let person_path = format!("/api/v1/people/{}", person.id);
+    let etag = get_authenticated(&server, &person_path, &auth.token)
+        .await
+        .header(ETAG);
+    let response = put_authenticated(
+        &server,
+        &person_path,
+        &auth.token,
+        &json!({ "name": "Alice Smith" }),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let response = get_if_none_match(&server, &person_path, &auth.token, &etag).await;
+    assert_status(&response, 200);
+    assert_ne!(response.header(ETAG), etag);
+    let updated: Value = extract_json(response);
+    assert_eq!(updated["name"], "Alice Smith");
+
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+    let category_path = format!("/api/v1/categories/{}", category.id);
+    let etag = get_authenticated(&server, &category_path, &auth.token)
+        .await
+        .header(ETAG);
+    let response = put_authenticated(
+        &server,
+        &category_path,
+        &auth.token,
+        &json!({ "color": "#00FF00" }),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let response = get_if_none_match(&server, &category_path, &auth.token, &etag).await;
+    assert_status(&response, 200);
+    assert_ne!(response.header(ETAG), etag);
+
+    let account_path = format!("/api/v1/accounts/{}", account.id);
+    let etag = get_authenticated(&server, &account_path, &auth.token)
+        .await
+        .header(ETAG);
+    let transaction_request = json!({
+        "account_id": account.id,
+        "title": "Salary",
+        "amount": 1000.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &transaction_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let response = get_if_none_match(&server, &account_path, &auth.token, &etag).await;
+    assert_status(&response, 200);
+    assert_ne!(response.header(ETAG), etag);
+}
@@ -5,6 +5,7 @@
 //! - Duplicate detection logic
 //! - Validation of parsed transactions
 //! - Error handling for invalid CSV files
+//! - Incremental parsing of files received in chunks
 
 use bigdecimal::BigDecimal;
 use chrono::Utc;
@@ -14,7 +15,9 @@ use master_of_coin_backend::{
         ParsedTransaction,
         parser_error::{ParserError, ValidationError},
     },
-    services::csv_parser_service::{CSVStatementParser, ParserFactory, StatementParser},
+    services::csv_parser_service::{
+        CSVStatementParser, CsvRecordStream, ParserFactory, StatementParser,
+    },
     types::ConfidenceLevel,
 };
 use std::str::FromStr;
@@ -24,7 +27,10 @@ fn test_import_config() -> ImportConfig {
     ImportConfig {
         max_file_size: 5 * 1024 * 1024,
         max_transactions: 1000,
+        max_stream_file_size: 100 * 1024 * 1024,
+        batch_size: 1000,
         duplicate_confidence_threshold: "MEDIUM".to_string(),
+        duplicate_window_days: 3,
     }
 }
 
@@ -101,6 +107,30 @@ ID3,2026-01-05 03:27:50,Merchant3,Purchase,$-75.00,\xE2\x80\xA2\xE2\x80\xA2\xE2\
     assert!(transactions[2].original_amount.is_some());
 }
 
+#[test]
+fn test_parse_category_and_account_columns() {
+    let csv_data = b"id,time,merchant,type,amount,card,category,account
+ID1,2026-01-03 03:27:50,Tesco,Purchase,\xE2\x82\xAC-40.00,\xE2\x80\xA2\xE2\x80\xA2\xE2\x80\xA2\xE2\x80\xA2 2133, Groceries ,Joint Account
+ID2,2026-01-04 03:27:50,Amazon,Purchase,\xE2\x82\xAC-10.00,\xE2\x80\xA2\xE2\x80\xA2\xE2\x80\xA2\xE2\x80\xA2 2133,,";
+
+    let parser = CSVStatementParser;
+    let config = test_import_config();
+    let transactions = parser.parse(csv_data, &config).unwrap();
+
+    assert_eq!(transactions[0].category_name, Some("Groceries".to_string()));
+    assert_eq!(
+        transactions[0].account_name,
+        Some("Joint Account".to_string())
+    );
+    // Names are resolved to IDs after parsing
+    assert_eq!(transactions[0].category_id, None);
+    assert_eq!(transactions[0].account_id, None);
+
+    // Empty cells mean no name
+    assert_eq!(transactions[1].category_name, None);
+    assert_eq!(transactions[1].account_name, None);
+}
+
 #[test]
 fn test_parse_empty_csv() {
     let csv_data = b"id,time,merchant,type,amount,card";
@@ -205,6 +235,10 @@ fn test_validation_empty_title() {
         notes: None,
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -226,6 +260,10 @@ fn test_validation_zero_amount() {
         notes: None,
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -249,6 +287,10 @@ fn test_validation_future_date() {
         notes: None,
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -259,6 +301,105 @@ fn test_validation_future_date() {
     assert!(errors.contains(&ValidationError::FutureDate));
 }
 
+#[test]
+fn test_stream_matches_whole_file_parse() {
+    let csv_data = "id,time,merchant,type,amount,card,category
+ID1,2026-01-03 03:27:50,\"Shop, Ltd\",Purchase,\u{20AC}-108.12,2133,Groceries
+ID2,2026-01-02 12:18:23,\"Line
+Break \"\"Cafe\"\"\",Purchase,\u{00A3}-23.84,2133,
+ID3,2026-01-01 08:00:00,Employer,Refund,$-50.00,2133,Salary";
+
+    let parser = CSVStatementParser;
+    let expected = parser
+        .parse(csv_data.as_bytes(), &test_import_config())
+        .unwrap();
+
+    // Feeding one byte at a time splits every record and quoted field
+    let mut stream = CsvRecordStream::new();
+    let mut records = Vec::new();
+    for byte in csv_data.as_bytes() {
+        records.extend(stream.push(std::slice::from_ref(byte)));
+    }
+    records.extend(stream.finish());
+
+    assert_eq!(records.len(), expected.len());
+    for ((line, record), (index, expected)) in records.into_iter().zip(expected.iter().enumerate())
+    {
+        let record = record.unwrap();
+        assert_eq!(line, index + 2);
+        assert_eq!(record.title, expected.title);
+        assert_eq!(record.amount, expected.amount);
+        assert_eq!(record.date, expected.date);
+        assert_eq!(record.original_currency, expected.original_currency);
+        assert_eq!(record.category_name, expected.category_name);
+    }
+}
+
+#[test]
+fn test_stream_reports_bad_records_and_continues() {
+    let csv_data = b"id,time,merchant,type,amount,card
+ID1,2026-01-03 03:27:50,Amazon,Purchase,abc,2133
+ID2,2026-01-03 03:27:50,Amazon,Purchase
+ID3,2026-01-03 03:27:50,Amazon,Purchase,-1.00,2133,Extra,Columns
+ID4,2026-01-03 03:27:50,Amazon,Purchase,-1.00,2133
+";
+
+    let mut stream = CsvRecordStream::new();
+    let mut records = stream.push(csv_data);
+    records.extend(stream.finish());
+
+    let lines: Vec<usize> = records.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![2, 3, 4, 5]);
+    assert!(matches!(
+        records[0].1,
+        Err(ParserError::InvalidAmount { line: 2, .. })
+    ));
+    assert!(matches!(
+        records[1].1,
+        Err(ParserError::CsvError { line: 3, .. })
+    ));
+    assert!(matches!(
+        records[2].1,
+        Err(ParserError::CsvError { line: 4, .. })
+    ));
+    assert!(records[3].1.is_ok());
+}
+
+#[test]
+fn test_stream_large_file_uses_bounded_buffer() {
+    let rows = 100_000;
+    let chunk_size = 8 * 1024;
+    let mut csv_data = String::from("id,time,merchant,type,amount,card\n");
+    for row in 0..rows {
+        csv_data.push_str(&format!(
+            "ID{},2026-01-03 03:27:50,Merchant {},Purchase,-{}.99,2133\n",
+            row,
+            row % 100,
+            row % 500
+        ));
+    }
+
+    let mut stream = CsvRecordStream::new();
+    let mut parsed = 0;
+    let mut total = BigDecimal::from(0);
+    for chunk in csv_data.as_bytes().chunks(chunk_size) {
+        for (_, record) in stream.push(chunk) {
+            total += record.unwrap().amount;
+            parsed += 1;
+        }
+        // Only the partial record at the end of the chunk is kept
+        assert!(stream.buffered_len() < chunk_size);
+    }
+    for (_, record) in stream.finish() {
+        total += record.unwrap().amount;
+        parsed += 1;
+    }
+
+    assert_eq!(parsed, rows);
+    let expected: i64 = (0..rows as i64).map(|row| -(row % 500) * 100 - 99).sum();
+    assert_eq!(total, BigDecimal::new(expected.into(), 2));
+}
+
 #[test]
 fn test_parser_factory_csv() {
     let result = ParserFactory::get_parser(".csv");
@@ -0,0 +1,364 @@
+//! Integration tests for manual exchange rates.
+//!
+//! This module tests:
+//! - POST /api/v1/exchange-rates/custom - Set a manual rate
+//! - DELETE /api/v1/exchange-rates/custom - Remove a manual rate
+//! - Manual rates taking precedence over fetched rates
+//! - Conversions falling back to a manual rate for a pair no provider has
+//! - Manual rates only applying to the user who set them
+//! - The accounts write scope being required with an API key
+//!
+//! Fetched rates are shared by all users, so each test uses its own currency
+//! pair and days far in the past, and removes rates set for today.
+
+use crate::common::*;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{Duration, NaiveDate, Utc};
+use master_of_coin_backend::{
+    errors::ApiError,
+    models::{
+        ApiKeyScopes, CreateApiKeyRequest, CreateApiKeyResponse, CustomRateResponse,
+        HistoricalRateResponse, NewExchangeRateRecord, ScopePermission,
+    },
+    repositories,
+    services::exchange_rate_service::{ExchangeRateProvider, ExchangeRateService},
+    types::CurrencyCode,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const CUSTOM_PATH: &str = "/api/v1/exchange-rates/custom";
+
+/// Mocked provider serving `rates` from every base, or failing without any
+struct MockProvider {
+    rates: Option<HashMap<CurrencyCode, BigDecimal>>,
+}
+
+#[async_trait]
+impl ExchangeRateProvider for MockProvider {
+    async fn fetch_rates(
+        &self,
+        _base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.rates
+            .clone()
+            .ok_or_else(|| ApiError::External("Not available".to_string()))
+    }
+}
+
+/// A day in the 1980s no other test run is likely to use
+fn unique_day(timestamp: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(1980, 1, 1).unwrap() + Duration::days(timestamp.rem_euclid(3650))
+}
+
+/// Test setting, reading and removing a manual rate.
+///
+/// Verifies that:
+/// - The rate is stored for the requested day
+/// - GET /exchange-rates returns it for that day over a fetched rate
+/// - Once removed, the fetched rate is returned, and a second removal is a 404
+#[tokio::test]
+async fn test_custom_rate_lifecycle() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("custom_rate_{}", timestamp)).await;
+    let date = unique_day(timestamp);
+
+    let request = json!({"base": "AUD", "quote": "INR", "rate": "55.25", "date": date});
+    let response = post_authenticated(&server, CUSTOM_PATH, &auth.token, &request).await;
+    assert_status(&response, 201);
+    let created: CustomRateResponse = extract_json(response);
+    assert_eq!(created.base, CurrencyCode::Aud);
+    assert_eq!(created.quote, CurrencyCode::Inr);
+    assert_eq!(created.date, date);
+    assert_eq!(decimal(&created.rate), decimal("55.25"));
+
+    repositories::exchange_rate::upsert_rates(
+        &state.db,
+        vec![NewExchangeRateRecord {
+            base_currency: CurrencyCode::Aud,
+            quote_currency: CurrencyCode::Inr,
+            rate_date: date,
+            rate: decimal("50"),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let path = format!("/api/v1/exchange-rates?base=AUD&quote=INR&date={}", date);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let rate: HistoricalRateResponse = extract_json(response);
+    assert_eq!(rate.rate_date, date);
+    assert_eq!(decimal(&rate.rate), decimal("55.25"));
+
+    let delete_path = format!("{}?base=AUD&quote=INR&date={}", CUSTOM_PATH, date);
+    let response = delete_authenticated(&server, &delete_path, &auth.token).await;
+    assert_status(&response, 204);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let rate: HistoricalRateResponse = extract_json(response);
+    assert_eq!(decimal(&rate.rate), decimal("50"));
+
+    let response = delete_authenticated(&server, &delete_path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test that setting a manual rate for a day with a fetched rate keeps both.
+#[tokio::test]
+async fn test_custom_rate_replaces_fetched_rate() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth =
+        register_unique_test_user(&server, &format!("custom_rate_replace_{}", timestamp)).await;
+    let date = unique_day(timestamp);
+
+    repositories::exchange_rate::upsert_rates(
+        &state.db,
+        vec![NewExchangeRateRecord {
+            base_currency: CurrencyCode::Gbp,
+            quote_currency: CurrencyCode::Aud,
+            rate_date: date,
+            rate: decimal("1.9"),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let request = json!({"base": "GBP", "quote": "AUD", "rate": 2.05, "date": date});
+    let response = post_authenticated(&server, CUSTOM_PATH, &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let record = repositories::exchange_rate::find_manual_on_or_before(
+        &state.db,
+        auth.user.id,
+        CurrencyCode::Gbp,
+        CurrencyCode::Aud,
+        date,
+    )
+    .await
+    .unwrap()
+    .expect("The manual rate should be stored");
+    assert_eq!(record.rate_date, date);
+    assert_eq!(record.rate, decimal("2.05"));
+    let fetched = repositories::exchange_rate::find_on_or_before(
+        &state.db,
+        CurrencyCode::Gbp,
+        CurrencyCode::Aud,
+        date,
+    )
+    .await
+    .unwrap()
+    .expect("The fetched rate should be kept");
+    assert_eq!(fetched.rate, decimal("1.9"));
+
+    let delete_path = format!("{}?base=GBP&quote=AUD&date={}", CUSTOM_PATH, date);
+    let response = delete_authenticated(&server, &delete_path, &auth.token).await;
+    assert_status(&response, 204);
+}
+
+/// Test that conversions prefer manual rates over fetched ones.
+///
+/// Verifies that:
+/// - A manual rate set for today is used instead of the provider's
+/// - Without one, the provider's rate is used
+/// - When the provider fails, the latest earlier manual rate is used
+#[tokio::test]
+async fn test_conversions_prefer_manual_rates() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("custom_rate_conv_{}", timestamp)).await;
+
+    let request = json!({"base": "CAD", "quote": "JPY", "rate": "111.5"});
+    let response = post_authenticated(&server, CUSTOM_PATH, &auth.token, &request).await;
+    assert_status(&response, 201);
+    let created: CustomRateResponse = extract_json(response);
+    assert_eq!(created.date, Utc::now().date_naive());
+
+    let provider = Arc::new(MockProvider {
+        rates: Some(HashMap::from([(CurrencyCode::Jpy, decimal("100"))])),
+    });
+    let service = ExchangeRateService::with_provider(provider.clone())
+        .with_manual_rates(state.db.clone(), auth.user.id);
+    let rate = service
+        .get_rate(CurrencyCode::Cad, CurrencyCode::Jpy)
+        .await
+        .unwrap();
+    assert_eq!(rate.rate, decimal("111.5"));
+
+    let response = delete_authenticated(
+        &server,
+        &format!("{}?base=CAD&quote=JPY", CUSTOM_PATH),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 204);
+    let rate = service
+        .get_rate(CurrencyCode::Cad, CurrencyCode::Jpy)
+        .await
+        .unwrap();
+    assert_eq!(rate.rate, decimal("100"));
+
+    // No provider has the pair, so an earlier manual rate stands in
+    let date = unique_day(timestamp);
+    let request = json!({"base": "JPY", "quote": "CAD", "rate": "0.0089", "date": date});
+    let response = post_authenticated(&server, CUSTOM_PATH, &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let failing = ExchangeRateService::with_provider(Arc::new(MockProvider { rates: None }))
+        .with_manual_rates(state.db.clone(), auth.user.id);
+    let rate = failing
+        .get_rate(CurrencyCode::Jpy, CurrencyCode::Cad)
+        .await
+        .expect("The manual rate should be used");
+    assert_eq!(rate.rate, decimal("0.0089"));
+    let converted = failing
+        .convert_currency(&decimal("1000"), CurrencyCode::Jpy, CurrencyCode::Cad)
+        .await
+        .unwrap();
+    assert_eq!(converted, decimal("8.9"));
+
+    // Without a user, manual rates are not consulted
+    let without_db = ExchangeRateService::with_provider(Arc::new(MockProvider { rates: None }));
+    assert!(
+        without_db
+            .get_rate(CurrencyCode::Jpy, CurrencyCode::Cad)
+            .await
+            .is_err()
+    );
+
+    let delete_path = format!("{}?base=JPY&quote=CAD&date={}", CUSTOM_PATH, date);
+    let response = delete_authenticated(&server, &delete_path, &auth.token).await;
+    assert_status(&response, 204);
+}
+
+/// Test that invalid manual rates are rejected.
+///
+/// Verifies that:
+/// - Zero and negative rates return 422
+/// - The same currency as base and quote returns 422
+/// - An unknown currency returns 422 when setting and 400 when removing
+#[tokio::test]
+async fn test_custom_rate_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("custom_rate_val_{}", timestamp)).await;
+
+    for request in [
+        json!({"base": "USD", "quote": "EUR", "rate": "0"}),
+        json!({"base": "USD", "quote": "EUR", "rate": "-1.5"}),
+        json!({"base": "USD", "quote": "USD", "rate": "1"}),
+        json!({"base": "XYZ", "quote": "EUR", "rate": "1.5"}),
+    ] {
+        let response = post_authenticated(&server, CUSTOM_PATH, &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let response = delete_authenticated(
+        &server,
+        &format!("{}?base=XYZ&quote=EUR", CUSTOM_PATH),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 400);
+}
+
+/// Test that a manual rate only affects the conversions of the user who set it.
+///
+/// Verifies that:
+/// - The user who set it converts at the manual rate
+/// - Another user converts at the fetched rate, and can't remove the rate
+#[tokio::test]
+async fn test_custom_rate_is_per_user() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let owner = register_unique_test_user(&server, &format!("custom_rate_own_{}", timestamp)).await;
+    let other =
+        register_unique_test_user(&server, &format!("custom_rate_other_{}", timestamp)).await;
+    let date = unique_day(timestamp);
+
+    repositories::exchange_rate::upsert_rates(
+        &state.db,
+        vec![NewExchangeRateRecord {
+            base_currency: CurrencyCode::Inr,
+            quote_currency: CurrencyCode::Gbp,
+            rate_date: date,
+            rate: decimal("0.0095"),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let request = json!({"base": "INR", "quote": "GBP", "rate": "0.5", "date": date});
+    let response = post_authenticated(&server, CUSTOM_PATH, &owner.token, &request).await;
+    assert_status(&response, 201);
+
+    let path = format!("/api/v1/exchange-rates?base=INR&quote=GBP&date={}", date);
+    let response = get_authenticated(&server, &path, &owner.token).await;
+    assert_status(&response, 200);
+    let rate: HistoricalRateResponse = extract_json(response);
+    assert_eq!(decimal(&rate.rate), decimal("0.5"));
+
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 200);
+    let rate: HistoricalRateResponse = extract_json(response);
+    assert_eq!(decimal(&rate.rate), decimal("0.0095"));
+
+    let failing = ExchangeRateService::with_provider(Arc::new(MockProvider { rates: None }))
+        .with_manual_rates(state.db.clone(), other.user.id);
+    let converted = failing
+        .convert_currency_on(
+            &state.db,
+            &decimal("1000"),
+            CurrencyCode::Inr,
+            CurrencyCode::Gbp,
+            date,
+        )
+        .await
+        .unwrap();
+    assert_eq!(converted, decimal("9.5"));
+
+    let delete_path = format!("{}?base=INR&quote=GBP&date={}", CUSTOM_PATH, date);
+    let response = delete_authenticated(&server, &delete_path, &other.token).await;
+    assert_status(&response, 404);
+    let response = delete_authenticated(&server, &delete_path, &owner.token).await;
+    assert_status(&response, 204);
+}
+
+/// Test that API keys need the accounts write scope to set manual rates.
+#[tokio::test]
+async fn test_custom_rate_requires_write_scope() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth =
+        register_unique_test_user(&server, &format!("custom_rate_scope_{}", timestamp)).await;
+
+    let request = CreateApiKeyRequest {
+        name: "Read Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: vec![ScopePermission::Read, ScopePermission::Write],
+            accounts: vec![ScopePermission::Read],
+            budgets: vec![],
+            categories: vec![],
+            people: vec![],
+        },
+        expires_in_days: None,
+        requests_per_minute: None,
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+
+    let request = json!({"base": "USD", "quote": "EUR", "rate": "0.9"});
+    let response = post_authenticated(&server, CUSTOM_PATH, &api_key.key, &request).await;
+    assert_status(&response, 403);
+    let response = delete_authenticated(
+        &server,
+        &format!("{}?base=USD&quote=EUR", CUSTOM_PATH),
+        &api_key.key,
+    )
+    .await;
+    assert_status(&response, 403);
+}
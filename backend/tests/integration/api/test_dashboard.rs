@@ -1,15 +1,19 @@
 //! Integration tests for dashboard/analytics API endpoints.
 //!
-//! This module tests the dashboard endpoint:
+//! This module tests the dashboard endpoints:
 //! - GET /api/v1/dashboard - Get dashboard data with analytics
+//! - GET /api/v1/dashboard/net-worth, /recent, /budgets, /categories - Get one section
+//! - GET /api/v1/dashboard/merchants - Get spending grouped by merchant
 //!
 //! Tests cover:
 //! - Empty dashboard for new users
 //! - Dashboard with accounts showing total balance
 //! - Dashboard with transactions showing income/expense totals
 //! - Dashboard with recent transactions
-//! - Dashboard with category breakdown
+//! - Dashboard with category breakdown (converted or grouped per currency, with category icons and colors)
 //! - Dashboard with budget status and alerts
+//! - Each section fetched on its own, matching the summary
+//! - Merchant spending report
 //! - Data isolation between users
 //! - Full integration scenario with all features
 
@@ -526,6 +530,236 @@ async fn test_get_dashboard_category_breakdown() {
     );
 }
 
+/// Test that the category breakdown carries each category's icon and color, and that
+/// transactions without a category are counted.
+///
+/// Verifies that:
+/// - Breakdown and top spending items include the category's icon and color
+/// - Uncategorized spending has no icon or color
+/// - Totals and percentages are unchanged
+/// - `uncategorized_count` counts the transactions without a category
+#[tokio::test]
+async fn test_get_dashboard_category_breakdown_metadata() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("catmeta_{}", timestamp)).await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/categories",
+        &auth.token,
+        &json!({ "name": "Dining", "icon": "🍽️", "color": "#FF5722" }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let dining: Value = extract_json(response);
+    let dining_id = dining["id"].as_str().unwrap();
+
+    let account = create_test_account(&server, &auth.token, "Checking", "CHECKING", 1000.0).await;
+    let account_id = account["id"].as_str().unwrap();
+
+    create_test_transaction(
+        &server,
+        &auth.token,
+        account_id,
+        -75.0,
+        "Dinner",
+        Some(dining_id),
+        None,
+    )
+    .await;
+    create_test_transaction(&server, &auth.token, account_id, -25.0, "Misc", None, None).await;
+
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    assert_status(&response, 200);
+    let dashboard = extract_dashboard(response);
+
+    for section in ["category_breakdown", "top_spending_categories"] {
+        let items = dashboard[section].as_array().unwrap();
+        let dining = items
+            .iter()
+            .find(|c| c["category_id"].as_str() == Some(dining_id))
+            .expect("Should have Dining category");
+        assert_eq!(dining["category_name"], "Dining");
+        assert_eq!(dining["category_icon"], "🍽️");
+        assert_eq!(dining["category_color"], "#FF5722");
+        assert_eq!(
+            BigDecimal::from_str(dining["total"].as_str().unwrap()).unwrap(),
+            BigDecimal::from(75)
+        );
+        assert_eq!(dining["percentage"].as_f64().unwrap(), 75.0);
+
+        let uncategorized = items
+            .iter()
+            .find(|c| c["category_id"].is_null())
+            .expect("Should have uncategorized spending");
+        assert!(uncategorized["category_icon"].is_null());
+        assert!(uncategorized["category_color"].is_null());
+    }
+
+    // The opening balance and "Misc" have no category
+    assert_eq!(dashboard["uncategorized_count"], 2);
+}
+
+/// Test that the category breakdown reports its currency and conversion.
+///
+/// Verifies that:
+/// - The breakdown is converted into the primary currency by default
+/// - Each item reports its currency and that conversion was applied
+#[tokio::test]
+async fn test_get_dashboard_category_breakdown_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("catcur_{}", timestamp),
+        &format!("catcur_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Category Currency User",
+    )
+    .await;
+
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+    let account = create_test_account(&server, &auth.token, "Checking", "CHECKING", 1000.0).await;
+    create_test_transaction(
+        &server,
+        &auth.token,
+        account["id"].as_str().unwrap(),
+        -120.0,
+        "Grocery Shopping",
+        Some(groceries["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    assert_status(&response, 200);
+    let dashboard = extract_dashboard(response);
+
+    assert_eq!(dashboard["breakdown_currency"], "EUR");
+    let breakdown = dashboard["category_breakdown"].as_array().unwrap();
+    assert_eq!(breakdown.len(), 1);
+    assert_eq!(breakdown[0]["currency"], "EUR");
+    assert_eq!(breakdown[0]["converted"], true);
+    assert_eq!(
+        BigDecimal::from_str(breakdown[0]["total"].as_str().unwrap()).unwrap(),
+        BigDecimal::from_str("120").unwrap()
+    );
+}
+
+/// Test grouping the category breakdown by currency.
+///
+/// Verifies that:
+/// - Spending in different currencies is never summed together
+/// - Items are not converted and report their own currency
+/// - Percentages are relative to spending in the same currency
+#[tokio::test]
+async fn test_get_dashboard_category_breakdown_grouped_by_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("catgroup_{}", timestamp),
+        &format!("catgroup_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Category Grouping User",
+    )
+    .await;
+
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+    let groceries_id = groceries["id"].as_str().unwrap();
+    let utilities = create_test_category(&server, &auth.token, "Utilities").await;
+    let utilities_id = utilities["id"].as_str().unwrap();
+
+    let eur_account =
+        create_test_account(&server, &auth.token, "Euro Checking", "CHECKING", 1000.0).await;
+    let eur_account_id = eur_account["id"].as_str().unwrap();
+
+    let request = json!({
+        "name": "Yen Wallet",
+        "account_type": "CASH",
+        "currency": "JPY",
+        "initial_balance": 50000.0
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let jpy_account = extract_json::<Value>(response);
+    let jpy_account_id = jpy_account["id"].as_str().unwrap();
+
+    create_test_transaction(
+        &server,
+        &auth.token,
+        eur_account_id,
+        -30.0,
+        "Supermarket",
+        Some(groceries_id),
+        None,
+    )
+    .await;
+    create_test_transaction(
+        &server,
+        &auth.token,
+        eur_account_id,
+        -10.0,
+        "Water Bill",
+        Some(utilities_id),
+        None,
+    )
+    .await;
+    create_test_transaction(
+        &server,
+        &auth.token,
+        jpy_account_id,
+        -1000.0,
+        "Konbini",
+        Some(groceries_id),
+        None,
+    )
+    .await;
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard?group_by_currency=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let dashboard = extract_dashboard(response);
+
+    assert!(dashboard["breakdown_currency"].is_null());
+    let breakdown = dashboard["category_breakdown"].as_array().unwrap();
+    assert_eq!(breakdown.len(), 3);
+    assert!(breakdown.iter().all(|item| item["converted"] == false));
+
+    let find = |name: &str, currency: &str| {
+        breakdown
+            .iter()
+            .find(|c| c["category_name"] == name && c["currency"] == currency)
+            .unwrap_or_else(|| panic!("Should have {} in {}", name, currency))
+    };
+
+    let eur_groceries = find("Groceries", "EUR");
+    assert_eq!(
+        BigDecimal::from_str(eur_groceries["total"].as_str().unwrap()).unwrap(),
+        BigDecimal::from_str("30").unwrap()
+    );
+    let percentage = eur_groceries["percentage"].as_f64().unwrap();
+    assert!((percentage - 75.0).abs() < 0.01);
+
+    let jpy_groceries = find("Groceries", "JPY");
+    assert_eq!(
+        BigDecimal::from_str(jpy_groceries["total"].as_str().unwrap()).unwrap(),
+        BigDecimal::from_str("1000").unwrap()
+    );
+    let percentage = jpy_groceries["percentage"].as_f64().unwrap();
+    assert!((percentage - 100.0).abs() < 0.01);
+
+    find("Utilities", "EUR");
+}
+
 // ============================================================================
 // Dashboard with Budgets Tests
 // ============================================================================
@@ -618,6 +852,101 @@ async fn test_get_dashboard_with_budgets() {
     );
 }
 
+/// Test that pending transactions do not count toward a budget until posted.
+///
+/// Verifies that:
+/// - A pending transaction is excluded from budget spending
+/// - Posting the transaction adds it to budget spending
+#[tokio::test]
+async fn test_get_dashboard_budget_ignores_pending() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("pendingbudget_{}", timestamp)).await;
+
+    let category = create_test_category(&server, &auth.token, "Food").await;
+    let category_id = category["id"].as_str().unwrap();
+    let account = create_test_account(&server, &auth.token, "Checking", "CHECKING", 2000.0).await;
+    let account_id = account["id"].as_str().unwrap();
+
+    create_test_budget(
+        &server,
+        &auth.token,
+        "Food Budget",
+        Some(category_id),
+        500.0,
+    )
+    .await;
+
+    create_test_transaction(
+        &server,
+        &auth.token,
+        account_id,
+        -100.0,
+        "Groceries",
+        Some(category_id),
+        None,
+    )
+    .await;
+
+    let pending_request = json!({
+        "account_id": account_id,
+        "category_id": category_id,
+        "amount": -60.0,
+        "title": "Card Hold",
+        "date": Utc::now().to_rfc3339(),
+        "status": "pending"
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &pending_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let pending: Value = extract_json(response);
+    assert_eq!(pending["status"], "pending");
+
+    let current_spending = |dashboard: &Value| {
+        BigDecimal::from_str(
+            dashboard["budget_statuses"][0]["current_spending"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    assert_status(&response, 200);
+    let dashboard = extract_dashboard(response);
+    assert_eq!(
+        current_spending(&dashboard),
+        BigDecimal::from_str("100").unwrap(),
+        "Pending transaction should not count toward the budget"
+    );
+
+    let response = post_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions/{}/post",
+            pending["id"].as_str().unwrap()
+        ),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    let dashboard = extract_dashboard(response);
+    assert_eq!(
+        current_spending(&dashboard),
+        BigDecimal::from_str("160").unwrap(),
+        "Posted transaction should count toward the budget"
+    );
+}
+
 /// Test that dashboard shows over-budget warnings.
 ///
 /// Verifies that:
@@ -706,6 +1035,153 @@ async fn test_get_dashboard_budget_alerts() {
     );
 }
 
+// ============================================================================
+// Merchant Spending Tests
+// ============================================================================
+
+/// Test fetching each dashboard section on its own.
+///
+/// Verifies that:
+/// - Every section endpoint requires authentication
+/// - Each section returns the same data as the summary
+/// - The net worth section lists each account's balance
+/// - The category section takes the summary's currency parameters
+#[tokio::test]
+async fn test_get_dashboard_sections() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("sections_{}", timestamp)).await;
+
+    for path in [
+        "/api/v1/dashboard/net-worth",
+        "/api/v1/dashboard/recent",
+        "/api/v1/dashboard/budgets",
+        "/api/v1/dashboard/categories",
+    ] {
+        let response = get_unauthenticated(&server, path).await;
+        assert_status(&response, 401);
+    }
+
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+    let category_id = category["id"].as_str().unwrap();
+    let account = create_test_account(&server, &auth.token, "Checking", "CHECKING", 1000.0).await;
+    let account_id = account["id"].as_str().unwrap();
+    create_test_transaction(
+        &server,
+        &auth.token,
+        account_id,
+        -45.0,
+        "Market",
+        Some(category_id),
+        None,
+    )
+    .await;
+    create_test_transaction(&server, &auth.token, account_id, -5.0, "Coffee", None, None).await;
+    create_test_budget(&server, &auth.token, "Food", Some(category_id), 300.0).await;
+
+    let response = get_authenticated(&server, "/api/v1/dashboard", &auth.token).await;
+    assert_status(&response, 200);
+    let summary = extract_dashboard(response);
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/net-worth", &auth.token).await;
+    assert_status(&response, 200);
+    let net_worth: Value = extract_json(response);
+    assert_eq!(net_worth["total"], summary["net_worth"]);
+    let accounts = net_worth["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0]["account_id"], account["id"]);
+    assert_eq!(accounts[0]["account_name"], "Checking");
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/recent", &auth.token).await;
+    assert_status(&response, 200);
+    let recent: Value = extract_json(response);
+    assert_eq!(recent, summary["recent_transactions"]);
+    // Both transactions and the opening balance
+    assert_eq!(recent.as_array().unwrap().len(), 3);
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/budgets", &auth.token).await;
+    assert_status(&response, 200);
+    let budgets: Value = extract_json(response);
+    assert_eq!(budgets, summary["budget_statuses"]);
+    assert_eq!(budgets.as_array().unwrap().len(), 1);
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/categories", &auth.token).await;
+    assert_status(&response, 200);
+    let categories: Value = extract_json(response);
+    for field in [
+        "category_breakdown",
+        "top_spending_categories",
+        "uncategorized_count",
+        "breakdown_currency",
+    ] {
+        assert_eq!(categories[field], summary[field], "{} differs", field);
+    }
+    // Coffee and the opening balance
+    assert_eq!(categories["uncategorized_count"], 2);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/categories?group_by_currency=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let grouped: Value = extract_json(response);
+    assert!(grouped["breakdown_currency"].is_null());
+    assert_eq!(
+        grouped["category_breakdown"].as_array().unwrap().len(),
+        categories["category_breakdown"].as_array().unwrap().len()
+    );
+}
+
+/// Test the merchant spending report.
+///
+/// Verifies that:
+/// - Expenses are grouped by merchant case-insensitively, largest total first
+/// - Income and transactions without a merchant are not counted
+#[tokio::test]
+async fn test_get_merchant_spending() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("merchants_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking", "CHECKING", 500.0).await;
+    let account_id = account["id"].as_str().unwrap();
+
+    for (merchant, amount) in [
+        (Some("Corner Market"), -30.0),
+        (Some("corner market"), -12.5),
+        (Some("Blue Bottle"), -4.5),
+        (Some("Blue Bottle"), 4.5),
+        (None, -100.0),
+    ] {
+        let mut request = json!({
+            "account_id": account_id,
+            "amount": amount,
+            "title": "Purchase",
+            "date": Utc::now().to_rfc3339()
+        });
+        if let Some(merchant) = merchant {
+            request["merchant"] = json!(merchant);
+        }
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/merchants", &auth.token).await;
+    assert_status(&response, 200);
+    let report: Vec<Value> = extract_json(response);
+
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0]["merchant"], "corner market");
+    assert_eq!(report[0]["total"], "42.50");
+    assert_eq!(report[0]["currency"], "EUR");
+    assert_eq!(report[0]["transaction_count"], 2);
+    assert_eq!(report[1]["merchant"], "Blue Bottle");
+    assert_eq!(report[1]["total"], "4.50");
+    assert_eq!(report[1]["transaction_count"], 1);
+}
+
 // ============================================================================
 // Data Isolation Tests
 // ============================================================================
@@ -10,7 +10,7 @@ use master_of_coin_backend::{
     models::{NewTransaction, ParsedTransaction},
     schema::transactions,
     services::import_service,
-    types::ConfidenceLevel,
+    types::{ConfidenceLevel, TransactionStatus},
 };
 use serial_test::serial;
 use std::str::FromStr;
@@ -36,6 +36,17 @@ fn create_test_transaction_in_db(
         amount: BigDecimal::from_str(amount).unwrap(),
         date,
         notes: Some("Test transaction".to_string()),
+        external_id: None,
+        status: TransactionStatus::Posted,
+        original_currency: None,
+        original_amount: None,
+        exchange_rate: None,
+        merchant: None,
+        latitude: None,
+        longitude: None,
+        transfer_id: None,
+        reimbursable: false,
+        tags: Vec::new(),
     };
 
     diesel::insert_into(transactions::table)
@@ -79,6 +90,10 @@ fn test_duplicate_detection_high_confidence() {
         notes: Some("Statement ID: TEST123 | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -140,6 +155,10 @@ fn test_duplicate_detection_medium_confidence() {
         notes: Some("Statement ID: TEST456 | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -213,6 +232,10 @@ fn test_duplicate_detection_low_confidence_not_flagged() {
         notes: Some("Statement ID: TEST789 | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -269,6 +292,10 @@ fn test_no_duplicate_two_days_apart() {
         notes: Some("Statement ID: TEST2DAY | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -337,6 +364,10 @@ fn test_timezone_same_date_different_timezone() {
         notes: Some("Statement ID: TESTTZ | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -405,6 +436,10 @@ fn test_midnight_boundary_same_day() {
         notes: Some("Statement ID: TESTMID | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
@@ -456,6 +491,10 @@ fn test_no_duplicate_different_amount() {
         notes: Some("Statement ID: TEST999 | Card: •••• 2133".to_string()),
         original_currency: None,
         original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: None,
         is_valid: true,
         validation_errors: None,
         is_potential_duplicate: false,
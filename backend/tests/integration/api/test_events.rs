@@ -0,0 +1,250 @@
+//! Integration tests for change notifications.
+//!
+//! This module tests change events including:
+//! - GET /api/v1/events - Event stream authentication
+//! - Events published by transaction and budget endpoints
+//! - Events published when a budget alert fires
+//! - Per-user filtering, channel cleanup and lagged subscribers of the event bus
+
+use crate::common::*;
+use chrono::Utc;
+use master_of_coin_backend::{
+    models::{AccountResponse, BudgetAlertResponse, BudgetResponse, TransactionResponse},
+    services::event_service::{ChangeEvent, EventBus, StreamItem},
+};
+use serde_json::json;
+use std::time::Duration;
+use tokio_stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+/// Wait for the next item of an event stream, failing the test after a second
+async fn next_item(stream: &mut (impl Stream<Item = StreamItem> + Unpin)) -> StreamItem {
+    tokio::time::timeout(Duration::from_secs(1), stream.next())
+        .await
+        .expect("Timed out waiting for an event")
+        .expect("Event stream ended")
+}
+
+/// Test that the event stream requires authentication.
+#[tokio::test]
+async fn test_events_unauthorized() {
+    let server = create_test_server().await;
+
+    let response = get_unauthenticated(&server, "/api/v1/events").await;
+    assert_status(&response, 401);
+}
+
+/// Test that mutations publish change events for their user.
+///
+/// Verifies that:
+/// - Creating, updating and deleting a transaction publish the matching events
+/// - Creating and deleting a budget publish budget events
+/// - Another user's changes are not delivered
+#[tokio::test]
+async fn test_mutations_publish_events() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("events_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("eventsother_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let other_account = create_test_account(&server, &other.token, "Checking").await;
+
+    let mut events = Box::pin(state.events.subscribe(auth.user.id));
+
+    let other_request = json!({
+        "account_id": other_account.id,
+        "title": "Not mine",
+        "amount": -5.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &other.token,
+        &other_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Coffee",
+        "amount": -4.5,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::TransactionCreated {
+            transaction_id: transaction.id
+        })
+    );
+
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+    let response =
+        put_authenticated(&server, &path, &auth.token, &json!({ "title": "Espresso" })).await;
+    assert_status(&response, 200);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::TransactionUpdated {
+            transaction_id: transaction.id
+        })
+    );
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::TransactionDeleted {
+            transaction_id: transaction.id
+        })
+    );
+
+    let budget = json!({ "name": "Coffee", "filters": {} });
+    let response = post_authenticated(&server, "/api/v1/budgets", &auth.token, &budget).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::BudgetChanged {
+            budget_id: budget.id
+        })
+    );
+
+    let path = format!("/api/v1/budgets/{}", budget.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::BudgetDeleted {
+            budget_id: budget.id
+        })
+    );
+}
+
+/// Test that recording a budget alert publishes it.
+///
+/// Verifies that:
+/// - Computing a status that crosses a threshold publishes `budget_alert_fired`
+///   with the budget and the recorded alert
+/// - Computing the status again publishes nothing more
+#[tokio::test]
+async fn test_budget_alert_publishes_event() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("alertevents_{}", timestamp)).await;
+
+    // In the primary currency, so no exchange rates are needed
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let budget_request = json!({ "name": "Everything", "filters": {}, "alert_thresholds": [0.5] });
+    let response =
+        post_authenticated(&server, "/api/v1/budgets", &auth.token, &budget_request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+    let today = Utc::now().date_naive();
+    let range_request = json!({
+        "limit_amount": 100.0,
+        "period": "MONTHLY",
+        "start_date": (today - chrono::Duration::days(1)).to_string(),
+        "end_date": (today + chrono::Duration::days(30)).to_string()
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/budgets/{}/ranges", budget.id),
+        &auth.token,
+        &range_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Shopping",
+        "amount": -60.0,
+        "date": (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let mut events = Box::pin(state.events.subscribe(auth.user.id));
+    let status_path = format!("/api/v1/budgets/{}/status", budget.id);
+    for _ in 0..2 {
+        let response = get_authenticated(&server, &status_path, &auth.token).await;
+        assert_status(&response, 200);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/budgets/alerts", &auth.token).await;
+    assert_status(&response, 200);
+    let alerts: Vec<BudgetAlertResponse> = extract_json(response);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(
+        next_item(&mut events).await,
+        StreamItem::Event(ChangeEvent::BudgetAlertFired {
+            budget_id: budget.id,
+            alert_id: alerts[0].id
+        })
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err()
+    );
+}
+
+/// Test that a subscriber falling behind is told how many events it missed.
+#[tokio::test]
+async fn test_event_bus_lagged_subscriber() {
+    let bus = EventBus::new(2);
+    let user_id = Uuid::new_v4();
+    let mut events = Box::pin(bus.subscribe(user_id));
+
+    let budget_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    for budget_id in &budget_ids {
+        bus.publish(
+            user_id,
+            ChangeEvent::BudgetChanged {
+                budget_id: *budget_id,
+            },
+        );
+    }
+
+    assert_eq!(next_item(&mut events).await, StreamItem::Lagged(1));
+    for budget_id in &budget_ids[1..] {
+        assert_eq!(
+            next_item(&mut events).await,
+            StreamItem::Event(ChangeEvent::BudgetChanged {
+                budget_id: *budget_id
+            })
+        );
+    }
+}
+
+/// Test that a user's channel lives exactly as long as their streams.
+#[tokio::test]
+async fn test_event_bus_removes_channel_with_last_stream() {
+    let bus = EventBus::default();
+    let user_id = Uuid::new_v4();
+    assert!(!bus.has_subscribers(user_id));
+
+    let first = bus.subscribe(user_id);
+    let second = bus.subscribe(user_id);
+    assert!(bus.has_subscribers(user_id));
+
+    drop(first);
+    assert!(bus.has_subscribers(user_id));
+
+    drop(second);
+    assert!(!bus.has_subscribers(user_id));
+}
@@ -0,0 +1,106 @@
+//! Tests for single-flight coalescing of exchange rate fetches.
+//!
+//! These tests exercise [`RateFetchCoalescer`] with a mocked provider so they
+//! do not depend on the upstream exchange rate API.
+
+use bigdecimal::BigDecimal;
+use master_of_coin_backend::{
+    errors::ApiError, services::exchange_rate_service::RateFetchCoalescer, types::CurrencyCode,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Mocked provider: counts calls and returns fixed rates after a short delay
+async fn mock_fetch(
+    calls: Arc<AtomicUsize>,
+) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(HashMap::from([(CurrencyCode::Usd, BigDecimal::from(2))]))
+}
+
+/// Test that many concurrent lookups for the same base share one fetch.
+#[tokio::test]
+async fn test_concurrent_fetches_share_one_upstream_call() {
+    let coalescer = Arc::new(RateFetchCoalescer::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..50)
+        .map(|_| {
+            let coalescer = coalescer.clone();
+            let calls = calls.clone();
+            tokio::spawn(async move {
+                coalescer
+                    .fetch(CurrencyCode::Eur, || mock_fetch(calls))
+                    .await
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let rates = handle.await.unwrap().expect("Fetch should succeed");
+        assert_eq!(rates.get(&CurrencyCode::Usd), Some(&BigDecimal::from(2)));
+    }
+
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        1,
+        "Provider should be called once"
+    );
+}
+
+/// Test that different base currencies are fetched independently.
+#[tokio::test]
+async fn test_different_bases_are_not_coalesced() {
+    let coalescer = Arc::new(RateFetchCoalescer::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let (eur, usd) = tokio::join!(
+        coalescer.fetch(CurrencyCode::Eur, || mock_fetch(calls.clone())),
+        coalescer.fetch(CurrencyCode::Usd, || mock_fetch(calls.clone())),
+    );
+
+    assert!(eur.is_ok() && usd.is_ok());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+/// Test that a completed fetch is not reused by later calls.
+#[tokio::test]
+async fn test_sequential_fetches_are_not_coalesced() {
+    let coalescer = RateFetchCoalescer::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    coalescer
+        .fetch(CurrencyCode::Eur, || mock_fetch(calls.clone()))
+        .await
+        .expect("First fetch should succeed");
+    coalescer
+        .fetch(CurrencyCode::Eur, || mock_fetch(calls.clone()))
+        .await
+        .expect("Second fetch should succeed");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+/// Test that a failed fetch is reported to every waiter.
+#[tokio::test]
+async fn test_failed_fetch_is_shared() {
+    let coalescer = Arc::new(RateFetchCoalescer::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let failing = |calls: Arc<AtomicUsize>| async move {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Err::<HashMap<CurrencyCode, BigDecimal>, _>(ApiError::Internal)
+    };
+
+    let (first, second) = tokio::join!(
+        coalescer.fetch(CurrencyCode::Gbp, || failing(calls.clone())),
+        coalescer.fetch(CurrencyCode::Gbp, || failing(calls.clone())),
+    );
+
+    assert!(first.is_err() && second.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,126 @@
+//! Tests for currency conversion with a mocked exchange rate provider.
+//!
+//! These tests exercise [`ExchangeRateService`] through
+//! [`ExchangeRateService::with_provider`], directly and behind the conversion
+//! endpoint, so they do not depend on the upstream exchange rate API.
+
+use crate::common::*;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use master_of_coin_backend::{
+    errors::ApiError,
+    services::exchange_rate_service::{ExchangeRateProvider, ExchangeRateService},
+    types::CurrencyCode,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Mocked provider: counts calls and returns fixed rates for any base
+#[derive(Default)]
+struct MockProvider {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl ExchangeRateProvider for MockProvider {
+    async fn fetch_rates(
+        &self,
+        _base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(HashMap::from([(
+            CurrencyCode::Usd,
+            BigDecimal::from_str("1.0825").unwrap(),
+        )]))
+    }
+}
+
+/// Test that a rate is returned with the time it was fetched, and cached.
+#[tokio::test]
+async fn test_get_rate_returns_fetch_time_and_uses_cache() {
+    let provider = Arc::new(MockProvider::default());
+    let service = ExchangeRateService::with_provider(provider.clone());
+
+    let before = chrono::Utc::now();
+    let first = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(first.rate, BigDecimal::from_str("1.0825").unwrap());
+    let fetched_at = first.fetched_at.expect("Fetched rate should have a time");
+    assert!(fetched_at >= before);
+
+    // A second lookup is served from the cache, with the original fetch time
+    let second = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(second.fetched_at, Some(fetched_at));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    // Conversions share the same cache
+    let converted = service
+        .convert_currency(&BigDecimal::from(20), CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .expect("Conversion should succeed");
+    assert_eq!(converted, BigDecimal::from_str("21.65").unwrap());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+/// Test that converting to the same currency does not call the provider.
+#[tokio::test]
+async fn test_get_rate_same_currency() {
+    let provider = Arc::new(MockProvider::default());
+    let service = ExchangeRateService::with_provider(provider.clone());
+
+    let rate = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Eur)
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(rate.rate, BigDecimal::from(1));
+    assert_eq!(rate.fetched_at, None);
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+}
+
+/// Test that a currency missing from the provider's rates is an error.
+#[tokio::test]
+async fn test_get_rate_missing_currency() {
+    let service = ExchangeRateService::with_provider(Arc::new(MockProvider::default()));
+
+    let result = service.get_rate(CurrencyCode::Eur, CurrencyCode::Jpy).await;
+    assert!(matches!(result, Err(ApiError::Internal)));
+}
+
+/// Test that the conversion endpoint converts with the application's service.
+///
+/// Verifies that:
+/// - GET /exchange-rates/convert returns 200 with the mocked rate
+/// - The converted amount is rounded to the target currency's minor units
+/// - The rate is fetched from the application's provider
+#[tokio::test]
+async fn test_convert_endpoint_uses_app_service() {
+    let provider = Arc::new(MockProvider::default());
+    let server = create_test_server_with_exchange_rates(ExchangeRateService::with_provider(
+        provider.clone(),
+    ))
+    .await;
+    let timestamp = chrono::Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("convert_mock_{}", timestamp)).await;
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/exchange-rates/convert?from=EUR&to=USD&amount=20",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["from"], "EUR");
+    assert_eq!(body["to"], "USD");
+    assert_eq!(body["converted_amount"], "21.65");
+    assert_eq!(body["rate"], "1.0825");
+    assert!(body["fetched_at"].is_string());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
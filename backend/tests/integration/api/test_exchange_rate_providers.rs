@@ -0,0 +1,194 @@
+//! Tests for falling back between exchange rate providers.
+//!
+//! These tests exercise [`ExchangeRateProviderChain`] with mocked providers so
+//! they do not depend on any upstream exchange rate API. They cover:
+//! - Falling back to the next provider when one fails or has no rates
+//! - Falling back for a single currency the first provider lacks
+//! - Failing when no provider can serve the rates
+//! - Parsing provider names from the configuration
+
+use crate::common::decimal;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use master_of_coin_backend::{
+    config::ExchangeRateProviderKind,
+    errors::ApiError,
+    services::exchange_rate_service::{
+        ExchangeRateProvider, ExchangeRateProviderChain, ExchangeRateService,
+    },
+    types::CurrencyCode,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How a mocked provider answers
+enum Answer {
+    Fail,
+    Rates(HashMap<CurrencyCode, BigDecimal>),
+}
+
+/// Mocked provider answering current and historical fetches alike
+struct MockProvider {
+    answer: Answer,
+    calls: AtomicUsize,
+}
+
+impl MockProvider {
+    fn failing() -> Arc<Self> {
+        Self::answering(Answer::Fail)
+    }
+
+    fn serving(rates: &[(CurrencyCode, &str)]) -> Arc<Self> {
+        Self::answering(Answer::Rates(
+            rates
+                .iter()
+                .map(|(currency, rate)| (*currency, decimal(rate)))
+                .collect(),
+        ))
+    }
+
+    fn answering(answer: Answer) -> Arc<Self> {
+        Arc::new(Self {
+            answer,
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for MockProvider {
+    async fn fetch_rates(
+        &self,
+        _base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match &self.answer {
+            Answer::Fail => Err(ApiError::External("Provider is down".to_string())),
+            Answer::Rates(rates) => Ok(rates.clone()),
+        }
+    }
+
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        _date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.fetch_rates(base_currency).await
+    }
+}
+
+fn service_with(providers: Vec<Arc<dyn ExchangeRateProvider>>) -> ExchangeRateService {
+    ExchangeRateService::with_provider(Arc::new(ExchangeRateProviderChain::new(providers)))
+}
+
+/// Test that a failing primary provider falls back to the secondary.
+///
+/// Verifies that:
+/// - Current and historical rates come from the secondary
+/// - The primary is tried first each time
+/// - The served rates are cached, so the primary is not retried
+#[tokio::test]
+async fn test_failing_primary_falls_back_to_secondary() {
+    let primary = MockProvider::failing();
+    let secondary = MockProvider::serving(&[(CurrencyCode::Usd, "1.08")]);
+    let service = service_with(vec![primary.clone(), secondary.clone()]);
+
+    let rate = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .expect("The secondary should serve the rate");
+    assert_eq!(rate.rate, decimal("1.08"));
+    assert_eq!(primary.calls(), 1);
+    assert_eq!(secondary.calls(), 1);
+
+    service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .unwrap();
+    assert_eq!(primary.calls(), 1, "Served rates should be cached");
+
+    let chain = ExchangeRateProviderChain::new(vec![primary.clone(), secondary.clone()]);
+    let date = NaiveDate::from_ymd_opt(2020, 3, 2).unwrap();
+    let historical = chain
+        .fetch_historical_rates(CurrencyCode::Eur, date)
+        .await
+        .expect("The secondary should serve historical rates");
+    assert_eq!(historical[&CurrencyCode::Usd], decimal("1.08"));
+    let single = chain
+        .fetch_rate(CurrencyCode::Eur, CurrencyCode::Usd, date)
+        .await
+        .unwrap();
+    assert_eq!(single, decimal("1.08"));
+    assert_eq!(primary.calls(), 3);
+}
+
+/// Test that a provider returning nothing, or lacking a currency, falls back.
+///
+/// Verifies that:
+/// - An empty set of rates is passed over for the next provider's
+/// - A currency missing from the rates served is fetched from the next
+///   provider that has it
+#[tokio::test]
+async fn test_provider_without_rates_falls_back() {
+    let empty = MockProvider::serving(&[]);
+    let partial = MockProvider::serving(&[(CurrencyCode::Usd, "1.08")]);
+    let complete = MockProvider::serving(&[(CurrencyCode::Usd, "1.10"), (CurrencyCode::Inr, "90")]);
+    let service = service_with(vec![empty.clone(), partial.clone(), complete.clone()]);
+
+    let rate = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Usd)
+        .await
+        .unwrap();
+    assert_eq!(rate.rate, decimal("1.08"));
+    assert_eq!(complete.calls(), 0);
+
+    let rate = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Inr)
+        .await
+        .expect("The last provider should serve the missing currency");
+    assert_eq!(rate.rate, decimal("90"));
+    assert_eq!(complete.calls(), 1);
+}
+
+/// Test that the rate lookup fails when every provider fails.
+#[tokio::test]
+async fn test_all_providers_failing() {
+    let first = MockProvider::failing();
+    let second = MockProvider::failing();
+    let service = service_with(vec![first.clone(), second.clone()]);
+
+    let result = service.get_rate(CurrencyCode::Eur, CurrencyCode::Usd).await;
+    assert!(result.is_err());
+    assert_eq!(first.calls(), 1);
+    assert_eq!(second.calls(), 1);
+
+    // The same currency needs no provider
+    let same = service
+        .get_rate(CurrencyCode::Eur, CurrencyCode::Eur)
+        .await
+        .unwrap();
+    assert_eq!(same.rate, BigDecimal::from(1));
+}
+
+/// Test that provider names from `EXCHANGE_RATE_PROVIDERS` parse.
+#[test]
+fn test_provider_kind_from_str() {
+    assert_eq!(
+        ExchangeRateProviderKind::from_str(" Frankfurter ").unwrap(),
+        ExchangeRateProviderKind::Frankfurter
+    );
+    assert_eq!(
+        "exchangerate-api"
+            .parse::<ExchangeRateProviderKind>()
+            .unwrap(),
+        ExchangeRateProviderKind::ExchangeRateApi
+    );
+    assert!("fixer".parse::<ExchangeRateProviderKind>().is_err());
+}
@@ -0,0 +1,147 @@
+//! Tests for the background refresh of exchange rates.
+//!
+//! These tests exercise [`ExchangeRateService::refresh_rates_in_use`] with a
+//! mocked provider so they do not depend on the upstream exchange rate API.
+//! They cover:
+//! - Refreshing and storing the rates between account currencies
+//! - Skipping a base currency whose fetch fails
+
+use crate::common::*;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use master_of_coin_backend::{
+    errors::ApiError,
+    models::AccountResponse,
+    repositories,
+    services::exchange_rate_service::{
+        ExchangeRateProvider, ExchangeRateService, PRIMARY_CURRENCY,
+    },
+    types::CurrencyCode,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Mocked provider: the same rate to every currency, failing for `failing`
+struct MockProvider {
+    failing: Option<CurrencyCode>,
+    calls: AtomicUsize,
+}
+
+impl MockProvider {
+    fn new(failing: Option<CurrencyCode>) -> Arc<Self> {
+        Arc::new(Self {
+            failing,
+            calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for MockProvider {
+    async fn fetch_rates(
+        &self,
+        base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.failing == Some(base_currency) {
+            return Err(ApiError::External("Not available".to_string()));
+        }
+        Ok(CurrencyCode::ALL
+            .into_iter()
+            .map(|quote| (quote, decimal("1.2345")))
+            .collect())
+    }
+}
+
+async fn create_account_in(
+    server: &axum_test::TestServer,
+    token: &str,
+    currency: CurrencyCode,
+) -> AccountResponse {
+    let request = json!({
+        "name": format!("{} Account", currency.as_str()),
+        "account_type": "CHECKING",
+        "currency": currency
+    });
+    let response = post_authenticated(server, "/api/v1/accounts", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test that the rates between account currencies are refreshed and stored.
+///
+/// Verifies that:
+/// - Pairs between account currencies and the primary currency are refreshed
+///   both ways, and pairs of a currency with itself are not
+/// - Today's rate is stored for the refreshed pairs
+/// - Refreshed rates are served from the cache afterwards
+#[tokio::test]
+async fn test_refresh_rates_in_use() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("rate_refresh_{}", timestamp)).await;
+    create_account_in(&server, &auth.token, CurrencyCode::Gbp).await;
+    create_account_in(&server, &auth.token, CurrencyCode::Cad).await;
+
+    let provider = MockProvider::new(None);
+    let service = ExchangeRateService::with_provider(provider.clone());
+    let refreshed = service
+        .refresh_rates_in_use(&state.db)
+        .await
+        .expect("Refresh should succeed");
+
+    for pair in [
+        (CurrencyCode::Gbp, PRIMARY_CURRENCY),
+        (PRIMARY_CURRENCY, CurrencyCode::Gbp),
+        (CurrencyCode::Gbp, CurrencyCode::Cad),
+        (CurrencyCode::Cad, CurrencyCode::Gbp),
+    ] {
+        assert!(refreshed.contains(&pair), "{:?} should be refreshed", pair);
+    }
+    assert!(refreshed.iter().all(|(base, quote)| base != quote));
+
+    let today = Utc::now().date_naive();
+    let stored = repositories::exchange_rate::find_on_or_before(
+        &state.db,
+        CurrencyCode::Gbp,
+        CurrencyCode::Cad,
+        today,
+    )
+    .await
+    .unwrap()
+    .expect("Refreshed rate should be stored");
+    assert_eq!(stored.rate_date, today);
+
+    let calls = provider.calls.load(Ordering::SeqCst);
+    let rate = service
+        .get_rate(CurrencyCode::Gbp, CurrencyCode::Cad)
+        .await
+        .unwrap();
+    assert_eq!(rate.rate, decimal("1.2345"));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), calls);
+}
+
+/// Test that a base currency whose fetch fails is skipped without stopping
+/// the refresh of the others.
+#[tokio::test]
+async fn test_refresh_rates_in_use_skips_failing_base() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth =
+        register_unique_test_user(&server, &format!("rate_refresh_fail_{}", timestamp)).await;
+    create_account_in(&server, &auth.token, CurrencyCode::Jpy).await;
+    create_account_in(&server, &auth.token, CurrencyCode::Aud).await;
+
+    let service = ExchangeRateService::with_provider(MockProvider::new(Some(CurrencyCode::Jpy)));
+    let refreshed = service
+        .refresh_rates_in_use(&state.db)
+        .await
+        .expect("A failing base should not fail the refresh");
+
+    assert!(refreshed.iter().all(|(base, _)| *base != CurrencyCode::Jpy));
+    assert!(refreshed.contains(&(CurrencyCode::Aud, CurrencyCode::Jpy)));
+    assert!(refreshed.contains(&(PRIMARY_CURRENCY, CurrencyCode::Jpy)));
+}
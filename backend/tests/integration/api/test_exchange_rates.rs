@@ -2,6 +2,7 @@
 //!
 //! This module tests the exchange rates endpoint:
 //! - GET /api/v1/exchange-rates - Get current exchange rates
+//! - GET /api/v1/exchange-rates/convert - Preview a currency conversion
 //!
 //! Tests cover:
 //! - Successful retrieval of exchange rates with default base currency
@@ -9,6 +10,8 @@
 //! - Response format validation
 //! - Authentication requirement
 //! - Supported currency codes
+//! - Conversion preview validation (conversion itself is covered with a mocked
+//!   provider in `test_exchange_rate_conversion`)
 
 use crate::common::*;
 use serde_json::Value;
@@ -360,3 +363,57 @@ async fn test_exchange_rates_sanity_check() {
         );
     }
 }
+
+// ============================================================================
+// Conversion Preview Tests
+// ============================================================================
+
+/// Test that conversion previews validate their parameters.
+///
+/// Verifies that:
+/// - Unknown currencies are rejected with 400
+/// - Missing, non-numeric, zero and negative amounts are rejected with 400
+/// - Unauthenticated requests are rejected with 401
+#[tokio::test]
+async fn test_convert_rejects_invalid_parameters() {
+    let server = create_test_server().await;
+    let timestamp = chrono::Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("convertuser_{}", timestamp),
+        &format!("convert_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Conversion User",
+    )
+    .await;
+
+    for query in [
+        "from=XYZ&to=USD&amount=20",
+        "from=EUR&to=usd1&amount=20",
+        "from=EUR&to=USD",
+        "from=EUR&to=USD&amount=abc",
+        "from=EUR&to=USD&amount=0",
+        "from=EUR&to=USD&amount=-5",
+    ] {
+        let response = get_authenticated(
+            &server,
+            &format!("/api/v1/exchange-rates/convert?{}", query),
+            &auth.token,
+        )
+        .await;
+        assert_eq!(
+            response.status_code(),
+            400,
+            "Query {} should be rejected",
+            query
+        );
+    }
+
+    let response = get_unauthenticated(
+        &server,
+        "/api/v1/exchange-rates/convert?from=EUR&to=USD&amount=20",
+    )
+    .await;
+    assert_status(&response, 401);
+}
@@ -0,0 +1,215 @@
+//! Tests for exchange rates stored per day.
+//!
+//! These tests exercise [`ExchangeRateService::get_rate_on`] with a mocked
+//! provider so they do not depend on the upstream exchange rate API. They
+//! cover:
+//! - Fetching and storing the rates of a day on a cache miss
+//! - Falling back to the nearest earlier stored rate
+//! - GET /api/v1/exchange-rates query validation
+
+use crate::common::*;
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use master_of_coin_backend::{
+    DbPool,
+    errors::ApiError,
+    models::NewExchangeRateRecord,
+    repositories,
+    schema::exchange_rates,
+    services::exchange_rate_service::{ExchangeRateProvider, ExchangeRateService},
+    types::CurrencyCode,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Mocked provider: counts historical fetches, which fail unless `historical`
+struct MockProvider {
+    historical: bool,
+    calls: AtomicUsize,
+}
+
+impl MockProvider {
+    fn new(historical: bool) -> Arc<Self> {
+        Arc::new(Self {
+            historical,
+            calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for MockProvider {
+    async fn fetch_rates(
+        &self,
+        _base_currency: CurrencyCode,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        Ok(HashMap::from([(CurrencyCode::Inr, decimal("100"))]))
+    }
+
+    async fn fetch_historical_rates(
+        &self,
+        base_currency: CurrencyCode,
+        _date: NaiveDate,
+    ) -> Result<HashMap<CurrencyCode, BigDecimal>, ApiError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if !self.historical {
+            return Err(ApiError::External("Not available".to_string()));
+        }
+        Ok(HashMap::from([
+            (base_currency, BigDecimal::from(1)),
+            (CurrencyCode::Inr, decimal("70.5")),
+        ]))
+    }
+}
+
+fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Remove rates stored by earlier runs for a base currency in the 1990s
+fn clear_rates(pool: &DbPool, base: CurrencyCode) {
+    let mut conn = pool.get().unwrap();
+    diesel::delete(
+        exchange_rates::table
+            .filter(exchange_rates::base_currency.eq(base))
+            .filter(exchange_rates::rate_date.lt(day(2000, 1, 1))),
+    )
+    .execute(&mut conn)
+    .unwrap();
+}
+
+/// Test that a day without a stored rate is fetched once and then stored.
+///
+/// Verifies that:
+/// - The rate is for the requested day
+/// - The fetched rates are stored, except the base currency's rate to itself
+/// - A second lookup is served from the stored rate
+#[tokio::test]
+async fn test_get_rate_on_fetches_and_stores_rates() {
+    let (_server, state) = create_test_server_with_state().await;
+    clear_rates(&state.db, CurrencyCode::Gbp);
+
+    let provider = MockProvider::new(true);
+    let service = ExchangeRateService::with_provider(provider.clone());
+    let date = day(1999, 6, 1);
+
+    let rate = service
+        .get_rate_on(&state.db, CurrencyCode::Gbp, CurrencyCode::Inr, date)
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(rate.rate, decimal("70.5"));
+    assert_eq!(rate.date, date);
+    assert!(rate.fetched_at.is_some());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    let stored = repositories::exchange_rate::find_on_or_before(
+        &state.db,
+        CurrencyCode::Gbp,
+        CurrencyCode::Inr,
+        date,
+    )
+    .await
+    .unwrap()
+    .expect("Fetched rate should be stored");
+    assert_eq!(stored.rate_date, date);
+    let itself = repositories::exchange_rate::find_on_or_before(
+        &state.db,
+        CurrencyCode::Gbp,
+        CurrencyCode::Gbp,
+        date,
+    )
+    .await
+    .unwrap();
+    assert!(itself.is_none());
+
+    let again = service
+        .get_rate_on(&state.db, CurrencyCode::Gbp, CurrencyCode::Inr, date)
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(again.rate, decimal("70.5"));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    let converted = service
+        .convert_currency_on(
+            &state.db,
+            &BigDecimal::from(2),
+            CurrencyCode::Gbp,
+            CurrencyCode::Inr,
+            date,
+        )
+        .await
+        .unwrap();
+    assert_eq!(converted, decimal("141.0"));
+}
+
+/// Test that the nearest earlier stored rate is used when the provider cannot
+/// serve a day.
+///
+/// Verifies that:
+/// - The stored rate's day is returned as `date`
+/// - A currency's rate to itself is 1 without calling the provider
+#[tokio::test]
+async fn test_get_rate_on_falls_back_to_earlier_rate() {
+    let (_server, state) = create_test_server_with_state().await;
+    clear_rates(&state.db, CurrencyCode::Jpy);
+
+    repositories::exchange_rate::upsert_rates(
+        &state.db,
+        vec![NewExchangeRateRecord {
+            base_currency: CurrencyCode::Jpy,
+            quote_currency: CurrencyCode::Inr,
+            rate_date: day(1999, 3, 1),
+            rate: decimal("0.36"),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let provider = MockProvider::new(false);
+    let service = ExchangeRateService::with_provider(provider.clone());
+
+    let rate = service
+        .get_rate_on(
+            &state.db,
+            CurrencyCode::Jpy,
+            CurrencyCode::Inr,
+            day(1999, 3, 15),
+        )
+        .await
+        .expect("Rate lookup should succeed");
+    assert_eq!(rate.rate, decimal("0.36"));
+    assert_eq!(rate.date, day(1999, 3, 1));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    let rate = service
+        .get_rate_on(
+            &state.db,
+            CurrencyCode::Jpy,
+            CurrencyCode::Jpy,
+            day(1999, 3, 15),
+        )
+        .await
+        .unwrap();
+    assert_eq!(rate.rate, BigDecimal::from(1));
+    assert_eq!(rate.fetched_at, None);
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+/// Test that invalid historical rate queries are rejected with 400.
+#[tokio::test]
+async fn test_get_historical_rate_invalid_query() {
+    let server = create_test_server().await;
+    let timestamp = chrono::Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ratehist_{}", timestamp)).await;
+
+    for path in [
+        "/api/v1/exchange-rates?base=USD&date=2023-06-01",
+        "/api/v1/exchange-rates?base=USD&quote=EUR&date=2023-13-01",
+    ] {
+        let response = get_authenticated(&server, path, &auth.token).await;
+        assert_status(&response, 400);
+    }
+}
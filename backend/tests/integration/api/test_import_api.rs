@@ -8,7 +8,10 @@ use serde_json::json;
 #[path = "../common/mod.rs"]
 mod common;
 
-use common::{auth_helpers::register_unique_test_user, test_server::create_test_server};
+use common::{
+    auth_helpers::{create_test_account, create_test_category, register_unique_test_user},
+    test_server::create_test_server,
+};
 
 #[tokio::test]
 async fn test_import_parse_requires_authentication() {
@@ -145,3 +148,736 @@ TEST123,2026-01-03 03:27:50,Amazon,Purchase,\xE2\x82\xAC-10.00,\xE2\x80\xA2\xE2\
     // Should return not found
     assert_eq!(response.status_code(), 404);
 }
+
+/// Upload a CSV for parsing, with optional extra multipart text fields
+async fn parse_csv(
+    server: &axum_test::TestServer,
+    token: &str,
+    account_id: &str,
+    csv_content: &[u8],
+    fields: &[(&str, &str)],
+) -> axum_test::TestResponse {
+    let file_part = Part::bytes(csv_content.to_vec())
+        .file_name("statement.csv")
+        .mime_type("text/csv");
+
+    let mut form = MultipartForm::new().add_part("account_id", Part::text(account_id.to_string()));
+    for (name, value) in fields {
+        form = form.add_part(*name, Part::text(value.to_string()));
+    }
+    let form = form.add_part("file", file_part);
+
+    server
+        .post("/api/v1/transactions/import/parse")
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .multipart(form)
+        .await
+}
+
+#[tokio::test]
+async fn test_import_parse_resolves_names() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("names_{}", timestamp)).await;
+
+    let default_account = create_test_account(&server, &auth.token, "Checking").await;
+    let joint_account = create_test_account(&server, &auth.token, "Joint Account").await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+
+    let csv_content = b"id,time,merchant,type,amount,card,category,account
+TEST1,2026-01-03 03:27:50,Tesco,Purchase,\xE2\x82\xAC-40.00,2133,groceries,JOINT ACCOUNT
+TEST2,2026-01-04 03:27:50,Cinema,Purchase,\xE2\x82\xAC-12.00,2133,Fun,
+TEST3,2026-01-05 03:27:50,Cafe,Purchase,\xE2\x82\xAC-4.00,2133,,";
+
+    // Unknown categories mark the row invalid unless they may be created
+    let response = parse_csv(
+        &server,
+        &auth.token,
+        &default_account.id.to_string(),
+        csv_content,
+        &[],
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let parse_response: serde_json::Value = response.json();
+    let transactions = &parse_response["data"]["transactions"];
+    assert_eq!(transactions[0]["category_id"], json!(groceries.id));
+    assert_eq!(transactions[0]["account_id"], json!(joint_account.id));
+    assert_eq!(transactions[1]["is_valid"], false);
+    assert_eq!(transactions[1]["category_id"], serde_json::Value::Null);
+    assert_eq!(transactions[2]["account_id"], serde_json::Value::Null);
+    assert_eq!(parse_response["data"]["summary"]["invalid"], 1);
+
+    let response = parse_csv(
+        &server,
+        &auth.token,
+        &default_account.id.to_string(),
+        csv_content,
+        &[("auto_create_categories", "true")],
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let parse_response: serde_json::Value = response.json();
+    let transactions = &parse_response["data"]["transactions"];
+    assert_eq!(transactions[1]["is_valid"], true);
+    let created_id = transactions[1]["category_id"].as_str().unwrap().to_string();
+
+    // The created category is reused by later imports
+    let response = parse_csv(
+        &server,
+        &auth.token,
+        &default_account.id.to_string(),
+        csv_content,
+        &[("auto_create_categories", "true")],
+    )
+    .await;
+    let parse_response: serde_json::Value = response.json();
+    assert_eq!(
+        parse_response["data"]["transactions"][1]["category_id"],
+        json!(created_id)
+    );
+}
+
+#[tokio::test]
+async fn test_import_parse_unknown_account_name() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("unk_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let csv_content = b"id,time,merchant,type,amount,card,category,account
+TEST1,2026-01-03 03:27:50,Tesco,Purchase,\xE2\x82\xAC-40.00,2133,Groceries,Checking
+TEST2,2026-01-04 03:27:50,Cinema,Purchase,\xE2\x82\xAC-12.00,2133,Fun,Holiday Fund";
+
+    let response = parse_csv(
+        &server,
+        &auth.token,
+        &account.id.to_string(),
+        csv_content,
+        &[("auto_create_categories", "true")],
+    )
+    .await;
+
+    // The whole import fails, naming the offending line
+    assert_eq!(response.status_code(), 422);
+    let body: serde_json::Value = response.json();
+    let error = body["error"].as_str().unwrap();
+    assert!(error.contains("line 3"));
+    assert!(error.contains("Holiday Fund"));
+
+    // No categories were created for the failed import
+    let response = server
+        .get("/api/v1/categories")
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", auth.token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .await;
+    let categories: serde_json::Value = response.json();
+    assert!(
+        categories
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|category| category["name"] != "Fun")
+    );
+}
+
+async fn import_csv(
+    server: &axum_test::TestServer,
+    token: &str,
+    account_id: &str,
+    csv_content: Vec<u8>,
+    fields: &[(&str, &str)],
+) -> axum_test::TestResponse {
+    post_import(
+        server,
+        "/api/v1/transactions/import",
+        token,
+        account_id,
+        csv_content,
+        fields,
+    )
+    .await
+}
+
+async fn post_import(
+    server: &axum_test::TestServer,
+    path: &str,
+    token: &str,
+    account_id: &str,
+    csv_content: Vec<u8>,
+    fields: &[(&str, &str)],
+) -> axum_test::TestResponse {
+    let file_part = Part::bytes(csv_content)
+        .file_name("statement.csv")
+        .mime_type("text/csv");
+
+    let mut form = MultipartForm::new().add_part("account_id", Part::text(account_id.to_string()));
+    for (name, value) in fields {
+        form = form.add_part(*name, Part::text(value.to_string()));
+    }
+    let form = form.add_part("file", file_part);
+
+    server
+        .post(path)
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .multipart(form)
+        .await
+}
+
+async fn create_eur_account(server: &axum_test::TestServer, token: &str) -> String {
+    let response = server
+        .post("/api/v1/accounts")
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .json(&json!({
+            "name": "Statement Account",
+            "account_type": "CHECKING",
+            "currency": "EUR",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let account: serde_json::Value = response.json();
+    account["id"].as_str().unwrap().to_string()
+}
+
+/// Generate a statement with `rows` rows alternating between a refund of 10.00
+/// and a purchase of 5.00, optionally with an invalid amount on one row
+fn generate_statement(rows: usize, invalid_row: Option<usize>) -> Vec<u8> {
+    let mut csv = String::from("id,time,merchant,type,amount,card\n");
+    for row in 1..=rows {
+        let (kind, amount) = match row {
+            _ if Some(row) == invalid_row => ("Purchase", "\u{20AC}abc"),
+            _ if row % 2 == 1 => ("Refund", "\u{20AC}10.00"),
+            _ => ("Purchase", "\u{20AC}-5.00"),
+        };
+        csv.push_str(&format!(
+            "ROW{},2026-01-03 03:27:50,Merchant {},{},{},2133\n",
+            row,
+            row % 50,
+            kind,
+            amount
+        ));
+    }
+    csv.into_bytes()
+}
+
+#[tokio::test]
+async fn test_import_csv_large_file_in_batches() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("big_{}", timestamp)).await;
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let rows = 5_500;
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        generate_statement(rows, None),
+        &[],
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+
+    // Five full batches of 1000 rows and a last one of 500
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["total"], rows);
+    assert_eq!(body["data"]["created"], rows);
+    assert_eq!(body["data"]["failed"], 0);
+    assert_eq!(body["data"]["batches"], 6);
+    assert_eq!(body["data"]["aborted"], false);
+    assert!(body["data"].get("errors").is_none());
+
+    // Every row was saved exactly once
+    let response = server
+        .get(&format!(
+            "/api/v1/transactions?account_id={}&limit=1",
+            account_id
+        ))
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", auth.token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.header("X-Total-Count"), rows.to_string());
+
+    let response = server
+        .get(&format!("/api/v1/accounts/{}", account_id))
+        .add_header(
+            "Authorization".parse::<http::HeaderName>().unwrap(),
+            format!("Bearer {}", auth.token)
+                .parse::<http::HeaderValue>()
+                .unwrap(),
+        )
+        .await;
+    let account: serde_json::Value = response.json();
+    assert_eq!(account["balance"].as_f64().unwrap(), 2_750.0 * 5.0);
+}
+
+#[tokio::test]
+async fn test_import_csv_failed_batch_abort_or_continue() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("batch_{}", timestamp)).await;
+
+    // Row 1500 (line 1501) fails the second batch, lines 1002-2001
+    let statement = generate_statement(2_500, Some(1_500));
+
+    let account_id = create_eur_account(&server, &auth.token).await;
+    let response = import_csv(&server, &auth.token, &account_id, statement.clone(), &[]).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["data"]["created"], 1_000);
+    assert_eq!(body["data"]["failed"], 1_000);
+    assert_eq!(body["data"]["batches"], 1);
+    assert_eq!(body["data"]["aborted"], true);
+    let error = &body["data"]["errors"][0];
+    assert_eq!(error["batch"], 2);
+    assert_eq!(error["first_line"], 1_002);
+    assert_eq!(error["last_line"], 2_001);
+    assert!(error["errors"][0].as_str().unwrap().contains("line 1501"));
+
+    let account_id = create_eur_account(&server, &auth.token).await;
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        statement,
+        &[("on_error", "continue")],
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["data"]["total"], 2_500);
+    assert_eq!(body["data"]["created"], 1_500);
+    assert_eq!(body["data"]["failed"], 1_000);
+    assert_eq!(body["data"]["batches"], 2);
+    assert_eq!(body["data"]["aborted"], false);
+    assert_eq!(body["data"]["errors"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_import_csv_rejects_invalid_requests() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("bad_{}", timestamp)).await;
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        generate_statement(10, None),
+        &[("on_error", "retry")],
+    )
+    .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        b"id,time,merchant,type,amount,card\n".to_vec(),
+        &[],
+    )
+    .await;
+    assert_eq!(response.status_code(), 422);
+
+    // The account is checked before anything is imported
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &uuid::Uuid::new_v4().to_string(),
+        generate_statement(10, None),
+        &[],
+    )
+    .await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_import_csv_dry_run_matches_import() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("dry_{}", timestamp)).await;
+    let authorization = format!("Bearer {}", auth.token);
+    let account_id = create_eur_account(&server, &auth.token).await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+    let coffee = create_test_category(&server, &auth.token, "Coffee").await;
+
+    let response = server
+        .post("/api/v1/category-rules")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({ "category_id": coffee.id, "match_type": "CONTAINS", "pattern": "cafe" }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    // An existing transaction the first row duplicates
+    let response = server
+        .post("/api/v1/transactions")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({
+            "account_id": account_id,
+            "title": "Tesco",
+            "amount": -40.0,
+            "date": "2026-01-03T03:27:50Z",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    let csv_content = b"id,time,merchant,type,amount,card,category,account
+TEST1,2026-01-03 03:27:50,Tesco,Purchase,\xE2\x82\xAC-40.00,2133,groceries,
+TEST2,2026-01-04 03:27:50,Cinema,Purchase,\xE2\x82\xAC-12.00,2133,Fun,
+TEST3,2026-01-05 03:27:50,Corner Cafe,Purchase,\xE2\x82\xAC-4.00,2133,,"
+        .to_vec();
+    // Forced, so the duplicate is flagged in the preview rather than skipped
+    let fields = [("auto_create_categories", "true"), ("force", "true")];
+
+    let response = post_import(
+        &server,
+        "/api/v1/transactions/import?dry_run=true",
+        &auth.token,
+        &account_id,
+        csv_content.clone(),
+        &fields,
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let dry_run: serde_json::Value = response.json();
+    assert_eq!(dry_run["success"], true);
+    assert_eq!(dry_run["data"]["created"], 3);
+
+    let preview = &dry_run["data"]["preview"];
+    let columns = preview["columns"].as_array().unwrap();
+    assert_eq!(columns.len(), 8);
+    assert_eq!(columns[2]["header"], "merchant");
+    assert_eq!(columns[4]["field"], "amount");
+    assert_eq!(columns[7]["field"], "account");
+
+    let transactions = preview["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[0]["line"], 2);
+    assert_eq!(transactions[0]["category_id"], json!(groceries.id));
+    assert_eq!(transactions[0]["category_source"], "file");
+    assert_eq!(transactions[0]["account_id"], json!(account_id));
+    assert_eq!(transactions[0]["is_potential_duplicate"], true);
+    assert_eq!(transactions[1]["category_id"], serde_json::Value::Null);
+    assert_eq!(transactions[1]["category_source"], "created");
+    assert_eq!(transactions[1]["is_potential_duplicate"], false);
+    assert_eq!(transactions[2]["category_id"], json!(coffee.id));
+    assert_eq!(transactions[2]["category_source"], "rule");
+
+    // Nothing was saved and no category was created
+    let response = server
+        .get(&format!("/api/v1/transactions?account_id={}", account_id))
+        .add_header("Authorization", authorization.clone())
+        .await;
+    assert_eq!(response.header("X-Total-Count"), "1");
+    let response = server
+        .get("/api/v1/categories")
+        .add_header("Authorization", authorization.clone())
+        .await;
+    let categories: serde_json::Value = response.json();
+    assert!(
+        categories
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|category| category["name"] != "Fun")
+    );
+
+    // Importing the same file gives the same result
+    let response = import_csv(&server, &auth.token, &account_id, csv_content, &fields).await;
+    assert_eq!(response.status_code(), 200);
+    let imported: serde_json::Value = response.json();
+    let mut expected = dry_run["data"].clone();
+    expected.as_object_mut().unwrap().remove("preview");
+    assert_eq!(imported["data"], expected);
+
+    let response = server
+        .get(&format!(
+            "/api/v1/transactions?account_id={}&category_id={}",
+            account_id, coffee.id
+        ))
+        .add_header("Authorization", authorization)
+        .await;
+    let categorized: serde_json::Value = response.json();
+    assert_eq!(categorized.as_array().unwrap().len(), 1);
+    assert_eq!(categorized[0]["title"], "Corner Cafe");
+}
+
+#[tokio::test]
+async fn test_import_csv_dry_run_reports_failed_batches() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("dryfail_{}", timestamp)).await;
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let statement = generate_statement(2_500, Some(1_500));
+    let fields = [("on_error", "continue")];
+    let response = post_import(
+        &server,
+        "/api/v1/transactions/import?dry_run=true",
+        &auth.token,
+        &account_id,
+        statement.clone(),
+        &fields,
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let mut dry_run: serde_json::Value = response.json();
+    assert_eq!(dry_run["success"], false);
+    assert_eq!(
+        dry_run["data"]["preview"]["transactions"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1_500
+    );
+
+    let response = import_csv(&server, &auth.token, &account_id, statement, &fields).await;
+    let imported: serde_json::Value = response.json();
+    dry_run["data"].as_object_mut().unwrap().remove("preview");
+    assert_eq!(imported, dry_run);
+}
+
+#[tokio::test]
+async fn test_import_csv_skips_duplicates() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("dupes_{}", timestamp)).await;
+    let authorization = format!("Bearer {}", auth.token);
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let first = b"id,time,merchant,type,amount,card
+JAN1,2026-01-03 10:00:00,TESCO STORES 1234,Purchase,\xE2\x82\xAC-40.00,2133
+JAN2,2026-01-05 10:00:00,Netflix,Purchase,\xE2\x82\xAC-9.99,2133"
+        .to_vec();
+    let response = import_csv(&server, &auth.token, &account_id, first, &[]).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 2);
+    assert_eq!(body["data"]["skipped_duplicates"], 0);
+    assert!(body["data"].get("duplicates").is_none());
+
+    let response = server
+        .get(&format!("/api/v1/transactions?account_id={}", account_id))
+        .add_header("Authorization", authorization.clone())
+        .await;
+    let existing: serde_json::Value = response.json();
+    let tesco_id = existing
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|transaction| transaction["title"] == "TESCO STORES 1234")
+        .unwrap()["id"]
+        .clone();
+
+    // An overlapping statement: the bank posted Tesco two days later under a
+    // shorter name; Netflix is six days off and a different amount is new
+    let overlapping = b"id,time,merchant,type,amount,card
+FEB1,2026-01-05 00:00:00,Tesco Stores,Purchase,\xE2\x82\xAC-40.00,2133
+FEB2,2026-01-11 10:00:00,Netflix,Purchase,\xE2\x82\xAC-9.99,2133
+FEB3,2026-01-05 10:00:00,Tesco Stores,Purchase,\xE2\x82\xAC-12.00,2133"
+        .to_vec();
+    let response = import_csv(&server, &auth.token, &account_id, overlapping.clone(), &[]).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["total"], 3);
+    assert_eq!(body["data"]["created"], 2);
+    assert_eq!(body["data"]["skipped_duplicates"], 1);
+    assert_eq!(
+        body["data"]["duplicates"],
+        json!([{ "line": 2, "title": "Tesco Stores", "transaction_id": tesco_id }])
+    );
+
+    // A wider window catches the second Netflix payment
+    let fields = [("duplicate_window_days", "7")];
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        overlapping.clone(),
+        &fields,
+    )
+    .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 0);
+    assert_eq!(body["data"]["skipped_duplicates"], 3);
+
+    // Forcing saves duplicates anyway
+    let fields = [("force", "true")];
+    let response = import_csv(&server, &auth.token, &account_id, overlapping, &fields).await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 3);
+    assert_eq!(body["data"]["skipped_duplicates"], 0);
+
+    let response = server
+        .get(&format!("/api/v1/transactions?account_id={}", account_id))
+        .add_header("Authorization", authorization)
+        .await;
+    assert_eq!(response.header("X-Total-Count"), "7");
+
+    let fields = [("duplicate_window_days", "-1")];
+    let response = import_csv(&server, &auth.token, &account_id, b"".to_vec(), &fields).await;
+    assert_eq!(response.status_code(), 422);
+}
+
+#[tokio::test]
+async fn test_import_csv_matches_each_existing_transaction_once() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("dupeonce_{}", timestamp)).await;
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let existing = b"id,time,merchant,type,amount,card
+JAN1,2026-01-03 10:00:00,Coffee Shop,Purchase,\xE2\x82\xAC-3.50,2133"
+        .to_vec();
+    let response = import_csv(&server, &auth.token, &account_id, existing, &[]).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 1);
+
+    // Two coffees on the same day: one is the saved copy, the other is new
+    let statement = b"id,time,merchant,type,amount,card
+FEB1,2026-01-03 10:00:00,Coffee Shop,Purchase,\xE2\x82\xAC-3.50,2133
+FEB2,2026-01-03 15:00:00,Coffee Shop,Purchase,\xE2\x82\xAC-3.50,2133"
+        .to_vec();
+    let response = import_csv(&server, &auth.token, &account_id, statement, &[]).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["total"], 2);
+    assert_eq!(body["data"]["created"], 1);
+    assert_eq!(body["data"]["skipped_duplicates"], 1);
+    assert_eq!(body["data"]["duplicates"][0]["line"], 2);
+
+    let response = server
+        .get(&format!("/api/v1/transactions?account_id={}", account_id))
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .await;
+    assert_eq!(response.header("X-Total-Count"), "2");
+}
+
+#[tokio::test]
+async fn test_import_csv_allocates_income_in_its_batch() {
+    let server = create_test_server().await;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let auth = register_unique_test_user(&server, &format!("alloc_import_{}", timestamp)).await;
+    let authorization = format!("Bearer {}", auth.token);
+    let account_id = create_eur_account(&server, &auth.token).await;
+
+    let response = server
+        .post("/api/v1/accounts")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({
+            "name": "Savings",
+            "account_type": "SAVINGS",
+            "currency": "EUR",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let savings: serde_json::Value = response.json();
+    let savings_id = savings["id"].as_str().unwrap().to_string();
+
+    let response = server
+        .post("/api/v1/allocation-rules")
+        .add_header("Authorization", authorization.clone())
+        .json(&json!({
+            "name": "Save refunds",
+            "source_account_id": account_id,
+            "destinations": [{"account_id": savings_id, "percentage": 100}]
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    // Two refunds of 10.00 are allocated; the two purchases are not
+    let response = import_csv(
+        &server,
+        &auth.token,
+        &account_id,
+        generate_statement(4, None),
+        &[],
+    )
+    .await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["created"], 4);
+
+    let balance = |account: serde_json::Value| account["balance"].as_f64().unwrap();
+    let response = server
+        .get(&format!("/api/v1/accounts/{}", savings_id))
+        .add_header("Authorization", authorization.clone())
+        .await;
+    assert_eq!(balance(response.json()), 20.0);
+    let response = server
+        .get(&format!("/api/v1/accounts/{}", account_id))
+        .add_header("Authorization", authorization.clone())
+        .await;
+    assert_eq!(balance(response.json()), -10.0);
+
+    // The source holds the rows and the outgoing transfer legs
+    let response = server
+        .get(&format!(
+            "/api/v1/transactions?account_id={}&limit=1",
+            account_id
+        ))
+        .add_header("Authorization", authorization)
+        .await;
+    assert_eq!(response.header("X-Total-Count"), "6");
+}
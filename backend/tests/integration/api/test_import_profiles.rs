@@ -0,0 +1,347 @@
+//! Integration tests for import profiles and CSV column mappings
+//!
+//! These tests verify:
+//! - POST /api/v1/import/profiles - Create import profile
+//! - GET /api/v1/import/profiles - List import profiles
+//! - GET /api/v1/import/profiles/:id - Get import profile
+//! - PUT /api/v1/import/profiles/:id - Update import profile
+//! - DELETE /api/v1/import/profiles/:id - Delete import profile
+//! - Importing CSV files with a mapping, directly or from a profile
+
+use crate::common::*;
+use axum_test::{
+    TestServer,
+    multipart::{MultipartForm, Part},
+};
+use chrono::{TimeZone, Utc};
+use master_of_coin_backend::models::{AmountSign, ImportProfileResponse, TransactionResponse};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Statement with separate debit and credit columns and US dates
+const DEBIT_CREDIT_STATEMENT: &str = "Posted Date,Details,Debit,Credit,Reference
+01/15/2026,Grocery Store,\"1,234.50\",,REF1
+01/16/2026,Salary,,2000.00,REF2
+";
+
+fn chase_profile(name: &str) -> Value {
+    json!({
+        "name": name,
+        "mapping": {
+            "date": "Posted Date",
+            "description": "Details",
+            "debit": "Debit",
+            "credit": "Credit",
+            "notes": "Reference"
+        },
+        "date_format": "%m/%d/%Y",
+        "amount_sign": "debit_credit_columns"
+    })
+}
+
+/// Import a CSV file with the given form fields, sent before the file
+async fn import_csv(
+    server: &TestServer,
+    token: &str,
+    path: &str,
+    account_id: Uuid,
+    fields: &[(&str, &str)],
+    content: &str,
+) -> axum_test::TestResponse {
+    let mut form = MultipartForm::new().add_part("account_id", Part::text(account_id.to_string()));
+    for (name, value) in fields {
+        form = form.add_part(*name, Part::text(value.to_string()));
+    }
+    let form = form.add_part(
+        "file",
+        Part::bytes(content.as_bytes().to_vec())
+            .file_name("statement.csv")
+            .mime_type("text/csv"),
+    );
+
+    server
+        .post(path)
+        .add_header("Authorization", format!("Bearer {}", token))
+        .multipart(form)
+        .await
+}
+
+/// List the account's transactions, newest first
+async fn list_transactions(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+) -> Vec<TransactionResponse> {
+    let path = format!("/api/v1/transactions?account_id={}", account_id);
+    let response = get_authenticated(server, &path, token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// Test the import profile lifecycle.
+///
+/// Verifies that:
+/// - Creating returns 201 with the mapping, date format and amount sign
+/// - A second profile with the same name, ignoring case, returns 409
+/// - Updating keeps omitted fields
+/// - Another user cannot read the profile
+/// - Deleting returns 204 and the profile is gone afterwards
+#[tokio::test]
+async fn test_import_profile_crud() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("profile_{}", timestamp)).await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/import/profiles",
+        &auth.token,
+        &chase_profile("Chase Checking"),
+    )
+    .await;
+    assert_status(&response, 201);
+    let profile: ImportProfileResponse = extract_json(response);
+    assert_eq!(profile.name, "Chase Checking");
+    assert_eq!(profile.mapping.date, "Posted Date");
+    assert_eq!(profile.mapping.debit.as_deref(), Some("Debit"));
+    assert_eq!(profile.date_format.as_deref(), Some("%m/%d/%Y"));
+    assert_eq!(profile.amount_sign, AmountSign::DebitCreditColumns);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/import/profiles",
+        &auth.token,
+        &chase_profile("chase checking"),
+    )
+    .await;
+    assert_status(&response, 409);
+
+    let simple = json!({
+        "name": "Amex",
+        "mapping": { "date": "Date", "description": "Description", "amount": "Amount" }
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/import/profiles", &auth.token, &simple).await;
+    assert_status(&response, 201);
+    let amex: ImportProfileResponse = extract_json(response);
+    assert_eq!(amex.amount_sign, AmountSign::NegativeDebits);
+    assert_eq!(amex.date_format, None);
+
+    let response = get_authenticated(&server, "/api/v1/import/profiles", &auth.token).await;
+    assert_status(&response, 200);
+    let profiles: Vec<ImportProfileResponse> = extract_json(response);
+    let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["Amex", "Chase Checking"]);
+
+    let path = format!("/api/v1/import/profiles/{}", profile.id);
+    let update = json!({ "date_format": "%d/%m/%Y" });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 200);
+    let updated: ImportProfileResponse = extract_json(response);
+    assert_eq!(updated.date_format.as_deref(), Some("%d/%m/%Y"));
+    assert_eq!(updated.mapping, profile.mapping);
+    assert_eq!(updated.amount_sign, AmountSign::DebitCreditColumns);
+
+    let update = json!({ "name": "AMEX" });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 409);
+
+    let other = register_unique_test_user(&server, &format!("profile_other_{}", timestamp)).await;
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test that incomplete mappings are rejected.
+///
+/// Verifies that 422 is returned for:
+/// - A signed amount without an amount column
+/// - Separate columns without a credit column
+/// - An amount column with separate columns
+/// - An invalid date format
+#[tokio::test]
+async fn test_import_profile_requires_complete_mapping() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("profile_bad_{}", timestamp)).await;
+
+    let invalid = [
+        json!({ "name": "A", "mapping": { "date": "Date", "description": "Details" } }),
+        json!({
+            "name": "B",
+            "mapping": { "date": "Date", "description": "Details", "debit": "Out" },
+            "amount_sign": "debit_credit_columns"
+        }),
+        json!({
+            "name": "C",
+            "mapping": {
+                "date": "Date",
+                "description": "Details",
+                "amount": "Amount",
+                "debit": "Out",
+                "credit": "In"
+            },
+            "amount_sign": "debit_credit_columns"
+        }),
+        json!({
+            "name": "D",
+            "mapping": { "date": "Date", "description": "Details", "amount": "Amount" },
+            "date_format": "%Y-%Q"
+        }),
+    ];
+    for request in invalid {
+        let response =
+            post_authenticated(&server, "/api/v1/import/profiles", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+}
+
+/// Test importing a CSV file with a mapping.
+///
+/// Verifies that:
+/// - Debit and credit columns become negative and positive amounts
+/// - Dates are read with the date format, and amounts in the account's currency
+/// - The same file imports the same way from a saved profile
+/// - A dry run reports the fields the mapped columns were read as
+#[tokio::test]
+async fn test_import_csv_with_mapping() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("mapped_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let profile = chase_profile("Chase");
+    let mapping = profile["mapping"].to_string();
+    let fields = [
+        ("mapping", mapping.as_str()),
+        ("date_format", "%m/%d/%Y"),
+        ("amount_sign", "debit_credit_columns"),
+    ];
+    let response = import_csv(
+        &server,
+        &auth.token,
+        "/api/v1/transactions/import",
+        account.id,
+        &fields,
+        DEBIT_CREDIT_STATEMENT,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: Value = response.json();
+    assert_eq!(body["data"]["created"], 2);
+
+    let transactions = list_transactions(&server, &auth.token, account.id).await;
+    assert_eq!(transactions.len(), 2);
+    let salary = &transactions[0];
+    assert_eq!(salary.title, "Salary");
+    assert_eq!(salary.amount.to_string(), "2000.00");
+    assert_eq!(
+        salary.date,
+        Utc.with_ymd_and_hms(2026, 1, 16, 0, 0, 0).unwrap()
+    );
+    assert_eq!(salary.notes.as_deref(), Some("REF2"));
+    let groceries = &transactions[1];
+    assert_eq!(groceries.title, "Grocery Store");
+    assert_eq!(groceries.amount.to_string(), "-1234.50");
+
+    // The same file from a saved profile, into another account
+    let response =
+        post_authenticated(&server, "/api/v1/import/profiles", &auth.token, &profile).await;
+    assert_status(&response, 201);
+    let saved: ImportProfileResponse = extract_json(response);
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let profile_id = saved.id.to_string();
+    let fields = [("profile_id", profile_id.as_str())];
+
+    let response = import_csv(
+        &server,
+        &auth.token,
+        "/api/v1/transactions/import?dry_run=true",
+        savings.id,
+        &fields,
+        DEBIT_CREDIT_STATEMENT,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: Value = response.json();
+    let columns: Vec<&Value> = body["data"]["preview"]["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|column| &column["field"])
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            &json!("date"),
+            &json!("description"),
+            &json!("debit"),
+            &json!("credit"),
+            &json!("notes")
+        ]
+    );
+
+    let response = import_csv(
+        &server,
+        &auth.token,
+        "/api/v1/transactions/import",
+        savings.id,
+        &fields,
+        DEBIT_CREDIT_STATEMENT,
+    )
+    .await;
+    assert_status(&response, 200);
+    let imported = list_transactions(&server, &auth.token, savings.id).await;
+    let amounts: Vec<String> = imported.iter().map(|t| t.amount.to_string()).collect();
+    let expected: Vec<String> = transactions.iter().map(|t| t.amount.to_string()).collect();
+    assert_eq!(amounts, expected);
+}
+
+/// Test that a file lacking a mapped column is rejected before anything is saved.
+#[tokio::test]
+async fn test_import_csv_mapping_missing_column() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("mapped_bad_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let mapping = json!({ "date": "Date", "description": "Details", "amount": "Amount" });
+    let mapping = mapping.to_string();
+    let fields = [("mapping", mapping.as_str()), ("date_format", "%m/%d/%Y")];
+    let response = import_csv(
+        &server,
+        &auth.token,
+        "/api/v1/transactions/import",
+        account.id,
+        &fields,
+        DEBIT_CREDIT_STATEMENT,
+    )
+    .await;
+    assert_status(&response, 422);
+    let body: Value = response.json();
+    let error = body.to_string();
+    assert!(error.contains("'Date'"));
+    assert!(error.contains("'Amount'"));
+
+    assert!(
+        list_transactions(&server, &auth.token, account.id)
+            .await
+            .is_empty()
+    );
+
+    // Options for a mapping are refused without one
+    let response = import_csv(
+        &server,
+        &auth.token,
+        "/api/v1/transactions/import",
+        account.id,
+        &[("date_format", "%m/%d/%Y")],
+        DEBIT_CREDIT_STATEMENT,
+    )
+    .await;
+    assert_status(&response, 422);
+}
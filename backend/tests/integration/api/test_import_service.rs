@@ -4,12 +4,16 @@
 //! - Summary calculation for parsed transactions
 //! - Confidence level logic
 //! - Import service utilities
+//! - Duplicate matching of imported rows
 
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use master_of_coin_backend::{
-    models::ParsedTransaction, services::import_service, types::ConfidenceLevel,
+    models::{ParsedTransaction, Transaction},
+    services::import_service,
+    types::{ConfidenceLevel, TransactionStatus},
 };
+use uuid::Uuid;
 
 #[test]
 fn test_import_summary_calculation() {
@@ -22,6 +26,10 @@ fn test_import_summary_calculation() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: false,
@@ -35,6 +43,10 @@ fn test_import_summary_calculation() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: true, // Marked as duplicate
@@ -48,6 +60,10 @@ fn test_import_summary_calculation() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: false, // Invalid
             validation_errors: Some(vec!["Zero amount".to_string()]),
             is_potential_duplicate: false,
@@ -61,6 +77,10 @@ fn test_import_summary_calculation() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: false,
@@ -100,6 +120,10 @@ fn test_import_summary_all_income() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: false,
@@ -113,6 +137,10 @@ fn test_import_summary_all_income() {
             notes: None,
             original_currency: None,
             original_amount: None,
+            category_name: None,
+            account_name: None,
+            category_id: None,
+            account_id: None,
             is_valid: true,
             validation_errors: None,
             is_potential_duplicate: false,
@@ -141,3 +169,130 @@ fn test_confidence_level_min_threshold() {
         ConfidenceLevel::Medium
     );
 }
+
+fn imported_row(
+    account_id: Uuid,
+    title: &str,
+    amount: i64,
+    date: DateTime<Utc>,
+) -> ParsedTransaction {
+    ParsedTransaction {
+        temp_id: "1".to_string(),
+        title: title.to_string(),
+        amount: BigDecimal::from(amount),
+        date,
+        notes: None,
+        original_currency: None,
+        original_amount: None,
+        category_name: None,
+        account_name: None,
+        category_id: None,
+        account_id: Some(account_id),
+        is_valid: true,
+        validation_errors: None,
+        is_potential_duplicate: false,
+        duplicate_match: None,
+    }
+}
+
+fn existing_transaction(
+    account_id: Uuid,
+    title: &str,
+    amount: i64,
+    date: DateTime<Utc>,
+) -> Transaction {
+    Transaction {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        account_id,
+        category_id: None,
+        title: title.to_string(),
+        amount: BigDecimal::from(amount),
+        date,
+        notes: None,
+        created_at: date,
+        updated_at: date,
+        external_id: None,
+        status: TransactionStatus::Posted,
+        original_currency: None,
+        original_amount: None,
+        exchange_rate: None,
+        merchant: None,
+        latitude: None,
+        longitude: None,
+        version: 1,
+        transfer_id: None,
+        deleted_at: None,
+        reimbursable: false,
+        reimbursed_at: None,
+    }
+}
+
+#[test]
+fn test_is_likely_duplicate_within_window() {
+    let account_id = Uuid::new_v4();
+    let date = Utc.with_ymd_and_hms(2026, 3, 10, 9, 30, 0).unwrap();
+    let existing = existing_transaction(account_id, "AMAZON.CO.UK*AB12CD", -25, date);
+
+    let same_day = imported_row(account_id, "Amazon.co.uk", -25, date);
+    assert!(import_service::is_likely_duplicate(&same_day, &existing, 3));
+
+    let three_days_later = imported_row(account_id, "amazon co uk", -25, date + Duration::days(3));
+    assert!(import_service::is_likely_duplicate(
+        &three_days_later,
+        &existing,
+        3
+    ));
+    assert!(!import_service::is_likely_duplicate(
+        &three_days_later,
+        &existing,
+        2
+    ));
+
+    let four_days_earlier = imported_row(account_id, "Amazon.co.uk", -25, date - Duration::days(4));
+    assert!(!import_service::is_likely_duplicate(
+        &four_days_earlier,
+        &existing,
+        3
+    ));
+}
+
+#[test]
+fn test_is_likely_duplicate_requires_matching_fields() {
+    let account_id = Uuid::new_v4();
+    let date = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+    let existing = existing_transaction(account_id, "Tesco Stores 1234", -40, date);
+
+    // Half the words of the shorter title are enough
+    let similar = imported_row(account_id, "TESCO EXPRESS", -40, date);
+    assert!(import_service::is_likely_duplicate(&similar, &existing, 3));
+
+    let other_amount = imported_row(account_id, "Tesco Stores 1234", -41, date);
+    assert!(!import_service::is_likely_duplicate(
+        &other_amount,
+        &existing,
+        3
+    ));
+
+    let other_account = imported_row(Uuid::new_v4(), "Tesco Stores 1234", -40, date);
+    assert!(!import_service::is_likely_duplicate(
+        &other_account,
+        &existing,
+        3
+    ));
+
+    let other_title = imported_row(account_id, "Sainsbury's", -40, date);
+    assert!(!import_service::is_likely_duplicate(
+        &other_title,
+        &existing,
+        3
+    ));
+
+    // Titles only match on whole words
+    let partial_word = imported_row(account_id, "Tes", -40, date);
+    assert!(!import_service::is_likely_duplicate(
+        &partial_word,
+        &existing,
+        3
+    ));
+}
@@ -0,0 +1,225 @@
+//! Integration tests for loan and mortgage accounts.
+//!
+//! This module tests:
+//! - Creating loans from a positive principal, carried as a negative balance
+//! - Payments bringing the principal owed down
+//! - Loans reducing net worth
+//! - GET /api/v1/accounts/:id/amortization - Project a loan's payoff schedule
+
+use crate::common::*;
+use chrono::{Months, Utc};
+use master_of_coin_backend::models::{AccountResponse, AmortizationResponse};
+use serde_json::{Value, json};
+
+async fn create_account(
+    server: &axum_test::TestServer,
+    token: &str,
+    request: Value,
+) -> AccountResponse {
+    let response = post_authenticated(server, "/api/v1/accounts", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+async fn create_mortgage(server: &axum_test::TestServer, token: &str) -> AccountResponse {
+    create_account(
+        server,
+        token,
+        json!({
+            "name": "Mortgage",
+            "account_type": "MORTGAGE",
+            "currency": "EUR",
+            "initial_balance": 1000.0,
+            "interest_rate": 12.0,
+            "original_principal": 1200.0,
+        }),
+    )
+    .await
+}
+
+/// Test that a loan's principal is owed and paid down by transfers.
+///
+/// Verifies that:
+/// - The initial balance is recorded as a negative balance
+/// - The interest rate, original and outstanding principal are returned
+/// - A payment transferred in lowers the outstanding principal without an
+///   overdraft warning
+#[tokio::test]
+async fn test_loan_balance_decreases_with_payments() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("loan_payments_{}", timestamp)).await;
+
+    let mortgage = create_mortgage(&server, &auth.token).await;
+    assert_eq!(mortgage.balance, -1000.0);
+    assert_eq!(mortgage.outstanding_principal, Some(1000.0));
+    assert_eq!(mortgage.interest_rate, Some(12.0));
+    assert_eq!(mortgage.original_principal, Some(1200.0));
+
+    let checking = create_account(
+        &server,
+        &auth.token,
+        json!({"name": "Checking", "account_type": "CHECKING", "currency": "EUR", "initial_balance": 3000.0}),
+    )
+    .await;
+    assert_eq!(checking.outstanding_principal, None);
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": mortgage.id,
+        "amount": 400,
+        "date": Utc::now().to_rfc3339(),
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let path = format!("/api/v1/accounts/{}", mortgage.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let mortgage: AccountResponse = extract_json(response);
+    assert_eq!(mortgage.balance, -600.0);
+    assert_eq!(mortgage.outstanding_principal, Some(600.0));
+
+    let request = json!({"interest_rate": 4.5});
+    let response = put_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let mortgage: AccountResponse = extract_json(response);
+    assert_eq!(mortgage.interest_rate, Some(4.5));
+    assert_eq!(mortgage.original_principal, Some(1200.0));
+}
+
+/// Test that loans count against net worth.
+#[tokio::test]
+async fn test_loans_reduce_net_worth() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("loan_net_worth_{}", timestamp)).await;
+
+    create_account(
+        &server,
+        &auth.token,
+        json!({"name": "Checking", "account_type": "CHECKING", "currency": "EUR", "initial_balance": 3000.0}),
+    )
+    .await;
+    create_mortgage(&server, &auth.token).await;
+    create_account(
+        &server,
+        &auth.token,
+        json!({"name": "Car loan", "account_type": "LOAN", "currency": "EUR", "initial_balance": 500.0}),
+    )
+    .await;
+
+    let response = get_authenticated(&server, "/api/v1/dashboard/net-worth", &auth.token).await;
+    assert_status(&response, 200);
+    let net_worth: Value = extract_json(response);
+    let total: f64 = net_worth["total"].as_str().unwrap().parse().unwrap();
+    assert_eq!(total, 1500.0);
+}
+
+/// Test the projected payoff schedule of a mortgage.
+///
+/// Verifies that:
+/// - Each month charges a twelfth of the annual rate on the remaining principal
+/// - The last payment only covers what is left
+/// - The totals and payoff date match the schedule
+#[tokio::test]
+async fn test_amortization_schedule() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("loan_schedule_{}", timestamp)).await;
+    let mortgage = create_mortgage(&server, &auth.token).await;
+
+    let path = format!("/api/v1/accounts/{}/amortization?payment=500", mortgage.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let body: Value = extract_json(response);
+    assert_eq!(body["principal"], "1000.00");
+    assert_eq!(body["payment"], "500.00");
+    assert_eq!(body["total_interest"], "15.25");
+
+    let rows: Vec<(&str, &str, &str, &str)> = body["schedule"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["payment"].as_str().unwrap(),
+                row["interest"].as_str().unwrap(),
+                row["principal"].as_str().unwrap(),
+                row["remaining"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("500.00", "10.00", "490.00", "510.00"),
+            ("500.00", "5.10", "494.90", "15.10"),
+            ("15.25", "0.15", "15.10", "0.00"),
+        ]
+    );
+
+    let schedule: AmortizationResponse = serde_json::from_value(body).unwrap();
+    let payoff = Utc::now().date_naive() + Months::new(3);
+    assert_eq!(schedule.payoff_date, Some(payoff));
+    assert_eq!(schedule.schedule[2].number, 3);
+    assert_eq!(schedule.schedule[2].date, payoff);
+}
+
+/// Test that amortization is refused when it cannot be projected.
+///
+/// Verifies that:
+/// - Accounts other than loans and loans without a rate return 422
+/// - A payment that is not positive or does not cover the interest returns 400
+/// - An interest rate over 100 percent is rejected
+#[tokio::test]
+async fn test_amortization_errors() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("loan_errors_{}", timestamp)).await;
+
+    let checking = create_account(
+        &server,
+        &auth.token,
+        json!({"name": "Checking", "account_type": "CHECKING", "currency": "EUR"}),
+    )
+    .await;
+    let path = format!("/api/v1/accounts/{}/amortization?payment=100", checking.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 422);
+
+    let loan = create_account(
+        &server,
+        &auth.token,
+        json!({"name": "Loan", "account_type": "LOAN", "currency": "EUR", "initial_balance": 1000.0}),
+    )
+    .await;
+    let path = format!("/api/v1/accounts/{}/amortization?payment=100", loan.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 422);
+
+    let mortgage = create_mortgage(&server, &auth.token).await;
+    for payment in ["0", "10", "abc"] {
+        let path = format!(
+            "/api/v1/accounts/{}/amortization?payment={}",
+            mortgage.id, payment
+        );
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 400);
+    }
+
+    let request = json!({
+        "name": "Loan",
+        "account_type": "LOAN",
+        "currency": "EUR",
+        "interest_rate": 150.0,
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 422);
+}
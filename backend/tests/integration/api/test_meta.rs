@@ -0,0 +1,120 @@
+//! Integration tests for the supported value endpoints.
+//!
+//! This module tests:
+//! - GET /api/v1/meta/currencies - List supported currencies
+//! - GET /api/v1/meta/account-types - List supported account types
+//! - Every listed value round-tripping through accounts stored in the database
+//! - Parsing of currency and account type names
+
+use crate::common::*;
+use chrono::Utc;
+use master_of_coin_backend::{
+    models::{AccountResponse, CurrencyResponse},
+    types::{AccountType, CurrencyCode},
+};
+use serde_json::json;
+use std::str::FromStr;
+
+/// Test that the supported currencies are listed with their decimal places.
+#[tokio::test]
+async fn test_list_currencies() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("meta_currencies_{}", timestamp)).await;
+
+    let response = get_authenticated(&server, "/api/v1/meta/currencies", &auth.token).await;
+    assert_status(&response, 200);
+    let currencies: Vec<CurrencyResponse> = extract_json(response);
+
+    let codes: Vec<CurrencyCode> = currencies.iter().map(|currency| currency.code).collect();
+    assert_eq!(codes, CurrencyCode::ALL.to_vec());
+    let jpy = currencies
+        .iter()
+        .find(|currency| currency.code == CurrencyCode::Jpy)
+        .unwrap();
+    assert_eq!(jpy.minor_units, 0);
+    let usd = currencies
+        .iter()
+        .find(|currency| currency.code == CurrencyCode::Usd)
+        .unwrap();
+    assert_eq!(usd.minor_units, 2);
+}
+
+/// Test that the supported account types are listed by name.
+#[tokio::test]
+async fn test_list_account_types() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("meta_types_{}", timestamp)).await;
+
+    let response = get_authenticated(&server, "/api/v1/meta/account-types", &auth.token).await;
+    assert_status(&response, 200);
+    let names: Vec<String> = extract_json(response);
+    let expected: Vec<&str> = AccountType::ALL.iter().map(AccountType::as_str).collect();
+    assert_eq!(names, expected);
+}
+
+/// Test that the endpoints require authentication.
+#[tokio::test]
+async fn test_meta_requires_auth() {
+    let server = create_test_server().await;
+
+    for path in ["/api/v1/meta/currencies", "/api/v1/meta/account-types"] {
+        let response = get_unauthenticated(&server, path).await;
+        assert_status(&response, 401);
+    }
+}
+
+/// Test that every listed currency and account type can be stored and read back.
+///
+/// Verifies that:
+/// - An account can be created in every currency and of every type
+/// - Reading the account back returns the same currency and type
+#[tokio::test]
+async fn test_listed_values_round_trip() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("meta_round_trip_{}", timestamp)).await;
+
+    let pairs = CurrencyCode::ALL
+        .iter()
+        .zip(AccountType::ALL.iter().cycle());
+    for (currency, account_type) in pairs {
+        let request = json!({
+            "name": format!("{} {}", currency.as_str(), account_type.as_str()),
+            "account_type": account_type,
+            "currency": currency,
+        });
+        let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+        assert_status(&response, 201);
+        let created: AccountResponse = extract_json(response);
+
+        let path = format!("/api/v1/accounts/{}", created.id);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 200);
+        let account: AccountResponse = extract_json(response);
+        assert_eq!(account.currency, *currency);
+        assert_eq!(account.account_type, *account_type);
+    }
+}
+
+/// Test that names parse ignoring case, and unknown names fail with the
+/// supported values.
+#[test]
+fn test_parse_names() {
+    assert_eq!(CurrencyCode::from_str(" usd ").unwrap(), CurrencyCode::Usd);
+    assert_eq!(
+        "credit_card".parse::<AccountType>().unwrap(),
+        AccountType::CreditCard
+    );
+
+    let error = CurrencyCode::from_str("CHF").unwrap_err();
+    assert_eq!(error.kind, "currency");
+    assert_eq!(error.value, "CHF");
+    assert_eq!(
+        error.to_string(),
+        "invalid currency 'CHF'; expected one of USD, EUR, GBP, INR, JPY, AUD, CAD"
+    );
+    let error = "PENSION".parse::<AccountType>().unwrap_err();
+    assert_eq!(error.expected.len(), AccountType::ALL.len());
+}
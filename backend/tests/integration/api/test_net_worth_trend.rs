@@ -0,0 +1,134 @@
+//! Integration tests for the net worth trend.
+//!
+//! This module tests GET /api/v1/dashboard/net-worth-trend, which reports the
+//! user's net worth at the end of each recent month.
+//!
+//! Tests cover the monthly totals across accounts, accounts created during the
+//! trend, and the month count limits.
+
+use crate::common::*;
+use chrono::{Datelike, Months, Utc};
+use diesel::prelude::*;
+use master_of_coin_backend::{schema::accounts, services::analytics_service::NetWorthTrend};
+use serde_json::json;
+use uuid::Uuid;
+
+fn get_test_db_pool() -> master_of_coin_backend::DbPool {
+    use diesel::PgConnection;
+    use diesel::r2d2::{self, ConnectionManager};
+    dotenvy::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for integration tests");
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .max_size(1)
+        .build(manager)
+        .expect("Failed to create test database pool")
+}
+
+/// Pretend an account was created `months` months ago
+fn backdate_account(account_id: Uuid, months: u32) {
+    let created_at = Utc::now().checked_sub_months(Months::new(months)).unwrap();
+    let mut conn = get_test_db_pool().get().unwrap();
+    diesel::update(accounts::table.find(account_id))
+        .set(accounts::created_at.eq(created_at))
+        .execute(&mut conn)
+        .unwrap();
+}
+
+/// Test the net worth at the end of each month.
+///
+/// Verifies that:
+/// - Each month sums the balances of all accounts at its end
+/// - Accounts created after a month ended count as zero for it
+/// - The last point is the current month
+#[tokio::test]
+async fn test_net_worth_trend() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("trend_{}", timestamp)).await;
+    let token = &auth.token;
+
+    let checking = create_test_account(&server, token, "Checking").await;
+    let savings = create_test_account(&server, token, "Savings").await;
+    backdate_account(checking.id, 3);
+
+    // The first of each of the last four months, the oldest first
+    let today = Utc::now().date_naive();
+    let first = |months_ago: u32| {
+        today
+            .with_day(1)
+            .unwrap()
+            .checked_sub_months(Months::new(months_ago))
+            .unwrap()
+    };
+    for (account_id, amount, months_ago) in [
+        (checking.id, "500.00", 2),
+        (checking.id, "-100.00", 1),
+        (savings.id, "1000.00", 2),
+        (savings.id, "50.00", 0),
+    ] {
+        let request = json!({
+            "account_id": account_id,
+            "title": "Trend test",
+            "amount": amount,
+            "date": format!("{}T12:00:00Z", first(months_ago))
+        });
+        let response = post_authenticated(&server, "/api/v1/transactions", token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/net-worth-trend?months=4&currency=USD",
+        token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let trend: NetWorthTrend = extract_json(response);
+    assert_eq!(trend.currency.as_str(), "USD");
+
+    let points: Vec<(String, String)> = trend
+        .points
+        .into_iter()
+        .map(|point| (point.month, point.net_worth.to_string()))
+        .collect();
+    let month = |months_ago: u32| first(months_ago).format("%Y-%m").to_string();
+    assert_eq!(
+        points,
+        vec![
+            // Checking existed, but had no transactions yet
+            (month(3), "0.00".to_string()),
+            // Savings, created this month, is left out until now
+            (month(2), "500.00".to_string()),
+            (month(1), "400.00".to_string()),
+            (month(0), "1450.00".to_string()),
+        ]
+    );
+}
+
+/// Test that the month count is limited.
+#[tokio::test]
+async fn test_net_worth_trend_month_limits() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("trend_limits_{}", timestamp)).await;
+
+    for months in ["0", "121"] {
+        let path = format!("/api/v1/dashboard/net-worth-trend?months={}", months);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 400);
+    }
+
+    // The default covers a year
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/net-worth-trend?currency=EUR",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let trend: NetWorthTrend = extract_json(response);
+    assert_eq!(trend.points.len(), 12);
+    assert!(trend.points.iter().all(|point| point.net_worth.is_zero()));
+}
@@ -0,0 +1,594 @@
+//! Integration tests for notification channels
+//!
+//! These tests verify:
+//! - GET/PUT /api/v1/notifications/preferences - Per event type channel settings
+//! - Webhook URLs resolving to non-public addresses are refused
+//! - Budget alerts are delivered through the enabled in-app, email and webhook channels
+//! - Webhook deliveries are signed, and failed ones are retried
+//! - GET /api/v1/notifications (with pagination) and POST /api/v1/notifications/:id/read
+//! - The notification routes are not available to API keys
+
+use crate::common::*;
+use async_trait::async_trait;
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use axum_test::TestServer;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use master_of_coin_backend::{
+    errors::ApiError,
+    models::{
+        AccountResponse, ApiKeyScopes, BudgetResponse, CreateApiKeyRequest, CreateApiKeyResponse,
+        NotificationChannelKind, NotificationEventType, NotificationPreferenceResponse,
+        NotificationResponse, ScopePermission,
+    },
+    services::{email_service::EmailSender, notification_service::WEBHOOK_SIGNATURE_HEADER},
+};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+use uuid::Uuid;
+
+/// Email sender that records notification emails instead of sending them
+#[derive(Default)]
+struct RecordingEmailSender {
+    notifications: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl EmailSender for RecordingEmailSender {
+    async fn send_password_reset(&self, _email: &str, _token: &str) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    async fn send_notification(
+        &self,
+        email: &str,
+        subject: &str,
+        _body: &str,
+    ) -> Result<(), ApiError> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .push((email.to_string(), subject.to_string()));
+        Ok(())
+    }
+}
+
+/// Raw body and signature header of a webhook delivery
+type Delivery = (Bytes, Option<String>);
+
+/// Webhook receiver that fails its first `failures` requests, recording the
+/// raw body and signature header of the others
+#[derive(Clone, Default)]
+struct WebhookReceiver {
+    failures: usize,
+    attempts: Arc<AtomicUsize>,
+    received: Arc<Mutex<Vec<Delivery>>>,
+}
+
+async fn receive_webhook(
+    State(receiver): State<WebhookReceiver>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if receiver.attempts.fetch_add(1, Ordering::SeqCst) < receiver.failures {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    let signature = headers
+        .get(WEBHOOK_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    receiver.received.lock().unwrap().push((body, signature));
+    StatusCode::NO_CONTENT
+}
+
+/// The signature a receiver expects for `body` under `secret`
+fn expected_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+/// Serve `receiver` on a local port, returning the URL to post to
+async fn serve_webhook(receiver: WebhookReceiver) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new()
+        .route("/hook", post(receive_webhook))
+        .with_state(receiver);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    format!("http://{}/hook", addr)
+}
+
+/// Poll `check` until it holds, failing after about ten seconds
+async fn wait_until(mut check: impl FnMut() -> bool) {
+    for _ in 0..100 {
+        if check() {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("Condition not met in time");
+}
+
+/// Create an account, and a budget alerting at 80% of a limit of 100
+async fn create_alerting_budget(server: &TestServer, token: &str) -> (Uuid, Uuid) {
+    // In the primary currency, so no exchange rates are needed
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "EUR"
+    });
+    let response = post_authenticated(server, "/api/v1/accounts", token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let request = json!({ "name": "Groceries", "filters": {}, "alert_thresholds": [0.8] });
+    let response = post_authenticated(server, "/api/v1/budgets", token, &request).await;
+    assert_status(&response, 201);
+    let budget: BudgetResponse = extract_json(response);
+
+    let today = Utc::now().date_naive();
+    let range_request = json!({
+        "limit_amount": 100.0,
+        "period": "MONTHLY",
+        "start_date": (today - Duration::days(1)).to_string(),
+        "end_date": (today + Duration::days(30)).to_string()
+    });
+    let path = format!("/api/v1/budgets/{}/ranges", budget.id);
+    let response = post_authenticated(server, &path, token, &range_request).await;
+    assert_status(&response, 201);
+
+    (account.id, budget.id)
+}
+
+/// Spend 90 from the account and compute the budget's status, firing its alert
+async fn fire_alert(server: &TestServer, token: &str, account_id: Uuid, budget_id: Uuid) {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Shopping",
+        "amount": -90.0,
+        "date": (Utc::now() - Duration::minutes(1)).to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+
+    let path = format!("/api/v1/budgets/{}/status", budget_id);
+    let response = get_authenticated(server, &path, token).await;
+    assert_status(&response, 200);
+}
+
+async fn list_notifications(
+    server: &TestServer,
+    token: &str,
+    path: &str,
+) -> Vec<NotificationResponse> {
+    let response = get_authenticated(server, path, token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+fn preference(
+    preferences: &[NotificationPreferenceResponse],
+    channel: NotificationChannelKind,
+) -> &NotificationPreferenceResponse {
+    preferences
+        .iter()
+        .find(|preference| {
+            preference.event_type == NotificationEventType::BudgetAlert
+                && preference.channel == channel
+        })
+        .expect("Missing preference")
+}
+
+/// Test reading and changing notification preferences.
+///
+/// Verifies that:
+/// - Every channel is listed, with only in-app enabled by default
+/// - Enabling the webhook channel needs an http(s) `webhook_url`
+/// - `webhook_url` is refused for other channels, and duplicates are refused
+/// - Channels are toggled independently, and unlisted ones keep their setting
+/// - Turning the webhook off without a URL keeps the saved one and its secret
+/// - A webhook gets a signing secret, kept while the URL stays the same and
+///   replaced when it changes
+#[tokio::test]
+async fn test_notification_preferences() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("notif_prefs_{}", timestamp)).await;
+    let path = "/api/v1/notifications/preferences";
+
+    let response = get_authenticated(&server, path, &auth.token).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    assert_eq!(preferences.len(), 3);
+    assert!(preference(&preferences, NotificationChannelKind::InApp).enabled);
+    assert!(!preference(&preferences, NotificationChannelKind::Email).enabled);
+    assert!(!preference(&preferences, NotificationChannelKind::Webhook).enabled);
+
+    for invalid in [
+        json!([{ "event_type": "budget_alert", "channel": "webhook", "enabled": true }]),
+        json!([{ "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+                 "webhook_url": "ftp://1.1.1.1/hook" }]),
+        json!([{ "event_type": "budget_alert", "channel": "email", "enabled": true,
+                 "webhook_url": "https://1.1.1.1/hook" }]),
+        json!([
+            { "event_type": "budget_alert", "channel": "email", "enabled": true },
+            { "event_type": "budget_alert", "channel": "email", "enabled": false }
+        ]),
+    ] {
+        let request = json!({ "preferences": invalid });
+        let response = put_authenticated(&server, path, &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "email", "enabled": true },
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+          "webhook_url": "https://1.1.1.1/hook" }
+    ] });
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    assert!(preference(&preferences, NotificationChannelKind::InApp).enabled);
+    assert!(preference(&preferences, NotificationChannelKind::Email).enabled);
+    let webhook = preference(&preferences, NotificationChannelKind::Webhook);
+    assert!(webhook.enabled);
+    assert_eq!(webhook.webhook_url.as_deref(), Some("https://1.1.1.1/hook"));
+    let secret = webhook
+        .webhook_secret
+        .clone()
+        .expect("Missing webhook secret");
+    assert_eq!(secret.len(), 64);
+    assert!(
+        preference(&preferences, NotificationChannelKind::Email)
+            .webhook_secret
+            .is_none()
+    );
+
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "in_app", "enabled": false },
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": false }
+    ] });
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    assert!(!preference(&preferences, NotificationChannelKind::InApp).enabled);
+    assert!(preference(&preferences, NotificationChannelKind::Email).enabled);
+    let webhook = preference(&preferences, NotificationChannelKind::Webhook);
+    assert!(!webhook.enabled);
+    assert_eq!(webhook.webhook_url.as_deref(), Some("https://1.1.1.1/hook"));
+    assert_eq!(webhook.webhook_secret.as_deref(), Some(secret.as_str()));
+
+    let response = get_authenticated(&server, path, &auth.token).await;
+    assert_status(&response, 200);
+    let fetched: Vec<NotificationPreferenceResponse> = extract_json(response);
+    assert!(!preference(&fetched, NotificationChannelKind::InApp).enabled);
+
+    // Saving the same URL again keeps the secret
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+          "webhook_url": "https://1.1.1.1/hook" }
+    ] });
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    let webhook = preference(&preferences, NotificationChannelKind::Webhook);
+    assert_eq!(webhook.webhook_secret.as_deref(), Some(secret.as_str()));
+
+    // A new URL gets a new secret
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+          "webhook_url": "https://1.0.0.1/hook" }
+    ] });
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    let webhook = preference(&preferences, NotificationChannelKind::Webhook);
+    assert_eq!(webhook.webhook_url.as_deref(), Some("https://1.0.0.1/hook"));
+    let new_secret = webhook
+        .webhook_secret
+        .clone()
+        .expect("Missing webhook secret");
+    assert_ne!(new_secret, secret);
+}
+
+/// Test that webhooks cannot target the server's own network.
+///
+/// Verifies that:
+/// - URLs whose host is or resolves to a loopback, private, link-local
+///   (cloud metadata) or unspecified address are refused with 422
+/// - Nothing is saved for a refused URL
+#[tokio::test]
+async fn test_webhook_urls_to_private_addresses_refused() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("notif_ssrf_{}", timestamp)).await;
+    let path = "/api/v1/notifications/preferences";
+
+    for url in [
+        "http://127.0.0.1/hook",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://localhost:8080/hook",
+        "http://10.0.0.1/hook",
+        "http://192.168.1.1/hook",
+        "http://0.0.0.0/hook",
+        "http://[::1]/hook",
+        "http://[::ffff:127.0.0.1]/hook",
+        "http://[fd00::1]/hook",
+    ] {
+        let request = json!({ "preferences": [
+            { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+              "webhook_url": url }
+        ] });
+        let response = put_authenticated(&server, path, &auth.token, &request).await;
+        assert_eq!(response.status_code(), 422, "{} was accepted", url);
+    }
+
+    let response = get_authenticated(&server, path, &auth.token).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    let webhook = preference(&preferences, NotificationChannelKind::Webhook);
+    assert!(!webhook.enabled);
+    assert!(webhook.webhook_url.is_none());
+}
+
+/// Test that a budget alert reaches the in-app and email channels.
+///
+/// Verifies that:
+/// - The in-app channel stores a notification naming the budget
+/// - The email channel emails the user through the email sender
+/// - Notifications can be marked read, and `?unread=true` leaves them out
+/// - `?paginated=true` wraps the list in the pagination envelope
+/// - Other users can neither list nor read the notification
+#[tokio::test]
+async fn test_budget_alert_delivered_through_enabled_channels() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("notif_alert_{}", timestamp)).await;
+
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "email", "enabled": true }
+    ] });
+    let response = put_authenticated(
+        &server,
+        "/api/v1/notifications/preferences",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let (account_id, budget_id) = create_alerting_budget(&server, &auth.token).await;
+    fire_alert(&server, &auth.token, account_id, budget_id).await;
+
+    let email = auth.user.email.clone();
+    wait_until(|| {
+        sender
+            .notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(to, subject)| to == &email && subject.contains("Groceries"))
+    })
+    .await;
+
+    let mut notifications = Vec::new();
+    for _ in 0..100 {
+        notifications = list_notifications(&server, &auth.token, "/api/v1/notifications").await;
+        if !notifications.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(notifications.len(), 1);
+    let notification = &notifications[0];
+    assert_eq!(notification.event_type, NotificationEventType::BudgetAlert);
+    assert!(notification.title.contains("Groceries"));
+    assert_eq!(notification.data["budget_id"], budget_id.to_string());
+    assert!(notification.read_at.is_none());
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/notifications?per_page=1&paginated=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let page: Value = extract_json(response);
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["per_page"], 1);
+    assert_eq!(page["has_more"], false);
+    assert_eq!(page["data"][0]["id"], notification.id.to_string());
+
+    // Computing the status again fires nothing new
+    let path = format!("/api/v1/budgets/{}/status", budget_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+
+    let other = register_unique_test_user(&server, &format!("notif_other_{}", timestamp)).await;
+    assert!(
+        list_notifications(&server, &other.token, "/api/v1/notifications")
+            .await
+            .is_empty()
+    );
+    let read_path = format!("/api/v1/notifications/{}/read", notification.id);
+    let response = post_authenticated(&server, &read_path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+
+    let response = post_authenticated(&server, &read_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let read: NotificationResponse = extract_json(response);
+    assert!(read.read_at.is_some());
+
+    let unread =
+        list_notifications(&server, &auth.token, "/api/v1/notifications?unread=true").await;
+    assert!(unread.is_empty());
+    assert_eq!(
+        list_notifications(&server, &auth.token, "/api/v1/notifications")
+            .await
+            .len(),
+        1
+    );
+}
+
+/// Test that a budget alert is posted, signed, to the user's webhook.
+///
+/// Verifies that:
+/// - The receiver's local address is refused by default, and accepted once
+///   private webhook URLs are allowed
+/// - The webhook channel posts the alert, retrying after a failed attempt
+/// - The `X-Signature-256` header is the HMAC-SHA256 of the raw body under
+///   the preference's `webhook_secret`
+#[tokio::test]
+async fn test_budget_alert_posted_to_webhook() {
+    let receiver = WebhookReceiver {
+        failures: 1,
+        ..Default::default()
+    };
+    let webhook_url = serve_webhook(receiver.clone()).await;
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+          "webhook_url": webhook_url }
+    ] });
+    let path = "/api/v1/notifications/preferences";
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let server = create_test_server().await;
+    let auth = register_unique_test_user(&server, &format!("notif_local_{}", timestamp)).await;
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let server = create_test_server_with_config(|config| {
+        config.notifications.allow_private_webhook_urls = true;
+    })
+    .await;
+    let auth = register_unique_test_user(&server, &format!("notif_hook_{}", timestamp)).await;
+    let response = put_authenticated(&server, path, &auth.token, &request).await;
+    assert_status(&response, 200);
+    let preferences: Vec<NotificationPreferenceResponse> = extract_json(response);
+    let secret = preference(&preferences, NotificationChannelKind::Webhook)
+        .webhook_secret
+        .clone()
+        .expect("Missing webhook secret");
+
+    let (account_id, budget_id) = create_alerting_budget(&server, &auth.token).await;
+    fire_alert(&server, &auth.token, account_id, budget_id).await;
+
+    wait_until(|| !receiver.received.lock().unwrap().is_empty()).await;
+    assert_eq!(receiver.attempts.load(Ordering::SeqCst), 2);
+    let (body, signature) = receiver.received.lock().unwrap()[0].clone();
+    assert_eq!(signature, Some(expected_signature(&secret, &body)));
+    let delivered: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(delivered["event_type"], "budget_alert");
+    assert_eq!(delivered["data"]["budget_id"], budget_id.to_string());
+    assert_eq!(delivered["data"]["threshold"], 0.8);
+    assert_eq!(receiver.received.lock().unwrap().len(), 1);
+}
+
+/// Test that disabled channels receive nothing.
+///
+/// Verifies that:
+/// - With in-app turned off, a budget alert stores no notification
+/// - The alert itself is still recorded
+#[tokio::test]
+async fn test_disabled_channels_receive_nothing() {
+    let sender = Arc::new(RecordingEmailSender::default());
+    let server = create_test_server_with_email_sender(sender.clone()).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("notif_off_{}", timestamp)).await;
+
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "in_app", "enabled": false }
+    ] });
+    let response = put_authenticated(
+        &server,
+        "/api/v1/notifications/preferences",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let (account_id, budget_id) = create_alerting_budget(&server, &auth.token).await;
+    fire_alert(&server, &auth.token, account_id, budget_id).await;
+
+    let response = get_authenticated(&server, "/api/v1/budgets/alerts", &auth.token).await;
+    assert_status(&response, 200);
+    let alerts: Vec<Value> = extract_json(response);
+    assert_eq!(alerts.len(), 1);
+
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert!(
+        list_notifications(&server, &auth.token, "/api/v1/notifications")
+            .await
+            .is_empty()
+    );
+    assert!(sender.notifications.lock().unwrap().is_empty());
+}
+
+/// Test that API keys cannot use the notification routes.
+///
+/// Verifies that:
+/// - Listing notifications and reading or changing preferences return 403
+#[tokio::test]
+async fn test_notifications_not_available_to_api_keys() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("notif_key_{}", timestamp)).await;
+
+    let request = CreateApiKeyRequest {
+        name: "Full Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: vec![ScopePermission::Read, ScopePermission::Write],
+            accounts: vec![ScopePermission::Read, ScopePermission::Write],
+            budgets: vec![ScopePermission::Read, ScopePermission::Write],
+            categories: vec![ScopePermission::Read, ScopePermission::Write],
+            people: vec![ScopePermission::Read, ScopePermission::Write],
+        },
+        expires_in_days: None,
+        requests_per_minute: None,
+    };
+    let response = server
+        .post("/api/v1/api-keys")
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .json(&request)
+        .await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+
+    let response = get_authenticated(&server, "/api/v1/notifications", &api_key.key).await;
+    assert_status(&response, 403);
+    let response =
+        get_authenticated(&server, "/api/v1/notifications/preferences", &api_key.key).await;
+    assert_status(&response, 403);
+    let request = json!({ "preferences": [
+        { "event_type": "budget_alert", "channel": "webhook", "enabled": true,
+          "webhook_url": "https://1.1.1.1/hook" }
+    ] });
+    let response = put_authenticated(
+        &server,
+        "/api/v1/notifications/preferences",
+        &api_key.key,
+        &request,
+    )
+    .await;
+    assert_status(&response, 403);
+}
@@ -0,0 +1,310 @@
+//! Integration tests for OFX/QFX statement import
+//!
+//! These tests verify:
+//! - Parsing OFX 1.x (SGML) and OFX 2.x (XML) statements
+//! - Rejecting malformed statements with a description of the problem
+//! - POST /api/v1/transactions/import/ofx - Import a statement, skipping
+//!   transactions imported before by their FITID
+
+use crate::common::*;
+use axum_test::multipart::{MultipartForm, Part};
+use bigdecimal::BigDecimal;
+use chrono::{TimeZone, Utc};
+use master_of_coin_backend::{
+    config::ImportConfig,
+    models::{AccountResponse, TransactionResponse, parser_error::ParserError},
+    services::{
+        csv_parser_service::ParserFactory,
+        ofx_parser_service::{OfxStatementParser, OfxTransaction},
+    },
+    types::CurrencyCode,
+};
+use serde_json::json;
+use std::str::FromStr;
+
+/// Helper to create test import config
+fn test_import_config() -> ImportConfig {
+    ImportConfig {
+        max_file_size: 5 * 1024 * 1024,
+        max_transactions: 1000,
+        max_stream_file_size: 100 * 1024 * 1024,
+        batch_size: 1000,
+        duplicate_confidence_threshold: "MEDIUM".to_string(),
+        duplicate_window_days: 3,
+    }
+}
+
+/// OFX 1.x statement in SGML syntax, with two transactions
+const SGML_STATEMENT: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+SECURITY:NONE
+ENCODING:USASCII
+
+<OFX>
+<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS></SONRS></SIGNONMSGSRSV1>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>USD
+<BANKTRANLIST>
+<DTSTART>20260101
+<DTEND>20260131
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20260105120000[-5:EST]
+<TRNAMT>-42.10
+<FITID>2026010501
+<NAME>CORNER MARKET
+<MEMO>Card 1234
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20260110
+<TRNAMT>2500.00
+<FITID>2026011001
+<NAME>ACME &amp; SONS PAYROLL
+</STMTTRN>
+</BANKTRANLIST>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+";
+
+/// OFX 2.x statement in XML syntax, with one transaction
+const XML_STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE"?>
+<OFX>
+  <BANKMSGSRSV1><STMTTRNRS><STMTRS>
+    <CURDEF>EUR</CURDEF>
+    <BANKTRANLIST>
+      <STMTTRN>
+        <TRNTYPE>DEBIT</TRNTYPE>
+        <DTPOSTED>20260203083000.000</DTPOSTED>
+        <TRNAMT>-7,50</TRNAMT>
+        <FITID>A-77</FITID>
+        <MEMO>Bakery</MEMO>
+      </STMTTRN>
+    </BANKTRANLIST>
+  </STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+"#;
+
+fn invalid_ofx_error(content: &str) -> String {
+    match OfxStatementParser.parse_statement(content.as_bytes(), &test_import_config()) {
+        Err(ParserError::InvalidOfx { error }) => error,
+        other => panic!("Expected InvalidOfx error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_sgml_statement() {
+    let statement = OfxStatementParser
+        .parse_statement(SGML_STATEMENT.as_bytes(), &test_import_config())
+        .expect("Failed to parse statement");
+
+    assert_eq!(statement.currency, Some(CurrencyCode::Usd));
+    assert_eq!(statement.transactions.len(), 2);
+
+    let market = &statement.transactions[0];
+    assert_eq!(market.fit_id, "2026010501");
+    assert_eq!(market.amount, BigDecimal::from_str("-42.10").unwrap());
+    // Noon EST is 17:00 UTC
+    assert_eq!(
+        market.date,
+        Utc.with_ymd_and_hms(2026, 1, 5, 17, 0, 0).unwrap()
+    );
+    assert_eq!(market.title(), "CORNER MARKET");
+    assert_eq!(market.notes().as_deref(), Some("Card 1234"));
+
+    let payroll = &statement.transactions[1];
+    assert_eq!(
+        payroll.date,
+        Utc.with_ymd_and_hms(2026, 1, 10, 0, 0, 0).unwrap()
+    );
+    assert_eq!(payroll.title(), "ACME & SONS PAYROLL");
+    assert_eq!(payroll.notes(), None);
+}
+
+#[test]
+fn test_parse_xml_statement() {
+    let statement = OfxStatementParser
+        .parse_statement(XML_STATEMENT.as_bytes(), &test_import_config())
+        .expect("Failed to parse statement");
+
+    assert_eq!(statement.currency, Some(CurrencyCode::Eur));
+    let transaction: &OfxTransaction = &statement.transactions[0];
+    assert_eq!(transaction.fit_id, "A-77");
+    assert_eq!(transaction.amount, BigDecimal::from_str("-7.50").unwrap());
+    assert_eq!(
+        transaction.date,
+        Utc.with_ymd_and_hms(2026, 2, 3, 8, 30, 0).unwrap()
+    );
+    // Without a NAME the memo is the title
+    assert_eq!(transaction.title(), "Bakery");
+    assert_eq!(transaction.notes(), None);
+}
+
+#[test]
+fn test_parse_malformed_statement() {
+    assert!(invalid_ofx_error("id,time,merchant\n").contains("no <OFX> element"));
+
+    let unclosed = "<OFX><STMTTRN><DTPOSTED>20260101<TRNAMT>-1.00<FITID>1";
+    assert!(invalid_ofx_error(unclosed).contains("transaction 1 is not closed"));
+
+    let no_fit_id = "<OFX><STMTTRN><DTPOSTED>20260101<TRNAMT>-1.00</STMTTRN></OFX>";
+    assert!(invalid_ofx_error(no_fit_id).contains("transaction 1 has no FITID"));
+
+    let bad_date = "<OFX><STMTTRN><DTPOSTED>2026-01-01<TRNAMT>-1<FITID>1</STMTTRN></OFX>";
+    assert!(invalid_ofx_error(bad_date).contains("invalid DTPOSTED '2026-01-01'"));
+
+    let bad_amount = "<OFX><STMTTRN><DTPOSTED>20260101<TRNAMT>ten<FITID>1</STMTTRN></OFX>";
+    assert!(invalid_ofx_error(bad_amount).contains("invalid TRNAMT 'ten'"));
+
+    let empty = OfxStatementParser.parse_statement(b"<OFX></OFX>", &test_import_config());
+    assert!(matches!(empty, Err(ParserError::EmptyFile)));
+}
+
+#[test]
+fn test_parser_factory_ofx() {
+    for extension in [".ofx", ".QFX"] {
+        let parser = ParserFactory::get_parser(extension).expect("OFX parser");
+        let transactions = parser
+            .parse(SGML_STATEMENT.as_bytes(), &test_import_config())
+            .expect("Failed to parse statement");
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].title, "CORNER MARKET");
+    }
+}
+
+/// Upload an OFX statement to the import endpoint
+async fn import_statement(
+    server: &axum_test::TestServer,
+    token: &str,
+    account_id: uuid::Uuid,
+    file_name: &str,
+    content: &str,
+) -> axum_test::TestResponse {
+    let form = MultipartForm::new()
+        .add_part("account_id", Part::text(account_id.to_string()))
+        .add_part(
+            "file",
+            Part::bytes(content.as_bytes().to_vec())
+                .file_name(file_name.to_string())
+                .mime_type("application/x-ofx"),
+        );
+
+    server
+        .post("/api/v1/transactions/import/ofx")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .multipart(form)
+        .await
+}
+
+/// Test importing an OFX statement, then importing it again.
+///
+/// Verifies that:
+/// - The statement's transactions are created with their title, notes and amount
+/// - Re-importing the same statement skips every transaction
+/// - A statement overlapping the first only creates the new transactions
+#[tokio::test]
+async fn test_import_ofx_statement() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ofx_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let response =
+        import_statement(&server, &auth.token, account.id, "jan.ofx", SGML_STATEMENT).await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["total"], 2);
+    assert_eq!(body["data"]["created"], 2);
+    assert_eq!(body["data"]["skipped"], 0);
+
+    let path = format!("/api/v1/accounts/{}/transactions", account.id);
+    let transactions: Vec<TransactionResponse> =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert_eq!(transactions.len(), 2);
+    let market = transactions
+        .iter()
+        .find(|t| t.title == "CORNER MARKET")
+        .expect("market transaction");
+    assert_eq!(market.amount.to_string(), "-42.10");
+    assert_eq!(market.notes.as_deref(), Some("Card 1234"));
+
+    // The same statement again, as QFX
+    let response =
+        import_statement(&server, &auth.token, account.id, "jan.qfx", SGML_STATEMENT).await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 0);
+    assert_eq!(body["data"]["skipped"], 2);
+
+    // An overlapping statement with one new transaction
+    let overlapping = SGML_STATEMENT.replace(
+        "</BANKTRANLIST>",
+        "<STMTTRN><DTPOSTED>20260120<TRNAMT>-15.00<FITID>2026012001<NAME>CINEMA</STMTTRN>\n</BANKTRANLIST>",
+    );
+    let response =
+        import_statement(&server, &auth.token, account.id, "feb.ofx", &overlapping).await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["data"]["created"], 1);
+    assert_eq!(body["data"]["skipped"], 2);
+
+    let path = format!("/api/v1/accounts/{}", account.id);
+    let account: AccountResponse =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert_eq!(account.balance, 2442.9);
+}
+
+/// Test that invalid OFX imports are rejected or reported.
+///
+/// Verifies that:
+/// - A malformed statement fails with 422 describing the problem, creating nothing
+/// - A statement in another currency than the account fails with 422
+/// - A file that is not .ofx or .qfx fails with 422
+/// - A transaction failing validation is reported while the others are created
+/// - Another user's account is rejected with 403
+#[tokio::test]
+async fn test_import_ofx_invalid() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ofxbad_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let malformed = SGML_STATEMENT.replace("<TRNAMT>-42.10", "<TRNAMT>lots");
+    let response = import_statement(&server, &auth.token, account.id, "bad.ofx", &malformed).await;
+    assert_status(&response, 422);
+    assert!(response.text().contains("invalid TRNAMT 'lots'"));
+
+    let response =
+        import_statement(&server, &auth.token, account.id, "eur.ofx", XML_STATEMENT).await;
+    assert_status(&response, 422);
+    assert!(response.text().contains("Statement is in EUR"));
+
+    let response =
+        import_statement(&server, &auth.token, account.id, "jan.csv", SGML_STATEMENT).await;
+    assert_status(&response, 422);
+
+    let path = format!("/api/v1/accounts/{}/transactions", account.id);
+    let transactions: Vec<TransactionResponse> =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert!(transactions.is_empty());
+
+    let zero = SGML_STATEMENT.replace("<TRNAMT>2500.00", "<TRNAMT>0.00");
+    let response = import_statement(&server, &auth.token, account.id, "zero.ofx", &zero).await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["data"]["created"], 1);
+    assert_eq!(body["data"]["failed"], 1);
+    assert_eq!(
+        body["data"]["errors"],
+        json!([{ "fit_id": "2026011001", "error": "Amount cannot be zero" }])
+    );
+
+    let other = register_unique_test_user(&server, &format!("ofxother_{}", timestamp)).await;
+    let response =
+        import_statement(&server, &other.token, account.id, "jan.ofx", SGML_STATEMENT).await;
+    assert_status(&response, 403);
+}
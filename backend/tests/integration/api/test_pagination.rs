@@ -0,0 +1,271 @@
+//! Integration tests for list pagination.
+//!
+//! This module tests the shared `limit`/`offset` handling of:
+//! - GET /api/v1/transactions
+//! - GET /api/v1/people
+//!
+//! Tests cover the default page size, clamping to the maximum page size,
+//! rejection of invalid values and the `Paginated` envelope, which accounts,
+//! budgets and categories return as well, paged in the database.
+
+use crate::common::*;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    AccountResponse, CategoryResponse, PAGINATED_JSON, Paginated, PersonResponse,
+    TransactionResponse,
+};
+use serde_json::json;
+
+/// Test the default and maximum page size of the transaction list.
+///
+/// Verifies that:
+/// - Without `limit`, the default page size (50) is returned
+/// - A `limit` above the maximum is clamped to 100
+/// - `offset` skips transactions
+#[tokio::test]
+async fn test_list_transactions_page_size() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("page_tx_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    for i in 0..105 {
+        let request = json!({
+            "account_id": account.id,
+            "title": format!("Transaction {}", i),
+            "amount": -1.0,
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 50);
+
+    let response =
+        get_authenticated(&server, "/api/v1/transactions?limit=1000000", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 100);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions?limit=100&offset=100",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 5);
+}
+
+/// Test paging through the people list.
+#[tokio::test]
+async fn test_list_people_pagination() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("page_people_{}", timestamp)).await;
+
+    for name in ["Alice", "Bob", "Carol"] {
+        create_test_person(&server, &auth.token, name).await;
+    }
+
+    let response = get_authenticated(&server, "/api/v1/people?limit=2", &auth.token).await;
+    assert_status(&response, 200);
+    let people: Vec<PersonResponse> = extract_json(response);
+    let names: Vec<&str> = people.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Alice", "Bob"]);
+
+    let response = get_authenticated(&server, "/api/v1/people?limit=2&offset=2", &auth.token).await;
+    assert_status(&response, 200);
+    let people: Vec<PersonResponse> = extract_json(response);
+    let names: Vec<&str> = people.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Carol"]);
+}
+
+/// Test that invalid pagination values are rejected with 400.
+#[tokio::test]
+async fn test_invalid_pagination_rejected() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("page_invalid_{}", timestamp)).await;
+
+    for query in [
+        "limit=-1",
+        "limit=0",
+        "offset=-5",
+        "limit=abc",
+        "limit=99999999999999999999",
+    ] {
+        for path in ["/api/v1/transactions", "/api/v1/people"] {
+            let response =
+                get_authenticated(&server, &format!("{}?{}", path, query), &auth.token).await;
+            assert_status(&response, 400);
+        }
+    }
+}
+
+/// Test the `Paginated` envelope of list endpoints.
+///
+/// Verifies that:
+/// - Lists stay bare arrays unless the envelope is asked for
+/// - `?paginated=true` and the `Accept` media type both return the envelope
+/// - `page` and `per_page` select the page, `total` counts every filtered item
+///   and `has_more` is false on the last page
+/// - Accounts and categories, which are otherwise returned whole, are paged too
+/// - Mixing `page` with `offset` and a page below 1 return 400
+#[tokio::test]
+async fn test_paginated_envelope() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("page_envelope_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    create_test_account(&server, &auth.token, "Savings").await;
+    create_test_account(&server, &auth.token, "Cash").await;
+
+    for i in 0..5 {
+        let request = json!({
+            "account_id": account.id,
+            "title": format!("Transaction {}", i),
+            "amount": if i < 3 { -1.0 } else { 1.0 },
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+    for name in ["Alice", "Bob", "Carol"] {
+        create_test_person(&server, &auth.token, name).await;
+        create_test_category(&server, &auth.token, name).await;
+    }
+
+    let response = get_authenticated(&server, "/api/v1/people?per_page=2", &auth.token).await;
+    assert_status(&response, 200);
+    let people: Vec<PersonResponse> = extract_json(response);
+    assert_eq!(people.len(), 2);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/people?page=2&per_page=2&paginated=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let people: Paginated<PersonResponse> = extract_json(response);
+    assert_eq!(people.data.len(), 1);
+    assert_eq!(people.data[0].name, "Carol");
+    assert_eq!(people.total, 3);
+    assert_eq!((people.page, people.per_page), (2, 2));
+    assert!(!people.has_more);
+
+    // The total counts the filtered transactions, not the user's
+    let response = server
+        .get("/api/v1/transactions?max_amount=0&per_page=2")
+        .add_header(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_str(&format!("Bearer {}", auth.token)).unwrap(),
+        )
+        .add_header(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static(PAGINATED_JSON),
+        )
+        .await;
+    assert_status(&response, 200);
+    let transactions: Paginated<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.data.len(), 2);
+    assert_eq!(transactions.total, 3);
+    assert_eq!(transactions.page, 1);
+    assert!(transactions.has_more);
+
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    assert_status(&response, 200);
+    let accounts: Vec<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.len(), 3);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/accounts?per_page=2&paginated=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let accounts: Paginated<AccountResponse> = extract_json(response);
+    assert_eq!(accounts.data.len(), 2);
+    assert_eq!(accounts.total, 3);
+    assert!(accounts.has_more);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/categories?page=3&per_page=1&paginated=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let categories: Paginated<CategoryResponse> = extract_json(response);
+    assert_eq!(categories.data.len(), 1);
+    assert_eq!(categories.total, 3);
+    assert!(!categories.has_more);
+
+    for query in [
+        "page=2&offset=10",
+        "per_page=5&limit=5",
+        "page=0",
+        "per_page=0",
+    ] {
+        let path = format!("/api/v1/people?{}", query);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 400);
+    }
+}
+
+/// Test that accounts, budgets and categories are paged in a stable order.
+///
+/// Verifies that:
+/// - Consecutive pages don't overlap and together hold every item
+/// - Each page matches the same slice of the unpaged list
+/// - `total` counts only the items modified since `updated_since`
+#[tokio::test]
+async fn test_pages_follow_list_order() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("page_order_{}", timestamp)).await;
+    for name in ["Alpha", "Bravo", "Charlie"] {
+        create_test_account(&server, &auth.token, name).await;
+        create_test_category(&server, &auth.token, name).await;
+        let request = json!({ "name": name, "filters": {} });
+        let response = post_authenticated(&server, "/api/v1/budgets", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    for list in ["accounts", "budgets", "categories"] {
+        let response = get_authenticated(&server, &format!("/api/v1/{}", list), &auth.token).await;
+        assert_status(&response, 200);
+        let all: Vec<serde_json::Value> = extract_json(response);
+        let ids: Vec<&serde_json::Value> = all.iter().map(|item| &item["id"]).collect();
+        assert_eq!(ids.len(), 3);
+
+        let mut paged = Vec::new();
+        for page in 1..=2 {
+            let path = format!("/api/v1/{}?page={}&per_page=2&paginated=true", list, page);
+            let response = get_authenticated(&server, &path, &auth.token).await;
+            assert_status(&response, 200);
+            let page: Paginated<serde_json::Value> = extract_json(response);
+            assert_eq!(page.total, 3);
+            paged.extend(page.data.into_iter().map(|item| item["id"].clone()));
+        }
+        let paged: Vec<&serde_json::Value> = paged.iter().collect();
+        assert_eq!(paged, ids, "pages of {} follow the list order", list);
+
+        let since = Utc::now().to_rfc3339().replace('+', "%2B");
+        let path = format!("/api/v1/{}?updated_since={}&paginated=true", list, since);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 200);
+        let page: Paginated<serde_json::Value> = extract_json(response);
+        assert_eq!(page.total, 0);
+        assert!(page.data.is_empty());
+    }
+}
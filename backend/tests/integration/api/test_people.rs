@@ -6,14 +6,21 @@
 //! - GET /api/v1/people/:id - Get specific person
 //! - PUT /api/v1/people/:id - Update person
 //! - DELETE /api/v1/people/:id - Delete person
+//! - GET /api/v1/people/debts - List debts with all people
 //! - GET /api/v1/people/:id/debts - Get debts for person
+//! - GET /api/v1/people/:id/transactions - List transactions the person has a split in
+//! - GET /api/v1/people/:id/ledger - List splits and settlements with a running balance
 //! - POST /api/v1/people/:id/settle-debt - Settle debt with person
+//! - POST /api/v1/transactions/:id/splits/:split_id/settle - Settle a single split
 //!
 //! Tests cover success cases, error cases, authorization, and data isolation.
 
 use crate::common::*;
 use chrono::Utc;
-use master_of_coin_backend::{models::PersonResponse, services::debt_service::PersonDebt};
+use master_of_coin_backend::{
+    models::{PersonResponse, PersonTransactionResponse, TransactionResponse},
+    services::debt_service::{PersonDebt, PersonLedgerEntry},
+};
 use serde_json::json;
 
 // ============================================================================
@@ -189,6 +196,55 @@ async fn test_list_people_isolation() {
     assert_eq!(people_b[0].name, "User B Person");
 }
 
+/// Test that list people can be limited to recently modified people.
+///
+/// Verifies that:
+/// - Responses include created_at and updated_at
+/// - Updating a person refreshes updated_at
+/// - updated_since only returns people modified at or after the given time
+#[tokio::test]
+async fn test_list_people_updated_since() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("people_since_{}", timestamp)).await;
+
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+    assert_eq!(alice.created_at, alice.updated_at);
+
+    // Update Alice so she is the only recently modified person
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/people/{}", alice.id),
+        &auth.token,
+        &json!({ "name": "Alice Updated" }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: PersonResponse = extract_json(response);
+    assert_eq!(updated.created_at, alice.created_at);
+    assert!(updated.updated_at > bob.updated_at);
+
+    let since = updated
+        .updated_at
+        .to_rfc3339_opts(chrono::SecondsFormat::Micros, true);
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people?updated_since={}", since),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let people: Vec<PersonResponse> = extract_json(response);
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id, alice.id);
+
+    // Without the filter every person is returned
+    let response = get_authenticated(&server, "/api/v1/people", &auth.token).await;
+    let people: Vec<PersonResponse> = extract_json(response);
+    assert_eq!(people.len(), 2);
+}
+
 // ============================================================================
 // Create Person Tests
 // ============================================================================
@@ -628,6 +684,49 @@ async fn test_update_person_partial() {
     assert_eq!(updated_person.notes, Some("Original notes".to_string()));
 }
 
+/// Test that an explicit `null` clears optional fields while omitting keeps them.
+///
+/// Verifies that:
+/// - `null` clears the email
+/// - The omitted phone is left unchanged
+/// - A value sets the notes
+#[tokio::test]
+async fn test_update_person_null_clears_fields() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("personnull_{}", timestamp)).await;
+
+    let create_request = json!({
+        "name": "Alice",
+        "email": "alice@example.com",
+        "phone": "+1111111111",
+        "notes": "Original notes"
+    });
+    let create_response =
+        post_authenticated(&server, "/api/v1/people", &auth.token, &create_request).await;
+    assert_status(&create_response, 201);
+    let person: PersonResponse = extract_json(create_response);
+
+    let update_request = json!({
+        "email": null,
+        "notes": "Updated notes"
+    });
+    let update_response = put_authenticated(
+        &server,
+        &format!("/api/v1/people/{}", person.id),
+        &auth.token,
+        &update_request,
+    )
+    .await;
+    assert_status(&update_response, 200);
+
+    let updated_person: PersonResponse = extract_json(update_response);
+    assert_eq!(updated_person.email, None);
+    assert_eq!(updated_person.phone, Some("+1111111111".to_string()));
+    assert_eq!(updated_person.notes, Some("Updated notes".to_string()));
+}
+
 /// Test that updating a non-existent person fails.
 ///
 /// Verifies that:
@@ -998,6 +1097,56 @@ async fn test_get_person_debts_with_data() {
     assert_eq!(debt.debt_amount, "50.00");
 }
 
+/// Test that listing all debts returns one entry per person owing or owed.
+///
+/// Verifies that:
+/// - Status code is 200 OK
+/// - People without outstanding splits are omitted
+/// - Amounts are totalled per person
+#[tokio::test]
+async fn test_list_debts() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("listdebts_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+    create_test_person(&server, &auth.token, "Carol").await;
+
+    for (amount, splits) in [
+        (
+            -90.0,
+            json!([
+                { "person_id": alice.id, "amount": 30.0 },
+                { "person_id": bob.id, "amount": 30.0 }
+            ]),
+        ),
+        (-40.0, json!([{ "person_id": alice.id, "amount": 20.0 }])),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Shared Expense",
+            "amount": amount,
+            "date": Utc::now().to_rfc3339(),
+            "splits": splits
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/people/debts", &auth.token).await;
+    assert_status(&response, 200);
+
+    let debts: Vec<PersonDebt> = extract_json(response);
+    assert_eq!(debts.len(), 2, "Carol has no outstanding splits");
+    assert_eq!(debts[0].person_id, alice.id);
+    assert_eq!(debts[0].debt_amount, "50.00");
+    assert_eq!(debts[1].person_id, bob.id);
+    assert_eq!(debts[1].debt_amount, "30.00");
+}
+
 /// Test that getting debts for non-existent person fails.
 ///
 /// Verifies that:
@@ -1562,3 +1711,403 @@ async fn test_full_debt_management_flow() {
     let final_debt: PersonDebt = extract_json(final_debt_response);
     assert_eq!(final_debt.debt_amount, "0");
 }
+
+// ============================================================================
+// Split Settlement Tests
+// ============================================================================
+
+/// Helper to create a shared expense with a single split for `person_id`.
+async fn create_split_expense(
+    server: &axum_test::TestServer,
+    token: &str,
+    account_id: uuid::Uuid,
+    person_id: uuid::Uuid,
+    split_amount: f64,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Shared Expense",
+        "amount": -100.0,
+        "date": "2023-01-01T00:00:00Z",
+        "splits": [
+            {
+                "person_id": person_id,
+                "amount": split_amount
+            }
+        ]
+    });
+
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test settling a single split locally.
+///
+/// Verifies that:
+/// - Status code is 200 OK with the split's settled_at set
+/// - The settled split no longer counts towards the person's debt
+/// - Settling the same split again is rejected with 409 Conflict
+#[tokio::test]
+async fn test_settle_split_excludes_from_debt() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("splitsettle_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person = create_test_person(&server, &auth.token, "Test Person").await;
+
+    let first = create_split_expense(&server, &auth.token, account.id, person.id, 30.0).await;
+    create_split_expense(&server, &auth.token, account.id, person.id, 20.0).await;
+    let split_id = first.splits.unwrap()[0].id;
+
+    let path = format!(
+        "/api/v1/transactions/{}/splits/{}/settle",
+        first.id, split_id
+    );
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let split: master_of_coin_backend::models::TransactionSplitResponse = extract_json(response);
+    assert_eq!(split.id, split_id);
+    assert!(split.settled_at.is_some());
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/debts", person.id),
+        &auth.token,
+    )
+    .await;
+    let debt: PersonDebt = extract_json(response);
+    assert_eq!(debt.debt_amount, "20.00");
+
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 409);
+}
+
+/// Test that splits can only be settled through their own transaction and owner.
+///
+/// Verifies that:
+/// - A split addressed through another transaction returns 404
+/// - Another user cannot settle the split (403)
+#[tokio::test]
+async fn test_settle_split_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("splitval_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("splitvalb_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person = create_test_person(&server, &auth.token, "Test Person").await;
+
+    let first = create_split_expense(&server, &auth.token, account.id, person.id, 30.0).await;
+    let second = create_split_expense(&server, &auth.token, account.id, person.id, 20.0).await;
+    let split_id = first.splits.unwrap()[0].id;
+
+    let response = post_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions/{}/splits/{}/settle",
+            second.id, split_id
+        ),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 404);
+
+    let response = post_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions/{}/splits/{}/settle",
+            first.id, split_id
+        ),
+        &other.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 403);
+}
+
+/// Test settling debt with `settle_splits` marks the oldest covered splits.
+///
+/// Verifies that:
+/// - Splits fully covered by the payment are marked as settled, oldest first
+/// - The uncovered remainder still reduces the debt
+/// - Splits the payment does not fully cover stay unsettled
+#[tokio::test]
+async fn test_settle_debt_marks_oldest_splits() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("settlesplits_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person = create_test_person(&server, &auth.token, "Test Person").await;
+
+    let first = create_split_expense(&server, &auth.token, account.id, person.id, 30.0).await;
+    let second = create_split_expense(&server, &auth.token, account.id, person.id, 50.0).await;
+
+    let settle_request = json!({
+        "amount": 40.0,
+        "account_id": account.id,
+        "settle_splits": true
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/settle", person.id),
+        &auth.token,
+        &settle_request,
+    )
+    .await;
+    assert_status(&response, 204);
+
+    // 80 owed - 40 paid
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/debts", person.id),
+        &auth.token,
+    )
+    .await;
+    let debt: PersonDebt = extract_json(response);
+    assert_eq!(debt.debt_amount, "40.00");
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", first.id),
+        &auth.token,
+    )
+    .await;
+    let first: TransactionResponse = extract_json(response);
+    assert!(first.splits.unwrap()[0].settled_at.is_some());
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", second.id),
+        &auth.token,
+    )
+    .await;
+    let second: TransactionResponse = extract_json(response);
+    assert!(second.splits.unwrap()[0].settled_at.is_none());
+}
+
+// ============================================================================
+// Person Transactions Tests
+// ============================================================================
+
+/// Test listing the transactions a person has a split in.
+///
+/// Verifies that:
+/// - Only transactions with a split for the person are listed, newest first
+/// - Each carries the person's split amount and settled status
+/// - The list is paginated
+#[tokio::test]
+async fn test_list_person_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("persontxns_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let mut created = Vec::new();
+    for (date, splits) in [
+        (
+            "2024-01-10T00:00:00Z",
+            json!([{ "person_id": alice.id, "amount": 25.0 }]),
+        ),
+        (
+            "2024-02-10T00:00:00Z",
+            json!([
+                { "person_id": alice.id, "amount": 40.0 },
+                { "person_id": bob.id, "amount": 30.0 }
+            ]),
+        ),
+        (
+            "2024-03-10T00:00:00Z",
+            json!([{ "person_id": bob.id, "amount": 50.0 }]),
+        ),
+        ("2024-04-10T00:00:00Z", json!([])),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Shared Expense",
+            "amount": -100.0,
+            "date": date,
+            "splits": splits
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+        let transaction: TransactionResponse = extract_json(response);
+        created.push(transaction);
+    }
+
+    // Settle Alice's oldest split
+    let oldest = &created[0];
+    let split_id = oldest.splits.as_ref().unwrap()[0].id;
+    let response = post_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions/{}/splits/{}/settle",
+            oldest.id, split_id
+        ),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let path = format!("/api/v1/people/{}/transactions", alice.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<PersonTransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 2);
+
+    assert_eq!(transactions[0].transaction.id, created[1].id);
+    assert_eq!(transactions[0].split.person_id, alice.id);
+    assert_eq!(transactions[0].split.amount.to_string(), "40.00");
+    assert!(transactions[0].split.settled_at.is_none());
+
+    assert_eq!(transactions[1].transaction.id, created[0].id);
+    assert_eq!(transactions[1].split.id, split_id);
+    assert_eq!(transactions[1].split.amount.to_string(), "25.00");
+    assert!(transactions[1].split.settled_at.is_some());
+
+    let response =
+        get_authenticated(&server, &format!("{}?limit=1&offset=1", path), &auth.token).await;
+    assert_status(&response, 200);
+    let page: Vec<PersonTransactionResponse> = extract_json(response);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].transaction.id, created[0].id);
+}
+
+/// Test that listing a person's transactions checks the person.
+///
+/// Verifies that:
+/// - An unknown person returns 404 Not Found
+/// - Another user's person returns 403 Forbidden
+#[tokio::test]
+async fn test_list_person_transactions_not_found_and_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth_a = register_unique_test_user(&server, &format!("persontxnsa_{}", timestamp)).await;
+    let auth_b = register_unique_test_user(&server, &format!("persontxnsb_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth_a.token, "User A Person").await;
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/transactions", uuid::Uuid::new_v4()),
+        &auth_a.token,
+    )
+    .await;
+    assert_status(&response, 404);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/transactions", person.id),
+        &auth_b.token,
+    )
+    .await;
+    assert_status(&response, 403);
+}
+
+// ============================================================================
+// Person Ledger Tests
+// ============================================================================
+
+/// Test the ledger of a person's debt after a partial settlement.
+///
+/// Verifies that:
+/// - Splits are listed oldest first with a running balance
+/// - The settlement's remainder split and the splits it settled are included
+/// - The final balance matches the person's debt
+#[tokio::test]
+async fn test_get_person_ledger() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ledger_{}", timestamp)).await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person = create_test_person(&server, &auth.token, "Test Person").await;
+
+    let first = create_split_expense(&server, &auth.token, account.id, person.id, 30.0).await;
+    let second = create_split_expense(&server, &auth.token, account.id, person.id, 50.0).await;
+
+    let settle_request = json!({
+        "amount": 40.0,
+        "account_id": account.id,
+        "settle_splits": true
+    });
+    let response = post_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/settle", person.id),
+        &auth.token,
+        &settle_request,
+    )
+    .await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/ledger", person.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let ledger: Vec<PersonLedgerEntry> = extract_json(response);
+
+    // Two splits, the settlement's 10 remainder, then the settled 30 split
+    assert_eq!(ledger.len(), 4);
+    assert_eq!(ledger[0].transaction_id, first.id);
+    assert_eq!(ledger[0].delta, "30.00");
+    assert_eq!(ledger[0].balance, "30.00");
+    assert_eq!(ledger[1].transaction_id, second.id);
+    assert_eq!(ledger[1].balance, "80.00");
+    assert!(ledger[2].description.starts_with("Debt settlement with"));
+    assert_eq!(ledger[2].delta, "-10.00");
+    assert_eq!(ledger[2].balance, "70.00");
+    assert_eq!(ledger[3].transaction_id, first.id);
+    assert_eq!(ledger[3].delta, "-30.00");
+    assert_eq!(ledger[3].balance, "40.00");
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/debts", person.id),
+        &auth.token,
+    )
+    .await;
+    let debt: PersonDebt = extract_json(response);
+    assert_eq!(debt.debt_amount, ledger[3].balance);
+}
+
+/// Test that reading a person's ledger checks the person.
+///
+/// Verifies that:
+/// - An unknown person returns 404 Not Found
+/// - Another user's person returns 403 Forbidden
+#[tokio::test]
+async fn test_get_person_ledger_not_found_and_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth_a = register_unique_test_user(&server, &format!("ledgera_{}", timestamp)).await;
+    let auth_b = register_unique_test_user(&server, &format!("ledgerb_{}", timestamp)).await;
+    let person = create_test_person(&server, &auth_a.token, "User A Person").await;
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/ledger", uuid::Uuid::new_v4()),
+        &auth_a.token,
+    )
+    .await;
+    assert_status(&response, 404);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/ledger", person.id),
+        &auth_b.token,
+    )
+    .await;
+    assert_status(&response, 403);
+}
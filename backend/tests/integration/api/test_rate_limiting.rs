@@ -0,0 +1,161 @@
+//! Integration tests for request rate limiting
+//!
+//! These tests verify:
+//! - Unauthenticated requests are limited per client IP
+//! - Login attempts get a stricter limit of their own
+//! - Requests to protected routes are limited per client IP before authentication
+//! - Authenticated requests are limited per user
+//! - Exhausted buckets refill over time
+
+use crate::common::*;
+use axum_test::{TestResponse, TestServer};
+use chrono::Utc;
+use master_of_coin_backend::{config::BucketLimit, middleware::rate_limit::RateLimiter};
+use serde_json::json;
+use std::time::Duration;
+
+/// A bucket of `capacity` requests, regaining one a minute so it can't refill
+/// during a test
+fn bucket(capacity: u32) -> BucketLimit {
+    BucketLimit {
+        capacity,
+        refill_per_minute: 1,
+    }
+}
+
+/// Assert the request was throttled by a bucket from [`bucket`]
+fn assert_throttled(response: &TestResponse) {
+    assert_status(response, 429);
+    let retry_after: u64 = response
+        .header("retry-after")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+}
+
+async fn login(server: &TestServer, email: &str, password: &str) -> TestResponse {
+    server
+        .post("/api/v1/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .await
+}
+
+/// Test limiting login attempts.
+///
+/// Verifies that:
+/// - Failed and successful attempts both count against the login limit
+/// - Once exhausted, logins return 429 with `Retry-After` while other public
+///   routes keep working
+#[tokio::test]
+async fn test_login_rate_limit() {
+    let server = create_test_server_with_config(|config| config.rate_limit.login = bucket(2)).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("login_limit_{}", timestamp)).await;
+    let email = auth.user.email.clone();
+
+    assert_status(&login(&server, &email, "wrong-password").await, 401);
+    assert_status(&login(&server, &email, "password123").await, 200);
+    assert_throttled(&login(&server, &email, "password123").await);
+
+    let forgot = server
+        .post("/api/v1/auth/forgot-password")
+        .json(&json!({ "email": email }))
+        .await;
+    assert_status(&forgot, 200);
+}
+
+/// Test limiting unauthenticated requests by IP.
+///
+/// Verifies that:
+/// - Public routes share the IP's bucket
+/// - Once exhausted, requests return 429 with `Retry-After`
+#[tokio::test]
+async fn test_ip_rate_limit() {
+    let server = create_test_server_with_config(|config| config.rate_limit.ip = bucket(2)).await;
+
+    let forgot_password = || {
+        server
+            .post("/api/v1/auth/forgot-password")
+            .json(&json!({ "email": "nobody@example.com" }))
+    };
+    assert_status(&forgot_password().await, 200);
+    assert_status(&forgot_password().await, 200);
+    assert_throttled(&forgot_password().await);
+}
+
+/// Test limiting requests to protected routes by IP before authentication.
+///
+/// Verifies that:
+/// - Requests with invalid API keys and JWTs count against the IP's bucket
+/// - Once exhausted, requests return 429 even with valid credentials
+/// - Public routes keep their own bucket
+#[tokio::test]
+async fn test_protected_ip_rate_limit() {
+    let server =
+        create_test_server_with_config(|config| config.rate_limit.protected_ip = bucket(2)).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("protected_limit_{}", timestamp)).await;
+
+    let response = get_authenticated(&server, "/api/v1/accounts", "moc_guessedkey").await;
+    assert_status(&response, 401);
+    let response = get_authenticated(&server, "/api/v1/accounts", "not.a.jwt").await;
+    assert_status(&response, 401);
+
+    let response = get_authenticated(&server, "/api/v1/accounts", "moc_anotherguess").await;
+    assert_throttled(&response);
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    assert_throttled(&response);
+
+    let forgot = server
+        .post("/api/v1/auth/forgot-password")
+        .json(&json!({ "email": auth.user.email }))
+        .await;
+    assert_status(&forgot, 200);
+}
+
+/// Test limiting authenticated requests by user.
+///
+/// Verifies that:
+/// - Requests are counted per user, so other users are not throttled
+/// - Once exhausted, requests return 429 with `Retry-After`
+#[tokio::test]
+async fn test_user_rate_limit() {
+    let server = create_test_server_with_config(|config| config.rate_limit.user = bucket(2)).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("user_limit_{}", timestamp)).await;
+    let other =
+        register_unique_test_user(&server, &format!("user_limit_other_{}", timestamp)).await;
+
+    for _ in 0..2 {
+        let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+        assert_status(&response, 200);
+    }
+    let response = get_authenticated(&server, "/api/v1/accounts", &auth.token).await;
+    assert_throttled(&response);
+
+    let other_response = get_authenticated(&server, "/api/v1/accounts", &other.token).await;
+    assert_status(&other_response, 200);
+}
+
+/// Test that an exhausted bucket refills over time.
+///
+/// Uses the limiter directly, as the requests of a test server take too long
+/// to reliably stay within a refill interval short enough to wait for.
+#[tokio::test]
+async fn test_bucket_refills() {
+    let limiter = RateLimiter::default();
+    let limit = BucketLimit {
+        capacity: 1,
+        refill_per_minute: 60,
+    };
+
+    assert_eq!(limiter.take("client", limit), Ok(0));
+    let retry_after = limiter.take("client", limit).unwrap_err();
+    assert_eq!(retry_after, 1);
+    assert_eq!(limiter.take("other", limit), Ok(0));
+
+    tokio::time::sleep(Duration::from_secs(retry_after)).await;
+    assert_eq!(limiter.take("client", limit), Ok(0));
+}
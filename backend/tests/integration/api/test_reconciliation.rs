@@ -0,0 +1,181 @@
+//! Integration tests for account reconciliation
+//!
+//! These tests verify:
+//! - POST /api/v1/accounts/:id/reconcile - Difference to the statement balance,
+//!   transactions since the previous reconciliation and adjustments
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{Duration, NaiveDate, Utc};
+use master_of_coin_backend::models::{
+    AccountResponse, ReconciliationResponse, TransactionResponse,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn create_posted(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    title: &str,
+    amount: f64,
+    days_ago: i64,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": title,
+        "amount": amount,
+        "date": (Utc::now() - Duration::days(days_ago)).to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+async fn reconcile(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    request: Value,
+) -> ReconciliationResponse {
+    let path = format!("/api/v1/accounts/{}/reconcile", account_id);
+    let response = post_authenticated(server, &path, token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+fn days_ago(days: i64) -> NaiveDate {
+    (Utc::now() - Duration::days(days)).date_naive()
+}
+
+/// Test reconciling an account against consecutive statements.
+///
+/// Verifies that:
+/// - The balance is the posted balance at the end of the statement date, so
+///   later and pending transactions are left out
+/// - The difference is the statement balance minus the balance
+/// - A second reconciliation only lists transactions since the first one
+/// - `create_adjustment` books the difference, after which the account
+///   balance matches the statement
+#[tokio::test]
+async fn test_reconcile_account() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("reconcile_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    create_posted(&server, &auth.token, account.id, "Salary", 1000.0, 10).await;
+    create_posted(&server, &auth.token, account.id, "Rent", -400.0, 8).await;
+    create_posted(&server, &auth.token, account.id, "Groceries", -50.0, 3).await;
+    let pending = json!({
+        "account_id": account.id,
+        "title": "Pending",
+        "amount": -25.0,
+        "date": (Utc::now() - Duration::days(6)).to_rfc3339(),
+        "status": "pending"
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &pending).await;
+    assert_status(&response, 201);
+
+    // The statement ends before the groceries and shows a bank fee we didn't record
+    let first = reconcile(
+        &server,
+        &auth.token,
+        account.id,
+        json!({ "statement_balance": "595.00", "statement_date": days_ago(5) }),
+    )
+    .await;
+    assert_eq!(first.balance.to_string(), "600.00");
+    assert_eq!(first.difference.to_string(), "-5.00");
+    assert!(first.previous_statement_date.is_none());
+    let titles: Vec<&str> = first
+        .transactions
+        .iter()
+        .map(|t| t.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Salary", "Rent"]);
+    assert!(first.adjustment.is_none());
+
+    create_posted(&server, &auth.token, account.id, "Coffee", -5.0, 1).await;
+
+    let second = reconcile(
+        &server,
+        &auth.token,
+        account.id,
+        json!({
+            "statement_balance": 535.0,
+            "statement_date": days_ago(0),
+            "create_adjustment": true
+        }),
+    )
+    .await;
+    assert_eq!(second.balance.to_string(), "545.00");
+    assert_eq!(second.difference.to_string(), "-10.00");
+    assert_eq!(second.previous_statement_date, Some(days_ago(5)));
+    assert_eq!(
+        second.previous_statement_balance.map(|b| b.to_string()),
+        Some("595.00".to_string())
+    );
+    let titles: Vec<&str> = second
+        .transactions
+        .iter()
+        .map(|t| t.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Groceries", "Coffee"]);
+    let adjustment = second.adjustment.expect("adjustment created");
+    assert_eq!(adjustment.amount.to_string(), "-10.00");
+
+    let path = format!("/api/v1/accounts/{}", account.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let account: AccountResponse = extract_json(response);
+    assert_eq!(account.balance, 535.0);
+
+    // Reconciled now, so no adjustment is needed
+    let third = reconcile(
+        &server,
+        &auth.token,
+        account.id,
+        json!({
+            "statement_balance": "535.00",
+            "statement_date": days_ago(0),
+            "create_adjustment": true
+        }),
+    )
+    .await;
+    assert!(third.difference.is_zero());
+    assert!(third.adjustment.is_none());
+}
+
+/// Test the checks on reconciliation requests.
+///
+/// Verifies that:
+/// - Statement dates in the future or before the last reconciliation return 422
+/// - Other users' accounts can't be reconciled
+#[tokio::test]
+async fn test_reconcile_account_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("reconcile_bad_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let path = format!("/api/v1/accounts/{}/reconcile", account.id);
+
+    reconcile(
+        &server,
+        &auth.token,
+        account.id,
+        json!({ "statement_balance": 0, "statement_date": days_ago(2) }),
+    )
+    .await;
+
+    for statement_date in [days_ago(3), days_ago(-1)] {
+        let request = json!({ "statement_balance": 0, "statement_date": statement_date });
+        let response = post_authenticated(&server, &path, &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let other = register_unique_test_user(&server, &format!("reconcile_other_{}", timestamp)).await;
+    let request = json!({ "statement_balance": 0, "statement_date": days_ago(0) });
+    let response = post_authenticated(&server, &path, &other.token, &request).await;
+    assert_status(&response, 403);
+}
@@ -0,0 +1,225 @@
+//! Integration tests for recurring transaction endpoints.
+//!
+//! This module tests recurring transactions including:
+//! - POST /api/v1/recurring - Create recurring transaction
+//! - GET /api/v1/recurring - List recurring transactions
+//! - GET /api/v1/recurring/:id - Get recurring transaction
+//! - PUT /api/v1/recurring/:id - Update recurring transaction
+//! - DELETE /api/v1/recurring/:id - Delete recurring transaction
+//! - Materialization of due occurrences into transactions
+
+use crate::common::*;
+use chrono::{NaiveDate, TimeZone, Utc};
+use master_of_coin_backend::{
+    models::{RecurringTransactionResponse, TransactionResponse},
+    services::recurring_service::{MATERIALIZE_BATCH_SIZE, materialize_due_recurring},
+    types::RecurrenceFrequency,
+};
+use serde_json::json;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Test the recurring transaction lifecycle.
+///
+/// Verifies that:
+/// - Creating returns 201 with the interval defaulting to 1
+/// - The rule shows up in the list and can be fetched
+/// - Updating keeps omitted fields, `null` clears the category, and a new
+///   frequency restarts the schedule from the next run date
+/// - Deleting returns 204 and the rule is gone afterwards
+#[tokio::test]
+async fn test_recurring_crud() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("recurring_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let category = create_test_category(&server, &auth.token, "Housing").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "Rent",
+        "amount": -1200,
+        "notes": "Flat",
+        "frequency": "MONTHLY",
+        "next_run_date": "2030-01-31"
+    });
+    let response = post_authenticated(&server, "/api/v1/recurring", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let rule: RecurringTransactionResponse = extract_json(response);
+    assert_eq!(rule.title, "Rent");
+    assert_eq!(rule.amount.to_string(), "-1200.00");
+    assert_eq!(rule.frequency, RecurrenceFrequency::Monthly);
+    assert_eq!(rule.interval, 1);
+    assert_eq!(rule.category_id, Some(category.id));
+    assert_eq!(rule.next_run_date, date(2030, 1, 31));
+
+    let response = get_authenticated(&server, "/api/v1/recurring", &auth.token).await;
+    assert_status(&response, 200);
+    let rules: Vec<RecurringTransactionResponse> = extract_json(response);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].id, rule.id);
+
+    let path = format!("/api/v1/recurring/{}", rule.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+
+    let update = json!({
+        "category_id": null,
+        "frequency": "WEEKLY",
+        "interval": 2,
+        "next_run_date": "2030-02-04"
+    });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 200);
+    let updated: RecurringTransactionResponse = extract_json(response);
+    assert_eq!(updated.title, "Rent");
+    assert_eq!(updated.notes.as_deref(), Some("Flat"));
+    assert_eq!(updated.category_id, None);
+    assert_eq!(updated.frequency, RecurrenceFrequency::Weekly);
+    assert_eq!(updated.interval, 2);
+    assert_eq!(updated.next_run_date, date(2030, 2, 4));
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test that invalid rules and other users' data are rejected.
+///
+/// Verifies that:
+/// - A zero amount, a zero interval or an unknown frequency is rejected with 422
+/// - Another user's account is rejected with 403
+/// - Another user's rule cannot be read, updated or deleted
+#[tokio::test]
+async fn test_recurring_validation_and_ownership() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let owner = register_unique_test_user(&server, &format!("recurringown_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("recurringother_{}", timestamp)).await;
+    let account = create_test_account(&server, &owner.token, "Checking").await;
+
+    let valid = json!({
+        "account_id": account.id,
+        "title": "Gym",
+        "amount": -30,
+        "frequency": "MONTHLY",
+        "next_run_date": "2030-01-01"
+    });
+    let invalid = [
+        ("amount", json!(0)),
+        ("interval", json!(0)),
+        ("frequency", json!("FORTNIGHTLY")),
+    ];
+    for (field, value) in invalid {
+        let mut request = valid.clone();
+        request[field] = value;
+        let response =
+            post_authenticated(&server, "/api/v1/recurring", &owner.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let response = post_authenticated(&server, "/api/v1/recurring", &other.token, &valid).await;
+    assert_status(&response, 403);
+
+    let response = post_authenticated(&server, "/api/v1/recurring", &owner.token, &valid).await;
+    assert_status(&response, 201);
+    let rule: RecurringTransactionResponse = extract_json(response);
+
+    let path = format!("/api/v1/recurring/{}", rule.id);
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+    let response = put_authenticated(&server, &path, &other.token, &json!({ "title": "x" })).await;
+    assert_status(&response, 403);
+    let response = delete_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+}
+
+/// Test creating the transactions of due occurrences.
+///
+/// Verifies that:
+/// - Every missed occurrence is created, with month-end dates clamped per month
+///   (Jan 31, Feb 28, Mar 31) rather than drifting
+/// - The schedule advances to the next future occurrence
+/// - Running again creates nothing, so restarts do not duplicate transactions
+#[tokio::test]
+async fn test_materialize_due_recurring() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("recurringrun_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Rent",
+        "amount": -1200,
+        "frequency": "MONTHLY",
+        "next_run_date": "2030-01-31"
+    });
+    let response = post_authenticated(&server, "/api/v1/recurring", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let rule: RecurringTransactionResponse = extract_json(response);
+
+    let now = Utc.with_ymd_and_hms(2030, 4, 15, 12, 0, 0).unwrap();
+    let created = materialize_due_recurring(&state.db, auth.user.id, now)
+        .await
+        .unwrap();
+    let dates: Vec<NaiveDate> = created.iter().map(|t| t.date.date_naive()).collect();
+    assert_eq!(
+        dates,
+        vec![date(2030, 1, 31), date(2030, 2, 28), date(2030, 3, 31)]
+    );
+
+    let path = format!("/api/v1/recurring/{}", rule.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    let rule: RecurringTransactionResponse = extract_json(response);
+    assert_eq!(rule.next_run_date, date(2030, 4, 30));
+
+    let created = materialize_due_recurring(&state.db, auth.user.id, now)
+        .await
+        .unwrap();
+    assert!(created.is_empty());
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 3);
+    assert!(transactions.iter().all(|t| t.title == "Rent"));
+}
+
+/// Test catching up on more occurrences than are created per batch.
+#[tokio::test]
+async fn test_materialize_due_recurring_catches_up() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("recurringcatch_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Coffee",
+        "amount": -3,
+        "frequency": "DAILY",
+        "next_run_date": "2030-01-01"
+    });
+    let response = post_authenticated(&server, "/api/v1/recurring", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    // 2030-01-01 through 2030-09-07 are 250 days
+    let now = Utc.with_ymd_and_hms(2030, 9, 7, 0, 0, 0).unwrap();
+    let created = materialize_due_recurring(&state.db, auth.user.id, now)
+        .await
+        .unwrap();
+    assert!(created.len() > MATERIALIZE_BATCH_SIZE);
+    assert_eq!(created.len(), 250);
+    assert_eq!(created.last().unwrap().date.date_naive(), date(2030, 9, 7));
+}
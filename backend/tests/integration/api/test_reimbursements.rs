@@ -0,0 +1,243 @@
+//! Integration tests for reimbursable transactions
+//!
+//! These tests verify:
+//! - Transactions can be marked reimbursable on create and update
+//! - GET /api/v1/transactions/reimbursable/outstanding - Outstanding amounts and filters
+//! - POST /api/v1/transactions/:id/mark-reimbursed - Recording the reimbursement
+//! - GET /api/v1/dashboard/categories?exclude_reimbursed=true - Spending without reimbursed items
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{Duration, Utc};
+use master_of_coin_backend::models::{OutstandingReimbursementsResponse, TransactionResponse};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn create_expense(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    category_id: Option<Uuid>,
+    amount: f64,
+    reimbursable: bool,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "category_id": category_id,
+        "title": "Work expense",
+        "amount": -amount,
+        "date": (Utc::now() - Duration::minutes(1)).to_rfc3339(),
+        "reimbursable": reimbursable
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+async fn outstanding(
+    server: &TestServer,
+    token: &str,
+    path: &str,
+) -> OutstandingReimbursementsResponse {
+    let response = get_authenticated(server, path, token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// Test tracking reimbursable transactions until they are paid back.
+///
+/// Verifies that:
+/// - `reimbursable` is stored on create and can be set on update
+/// - Outstanding totals are owed amounts per currency and per category
+/// - `?category_id=` narrows the outstanding transactions down
+/// - Marking reimbursed sets `reimbursed_at`, keeps the first time and removes
+///   the transaction from the outstanding list
+/// - Marking a transaction that is not reimbursable returns 409
+/// - Clearing `reimbursable` clears `reimbursed_at`
+/// - Other users can neither see nor mark the transactions
+#[tokio::test]
+async fn test_reimbursable_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("reimburse_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let travel = create_test_category(&server, &auth.token, "Travel").await;
+    let meals = create_test_category(&server, &auth.token, "Meals").await;
+
+    let flight = create_expense(
+        &server,
+        &auth.token,
+        account.id,
+        Some(travel.id),
+        300.0,
+        true,
+    )
+    .await;
+    assert!(flight.reimbursable);
+    assert!(flight.reimbursed_at.is_none());
+    create_expense(&server, &auth.token, account.id, Some(meals.id), 40.0, true).await;
+    let personal = create_expense(
+        &server,
+        &auth.token,
+        account.id,
+        Some(meals.id),
+        25.0,
+        false,
+    )
+    .await;
+    assert!(!personal.reimbursable);
+
+    let path = "/api/v1/transactions/reimbursable/outstanding";
+    let report = outstanding(&server, &auth.token, path).await;
+    assert_eq!(report.transactions.len(), 2);
+    assert_eq!(report.totals.len(), 1);
+    assert_eq!(report.totals[0].count, 2);
+    assert_eq!(report.totals[0].amount.to_string(), "340.00");
+    assert_eq!(report.by_category.len(), 2);
+    let travel_total = report
+        .by_category
+        .iter()
+        .find(|total| total.category_id == Some(travel.id))
+        .expect("travel total");
+    assert_eq!(travel_total.amount.to_string(), "300.00");
+
+    let filtered = outstanding(
+        &server,
+        &auth.token,
+        &format!("{}?category_id={}", path, meals.id),
+    )
+    .await;
+    assert_eq!(filtered.transactions.len(), 1);
+    assert_eq!(filtered.totals[0].amount.to_string(), "40.00");
+
+    // Marking the personal expense reimbursable adds it to the outstanding amount
+    let personal_path = format!("/api/v1/transactions/{}", personal.id);
+    let response = post_authenticated(
+        &server,
+        &format!("{}/mark-reimbursed", personal_path),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 409);
+    let response = put_authenticated(
+        &server,
+        &personal_path,
+        &auth.token,
+        &json!({ "reimbursable": true }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let report = outstanding(&server, &auth.token, path).await;
+    assert_eq!(report.totals[0].amount.to_string(), "365.00");
+
+    // Another user can't mark it, nor see it
+    let other = register_unique_test_user(&server, &format!("reimburse_other_{}", timestamp)).await;
+    let mark_path = format!("/api/v1/transactions/{}/mark-reimbursed", flight.id);
+    let response = post_authenticated(&server, &mark_path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+    let report = outstanding(&server, &other.token, path).await;
+    assert!(report.transactions.is_empty());
+    assert!(report.totals.is_empty());
+
+    let response = post_authenticated(&server, &mark_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let reimbursed: TransactionResponse = extract_json(response);
+    let reimbursed_at = reimbursed.reimbursed_at.expect("reimbursed_at set");
+    assert!(reimbursed.version > flight.version);
+
+    let response = post_authenticated(&server, &mark_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let again: TransactionResponse = extract_json(response);
+    assert_eq!(again.reimbursed_at, Some(reimbursed_at));
+
+    let report = outstanding(&server, &auth.token, path).await;
+    assert_eq!(report.transactions.len(), 2);
+    assert!(report.transactions.iter().all(|t| t.id != flight.id));
+    assert_eq!(report.totals[0].amount.to_string(), "65.00");
+
+    let flight_path = format!("/api/v1/transactions/{}", flight.id);
+    let response = put_authenticated(
+        &server,
+        &flight_path,
+        &auth.token,
+        &json!({ "reimbursable": false }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let cleared: TransactionResponse = extract_json(response);
+    assert!(!cleared.reimbursable);
+    assert!(cleared.reimbursed_at.is_none());
+}
+
+/// Test leaving reimbursed spending out of the dashboard's category totals.
+///
+/// Verifies that:
+/// - Reimbursed spending counts by default
+/// - `?exclude_reimbursed=true` leaves it out, while outstanding reimbursable
+///   spending still counts
+#[tokio::test]
+async fn test_dashboard_exclude_reimbursed() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("reimburse_dash_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let travel = create_test_category(&server, &auth.token, "Travel").await;
+
+    let hotel = create_expense(
+        &server,
+        &auth.token,
+        account.id,
+        Some(travel.id),
+        200.0,
+        true,
+    )
+    .await;
+    create_expense(
+        &server,
+        &auth.token,
+        account.id,
+        Some(travel.id),
+        50.0,
+        true,
+    )
+    .await;
+    let path = format!("/api/v1/transactions/{}/mark-reimbursed", hotel.id);
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+
+    // Grouped per currency, so no exchange rates are needed
+    let travel_id = travel.id.to_string();
+    let travel_total = |categories: Value| {
+        categories["category_breakdown"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["category_id"] == travel_id.as_str())
+            .map(|item| item["total"].as_str().unwrap().to_string())
+    };
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/categories?group_by_currency=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert_eq!(
+        travel_total(extract_json(response)).as_deref(),
+        Some("250.00")
+    );
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/dashboard/categories?group_by_currency=true&exclude_reimbursed=true",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert_eq!(
+        travel_total(extract_json(response)).as_deref(),
+        Some("50.00")
+    );
+}
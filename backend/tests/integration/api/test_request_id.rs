@@ -0,0 +1,62 @@
+//! Integration tests for request IDs
+//!
+//! These tests verify:
+//! - Every response carries an `X-Request-Id` header
+//! - A usable `X-Request-Id` sent by the client is echoed back
+
+use crate::common::*;
+use chrono::Utc;
+use http::header::AUTHORIZATION;
+use http::{HeaderName, HeaderValue};
+use uuid::Uuid;
+
+const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Test that responses carry the request's ID.
+///
+/// Verifies that:
+/// - Without an incoming ID, a UUID is generated per request
+/// - An incoming ID is echoed back, also on authenticated requests
+/// - IDs that are too long or contain spaces are replaced by a UUID
+/// - Rejected requests carry the ID too
+#[tokio::test]
+async fn test_request_id_header() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("request_id_{}", timestamp)).await;
+
+    let first = server.get("/api/v1/auth/me").await;
+    let second = server.get("/api/v1/auth/me").await;
+    let first_id = first.header(REQUEST_ID);
+    let first_id = first_id.to_str().unwrap();
+    assert!(Uuid::parse_str(first_id).is_ok());
+    assert_ne!(first_id, second.header(REQUEST_ID).to_str().unwrap());
+
+    let response = server
+        .get("/api/v1/accounts")
+        .add_header(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", auth.token)).unwrap(),
+        )
+        .add_header(REQUEST_ID, HeaderValue::from_static("client-trace-42"))
+        .await;
+    assert_status(&response, 200);
+    assert_eq!(response.header(REQUEST_ID), "client-trace-42");
+
+    let too_long = "a".repeat(129);
+    for invalid in [too_long.as_str(), "has spaces"] {
+        let response = server
+            .get("/api/v1/auth/me")
+            .add_header(REQUEST_ID, HeaderValue::from_str(invalid).unwrap())
+            .await;
+        let id = response.header(REQUEST_ID);
+        assert!(Uuid::parse_str(id.to_str().unwrap()).is_ok());
+    }
+
+    let response = server
+        .get("/api/v1/accounts")
+        .add_header(REQUEST_ID, HeaderValue::from_static("rejected-1"))
+        .await;
+    assert_status(&response, 401);
+    assert_eq!(response.header(REQUEST_ID), "rejected-1");
+}
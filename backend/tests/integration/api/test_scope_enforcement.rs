@@ -40,6 +40,7 @@ async fn test_api_key_transaction_scope_cannot_access_accounts() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -92,6 +93,7 @@ async fn test_api_key_account_read_scope_can_list_accounts() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -141,6 +143,7 @@ async fn test_api_key_account_read_scope_cannot_create() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -260,6 +263,7 @@ async fn test_api_key_multiple_scopes() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -325,6 +329,7 @@ async fn test_api_key_write_scope_allows_write_operations() {
             people: vec![],
         },
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     let create_response = server
@@ -380,6 +385,7 @@ async fn test_scope_enforcement_all_resources() {
         name: "No Permissions Key".to_string(),
         scopes: ApiKeyScopes::default(), // Empty scopes
         expires_in_days: Some(90),
+        requests_per_minute: None,
     };
 
     // This should fail validation (no scopes)
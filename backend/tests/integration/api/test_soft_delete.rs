@@ -0,0 +1,344 @@
+//! Integration tests for deleting and restoring transactions.
+//!
+//! This module tests soft deletes including:
+//! - DELETE /api/v1/transactions/:id - Keep the deleted transaction out of sight
+//! - GET /api/v1/transactions?include_deleted=true - List deleted transactions
+//! - POST /api/v1/transactions/:id/restore - Restore a deleted transaction
+//! - Deleting an account whose only transactions are deleted
+//! - POST /api/v1/admin/purge - Purge transactions deleted beyond the retention period
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use master_of_coin_backend::{
+    models::{AccountResponse, PurgeResponse, TransactionResponse, TransferResponse},
+    schema::{transaction_splits, transactions},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+const ADMIN_KEY: &str = "test_admin_key_at_least_32_characters_long";
+
+/// Create a transaction on the account and return it
+async fn create_transaction(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    amount: f64,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Groceries",
+        "amount": amount,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Get the account's balance
+async fn account_balance(server: &TestServer, token: &str, account_id: Uuid) -> f64 {
+    let path = format!("/api/v1/accounts/{}", account_id);
+    let account: AccountResponse = extract_json(get_authenticated(server, &path, token).await);
+    account.balance
+}
+
+/// Test that a deleted transaction disappears until it is restored.
+///
+/// Verifies that:
+/// - After a delete the transaction returns 404, is left out of the list and
+///   no longer counts toward the account balance
+/// - `include_deleted=true` lists it with `deleted_at` set
+/// - Restoring returns it without `deleted_at` and counts it again
+#[tokio::test]
+async fn test_delete_and_restore_transaction() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("softdel_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let kept = create_transaction(&server, &auth.token, account.id, -10.0).await;
+    let deleted = create_transaction(&server, &auth.token, account.id, -25.0).await;
+
+    let path = format!("/api/v1/transactions/{}", deleted.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    let ids: Vec<Uuid> = transactions.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![kept.id]);
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        -10.0
+    );
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions?include_deleted=true",
+        &auth.token,
+    )
+    .await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 2);
+    let listed = transactions.iter().find(|t| t.id == deleted.id).unwrap();
+    assert!(listed.deleted_at.is_some());
+
+    let restore_path = format!("/api/v1/transactions/{}/restore", deleted.id);
+    let response = post_authenticated(&server, &restore_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let restored: TransactionResponse = extract_json(response);
+    assert_eq!(restored.id, deleted.id);
+    assert!(restored.deleted_at.is_none());
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        -35.0
+    );
+}
+
+/// Test that only the owner can restore, and only deleted transactions.
+///
+/// Verifies that:
+/// - Restoring a transaction that is not deleted returns 409
+/// - Restoring another user's deleted transaction returns 403
+/// - Restoring an unknown transaction returns 404
+#[tokio::test]
+async fn test_restore_transaction_rejected() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("restorebad_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("restoreother_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let transaction = create_transaction(&server, &auth.token, account.id, -5.0).await;
+
+    let path = format!("/api/v1/transactions/{}/restore", transaction.id);
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 409);
+
+    let delete_path = format!("/api/v1/transactions/{}", transaction.id);
+    let response = delete_authenticated(&server, &delete_path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = post_authenticated(&server, &path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+
+    let path = format!("/api/v1/transactions/{}/restore", Uuid::new_v4());
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 404);
+}
+
+/// Test that restoring either leg of a deleted transfer restores both.
+#[tokio::test]
+async fn test_restore_transfer_leg() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("restorexfer_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 40,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transfer: TransferResponse = extract_json(response);
+
+    let path = format!("/api/v1/transactions/{}", transfer.from_transaction.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+    assert_eq!(account_balance(&server, &auth.token, savings.id).await, 0.0);
+
+    let path = format!(
+        "/api/v1/transactions/{}/restore",
+        transfer.to_transaction.id
+    );
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+
+    for leg in [&transfer.from_transaction, &transfer.to_transaction] {
+        let path = format!("/api/v1/transactions/{}", leg.id);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 200);
+    }
+    assert_eq!(
+        account_balance(&server, &auth.token, checking.id).await,
+        -40.0
+    );
+    assert_eq!(
+        account_balance(&server, &auth.token, savings.id).await,
+        40.0
+    );
+}
+
+/// Test that an account whose transactions are all deleted can be deleted.
+#[tokio::test]
+async fn test_delete_account_with_deleted_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("softdelacct_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let transaction = create_transaction(&server, &auth.token, account.id, -5.0).await;
+
+    let account_path = format!("/api/v1/accounts/{}", account.id);
+    let response = delete_authenticated(&server, &account_path, &auth.token).await;
+    assert_status(&response, 422);
+
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = delete_authenticated(&server, &account_path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, &account_path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+fn get_test_db_pool() -> master_of_coin_backend::DbPool {
+    use diesel::PgConnection;
+    use diesel::r2d2::{self, ConnectionManager};
+    dotenvy::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for integration tests");
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .max_size(5)
+        .build(manager)
+        .expect("Failed to create test database pool")
+}
+
+/// Test that the purge endpoint is refused without the configured admin key.
+#[tokio::test]
+async fn test_purge_requires_admin_key() {
+    let unconfigured = create_test_server().await;
+    let response = unconfigured
+        .post("/api/v1/admin/purge")
+        .add_header("X-Admin-Key", ADMIN_KEY)
+        .await;
+    assert_status(&response, 401);
+
+    let server = create_test_server_with_config(|config| {
+        config.admin_api_key = Some(ADMIN_KEY.to_string());
+    })
+    .await;
+    let response = server.post("/api/v1/admin/purge").await;
+    assert_status(&response, 401);
+    let response = server
+        .post("/api/v1/admin/purge")
+        .add_header("X-Admin-Key", "not_the_admin_key_but_just_as_long_as_it")
+        .await;
+    assert_status(&response, 401);
+
+    // A user's token is no admin key
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("purgeuser_{}", timestamp)).await;
+    let response =
+        post_authenticated(&server, "/api/v1/admin/purge", &auth.token, &json!({})).await;
+    assert_status(&response, 401);
+}
+
+/// Test that the purge removes transactions deleted beyond the retention period only.
+///
+/// Verifies that:
+/// - A transaction deleted longer ago than the retention is removed for good,
+///   with its splits, and counted per table
+/// - Batches smaller than the rows to purge still purge them all
+/// - A recently deleted transaction can still be restored
+/// - Live transactions are not touched
+#[tokio::test]
+async fn test_purge_removes_expired_deleted_transactions() {
+    let server = create_test_server_with_config(|config| {
+        config.admin_api_key = Some(ADMIN_KEY.to_string());
+        config.purge.retention_days = 30;
+        config.purge.batch_size = 1;
+    })
+    .await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("purge_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Friend").await;
+    let live = create_transaction(&server, &auth.token, account.id, -10.0).await;
+    let recent = create_transaction(&server, &auth.token, account.id, -20.0).await;
+    let mut expired = Vec::new();
+    for _ in 0..2 {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Dinner",
+            "amount": -30.0,
+            "date": Utc::now().to_rfc3339(),
+            "splits": [{"person_id": person.id, "amount": 15.0}]
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+        let transaction: TransactionResponse = extract_json(response);
+        expired.push(transaction.id);
+    }
+
+    for id in expired.iter().chain([&recent.id]) {
+        let path = format!("/api/v1/transactions/{}", id);
+        let response = delete_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 204);
+    }
+    let pool = get_test_db_pool();
+    let mut conn = pool.get().unwrap();
+    diesel::update(transactions::table.filter(transactions::id.eq_any(&expired)))
+        .set(transactions::deleted_at.eq(Some(Utc::now() - Duration::days(31))))
+        .execute(&mut conn)
+        .unwrap();
+
+    let response = server
+        .post("/api/v1/admin/purge")
+        .add_header("X-Admin-Key", ADMIN_KEY)
+        .await;
+    assert_status(&response, 200);
+    let purge: PurgeResponse = extract_json(response);
+    assert!(purge.cutoff.is_some());
+    assert!(purge.purged.transactions >= 2);
+    assert!(purge.purged.transaction_splits >= 2);
+
+    let remaining: i64 = transactions::table
+        .filter(transactions::id.eq_any(&expired))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(remaining, 0);
+    let remaining_splits: i64 = transaction_splits::table
+        .filter(transaction_splits::transaction_id.eq_any(&expired))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(remaining_splits, 0);
+
+    let path = format!("/api/v1/transactions/{}/restore", recent.id);
+    let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let path = format!("/api/v1/transactions/{}", live.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    assert_eq!(
+        account_balance(&server, &auth.token, account.id).await,
+        -30.0
+    );
+}
@@ -0,0 +1,225 @@
+//! Integration tests for split group endpoints.
+//!
+//! This module tests split groups including:
+//! - POST /api/v1/split-groups - Create split group
+//! - GET /api/v1/split-groups - List split groups
+//! - GET /api/v1/split-groups/:id - Get split group
+//! - PUT /api/v1/split-groups/:id - Update split group
+//! - DELETE /api/v1/split-groups/:id - Delete split group
+//! - POST /api/v1/transactions with `split_group_id`
+
+use crate::common::*;
+use chrono::Utc;
+use master_of_coin_backend::models::{SplitGroupResponse, TransactionResponse};
+use serde_json::json;
+
+/// Test the split group lifecycle.
+///
+/// Verifies that:
+/// - Creating returns 201 with members in ascending person ID order
+/// - The group shows up in the list and can be fetched
+/// - Updating members replaces them
+/// - Deleting returns 204 and the group is gone afterwards
+#[tokio::test]
+async fn test_split_group_crud() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitgroup_{}", timestamp)).await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let request = json!({
+        "name": "Household",
+        "members": [
+            { "person_id": alice.id, "percentage": 25 },
+            { "person_id": bob.id, "percentage": 33.33 }
+        ]
+    });
+    let response = post_authenticated(&server, "/api/v1/split-groups", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let group: SplitGroupResponse = extract_json(response);
+    assert_eq!(group.name, "Household");
+    assert_eq!(group.members.len(), 2);
+    let mut expected = vec![(alice.id, "25.00"), (bob.id, "33.33")];
+    expected.sort();
+    for (member, (person_id, percentage)) in group.members.iter().zip(&expected) {
+        assert_eq!(member.person_id, *person_id);
+        assert_eq!(member.percentage, *percentage);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/split-groups", &auth.token).await;
+    assert_status(&response, 200);
+    let groups: Vec<SplitGroupResponse> = extract_json(response);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].id, group.id);
+
+    let path = format!("/api/v1/split-groups/{}", group.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+
+    let update = json!({ "members": [{ "person_id": bob.id, "percentage": 50 }] });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 200);
+    let updated: SplitGroupResponse = extract_json(response);
+    assert_eq!(updated.name, "Household");
+    assert_eq!(updated.members.len(), 1);
+    assert_eq!(updated.members[0].person_id, bob.id);
+    assert_eq!(updated.members[0].percentage, "50.00");
+
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 404);
+}
+
+/// Test that invalid member lists are rejected with 422.
+#[tokio::test]
+async fn test_create_split_group_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitgroupval_{}", timestamp)).await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let invalid_members = [
+        json!([]),
+        json!([
+            { "person_id": alice.id, "percentage": 60 },
+            { "person_id": bob.id, "percentage": 40.01 }
+        ]),
+        json!([
+            { "person_id": alice.id, "percentage": 20 },
+            { "person_id": alice.id, "percentage": 20 }
+        ]),
+        json!([{ "person_id": alice.id, "percentage": 12.345 }]),
+        json!([{ "person_id": alice.id, "percentage": 0 }]),
+    ];
+
+    for members in invalid_members {
+        let request = json!({ "name": "Invalid", "members": members });
+        let response =
+            post_authenticated(&server, "/api/v1/split-groups", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+}
+
+/// Test that split groups cannot use or expose other users' data.
+///
+/// Verifies that:
+/// - A group with another user's person is rejected with 403
+/// - Another user's group cannot be read, updated or deleted
+#[tokio::test]
+async fn test_split_group_ownership() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let owner = register_unique_test_user(&server, &format!("splitgroupown_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("splitgroupother_{}", timestamp)).await;
+    let alice = create_test_person(&server, &owner.token, "Alice").await;
+
+    let request = json!({
+        "name": "Not mine",
+        "members": [{ "person_id": alice.id, "percentage": 50 }]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/split-groups", &other.token, &request).await;
+    assert_status(&response, 403);
+
+    let response =
+        post_authenticated(&server, "/api/v1/split-groups", &owner.token, &request).await;
+    assert_status(&response, 201);
+    let group: SplitGroupResponse = extract_json(response);
+
+    let path = format!("/api/v1/split-groups/{}", group.id);
+    let response = get_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+    let response = put_authenticated(&server, &path, &other.token, &json!({ "name": "x" })).await;
+    assert_status(&response, 403);
+    let response = delete_authenticated(&server, &path, &other.token).await;
+    assert_status(&response, 403);
+}
+
+/// Test creating a transaction split with a group.
+///
+/// Verifies that:
+/// - Members' shares are recorded as splits, the user keeping the rest
+/// - Leftover cents go to the lowest person ID, so shares are deterministic
+/// - Combining `split_group_id` with `splits` is rejected with 422
+/// - Another user's group is rejected with 403 and no transaction is saved
+#[tokio::test]
+async fn test_create_transaction_with_split_group() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitgrouptx_{}", timestamp)).await;
+    let other =
+        register_unique_test_user(&server, &format!("splitgrouptxother_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let request = json!({
+        "name": "Flatmates",
+        "members": [
+            { "person_id": alice.id, "percentage": 50 },
+            { "person_id": bob.id, "percentage": 50 }
+        ]
+    });
+    let response = post_authenticated(&server, "/api/v1/split-groups", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let group: SplitGroupResponse = extract_json(response);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Groceries",
+        "amount": -10.01,
+        "date": Utc::now().to_rfc3339(),
+        "split_group_id": group.id
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let transaction: TransactionResponse = extract_json(response);
+    let mut splits = transaction.splits.expect("Splits should be created");
+    splits.sort_by_key(|split| split.person_id);
+    let mut people = [alice.id, bob.id];
+    people.sort();
+    assert_eq!(splits.len(), 2);
+    assert_eq!(splits[0].person_id, people[0]);
+    assert_eq!(splits[0].amount.as_decimal(), &decimal("5.01"));
+    assert_eq!(splits[1].person_id, people[1]);
+    assert_eq!(splits[1].amount.as_decimal(), &decimal("5.00"));
+
+    let conflicting = json!({
+        "account_id": account.id,
+        "title": "Groceries",
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [{ "person_id": alice.id, "amount": 5.0 }],
+        "split_group_id": group.id
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions", &auth.token, &conflicting).await;
+    assert_status(&response, 422);
+
+    let other_account = create_test_account(&server, &other.token, "Checking").await;
+    let foreign = json!({
+        "account_id": other_account.id,
+        "title": "Groceries",
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339(),
+        "split_group_id": group.id
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions", &other.token, &foreign).await;
+    assert_status(&response, 403);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &other.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert!(transactions.is_empty());
+}
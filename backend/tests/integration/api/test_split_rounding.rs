@@ -0,0 +1,187 @@
+//! Integration tests for equal split rounding.
+//!
+//! This module tests the split rounding policy including:
+//! - Leftover cents are distributed one at a time in ascending person ID order
+//! - Currencies without minor units are split into whole amounts
+//! - Shares always sum exactly to the split total (property-style check)
+//! - POST /api/v1/transactions with `split_equally_with`
+
+use crate::common::*;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    AccountResponse, TransactionResponse, transaction_split::split_equally,
+};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Test that $10 split three ways gives the extra cent to the lowest person ID.
+#[test]
+fn test_split_equally_assigns_leftover_cents_by_person_id() {
+    let mut participants: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    participants.sort();
+
+    let shares = split_equally(&decimal("10.00"), &participants, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (participants[0], decimal("3.34")),
+            (participants[1], decimal("3.33")),
+            (participants[2], decimal("3.33")),
+        ]
+    );
+
+    let shares = split_equally(&decimal("-0.05"), &participants, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (participants[0], decimal("-0.02")),
+            (participants[1], decimal("-0.02")),
+            (participants[2], decimal("-0.01")),
+        ]
+    );
+}
+
+/// Test that a JPY total is split into whole yen.
+#[test]
+fn test_split_equally_in_zero_decimal_currency() {
+    let mut participants: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    participants.sort();
+
+    let shares = split_equally(&decimal("1000"), &participants, 0);
+    assert_eq!(
+        shares,
+        vec![
+            (participants[0], decimal("334")),
+            (participants[1], decimal("333")),
+            (participants[2], decimal("333")),
+        ]
+    );
+}
+
+/// Property-style test over random totals and participant counts.
+///
+/// Verifies that:
+/// - Shares always sum exactly to the total
+/// - Shares differ by at most one cent
+/// - The result does not depend on the order participants are given in
+#[test]
+fn test_split_equally_shares_sum_to_total() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000 {
+        let cents: i64 = rng.gen_range(-10_000_000..=10_000_000);
+        let total = BigDecimal::new(cents.into(), 2);
+        let count = rng.gen_range(1..=25);
+        let mut participants: Vec<Uuid> = (0..count).map(|_| Uuid::new_v4()).collect();
+
+        let shares = split_equally(&total, &participants, 2);
+        assert_eq!(shares.len(), count);
+
+        let sum: BigDecimal = shares.iter().map(|(_, share)| share.clone()).sum();
+        assert_eq!(sum, total, "Shares of {} between {} must sum", total, count);
+
+        let smallest = shares.iter().map(|(_, share)| share).min().unwrap();
+        let largest = shares.iter().map(|(_, share)| share).max().unwrap();
+        assert!(largest - smallest <= decimal("0.01"));
+
+        participants.shuffle(&mut rng);
+        assert_eq!(split_equally(&total, &participants, 2), shares);
+    }
+}
+
+/// Test creating a transaction split equally with people.
+///
+/// Verifies that:
+/// - Status code is 201 Created
+/// - The user's own share is not recorded as a split
+/// - Split amounts follow the rounding policy
+/// - Combining `splits` and `split_equally_with` is rejected with 422
+#[tokio::test]
+async fn test_create_transaction_split_equally() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitequal_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339(),
+        "split_equally_with": [alice.id, bob.id]
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let transaction: TransactionResponse = extract_json(response);
+    let splits = transaction.splits.expect("Splits should be created");
+    assert_eq!(splits.len(), 2);
+
+    let expected = split_equally(&decimal("10.00"), &[auth.user.id, alice.id, bob.id], 2);
+    for split in &splits {
+        let (_, share) = expected
+            .iter()
+            .find(|(person_id, _)| *person_id == split.person_id)
+            .expect("Split should belong to a participant");
+        assert_eq!(split.amount.as_decimal(), share);
+    }
+
+    let conflicting = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [{ "person_id": alice.id, "amount": 5.0 }],
+        "split_equally_with": [bob.id]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions", &auth.token, &conflicting).await;
+    assert_status(&response, 422);
+}
+
+/// Test that splitting a transaction on a JPY account equally records whole yen.
+#[tokio::test]
+async fn test_create_transaction_split_equally_zero_decimal_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitequal_jpy_{}", timestamp)).await;
+    let request = json!({
+        "name": "Yen Wallet",
+        "account_type": "CASH",
+        "currency": "JPY"
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Ramen",
+        "amount": "-1000",
+        "date": Utc::now().to_rfc3339(),
+        "split_equally_with": [alice.id, bob.id]
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let transaction: TransactionResponse = extract_json(response);
+    let splits = transaction.splits.expect("Splits should be created");
+    let expected = split_equally(&decimal("1000"), &[auth.user.id, alice.id, bob.id], 0);
+    for split in &splits {
+        let (_, share) = expected
+            .iter()
+            .find(|(person_id, _)| *person_id == split.person_id)
+            .expect("Split should belong to a participant");
+        assert_eq!(split.amount.as_decimal(), share);
+        assert_eq!(split.amount.as_decimal().fractional_digit_count(), 0);
+    }
+}
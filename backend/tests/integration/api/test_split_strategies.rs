@@ -0,0 +1,258 @@
+//! Integration tests for split strategies.
+//!
+//! This module tests splits computed from a `split_strategy` including:
+//! - EQUAL, PERCENTAGE and SHARES amounts, with leftover units handed out one
+//!   at a time in ascending person ID order, matching `split_equally`
+//! - POST /api/v1/transactions with `split_strategy` and `participants`
+//! - Rejecting percentages that do not add up to 100
+
+use crate::common::*;
+use bigdecimal::Zero;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    SplitParticipant, SplitStrategy, TransactionResponse,
+    transaction_split::{split_by_strategy, split_equally},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+fn participant(percentage: Option<f64>, shares: Option<u32>) -> SplitParticipant {
+    SplitParticipant {
+        person_id: Uuid::new_v4(),
+        percentage,
+        shares,
+    }
+}
+
+/// Participants with ascending person IDs, so the order leftover units are
+/// handed out in is known
+fn sorted(mut people: Vec<SplitParticipant>) -> Vec<SplitParticipant> {
+    people.sort_by_key(|participant| participant.person_id);
+    people
+}
+
+/// Test that each strategy's shares add up to the total, with leftover units
+/// handed out one at a time in ascending person ID order.
+#[test]
+fn test_split_by_strategy_hands_out_leftover_units_by_person_id() {
+    let people = sorted((0..3).map(|_| participant(None, None)).collect());
+    let shares = split_by_strategy(&decimal("10.00"), SplitStrategy::Equal, &people, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (people[0].person_id, decimal("3.34")),
+            (people[1].person_id, decimal("3.33")),
+            (people[2].person_id, decimal("3.33")),
+        ]
+    );
+
+    // Shares come back in the given order, but rounding does not depend on it
+    let reversed: Vec<SplitParticipant> = people.iter().rev().cloned().collect();
+    let shares = split_by_strategy(&decimal("10.00"), SplitStrategy::Equal, &reversed, 2);
+    assert_eq!(shares[2], (people[0].person_id, decimal("3.34")));
+
+    // An equal split matches split_equally between the same people
+    let person_ids: Vec<Uuid> = people.iter().map(|p| p.person_id).collect();
+    for total in ["0.05", "-0.05", "100.01", "7"] {
+        for minor_units in [0, 2] {
+            let total = decimal(total);
+            assert_eq!(
+                split_by_strategy(&total, SplitStrategy::Equal, &people, minor_units),
+                split_equally(&total, &person_ids, minor_units)
+                    .into_iter()
+                    .filter(|(_, share)| !share.is_zero())
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    let mut person_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    person_ids.sort();
+    let people: Vec<SplitParticipant> = person_ids
+        .iter()
+        .zip([33.33, 33.33, 33.34])
+        .map(|(person_id, percentage)| SplitParticipant {
+            person_id: *person_id,
+            percentage: Some(percentage),
+            shares: None,
+        })
+        .collect();
+    let shares = split_by_strategy(&decimal("100.01"), SplitStrategy::Percentage, &people, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (people[0].person_id, decimal("33.34")),
+            (people[1].person_id, decimal("33.33")),
+            (people[2].person_id, decimal("33.34")),
+        ]
+    );
+
+    let mut person_ids: Vec<Uuid> = (0..2).map(|_| Uuid::new_v4()).collect();
+    person_ids.sort();
+    let people: Vec<SplitParticipant> = person_ids
+        .iter()
+        .zip([1, 2])
+        .map(|(person_id, shares)| SplitParticipant {
+            person_id: *person_id,
+            percentage: None,
+            shares: Some(shares),
+        })
+        .collect();
+    let shares = split_by_strategy(&decimal("10.00"), SplitStrategy::Shares, &people, 2);
+    assert_eq!(
+        shares,
+        vec![
+            (people[0].person_id, decimal("3.34")),
+            (people[1].person_id, decimal("6.66")),
+        ]
+    );
+
+    // Currencies without minor units round to whole amounts
+    let shares = split_by_strategy(&decimal("1000"), SplitStrategy::Shares, &people, 0);
+    assert_eq!(
+        shares,
+        vec![
+            (people[0].person_id, decimal("334")),
+            (people[1].person_id, decimal("666")),
+        ]
+    );
+}
+
+/// Test creating transactions split by each strategy.
+///
+/// Verifies that:
+/// - Status code is 201 Created
+/// - The splits follow the strategy and add up exactly to the amount
+#[tokio::test]
+async fn test_create_transaction_with_split_strategy() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitstrategy_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+    let carol = create_test_person(&server, &auth.token, "Carol").await;
+    // The leftover cent goes to the lowest person ID, whatever the order given
+    let thirds = split_equally(&decimal("10.00"), &[alice.id, bob.id, carol.id], 2);
+
+    let cases = [
+        (
+            "EQUAL",
+            json!([
+                { "person_id": bob.id },
+                { "person_id": alice.id },
+                { "person_id": carol.id }
+            ]),
+            thirds.clone(),
+        ),
+        (
+            "PERCENTAGE",
+            json!([
+                { "person_id": alice.id, "percentage": 50 },
+                { "person_id": bob.id, "percentage": 25 },
+                { "person_id": carol.id, "percentage": 25 }
+            ]),
+            vec![
+                (alice.id, decimal("5.00")),
+                (bob.id, decimal("2.50")),
+                (carol.id, decimal("2.50")),
+            ],
+        ),
+        (
+            "SHARES",
+            json!([
+                { "person_id": carol.id, "shares": 1 },
+                { "person_id": alice.id, "shares": 1 },
+                { "person_id": bob.id, "shares": 1 }
+            ]),
+            thirds,
+        ),
+    ];
+
+    for (strategy, participants, expected) in cases {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Dinner",
+            "amount": -10.0,
+            "date": Utc::now().to_rfc3339(),
+            "split_strategy": strategy,
+            "participants": participants
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+
+        let transaction: TransactionResponse = extract_json(response);
+        let splits = transaction.splits.expect("Splits should be created");
+        assert_eq!(splits.len(), expected.len(), "{}", strategy);
+        for (person_id, amount) in expected {
+            let split = splits
+                .iter()
+                .find(|split| split.person_id == person_id)
+                .expect("Split should belong to a participant");
+            assert_eq!(split.amount.as_decimal(), &amount, "{}", strategy);
+        }
+    }
+}
+
+/// Test that invalid split strategies are rejected.
+///
+/// Verifies that the following are rejected with 422:
+/// - Percentages that do not add up to 100
+/// - A participant without the weight their strategy uses
+/// - `split_strategy` without `participants`
+/// - Combining `split_strategy` with explicit `splits`
+#[tokio::test]
+async fn test_create_transaction_with_invalid_split_strategy() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("splitstratbad_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+
+    let base = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let invalid = [
+        json!({
+            "split_strategy": "PERCENTAGE",
+            "participants": [
+                { "person_id": alice.id, "percentage": 50 },
+                { "person_id": bob.id, "percentage": 40 }
+            ]
+        }),
+        json!({
+            "split_strategy": "SHARES",
+            "participants": [
+                { "person_id": alice.id, "shares": 2 },
+                { "person_id": bob.id }
+            ]
+        }),
+        json!({ "split_strategy": "EQUAL" }),
+        json!({
+            "split_strategy": "EQUAL",
+            "participants": [{ "person_id": alice.id }],
+            "splits": [{ "person_id": bob.id, "amount": 5.0 }]
+        }),
+    ];
+
+    for fields in invalid {
+        let mut request = base.clone();
+        for (key, value) in fields.as_object().unwrap() {
+            request[key] = value.clone();
+        }
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert!(transactions.is_empty());
+}
@@ -3,21 +3,44 @@
 //! Tests cover:
 //! - GET /api/v1/splits/:id/sync-status - Get sync status for a split
 //! - POST /api/v1/splits/:id/retry-sync - Retry a failed sync
+//! - PUT /api/v1/transactions/:id - Editing synced splits flags the expense for re-sync
+//! - GET /api/v1/integrations/sync/status - Summarize sync status across providers
+//! - Selection of sync records for background reconciliation
+//! - GET /api/v1/transactions/:id - Each split lists its sync state per provider
+//! - DELETE /api/v1/transactions/:id - Deleting a synced transaction deletes its external expense
+//! - Syncing with an inactive provider is refused, and repeated auth failures disable it
+//! - Expired credentials are refreshed and stored before syncing
+//! - GET /api/v1/integrations/sync-report - Compare splits with each person's provider
 //!
 //! These tests create sync records directly in the DB since sync records
 //! are normally created by the SplitSyncService during transaction creation.
 
 use crate::common::*;
+use async_trait::async_trait;
 use chrono::Utc;
 use diesel::prelude::*;
 use master_of_coin_backend::{
     models::{
-        NewSplitProvider, SplitProvider,
-        split_sync_record::{NewSplitSyncRecord, SplitSyncStatusResponse},
+        NewSplitProvider, SplitProvider, SplitProviderResponse,
+        split_sync_record::{
+            NewSplitSyncRecord, SplitSyncStatusResponse, SyncReportResponse,
+            SyncStatusSummaryResponse,
+        },
     },
+    repositories::split_sync_record::SplitSyncRecordRepository,
     schema::{split_providers, split_sync_records, transaction_splits},
+    services::{
+        split_provider::{
+            CreateExternalExpense, ExpenseUser, ExternalExpense, ExternalExpenseResult,
+            SplitProvider as SplitProviderImpl, SplitProviderError, UpdateExternalExpense,
+        },
+        split_sync_service::{MAX_CONSECUTIVE_AUTH_FAILURES, SplitSyncService},
+    },
 };
-use serde_json::json;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 // ============================================================================
@@ -106,6 +129,7 @@ fn create_sync_record(
         },
         last_error: error.map(|e| e.to_string()),
         retry_count: 0,
+        external_user_id: None,
     };
     diesel::insert_into(split_sync_records::table)
         .values(&new_record)
@@ -347,3 +371,1154 @@ async fn test_retry_sync_not_found() {
         status
     );
 }
+
+// ============================================================================
+// Sync Status Summary Tests
+// ============================================================================
+
+/// Test summarizing sync records by status.
+///
+/// Verifies that:
+/// - Records are counted per status, including drifted ones
+/// - Inactive providers are reported as needing reconnection
+#[tokio::test]
+async fn test_get_sync_summary() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_summary_{}", ts)).await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let provider = create_test_split_provider(&pool, auth.user.id);
+
+    for status in ["synced", "synced", "drifted", "failed"] {
+        let split_id =
+            create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+                .await;
+        create_sync_record(&pool, split_id, provider.id, status, None);
+    }
+
+    let resp = get_authenticated(&server, "/api/v1/integrations/sync/status", &auth.token).await;
+    assert_status(&resp, 200);
+    let summary: SyncStatusSummaryResponse = extract_json(resp);
+    assert_eq!(summary.synced, 2);
+    assert_eq!(summary.drifted, 1);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.pending, 0);
+    assert!(summary.providers_needing_reconnect.is_empty());
+
+    // A provider whose credentials were rejected is flagged for reconnection
+    master_of_coin_backend::repositories::split_provider::update_active_status(
+        &pool,
+        provider.id,
+        auth.user.id,
+        false,
+    )
+    .await
+    .expect("Failed to deactivate provider");
+
+    let resp = get_authenticated(&server, "/api/v1/integrations/sync/status", &auth.token).await;
+    let summary: SyncStatusSummaryResponse = extract_json(resp);
+    assert_eq!(summary.providers_needing_reconnect, vec![provider.id]);
+}
+
+/// Test which sync records background reconciliation checks.
+///
+/// Verifies that:
+/// - Synced and drifted records of active providers are selected
+/// - Failed records and records of inactive providers are skipped
+#[tokio::test]
+async fn test_find_records_for_reconciliation() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_reconcile_{}", ts)).await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let provider = create_test_split_provider(&pool, auth.user.id);
+
+    let mut records = Vec::new();
+    for status in ["synced", "drifted", "failed"] {
+        let split_id =
+            create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+                .await;
+        let mut record = create_sync_record(&pool, split_id, provider.id, status, None);
+        if status == "drifted" {
+            // Drifted records keep the external expense they were synced to
+            let mut conn = pool.get().expect("Failed to get DB connection");
+            record = diesel::update(split_sync_records::table.find(record.id))
+                .set(split_sync_records::external_expense_id.eq("ext_456"))
+                .get_result(&mut conn)
+                .expect("Failed to set external expense ID");
+        }
+        records.push(record);
+    }
+
+    let selected: Vec<Uuid> = SplitSyncRecordRepository::find_for_reconciliation(&pool, i64::MAX)
+        .expect("Failed to find records")
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    assert!(selected.contains(&records[0].id));
+    assert!(selected.contains(&records[1].id));
+    assert!(!selected.contains(&records[2].id));
+
+    master_of_coin_backend::repositories::split_provider::update_active_status(
+        &pool,
+        provider.id,
+        auth.user.id,
+        false,
+    )
+    .await
+    .expect("Failed to deactivate provider");
+
+    let selected: Vec<Uuid> = SplitSyncRecordRepository::find_for_reconciliation(&pool, i64::MAX)
+        .expect("Failed to find records")
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    assert!(records.iter().all(|r| !selected.contains(&r.id)));
+}
+
+// ============================================================================
+// Per-Split Sync State Tests
+// ============================================================================
+
+/// Test that each split of a transaction exposes its own sync state.
+///
+/// Verifies that:
+/// - Splits synced to different providers report their own provider and status
+/// - The provider user each split was synced as is included
+/// - Splits without sync records omit the sync state
+#[tokio::test]
+async fn test_transaction_splits_expose_sync_state() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_state_{}", ts)).await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let alice = create_test_person(&server, &auth.token, "Alice").await;
+    let bob = create_test_person(&server, &auth.token, "Bob").await;
+    let carol = create_test_person(&server, &auth.token, "Carol").await;
+    let splitwise = create_test_split_provider(&pool, auth.user.id);
+    let splitpro = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        diesel::insert_into(split_providers::table)
+            .values(&NewSplitProvider {
+                user_id: auth.user.id,
+                provider_type: "splitpro".to_string(),
+                credentials: json!({"encrypted": "test_encrypted_credentials"}),
+                is_active: true,
+            })
+            .get_result::<SplitProvider>(&mut conn)
+            .expect("Failed to create test split provider")
+    };
+
+    let req = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": "-90.00",
+        "date": "2023-06-15T00:00:00Z",
+        "splits": [
+            {"person_id": alice.id, "amount": "30.00"},
+            {"person_id": bob.id, "amount": "30.00"},
+            {"person_id": carol.id, "amount": "20.00"}
+        ]
+    });
+    let resp = post_authenticated(&server, "/api/v1/transactions", &auth.token, &req).await;
+    assert_status(&resp, 201);
+    let tx: serde_json::Value = extract_json(resp);
+    let split_of = |person_id: Uuid| {
+        tx["splits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|split| split["person_id"] == json!(person_id))
+            .map(|split| Uuid::parse_str(split["id"].as_str().unwrap()).unwrap())
+            .unwrap()
+    };
+    let (alice_split, bob_split, carol_split) =
+        (split_of(alice.id), split_of(bob.id), split_of(carol.id));
+
+    {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        let records = vec![
+            NewSplitSyncRecord {
+                transaction_split_id: alice_split,
+                split_provider_id: splitwise.id,
+                external_expense_id: Some("sw_1".to_string()),
+                sync_status: "synced".to_string(),
+                last_sync_at: Some(Utc::now()),
+                last_error: None,
+                retry_count: 0,
+                external_user_id: Some("1001".to_string()),
+            },
+            NewSplitSyncRecord {
+                transaction_split_id: bob_split,
+                split_provider_id: splitpro.id,
+                external_expense_id: Some("sp_1".to_string()),
+                sync_status: "drifted".to_string(),
+                last_sync_at: Some(Utc::now()),
+                last_error: Some("Share of provider user bob differs".to_string()),
+                retry_count: 0,
+                external_user_id: Some("bob".to_string()),
+            },
+        ];
+        diesel::insert_into(split_sync_records::table)
+            .values(&records)
+            .execute(&mut conn)
+            .expect("Failed to create sync records");
+    }
+
+    let resp = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", tx["id"].as_str().unwrap()),
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 200);
+    let tx: serde_json::Value = extract_json(resp);
+    let split = |split_id: Uuid| {
+        tx["splits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|split| split["id"] == json!(split_id))
+            .cloned()
+            .unwrap()
+    };
+
+    let alice_sync = &split(alice_split)["sync"];
+    assert_eq!(alice_sync.as_array().unwrap().len(), 1);
+    assert_eq!(alice_sync[0]["split_provider_id"], json!(splitwise.id));
+    assert_eq!(alice_sync[0]["provider_type"], "splitwise");
+    assert_eq!(alice_sync[0]["external_user_id"], "1001");
+    assert_eq!(alice_sync[0]["external_expense_id"], "sw_1");
+    assert_eq!(alice_sync[0]["sync_status"], "synced");
+
+    let bob_sync = &split(bob_split)["sync"];
+    assert_eq!(bob_sync[0]["provider_type"], "splitpro");
+    assert_eq!(bob_sync[0]["sync_status"], "drifted");
+    assert!(bob_sync[0]["last_error"].is_string());
+
+    assert!(split(carol_split).get("sync").is_none());
+}
+
+// ============================================================================
+// Split Edit Re-sync Tests
+// ============================================================================
+
+/// Test that editing splits of a synced transaction flags it for re-sync.
+///
+/// Verifies that:
+/// - The update response sets `needs_resync`
+/// - The remaining split's sync record is reset to pending
+#[tokio::test]
+async fn test_update_synced_splits_needs_resync() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_test_user(
+        &server,
+        &format!("ss_resync_{}", ts),
+        &format!("ss_resync_{}@example.com", ts),
+        "SecurePass123!",
+        "SS Resync",
+    )
+    .await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let provider = create_test_split_provider(&pool, auth.user.id);
+
+    let split_id =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    create_sync_record(&pool, split_id, provider.id, "synced", None);
+
+    let transaction_id: Uuid = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        transaction_splits::table
+            .find(split_id)
+            .select(transaction_splits::transaction_id)
+            .first(&mut conn)
+            .expect("Failed to load split")
+    };
+
+    let update = json!({
+        "splits": [{"person_id": person.id, "amount": 60.0}]
+    });
+    let resp = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction_id),
+        &auth.token,
+        &update,
+    )
+    .await;
+    assert_status(&resp, 200);
+    let tx: serde_json::Value = extract_json(resp);
+    assert_eq!(tx["needs_resync"], json!(true));
+    assert_eq!(tx["splits"][0]["id"], json!(split_id));
+    assert_eq!(tx["splits"][0]["amount"], json!("60.00"));
+
+    let resp = get_authenticated(
+        &server,
+        &format!("/api/v1/splits/{}/sync-status", split_id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 200);
+    let statuses: Vec<SplitSyncStatusResponse> = extract_json(resp);
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(
+        statuses[0].sync_status,
+        master_of_coin_backend::models::split_sync_record::SyncStatus::Pending
+    );
+}
+
+// ============================================================================
+// Delete Reversal Tests
+// ============================================================================
+
+/// Test deleting a transaction whose linked external expense cannot be deleted.
+///
+/// Verifies that:
+/// - The delete fails with 502 and the transaction is kept
+/// - `force=true` deletes the transaction and records the orphaned expense
+/// - The orphaned expense is counted in the sync summary
+#[tokio::test]
+async fn test_delete_synced_transaction() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_delete_{}", ts)).await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    // The test provider's credentials cannot be decrypted, so deleting the expense fails
+    let provider = create_test_split_provider(&pool, auth.user.id);
+
+    let split_id =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    create_sync_record(&pool, split_id, provider.id, "synced", None);
+
+    let transaction_id: Uuid = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        transaction_splits::table
+            .find(split_id)
+            .select(transaction_splits::transaction_id)
+            .first(&mut conn)
+            .expect("Failed to load split")
+    };
+    let path = format!("/api/v1/transactions/{}", transaction_id);
+
+    let resp = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&resp, 502);
+    let resp = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&resp, 200);
+
+    let resp = delete_authenticated(&server, &format!("{}?force=true", path), &auth.token).await;
+    assert_status(&resp, 204);
+    let resp = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&resp, 404);
+
+    let orphans = SplitSyncRecordRepository::list_orphaned_for_user(&pool, auth.user.id)
+        .expect("Failed to list orphaned expenses");
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].external_expense_id, "ext_123");
+    assert_eq!(orphans[0].transaction_id, transaction_id);
+    assert!(orphans[0].last_error.is_some());
+
+    let resp = get_authenticated(&server, "/api/v1/integrations/sync/status", &auth.token).await;
+    let summary: SyncStatusSummaryResponse = extract_json(resp);
+    assert_eq!(summary.orphaned, 1);
+}
+
+/// Test that a transaction whose splits were never synced deletes without force.
+#[tokio::test]
+async fn test_delete_unsynced_transaction() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_delete_pending_{}", ts)).await;
+
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let provider = create_test_split_provider(&pool, auth.user.id);
+
+    let split_id =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    create_sync_record(&pool, split_id, provider.id, "pending", None);
+
+    let transaction_id: Uuid = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        transaction_splits::table
+            .find(split_id)
+            .select(transaction_splits::transaction_id)
+            .first(&mut conn)
+            .expect("Failed to load split")
+    };
+
+    let resp = delete_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction_id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 204);
+
+    let orphans = SplitSyncRecordRepository::list_orphaned_for_user(&pool, auth.user.id)
+        .expect("Failed to list orphaned expenses");
+    assert!(orphans.is_empty());
+}
+
+// ============================================================================
+// Inactive Provider Tests
+// ============================================================================
+
+/// Mocked split provider: counts calls, and rejects credentials while `reject` is set
+///
+/// Credentials in `refreshed` are handed out by the next refresh, once, and the
+/// credentials each refresh started from are kept in `refreshed_from`; listing
+/// expenses returns `expenses`.
+#[derive(Default)]
+struct MockSplitProvider {
+    calls: AtomicUsize,
+    reject: AtomicBool,
+    refreshed: Mutex<Option<Value>>,
+    refreshed_from: Mutex<Vec<Value>>,
+    expenses: Mutex<Vec<ExternalExpense>>,
+    used_credentials: Mutex<Vec<Value>>,
+}
+
+impl MockSplitProvider {
+    fn respond<T>(&self, credentials: &Value, value: T) -> Result<T, SplitProviderError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.used_credentials
+            .lock()
+            .unwrap()
+            .push(credentials.clone());
+        if self.reject.load(Ordering::SeqCst) {
+            Err(SplitProviderError::AuthenticationFailed(
+                "token revoked".to_string(),
+            ))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+#[async_trait]
+impl SplitProviderImpl for MockSplitProvider {
+    fn provider_type(&self) -> &str {
+        "splitwise"
+    }
+
+    async fn create_expense(
+        &self,
+        credentials: &Value,
+        _request: CreateExternalExpense,
+    ) -> Result<ExternalExpenseResult, SplitProviderError> {
+        self.respond(
+            credentials,
+            ExternalExpenseResult {
+                external_expense_id: "ext_mock".to_string(),
+                external_url: None,
+            },
+        )
+    }
+
+    async fn update_expense(
+        &self,
+        credentials: &Value,
+        external_expense_id: &str,
+        _request: UpdateExternalExpense,
+    ) -> Result<ExternalExpenseResult, SplitProviderError> {
+        self.respond(
+            credentials,
+            ExternalExpenseResult {
+                external_expense_id: external_expense_id.to_string(),
+                external_url: None,
+            },
+        )
+    }
+
+    async fn get_expense(
+        &self,
+        _credentials: &Value,
+        external_expense_id: &str,
+    ) -> Result<ExternalExpense, SplitProviderError> {
+        Err(SplitProviderError::NotFound(
+            external_expense_id.to_string(),
+        ))
+    }
+
+    async fn list_expenses(
+        &self,
+        _credentials: &Value,
+        _start: chrono::DateTime<Utc>,
+        _end: chrono::DateTime<Utc>,
+    ) -> Result<Vec<ExternalExpense>, SplitProviderError> {
+        Ok(self.expenses.lock().unwrap().clone())
+    }
+
+    async fn delete_expense(
+        &self,
+        credentials: &Value,
+        _external_expense_id: &str,
+    ) -> Result<(), SplitProviderError> {
+        self.respond(credentials, ())
+    }
+
+    async fn validate_credentials(&self, _credentials: &Value) -> Result<bool, SplitProviderError> {
+        Ok(true)
+    }
+
+    async fn refresh_credentials(
+        &self,
+        credentials: &Value,
+    ) -> Result<Option<Value>, SplitProviderError> {
+        self.refreshed_from
+            .lock()
+            .unwrap()
+            .push(credentials.clone());
+        // Leave concurrent syncs time to start a refresh of their own
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        Ok(self.refreshed.lock().unwrap().take())
+    }
+}
+
+fn mock_sync_service(
+    pool: &master_of_coin_backend::DbPool,
+    provider: Arc<MockSplitProvider>,
+) -> SplitSyncService {
+    let providers: HashMap<String, Arc<dyn SplitProviderImpl>> = HashMap::from([(
+        "splitwise".to_string(),
+        provider as Arc<dyn SplitProviderImpl>,
+    )]);
+    SplitSyncService::with_providers(pool.clone(), providers)
+}
+
+/// Create a synced transaction and return its ID
+async fn create_synced_transaction(
+    server: &axum_test::TestServer,
+    pool: &master_of_coin_backend::DbPool,
+    token: &str,
+    provider_id: Uuid,
+    (account_id, category_id, person_id): (Uuid, Uuid, Uuid),
+) -> Uuid {
+    let split_id =
+        create_transaction_with_split(server, token, account_id, category_id, person_id).await;
+    create_sync_record(pool, split_id, provider_id, "synced", None);
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    transaction_splits::table
+        .find(split_id)
+        .select(transaction_splits::transaction_id)
+        .first(&mut conn)
+        .expect("Failed to load split")
+}
+
+fn load_provider(pool: &master_of_coin_backend::DbPool, provider_id: Uuid) -> SplitProvider {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    split_providers::table
+        .find(provider_id)
+        .first(&mut conn)
+        .expect("Failed to load provider")
+}
+
+/// Test that syncing with a disabled provider fails without contacting it.
+///
+/// Verifies that:
+/// - Disabling a provider returns it as inactive, without needing reconnection
+/// - Deleting its expense fails with a "disconnected" error and no provider call
+/// - Enabling it lets the sync go through
+#[tokio::test]
+async fn test_sync_with_inactive_provider_is_refused() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_inactive_{}", ts)).await;
+
+    let provider = create_test_split_provider(&pool, auth.user.id);
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let owners = (account.id, category.id, person.id);
+    let transaction_id =
+        create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+
+    let resp = post_authenticated(
+        &server,
+        &format!("/api/v1/integrations/providers/{}/disable", provider.id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&resp, 200);
+    let disabled: SplitProviderResponse = extract_json(resp);
+    assert!(!disabled.is_active);
+    assert!(!disabled.needs_reconnect);
+
+    let mock = Arc::new(MockSplitProvider::default());
+    let service = mock_sync_service(&pool, mock.clone());
+
+    let err = service
+        .delete_transaction_expenses(transaction_id, false)
+        .await
+        .expect_err("Sync with an inactive provider should fail");
+    assert!(
+        err.to_string().to_lowercase().contains("disconnected"),
+        "Unexpected error: {}",
+        err
+    );
+    assert_eq!(mock.calls.load(Ordering::SeqCst), 0);
+
+    // A user-disabled provider is not reported as needing reconnection
+    let resp = get_authenticated(&server, "/api/v1/integrations/sync/status", &auth.token).await;
+    let summary: SyncStatusSummaryResponse = extract_json(resp);
+    assert!(summary.providers_needing_reconnect.is_empty());
+
+    let resp = post_authenticated(
+        &server,
+        &format!("/api/v1/integrations/providers/{}/enable", provider.id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&resp, 200);
+    let enabled: SplitProviderResponse = extract_json(resp);
+    assert!(enabled.is_active);
+}
+
+/// Test that a provider rejecting credentials repeatedly is disabled.
+///
+/// Verifies that:
+/// - A successful request resets the count of auth failures
+/// - The provider is disabled and flagged for reconnection after the limit
+/// - Further syncs fail without contacting the provider
+/// - Enabling the provider clears its auth failures
+#[tokio::test]
+async fn test_repeated_auth_failures_disable_provider() {
+    // Credentials must decrypt for requests to reach the provider
+    unsafe {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "aO42n1ptrggkyZKYtsFS2wwsu8+Y9mFhNQ4oAide1Ko=",
+        );
+    }
+
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_authfail_{}", ts)).await;
+
+    let credentials = master_of_coin_backend::utils::encrypt_credentials(
+        &json!({"access_token": "revoked", "splitwise_user_id": 1}),
+    )
+    .expect("Failed to encrypt credentials");
+    let provider: SplitProvider = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        diesel::insert_into(split_providers::table)
+            .values(&NewSplitProvider {
+                user_id: auth.user.id,
+                provider_type: "splitwise".to_string(),
+                credentials: json!({ "encrypted": credentials }),
+                is_active: true,
+            })
+            .get_result(&mut conn)
+            .expect("Failed to create test split provider")
+    };
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let owners = (account.id, category.id, person.id);
+    let transaction_id =
+        create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+
+    let mock = Arc::new(MockSplitProvider::default());
+    mock.reject.store(true, Ordering::SeqCst);
+    let service = mock_sync_service(&pool, mock.clone());
+
+    // A failure followed by a success starts the count over
+    assert!(
+        service
+            .delete_transaction_expenses(transaction_id, false)
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        load_provider(&pool, provider.id).consecutive_auth_failures,
+        1
+    );
+
+    mock.reject.store(false, Ordering::SeqCst);
+    let other_transaction =
+        create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+    service
+        .delete_transaction_expenses(other_transaction, false)
+        .await
+        .expect("Delete should succeed");
+    assert_eq!(
+        load_provider(&pool, provider.id).consecutive_auth_failures,
+        0
+    );
+
+    mock.reject.store(true, Ordering::SeqCst);
+    for _ in 0..MAX_CONSECUTIVE_AUTH_FAILURES {
+        assert!(
+            service
+                .delete_transaction_expenses(transaction_id, false)
+                .await
+                .is_err()
+        );
+    }
+    let stored = load_provider(&pool, provider.id);
+    assert!(!stored.is_active);
+    assert!(stored.needs_reconnect());
+    let calls = mock.calls.load(Ordering::SeqCst);
+
+    // The disabled provider is no longer contacted
+    let err = service
+        .delete_transaction_expenses(transaction_id, false)
+        .await
+        .expect_err("Sync with a disabled provider should fail");
+    assert!(err.to_string().to_lowercase().contains("disconnected"));
+    assert_eq!(mock.calls.load(Ordering::SeqCst), calls);
+
+    let resp = get_authenticated(&server, "/api/v1/integrations/sync/status", &auth.token).await;
+    let summary: SyncStatusSummaryResponse = extract_json(resp);
+    assert_eq!(summary.providers_needing_reconnect, vec![provider.id]);
+
+    let resp = post_authenticated(
+        &server,
+        &format!("/api/v1/integrations/providers/{}/enable", provider.id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&resp, 200);
+    let stored = load_provider(&pool, provider.id);
+    assert!(stored.is_active);
+    assert_eq!(stored.consecutive_auth_failures, 0);
+}
+
+// ============================================================================
+// Credentials Refresh Tests
+// ============================================================================
+
+/// Create a provider whose credentials are encrypted, so requests reach the provider
+fn create_encrypted_split_provider(
+    pool: &master_of_coin_backend::DbPool,
+    user_id: Uuid,
+    credentials: Value,
+) -> SplitProvider {
+    unsafe {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "aO42n1ptrggkyZKYtsFS2wwsu8+Y9mFhNQ4oAide1Ko=",
+        );
+    }
+
+    let encrypted = master_of_coin_backend::utils::encrypt_credentials(&credentials)
+        .expect("Failed to encrypt credentials");
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    diesel::insert_into(split_providers::table)
+        .values(&NewSplitProvider {
+            user_id,
+            provider_type: "splitwise".to_string(),
+            credentials: json!({ "encrypted": encrypted }),
+            is_active: true,
+        })
+        .get_result(&mut conn)
+        .expect("Failed to create test split provider")
+}
+
+/// Test that expired credentials are refreshed before creating an expense.
+///
+/// Verifies that:
+/// - The expense is created with the refreshed credentials
+/// - The refreshed credentials are encrypted and stored on the provider
+/// - Later requests use the stored credentials without refreshing again
+#[tokio::test]
+async fn test_refresh_credentials_before_create() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_refresh_{}", ts)).await;
+
+    let provider = create_encrypted_split_provider(
+        &pool,
+        auth.user.id,
+        json!({"access_token": "expired", "splitwise_user_id": 1}),
+    );
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+
+    let resp = put_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/split-config", person.id),
+        &auth.token,
+        &json!({"split_provider_id": provider.id, "external_user_id": "2"}),
+    )
+    .await;
+    assert_status(&resp, 200);
+
+    let split_id =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    let transaction_id: Uuid = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        transaction_splits::table
+            .find(split_id)
+            .select(transaction_splits::transaction_id)
+            .first(&mut conn)
+            .expect("Failed to load split")
+    };
+
+    let mock = Arc::new(MockSplitProvider::default());
+    *mock.refreshed.lock().unwrap() =
+        Some(json!({"access_token": "fresh", "splitwise_user_id": 1}));
+    let service = mock_sync_service(&pool, mock.clone());
+
+    service
+        .on_transaction_splits_created(transaction_id, vec![split_id])
+        .await
+        .expect("Sync should succeed");
+
+    assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        mock.used_credentials.lock().unwrap()[0]["access_token"],
+        "fresh"
+    );
+
+    let stored = load_provider(&pool, provider.id);
+    let encrypted = stored.credentials["encrypted"].as_str().unwrap();
+    let decrypted = master_of_coin_backend::utils::decrypt_credentials(encrypted)
+        .expect("Stored credentials should decrypt");
+    assert_eq!(decrypted["access_token"], "fresh");
+
+    let record =
+        SplitSyncRecordRepository::find_by_split_and_provider(&pool, split_id, provider.id)
+            .expect("Failed to load sync record")
+            .expect("Sync record should exist");
+    assert_eq!(record.sync_status, "synced");
+    assert_eq!(record.external_expense_id.as_deref(), Some("ext_mock"));
+
+    // The stored credentials are used as they are from now on
+    service
+        .delete_transaction_expenses(transaction_id, false)
+        .await
+        .expect("Delete should succeed");
+    assert_eq!(
+        mock.used_credentials.lock().unwrap()[1]["access_token"],
+        "fresh"
+    );
+}
+
+/// Test that concurrent syncs refresh a provider's credentials one at a time.
+///
+/// Verifies that:
+/// - The second sync waits for the first one's refresh to be stored
+/// - It then starts from the refreshed credentials, not the ones it loaded
+#[tokio::test]
+async fn test_concurrent_syncs_refresh_credentials_once() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_refreshlock_{}", ts)).await;
+
+    let provider = create_encrypted_split_provider(
+        &pool,
+        auth.user.id,
+        json!({"access_token": "expired", "splitwise_user_id": 1}),
+    );
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let owners = (account.id, category.id, person.id);
+    let first = create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+    let second = create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+
+    let mock = Arc::new(MockSplitProvider::default());
+    *mock.refreshed.lock().unwrap() =
+        Some(json!({"access_token": "fresh", "splitwise_user_id": 1}));
+    let service = mock_sync_service(&pool, mock.clone());
+
+    let (first_result, second_result) = tokio::join!(
+        service.delete_transaction_expenses(first, false),
+        service.delete_transaction_expenses(second, false),
+    );
+    first_result.expect("First delete should succeed");
+    second_result.expect("Second delete should succeed");
+
+    let refreshed_from: Vec<Value> = mock
+        .refreshed_from
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|credentials| credentials["access_token"].clone())
+        .collect();
+    assert_eq!(refreshed_from, vec![json!("expired"), json!("fresh")]);
+    assert!(
+        mock.used_credentials
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|credentials| credentials["access_token"] == "fresh")
+    );
+}
+
+/// Test that a provider rejecting refreshed credentials is disabled at once.
+///
+/// Verifies that:
+/// - The request fails with an expired authorization error
+/// - The provider is marked inactive and flagged for reconnection
+#[tokio::test]
+async fn test_rejected_refreshed_credentials_disable_provider() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_refreshfail_{}", ts)).await;
+
+    let provider = create_encrypted_split_provider(
+        &pool,
+        auth.user.id,
+        json!({"access_token": "expired", "splitwise_user_id": 1}),
+    );
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+    let owners = (account.id, category.id, person.id);
+    let transaction_id =
+        create_synced_transaction(&server, &pool, &auth.token, provider.id, owners).await;
+
+    let mock = Arc::new(MockSplitProvider::default());
+    mock.reject.store(true, Ordering::SeqCst);
+    *mock.refreshed.lock().unwrap() =
+        Some(json!({"access_token": "still_bad", "splitwise_user_id": 1}));
+    let service = mock_sync_service(&pool, mock.clone());
+
+    let err = service
+        .delete_transaction_expenses(transaction_id, false)
+        .await
+        .expect_err("Delete with rejected credentials should fail");
+    assert!(
+        err.to_string()
+            .contains(&SplitProviderError::AuthExpired.to_string()),
+        "Unexpected error: {}",
+        err
+    );
+
+    let stored = load_provider(&pool, provider.id);
+    assert!(!stored.is_active);
+    assert!(stored.needs_reconnect());
+}
+
+// ============================================================================
+// Sync Report Tests
+// ============================================================================
+
+/// An expense of 100.00 paid by provider user 1 and shared with provider user 2
+fn shared_expense(external_expense_id: &str, owed_share: &str) -> ExternalExpense {
+    ExternalExpense {
+        external_expense_id: external_expense_id.to_string(),
+        cost: "100.00".to_string(),
+        users: vec![
+            ExpenseUser {
+                external_user_id: "1".to_string(),
+                paid_share: "100.00".to_string(),
+                owed_share: "0.00".to_string(),
+            },
+            ExpenseUser {
+                external_user_id: "2".to_string(),
+                paid_share: "0.00".to_string(),
+                owed_share: owed_share.to_string(),
+            },
+        ],
+        deleted: false,
+    }
+}
+
+/// Test the sync report over splits in sync, drifted and missing on either side.
+///
+/// Verifies that:
+/// - Each split is compared with the expense on its person's provider
+/// - Provider expenses shared with the person but not linked locally are listed
+/// - A person whose provider cannot be read is reported without failing the report
+/// - The report does not change any sync record
+#[tokio::test]
+async fn test_sync_report() {
+    let server = create_test_server().await;
+    let pool = get_test_db_pool();
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_report_{}", ts)).await;
+
+    let provider = create_encrypted_split_provider(
+        &pool,
+        auth.user.id,
+        json!({"access_token": "valid", "splitwise_user_id": 1}),
+    );
+    let account = create_test_account(&server, &auth.token, "Sync Account").await;
+    let category = create_test_category(&server, &auth.token, "Sync Category").await;
+    let person = create_test_person(&server, &auth.token, "Sync Person").await;
+
+    // Created before the person is linked, so creating them syncs nothing
+    let in_sync =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    let drifted =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    let unsynced =
+        create_transaction_with_split(&server, &auth.token, account.id, category.id, person.id)
+            .await;
+    let resp = put_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/split-config", person.id),
+        &auth.token,
+        &json!({"split_provider_id": provider.id, "external_user_id": "2"}),
+    )
+    .await;
+    assert_status(&resp, 200);
+
+    // A second person on a provider type without an implementation cannot be compared
+    let unreadable = {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        diesel::insert_into(split_providers::table)
+            .values(&NewSplitProvider {
+                user_id: auth.user.id,
+                provider_type: "splitpro".to_string(),
+                credentials: json!({"encrypted": "unused"}),
+                is_active: true,
+            })
+            .get_result::<SplitProvider>(&mut conn)
+            .expect("Failed to create test split provider")
+    };
+    let other_person = create_test_person(&server, &auth.token, "Other Person").await;
+    let resp = put_authenticated(
+        &server,
+        &format!("/api/v1/people/{}/split-config", other_person.id),
+        &auth.token,
+        &json!({"split_provider_id": unreadable.id, "external_user_id": "3"}),
+    )
+    .await;
+    assert_status(&resp, 200);
+
+    for (split_id, external_expense_id) in [(in_sync, "ext_in_sync"), (drifted, "ext_drifted")] {
+        let record = create_sync_record(&pool, split_id, provider.id, "synced", None);
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        diesel::update(split_sync_records::table.find(record.id))
+            .set(split_sync_records::external_expense_id.eq(external_expense_id))
+            .execute(&mut conn)
+            .expect("Failed to link sync record");
+    }
+
+    let mock = Arc::new(MockSplitProvider::default());
+    *mock.expenses.lock().unwrap() = vec![
+        shared_expense("ext_in_sync", "50.00"),
+        shared_expense("ext_drifted", "30.00"),
+        shared_expense("ext_unlinked", "20.00"),
+    ];
+    let service = mock_sync_service(&pool, mock.clone());
+
+    let start = "2023-06-01T00:00:00Z".parse().unwrap();
+    let end = "2023-06-30T23:59:59Z".parse().unwrap();
+    let report: SyncReportResponse = service
+        .sync_report(auth.user.id, start, end)
+        .await
+        .expect("Report should succeed");
+
+    assert_eq!(report.in_sync, 1);
+    assert_eq!(report.drifted, 1);
+    assert_eq!(report.drifted_splits[0].transaction_split_id, drifted);
+    assert_eq!(report.missing_on_external, 1);
+    assert_eq!(
+        report.missing_on_external_splits[0].transaction_split_id,
+        unsynced
+    );
+    assert_eq!(report.missing_on_local, 1);
+    assert_eq!(
+        report.missing_on_local_expenses[0].external_expense_id,
+        "ext_unlinked"
+    );
+    assert_eq!(report.missing_on_local_expenses[0].owed_share, "20.00");
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].person_id, other_person.id);
+    assert_eq!(report.errors[0].split_provider_id, unreadable.id);
+
+    // Nothing was written
+    assert_eq!(mock.calls.load(Ordering::SeqCst), 0);
+    let record = SplitSyncRecordRepository::find_by_split_and_provider(&pool, drifted, provider.id)
+        .expect("Failed to load sync record")
+        .expect("Sync record should exist");
+    assert_eq!(record.sync_status, "synced");
+    assert!(
+        SplitSyncRecordRepository::find_by_split_and_provider(&pool, unsynced, provider.id)
+            .expect("Failed to load sync record")
+            .is_none()
+    );
+
+    // Splits outside the range are left out
+    let start = "2024-01-01T00:00:00Z".parse().unwrap();
+    let end = "2024-01-31T00:00:00Z".parse().unwrap();
+    let report = service
+        .sync_report(auth.user.id, start, end)
+        .await
+        .expect("Report should succeed");
+    assert_eq!(
+        report.in_sync + report.drifted + report.missing_on_external,
+        0
+    );
+}
+
+/// Test the sync report endpoint.
+///
+/// Verifies that:
+/// - A user without split configs gets an empty report
+/// - A start date after the end date returns 400 Bad Request
+/// - Both dates are required
+#[tokio::test]
+async fn test_sync_report_endpoint() {
+    let server = create_test_server().await;
+    let ts = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("ss_reportapi_{}", ts)).await;
+
+    let resp = get_authenticated(
+        &server,
+        "/api/v1/integrations/sync-report?start_date=2023-01-01T00:00:00Z&end_date=2023-12-31T00:00:00Z",
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 200);
+    let report: SyncReportResponse = extract_json(resp);
+    assert_eq!(report.in_sync, 0);
+    assert!(report.errors.is_empty());
+
+    let resp = get_authenticated(
+        &server,
+        "/api/v1/integrations/sync-report?start_date=2023-12-31T00:00:00Z&end_date=2023-01-01T00:00:00Z",
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 400);
+
+    let resp = get_authenticated(
+        &server,
+        "/api/v1/integrations/sync-report?start_date=2023-01-01T00:00:00Z",
+        &auth.token,
+    )
+    .await;
+    assert_status(&resp, 400);
+}
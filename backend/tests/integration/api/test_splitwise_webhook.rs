@@ -0,0 +1,286 @@
+//! Integration tests for the Splitwise webhook.
+//!
+//! This module tests POST /api/v1/integrations/splitwise/webhook:
+//! - Rejecting deliveries with a missing or wrong signature, without changes
+//! - Applying changed shares to the splits synced to an expense, with a new
+//!   transaction version and an audit entry
+//! - Deleting the splits of an expense deleted on Splitwise
+//! - Storing unmatched and unrecognized events for inspection
+//!
+//! Sync records are created directly in the DB, as in test_split_sync.
+
+use crate::common::*;
+use axum_test::{TestResponse, TestServer};
+use chrono::Utc;
+use diesel::prelude::*;
+use hmac::{Hmac, KeyInit, Mac};
+use master_of_coin_backend::{
+    models::{
+        AuditAction, AuditLogResponse, NewSplitProvider, SplitProvider, TransactionResponse,
+        WebhookEvent, WebhookOutcome, WebhookResponse,
+        split_sync_record::{NewSplitSyncRecord, SplitSyncRecord},
+    },
+    schema::{split_providers, split_sync_records, webhook_events},
+};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use uuid::Uuid;
+
+const SECRET: &str = "test_webhook_secret";
+const WEBHOOK_PATH: &str = "/api/v1/integrations/splitwise/webhook";
+const PAYER_ID: &str = "100";
+const FRIEND_ID: &str = "200";
+
+fn get_test_db_pool() -> master_of_coin_backend::DbPool {
+    use diesel::PgConnection;
+    use diesel::r2d2::{self, ConnectionManager};
+    dotenvy::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for integration tests");
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .max_size(2)
+        .build(manager)
+        .expect("Failed to create test database pool")
+}
+
+async fn create_webhook_server() -> TestServer {
+    create_test_server_with_config(|config| {
+        config.splitwise_webhook_secret = Some(SECRET.to_string());
+    })
+    .await
+}
+
+fn sign(body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+async fn deliver(server: &TestServer, payload: &Value, signature: Option<&str>) -> TestResponse {
+    let body = payload.to_string();
+    let mut request = server
+        .post(WEBHOOK_PATH)
+        .content_type("application/json")
+        .bytes(body.clone().into());
+    if let Some(signature) = signature {
+        request = request.add_header(
+            "x-splitwise-signature".parse::<http::HeaderName>().unwrap(),
+            signature.parse::<http::HeaderValue>().unwrap(),
+        );
+    }
+    request.await
+}
+
+async fn deliver_signed(server: &TestServer, payload: &Value) -> WebhookResponse {
+    let response = deliver(server, payload, Some(&sign(&payload.to_string()))).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+/// An expense event as Splitwise delivers it
+fn expense_event(event_type: &str, expense_id: i64, cost: &str, friend_share: &str) -> Value {
+    json!({
+        "type": event_type,
+        "expense": {
+            "id": expense_id,
+            "cost": cost,
+            "deleted_at": null,
+            "users": [
+                {"user_id": PAYER_ID.parse::<i64>().unwrap(), "paid_share": cost, "owed_share": "0.00"},
+                {"user_id": FRIEND_ID.parse::<i64>().unwrap(), "paid_share": "0.00", "owed_share": friend_share}
+            ]
+        }
+    })
+}
+
+/// A transaction of 100.00 with a 40.00 split, synced to Splitwise expense `expense_id`
+///
+/// Returns the transaction ID.
+async fn create_synced_split(
+    server: &TestServer,
+    token: &str,
+    user_id: Uuid,
+    expense_id: i64,
+) -> Uuid {
+    let account = create_test_account(server, token, "Webhook Checking").await;
+    let person = create_test_person(server, token, "Friend").await;
+    let request = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": "-100.00",
+        "date": Utc::now().to_rfc3339(),
+        "splits": [{"person_id": person.id, "amount": "40.00"}]
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    let split_id = transaction.splits.unwrap()[0].id;
+
+    let pool = get_test_db_pool();
+    let mut conn = pool.get().unwrap();
+    let provider: SplitProvider = diesel::insert_into(split_providers::table)
+        .values(&NewSplitProvider {
+            user_id,
+            provider_type: "splitwise".to_string(),
+            credentials: json!({"encrypted": "test_encrypted_credentials"}),
+            is_active: true,
+        })
+        .get_result(&mut conn)
+        .unwrap();
+    diesel::insert_into(split_sync_records::table)
+        .values(&NewSplitSyncRecord {
+            transaction_split_id: split_id,
+            split_provider_id: provider.id,
+            external_expense_id: Some(expense_id.to_string()),
+            sync_status: "synced".to_string(),
+            last_sync_at: Some(Utc::now()),
+            last_error: None,
+            retry_count: 0,
+            external_user_id: Some(FRIEND_ID.to_string()),
+        })
+        .execute(&mut conn)
+        .unwrap();
+
+    transaction.id
+}
+
+async fn get_transaction(server: &TestServer, token: &str, id: Uuid) -> TransactionResponse {
+    let response = get_authenticated(server, &format!("/api/v1/transactions/{}", id), token).await;
+    assert_status(&response, 200);
+    extract_json(response)
+}
+
+fn stored_events(external_id: &str) -> Vec<WebhookEvent> {
+    let pool = get_test_db_pool();
+    let mut conn = pool.get().unwrap();
+    webhook_events::table
+        .filter(webhook_events::external_id.eq(external_id))
+        .load(&mut conn)
+        .unwrap()
+}
+
+/// Test deliveries without a valid signature are rejected and change nothing.
+#[tokio::test]
+async fn test_webhook_rejects_invalid_signature() {
+    let server = create_webhook_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("webhook_sig_{}", timestamp)).await;
+    let transaction_id = create_synced_split(&server, &auth.token, auth.user.id, timestamp).await;
+    let payload = expense_event("expense.updated", timestamp, "100.00", "60.00");
+
+    let response = deliver(&server, &payload, None).await;
+    assert_status(&response, 401);
+    let response = deliver(&server, &payload, Some(&sign("{}"))).await;
+    assert_status(&response, 401);
+    let response = deliver(&server, &payload, Some("not hex")).await;
+    assert_status(&response, 401);
+
+    let transaction = get_transaction(&server, &auth.token, transaction_id).await;
+    assert_eq!(transaction.splits.unwrap()[0].amount.to_string(), "40.00");
+    assert!(stored_events(&timestamp.to_string()).is_empty());
+
+    // Without a configured secret nothing can be verified
+    let unconfigured = create_test_server().await;
+    let response = deliver(&unconfigured, &payload, Some(&sign(&payload.to_string()))).await;
+    assert_status(&response, 401);
+}
+
+/// Test a changed share on Splitwise is applied to the synced split.
+///
+/// Verifies that:
+/// - The split takes the person's new owed share
+/// - The transaction gets a new version and an audit entry for the split change
+/// - The sync record stays synced
+#[tokio::test]
+async fn test_webhook_applies_updated_share() {
+    let server = create_webhook_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("webhook_upd_{}", timestamp)).await;
+    let transaction_id = create_synced_split(&server, &auth.token, auth.user.id, timestamp).await;
+    let version = get_transaction(&server, &auth.token, transaction_id)
+        .await
+        .version;
+
+    let payload = expense_event("expense.updated", timestamp, "100.00", "60.00");
+    let response = deliver_signed(&server, &payload).await;
+    assert_eq!(response.outcome, WebhookOutcome::Applied);
+
+    let transaction = get_transaction(&server, &auth.token, transaction_id).await;
+    assert_eq!(transaction.version, version + 1);
+    let split = &transaction.splits.unwrap()[0];
+    assert_eq!(split.amount.to_string(), "60.00");
+
+    let path = format!("/api/v1/audit?entity_id={}", transaction_id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let entries: Vec<AuditLogResponse> = extract_json(response);
+    assert_eq!(entries[0].action, AuditAction::Update);
+    assert!(entries[0].changes.get("splits").is_some());
+
+    let pool = get_test_db_pool();
+    let mut conn = pool.get().unwrap();
+    let record: SplitSyncRecord = split_sync_records::table
+        .filter(split_sync_records::transaction_split_id.eq(split.id))
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(record.sync_status, "synced");
+    assert!(record.last_error.is_none());
+}
+
+/// Test deleting an expense on Splitwise deletes the synced split.
+#[tokio::test]
+async fn test_webhook_deletes_split_of_deleted_expense() {
+    let server = create_webhook_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("webhook_del_{}", timestamp)).await;
+    let transaction_id = create_synced_split(&server, &auth.token, auth.user.id, timestamp).await;
+
+    let payload = expense_event("expense.deleted", timestamp, "100.00", "40.00");
+    let response = deliver_signed(&server, &payload).await;
+    assert_eq!(response.outcome, WebhookOutcome::Applied);
+
+    let transaction = get_transaction(&server, &auth.token, transaction_id).await;
+    assert!(transaction.splits.unwrap_or_default().is_empty());
+}
+
+/// Test events that cannot be applied are stored instead of dropped.
+///
+/// Verifies that:
+/// - An expense no split was synced to is stored as unmatched
+/// - An event type that is not handled is stored as unrecognized
+#[tokio::test]
+async fn test_webhook_stores_unmatched_and_unrecognized_events() {
+    let server = create_webhook_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let payload = expense_event("expense.created", timestamp, "25.00", "10.00");
+    let response = deliver_signed(&server, &payload).await;
+    assert_eq!(response.outcome, WebhookOutcome::Unmatched);
+    let events = stored_events(&timestamp.to_string());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].status, "unmatched");
+    assert_eq!(events[0].event_type.as_deref(), Some("expense.created"));
+    assert_eq!(events[0].payload, payload);
+
+    let marker = format!("group_{}", timestamp);
+    let payload = json!({"type": "group.updated", "group": {"name": marker}});
+    let response = deliver_signed(&server, &payload).await;
+    assert_eq!(response.outcome, WebhookOutcome::Unrecognized);
+
+    let pool = get_test_db_pool();
+    let mut conn = pool.get().unwrap();
+    let events: Vec<WebhookEvent> = webhook_events::table
+        .filter(webhook_events::event_type.eq("group.updated"))
+        .load(&mut conn)
+        .unwrap();
+    let event = events
+        .iter()
+        .find(|event| event.payload == payload)
+        .expect("The unrecognized event should be stored");
+    assert_eq!(event.status, "unrecognized");
+}
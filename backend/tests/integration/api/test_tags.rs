@@ -0,0 +1,293 @@
+//! Integration tests for transaction tags
+//!
+//! These tests verify:
+//! - Tags are normalized when transactions are created and updated
+//! - Tags are saved with their transactions, also in bulk requests
+//! - GET /api/v1/transactions?tags= - Filter by any or all of several tags
+//! - GET /api/v1/tags - List tags with usage counts
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::models::{TagResponse, TransactionResponse};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn create_tagged(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    title: &str,
+    tags: Value,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": title,
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339(),
+        "tags": tags
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Titles of the transactions listed at `path`, sorted
+async fn list_titles(server: &TestServer, token: &str, path: &str) -> Vec<String> {
+    let response = get_authenticated(server, path, token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    let mut titles: Vec<String> = transactions.into_iter().map(|t| t.title).collect();
+    titles.sort();
+    titles
+}
+
+/// Test tagging transactions on create and update.
+///
+/// Verifies that:
+/// - Tags are trimmed, lowercased, deduplicated and sorted
+/// - Tags are returned when getting the transaction
+/// - Updating replaces the tags, `[]` removes them and omitting them keeps them
+/// - Empty tags and tags with commas return 422
+#[tokio::test]
+async fn test_transaction_tags() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("tags_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let created = create_tagged(
+        &server,
+        &auth.token,
+        account.id,
+        "Hotel",
+        json!([" Vacation-2024 ", "travel", "TRAVEL"]),
+    )
+    .await;
+    assert_eq!(created.tags, vec!["travel", "vacation-2024"]);
+
+    let path = format!("/api/v1/transactions/{}", created.id);
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.tags, vec!["travel", "vacation-2024"]);
+
+    let response =
+        put_authenticated(&server, &path, &auth.token, &json!({ "tags": ["work"] })).await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.tags, vec!["work"]);
+
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({ "title": "Hotel stay" }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.tags, vec!["work"]);
+
+    let response = put_authenticated(&server, &path, &auth.token, &json!({ "tags": [] })).await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert!(updated.tags.is_empty());
+
+    for tags in [json!(["  "]), json!(["a,b"])] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Bad",
+            "amount": -10.0,
+            "date": Utc::now().to_rfc3339(),
+            "tags": tags
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+}
+
+/// Test that tags are written in the same DB transaction as the transaction.
+///
+/// Verifies that:
+/// - An update that fails on its splits leaves the tags as they were
+/// - The tag it would have created is not listed
+#[tokio::test]
+async fn test_failed_update_keeps_tags() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("tagsroll_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Person").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Shared Taxi",
+        "amount": -40.0,
+        "date": Utc::now().to_rfc3339(),
+        "tags": ["travel"],
+        "splits": [{ "person_id": person.id, "amount": 20.0 }]
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let created: TransactionResponse = extract_json(response);
+    let split_id = created.splits.as_ref().unwrap()[0].id;
+    let path = format!("/api/v1/transactions/{}", created.id);
+
+    let response = post_authenticated(
+        &server,
+        &format!("{}/splits/{}/settle", path, split_id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    // Removing the settled split fails after the tags would have been replaced
+    let request = json!({ "tags": ["commute"], "splits": [] });
+    let response = put_authenticated(&server, &path, &auth.token, &request).await;
+    assert_status(&response, 409);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.tags, vec!["travel"]);
+
+    let response = get_authenticated(&server, "/api/v1/tags", &auth.token).await;
+    assert_status(&response, 200);
+    let tags: Vec<TagResponse> = extract_json(response);
+    let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+    assert_eq!(names, vec!["travel"]);
+}
+
+/// Test tagging transactions in a bulk request.
+///
+/// Verifies that:
+/// - Created and updated items are saved and returned with their tags
+/// - An invalid tag fails its item
+#[tokio::test]
+async fn test_bulk_tags() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("tagsbulk_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let existing = create_tagged(&server, &auth.token, account.id, "Taxi", json!(["work"])).await;
+
+    let date = Utc::now().to_rfc3339();
+    let request = json!({
+        "create": [{
+            "account_id": account.id,
+            "title": "Hotel",
+            "amount": -120,
+            "date": date,
+            "tags": ["Travel", "work"]
+        }],
+        "update": [{ "id": existing.id, "tags": ["travel"] }]
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions/bulk", &auth.token, &request).await;
+    assert_status(&response, 200);
+    let body: Value = extract_json(response);
+    assert_eq!(body["success"], true);
+    assert_eq!(
+        body["results"][0]["transaction"]["tags"],
+        json!(["travel", "work"])
+    );
+    assert_eq!(body["results"][1]["transaction"]["tags"], json!(["travel"]));
+
+    let created_id = body["results"][0]["transaction"]["id"].as_str().unwrap();
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", created_id),
+        &auth.token,
+    )
+    .await;
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.tags, vec!["travel", "work"]);
+
+    let request = json!({ "update": [{ "id": existing.id, "tags": ["a,b"] }] });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions/bulk", &auth.token, &request).await;
+    assert_status(&response, 200);
+    let body: Value = extract_json(response);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["results"][0]["status"], "failed");
+}
+
+/// Test filtering transactions by tags and listing tag usage.
+///
+/// Verifies that:
+/// - `?tags=` matches transactions with any of the tags by default
+/// - `&tag_mode=all` matches transactions with every tag
+/// - Tag names in the filter are matched case-insensitively
+/// - Usage counts leave out deleted transactions
+/// - Other users' tags are neither listed nor matched
+#[tokio::test]
+async fn test_filter_and_list_tags() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("tag_filter_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    create_tagged(
+        &server,
+        &auth.token,
+        account.id,
+        "Flight",
+        json!(["travel", "work"]),
+    )
+    .await;
+    create_tagged(
+        &server,
+        &auth.token,
+        account.id,
+        "Museum",
+        json!(["travel"]),
+    )
+    .await;
+    create_tagged(&server, &auth.token, account.id, "Laptop", json!(["work"])).await;
+    let deleted = create_tagged(&server, &auth.token, account.id, "Taxi", json!(["travel"])).await;
+    create_tagged(&server, &auth.token, account.id, "Groceries", json!([])).await;
+
+    let path = format!("/api/v1/transactions/{}", deleted.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    let titles = list_titles(
+        &server,
+        &auth.token,
+        "/api/v1/transactions?tags=travel,work",
+    )
+    .await;
+    assert_eq!(titles, vec!["Flight", "Laptop", "Museum"]);
+
+    let titles = list_titles(
+        &server,
+        &auth.token,
+        "/api/v1/transactions?tags=Travel,work&tag_mode=all",
+    )
+    .await;
+    assert_eq!(titles, vec!["Flight"]);
+
+    let titles = list_titles(&server, &auth.token, "/api/v1/transactions?tags=unused").await;
+    assert!(titles.is_empty());
+
+    let response = get_authenticated(&server, "/api/v1/tags", &auth.token).await;
+    assert_status(&response, 200);
+    let tags: Vec<TagResponse> = extract_json(response);
+    let usage: Vec<(&str, i64)> = tags
+        .iter()
+        .map(|tag| (tag.name.as_str(), tag.usage_count))
+        .collect();
+    assert_eq!(usage, vec![("travel", 2), ("work", 2)]);
+
+    // Another user's tags are separate
+    let other = register_unique_test_user(&server, &format!("tag_other_{}", timestamp)).await;
+    let response = get_authenticated(&server, "/api/v1/tags", &other.token).await;
+    assert_status(&response, 200);
+    let tags: Vec<TagResponse> = extract_json(response);
+    assert!(tags.is_empty());
+    let titles = list_titles(&server, &other.token, "/api/v1/transactions?tags=travel").await;
+    assert!(titles.is_empty());
+}
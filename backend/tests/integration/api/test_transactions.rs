@@ -1,18 +1,34 @@
 //! Integration tests for transaction API endpoints.
 //!
 //! This module tests the transaction endpoints including:
-//! - GET /api/v1/transactions - List transactions with optional filters
-//! - POST /api/v1/transactions - Create new transaction
+//! - GET /api/v1/transactions - List transactions with optional filters (as JSON or CSV)
+//! - GET /api/v1/transactions/export.csv - Export the filtered transactions as a CSV file
+//! - POST /api/v1/transactions - Create new transaction (optionally with an Idempotency-Key)
 //! - GET /api/v1/transactions/:id - Get specific transaction
-//! - PUT /api/v1/transactions/:id - Update transaction
+//! - GET /api/v1/transactions/suggest-category - Suggest a category from title history
+//! - PUT /api/v1/transactions/:id - Update transaction (including split edits and version checks)
 //! - DELETE /api/v1/transactions/:id - Delete transaction
+//! - POST /api/v1/transactions/:id/post - Post a pending transaction
+//! - POST /api/v1/transactions/:id/void - Void a transaction
+//! - POST /api/v1/transactions/:id/unvoid - Restore a void transaction
+//! - GET /api/v1/accounts/:id/transactions - List an account's transactions
+//! - GET /api/v1/categories/:id/transactions - List a category's transactions
 //!
-//! Tests cover success cases, error cases, authorization, data isolation, and splits functionality.
+//! Tests cover success cases, error cases, authorization, data isolation, splits functionality,
+//! overdraft/credit limit enforcement, pending/posted/void status, foreign-currency details, merchant and
+//! location details, locale display formatting, validation warnings, idempotent retries, and balance
+//! consistency under concurrent writes.
 
 use crate::common::*;
+use axum_test::TestServer;
 use chrono::{Duration, Utc};
-use master_of_coin_backend::models::TransactionResponse;
+use master_of_coin_backend::models::{
+    AccountResponse, CategorySuggestionResponse, TransactionResponse,
+};
+use master_of_coin_backend::services::debt_service::PersonDebt;
+use master_of_coin_backend::types::{CurrencyCode, TransactionStatus};
 use serde_json::json;
+use std::sync::Arc;
 
 // ============================================================================
 // List Transactions Tests
@@ -112,11 +128,11 @@ async fn test_list_transactions_with_data() {
         .iter()
         .find(|t| t.title == "Grocery Shopping")
         .unwrap();
-    assert_eq!(grocery.amount, "-50.00");
+    assert_eq!(grocery.amount.to_string(), "-50.00");
     assert_eq!(grocery.account_id, account.id);
 
     let salary = transactions.iter().find(|t| t.title == "Salary").unwrap();
-    assert_eq!(salary.amount, "3000.00");
+    assert_eq!(salary.amount.to_string(), "3000.00");
 }
 
 /// Test filtering transactions by account_id.
@@ -311,6 +327,239 @@ async fn test_list_transactions_filter_by_date_range() {
     assert_eq!(transactions[0].title, "Current Transaction");
 }
 
+/// Test filtering transactions by modification time.
+///
+/// Verifies that:
+/// - Updating a transaction refreshes updated_at but keeps created_at
+/// - Only transactions modified at or after updated_since are returned
+#[tokio::test]
+async fn test_list_transactions_filter_by_updated_since() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("filter_updated_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+
+    let mut created = Vec::new();
+    for title in ["Untouched", "Edited"] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": account.id,
+                "title": title,
+                "amount": -10.00,
+                "date": Utc::now().to_rfc3339()
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+        created.push(extract_json::<TransactionResponse>(response));
+    }
+
+    let edited = &created[1];
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", edited.id),
+        &auth.token,
+        &json!({ "notes": "Edited later" }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.created_at, edited.created_at);
+    assert!(updated.updated_at > edited.updated_at);
+
+    let since = updated
+        .updated_at
+        .to_rfc3339_opts(chrono::SecondsFormat::Micros, true);
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions?updated_since={}", since),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].id, edited.id);
+}
+
+/// Test filtering transactions by amount range and type.
+///
+/// Verifies that:
+/// - `type=INCOME` and `type=EXPENSE` match positive and negative amounts
+/// - `type=TRANSFER` matches both legs of a transfer
+/// - `min_amount`/`max_amount` compose with `type` and `sort`
+/// - A `min_amount` above `max_amount` returns 422 and an unknown type 400
+#[tokio::test]
+async fn test_list_transactions_filter_by_amount_and_type() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("filter_type_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    for (title, amount) in [
+        ("Salary", 2000.0),
+        ("Laptop", -1200.0),
+        ("Groceries", -150.0),
+        ("Coffee", -4.5),
+    ] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": checking.id,
+                "title": title,
+                "amount": amount,
+                "date": Utc::now().to_rfc3339()
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &json!({
+            "from_account_id": checking.id,
+            "to_account_id": savings.id,
+            "amount": 300.0,
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let list = |query: &'static str| {
+        let server = &server;
+        let token = auth.token.clone();
+        async move {
+            let path = format!("/api/v1/transactions?{}", query);
+            let response = get_authenticated(server, &path, &token).await;
+            assert_status(&response, 200);
+            extract_json::<Vec<TransactionResponse>>(response)
+        }
+    };
+
+    let transfers = list("type=TRANSFER").await;
+    assert_eq!(transfers.len(), 2);
+    assert!(transfers.iter().all(|t| t.transfer_id.is_some()));
+    assert_eq!(list("type=INCOME").await.len(), 2);
+    assert_eq!(list("type=EXPENSE").await.len(), 4);
+
+    let titles: Vec<String> = list("type=EXPENSE&max_amount=-100&sort=amount")
+        .await
+        .into_iter()
+        .map(|t| t.title)
+        .collect();
+    assert_eq!(titles.len(), 3);
+    assert_eq!(titles[0], "Laptop");
+    assert_eq!(titles[2], "Groceries");
+
+    let in_range = list("min_amount=-200&max_amount=0").await;
+    assert_eq!(in_range.len(), 2);
+
+    let response =
+        get_authenticated(&server, "/api/v1/transactions?type=refund", &auth.token).await;
+    assert_status(&response, 400);
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions?min_amount=10&max_amount=5",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 422);
+    let body: serde_json::Value = extract_json(response);
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("min_amount"), "{}", message);
+}
+
+/// Test sorting the transaction list.
+///
+/// Verifies that:
+/// - Transactions are newest first by default
+/// - `sort` orders by date, amount or title, descending with a leading `-`
+/// - Ties are broken by id, so pages neither repeat nor skip transactions
+/// - An unknown sort key returns 422 naming the allowed keys
+#[tokio::test]
+async fn test_list_transactions_sort() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("sort_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+
+    let now = Utc::now();
+    for (title, amount, days_ago) in [
+        ("Bakery", -5.0, 2),
+        ("Salary", 100.0, 3),
+        ("Cinema", -20.0, 1),
+        ("Apples", -5.0, 4),
+    ] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": account.id,
+                "title": title,
+                "amount": amount,
+                "date": (now - Duration::days(days_ago)).to_rfc3339()
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+
+    let list_titles = |query: &'static str| {
+        let server = &server;
+        let token = auth.token.clone();
+        async move {
+            let path = format!("/api/v1/transactions{}", query);
+            let response = get_authenticated(server, &path, &token).await;
+            assert_status(&response, 200);
+            extract_json::<Vec<TransactionResponse>>(response)
+                .into_iter()
+                .map(|t| t.title)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let expected = ["Cinema", "Bakery", "Salary", "Apples"];
+    assert_eq!(list_titles("").await, expected);
+    assert_eq!(list_titles("?sort=-date").await, expected);
+    assert_eq!(
+        list_titles("?sort=date").await,
+        ["Apples", "Salary", "Bakery", "Cinema"]
+    );
+    assert_eq!(
+        list_titles("?sort=title").await,
+        ["Apples", "Bakery", "Cinema", "Salary"]
+    );
+    assert_eq!(
+        list_titles("?sort=-amount").await[0..1],
+        ["Salary".to_string()]
+    );
+
+    // The two -5.00 transactions keep their relative order across pages
+    let all = list_titles("?sort=amount").await;
+    assert_eq!(all[0], "Cinema");
+    assert_eq!(all[3], "Salary");
+    let mut paged = list_titles("?sort=amount&limit=2").await;
+    paged.extend(list_titles("?sort=amount&limit=2&offset=2").await);
+    assert_eq!(paged, all);
+
+    let response = get_authenticated(&server, "/api/v1/transactions?sort=notes", &auth.token).await;
+    assert_status(&response, 422);
+    let body: serde_json::Value = extract_json(response);
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("date, amount, title"), "{}", message);
+}
+
 /// Test that listing transactions without authentication fails.
 ///
 /// Verifies that:
@@ -402,6 +651,369 @@ async fn test_list_transactions_isolation() {
     assert_eq!(transactions_b[0].title, "User B Transaction");
 }
 
+/// Test the summary headers of the transaction list.
+///
+/// Verifies that:
+/// - The totals cover every transaction matching the filter, not just the page
+/// - The totals change with the filter
+/// - Totals are reported per account currency
+#[tokio::test]
+async fn test_list_transactions_summary_headers() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("summary_{}", timestamp)).await;
+    let usd_account = create_test_account(&server, &auth.token, "Checking").await;
+    let response = post_authenticated(
+        &server,
+        "/api/v1/accounts",
+        &auth.token,
+        &json!({ "name": "Euro Checking", "account_type": "CHECKING", "currency": "EUR" }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let eur_account: AccountResponse = extract_json(response);
+
+    for (account_id, title, amount) in [
+        (usd_account.id, "Salary", "1000.00"),
+        (usd_account.id, "Rent", "-600.00"),
+        (usd_account.id, "Groceries", "-45.50"),
+        (eur_account.id, "Museum", "-12.00"),
+    ] {
+        let request = json!({
+            "account_id": account_id,
+            "title": title,
+            "amount": amount,
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let header = |response: &axum_test::TestResponse, name: &str| {
+        response.header(name).to_str().unwrap().to_string()
+    };
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions?account_id={}&limit=1", usd_account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert_eq!(header(&response, "x-total-count"), "3");
+    assert_eq!(header(&response, "x-total-income"), "USD 1000.00");
+    assert_eq!(header(&response, "x-total-expenses"), "USD 645.50");
+    assert_eq!(header(&response, "x-total-net"), "USD 354.50");
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+
+    let response =
+        get_authenticated(&server, "/api/v1/transactions?max_amount=0", &auth.token).await;
+    assert_status(&response, 200);
+    assert_eq!(header(&response, "x-total-count"), "3");
+    assert_eq!(header(&response, "x-total-income"), "EUR 0.00, USD 0.00");
+    assert_eq!(
+        header(&response, "x-total-expenses"),
+        "EUR 12.00, USD 645.50"
+    );
+    assert_eq!(header(&response, "x-total-net"), "EUR -12.00, USD -645.50");
+}
+
+/// Test that the transaction list is returned as CSV when `Accept: text/csv` is sent.
+///
+/// Verifies that:
+/// - JSON is returned without an `Accept` header and for `application/json`
+/// - `text/csv` returns a header row and one row per transaction, in the
+///   columns of the export
+/// - Both formats list the same transactions for the same filter, and the
+///   CSV honours the page size
+#[tokio::test]
+async fn test_list_transactions_csv_negotiation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("csvlist_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+
+    for (title, amount, category_id) in [
+        ("Market, weekly", "-42.10", Some(groceries.id)),
+        ("Bakery", "-6.50", Some(groceries.id)),
+        ("Salary", "2500.00", None),
+    ] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": account.id,
+                "category_id": category_id,
+                "title": title,
+                "amount": amount,
+                "date": Utc::now().to_rfc3339()
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+
+    let path = format!("/api/v1/transactions?category_id={}", groceries.id);
+    let get_as = |accept: Option<&'static str>| {
+        let mut request = server.get(&path).add_header(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_str(&format!("Bearer {}", auth.token)).unwrap(),
+        );
+        if let Some(accept) = accept {
+            request =
+                request.add_header(http::header::ACCEPT, http::HeaderValue::from_static(accept));
+        }
+        request
+    };
+
+    for accept in [None, Some("application/json")] {
+        let response = get_as(accept).await;
+        assert_status(&response, 200);
+        let transactions: Vec<TransactionResponse> = extract_json(response);
+        assert_eq!(transactions.len(), 2);
+    }
+    let response = get_as(None).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+
+    let response = get_as(Some("text/csv")).await;
+    assert_status(&response, 200);
+    assert!(
+        response
+            .header(http::header::CONTENT_TYPE)
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    assert_eq!(response.header("x-total-count"), "2");
+
+    let body = response.text();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let columns: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+    assert_eq!(
+        columns,
+        [
+            "date", "account", "category", "title", "amount", "currency", "notes"
+        ]
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(rows.len(), transactions.len());
+    for (row, transaction) in rows.iter().zip(&transactions) {
+        assert_eq!(&row[1], "Checking");
+        assert_eq!(&row[2], "Groceries");
+        assert_eq!(&row[3], transaction.title);
+        assert_eq!(&row[5], "USD");
+    }
+
+    let response = server
+        .get(&format!("{}&limit=1", path))
+        .add_header(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_str(&format!("Bearer {}", auth.token)).unwrap(),
+        )
+        .add_header(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static("text/csv"),
+        )
+        .await;
+    assert_status(&response, 200);
+    let body = response.text();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(&rows[0][3], transactions[0].title);
+}
+
+/// Test exporting transactions as a CSV file.
+///
+/// Verifies that:
+/// - The export is a CSV attachment with account and category names
+/// - Amounts are written exactly as stored
+/// - The list filters apply, and a filter without matches returns just the header row
+/// - Filtering on another user's account is rejected with 403
+#[tokio::test]
+async fn test_export_transactions_csv() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("csvexport_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+
+    for (title, amount, category_id, days_ago) in [
+        ("Market, weekly", "-42.10", Some(groceries.id), 2),
+        ("Bakery", "-0.30", Some(groceries.id), 1),
+        ("Salary", "2500", None, 0),
+    ] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": account.id,
+                "category_id": category_id,
+                "title": title,
+                "amount": amount,
+                "date": (Utc::now() - Duration::days(days_ago)).to_rfc3339(),
+                "notes": format!("{} notes", title)
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/export.csv?account_id={}", account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert!(
+        response
+            .header(http::header::CONTENT_TYPE)
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    assert_eq!(
+        response.header(http::header::CONTENT_DISPOSITION),
+        "attachment; filename=\"transactions.csv\""
+    );
+
+    let body = response.text();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let columns: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+    assert_eq!(
+        columns,
+        [
+            "date", "account", "category", "title", "amount", "currency", "notes"
+        ]
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    let rows: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| row.iter().skip(1).collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ["Checking", "", "Salary", "2500.00", "USD", "Salary notes"],
+            [
+                "Checking",
+                "Groceries",
+                "Bakery",
+                "-0.30",
+                "USD",
+                "Bakery notes"
+            ],
+            [
+                "Checking",
+                "Groceries",
+                "Market, weekly",
+                "-42.10",
+                "USD",
+                "Market, weekly notes"
+            ],
+        ]
+    );
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/export.csv?search=bakery",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body = response.text();
+    assert_eq!(body.lines().count(), 2);
+    assert!(body.lines().nth(1).unwrap().contains("Bakery"));
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/export.csv?search=nothing-matches",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert_eq!(
+        response.text(),
+        "date,account,category,title,amount,currency,notes\n"
+    );
+
+    let other = register_unique_test_user(&server, &format!("csvexport2_{}", timestamp)).await;
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/export.csv?account_id={}", account.id),
+        &other.token,
+    )
+    .await;
+    assert_status(&response, 403);
+}
+
+/// Test listing uncategorized transactions for triage.
+///
+/// Verifies that:
+/// - `?uncategorized=true` returns only transactions without a category
+/// - `X-Total-Count` counts every uncategorized transaction, not just the page
+/// - Combining `uncategorized` with `category_id` is rejected with 422
+#[tokio::test]
+async fn test_list_uncategorized_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("uncategorized_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+
+    for (title, category_id) in [
+        ("Market", Some(groceries.id)),
+        ("Unknown shop", None),
+        ("ATM", None),
+        ("Kiosk", None),
+    ] {
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions",
+            &auth.token,
+            &json!({
+                "account_id": account.id,
+                "category_id": category_id,
+                "title": title,
+                "amount": "-10.00",
+                "date": Utc::now().to_rfc3339()
+            }),
+        )
+        .await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions?uncategorized=true&limit=2",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    assert_eq!(response.header("x-total-count"), "3");
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 2);
+    assert!(transactions.iter().all(|t| t.category_id.is_none()));
+
+    let response = get_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions?uncategorized=true&category_id={}",
+            groceries.id
+        ),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 422);
+}
+
 // ============================================================================
 // Create Transaction Tests
 // ============================================================================
@@ -444,7 +1056,7 @@ async fn test_create_transaction_success() {
 
     let transaction: TransactionResponse = extract_json(response);
     assert_eq!(transaction.title, "Test Transaction");
-    assert_eq!(transaction.amount, "-75.50");
+    assert_eq!(transaction.amount.to_string(), "-75.50");
     assert_eq!(transaction.account_id, account.id);
     assert_eq!(transaction.category_id, Some(category.id));
     assert_eq!(transaction.user_id, auth.user.id);
@@ -502,77 +1114,202 @@ async fn test_create_transaction_with_splits() {
 
     let transaction: TransactionResponse = extract_json(response);
     assert_eq!(transaction.title, "Shared Expense");
-    assert_eq!(transaction.amount, "-100.00");
+    assert_eq!(transaction.amount.to_string(), "-100.00");
     assert!(transaction.splits.is_some());
 
     let splits = transaction.splits.unwrap();
     assert_eq!(splits.len(), 2);
 }
 
-/// Test that list transactions includes splits for transactions with splits.
+/// Test that a person can only appear once in a new transaction's splits.
 ///
 /// Verifies that:
-/// - Status code is 200 OK
-/// - Transactions with splits include split data in list response
-/// - Split data is correctly populated
+/// - Status code is 422 Unprocessable Entity
+/// - No transaction is created
 #[tokio::test]
-async fn test_list_transactions_includes_splits() {
+async fn test_create_transaction_duplicate_split_person() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
-    let auth = register_test_user(
-        &server,
-        &format!("listsplitsuser_{}", timestamp),
-        &format!("listsplits_{}@example.com", timestamp),
-        "SecurePass123!",
-        "List Splits Test User",
-    )
-    .await;
-
-    let account = create_test_account(&server, &auth.token, "Test Account").await;
-    let category = create_test_category(&server, &auth.token, "Test Category").await;
-
-    // Create people for splits
-    let person1 = create_test_person(&server, &auth.token, "Person 1").await;
-    let person2 = create_test_person(&server, &auth.token, "Person 2").await;
+    let auth = register_unique_test_user(&server, &format!("dupsplit_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Alice").await;
 
-    // Create a transaction with splits
-    let request_with_splits = json!({
+    let request = json!({
         "account_id": account.id,
-        "category_id": category.id,
-        "title": "Transaction with Splits",
-        "amount": -100.00,
+        "title": "Dinner",
+        "amount": -30.0,
         "date": Utc::now().to_rfc3339(),
         "splits": [
-            {
-                "person_id": person1.id,
-                "amount": 50.00
-            },
-            {
-                "person_id": person2.id,
-                "amount": 50.00
-            }
+            { "person_id": person.id, "amount": 10.0 },
+            { "person_id": person.id, "amount": 10.0 }
         ]
     });
-    let response_with_splits = post_authenticated(
-        &server,
-        "/api/v1/transactions",
-        &auth.token,
-        &request_with_splits,
-    )
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert!(transactions.is_empty());
+}
+
+/// Test the configurable maximum number of splits per transaction.
+///
+/// Verifies that:
+/// - Splitting with up to `max_splits` people is accepted
+/// - More splits are rejected with 422 on create, `split_equally_with` and update
+/// - Bulk create reports the transaction over the limit as failed
+#[tokio::test]
+async fn test_transaction_split_limit() {
+    let server = create_test_server_with_config(|config| {
+        config.transactions.max_splits = 2;
+    })
     .await;
-    assert_status(&response_with_splits, 201);
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
-    // Create a transaction without splits
-    let request_without_splits = json!({
+    let auth = register_unique_test_user(&server, &format!("splitlimit_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let mut people = Vec::new();
+    for name in ["Alice", "Bob", "Carol"] {
+        people.push(create_test_person(&server, &auth.token, name).await.id);
+    }
+    let splits = |count: usize| -> Vec<serde_json::Value> {
+        people[..count]
+            .iter()
+            .map(|person_id| json!({ "person_id": person_id, "amount": 10.0 }))
+            .collect()
+    };
+
+    let request = json!({
         "account_id": account.id,
-        "category_id": category.id,
-        "title": "Transaction without Splits",
-        "amount": -50.00,
-        "date": Utc::now().to_rfc3339()
+        "title": "Dinner",
+        "amount": -40.0,
+        "date": Utc::now().to_rfc3339(),
+        "splits": splits(2)
     });
-    let response_without_splits = post_authenticated(
-        &server,
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -40.0,
+        "date": Utc::now().to_rfc3339(),
+        "splits": splits(3)
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -40.0,
+        "date": Utc::now().to_rfc3339(),
+        "split_equally_with": people
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let update_request = json!({ "splits": splits(3) });
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth.token,
+        &update_request,
+    )
+    .await;
+    assert_status(&response, 422);
+
+    let bulk_request = json!({
+        "account_id": account.id,
+        "transactions": [
+            {
+                "account_id": account.id,
+                "title": "Lunch",
+                "amount": -40.0,
+                "date": Utc::now().to_rfc3339(),
+                "splits": splits(3)
+            }
+        ]
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/bulk-create",
+        &auth.token,
+        &bulk_request,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["data"]["created"], 0);
+    assert_eq!(body["data"]["failed"], 1);
+}
+
+/// Test that list transactions includes splits for transactions with splits.
+///
+/// Verifies that:
+/// - Status code is 200 OK
+/// - Transactions with splits include split data in list response
+/// - Split data is correctly populated
+#[tokio::test]
+async fn test_list_transactions_includes_splits() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("listsplitsuser_{}", timestamp),
+        &format!("listsplits_{}@example.com", timestamp),
+        "SecurePass123!",
+        "List Splits Test User",
+    )
+    .await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let category = create_test_category(&server, &auth.token, "Test Category").await;
+
+    // Create people for splits
+    let person1 = create_test_person(&server, &auth.token, "Person 1").await;
+    let person2 = create_test_person(&server, &auth.token, "Person 2").await;
+
+    // Create a transaction with splits
+    let request_with_splits = json!({
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "Transaction with Splits",
+        "amount": -100.00,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [
+            {
+                "person_id": person1.id,
+                "amount": 50.00
+            },
+            {
+                "person_id": person2.id,
+                "amount": 50.00
+            }
+        ]
+    });
+    let response_with_splits = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &request_with_splits,
+    )
+    .await;
+    assert_status(&response_with_splits, 201);
+
+    // Create a transaction without splits
+    let request_without_splits = json!({
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "Transaction without Splits",
+        "amount": -50.00,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response_without_splits = post_authenticated(
+        &server,
         "/api/v1/transactions",
         &auth.token,
         &request_without_splits,
@@ -604,7 +1341,7 @@ async fn test_list_transactions_includes_splits() {
     // Verify split amounts
     let total_split_amount: f64 = splits
         .iter()
-        .map(|s| s.amount.parse::<f64>().unwrap())
+        .map(|s| s.amount.to_string().parse::<f64>().unwrap())
         .sum();
     assert_eq!(
         total_split_amount, 100.00,
@@ -852,7 +1589,7 @@ async fn test_get_transaction_success() {
     let transaction: TransactionResponse = extract_json(get_response);
     assert_eq!(transaction.id, created_transaction.id);
     assert_eq!(transaction.title, "Test Transaction");
-    assert_eq!(transaction.amount, "-125.75");
+    assert_eq!(transaction.amount.to_string(), "-125.75");
     assert_eq!(transaction.account_id, account.id);
 }
 
@@ -1039,7 +1776,7 @@ async fn test_update_transaction_success() {
     let updated_transaction: TransactionResponse = extract_json(update_response);
     assert_eq!(updated_transaction.id, transaction.id);
     assert_eq!(updated_transaction.title, "Updated Title");
-    assert_eq!(updated_transaction.amount, "-150.00");
+    assert_eq!(updated_transaction.amount.to_string(), "-150.00");
     assert_eq!(updated_transaction.notes, Some("Updated notes".to_string()));
     // Account and category should remain unchanged
     assert_eq!(updated_transaction.account_id, account.id);
@@ -1103,260 +1840,382 @@ async fn test_update_transaction_partial() {
 
     let updated_transaction: TransactionResponse = extract_json(update_response);
     assert_eq!(updated_transaction.title, "New Title Only");
-    assert_eq!(updated_transaction.amount, "-200.00");
+    assert_eq!(updated_transaction.amount.to_string(), "-200.00");
     assert_eq!(
         updated_transaction.notes,
         Some("Original notes".to_string())
     );
 }
 
-/// Test that updating a non-existent transaction fails.
-///
-/// Verifies that:
-/// - Status code is 404 Not Found
-/// - Error message indicates transaction not found
-#[tokio::test]
-async fn test_update_transaction_not_found() {
-    let server = create_test_server().await;
-    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
-
-    let auth = register_test_user(
-        &server,
-        &format!("updatenotfound_{}", timestamp),
-        &format!("updatenotfound_{}@example.com", timestamp),
-        "SecurePass123!",
-        "Update Not Found User",
-    )
-    .await;
-
-    let fake_id = uuid::Uuid::new_v4();
-    let update_request = json!({
-        "title": "New Title"
-    });
-    let response = put_authenticated(
-        &server,
-        &format!("/api/v1/transactions/{}", fake_id),
-        &auth.token,
-        &update_request,
-    )
-    .await;
-    assert_status(&response, 404);
-}
-
-/// Test that users cannot update other users' transactions.
+/// Test that an explicit `null` clears optional fields while omitting keeps them.
 ///
 /// Verifies that:
-/// - Status code is 403 Forbidden or 404 Not Found
-/// - User B cannot update User A's transaction
+/// - `null` clears `category_id` and `notes`
+/// - The omitted `merchant` is left unchanged
+/// - A value sets a previously cleared field again
 #[tokio::test]
-async fn test_update_transaction_wrong_user() {
+async fn test_update_transaction_null_clears_fields() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
-    // Register two users
-    let auth_a = register_test_user(
-        &server,
-        &format!("updatewronga_{}", timestamp),
-        &format!("updatewronga_{}@example.com", timestamp),
-        "SecurePass123!",
-        "Update Wrong A",
-    )
-    .await;
-
-    let auth_b = register_test_user(
-        &server,
-        &format!("updatewrongb_{}", timestamp),
-        &format!("updatewrongb_{}@example.com", timestamp),
-        "SecurePass123!",
-        "Update Wrong B",
-    )
-    .await;
+    let auth = register_unique_test_user(&server, &format!("txnull_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let category = create_test_category(&server, &auth.token, "Test Category").await;
 
-    // User A creates a transaction
-    let account_a = create_test_account(&server, &auth_a.token, "User A Account").await;
-    let category_a = create_test_category(&server, &auth_a.token, "User A Category").await;
     let create_request = json!({
-        "account_id": account_a.id,
-        "category_id": category_a.id,
-        "title": "User A Transaction",
-        "amount": -75.00,
-        "date": Utc::now().to_rfc3339()
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "Coffee",
+        "amount": -4.50,
+        "date": Utc::now().to_rfc3339(),
+        "notes": "Original notes",
+        "merchant": "Corner Cafe"
     });
     let create_response = post_authenticated(
         &server,
         "/api/v1/transactions",
-        &auth_a.token,
+        &auth.token,
         &create_request,
     )
     .await;
     assert_status(&create_response, 201);
     let transaction: TransactionResponse = extract_json(create_response);
+    let path = format!("/api/v1/transactions/{}", transaction.id);
 
-    // User B tries to update User A's transaction
     let update_request = json!({
-        "title": "Hacked Title"
+        "category_id": null,
+        "notes": null
     });
-    let response = put_authenticated(
-        &server,
-        &format!("/api/v1/transactions/{}", transaction.id),
-        &auth_b.token,
-        &update_request,
-    )
-    .await;
-
-    // Should be 403 Forbidden (user authenticated but accessing wrong resource)
-    assert_status(&response, 403);
-}
+    let update_response = put_authenticated(&server, &path, &auth.token, &update_request).await;
+    assert_status(&update_response, 200);
 
-/// Test that updating transaction without authentication fails.
-///
-/// Verifies that:
-/// - Status code is 401 Unauthorized
-/// - Error message indicates missing authentication
-#[tokio::test]
-async fn test_update_transaction_unauthorized() {
-    let server = create_test_server().await;
+    let updated: TransactionResponse = extract_json(update_response);
+    assert_eq!(updated.category_id, None);
+    assert_eq!(updated.notes, None);
+    assert_eq!(updated.merchant, Some("Corner Cafe".to_string()));
 
-    let fake_id = uuid::Uuid::new_v4();
     let update_request = json!({
-        "title": "New Title"
+        "category_id": category.id,
+        "notes": "New notes"
     });
+    let update_response = put_authenticated(&server, &path, &auth.token, &update_request).await;
+    assert_status(&update_response, 200);
 
-    let response = server
-        .put(&format!("/api/v1/transactions/{}", fake_id))
-        .json(&update_request)
-        .await;
-    assert_status(&response, 401);
+    let updated: TransactionResponse = extract_json(update_response);
+    assert_eq!(updated.category_id, Some(category.id));
+    assert_eq!(updated.notes, Some("New notes".to_string()));
+    assert_eq!(updated.merchant, Some("Corner Cafe".to_string()));
 }
 
-// ============================================================================
-// Delete Transaction Tests
-// ============================================================================
-
-/// Test successful transaction deletion.
+/// Test replacing a transaction's splits on update.
 ///
 /// Verifies that:
-/// - Status code is 204 No Content
-/// - Transaction is actually deleted
-/// - Subsequent GET returns 404
+/// - Status code is 200 OK
+/// - Matching splits are updated in place, new people are added and others removed
+/// - The updated splits are returned in the response and persisted
 #[tokio::test]
-async fn test_delete_transaction_success() {
+async fn test_update_transaction_splits() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
     let auth = register_test_user(
         &server,
-        &format!("deleteuser_{}", timestamp),
-        &format!("delete_{}@example.com", timestamp),
+        &format!("updatesplits_{}", timestamp),
+        &format!("updatesplits_{}@example.com", timestamp),
         "SecurePass123!",
-        "Delete Test User",
+        "Update Splits User",
     )
     .await;
 
     let account = create_test_account(&server, &auth.token, "Test Account").await;
-    let category = create_test_category(&server, &auth.token, "Test Category").await;
+    let person1 = create_test_person(&server, &auth.token, "Person 1").await;
+    let person2 = create_test_person(&server, &auth.token, "Person 2").await;
+    let person3 = create_test_person(&server, &auth.token, "Person 3").await;
 
-    // Create a transaction
     let create_request = json!({
         "account_id": account.id,
-        "category_id": category.id,
-        "title": "Transaction to Delete",
-        "amount": -50.00,
-        "date": Utc::now().to_rfc3339()
+        "title": "Shared Dinner",
+        "amount": -100.00,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [
+            { "person_id": person1.id, "amount": 30.00 },
+            { "person_id": person2.id, "amount": 20.00 }
+        ]
     });
-    let create_response = post_authenticated(
+    let response = post_authenticated(
         &server,
         "/api/v1/transactions",
         &auth.token,
         &create_request,
     )
     .await;
-    assert_status(&create_response, 201);
-    let transaction: TransactionResponse = extract_json(create_response);
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    let original_splits = transaction.splits.unwrap();
+    let person1_split = original_splits
+        .iter()
+        .find(|s| s.person_id == person1.id)
+        .unwrap();
 
-    // Delete the transaction
-    let delete_response = delete_authenticated(
+    // Keep person 1 with a new amount, drop person 2 and add person 3
+    let update_request = json!({
+        "splits": [
+            { "person_id": person1.id, "amount": 40.00 },
+            { "person_id": person3.id, "amount": 10.00 }
+        ]
+    });
+    let response = put_authenticated(
         &server,
         &format!("/api/v1/transactions/{}", transaction.id),
         &auth.token,
+        &update_request,
     )
     .await;
-    assert_status(&delete_response, 204);
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert!(!updated.needs_resync);
 
-    // Verify transaction is deleted - GET should return 404
-    let get_response = get_authenticated(
+    let splits = updated.splits.unwrap();
+    assert_eq!(splits.len(), 2);
+    let updated_person1 = splits.iter().find(|s| s.person_id == person1.id).unwrap();
+    assert_eq!(updated_person1.id, person1_split.id);
+    assert_eq!(updated_person1.amount.to_string(), "40.00");
+    let added_person3 = splits.iter().find(|s| s.person_id == person3.id).unwrap();
+    assert_eq!(added_person3.amount.to_string(), "10.00");
+    assert!(!splits.iter().any(|s| s.person_id == person2.id));
+
+    let response = get_authenticated(
         &server,
         &format!("/api/v1/transactions/{}", transaction.id),
         &auth.token,
     )
     .await;
-    assert_status(&get_response, 404);
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.splits.unwrap().len(), 2);
 
-    // Verify transaction is not in list
-    let list_response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
-    assert_status(&list_response, 200);
-    let transactions: Vec<TransactionResponse> = extract_json(list_response);
-    assert!(
-        !transactions.iter().any(|t| t.id == transaction.id),
-        "Deleted transaction should not appear in list"
-    );
+    // An empty array removes all splits
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth.token,
+        &json!({ "splits": [] }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let cleared: TransactionResponse = extract_json(response);
+    assert_eq!(cleared.splits.unwrap().len(), 0);
 }
 
-/// Test that deleting a non-existent transaction fails.
+/// Test that split updates are validated like splits on create.
+///
+/// Verifies that:
+/// - Splits exceeding the stored amount are rejected with 422
+/// - Splits exceeding a new amount in the same request are rejected with 422
+/// - Duplicate people are rejected with 422
+/// - Settled splits cannot be removed (409 Conflict)
+#[tokio::test]
+async fn test_update_transaction_splits_validation() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("updatesplitsval_{}", timestamp),
+        &format!("updatesplitsval_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Update Splits Validation User",
+    )
+    .await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person1 = create_test_person(&server, &auth.token, "Person 1").await;
+    let person2 = create_test_person(&server, &auth.token, "Person 2").await;
+
+    let create_request = json!({
+        "account_id": account.id,
+        "title": "Shared Groceries",
+        "amount": -60.00,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [
+            { "person_id": person1.id, "amount": 20.00 }
+        ]
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &create_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    let split_id = transaction.splits.unwrap()[0].id;
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+
+    let exceeds_stored = json!({
+        "splits": [
+            { "person_id": person1.id, "amount": 40.00 },
+            { "person_id": person2.id, "amount": 30.00 }
+        ]
+    });
+    let response = put_authenticated(&server, &path, &auth.token, &exceeds_stored).await;
+    assert_status(&response, 422);
+
+    let exceeds_new = json!({
+        "amount": -30.00,
+        "splits": [
+            { "person_id": person1.id, "amount": 20.00 },
+            { "person_id": person2.id, "amount": 20.00 }
+        ]
+    });
+    let response = put_authenticated(&server, &path, &auth.token, &exceeds_new).await;
+    assert_status(&response, 422);
+
+    let duplicate = json!({
+        "splits": [
+            { "person_id": person1.id, "amount": 10.00 },
+            { "person_id": person1.id, "amount": 10.00 }
+        ]
+    });
+    let response = put_authenticated(&server, &path, &auth.token, &duplicate).await;
+    assert_status(&response, 422);
+
+    let response = post_authenticated(
+        &server,
+        &format!("{}/splits/{}/settle", path, split_id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let response = put_authenticated(&server, &path, &auth.token, &json!({ "splits": [] })).await;
+    assert_status(&response, 409);
+
+    // The rejected updates left the original split in place
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    let splits = fetched.splits.unwrap();
+    assert_eq!(splits.len(), 1);
+    assert_eq!(splits[0].amount.to_string(), "20.00");
+}
+
+/// Test that an update whose splits can't be replaced changes nothing.
+///
+/// Verifies that:
+/// - Removing a settled split along with other fields fails with 409
+/// - The title, amount and version are left as they were
+#[tokio::test]
+async fn test_update_transaction_rolls_back_with_splits() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("updaterollback_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let person = create_test_person(&server, &auth.token, "Person").await;
+
+    let create_request = json!({
+        "account_id": account.id,
+        "title": "Shared Taxi",
+        "amount": -40.00,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [{ "person_id": person.id, "amount": 20.00 }]
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &create_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    let split_id = transaction.splits.as_ref().unwrap()[0].id;
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+
+    let response = post_authenticated(
+        &server,
+        &format!("{}/splits/{}/settle", path, split_id),
+        &auth.token,
+        &json!({}),
+    )
+    .await;
+    assert_status(&response, 200);
+
+    let update_request = json!({ "title": "Solo Taxi", "amount": -45.00, "splits": [] });
+    let response = put_authenticated(&server, &path, &auth.token, &update_request).await;
+    assert_status(&response, 409);
+
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.title, "Shared Taxi");
+    assert_eq!(fetched.amount.to_string(), "-40.00");
+    assert_eq!(fetched.version, transaction.version);
+    assert_eq!(fetched.splits.unwrap().len(), 1);
+}
+
+/// Test that updating a non-existent transaction fails.
 ///
 /// Verifies that:
 /// - Status code is 404 Not Found
 /// - Error message indicates transaction not found
 #[tokio::test]
-async fn test_delete_transaction_not_found() {
+async fn test_update_transaction_not_found() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
     let auth = register_test_user(
         &server,
-        &format!("deletenotfound_{}", timestamp),
-        &format!("deletenotfound_{}@example.com", timestamp),
+        &format!("updatenotfound_{}", timestamp),
+        &format!("updatenotfound_{}@example.com", timestamp),
         "SecurePass123!",
-        "Delete Not Found User",
+        "Update Not Found User",
     )
     .await;
 
     let fake_id = uuid::Uuid::new_v4();
-    let response = delete_authenticated(
+    let update_request = json!({
+        "title": "New Title"
+    });
+    let response = put_authenticated(
         &server,
         &format!("/api/v1/transactions/{}", fake_id),
         &auth.token,
+        &update_request,
     )
     .await;
     assert_status(&response, 404);
 }
 
-/// Test that users cannot delete other users' transactions.
+/// Test that users cannot update other users' transactions.
 ///
 /// Verifies that:
 /// - Status code is 403 Forbidden or 404 Not Found
-/// - User B cannot delete User A's transaction
+/// - User B cannot update User A's transaction
 #[tokio::test]
-async fn test_delete_transaction_wrong_user() {
+async fn test_update_transaction_wrong_user() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
     // Register two users
     let auth_a = register_test_user(
         &server,
-        &format!("deletewronga_{}", timestamp),
-        &format!("deletewronga_{}@example.com", timestamp),
+        &format!("updatewronga_{}", timestamp),
+        &format!("updatewronga_{}@example.com", timestamp),
         "SecurePass123!",
-        "Delete Wrong A",
+        "Update Wrong A",
     )
     .await;
 
     let auth_b = register_test_user(
         &server,
-        &format!("deletewrongb_{}", timestamp),
-        &format!("deletewrongb_{}@example.com", timestamp),
+        &format!("updatewrongb_{}", timestamp),
+        &format!("updatewrongb_{}@example.com", timestamp),
         "SecurePass123!",
-        "Delete Wrong B",
+        "Update Wrong B",
     )
     .await;
 
@@ -1367,7 +2226,7 @@ async fn test_delete_transaction_wrong_user() {
         "account_id": account_a.id,
         "category_id": category_a.id,
         "title": "User A Transaction",
-        "amount": -100.00,
+        "amount": -75.00,
         "date": Utc::now().to_rfc3339()
     });
     let create_response = post_authenticated(
@@ -1380,80 +2239,173 @@ async fn test_delete_transaction_wrong_user() {
     assert_status(&create_response, 201);
     let transaction: TransactionResponse = extract_json(create_response);
 
-    // User B tries to delete User A's transaction
-    let response = delete_authenticated(
+    // User B tries to update User A's transaction
+    let update_request = json!({
+        "title": "Hacked Title"
+    });
+    let response = put_authenticated(
         &server,
         &format!("/api/v1/transactions/{}", transaction.id),
         &auth_b.token,
+        &update_request,
     )
     .await;
 
     // Should be 403 Forbidden (user authenticated but accessing wrong resource)
     assert_status(&response, 403);
-
-    // Verify transaction still exists for User A
-    let get_response = get_authenticated(
-        &server,
-        &format!("/api/v1/transactions/{}", transaction.id),
-        &auth_a.token,
-    )
-    .await;
-    assert_status(&get_response, 200);
 }
 
-/// Test that deleting transaction without authentication fails.
+/// Test that updating transaction without authentication fails.
 ///
 /// Verifies that:
 /// - Status code is 401 Unauthorized
 /// - Error message indicates missing authentication
 #[tokio::test]
-async fn test_delete_transaction_unauthorized() {
+async fn test_update_transaction_unauthorized() {
     let server = create_test_server().await;
 
     let fake_id = uuid::Uuid::new_v4();
+    let update_request = json!({
+        "title": "New Title"
+    });
+
     let response = server
-        .delete(&format!("/api/v1/transactions/{}", fake_id))
+        .put(&format!("/api/v1/transactions/{}", fake_id))
+        .json(&update_request)
         .await;
     assert_status(&response, 401);
 }
 
+/// Test optimistic concurrency control on transaction updates.
+///
+/// Verifies that:
+/// - Each update increments the version
+/// - An update based on the current version (body or If-Match) succeeds
+/// - A stale version returns 409 Conflict with the current transaction
+/// - Conflicting header and body versions return 400
+/// - Updates without a version are applied unconditionally
+#[tokio::test]
+async fn test_update_transaction_version() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("tx_version_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let create_request = json!({
+        "account_id": account.id,
+        "title": "Groceries",
+        "amount": -40.00,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &create_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.version, 1);
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+
+    // Current version in the body
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({"title": "Weekly groceries", "version": 1}),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.version, 2);
+
+    // Stale version in the body
+    let response = put_authenticated(
+        &server,
+        &path,
+        &auth.token,
+        &json!({"title": "Lost update", "version": 1}),
+    )
+    .await;
+    assert_status(&response, 409);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["current"]["version"], 2);
+    assert_eq!(body["current"]["title"], "Weekly groceries");
+
+    // Stale version in If-Match
+    let response = server
+        .put(&path)
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .add_header("If-Match", "\"1\"")
+        .json(&json!({"title": "Lost update"}))
+        .await;
+    assert_status(&response, 409);
+
+    // Current version in If-Match
+    let response = server
+        .put(&path)
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .add_header("If-Match", "\"2\"")
+        .json(&json!({"amount": -45.00}))
+        .await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.version, 3);
+    assert_eq!(updated.amount.to_string(), "-45.00");
+
+    // Header and body disagree
+    let response = server
+        .put(&path)
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .add_header("If-Match", "\"3\"")
+        .json(&json!({"title": "Mixed", "version": 2}))
+        .await;
+    assert_status(&response, 400);
+
+    // Without a version the update is unconditional
+    let response = put_authenticated(&server, &path, &auth.token, &json!({"title": "Final"})).await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.version, 4);
+    assert_eq!(updated.title, "Final");
+}
+
 // ============================================================================
-// Integration Flow Test
+// Delete Transaction Tests
 // ============================================================================
 
-/// Test complete CRUD flow: Create → Read → Update → Delete.
+/// Test successful transaction deletion.
 ///
 /// Verifies that:
-/// - Transaction can be created successfully
-/// - Transaction can be retrieved with correct data
-/// - Transaction can be updated with new data
-/// - Transaction can be deleted successfully
-/// - All operations maintain data consistency
+/// - Status code is 204 No Content
+/// - Transaction is actually deleted
+/// - Subsequent GET returns 404
 #[tokio::test]
-async fn test_full_transaction_crud_flow() {
+async fn test_delete_transaction_success() {
     let server = create_test_server().await;
     let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
 
     let auth = register_test_user(
         &server,
-        &format!("cruduser_{}", timestamp),
-        &format!("crud_{}@example.com", timestamp),
+        &format!("deleteuser_{}", timestamp),
+        &format!("delete_{}@example.com", timestamp),
         "SecurePass123!",
-        "CRUD Test User",
+        "Delete Test User",
     )
     .await;
 
     let account = create_test_account(&server, &auth.token, "Test Account").await;
     let category = create_test_category(&server, &auth.token, "Test Category").await;
 
-    // Step 1: Create transaction
+    // Create a transaction
     let create_request = json!({
         "account_id": account.id,
         "category_id": category.id,
-        "title": "CRUD Test Transaction",
-        "amount": -250.50,
-        "date": Utc::now().to_rfc3339(),
-        "notes": "Initial notes"
+        "title": "Transaction to Delete",
+        "amount": -50.00,
+        "date": Utc::now().to_rfc3339()
     });
     let create_response = post_authenticated(
         &server,
@@ -1463,15 +2415,206 @@ async fn test_full_transaction_crud_flow() {
     )
     .await;
     assert_status(&create_response, 201);
-    let created_transaction: TransactionResponse = extract_json(create_response);
-
-    assert_eq!(created_transaction.title, "CRUD Test Transaction");
-    assert_eq!(created_transaction.amount, "-250.50");
-    assert_eq!(created_transaction.account_id, account.id);
-    assert_eq!(created_transaction.user_id, auth.user.id);
+    let transaction: TransactionResponse = extract_json(create_response);
 
-    // Step 2: Read transaction
-    let get_response = get_authenticated(
+    // Delete the transaction
+    let delete_response = delete_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&delete_response, 204);
+
+    // Verify transaction is deleted - GET should return 404
+    let get_response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&get_response, 404);
+
+    // Verify transaction is not in list
+    let list_response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&list_response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(list_response);
+    assert!(
+        !transactions.iter().any(|t| t.id == transaction.id),
+        "Deleted transaction should not appear in list"
+    );
+}
+
+/// Test that deleting a non-existent transaction fails.
+///
+/// Verifies that:
+/// - Status code is 404 Not Found
+/// - Error message indicates transaction not found
+#[tokio::test]
+async fn test_delete_transaction_not_found() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("deletenotfound_{}", timestamp),
+        &format!("deletenotfound_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Delete Not Found User",
+    )
+    .await;
+
+    let fake_id = uuid::Uuid::new_v4();
+    let response = delete_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", fake_id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 404);
+}
+
+/// Test that users cannot delete other users' transactions.
+///
+/// Verifies that:
+/// - Status code is 403 Forbidden or 404 Not Found
+/// - User B cannot delete User A's transaction
+#[tokio::test]
+async fn test_delete_transaction_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    // Register two users
+    let auth_a = register_test_user(
+        &server,
+        &format!("deletewronga_{}", timestamp),
+        &format!("deletewronga_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Delete Wrong A",
+    )
+    .await;
+
+    let auth_b = register_test_user(
+        &server,
+        &format!("deletewrongb_{}", timestamp),
+        &format!("deletewrongb_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Delete Wrong B",
+    )
+    .await;
+
+    // User A creates a transaction
+    let account_a = create_test_account(&server, &auth_a.token, "User A Account").await;
+    let category_a = create_test_category(&server, &auth_a.token, "User A Category").await;
+    let create_request = json!({
+        "account_id": account_a.id,
+        "category_id": category_a.id,
+        "title": "User A Transaction",
+        "amount": -100.00,
+        "date": Utc::now().to_rfc3339()
+    });
+    let create_response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth_a.token,
+        &create_request,
+    )
+    .await;
+    assert_status(&create_response, 201);
+    let transaction: TransactionResponse = extract_json(create_response);
+
+    // User B tries to delete User A's transaction
+    let response = delete_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth_b.token,
+    )
+    .await;
+
+    // Should be 403 Forbidden (user authenticated but accessing wrong resource)
+    assert_status(&response, 403);
+
+    // Verify transaction still exists for User A
+    let get_response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", transaction.id),
+        &auth_a.token,
+    )
+    .await;
+    assert_status(&get_response, 200);
+}
+
+/// Test that deleting transaction without authentication fails.
+///
+/// Verifies that:
+/// - Status code is 401 Unauthorized
+/// - Error message indicates missing authentication
+#[tokio::test]
+async fn test_delete_transaction_unauthorized() {
+    let server = create_test_server().await;
+
+    let fake_id = uuid::Uuid::new_v4();
+    let response = server
+        .delete(&format!("/api/v1/transactions/{}", fake_id))
+        .await;
+    assert_status(&response, 401);
+}
+
+// ============================================================================
+// Integration Flow Test
+// ============================================================================
+
+/// Test complete CRUD flow: Create → Read → Update → Delete.
+///
+/// Verifies that:
+/// - Transaction can be created successfully
+/// - Transaction can be retrieved with correct data
+/// - Transaction can be updated with new data
+/// - Transaction can be deleted successfully
+/// - All operations maintain data consistency
+#[tokio::test]
+async fn test_full_transaction_crud_flow() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("cruduser_{}", timestamp),
+        &format!("crud_{}@example.com", timestamp),
+        "SecurePass123!",
+        "CRUD Test User",
+    )
+    .await;
+
+    let account = create_test_account(&server, &auth.token, "Test Account").await;
+    let category = create_test_category(&server, &auth.token, "Test Category").await;
+
+    // Step 1: Create transaction
+    let create_request = json!({
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "CRUD Test Transaction",
+        "amount": -250.50,
+        "date": Utc::now().to_rfc3339(),
+        "notes": "Initial notes"
+    });
+    let create_response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &create_request,
+    )
+    .await;
+    assert_status(&create_response, 201);
+    let created_transaction: TransactionResponse = extract_json(create_response);
+
+    assert_eq!(created_transaction.title, "CRUD Test Transaction");
+    assert_eq!(created_transaction.amount.to_string(), "-250.50");
+    assert_eq!(created_transaction.account_id, account.id);
+    assert_eq!(created_transaction.user_id, auth.user.id);
+
+    // Step 2: Read transaction
+    let get_response = get_authenticated(
         &server,
         &format!("/api/v1/transactions/{}", created_transaction.id),
         &auth.token,
@@ -1502,7 +2645,7 @@ async fn test_full_transaction_crud_flow() {
 
     assert_eq!(updated_transaction.id, created_transaction.id);
     assert_eq!(updated_transaction.title, "Updated CRUD Transaction");
-    assert_eq!(updated_transaction.amount, "-300.00");
+    assert_eq!(updated_transaction.amount.to_string(), "-300.00");
     assert_eq!(updated_transaction.notes, Some("Updated notes".to_string()));
     // Account and category should remain unchanged
     assert_eq!(updated_transaction.account_id, account.id);
@@ -1518,7 +2661,7 @@ async fn test_full_transaction_crud_flow() {
     assert_status(&get_response2, 200);
     let verified_transaction: TransactionResponse = extract_json(get_response2);
     assert_eq!(verified_transaction.title, "Updated CRUD Transaction");
-    assert_eq!(verified_transaction.amount, "-300.00");
+    assert_eq!(verified_transaction.amount.to_string(), "-300.00");
 
     // Step 5: Delete transaction
     let delete_response = delete_authenticated(
@@ -1548,3 +2691,1472 @@ async fn test_full_transaction_crud_flow() {
         "All transactions should be deleted"
     );
 }
+
+// ============================================================================
+// Overdraft and Credit Limit Tests
+// ============================================================================
+
+/// Test that an overdraft on an account allowing it returns a warning.
+///
+/// Verifies that:
+/// - Status code is 201 Created
+/// - Response includes the projected balance
+/// - Response includes a balance warning
+#[tokio::test]
+async fn test_create_transaction_overdraft_warning() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("overdraftwarn_{}", timestamp),
+        &format!("overdraftwarn_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Overdraft Warn User",
+    )
+    .await;
+
+    let account_request = json!({
+        "name": "Checking",
+        "account_type": "CHECKING",
+        "currency": "USD",
+        "initial_balance": 100.0
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+    assert!(account.allow_overdraft);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Big Purchase",
+        "amount": -150.0,
+        "date": Utc::now().to_rfc3339()
+    });
+
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(
+        transaction
+            .projected_balance
+            .map(|b| b.to_string())
+            .as_deref(),
+        Some("-50.00")
+    );
+    assert!(transaction.balance_warning.is_some());
+}
+
+/// Test that an overdraft is rejected when the account disallows it.
+///
+/// Verifies that:
+/// - Status code is 422 Unprocessable Entity
+/// - Transactions within the overdraft limit are still accepted
+#[tokio::test]
+async fn test_create_transaction_overdraft_rejected() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("overdraftreject_{}", timestamp),
+        &format!("overdraftreject_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Overdraft Reject User",
+    )
+    .await;
+
+    let account_request = json!({
+        "name": "Strict Checking",
+        "account_type": "CHECKING",
+        "currency": "USD",
+        "initial_balance": 100.0,
+        "allow_overdraft": false,
+        "overdraft_limit": 20.0
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    // Within the overdraft limit: accepted without warning
+    let request = json!({
+        "account_id": account.id,
+        "title": "Small Overdraft",
+        "amount": -110.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(
+        transaction
+            .projected_balance
+            .map(|b| b.to_string())
+            .as_deref(),
+        Some("-10.00")
+    );
+    assert!(transaction.balance_warning.is_none());
+
+    // Beyond the overdraft limit: rejected
+    let request = json!({
+        "account_id": account.id,
+        "title": "Too Much",
+        "amount": -20.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+}
+
+/// Test that credit card accounts are checked against their credit limit.
+///
+/// Verifies that:
+/// - Account response reports available credit
+/// - Charges beyond the credit limit are rejected when overdraft is disallowed
+#[tokio::test]
+async fn test_create_transaction_credit_limit() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("creditlimit_{}", timestamp),
+        &format!("creditlimit_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Credit Limit User",
+    )
+    .await;
+
+    let account_request = json!({
+        "name": "Credit Card",
+        "account_type": "CREDIT_CARD",
+        "currency": "USD",
+        "allow_overdraft": false,
+        "credit_limit": 500.0
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+    assert_eq!(account.available_credit, Some(500.0));
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Groceries",
+        "amount": -400.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Electronics",
+        "amount": -200.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 422);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}", account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let account: AccountResponse = extract_json(response);
+    assert_eq!(account.available_credit, Some(100.0));
+}
+
+// ============================================================================
+// Nested Transaction Routes Tests
+// ============================================================================
+
+/// Test listing transactions through the account and category routes.
+///
+/// Verifies that:
+/// - Only the parent resource's transactions are returned
+/// - A parent without transactions returns an empty array
+#[tokio::test]
+async fn test_list_nested_transactions() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("nested_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let empty_account = create_test_account(&server, &auth.token, "Savings").await;
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+    let empty_category = create_test_category(&server, &auth.token, "Travel").await;
+
+    for (title, category_id) in [("Market", Some(category.id)), ("Rent", None)] {
+        let request = json!({
+            "account_id": account.id,
+            "category_id": category_id,
+            "title": title,
+            "amount": -25.00,
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+    }
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}/transactions", account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 2);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/categories/{}/transactions?limit=10", category.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].title, "Market");
+
+    for path in [
+        format!("/api/v1/accounts/{}/transactions", empty_account.id),
+        format!("/api/v1/categories/{}/transactions", empty_category.id),
+    ] {
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 200);
+        let transactions: Vec<TransactionResponse> = extract_json(response);
+        assert!(transactions.is_empty());
+    }
+}
+
+/// Test that nested transaction routes check ownership of the parent resource.
+///
+/// Verifies that:
+/// - Another user's account or category returns 403 Forbidden
+/// - An unknown account or category returns 404 Not Found
+#[tokio::test]
+async fn test_list_nested_transactions_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let owner = register_unique_test_user(&server, &format!("nested_owner_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("nested_other_{}", timestamp)).await;
+    let account = create_test_account(&server, &owner.token, "Checking").await;
+    let category = create_test_category(&server, &owner.token, "Groceries").await;
+
+    for path in [
+        format!("/api/v1/accounts/{}/transactions", account.id),
+        format!("/api/v1/categories/{}/transactions", category.id),
+    ] {
+        let response = get_authenticated(&server, &path, &other.token).await;
+        assert_status(&response, 403);
+    }
+
+    for path in [
+        format!("/api/v1/accounts/{}/transactions", uuid::Uuid::new_v4()),
+        format!("/api/v1/categories/{}/transactions", uuid::Uuid::new_v4()),
+    ] {
+        let response = get_authenticated(&server, &path, &owner.token).await;
+        assert_status(&response, 404);
+    }
+}
+
+// ============================================================================
+// Transaction Status Tests
+// ============================================================================
+
+/// Test that pending transactions only affect the available balance until posted.
+///
+/// Verifies that:
+/// - Transactions default to posted
+/// - A pending transaction is excluded from the cleared balance
+/// - A pending transaction is included in the available balance
+/// - Posting moves it into the cleared balance
+/// - Posting a transaction that is not pending returns 409 Conflict
+#[tokio::test]
+async fn test_post_pending_transaction() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("pendingtx_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let posted_request = json!({
+        "account_id": account.id,
+        "title": "Salary",
+        "amount": 500.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &posted_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let posted: TransactionResponse = extract_json(response);
+    assert_eq!(posted.status, TransactionStatus::Posted);
+
+    let pending_request = json!({
+        "account_id": account.id,
+        "title": "Card Hold",
+        "amount": -80.0,
+        "date": Utc::now().to_rfc3339(),
+        "status": "pending"
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &pending_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let pending: TransactionResponse = extract_json(response);
+    assert_eq!(pending.status, TransactionStatus::Pending);
+
+    let account_path = format!("/api/v1/accounts/{}", account.id);
+    let response = get_authenticated(&server, &account_path, &auth.token).await;
+    let balances: AccountResponse = extract_json(response);
+    assert_eq!(balances.balance, 500.0);
+    assert_eq!(balances.available_balance, 420.0);
+
+    let post_path = format!("/api/v1/transactions/{}/post", pending.id);
+    let response = post_authenticated(&server, &post_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.status, TransactionStatus::Posted);
+
+    let response = get_authenticated(&server, &account_path, &auth.token).await;
+    let balances: AccountResponse = extract_json(response);
+    assert_eq!(balances.balance, 420.0);
+    assert_eq!(balances.available_balance, 420.0);
+
+    let response = post_authenticated(&server, &post_path, &auth.token, &json!({})).await;
+    assert_status(&response, 409);
+}
+
+/// Test that voiding a transaction keeps it listed but excludes it from balances and debts.
+///
+/// Verifies that:
+/// - Voiding returns the transaction with status void
+/// - A void transaction no longer counts toward the balance or the person's debt
+/// - A void transaction is still listed
+/// - Voiding a void transaction and unvoiding a non-void one return 409 Conflict
+/// - Unvoiding restores it as posted, with its amount and split counted again
+#[tokio::test]
+async fn test_void_and_unvoid_transaction() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("voidtx_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let person = create_test_person(&server, &auth.token, "Friend").await;
+
+    let income_request = json!({
+        "account_id": account.id,
+        "title": "Salary",
+        "amount": 500.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &income_request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let expense_request = json!({
+        "account_id": account.id,
+        "title": "Dinner",
+        "amount": -120.0,
+        "date": Utc::now().to_rfc3339(),
+        "splits": [{ "person_id": person.id, "amount": 40.0 }]
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &expense_request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let expense: TransactionResponse = extract_json(response);
+
+    let void_path = format!("/api/v1/transactions/{}/void", expense.id);
+    let response = post_authenticated(&server, &void_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let voided: TransactionResponse = extract_json(response);
+    assert_eq!(voided.status, TransactionStatus::Void);
+
+    let account_path = format!("/api/v1/accounts/{}", account.id);
+    let response = get_authenticated(&server, &account_path, &auth.token).await;
+    let balances: AccountResponse = extract_json(response);
+    assert_eq!(balances.balance, 500.0);
+    assert_eq!(balances.available_balance, 500.0);
+
+    let debts_path = format!("/api/v1/people/{}/debts", person.id);
+    let response = get_authenticated(&server, &debts_path, &auth.token).await;
+    let debt: PersonDebt = extract_json(response);
+    assert_eq!(debt.debt_amount, "0");
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    let listed = transactions
+        .iter()
+        .find(|t| t.id == expense.id)
+        .expect("Void transaction should still be listed");
+    assert_eq!(listed.status, TransactionStatus::Void);
+
+    let response = post_authenticated(&server, &void_path, &auth.token, &json!({})).await;
+    assert_status(&response, 409);
+
+    let unvoid_path = format!("/api/v1/transactions/{}/unvoid", expense.id);
+    let response = post_authenticated(&server, &unvoid_path, &auth.token, &json!({})).await;
+    assert_status(&response, 200);
+    let restored: TransactionResponse = extract_json(response);
+    assert_eq!(restored.status, TransactionStatus::Posted);
+
+    let response = get_authenticated(&server, &account_path, &auth.token).await;
+    let balances: AccountResponse = extract_json(response);
+    assert_eq!(balances.balance, 380.0);
+
+    let response = get_authenticated(&server, &debts_path, &auth.token).await;
+    let debt: PersonDebt = extract_json(response);
+    assert_eq!(debt.debt_amount.parse::<f64>().unwrap(), 40.0);
+
+    let response = post_authenticated(&server, &unvoid_path, &auth.token, &json!({})).await;
+    assert_status(&response, 409);
+}
+
+/// Test that voiding and unvoiding another user's transaction is forbidden.
+#[tokio::test]
+async fn test_void_transaction_wrong_user() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let owner = register_unique_test_user(&server, &format!("voidowner_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("voidother_{}", timestamp)).await;
+    let account = create_test_account(&server, &owner.token, "Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Salary",
+        "amount": 500.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/transactions", &owner.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+
+    let void_path = format!("/api/v1/transactions/{}/void", transaction.id);
+    let response = post_authenticated(&server, &void_path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+
+    let unvoid_path = format!("/api/v1/transactions/{}/unvoid", transaction.id);
+    let response = post_authenticated(&server, &unvoid_path, &other.token, &json!({})).await;
+    assert_status(&response, 403);
+}
+
+// ============================================================================
+// Original Currency Tests
+// ============================================================================
+
+/// Test recording a foreign-currency transaction on a home-currency account.
+///
+/// Verifies that:
+/// - The original currency, amount and exchange rate are returned
+/// - The amount must match the converted original amount within rounding (422 otherwise)
+/// - The original details must be given together (422 otherwise)
+/// - The original currency must differ from the account currency (422 otherwise)
+/// - Updating the amount is checked against the stored original details
+#[tokio::test]
+async fn test_create_transaction_with_original_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("travel_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "USD Card").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Museum",
+        "amount": -22.0,
+        "date": Utc::now().to_rfc3339(),
+        "original_currency": "EUR",
+        "original_amount": -20.0,
+        "exchange_rate": 1.1
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.amount.to_string(), "-22.00");
+    assert_eq!(transaction.original_currency, Some(CurrencyCode::Eur));
+    assert_eq!(
+        transaction
+            .original_amount
+            .map(|amount| amount.to_string())
+            .as_deref(),
+        Some("-20.00")
+    );
+    assert_eq!(transaction.exchange_rate.as_deref(), Some("1.1"));
+
+    for invalid in [
+        json!({ "amount": -25.0, "original_currency": "EUR", "original_amount": -20.0, "exchange_rate": 1.1 }),
+        json!({ "amount": -22.0, "original_currency": "EUR" }),
+        json!({ "amount": -22.0, "original_currency": "USD", "original_amount": -20.0, "exchange_rate": 1.1 }),
+    ] {
+        let mut request = request.clone();
+        request.as_object_mut().unwrap().remove("original_amount");
+        request.as_object_mut().unwrap().remove("exchange_rate");
+        for (key, value) in invalid.as_object().unwrap() {
+            request[key] = value.clone();
+        }
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+
+    let path = format!("/api/v1/transactions/{}", transaction.id);
+    let response =
+        put_authenticated(&server, &path, &auth.token, &json!({ "amount": -30.0 })).await;
+    assert_status(&response, 422);
+
+    let update = json!({
+        "amount": -33.0,
+        "original_currency": "EUR",
+        "original_amount": -30.0,
+        "exchange_rate": 1.1
+    });
+    let response = put_authenticated(&server, &path, &auth.token, &update).await;
+    assert_status(&response, 200);
+    let transaction: TransactionResponse = extract_json(response);
+    assert_eq!(transaction.amount.to_string(), "-33.00");
+    assert_eq!(
+        transaction
+            .original_amount
+            .map(|amount| amount.to_string())
+            .as_deref(),
+        Some("-30.00")
+    );
+}
+
+/// Test that amounts are accepted as strings or numbers and returned in the account currency.
+///
+/// Verifies that:
+/// - String amounts are accepted and stored exactly
+/// - Number amounts are returned as strings with two decimals for USD
+/// - Amounts and the projected balance on a JPY account are returned without decimals
+/// - Non-numeric amounts are rejected
+/// - Amounts with more than 17 integer digits or 2 decimal places are rejected with 422
+#[tokio::test]
+async fn test_transaction_amount_formats() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("money_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "USD Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Lunch",
+        "amount": "-75.5",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["amount"], json!("-75.50"));
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Coffee",
+        "amount": -0.1,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["amount"], json!("-0.10"));
+
+    let request = json!({
+        "name": "Yen Wallet",
+        "account_type": "CASH",
+        "currency": "JPY"
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let yen_account: AccountResponse = extract_json(response);
+
+    let request = json!({
+        "account_id": yen_account.id,
+        "title": "Ramen",
+        "amount": "-1500",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["amount"], json!("-1500"));
+    assert_eq!(body["projected_balance"], json!("-1500"));
+
+    let path = format!("/api/v1/transactions/{}", body["id"].as_str().unwrap());
+    let response = get_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = extract_json(response);
+    assert_eq!(body["amount"], json!("-1500"));
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Invalid",
+        "amount": "ten dollars",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+    assert!(response.status_code().is_client_error());
+
+    for amount in [
+        json!("1e30000"),
+        json!("99999999999999999999"),
+        json!("-123456789012345678"),
+        json!("0.001"),
+        json!("1e-5"),
+        json!(1e300),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "Out of range",
+            "amount": amount,
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_eq!(response.status_code(), 422, "{} was accepted", amount);
+    }
+
+    // The largest amount that fits, and trailing zeros past the decimal places
+    for (amount, expected) in [
+        ("-99999999999999999.99", "-99999999999999999.99"),
+        ("12.3400", "12.34"),
+    ] {
+        let request = json!({
+            "account_id": account.id,
+            "title": "In range",
+            "amount": amount,
+            "date": Utc::now().to_rfc3339()
+        });
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 201);
+        let body: serde_json::Value = extract_json(response);
+        assert_eq!(body["amount"], json!(expected));
+    }
+}
+
+// ============================================================================
+// Display Locale Tests
+// ============================================================================
+
+/// Test that `?locale=` adds an amount formatted for the locale.
+///
+/// Verifies that:
+/// - `en-US` groups with commas and uses a decimal point
+/// - `de-DE` groups with dots and uses a decimal comma
+/// - The canonical amount is unchanged and no display string is returned without a locale
+#[tokio::test]
+async fn test_transaction_amount_display_locale() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("locale_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions?locale=en-US",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "title": "Laptop",
+            "amount": "-1234567.5",
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let created: TransactionResponse = extract_json(response);
+    assert_eq!(created.amount.to_string(), "-1234567.50");
+    assert_eq!(created.amount_display.as_deref(), Some("-1,234,567.50"));
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}?locale=de-DE", created.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.amount.to_string(), "-1234567.50");
+    assert_eq!(fetched.amount_display.as_deref(), Some("-1.234.567,50"));
+
+    let response =
+        get_authenticated(&server, "/api/v1/transactions?locale=de_de", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(
+        transactions[0].amount_display.as_deref(),
+        Some("-1.234.567,50")
+    );
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", created.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let body: serde_json::Value = extract_json(response);
+    assert!(body.get("amount_display").is_none());
+
+    let response =
+        get_authenticated(&server, "/api/v1/transactions?locale=xx-XX", &auth.token).await;
+    assert_status(&response, 400);
+}
+
+/// Test that amounts in a zero-decimal currency are displayed without decimals.
+#[tokio::test]
+async fn test_transaction_amount_display_zero_decimal_currency() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("locale_jpy_{}", timestamp)).await;
+
+    let request = json!({
+        "name": "Yen Wallet",
+        "account_type": "CASH",
+        "currency": "JPY"
+    });
+    let response = post_authenticated(&server, "/api/v1/accounts", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions?locale=de-DE",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "title": "Rail pass",
+            "amount": "-29650",
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let created: serde_json::Value = extract_json(response);
+    assert_eq!(created["amount"], json!("-29650"));
+    assert_eq!(created["amount_display"], json!("-29.650"));
+
+    let response = get_authenticated(
+        &server,
+        &format!(
+            "/api/v1/transactions/{}?locale=ja-JP",
+            created["id"].as_str().unwrap()
+        ),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.amount_display.as_deref(), Some("-29,650"));
+}
+
+// ============================================================================
+// Category Suggestion Tests
+// ============================================================================
+
+/// Create a transaction with the given title and optional category.
+async fn create_titled_transaction(
+    server: &TestServer,
+    token: &str,
+    account_id: uuid::Uuid,
+    title: &str,
+    category_id: Option<uuid::Uuid>,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "category_id": category_id,
+        "title": title,
+        "amount": -10.0,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test suggesting a category from past transactions with the same title.
+///
+/// Verifies that:
+/// - Titles match ignoring case and whitespace
+/// - The most used category is suggested with its share as confidence
+/// - Uncategorized transactions and other users' transactions are ignored
+/// - An unknown title yields no suggestion
+#[tokio::test]
+async fn test_suggest_category() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("suggest_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let groceries = create_test_category(&server, &auth.token, "Groceries").await;
+    let dining = create_test_category(&server, &auth.token, "Dining").await;
+
+    for (title, category_id) in [
+        ("Whole Foods", Some(groceries.id)),
+        ("whole  foods ", Some(groceries.id)),
+        ("WHOLE FOODS", Some(dining.id)),
+        ("Whole Foods", None),
+        ("Whole Foods Market", Some(dining.id)),
+    ] {
+        create_titled_transaction(&server, &auth.token, account.id, title, category_id).await;
+    }
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/suggest-category?title=Whole%20Foods",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let suggestion: CategorySuggestionResponse = extract_json(response);
+    assert_eq!(suggestion.category_id, Some(groceries.id));
+    assert_eq!(suggestion.matching_transactions, 3);
+    assert!((suggestion.confidence - 2.0 / 3.0).abs() < 1e-9);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/suggest-category?title=Unknown%20Shop",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let suggestion: CategorySuggestionResponse = extract_json(response);
+    assert_eq!(suggestion.category_id, None);
+    assert_eq!(suggestion.matching_transactions, 0);
+
+    let other = register_unique_test_user(&server, &format!("suggest_other_{}", timestamp)).await;
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/suggest-category?title=Whole%20Foods",
+        &other.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let suggestion: CategorySuggestionResponse = extract_json(response);
+    assert_eq!(suggestion.category_id, None);
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions/suggest-category?title=",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 422);
+}
+
+/// Test applying the suggested category to new uncategorized transactions.
+///
+/// Verifies that:
+/// - A confident suggestion is applied when auto-apply is enabled
+/// - An ambiguous suggestion is not applied
+/// - An explicit category is kept
+#[tokio::test]
+async fn test_auto_apply_suggested_category() {
+    let server =
+        create_test_server_with_config(|config| config.category_suggestion.auto_apply = true).await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("autocat_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let streaming = create_test_category(&server, &auth.token, "Streaming").await;
+    let shopping = create_test_category(&server, &auth.token, "Shopping").await;
+
+    for (title, category_id) in [
+        ("Netflix", streaming.id),
+        ("Netflix", streaming.id),
+        ("Amazon", shopping.id),
+        ("Amazon", streaming.id),
+    ] {
+        create_titled_transaction(&server, &auth.token, account.id, title, Some(category_id)).await;
+    }
+
+    let transaction =
+        create_titled_transaction(&server, &auth.token, account.id, "netflix", None).await;
+    assert_eq!(transaction.category_id, Some(streaming.id));
+
+    let transaction =
+        create_titled_transaction(&server, &auth.token, account.id, "Amazon", None).await;
+    assert_eq!(transaction.category_id, None);
+
+    let transaction = create_titled_transaction(
+        &server,
+        &auth.token,
+        account.id,
+        "Netflix",
+        Some(shopping.id),
+    )
+    .await;
+    assert_eq!(transaction.category_id, Some(shopping.id));
+}
+
+// ============================================================================
+// Merchant and Location Tests
+// ============================================================================
+
+/// Test storing, updating and filtering by merchant and location.
+///
+/// Verifies that:
+/// - Merchant and coordinates are stored on create and returned
+/// - They can be replaced on update
+/// - `?merchant=` matches case-insensitively and excludes other merchants
+#[tokio::test]
+async fn test_transaction_merchant_and_location() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("merchant_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "title": "Coffee",
+            "amount": "-4.50",
+            "date": Utc::now().to_rfc3339(),
+            "merchant": "Blue Bottle",
+            "latitude": 37.7764,
+            "longitude": -122.4231
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let coffee: TransactionResponse = extract_json(response);
+    assert_eq!(coffee.merchant.as_deref(), Some("Blue Bottle"));
+    assert_eq!(coffee.latitude, Some(37.7764));
+    assert_eq!(coffee.longitude, Some(-122.4231));
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "title": "Groceries",
+            "amount": "-30.00",
+            "date": Utc::now().to_rfc3339(),
+            "merchant": "Corner Market"
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let groceries: TransactionResponse = extract_json(response);
+    assert!(groceries.latitude.is_none());
+
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", coffee.id),
+        &auth.token,
+        &json!({ "latitude": 40.7128, "longitude": -74.006 }),
+    )
+    .await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.merchant.as_deref(), Some("Blue Bottle"));
+    assert_eq!(updated.latitude, Some(40.7128));
+    assert_eq!(updated.longitude, Some(-74.006));
+
+    let response = get_authenticated(
+        &server,
+        "/api/v1/transactions?merchant=blue%20bottle",
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].id, coffee.id);
+}
+
+/// Test that invalid coordinates are rejected with 422.
+#[tokio::test]
+async fn test_transaction_invalid_location() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("location_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    for location in [
+        json!({ "latitude": 91.0, "longitude": 0.0 }),
+        json!({ "latitude": 0.0, "longitude": -180.5 }),
+        json!({ "latitude": 45.0 }),
+    ] {
+        let mut request = json!({
+            "account_id": account.id,
+            "title": "Somewhere",
+            "amount": "-1.00",
+            "date": Utc::now().to_rfc3339()
+        });
+        request
+            .as_object_mut()
+            .unwrap()
+            .extend(location.as_object().unwrap().clone());
+
+        let response =
+            post_authenticated(&server, "/api/v1/transactions", &auth.token, &request).await;
+        assert_status(&response, 422);
+    }
+}
+
+// ============================================================================
+// Validation Warnings Tests
+// ============================================================================
+
+/// Test that suspicious but valid inputs are saved with warnings.
+///
+/// Verifies that:
+/// - A positive amount in a category only used for expenses is created with a warning
+/// - A transaction dated in the future is created with a warning
+/// - An ordinary expense in the category has no warnings
+#[tokio::test]
+async fn test_transaction_validation_warnings() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("warnings_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let category = create_test_category(&server, &auth.token, "Groceries").await;
+
+    let expense = json!({
+        "account_id": account.id,
+        "category_id": category.id,
+        "title": "Weekly shop",
+        "amount": "-45.00",
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &expense).await;
+    assert_status(&response, 201);
+    let created: TransactionResponse = extract_json(response);
+    assert!(created.warnings.is_empty());
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "category_id": category.id,
+            "title": "Groceries again",
+            "amount": "20.00",
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let positive: TransactionResponse = extract_json(response);
+    assert_eq!(positive.warnings.len(), 1);
+    assert!(positive.warnings[0].contains("only used for expenses"));
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "title": "Rent",
+            "amount": "-900.00",
+            "date": (Utc::now() + Duration::days(30)).to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let future: TransactionResponse = extract_json(response);
+    assert_eq!(future.warnings.len(), 1);
+    assert!(future.warnings[0].contains("future"));
+
+    // Both transactions were saved
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}", positive.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let fetched: TransactionResponse = extract_json(response);
+    assert_eq!(fetched.amount.to_string(), "20.00");
+    assert!(fetched.warnings.is_empty());
+}
+
+/// Test that `?strict=true` rejects transactions that raise warnings.
+///
+/// Verifies that:
+/// - A create raising a warning returns 422 and nothing is saved
+/// - An update raising a warning returns 422 and the transaction is unchanged
+/// - A strict request without warnings succeeds
+#[tokio::test]
+async fn test_transaction_strict_warnings() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+    let auth = register_unique_test_user(&server, &format!("strict_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+    let category = create_test_category(&server, &auth.token, "Dining").await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions?strict=true",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "category_id": category.id,
+            "title": "Dinner",
+            "amount": "-60.00",
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 201);
+    let dinner: TransactionResponse = extract_json(response);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions?strict=true",
+        &auth.token,
+        &json!({
+            "account_id": account.id,
+            "category_id": category.id,
+            "title": "Refund",
+            "amount": "15.00",
+            "date": Utc::now().to_rfc3339()
+        }),
+    )
+    .await;
+    assert_status(&response, 422);
+
+    let response = put_authenticated(
+        &server,
+        &format!("/api/v1/transactions/{}?strict=true", dinner.id),
+        &auth.token,
+        &json!({ "date": (Utc::now() + Duration::days(7)).to_rfc3339() }),
+    )
+    .await;
+    assert_status(&response, 422);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    assert_status(&response, 200);
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].date, dinner.date);
+}
+
+// ============================================================================
+// Concurrency Tests
+// ============================================================================
+
+/// Post all transaction requests concurrently and return their status codes.
+///
+/// Test responses are not `Send`, so the requests run as tasks on a local set.
+async fn post_transactions_concurrently(
+    server: &Arc<TestServer>,
+    token: &str,
+    requests: Vec<serde_json::Value>,
+) -> Vec<u16> {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let handles: Vec<_> = requests
+                .into_iter()
+                .map(|request| {
+                    let server = Arc::clone(server);
+                    let token = token.to_string();
+                    tokio::task::spawn_local(async move {
+                        post_authenticated(&server, "/api/v1/transactions", &token, &request)
+                            .await
+                            .status_code()
+                            .as_u16()
+                    })
+                })
+                .collect();
+
+            let mut statuses = Vec::new();
+            for handle in handles {
+                statuses.push(handle.await.expect("Request task panicked"));
+            }
+            statuses
+        })
+        .await
+}
+
+/// Test that parallel transaction writes to one account all land in the balance.
+///
+/// Verifies that:
+/// - Every concurrent create succeeds
+/// - The final balance equals the initial balance plus every amount
+#[tokio::test]
+async fn test_concurrent_transactions_balance_consistent() {
+    let server = Arc::new(create_test_server().await);
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("concurrentbal_{}", timestamp),
+        &format!("concurrentbal_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Concurrent Balance User",
+    )
+    .await;
+
+    let account_request = json!({
+        "name": "Busy Checking",
+        "account_type": "CHECKING",
+        "currency": "USD",
+        "initial_balance": 1000.0
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let requests = (0..20)
+        .map(|i| {
+            let amount = if i % 2 == 0 { -15.0 } else { 40.0 };
+            json!({
+                "account_id": account.id,
+                "title": format!("Parallel {}", i),
+                "amount": amount,
+                "date": Utc::now().to_rfc3339()
+            })
+        })
+        .collect();
+
+    let statuses = post_transactions_concurrently(&server, &auth.token, requests).await;
+    assert!(statuses.iter().all(|&status| status == 201));
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}", account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let account: AccountResponse = extract_json(response);
+    // 1000 + 10 * (-15) + 10 * 40
+    assert_eq!(account.balance, 1250.0);
+}
+
+/// Test that parallel withdrawals cannot jointly overdraw a strict account.
+///
+/// Verifies that:
+/// - Exactly as many withdrawals succeed as the balance covers
+/// - The remaining withdrawals are rejected with 422
+/// - The final balance never goes below the overdraft limit
+#[tokio::test]
+async fn test_concurrent_withdrawals_respect_overdraft_limit() {
+    let server = Arc::new(create_test_server().await);
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_test_user(
+        &server,
+        &format!("concurrentod_{}", timestamp),
+        &format!("concurrentod_{}@example.com", timestamp),
+        "SecurePass123!",
+        "Concurrent Overdraft User",
+    )
+    .await;
+
+    let account_request = json!({
+        "name": "Strict Savings",
+        "account_type": "SAVINGS",
+        "currency": "USD",
+        "initial_balance": 100.0,
+        "allow_overdraft": false,
+        "overdraft_limit": 0.0
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/accounts", &auth.token, &account_request).await;
+    assert_status(&response, 201);
+    let account: AccountResponse = extract_json(response);
+
+    let requests = (0..20)
+        .map(|i| {
+            json!({
+                "account_id": account.id,
+                "title": format!("Withdrawal {}", i),
+                "amount": -10.0,
+                "date": Utc::now().to_rfc3339()
+            })
+        })
+        .collect();
+
+    let statuses = post_transactions_concurrently(&server, &auth.token, requests).await;
+    let accepted = statuses.iter().filter(|&&status| status == 201).count();
+    let rejected = statuses.iter().filter(|&&status| status == 422).count();
+    assert_eq!(rejected, statuses.len() - accepted);
+    assert_eq!(accepted, 10);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}", account.id),
+        &auth.token,
+    )
+    .await;
+    assert_status(&response, 200);
+    let account: AccountResponse = extract_json(response);
+    assert_eq!(account.balance, 0.0);
+}
+
+// ============================================================================
+// Idempotency Tests
+// ============================================================================
+
+/// Test that retrying a transaction create with the same idempotency key does not duplicate it.
+///
+/// Verifies that:
+/// - A retry with the same `Idempotency-Key` and body replays the original response
+/// - Only one transaction is created
+/// - Reusing the key for a different body fails with 409 Conflict
+/// - A different key creates a new transaction
+#[tokio::test]
+async fn test_create_transaction_idempotency_key() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("txidem_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Coffee",
+        "amount": -4.5,
+        "date": Utc::now().to_rfc3339()
+    });
+    let create = |key: &'static str, request: &serde_json::Value| {
+        server
+            .post("/api/v1/transactions")
+            .add_header("Authorization", format!("Bearer {}", auth.token))
+            .add_header("Idempotency-Key", key)
+            .json(request)
+    };
+
+    let response = create("coffee-1", &request).await;
+    assert_status(&response, 201);
+    let original: TransactionResponse = extract_json(response);
+
+    // Retry, e.g. after a timeout
+    let response = create("coffee-1", &request).await;
+    assert_status(&response, 201);
+    let retried: TransactionResponse = extract_json(response);
+    assert_eq!(retried.id, original.id);
+    assert_eq!(retried.amount, original.amount);
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}/transactions", account.id),
+        &auth.token,
+    )
+    .await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+
+    // Same key, different request
+    let mut changed = request.clone();
+    changed["amount"] = json!(-5.0);
+    let response = create("coffee-1", &changed).await;
+    assert_status(&response, 409);
+    assert!(response.text().contains("different request"));
+
+    // A new key is a new request
+    let response = create("coffee-2", &request).await;
+    assert_status(&response, 201);
+    let other: TransactionResponse = extract_json(response);
+    assert_ne!(other.id, original.id);
+}
+
+/// Test that concurrent requests with the same idempotency key create one transaction.
+///
+/// Verifies that:
+/// - Every request either succeeds with the same transaction or fails with 409
+/// - Exactly one transaction is created
+#[tokio::test]
+async fn test_concurrent_requests_same_idempotency_key() {
+    let server = Arc::new(create_test_server().await);
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("txidemconc_{}", timestamp)).await;
+    let account = create_test_account(&server, &auth.token, "Checking").await;
+
+    let request = json!({
+        "account_id": account.id,
+        "title": "Rent",
+        "amount": -1200.0,
+        "date": Utc::now().to_rfc3339()
+    });
+
+    let local = tokio::task::LocalSet::new();
+    let responses = local
+        .run_until(async {
+            let handles: Vec<_> = (0..10)
+                .map(|_| {
+                    let server = Arc::clone(&server);
+                    let token = auth.token.clone();
+                    let request = request.clone();
+                    tokio::task::spawn_local(async move {
+                        let response = server
+                            .post("/api/v1/transactions")
+                            .add_header("Authorization", format!("Bearer {}", token))
+                            .add_header("Idempotency-Key", "rent-october")
+                            .json(&request)
+                            .await;
+                        let status = response.status_code().as_u16();
+                        let id = (status == 201)
+                            .then(|| extract_json::<TransactionResponse>(response).id);
+                        (status, id)
+                    })
+                })
+                .collect();
+
+            let mut responses = Vec::new();
+            for handle in handles {
+                responses.push(handle.await.expect("Request task panicked"));
+            }
+            responses
+        })
+        .await;
+
+    let ids: Vec<_> = responses.iter().filter_map(|(_, id)| *id).collect();
+    assert!(!ids.is_empty());
+    assert!(ids.iter().all(|id| *id == ids[0]));
+    assert!(
+        responses
+            .iter()
+            .all(|(status, _)| *status == 201 || *status == 409)
+    );
+
+    let response = get_authenticated(
+        &server,
+        &format!("/api/v1/accounts/{}/transactions", account.id),
+        &auth.token,
+    )
+    .await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert_eq!(transactions.len(), 1);
+}
@@ -0,0 +1,422 @@
+//! Integration tests for transfers between accounts.
+//!
+//! This module tests transfers including:
+//! - POST /api/v1/transactions/transfer - Create a transfer
+//! - Conversion between account currencies
+//! - Deleting either leg of a transfer
+//! - Refusing amount, account and status changes to one leg
+//! - Retrying a transfer with an Idempotency-Key
+//! - Leaving transfers out of the dashboard's spending breakdown
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::{Duration, Utc};
+use master_of_coin_backend::{
+    models::{AccountResponse, TransactionResponse, TransferResponse},
+    services::analytics_service,
+    types::CurrencyCode,
+};
+use serde_json::json;
+
+/// Create an account with the given currency, rejecting overdrafts
+async fn create_strict_account(
+    server: &TestServer,
+    token: &str,
+    name: &str,
+    currency: &str,
+) -> AccountResponse {
+    let request = json!({
+        "name": name,
+        "account_type": "SAVINGS",
+        "currency": currency,
+        "allow_overdraft": false
+    });
+    let response = post_authenticated(server, "/api/v1/accounts", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Test transferring between accounts in the same currency.
+///
+/// Verifies that:
+/// - The transfer returns 201 with a negative leg on the source account and a
+///   positive leg on the destination account, sharing the transfer ID
+/// - Both account balances reflect the transfer
+#[tokio::test]
+async fn test_create_transfer() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transfer_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 250.5,
+        "date": Utc::now().to_rfc3339(),
+        "notes": "Monthly savings"
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let transfer: TransferResponse = extract_json(response);
+    let (from, to) = (&transfer.from_transaction, &transfer.to_transaction);
+    assert_eq!(from.account_id, checking.id);
+    assert_eq!(from.amount.to_string(), "-250.50");
+    assert_eq!(from.title, "Transfer to Savings");
+    assert_eq!(to.account_id, savings.id);
+    assert_eq!(to.amount.to_string(), "250.50");
+    assert_eq!(to.title, "Transfer from Checking");
+    assert_eq!(to.notes.as_deref(), Some("Monthly savings"));
+    assert_eq!(from.transfer_id, Some(transfer.transfer_id));
+    assert_eq!(to.transfer_id, Some(transfer.transfer_id));
+    assert!(to.exchange_rate.is_none());
+
+    let path = format!("/api/v1/accounts/{}", checking.id);
+    let account: AccountResponse =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert_eq!(account.balance, -250.5);
+
+    let path = format!("/api/v1/accounts/{}", savings.id);
+    let account: AccountResponse =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert_eq!(account.balance, 250.5);
+}
+
+/// Test transferring between accounts in different currencies.
+///
+/// Verifies that the incoming leg is converted at the given rate, rounded to
+/// the destination currency, and records the original amount and rate.
+#[tokio::test]
+async fn test_create_transfer_between_currencies() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferfx_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let euros = create_strict_account(&server, &auth.token, "Euros", "EUR").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": euros.id,
+        "amount": 100.01,
+        "date": Utc::now().to_rfc3339(),
+        "exchange_rate": 0.9
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let transfer: TransferResponse = extract_json(response);
+    let to = &transfer.to_transaction;
+    assert_eq!(transfer.from_transaction.amount.to_string(), "-100.01");
+    assert_eq!(to.amount.to_string(), "90.01");
+    assert_eq!(to.original_currency, Some(CurrencyCode::Usd));
+    assert_eq!(
+        to.original_amount.as_ref().map(|a| a.to_string()),
+        Some("100.01".to_string())
+    );
+    assert_eq!(to.exchange_rate.as_deref(), Some("0.9"));
+}
+
+/// Test that invalid transfers are rejected without creating anything.
+///
+/// Verifies that:
+/// - A transfer to the same account, a non-positive amount, or an exchange
+///   rate between accounts in the same currency is rejected with 422
+/// - Another user's account is rejected with 403
+/// - A transfer beyond the source account's overdraft limit is rejected with 422
+#[tokio::test]
+async fn test_create_transfer_rejected() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferbad_{}", timestamp)).await;
+    let other = register_unique_test_user(&server, &format!("transferother_{}", timestamp)).await;
+    let strict = create_strict_account(&server, &auth.token, "Savings", "USD").await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let other_account = create_test_account(&server, &other.token, "Checking").await;
+
+    let valid = json!({
+        "from_account_id": strict.id,
+        "to_account_id": checking.id,
+        "amount": 10,
+        "date": Utc::now().to_rfc3339()
+    });
+    let invalid = [
+        ("to_account_id", json!(strict.id)),
+        ("amount", json!(0)),
+        ("amount", json!(-10)),
+        ("exchange_rate", json!(1.1)),
+    ];
+    for (field, value) in invalid {
+        let mut request = valid.clone();
+        request[field] = value;
+        let response = post_authenticated(
+            &server,
+            "/api/v1/transactions/transfer",
+            &auth.token,
+            &request,
+        )
+        .await;
+        assert_status(&response, 422);
+    }
+
+    let mut request = valid.clone();
+    request["to_account_id"] = json!(other_account.id);
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 403);
+
+    // The strict account is empty and does not allow overdrafts
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &valid,
+    )
+    .await;
+    assert_status(&response, 422);
+
+    let response = get_authenticated(&server, "/api/v1/transactions", &auth.token).await;
+    let transactions: Vec<TransactionResponse> = extract_json(response);
+    assert!(transactions.is_empty());
+}
+
+/// Test that deleting either leg of a transfer deletes both, and neither
+/// account balance counts it anymore.
+#[tokio::test]
+async fn test_delete_transfer_leg() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferdel_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 40,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &request,
+    )
+    .await;
+    assert_status(&response, 201);
+    let transfer: TransferResponse = extract_json(response);
+
+    let path = format!("/api/v1/transactions/{}", transfer.to_transaction.id);
+    let response = delete_authenticated(&server, &path, &auth.token).await;
+    assert_status(&response, 204);
+
+    for leg in [&transfer.from_transaction, &transfer.to_transaction] {
+        let path = format!("/api/v1/transactions/{}", leg.id);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        assert_status(&response, 404);
+    }
+
+    for account_id in [checking.id, savings.id] {
+        let path = format!("/api/v1/accounts/{}", account_id);
+        let response = get_authenticated(&server, &path, &auth.token).await;
+        let account: AccountResponse = extract_json(response);
+        assert_eq!(account.balance, 0.0, "The transfer should no longer count");
+        assert_eq!(account.available_balance, 0.0);
+    }
+}
+
+/// Create a transfer of 40 from one account to another
+async fn create_test_transfer(
+    server: &TestServer,
+    token: &str,
+    from: &AccountResponse,
+    to: &AccountResponse,
+) -> TransferResponse {
+    let request = json!({
+        "from_account_id": from.id,
+        "to_account_id": to.id,
+        "amount": 40,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response =
+        post_authenticated(server, "/api/v1/transactions/transfer", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Assert that the accounts' balances still reflect a transfer of 40
+async fn assert_transfer_balances(
+    server: &TestServer,
+    token: &str,
+    from: &AccountResponse,
+    to: &AccountResponse,
+) {
+    for (account, balance) in [(from, -40.0), (to, 40.0)] {
+        let path = format!("/api/v1/accounts/{}", account.id);
+        let account: AccountResponse = extract_json(get_authenticated(server, &path, token).await);
+        assert_eq!(account.balance, balance);
+    }
+}
+
+/// Test that the amount and account of one transfer leg can't be changed.
+///
+/// Verifies that:
+/// - Changing a leg's amount or moving it to another account returns 409
+/// - Other edits, and resending the unchanged amount and account, succeed
+/// - Both account balances still reflect the whole transfer
+#[tokio::test]
+async fn test_update_transfer_leg() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferupd_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let brokerage = create_test_account(&server, &auth.token, "Brokerage").await;
+    let transfer = create_test_transfer(&server, &auth.token, &checking, &savings).await;
+
+    let path = format!("/api/v1/transactions/{}", transfer.to_transaction.id);
+    for changes in [json!({"amount": 55}), json!({"account_id": brokerage.id})] {
+        let response = put_authenticated(&server, &path, &auth.token, &changes).await;
+        assert_status(&response, 409);
+    }
+
+    let changes = json!({"title": "Rainy day fund", "amount": 40, "account_id": savings.id});
+    let response = put_authenticated(&server, &path, &auth.token, &changes).await;
+    assert_status(&response, 200);
+    let updated: TransactionResponse = extract_json(response);
+    assert_eq!(updated.title, "Rainy day fund");
+
+    assert_transfer_balances(&server, &auth.token, &checking, &savings).await;
+}
+
+/// Test that one transfer leg can't be voided, leaving the other to count alone.
+#[tokio::test]
+async fn test_void_transfer_leg() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transfervoid_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+    let transfer = create_test_transfer(&server, &auth.token, &checking, &savings).await;
+
+    for leg in [&transfer.from_transaction, &transfer.to_transaction] {
+        let path = format!("/api/v1/transactions/{}/void", leg.id);
+        let response = post_authenticated(&server, &path, &auth.token, &json!({})).await;
+        assert_status(&response, 409);
+    }
+
+    assert_transfer_balances(&server, &auth.token, &checking, &savings).await;
+}
+
+/// Test that retrying a transfer with the same idempotency key does not move money twice.
+///
+/// Verifies that the retry replays the original transfer and the balances only
+/// reflect it once.
+#[tokio::test]
+async fn test_create_transfer_idempotency_key() {
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferidem_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let request = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 75,
+        "date": Utc::now().to_rfc3339()
+    });
+    let mut transfer_ids = Vec::new();
+    for _ in 0..2 {
+        let response = server
+            .post("/api/v1/transactions/transfer")
+            .add_header("Authorization", format!("Bearer {}", auth.token))
+            .add_header("Idempotency-Key", "save-75")
+            .json(&request)
+            .await;
+        assert_status(&response, 201);
+        let transfer: TransferResponse = extract_json(response);
+        transfer_ids.push(transfer.transfer_id);
+    }
+    assert_eq!(transfer_ids[0], transfer_ids[1]);
+
+    let path = format!("/api/v1/accounts/{}", savings.id);
+    let account: AccountResponse =
+        extract_json(get_authenticated(&server, &path, &auth.token).await);
+    assert_eq!(account.balance, 75.0);
+}
+
+/// Test that transfers are left out of the spending breakdown.
+#[tokio::test]
+async fn test_transfers_not_counted_as_spending() {
+    let (server, state) = create_test_server_with_state().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("transferdash_{}", timestamp)).await;
+    let checking = create_test_account(&server, &auth.token, "Checking").await;
+    let savings = create_test_account(&server, &auth.token, "Savings").await;
+
+    let expense = json!({
+        "account_id": checking.id,
+        "title": "Groceries",
+        "amount": -30,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(&server, "/api/v1/transactions", &auth.token, &expense).await;
+    assert_status(&response, 201);
+
+    let transfer = json!({
+        "from_account_id": checking.id,
+        "to_account_id": savings.id,
+        "amount": 500,
+        "date": Utc::now().to_rfc3339()
+    });
+    let response = post_authenticated(
+        &server,
+        "/api/v1/transactions/transfer",
+        &auth.token,
+        &transfer,
+    )
+    .await;
+    assert_status(&response, 201);
+
+    let now = Utc::now();
+    let breakdown = analytics_service::get_category_breakdown(
+        &state.db,
+        auth.user.id,
+        now - Duration::days(1),
+        now + Duration::days(1),
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(breakdown.len(), 1);
+    assert_eq!(breakdown[0].total, "30.00");
+    assert_eq!(breakdown[0].percentage, 100.0);
+}
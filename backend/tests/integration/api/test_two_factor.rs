@@ -0,0 +1,341 @@
+//! Integration tests for two-factor authentication.
+//!
+//! This module tests TOTP two-factor authentication including:
+//! - POST /api/v1/auth/2fa/setup - Start setup
+//! - POST /api/v1/auth/2fa/verify - Confirm a code and enable 2FA
+//! - The two-factor challenge and code check at login
+//! - Logging in with single-use recovery codes
+//! - Setup requiring the current password and a session rather than an API key
+//! - Codes from the authenticator app being usable only once
+
+use crate::common::*;
+use axum_test::TestServer;
+use chrono::Utc;
+use master_of_coin_backend::models::{
+    ApiKeyScopes, AuthResponse, CreateApiKeyRequest, CreateApiKeyResponse, LoginRequest,
+    ScopePermission, TwoFactorSetupResponse,
+};
+use serde_json::{Value, json};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const PASSWORD: &str = "SecurePass123!";
+
+/// Set the key TOTP secrets are encrypted with
+fn set_encryption_key() {
+    unsafe {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "aO42n1ptrggkyZKYtsFS2wwsu8+Y9mFhNQ4oAide1Ko=",
+        );
+    }
+}
+
+/// Current code for a base32 TOTP secret, as an authenticator app shows it
+fn current_code(secret: &str) -> String {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    TOTP::new(Algorithm::SHA1, 6, 1, 30, bytes, None, String::new())
+        .unwrap()
+        .generate_current()
+        .unwrap()
+}
+
+/// Body of a setup request with the user's password
+fn setup_request() -> Value {
+    json!({ "current_password": PASSWORD })
+}
+
+/// Body of a verify request with the user's password and `code`
+fn verify_request(code: &str) -> Value {
+    json!({ "current_password": PASSWORD, "code": code })
+}
+
+/// Code for the step after the current one, which is accepted for clock drift
+///
+/// It's always later than a code used just before, so it hasn't been used yet.
+fn next_code(secret: &str) -> String {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    TOTP::new(Algorithm::SHA1, 6, 1, 30, bytes, None, String::new())
+        .unwrap()
+        .generate(Utc::now().timestamp() as u64 + 30)
+}
+
+/// Log in with an optional two-factor code
+async fn login(server: &TestServer, email: &str, code: Option<&str>) -> axum_test::TestResponse {
+    let request = LoginRequest {
+        email: email.to_string(),
+        password: PASSWORD.to_string(),
+        two_factor_code: code.map(str::to_string),
+    };
+    server.post("/api/v1/auth/login").json(&request).await
+}
+
+/// Register a user and enable 2FA for them, returning their email and setup
+async fn register_with_two_factor(
+    server: &TestServer,
+    suffix: &str,
+) -> (String, TwoFactorSetupResponse) {
+    let email = format!("{}@example.com", suffix);
+    let auth = register_test_user(server, suffix, &email, PASSWORD, "Two Factor User").await;
+
+    let response = post_authenticated(
+        server,
+        "/api/v1/auth/2fa/setup",
+        &auth.token,
+        &setup_request(),
+    )
+    .await;
+    assert_status(&response, 200);
+    let setup: TwoFactorSetupResponse = extract_json(response);
+
+    let request = verify_request(&current_code(&setup.secret));
+    let response =
+        post_authenticated(server, "/api/v1/auth/2fa/verify", &auth.token, &request).await;
+    assert_status(&response, 204);
+
+    (email, setup)
+}
+
+/// Test setting up and verifying two-factor authentication.
+///
+/// Verifies that:
+/// - Setup returns an otpauth URI for the secret and ten distinct recovery codes
+/// - Login doesn't ask for a code before setup is verified
+/// - A wrong verification code fails with 401
+/// - A correct code enables 2FA, and setting up again fails with 409
+#[tokio::test]
+async fn test_two_factor_setup_and_verify() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let suffix = format!("2fa_setup_{}", timestamp);
+    let email = format!("{}@example.com", suffix);
+    let auth = register_test_user(&server, &suffix, &email, PASSWORD, "Two Factor User").await;
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/auth/2fa/setup",
+        &auth.token,
+        &setup_request(),
+    )
+    .await;
+    assert_status(&response, 200);
+    let setup: TwoFactorSetupResponse = extract_json(response);
+    assert!(setup.otpauth_uri.starts_with("otpauth://totp/"));
+    assert!(
+        setup
+            .otpauth_uri
+            .contains(&format!("secret={}", setup.secret))
+    );
+    let mut codes = setup.recovery_codes.clone();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), 10);
+
+    let response = login(&server, &email, None).await;
+    assert_status(&response, 200);
+    let _: AuthResponse = extract_json(response);
+
+    let request = verify_request("000000");
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/verify", &auth.token, &request).await;
+    assert_status(&response, 401);
+
+    let request = verify_request(&current_code(&setup.secret));
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/verify", &auth.token, &request).await;
+    assert_status(&response, 204);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/auth/2fa/setup",
+        &auth.token,
+        &setup_request(),
+    )
+    .await;
+    assert_status(&response, 409);
+}
+
+/// Test that 2FA can't be verified before setup is started.
+#[tokio::test]
+async fn test_two_factor_verify_without_setup() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let suffix = format!("2fa_nosetup_{}", timestamp);
+    let email = format!("{}@example.com", suffix);
+    let auth = register_test_user(&server, &suffix, &email, PASSWORD, "Two Factor User").await;
+
+    let request = verify_request("123456");
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/verify", &auth.token, &request).await;
+    assert_status(&response, 400);
+}
+
+/// Test login for a user with 2FA enabled.
+///
+/// Verifies that:
+/// - A correct password without a code returns a `requires_2fa` challenge and no tokens
+/// - A wrong code fails with 401
+/// - A code from the authenticator app that wasn't used to enable 2FA logs in
+#[tokio::test]
+async fn test_login_requires_two_factor_code() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let (email, setup) =
+        register_with_two_factor(&server, &format!("2fa_login_{}", timestamp)).await;
+
+    let response = login(&server, &email, None).await;
+    assert_status(&response, 200);
+    let challenge: Value = extract_json(response);
+    assert_eq!(challenge, json!({ "requires_2fa": true }));
+
+    let response = login(&server, &email, Some("000000")).await;
+    assert_status(&response, 401);
+
+    let response = login(&server, &email, Some(&next_code(&setup.secret))).await;
+    assert_status(&response, 200);
+    let auth: AuthResponse = extract_json(response);
+    assert_eq!(auth.user.email, email);
+}
+
+/// Test logging in with recovery codes.
+///
+/// Verifies that:
+/// - A recovery code logs in once, whatever its case or surrounding spaces
+/// - Another user's recovery code or an unknown code fails with 401
+#[tokio::test]
+async fn test_login_with_recovery_code() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let (email, setup) =
+        register_with_two_factor(&server, &format!("2fa_recovery_{}", timestamp)).await;
+    let (_, other_setup) =
+        register_with_two_factor(&server, &format!("2fa_recovery_other_{}", timestamp)).await;
+    let code = &setup.recovery_codes[0];
+
+    let response = login(&server, &email, Some(&format!(" {} ", code.to_uppercase()))).await;
+    assert_status(&response, 200);
+    let _: AuthResponse = extract_json(response);
+
+    let response = login(&server, &email, Some(code)).await;
+    assert_status(&response, 401);
+
+    let response = login(&server, &email, Some(&other_setup.recovery_codes[0])).await;
+    assert_status(&response, 401);
+    let response = login(&server, &email, Some("aaaaa-bbbbb")).await;
+    assert_status(&response, 401);
+
+    let response = login(&server, &email, Some(&setup.recovery_codes[1])).await;
+    assert_status(&response, 200);
+}
+
+/// Test that setup and verification require the current password.
+#[tokio::test]
+async fn test_two_factor_requires_current_password() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let suffix = format!("2fa_password_{}", timestamp);
+    let email = format!("{}@example.com", suffix);
+    let auth = register_test_user(&server, &suffix, &email, PASSWORD, "Two Factor User").await;
+
+    let request = json!({ "current_password": "WrongPass123!" });
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/setup", &auth.token, &request).await;
+    assert_status(&response, 401);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/auth/2fa/setup",
+        &auth.token,
+        &setup_request(),
+    )
+    .await;
+    assert_status(&response, 200);
+    let setup: TwoFactorSetupResponse = extract_json(response);
+
+    let request = json!({
+        "current_password": "WrongPass123!",
+        "code": current_code(&setup.secret),
+    });
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/verify", &auth.token, &request).await;
+    assert_status(&response, 401);
+
+    // Login still doesn't ask for a code
+    let response = login(&server, &email, None).await;
+    assert_status(&response, 200);
+    let _: AuthResponse = extract_json(response);
+}
+
+/// Test that API keys can't set up or verify 2FA, even with the password.
+#[tokio::test]
+async fn test_two_factor_refused_for_api_keys() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let auth = register_unique_test_user(&server, &format!("2fa_api_key_{}", timestamp)).await;
+    let request = CreateApiKeyRequest {
+        name: "Read Key".to_string(),
+        scopes: ApiKeyScopes {
+            transactions: vec![ScopePermission::Read],
+            accounts: vec![],
+            budgets: vec![],
+            categories: vec![],
+            people: vec![],
+        },
+        expires_in_days: None,
+        requests_per_minute: None,
+    };
+    let response = post_authenticated(&server, "/api/v1/api-keys", &auth.token, &request).await;
+    assert_status(&response, 201);
+    let api_key: CreateApiKeyResponse = extract_json(response);
+
+    let response = post_authenticated(
+        &server,
+        "/api/v1/auth/2fa/setup",
+        &api_key.key,
+        &setup_request(),
+    )
+    .await;
+    assert_status(&response, 403);
+
+    let request = verify_request("123456");
+    let response =
+        post_authenticated(&server, "/api/v1/auth/2fa/verify", &api_key.key, &request).await;
+    assert_status(&response, 403);
+}
+
+/// Test that a code from the authenticator app can't be replayed.
+///
+/// Verifies that:
+/// - A code that logged in can't log in again
+/// - Codes from earlier time steps are refused afterwards, though still current
+#[tokio::test]
+async fn test_two_factor_code_cannot_be_reused() {
+    set_encryption_key();
+    let server = create_test_server().await;
+    let timestamp = Utc::now().timestamp_nanos_opt().unwrap();
+
+    let (email, setup) =
+        register_with_two_factor(&server, &format!("2fa_replay_{}", timestamp)).await;
+
+    let next_code = next_code(&setup.secret);
+    let response = login(&server, &email, Some(&next_code)).await;
+    assert_status(&response, 200);
+    let _: AuthResponse = extract_json(response);
+
+    let response = login(&server, &email, Some(&next_code)).await;
+    assert_status(&response, 401);
+
+    let response = login(&server, &email, Some(&current_code(&setup.secret))).await;
+    assert_status(&response, 401);
+}
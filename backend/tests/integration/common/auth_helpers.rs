@@ -58,6 +58,7 @@ pub fn create_test_jwt_config() -> JwtConfig {
     JwtConfig {
         secret: jwt_secret,
         expiration_hours: 24,
+        refresh_expiration_days: 30,
     }
 }
 
@@ -126,6 +127,7 @@ pub async fn register_test_user(
         email: email.to_string(),
         password: password.to_string(),
         name: name.to_string(),
+        preferences: None,
     };
 
     let response = server.post("/api/v1/auth/register").json(&request).await;
@@ -170,6 +172,7 @@ pub async fn login_test_user(server: &TestServer, email: &str, password: &str) -
     let request = LoginRequest {
         email: email.to_string(),
         password: password.to_string(),
+        two_factor_code: None,
     };
 
     let response = server.post("/api/v1/auth/login").json(&request).await;
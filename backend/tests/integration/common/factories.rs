@@ -16,7 +16,7 @@ use master_of_coin_backend::{
         user::{NewUser, User},
     },
     schema::{accounts, categories, people, transactions, users},
-    types::{AccountType, CurrencyCode},
+    types::{AccountType, CurrencyCode, TransactionStatus},
 };
 use std::str::FromStr;
 use uuid::Uuid;
@@ -181,6 +181,11 @@ impl AccountFactory {
             account_type: self.account_type,
             currency: self.currency,
             notes: self.notes,
+            allow_overdraft: true,
+            overdraft_limit: None,
+            credit_limit: None,
+            interest_rate: None,
+            original_principal: None,
         };
 
         diesel::insert_into(accounts::table)
@@ -450,6 +455,17 @@ impl TransactionFactory {
             amount: self.amount,
             date: self.date,
             notes: self.notes,
+            external_id: None,
+            status: TransactionStatus::Posted,
+            original_currency: None,
+            original_amount: None,
+            exchange_rate: None,
+            merchant: None,
+            latitude: None,
+            longitude: None,
+            transfer_id: None,
+            reimbursable: false,
+            tags: Vec::new(),
         };
 
         diesel::insert_into(transactions::table)
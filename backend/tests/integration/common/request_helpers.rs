@@ -3,9 +3,15 @@
 //! This module provides convenient wrapper functions for making HTTP requests
 //! with proper headers, authentication, and JSON serialization/deserialization.
 
+use std::str::FromStr;
+
 use axum_test::{TestResponse, TestServer};
+use bigdecimal::BigDecimal;
 use http::HeaderValue;
+use master_of_coin_backend::models::TransactionResponse;
 use serde::{Serialize, de::DeserializeOwned};
+use serde_json::json;
+use uuid::Uuid;
 
 /// Makes an authenticated GET request to the specified path.
 ///
@@ -386,3 +392,65 @@ pub fn assert_error(response: &TestResponse) {
         );
     }
 }
+
+/// Creates a transaction on an account and returns it.
+///
+/// # Arguments
+///
+/// * `server` - Reference to the test server
+/// * `token` - JWT authentication token of the account's owner
+/// * `account_id` - Account the transaction is booked on
+/// * `amount` - Decimal amount, negative for expenses (e.g., "-25.50")
+/// * `date` - RFC 3339 timestamp of the transaction
+///
+/// # Panics
+///
+/// Panics if the transaction is not created with 201 Created
+///
+/// # Example
+///
+/// ```no_run
+/// use integration::common::request_helpers::create_transaction;
+///
+/// #[tokio::test]
+/// async fn test_balance() {
+///     let server = /* create test server */;
+///     let transaction =
+///         create_transaction(&server, token, account_id, "-25.50", "2026-01-03T12:00:00Z").await;
+///     assert_eq!(transaction.amount.to_string(), "-25.50");
+/// }
+/// ```
+pub async fn create_transaction(
+    server: &TestServer,
+    token: &str,
+    account_id: Uuid,
+    amount: &str,
+    date: &str,
+) -> TransactionResponse {
+    let request = json!({
+        "account_id": account_id,
+        "title": "Test transaction",
+        "amount": amount,
+        "date": date
+    });
+    let response = post_authenticated(server, "/api/v1/transactions", token, &request).await;
+    assert_status(&response, 201);
+    extract_json(response)
+}
+
+/// Parses a decimal literal, for comparing amounts exactly.
+///
+/// # Panics
+///
+/// Panics if `value` is not a decimal number
+///
+/// # Example
+///
+/// ```no_run
+/// use integration::common::request_helpers::decimal;
+///
+/// assert_eq!(decimal("10.50") + decimal("0.50"), decimal("11"));
+/// ```
+pub fn decimal(value: &str) -> BigDecimal {
+    BigDecimal::from_str(value).unwrap()
+}
@@ -7,7 +7,14 @@
 use axum_test::TestServer;
 use diesel::PgConnection;
 use diesel::r2d2::{self, ConnectionManager};
-use master_of_coin_backend::{AppState, Config, api::routes::create_router};
+use master_of_coin_backend::{
+    AppState, Config,
+    api::routes::create_router,
+    middleware::logging::log_request,
+    services::{email_service::EmailSender, exchange_rate_service::ExchangeRateService},
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
 
 use super::get_test_database_url;
 
@@ -16,7 +23,7 @@ use super::get_test_database_url;
 /// This function:
 /// 1. Loads test configuration from environment variables
 /// 2. Creates a new database connection pool
-/// 3. Builds the Axum router with all routes
+/// 3. Builds the Axum router with all routes and the request logging middleware
 /// 4. Wraps it in a TestServer for easy HTTP testing
 ///
 /// # Returns
@@ -43,8 +50,27 @@ use super::get_test_database_url;
 /// }
 /// ```
 pub async fn create_test_server() -> TestServer {
+    create_test_server_with_config(|_| {}).await
+}
+
+/// Creates a test server whose test configuration is adjusted by `configure`.
+///
+/// # Example
+///
+/// ```no_run
+/// use integration::common::test_server::create_test_server_with_config;
+///
+/// #[tokio::test]
+/// async fn test_with_auto_categorization() {
+///     let server =
+///         create_test_server_with_config(|config| config.category_suggestion.auto_apply = true)
+///             .await;
+/// }
+/// ```
+pub async fn create_test_server_with_config(configure: impl FnOnce(&mut Config)) -> TestServer {
     // Load test configuration
-    let config = create_test_config();
+    let mut config = create_test_config();
+    configure(&mut config);
 
     // Create database connection pool
     let db_pool = create_test_db_pool();
@@ -52,13 +78,66 @@ pub async fn create_test_server() -> TestServer {
     // Create application state
     let state = AppState::new(db_pool, config);
 
-    // Create router with all routes
-    let app = create_router(state);
+    // Create router with all routes, logged like in main.rs
+    let app = create_router(state).layer(axum::middleware::from_fn(log_request));
 
     // Wrap in TestServer for easy testing
     TestServer::new(app).expect("Failed to create test server")
 }
 
+/// Creates a test server served over HTTP with connection info, whose test
+/// configuration is adjusted by `configure`.
+///
+/// Requests reach the server from 127.0.0.1, so tests can check how the peer
+/// address and forwarded headers resolve to a client IP.
+pub async fn create_test_server_with_connect_info(
+    configure: impl FnOnce(&mut Config),
+) -> TestServer {
+    let mut config = create_test_config();
+    configure(&mut config);
+
+    let state = AppState::new(create_test_db_pool(), config);
+    let app = create_router(state).into_make_service_with_connect_info::<SocketAddr>();
+
+    TestServer::new(app).expect("Failed to create test server")
+}
+
+/// Creates a test server together with a handle to its application state.
+///
+/// The state shares the server's pools and event bus, so tests can observe
+/// side effects such as published change events.
+pub async fn create_test_server_with_state() -> (TestServer, AppState) {
+    let state = AppState::new(create_test_db_pool(), create_test_config());
+    let app = create_router(state.clone());
+
+    let server = TestServer::new(app).expect("Failed to create test server");
+    (server, state)
+}
+
+/// Creates a test server that sends emails through `sender`.
+///
+/// Lets tests capture outgoing emails, such as password reset tokens.
+pub async fn create_test_server_with_email_sender(sender: Arc<dyn EmailSender>) -> TestServer {
+    let state =
+        AppState::new(create_test_db_pool(), create_test_config()).with_email_sender(sender);
+    let app = create_router(state);
+
+    TestServer::new(app).expect("Failed to create test server")
+}
+
+/// Creates a test server that converts currencies with `exchange_rates`.
+///
+/// Lets tests serve exchange rates from a mocked provider instead of the network.
+pub async fn create_test_server_with_exchange_rates(
+    exchange_rates: ExchangeRateService,
+) -> TestServer {
+    let state = AppState::new(create_test_db_pool(), create_test_config())
+        .with_exchange_rate_service(exchange_rates);
+    let app = create_router(state);
+
+    TestServer::new(app).expect("Failed to create test server")
+}
+
 /// Creates a test configuration with appropriate test settings.
 ///
 /// This function loads configuration from environment variables but ensures
@@ -79,21 +158,54 @@ fn create_test_config() -> Config {
     let jwt_secret = std::env::var("JWT_SECRET")
         .unwrap_or_else(|_| "test_secret_key_at_least_32_characters_long_for_testing".to_string());
 
+    let generous_limit = master_of_coin_backend::config::BucketLimit {
+        capacity: 10_000,
+        refill_per_minute: 10_000,
+    };
+
     Config {
         server: master_of_coin_backend::config::ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 0, // Use random port for tests
+            trusted_proxies: Vec::new(),
         },
         database: master_of_coin_backend::config::DatabaseConfig {
             url: get_test_database_url(),
+            replica_url: None,
             max_connections: 5, // Fewer connections for tests
         },
         jwt: master_of_coin_backend::config::JwtConfig {
             secret: jwt_secret,
             expiration_hours: 24,
+            refresh_expiration_days: 30,
         },
         import: master_of_coin_backend::config::ImportConfig::default(),
+        auth_events: master_of_coin_backend::config::AuthEventConfig::default(),
+        purge: master_of_coin_backend::config::PurgeConfig::default(),
+        split_reconciliation: master_of_coin_backend::config::SplitReconciliationConfig::default(),
+        pagination: master_of_coin_backend::config::PaginationConfig::default(),
+        transactions: master_of_coin_backend::config::TransactionConfig::default(),
+        recurring: master_of_coin_backend::config::RecurringConfig::default(),
+        exchange_rates: master_of_coin_backend::config::ExchangeRateConfig::default(),
+        exchange_rate_refresh: master_of_coin_backend::config::ExchangeRateRefreshConfig::default(),
+        category_suggestion: master_of_coin_backend::config::CategorySuggestionConfig::default(),
+        display: master_of_coin_backend::config::DisplayConfig::default(),
+        // No starter data, so tests start from users without categories
+        onboarding: master_of_coin_backend::config::OnboardingConfig {
+            create_default_categories: false,
+            ..Default::default()
+        },
+        // Generous limits, so tests sharing a server's buckets are not throttled
+        rate_limit: master_of_coin_backend::config::RateLimitConfig {
+            ip: generous_limit,
+            user: generous_limit,
+            login: generous_limit,
+            protected_ip: generous_limit,
+        },
+        notifications: master_of_coin_backend::config::NotificationConfig::default(),
         splitwise: None,
+        splitwise_webhook_secret: None,
+        admin_api_key: None,
         encryption_key_configured: false,
     }
 }
@@ -8,15 +8,23 @@
 // - Transaction handling
 // - Async/sync bridge pattern
 // - API key CRUD operations
+// - Auth event recording and retention purge
+// - Layered configuration from a TOML file and env vars
+// - Grouped debt queries
+// - Idempotency key claims and expiry
 
 #[path = "../common/mod.rs"]
 mod common;
 
 mod test_api_key_crud;
 mod test_async_bridge;
+mod test_auth_event_crud;
+mod test_config_file;
 mod test_connection;
 mod test_custom_types;
+mod test_debt_queries;
 mod test_encryption;
+mod test_idempotency_keys;
 mod test_relationships;
 mod test_transactions;
 mod test_user_crud;
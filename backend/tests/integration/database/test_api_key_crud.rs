@@ -46,6 +46,7 @@ fn test_api_key_create() {
         scopes: scopes_json,
         status: ApiKeyStatus::Active,
         expires_at: None,
+        requests_per_minute: None,
     };
 
     let created_key: ApiKey = diesel::insert_into(api_keys::table)
@@ -87,6 +88,7 @@ fn test_api_key_find_by_prefix() {
         scopes: json!({}),
         status: ApiKeyStatus::Active,
         expires_at: None,
+        requests_per_minute: None,
     };
 
     diesel::insert_into(api_keys::table)
@@ -131,6 +133,7 @@ fn test_api_key_update_status() {
         scopes: json!({}),
         status: ApiKeyStatus::Active,
         expires_at: None,
+        requests_per_minute: None,
     };
 
     let created_key: ApiKey = diesel::insert_into(api_keys::table)
@@ -178,6 +181,7 @@ fn test_api_key_list_by_user() {
             scopes: json!({}),
             status: ApiKeyStatus::Active,
             expires_at: None,
+            requests_per_minute: None,
         };
 
         diesel::insert_into(api_keys::table)
@@ -222,6 +226,7 @@ fn test_api_key_cascade_delete() {
         scopes: json!({}),
         status: ApiKeyStatus::Active,
         expires_at: None,
+        requests_per_minute: None,
     };
 
     let created_key: ApiKey = diesel::insert_into(api_keys::table)
@@ -0,0 +1,109 @@
+use super::common;
+
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use master_of_coin_backend::db::{create_pool, run_migrations};
+use master_of_coin_backend::models::{AuthEventType, NewAuthEvent};
+use master_of_coin_backend::repositories::auth_event;
+use master_of_coin_backend::schema::auth_events;
+use master_of_coin_backend::services::auth_event_service;
+use serial_test::serial;
+use uuid::Uuid;
+
+fn new_event(user_id: Option<Uuid>, event_type: AuthEventType) -> NewAuthEvent {
+    NewAuthEvent {
+        user_id,
+        event_type: event_type.as_str().to_string(),
+        ip_address: Some("203.0.113.7".to_string()),
+        user_agent: Some("integration-test".to_string()),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_auth_event_create_and_find_recent() {
+    let database_url = common::get_test_database_url();
+    let pool = create_pool(&database_url, 5).expect("Failed to create pool");
+
+    let user = {
+        let mut conn = pool.get().expect("Failed to get connection");
+        run_migrations(&mut conn).expect("Failed to run migrations");
+        common::cleanup_test_data(&mut conn);
+        common::create_test_user(&mut conn, "auth_events").expect("Failed to create user")
+    };
+
+    for event_type in [
+        AuthEventType::Register,
+        AuthEventType::Login,
+        AuthEventType::Logout,
+    ] {
+        auth_event::create(&pool, new_event(Some(user.id), event_type))
+            .await
+            .expect("Failed to create auth event");
+    }
+    // Events not tied to the user must not be returned
+    auth_event::create(&pool, new_event(None, AuthEventType::LoginFailed))
+        .await
+        .expect("Failed to create anonymous auth event");
+
+    let events = auth_event::find_recent_by_user_id(&pool, user.id, 10)
+        .await
+        .expect("Failed to load auth events");
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].event_type(), AuthEventType::Logout);
+    assert_eq!(events[2].event_type(), AuthEventType::Register);
+    assert_eq!(events[0].ip_address.as_deref(), Some("203.0.113.7"));
+
+    let limited = auth_event::find_recent_by_user_id(&pool, user.id, 1)
+        .await
+        .expect("Failed to load auth events");
+    assert_eq!(limited.len(), 1);
+    assert_eq!(limited[0].event_type(), AuthEventType::Logout);
+
+    let mut conn = pool.get().expect("Failed to get connection");
+    let _ = diesel::delete(auth_events::table).execute(&mut conn);
+    common::cleanup_test_data(&mut conn);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_auth_event_purge_expired() {
+    let database_url = common::get_test_database_url();
+    let pool = create_pool(&database_url, 5).expect("Failed to create pool");
+
+    let user = {
+        let mut conn = pool.get().expect("Failed to get connection");
+        run_migrations(&mut conn).expect("Failed to run migrations");
+        common::cleanup_test_data(&mut conn);
+        common::create_test_user(&mut conn, "auth_purge").expect("Failed to create user")
+    };
+
+    let old = auth_event::create(&pool, new_event(Some(user.id), AuthEventType::Login))
+        .await
+        .expect("Failed to create auth event");
+    let recent = auth_event::create(&pool, new_event(Some(user.id), AuthEventType::Logout))
+        .await
+        .expect("Failed to create auth event");
+
+    {
+        let mut conn = pool.get().expect("Failed to get connection");
+        diesel::update(auth_events::table.find(old.id))
+            .set(auth_events::created_at.eq(Utc::now() - Duration::days(100)))
+            .execute(&mut conn)
+            .expect("Failed to backdate auth event");
+    }
+
+    let purged = auth_event_service::purge_expired(&pool, 90)
+        .await
+        .expect("Failed to purge auth events");
+    assert!(purged >= 1);
+
+    let remaining = auth_event::find_recent_by_user_id(&pool, user.id, 10)
+        .await
+        .expect("Failed to load auth events");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, recent.id);
+
+    let mut conn = pool.get().expect("Failed to get connection");
+    common::cleanup_test_data(&mut conn);
+}
@@ -0,0 +1,161 @@
+use master_of_coin_backend::config::{Config, ConfigError};
+use serial_test::serial;
+use std::env;
+use std::path::PathBuf;
+
+/// Environment variables that may be provided by the config file in these tests
+const LAYERED_VARS: [&str; 5] = [
+    "SERVER_HOST",
+    "SERVER_PORT",
+    "DATABASE_MAX_CONNECTIONS",
+    "DATABASE_REPLICA_URL",
+    "JWT_EXPIRATION_HOURS",
+];
+
+fn write_config_file(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("moc_config_{}_{}.toml", name, std::process::id()));
+    std::fs::write(&path, contents).expect("Failed to write config file");
+    path
+}
+
+fn clear_layered_vars() {
+    for var in LAYERED_VARS {
+        unsafe {
+            env::remove_var(var);
+        }
+    }
+}
+
+#[test]
+#[serial]
+fn test_config_file_values_used_when_env_unset() {
+    clear_layered_vars();
+    let path = write_config_file(
+        "values",
+        r#"
+[server]
+host = "0.0.0.0"
+port = 8088
+
+[database]
+max_connections = 3
+
+[jwt]
+expiration_hours = 12
+"#,
+    );
+
+    let config = Config::from_file(&path).expect("Config file should load");
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 8088);
+    assert_eq!(config.database.max_connections, 3);
+    assert_eq!(config.jwt.expiration_hours, 12);
+    // Settings outside the file keep their defaults
+    assert_eq!(config.auth_events.retention_days, 90);
+
+    // Selecting the file explicitly behaves the same
+    let config = Config::load(Some(&path)).expect("Config file should load");
+    assert_eq!(config.server.port, 8088);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+#[serial]
+fn test_env_overrides_config_file() {
+    clear_layered_vars();
+    unsafe {
+        env::set_var("SERVER_PORT", "9099");
+        env::set_var("DATABASE_MAX_CONNECTIONS", "7");
+    }
+    let path = write_config_file(
+        "precedence",
+        r#"
+[server]
+host = "0.0.0.0"
+port = 8088
+
+[database]
+url = "postgres://file-user@file-host/file_db"
+max_connections = 3
+"#,
+    );
+
+    let config = Config::from_file(&path).expect("Config file should load");
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 9099);
+    assert_eq!(config.database.max_connections, 7);
+    assert_eq!(
+        config.database.url,
+        env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests")
+    );
+
+    clear_layered_vars();
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+#[serial]
+fn test_database_replica_url() {
+    clear_layered_vars();
+    let path = write_config_file(
+        "replica",
+        r#"
+[database]
+replica_url = "postgres://reader@file-replica/file_db"
+"#,
+    );
+
+    let config = Config::from_file(&path).expect("Config file should load");
+    assert_eq!(
+        config.database.replica_url.as_deref(),
+        Some("postgres://reader@file-replica/file_db")
+    );
+
+    unsafe {
+        env::set_var(
+            "DATABASE_REPLICA_URL",
+            "postgres://reader@env-replica/env_db",
+        );
+    }
+    let config = Config::from_file(&path).expect("Config file should load");
+    assert_eq!(
+        config.database.replica_url.as_deref(),
+        Some("postgres://reader@env-replica/env_db")
+    );
+    std::fs::remove_file(&path).ok();
+
+    // Without a replica, reads use the primary
+    clear_layered_vars();
+    let path = write_config_file("no_replica", "[server]\nport = 8088\n");
+    let config = Config::from_file(&path).expect("Config file should load");
+    assert_eq!(config.database.replica_url, None);
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+#[serial]
+fn test_config_file_errors() {
+    clear_layered_vars();
+
+    // File values go through the same validation as env values
+    let path = write_config_file("invalid", "[database]\nmax_connections = 0\n");
+    let result = Config::from_file(&path);
+    assert!(matches!(result, Err(ConfigError::InvalidConfig(_))));
+    std::fs::remove_file(&path).ok();
+
+    // Unknown keys are rejected so typos don't go unnoticed
+    let path = write_config_file("unknown", "[server]\nprot = 8088\n");
+    let result = Config::from_file(&path);
+    assert!(matches!(result, Err(ConfigError::InvalidFile(_))));
+    std::fs::remove_file(&path).ok();
+
+    let path = write_config_file("malformed", "[server\nport = 8088\n");
+    let result = Config::from_file(&path);
+    assert!(matches!(result, Err(ConfigError::InvalidFile(_))));
+    std::fs::remove_file(&path).ok();
+
+    let missing = env::temp_dir().join("moc_config_does_not_exist.toml");
+    let result = Config::from_file(&missing);
+    assert!(matches!(result, Err(ConfigError::InvalidFile(_))));
+}
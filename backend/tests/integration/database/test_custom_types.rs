@@ -33,6 +33,11 @@ fn test_account_type_enum() {
             account_type: *account_type,
             currency: CurrencyCode::Usd,
             notes: None,
+            allow_overdraft: true,
+            overdraft_limit: None,
+            credit_limit: None,
+            interest_rate: None,
+            original_principal: None,
         };
 
         let created_account: Account = diesel::insert_into(accounts::table)
@@ -82,6 +87,11 @@ fn test_currency_code_enum() {
             account_type: AccountType::Checking,
             currency: *currency,
             notes: None,
+            allow_overdraft: true,
+            overdraft_limit: None,
+            credit_limit: None,
+            interest_rate: None,
+            original_principal: None,
         };
 
         let created_account: Account = diesel::insert_into(accounts::table)
@@ -141,6 +151,11 @@ fn test_account_with_all_custom_types() {
         account_type: AccountType::Savings,
         currency: CurrencyCode::Eur,
         notes: Some("Test savings account".to_string()),
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     let created_account: Account = diesel::insert_into(accounts::table)
@@ -0,0 +1,110 @@
+use super::common;
+
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use diesel::prelude::*;
+use master_of_coin_backend::DbPool;
+use master_of_coin_backend::db::{create_pool, run_migrations};
+use master_of_coin_backend::models::{NewPerson, NewTransactionSplit, User};
+use master_of_coin_backend::schema::{people, transaction_splits};
+use master_of_coin_backend::services::debt_service;
+use serial_test::serial;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts every query started on the connection it is attached to
+struct QueryCounter(Arc<AtomicUsize>);
+
+impl Instrumentation for QueryCounter {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        if let InstrumentationEvent::StartQuery { .. } = event {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Create `count` people, each with one unsettled split owing `amount`
+fn create_people_with_splits(pool: &DbPool, user: &User, account_id: uuid::Uuid, count: usize) {
+    let mut conn = pool.get().expect("Failed to get connection");
+    let transaction = common::TransactionFactory::new(user.id, account_id)
+        .amount("-100.00")
+        .build(&mut conn);
+
+    for _ in 0..count {
+        let person_id: uuid::Uuid = diesel::insert_into(people::table)
+            .values(&NewPerson {
+                user_id: user.id,
+                name: format!("Person {}", uuid::Uuid::new_v4()),
+                email: None,
+                phone: None,
+                notes: None,
+            })
+            .returning(people::id)
+            .get_result(&mut conn)
+            .expect("Failed to create person");
+
+        diesel::insert_into(transaction_splits::table)
+            .values(&NewTransactionSplit {
+                transaction_id: transaction.id,
+                person_id,
+                amount: "25.00".parse().unwrap(),
+            })
+            .execute(&mut conn)
+            .expect("Failed to create split");
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_all_debts_use_constant_number_of_queries() {
+    let database_url = common::get_test_database_url();
+    // A single connection, so every query of the debt path goes through the counter
+    let pool = create_pool(&database_url, 1).expect("Failed to create pool");
+    let queries = Arc::new(AtomicUsize::new(0));
+
+    let (user, account) = {
+        let mut conn = pool.get().expect("Failed to get connection");
+        run_migrations(&mut conn).expect("Failed to run migrations");
+        common::cleanup_test_data(&mut conn);
+        let user =
+            common::create_test_user(&mut conn, "debt_queries").expect("Failed to create user");
+        let account = common::AccountFactory::new(user.id).build(&mut conn);
+        (user, account)
+    };
+
+    create_people_with_splits(&pool, &user, account.id, 3);
+    pool.get()
+        .expect("Failed to get connection")
+        .set_instrumentation(QueryCounter(queries.clone()));
+
+    queries.store(0, Ordering::SeqCst);
+    let debts = debt_service::get_all_debts_for_user(&pool, user.id)
+        .await
+        .expect("Failed to list debts");
+    let queries_for_few = queries.load(Ordering::SeqCst);
+    assert_eq!(debts.len(), 3);
+
+    create_people_with_splits(&pool, &user, account.id, 20);
+
+    queries.store(0, Ordering::SeqCst);
+    let debts = debt_service::get_all_debts_for_user(&pool, user.id)
+        .await
+        .expect("Failed to list debts");
+    let queries_for_many = queries.load(Ordering::SeqCst);
+    assert_eq!(debts.len(), 23);
+    assert!(debts.iter().all(|debt| debt.debt_amount == "25.00"));
+
+    assert!(queries_for_few > 0, "Queries should be counted");
+    assert_eq!(
+        queries_for_few, queries_for_many,
+        "Listing debts should not issue a query per person"
+    );
+
+    // The per-person path reuses the grouped query
+    let debt = debt_service::calculate_debt_for_person(&pool, debts[0].person_id, user.id)
+        .await
+        .expect("Failed to calculate debt");
+    assert_eq!(debt, "25.00");
+
+    let mut conn = pool.get().expect("Failed to get connection");
+    common::cleanup_test_data(&mut conn);
+}
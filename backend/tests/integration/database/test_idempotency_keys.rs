@@ -0,0 +1,116 @@
+use super::common;
+
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use master_of_coin_backend::db::{create_pool, run_migrations};
+use master_of_coin_backend::errors::ApiError;
+use master_of_coin_backend::models::idempotency_key::IdempotencyScope;
+use master_of_coin_backend::schema::idempotency_keys;
+use master_of_coin_backend::services::idempotency_service::{self, Claim};
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn test_idempotency_key_claim_and_complete() {
+    let database_url = common::get_test_database_url();
+    let pool = create_pool(&database_url, 5).expect("Failed to create pool");
+
+    let user = {
+        let mut conn = pool.get().expect("Failed to get connection");
+        run_migrations(&mut conn).expect("Failed to run migrations");
+        common::cleanup_test_data(&mut conn);
+        common::create_test_user(&mut conn, "idem_claim").expect("Failed to create user")
+    };
+    let scope = IdempotencyScope::Transaction;
+    let claim = |hash: &str| {
+        idempotency_service::claim(&pool, user.id, scope, "key-1".to_string(), hash.to_string())
+    };
+
+    assert!(matches!(claim("hash-a").await, Ok(Claim::Claimed)));
+
+    // A second request while the first is running is refused
+    assert!(matches!(claim("hash-a").await, Err(ApiError::Conflict(_))));
+
+    idempotency_service::complete(
+        &pool,
+        user.id,
+        scope,
+        "key-1".to_string(),
+        201,
+        "{\"id\":1}".to_string(),
+    )
+    .await
+    .expect("Failed to store response");
+
+    match claim("hash-a").await {
+        Ok(Claim::Completed { status, body }) => {
+            assert_eq!(status, 201);
+            assert_eq!(body, "{\"id\":1}");
+        }
+        other => panic!("expected the stored response, got {:?}", other),
+    }
+    assert!(matches!(claim("hash-b").await, Err(ApiError::Conflict(_))));
+
+    // The same key in another scope is a different key
+    let other_scope = idempotency_service::claim(
+        &pool,
+        user.id,
+        IdempotencyScope::Transfer,
+        "key-1".to_string(),
+        "hash-b".to_string(),
+    )
+    .await;
+    assert!(matches!(other_scope, Ok(Claim::Claimed)));
+
+    let mut conn = pool.get().expect("Failed to get connection");
+    common::cleanup_test_data(&mut conn);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_idempotency_key_purge_expired() {
+    let database_url = common::get_test_database_url();
+    let pool = create_pool(&database_url, 5).expect("Failed to create pool");
+
+    let user = {
+        let mut conn = pool.get().expect("Failed to get connection");
+        run_migrations(&mut conn).expect("Failed to run migrations");
+        common::cleanup_test_data(&mut conn);
+        common::create_test_user(&mut conn, "idem_purge").expect("Failed to create user")
+    };
+    let scope = IdempotencyScope::Transaction;
+
+    for key in ["old", "recent"] {
+        let claim =
+            idempotency_service::claim(&pool, user.id, scope, key.to_string(), "hash".to_string())
+                .await;
+        assert!(matches!(claim, Ok(Claim::Claimed)));
+    }
+
+    {
+        let mut conn = pool.get().expect("Failed to get connection");
+        diesel::update(
+            idempotency_keys::table
+                .filter(idempotency_keys::user_id.eq(user.id))
+                .filter(idempotency_keys::key.eq("old")),
+        )
+        .set(idempotency_keys::created_at.eq(Utc::now() - Duration::hours(25)))
+        .execute(&mut conn)
+        .expect("Failed to backdate idempotency key");
+    }
+
+    let purged = idempotency_service::purge_expired(&pool)
+        .await
+        .expect("Failed to purge idempotency keys");
+    assert!(purged >= 1);
+
+    let mut conn = pool.get().expect("Failed to get connection");
+    let remaining: Vec<String> = idempotency_keys::table
+        .filter(idempotency_keys::user_id.eq(user.id))
+        .select(idempotency_keys::key)
+        .load(&mut conn)
+        .expect("Failed to load idempotency keys");
+    assert_eq!(remaining, vec!["recent".to_string()]);
+
+    common::cleanup_test_data(&mut conn);
+}
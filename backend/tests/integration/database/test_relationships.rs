@@ -25,6 +25,11 @@ fn test_user_has_many_accounts() {
         account_type: AccountType::Checking,
         currency: CurrencyCode::Usd,
         notes: None,
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     let account2 = NewAccount {
@@ -33,6 +38,11 @@ fn test_user_has_many_accounts() {
         account_type: AccountType::Savings,
         currency: CurrencyCode::Usd,
         notes: None,
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     diesel::insert_into(accounts::table)
@@ -81,6 +91,11 @@ fn test_multiple_users_with_accounts() {
         account_type: AccountType::Checking,
         currency: CurrencyCode::Usd,
         notes: None,
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     let account2 = NewAccount {
@@ -89,6 +104,11 @@ fn test_multiple_users_with_accounts() {
         account_type: AccountType::Savings,
         currency: CurrencyCode::Eur,
         notes: None,
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     diesel::insert_into(accounts::table)
@@ -141,6 +161,11 @@ fn test_account_belongs_to_user() {
         account_type: AccountType::Checking,
         currency: CurrencyCode::Usd,
         notes: None,
+        allow_overdraft: true,
+        overdraft_limit: None,
+        credit_limit: None,
+        interest_rate: None,
+        original_principal: None,
     };
 
     let account: Account = diesel::insert_into(accounts::table)